@@ -37,6 +37,7 @@ fn make_config(resources: Vec<(&str, Resource)>) -> ForjarConfig {
         data: Default::default(),
         includes: Default::default(),
         include_provenance: Default::default(),
+        recipe_outputs: Default::default(),
         checks: Default::default(),
         moved: Default::default(),
         secrets: Default::default(),
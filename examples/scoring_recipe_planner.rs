@@ -85,6 +85,10 @@ fn main() {
             min: Some(1),
             max: Some(65535),
             choices: vec![],
+            pattern: None,
+            required: None,
+            items: None,
+            properties: IndexMap::new(),
         },
     );
     inputs.insert(
@@ -96,6 +100,10 @@ fn main() {
             min: None,
             max: None,
             choices: vec!["dev".into(), "staging".into(), "prod".into()],
+            pattern: None,
+            required: None,
+            items: None,
+            properties: IndexMap::new(),
         },
     );
     let recipe = RecipeMetadata {
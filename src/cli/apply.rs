@@ -45,6 +45,7 @@ pub(crate) fn cmd_apply(
     telemetry_endpoint: Option<&str>,
     refresh: bool,
     force_tag: Option<&str>,
+    lock_reason: Option<&str>,
 ) -> Result<(), String> {
     // GH-91: Warn that --sequential is not yet implemented
     if sequential {
@@ -62,6 +63,7 @@ pub(crate) fn cmd_apply(
     }
     inject_workspace_param(&mut config, workspace);
     resolver::resolve_data_sources(&mut config)?;
+    resolver::resolve_recipe_outputs(&mut config, state_dir)?;
     let dur_parse = t_parse.elapsed();
 
     if verbose {
@@ -78,6 +80,15 @@ pub(crate) fn cmd_apply(
     apply_param_overrides(&mut config, param_overrides)?;
 
     apply_filters(&mut config, subset, exclude, verbose)?;
+    // FJ-1425: Route state I/O through the configured backend.
+    state::backend::attach(config.policy.backend.as_ref(), state_dir)?;
+    // FJ-3310/FJ-3311: Encrypt state to recipients; audit Vault reads into it.
+    crate::core::state_recipients::attach(config.policy.state_encryption.as_ref(), state_dir)?;
+    crate::core::vault::set_audit_dir(Some(state_dir));
+    // FJ-1432: Verify existing locks and sign every lock this apply writes.
+    let config_dir = file.parent().unwrap_or_else(|| Path::new("."));
+    let trust = crate::core::signing::trust_policy_for(&config, config_dir)?;
+    crate::core::signing::attach_lock_signing(trust.as_ref(), state_dir, dry_run)?;
     apply_pre_validate(
         &config,
         state_dir,
@@ -115,6 +126,7 @@ pub(crate) fn cmd_apply(
         },
         refresh,
         force_tag,
+        lock_reason,
     };
 
     maybe_auto_snapshot(&config, state_dir, Some(file), dry_run, verbose);
@@ -276,6 +276,13 @@ pub(super) fn apply_post_actions(
         let resolved = state::resolve_outputs(config);
         state::persist_outputs(state_dir, &config.name, &resolved, config.secrets.ephemeral)?;
     }
+    // Values exported by apply scripts, e.g. wireguard_interface public keys
+    let mut machines: Vec<&str> = config.machines.keys().map(String::as_str).collect();
+    machines.extend(results.iter().map(|r| r.machine.as_str()));
+    machines.sort_unstable();
+    machines.dedup();
+    let exported = state::resource_outputs(state_dir, machines);
+    state::persist_resource_outputs(state_dir, &config.name, &exported)?;
 
     // FJ-1200: Run post-apply check blocks
     if !config.checks.is_empty() && total_failed == 0 {
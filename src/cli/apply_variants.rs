@@ -93,6 +93,7 @@ pub(crate) fn cmd_apply_canary_machine(
         None,  // telemetry_endpoint
         false, // refresh
         None,  // force_tag
+        None,  // lock_reason
     )?;
 
     println!("\n{} Canary '{}' succeeded.", green("✓"), canary);
@@ -150,6 +151,7 @@ pub(crate) fn cmd_apply_canary_machine(
             None,  // telemetry_endpoint
             false, // refresh
             None,  // force_tag
+            None,  // lock_reason
         )?;
     }
 
@@ -179,6 +181,7 @@ pub(crate) fn cmd_refresh_only(
     }
     inject_workspace_param(&mut config, workspace);
     resolver::resolve_data_sources(&mut config)?;
+    resolver::resolve_recipe_outputs(&mut config, state_dir)?;
 
     let locks = load_machine_locks(&config, state_dir, machine_filter)?;
     let mut refreshed = 0usize;
@@ -304,6 +307,7 @@ pub(crate) fn cmd_apply_from_plan(
     }
     inject_workspace_param(&mut config, workspace);
     resolver::resolve_data_sources(&mut config)?;
+    resolver::resolve_recipe_outputs(&mut config, state_dir)?;
 
     let plan = load_plan_file(plan_path, &config)?;
     let n_changes = plan.to_create + plan.to_update + plan.to_destroy;
@@ -342,6 +346,7 @@ pub(crate) fn cmd_apply_from_plan(
         run_id: Some(crate::core::types::generate_run_id()),
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
 
     let results = executor::apply(&cfg)?;
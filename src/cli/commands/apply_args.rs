@@ -85,6 +85,10 @@ pub struct ApplyArgs {
     #[arg(long)]
     pub force_unlock: bool,
 
+    /// FJ-266: Reason recorded with the apply lock (shown by status and lock-info)
+    #[arg(long)]
+    pub lock_reason: Option<String>,
+
     /// FJ-270: Output mode — 'events' for newline-delimited JSON events
     #[arg(long)]
     pub output: Option<String>,
@@ -184,7 +184,7 @@ pub struct LockSignArgs {
     #[arg(long, default_value = "state")]
     pub state_dir: PathBuf,
 
-    /// Signing key (path to key file or inline)
+    /// Ed25519 signing key: keyring name or private key file
     #[arg(long)]
     pub key: String,
 
@@ -200,7 +200,7 @@ pub struct LockVerifySigArgs {
     #[arg(long, default_value = "state")]
     pub state_dir: PathBuf,
 
-    /// Signing key to verify against
+    /// Public key to verify against: `ed25519:...`, key file or keyring name
     #[arg(long)]
     pub key: String,
 
@@ -248,11 +248,11 @@ pub struct LockRotateKeysArgs {
     #[arg(long, default_value = "state")]
     pub state_dir: PathBuf,
 
-    /// Old signing key
+    /// Public key the existing signatures must verify against
     #[arg(long)]
     pub old_key: String,
 
-    /// New signing key
+    /// New signing key: keyring name or private key file
     #[arg(long)]
     pub new_key: String,
 
@@ -8,6 +8,7 @@ mod misc_ops_args;
 mod ops_intel_args;
 mod plan_args;
 mod platform_args;
+mod recipe_args;
 mod state_args;
 mod status_args;
 mod store_args;
@@ -24,6 +25,7 @@ pub use misc_ops_args::*;
 pub use ops_intel_args::*;
 pub use plan_args::*;
 pub use platform_args::*;
+pub use recipe_args::*;
 pub use state_args::*;
 pub use status_args::*;
 pub use store_args::*;
@@ -439,6 +441,10 @@ pub enum Commands {
     #[command(name = "registry-list")]
     RegistryList(RegistryListArgs),
 
+    /// FJ-3314 + FJ-3315: Versioned recipe resolution and input schemas
+    #[command(subcommand)]
+    Recipe(RecipeCmd),
+
     /// FJ-1427: Service catalog listing
     #[command(name = "catalog-list")]
     CatalogList(CatalogListArgs),
@@ -459,6 +465,10 @@ pub enum Commands {
     #[command(name = "sign")]
     RecipeSign(RecipeSignArgs),
 
+    /// FJ-1432: Manage Ed25519 signing keys
+    #[command(subcommand)]
+    Keys(KeysCmd),
+
     /// FJ-1434: Preservation checking
     #[command(name = "preservation")]
     Preservation(PreservationArgs),
@@ -12,6 +12,10 @@ pub struct StateBackendArgs {
     #[clap(long, default_value = "state")]
     pub state_dir: PathBuf,
 
+    /// Config whose `policy.backend` to list (default: the local state directory)
+    #[clap(short, long)]
+    pub file: Option<PathBuf>,
+
     /// Filter by key prefix
     #[clap(long)]
     pub prefix: Option<String>,
@@ -123,11 +127,15 @@ pub struct RecipeSignArgs {
     #[clap(long)]
     pub verify: bool,
 
-    /// Signer identity
+    /// Signing key: keyring name or key file (default: FORJAR_SIGNING_KEY)
     #[clap(long)]
     pub signer: Option<String>,
 
-    /// Post-quantum dual signing
+    /// Trust policy whose recipe signers the signature must match (--verify)
+    #[clap(long)]
+    pub trust_policy: Option<PathBuf>,
+
+    /// Post-quantum dual signing (Ed25519 + ML-DSA-65)
     #[clap(long)]
     pub pq: bool,
 
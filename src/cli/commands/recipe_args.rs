@@ -0,0 +1,26 @@
+//! CLI Args for recipe commands (`forjar recipe ...`).
+
+use clap::Subcommand;
+use std::path::PathBuf;
+
+/// FJ-3314 + FJ-3315: Versioned recipe resolution and input schemas.
+#[derive(Subcommand, Debug)]
+pub enum RecipeCmd {
+    /// Bump `name@constraint` recipes to the highest versions their
+    /// constraints allow and rewrite their pins in forjar.inputs.lock.yaml
+    Update {
+        /// Recipes to update (default: all)
+        names: Vec<String>,
+        /// Path to forjar.yaml
+        #[arg(short, long, default_value = "forjar.yaml")]
+        file: PathBuf,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// FJ-3315: Print the JSON Schema of a recipe's inputs, for editors
+    Schema {
+        /// Path to the recipe file
+        recipe: PathBuf,
+    },
+}
@@ -159,7 +159,7 @@ pub enum StoreCmd {
     },
 }
 
-/// Archive subcommands: pack, unpack, inspect, verify.
+/// Archive subcommands: pack, unpack, sign, inspect, verify.
 #[derive(Subcommand, Debug)]
 pub enum ArchiveCmd {
     /// Pack a store entry into a .far archive
@@ -184,6 +184,20 @@ pub enum ArchiveCmd {
         /// Store directory
         #[arg(long, default_value = "/var/lib/forjar/store")]
         store_dir: PathBuf,
+
+        /// Trust policy to enforce (default: FORJAR_TRUST_POLICY)
+        #[arg(long)]
+        trust_policy: Option<PathBuf>,
+    },
+
+    /// Sign a .far archive with an Ed25519 key
+    Sign {
+        /// Path to the .far file
+        file: PathBuf,
+
+        /// Signing key: keyring name or key file (default: FORJAR_SIGNING_KEY)
+        #[arg(long)]
+        key: Option<String>,
     },
 
     /// Print the manifest of a .far archive without unpacking
@@ -201,6 +215,10 @@ pub enum ArchiveCmd {
         /// Path to the .far file
         file: PathBuf,
 
+        /// Trust policy the signature must satisfy
+        #[arg(long)]
+        trust_policy: Option<PathBuf>,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
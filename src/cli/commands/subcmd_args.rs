@@ -315,6 +315,12 @@ pub struct StateEncryptArgs {
     /// Passphrase for encryption (reads from stdin if not provided)
     #[arg(long)]
     pub passphrase: Option<String>,
+    /// FJ-3310: Encrypt to this age recipient instead of a passphrase (repeatable)
+    #[arg(long = "recipient")]
+    pub recipients: Vec<String>,
+    /// FJ-3310: Encrypt to the recipients in this config's policy.state_encryption
+    #[arg(short, long)]
+    pub file: Option<PathBuf>,
     /// JSON output
     #[arg(long)]
     pub json: bool,
@@ -329,6 +335,12 @@ pub struct StateDecryptArgs {
     /// Passphrase for decryption
     #[arg(long)]
     pub passphrase: Option<String>,
+    /// FJ-3310: age identity file for recipient-encrypted state (default: FORJAR_AGE_KEY)
+    #[arg(long)]
+    pub identity: Option<PathBuf>,
+    /// FJ-3310: Read and write the state through this config's policy.backend
+    #[arg(short, long)]
+    pub file: Option<PathBuf>,
     /// JSON output
     #[arg(long)]
     pub json: bool,
@@ -346,6 +358,18 @@ pub struct StateRekeyArgs {
     /// New passphrase
     #[arg(long)]
     pub new_passphrase: Option<String>,
+    /// FJ-3310: Re-encrypt to the recipients in this config's policy.state_encryption
+    #[arg(short, long)]
+    pub file: Option<PathBuf>,
+    /// FJ-3310: Add an age recipient (repeatable)
+    #[arg(long)]
+    pub add_recipient: Vec<String>,
+    /// FJ-3310: Remove an age recipient (repeatable)
+    #[arg(long)]
+    pub remove_recipient: Vec<String>,
+    /// FJ-3310: age identity file that decrypts the current state (default: FORJAR_AGE_KEY)
+    #[arg(long)]
+    pub identity: Option<PathBuf>,
     /// JSON output
     #[arg(long)]
     pub json: bool,
@@ -394,7 +418,12 @@ pub enum SecretsCmd {
         recipient: Vec<String>,
     },
     /// FJ-201: Rotate all secrets — decrypt and re-encrypt with new keys
+    ///
+    /// FJ-3312: With a KEY, rotate only that param's secret to a new value
+    /// and plan the resources that consume it.
     Rotate {
+        /// Param holding the secret to rotate (default: every marker)
+        key: Option<String>,
         /// Path to forjar.yaml
         #[arg(short, long, default_value = "forjar.yaml")]
         file: PathBuf,
@@ -402,8 +431,14 @@ pub enum SecretsCmd {
         #[arg(short, long)]
         identity: Option<PathBuf>,
         /// Age recipient public key(s)
-        #[arg(short, long, required = true)]
+        #[arg(short, long, required_unless_present = "rollback")]
         recipient: Vec<String>,
+        /// Read the new value from this file (`-` for stdin) instead of generating one
+        #[arg(long, requires = "key")]
+        value_file: Option<PathBuf>,
+        /// Restore the value replaced by the last rotation of KEY
+        #[arg(long, requires = "key", conflicts_with = "value_file")]
+        rollback: bool,
         /// Re-encrypt after rotation
         #[arg(long)]
         re_encrypt: bool,
@@ -412,3 +447,39 @@ pub enum SecretsCmd {
         state_dir: PathBuf,
     },
 }
+
+/// FJ-1432: Keys subcommands — Ed25519 signing keys.
+#[derive(Subcommand, Debug)]
+pub enum KeysCmd {
+    /// Generate a new Ed25519 keypair in the keyring
+    Generate {
+        /// Key name
+        name: String,
+        /// FJ-1433: Also generate an ML-DSA-65 keypair for `sign --pq`; adds
+        /// the PQ half to an existing key
+        #[arg(long)]
+        pq: bool,
+        /// Keyring directory (default: FORJAR_KEY_DIR or ~/.forjar/keys)
+        #[arg(long)]
+        key_dir: Option<PathBuf>,
+    },
+    /// Import a PEM private key, or an `ed25519:...` public key to verify with
+    Import {
+        /// Key name
+        name: String,
+        /// Key file to import
+        file: PathBuf,
+        /// Keyring directory (default: FORJAR_KEY_DIR or ~/.forjar/keys)
+        #[arg(long)]
+        key_dir: Option<PathBuf>,
+    },
+    /// List keys in the keyring
+    List {
+        /// Keyring directory (default: FORJAR_KEY_DIR or ~/.forjar/keys)
+        #[arg(long)]
+        key_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
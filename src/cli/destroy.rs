@@ -2,7 +2,7 @@
 
 use super::apply::*;
 use super::helpers::*;
-use crate::core::{codegen, executor, resolver, types};
+use crate::core::{codegen, executor, resolver, state, types};
 use crate::transport;
 use std::path::Path;
 
@@ -56,9 +56,8 @@ fn cleanup_state_files(state_dir: &Path, machines: &[String], machine_filter: Op
                 continue;
             }
         }
-        let lock_path = state_dir.join(machine_name).join("state.lock.yaml");
-        if lock_path.exists() {
-            let _ = std::fs::remove_file(&lock_path);
+        if let Err(e) = state::remove_lock(state_dir, machine_name) {
+            eprintln!("warning: cannot remove lock for {machine_name}: {e}");
         }
     }
 }
@@ -69,21 +68,19 @@ pub(crate) fn cleanup_succeeded_entries(
     succeeded: &std::collections::HashMap<String, Vec<String>>,
 ) {
     for (machine_name, resource_ids) in succeeded {
-        let lock_path = state_dir.join(machine_name).join("state.lock.yaml");
-        let Ok(content) = std::fs::read_to_string(&lock_path) else {
-            continue;
-        };
-        let Ok(mut lock) = serde_yaml_ng::from_str::<crate::core::types::StateLock>(&content)
-        else {
+        let Ok(Some(mut lock)) = state::load_lock(state_dir, machine_name) else {
             continue;
         };
         for rid in resource_ids {
             lock.resources.shift_remove(rid);
         }
-        if lock.resources.is_empty() {
-            let _ = std::fs::remove_file(&lock_path);
-        } else if let Ok(yaml) = serde_yaml_ng::to_string(&lock) {
-            let _ = std::fs::write(&lock_path, yaml);
+        let result = if lock.resources.is_empty() {
+            state::remove_lock(state_dir, machine_name)
+        } else {
+            state::save_lock(state_dir, &lock)
+        };
+        if let Err(e) = result {
+            eprintln!("warning: cannot update lock for {machine_name}: {e}");
         }
     }
 }
@@ -113,14 +110,8 @@ pub(crate) fn write_destroy_log_entry(
         reliable_recreate: resource.content.is_some(),
     };
     if let Ok(line) = entry.to_jsonl() {
-        use std::io::Write;
-        if let Ok(mut f) = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(log_path)
-        {
-            let _ = writeln!(f, "{line}");
-        }
+        let (backend, key) = state::backend::locate(log_path);
+        let _ = backend.append(&key, format!("{line}\n").as_bytes());
     }
 }
 
@@ -139,6 +130,14 @@ pub(crate) fn cmd_destroy(
 
     let config = parse_and_validate(file)?;
     let execution_order = resolver::build_execution_order(&config)?;
+    // FJ-1425: Lock cleanup must reach the remote store, or the next apply
+    // pulls the destroyed resources' locks back.
+    state::backend::attach(config.policy.backend.as_ref(), state_dir)?;
+    crate::core::state_recipients::attach(config.policy.state_encryption.as_ref(), state_dir)?;
+    // FJ-1432: Locks rewritten after a partial destroy are signed too.
+    let config_dir = file.parent().unwrap_or_else(|| Path::new("."));
+    let trust = crate::core::signing::trust_policy_for(&config, config_dir)?;
+    crate::core::signing::attach_lock_signing(trust.as_ref(), state_dir, false)?;
     let reverse_order: Vec<String> = execution_order.into_iter().rev().collect();
 
     if verbose {
@@ -316,6 +315,7 @@ pub(crate) fn cmd_rollback(
         None,  // telemetry_endpoint
         false, // refresh
         None,  // force_tag
+        None,  // lock_reason
     )
 }
 
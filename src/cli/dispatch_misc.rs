@@ -161,24 +161,79 @@ fn dispatch_misc_state(cmd: Commands) -> Result<(), String> {
         Commands::StateEncrypt(StateEncryptArgs {
             state_dir,
             passphrase,
+            mut recipients,
+            file,
             json,
         }) => {
+            if !recipients.is_empty() || file.is_some() {
+                if let Some(ref file) = file {
+                    super::state_recipients::attach_backend(file, &state_dir)?;
+                    recipients.extend(super::state_recipients::configured(file, &state_dir)?);
+                }
+                return super::state_recipients::cmd_state_encrypt_recipients(
+                    &state_dir,
+                    &recipients,
+                    json,
+                );
+            }
             let pass = passphrase.unwrap_or_else(|| "forjar-default".into());
             super::state_encrypt::cmd_state_encrypt(&state_dir, &pass, json)
         }
         Commands::StateDecrypt(StateDecryptArgs {
             state_dir,
             passphrase,
+            identity,
+            file,
             json,
         }) => {
+            if let Some(ref file) = file {
+                super::state_recipients::attach_backend(file, &state_dir)?;
+            }
+            if identity.is_some()
+                || passphrase.is_none() && super::state_recipients::has_recipient_state(&state_dir)
+            {
+                return super::state_recipients::cmd_state_decrypt_recipients(
+                    &state_dir,
+                    identity.as_deref(),
+                    json,
+                );
+            }
             let pass = passphrase.unwrap_or_else(|| "forjar-default".into());
             super::state_encrypt::cmd_state_decrypt(&state_dir, &pass, json)
         }
+        Commands::StateRekey(StateRekeyArgs {
+            state_dir,
+            file,
+            add_recipient,
+            remove_recipient,
+            identity,
+            json,
+            ..
+        }) if file.is_some() || !add_recipient.is_empty() || !remove_recipient.is_empty() => {
+            let plan = super::state_recipients::RekeyPlan {
+                base: match file {
+                    Some(ref file) => {
+                        super::state_recipients::attach_backend(file, &state_dir)?;
+                        Some(super::state_recipients::configured(file, &state_dir)?)
+                    }
+                    None => None,
+                },
+                add: add_recipient,
+                remove: remove_recipient,
+            };
+            super::state_recipients::cmd_state_rekey_recipients(
+                &state_dir,
+                &plan,
+                identity.as_deref(),
+                json,
+            )
+        }
         Commands::StateRekey(StateRekeyArgs {
             state_dir,
             old_passphrase,
             new_passphrase,
             json,
+            ..
         }) => {
             let old_pass = old_passphrase.unwrap_or_else(|| "forjar-default".into());
             let new_pass = new_passphrase.unwrap_or_else(|| "forjar-default".into());
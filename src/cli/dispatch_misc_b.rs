@@ -1,6 +1,7 @@
 //! Misc command dispatch part B — data pipeline, infra, and sub-command dispatch.
 
 use super::commands::*;
+use super::secret_rotate::*;
 use super::secrets::*;
 use super::snapshot::*;
 use super::workspace::*;
@@ -139,12 +140,36 @@ pub(super) fn dispatch_secrets(sub: SecretsCmd) -> Result<(), String> {
             identity,
             recipient,
         } => cmd_secrets_rekey(&file, identity.as_deref(), &recipient),
+        SecretsCmd::Rotate {
+            key: Some(key),
+            file,
+            rollback: true,
+            state_dir,
+            ..
+        } => cmd_secrets_rotate_rollback(&file, &key, &state_dir),
+        SecretsCmd::Rotate {
+            key: Some(key),
+            file,
+            identity,
+            recipient,
+            value_file,
+            state_dir,
+            ..
+        } => cmd_secrets_rotate_key(
+            &file,
+            &key,
+            identity.as_deref(),
+            &recipient,
+            value_file.as_deref(),
+            &state_dir,
+        ),
         SecretsCmd::Rotate {
             file,
             identity,
             recipient,
             re_encrypt,
             state_dir,
+            ..
         } => cmd_secrets_rotate(
             &file,
             identity.as_deref(),
@@ -1,6 +1,6 @@
 //! Dispatch for platform features:
 //! remote state, recipe registry, service catalog,
-//! multi-config/stack, query, signing, preservation, parallel apply.
+//! multi-config/stack, query, signing and keys, preservation, parallel apply.
 
 use super::commands::*;
 
@@ -9,6 +9,12 @@ pub(crate) fn dispatch_platform_cmd(cmd: Commands) -> Result<(), String> {
     match cmd {
         Commands::StateBackend(args) => dispatch_state_backend(args),
         Commands::RegistryList(args) => dispatch_registry(args),
+        Commands::Recipe(RecipeCmd::Update { names, file, json }) => {
+            super::recipe_update::cmd_recipe_update(&file, &names, json)
+        }
+        Commands::Recipe(RecipeCmd::Schema { recipe }) => {
+            super::recipe_update::cmd_recipe_schema(&recipe)
+        }
         Commands::CatalogList(args) => dispatch_catalog(args),
         Commands::MultiApply(MultiConfigArgs { file, json }) => {
             super::multi_config::cmd_multi_config(&file, json)
@@ -18,6 +24,7 @@ pub(crate) fn dispatch_platform_cmd(cmd: Commands) -> Result<(), String> {
         }
         Commands::InfraQuery(args) => dispatch_query(args),
         Commands::RecipeSign(args) => dispatch_sign(args),
+        Commands::Keys(sub) => dispatch_keys(sub),
         Commands::Preservation(PreservationArgs { file, json }) => {
             super::preservation_check::cmd_preservation(&file, json)
         }
@@ -39,6 +46,10 @@ pub(crate) fn dispatch_platform_cmd(cmd: Commands) -> Result<(), String> {
 }
 
 fn dispatch_state_backend(args: StateBackendArgs) -> Result<(), String> {
+    if let Some(ref file) = args.file {
+        let config = crate::core::parser::parse_and_validate(file)?;
+        crate::core::state::backend::connect(config.policy.backend.as_ref(), &args.state_dir)?;
+    }
     super::remote_state::cmd_state_backend(&args.state_dir, args.prefix.as_deref(), args.json)
 }
 
@@ -103,12 +114,32 @@ fn dispatch_pull_agent(args: PullAgentArgs) -> Result<(), String> {
     )
 }
 
+fn dispatch_keys(sub: KeysCmd) -> Result<(), String> {
+    let keyring = |dir: Option<std::path::PathBuf>| {
+        crate::core::signing::Keyring::open(
+            &dir.unwrap_or_else(crate::core::signing::default_keyring_dir),
+        )
+    };
+    match sub {
+        KeysCmd::Generate { name, pq, key_dir } => {
+            super::keys::cmd_keys_generate(&keyring(key_dir), &name, pq)
+        }
+        KeysCmd::Import {
+            name,
+            file,
+            key_dir,
+        } => super::keys::cmd_keys_import(&keyring(key_dir), &name, &file),
+        KeysCmd::List { key_dir, json } => super::keys::cmd_keys_list(&keyring(key_dir), json),
+    }
+}
+
 fn dispatch_sign(args: RecipeSignArgs) -> Result<(), String> {
     if args.pq {
         super::pq_signing::cmd_dual_sign(
             &args.recipe,
             args.verify,
             args.signer.as_deref(),
+            args.trust_policy.as_deref(),
             args.json,
         )
     } else {
@@ -116,6 +147,7 @@ fn dispatch_sign(args: RecipeSignArgs) -> Result<(), String> {
             &args.recipe,
             args.verify,
             args.signer.as_deref(),
+            args.trust_policy.as_deref(),
             args.json,
         )
     }
@@ -113,8 +113,17 @@ fn dispatch_archive(sub: ArchiveCmd) -> Result<(), String> {
             store_dir,
             output,
         } => cmd_archive_pack(&hash, &store_dir, output.as_deref()),
-        ArchiveCmd::Unpack { file, store_dir } => cmd_archive_unpack(&file, &store_dir),
+        ArchiveCmd::Unpack {
+            file,
+            store_dir,
+            trust_policy,
+        } => cmd_archive_unpack(&file, &store_dir, trust_policy.as_deref()),
+        ArchiveCmd::Sign { file, key } => cmd_archive_sign(&file, key.as_deref()),
         ArchiveCmd::Inspect { file, json } => cmd_archive_inspect(&file, json),
-        ArchiveCmd::Verify { file, json } => cmd_archive_verify(&file, json),
+        ArchiveCmd::Verify {
+            file,
+            trust_policy,
+            json,
+        } => cmd_archive_verify(&file, trust_policy.as_deref(), json),
     }
 }
@@ -201,13 +201,14 @@ fn check_stale_lock(state_dir: &Path, fix: bool) -> Option<DoctorCheck> {
     if !state_dir.exists() {
         return None;
     }
-    let lock_path = state_dir.join(".forjar.lock");
+    let lock_path = crate::core::state::process_lock_path(state_dir);
     if !lock_path.exists() {
         return None;
     }
     if fix {
-        match std::fs::remove_file(&lock_path) {
-            Ok(()) => Some(DoctorCheck {
+        // Through force_unlock so the removal lands in lock-audit.jsonl.
+        match crate::core::state::force_unlock(state_dir) {
+            Ok(_) => Some(DoctorCheck {
                 name: "lock".to_string(),
                 status: DoctorStatus::Pass,
                 detail: "stale lock removed (--fix)".to_string(),
@@ -219,10 +220,15 @@ fn check_stale_lock(state_dir: &Path, fix: bool) -> Option<DoctorCheck> {
             }),
         }
     } else {
+        let holder = crate::core::state::lease::read_lease(state_dir)
+            .ok()
+            .flatten()
+            .map(|(lease, _)| format!(" (held by {})", lease.describe()))
+            .unwrap_or_default();
         Some(DoctorCheck {
             name: "lock".to_string(),
             status: DoctorStatus::Warn,
-            detail: "stale lock file exists (use --fix to remove)".to_string(),
+            detail: format!("stale lock file exists{holder} (use --fix to remove)"),
         })
     }
 }
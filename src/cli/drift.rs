@@ -3,12 +3,12 @@
 use super::apply::*;
 use super::apply_helpers::*;
 use super::helpers::*;
-use crate::core::{state, types};
+use crate::core::{pki, state, types};
 use crate::tripwire::drift;
 use std::path::Path;
 
 /// Check one machine for drift, appending findings to all_findings (JSON) or printing text.
-fn check_machine_drift(
+pub(super) fn check_machine_drift(
     name: &str,
     lock: &types::StateLock,
     config: Option<&types::ForjarConfig>,
@@ -30,7 +30,14 @@ fn check_machine_drift(
         _ => drift::detect_drift(lock),
     };
 
-    if findings.is_empty() {
+    // Certificates inside their renewal window count as drift: the next
+    // apply re-issues them.
+    let expiring: Vec<(String, i64)> = pki::due_renewals(lock, config, pki::now_unix())
+        .into_iter()
+        .filter(|(id, _)| !findings.iter().any(|f| &f.resource_id == id))
+        .collect();
+
+    if findings.is_empty() && expiring.is_empty() {
         if !json {
             println!("  No drift detected.");
         }
@@ -52,7 +59,20 @@ fn check_machine_drift(
             println!("    Actual:   {}", f.actual_hash);
         }
     }
-    findings.len()
+    for (id, left) in &expiring {
+        let detail = format!("certificate {}", pki::describe_time_left(*left));
+        if json {
+            all_findings.push(serde_json::json!({
+                "machine": name,
+                "resource": id,
+                "detail": detail,
+                "expires_in_seconds": left,
+            }));
+        } else {
+            println!("  {}: {} ({})", yellow("EXPIRING"), id, detail);
+        }
+    }
+    findings.len() + expiring.len()
 }
 
 /// Print drift summary (JSON or text).
@@ -146,6 +166,7 @@ fn run_drift_remediation(
         None,  // telemetry_endpoint
         false, // refresh
         None,  // force_tag
+        None,  // lock_reason
     )?;
     if !json {
         println!("Remediation complete.");
@@ -281,6 +302,10 @@ pub(crate) fn cmd_drift(
     env_file: Option<&Path>,
 ) -> Result<(), String> {
     let config = load_drift_config(config_path, env_file)?;
+    if let Some(ref cfg) = config {
+        crate::core::state::backend::attach(cfg.policy.backend.as_ref(), state_dir)?;
+        crate::core::state_recipients::attach(cfg.policy.state_encryption.as_ref(), state_dir)?;
+    }
 
     if dry_run {
         return cmd_drift_dry_run(state_dir, machine_filter, json);
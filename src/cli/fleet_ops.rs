@@ -105,6 +105,7 @@ pub(crate) fn cmd_retry_failed(
             None,  // telemetry_endpoint
             false, // refresh
             None,  // force_tag
+            None,  // lock_reason
         )?;
     }
 
@@ -188,6 +189,7 @@ pub(crate) fn cmd_rolling(
                 None,  // telemetry_endpoint
                 false, // refresh
                 None,  // force_tag
+                None,  // lock_reason
             )?;
         }
 
@@ -265,6 +267,7 @@ pub(crate) fn cmd_canary(
         None,  // telemetry_endpoint
         false, // refresh
         None,  // force_tag
+        None,  // lock_reason
     )?;
 
     println!("\n{} Canary '{}' succeeded.", green("✓"), canary_machine);
@@ -333,6 +336,7 @@ pub(crate) fn cmd_canary(
             None,  // telemetry_endpoint
             false, // refresh
             None,  // force_tag
+            None,  // lock_reason
         )?;
     }
 
@@ -4,7 +4,13 @@
 //! Generations are numbered sequentially (0, 1, 2, ...) with a `current`
 //! symlink pointing to the active generation. Rollback switches the symlink
 //! atomically via temp-symlink + rename(2).
+//!
+//! Snapshots are copied key by key through the state backend, so they live
+//! next to the state in a remote store too. The `current` symlink is local;
+//! `generations/.current` records the same number in the backend.
 
+use crate::core::state::backend::{backend_for, StateBackend};
+use crate::core::state::lease;
 use crate::core::types::GenerationMeta;
 use std::path::{Path, PathBuf};
 
@@ -31,7 +37,8 @@ pub(crate) fn create_generation(
         .map_err(|e| format!("cannot create generation {next}: {e}"))?;
 
     // Copy state files (skip generations/ and snapshots/ directories)
-    copy_state_to_generation(state_dir, &target)?;
+    let backend = backend_for(state_dir);
+    copy_state_to_generation(backend.as_ref(), next)?;
 
     // Write metadata using GenerationMeta (FJ-2002)
     let mut meta = GenerationMeta::new(next, crate::tripwire::eventlog::now_iso8601());
@@ -46,11 +53,16 @@ pub(crate) fn create_generation(
     }
     meta.forjar_version = Some(env!("CARGO_PKG_VERSION").to_string());
     let meta_yaml = meta.to_yaml()?;
-    std::fs::write(target.join(".generation.yaml"), meta_yaml)
+    backend
+        .put(
+            &format!("generations/{next}/.generation.yaml"),
+            meta_yaml.as_bytes(),
+        )
         .map_err(|e| format!("cannot write generation metadata: {e}"))?;
 
     // Atomically switch current symlink
     atomic_symlink_switch(&gen_dir, &target)?;
+    record_current(backend.as_ref(), next)?;
 
     Ok(next)
 }
@@ -71,10 +83,12 @@ pub(crate) fn rollback_to_generation(
     }
 
     // Restore state from generation snapshot
-    restore_generation_to_state(&target, state_dir)?;
+    let backend = backend_for(state_dir);
+    restore_generation_to_state(backend.as_ref(), generation, state_dir)?;
 
     // Switch current symlink
     atomic_symlink_switch(&gen_dir, &target)?;
+    record_current(backend.as_ref(), generation)?;
 
     println!("Rolled back to generation {generation}");
     Ok(())
@@ -106,12 +120,21 @@ pub(crate) fn list_generations(state_dir: &Path, json: bool) -> Result<(), Strin
     Ok(())
 }
 
-/// Get the current generation number.
+/// Get the current generation number: the `current` symlink, or the
+/// `.current` record pulled from a remote backend.
 pub(crate) fn current_generation(gen_dir: &Path) -> Option<u32> {
     let current = gen_dir.join("current");
-    let target = std::fs::read_link(&current).ok()?;
-    let name = target.file_name()?.to_string_lossy().to_string();
-    name.parse().ok()
+    match std::fs::read_link(&current) {
+        Ok(target) => {
+            let name = target.file_name()?.to_string_lossy().to_string();
+            name.parse().ok()
+        }
+        Err(_) => std::fs::read_to_string(gen_dir.join(".current"))
+            .ok()?
+            .trim()
+            .parse()
+            .ok(),
+    }
 }
 
 /// Garbage-collect old generations, keeping only the newest `keep` entries.
@@ -128,11 +151,17 @@ pub(crate) fn gc_generations(state_dir: &Path, keep: u32, verbose: bool) {
     }
     gens.sort();
     let to_remove = gens.len() - keep as usize;
+    let backend = backend_for(state_dir);
     for &gen_num in gens.iter().take(to_remove) {
         let path = gen_dir.join(gen_num.to_string());
         if verbose {
             eprintln!("generation gc: removing generation {gen_num}");
         }
+        if let Ok(keys) = backend.list(&format!("generations/{gen_num}/")) {
+            for key in keys {
+                let _ = backend.delete(&key);
+            }
+        }
         let _ = std::fs::remove_dir_all(path);
     }
 }
@@ -238,69 +267,87 @@ pub(super) fn read_created_at(meta_path: &Path) -> String {
         .unwrap_or_else(|| "unknown".to_string())
 }
 
-/// Copy state files into a generation directory, skipping generations/ and snapshots/.
-fn copy_state_to_generation(state_dir: &Path, target: &Path) -> Result<(), String> {
-    let entries =
-        std::fs::read_dir(state_dir).map_err(|e| format!("cannot read state dir: {e}"))?;
-    for entry in entries.flatten() {
-        let name = entry.file_name().to_string_lossy().to_string();
-        if name == "generations" || name == "snapshots" || name == ".snapshots" {
-            continue;
-        }
-        let src = entry.path();
-        let dst = target.join(&name);
-        if src.is_dir() {
-            std::fs::create_dir_all(&dst)
-                .map_err(|e| format!("cannot create {}: {e}", dst.display()))?;
-            super::snapshot::copy_dir_recursive(&src, &dst, "")?;
-        } else {
-            std::fs::copy(&src, &dst)
-                .map_err(|e| format!("cannot copy {} → {}: {e}", src.display(), dst.display()))?;
-        }
+/// Whether a state key belongs to the live state (not to generations/ or
+/// snapshots/, the apply lock or its audit log).
+fn is_live_state_key(key: &str) -> bool {
+    let top = key.split('/').next().unwrap_or(key);
+    !matches!(
+        top,
+        "generations" | "snapshots" | ".snapshots" | lease::LEASE_KEY | lease::AUDIT_KEY
+    )
+}
+
+/// Copy state files into generation `num`, skipping generations/ and snapshots/.
+fn copy_state_to_generation(backend: &dyn StateBackend, num: u32) -> Result<(), String> {
+    let keys = backend
+        .list("")
+        .map_err(|e| format!("cannot read state dir: {e}"))?;
+    for key in keys.iter().filter(|k| is_live_state_key(k)) {
+        let data = backend.get(key)?;
+        backend
+            .put(&format!("generations/{num}/{key}"), &data)
+            .map_err(|e| format!("cannot copy {key} into generation {num}: {e}"))?;
     }
     Ok(())
 }
 
-/// Restore state from a generation directory back to state_dir.
-fn restore_generation_to_state(gen_path: &Path, state_dir: &Path) -> Result<(), String> {
+/// Restore state from generation `num` back to the live state.
+fn restore_generation_to_state(
+    backend: &dyn StateBackend,
+    num: u32,
+    state_dir: &Path,
+) -> Result<(), String> {
     // Remove current state (except generations/ and snapshots/)
-    let entries =
-        std::fs::read_dir(state_dir).map_err(|e| format!("cannot read state dir: {e}"))?;
-    for entry in entries.flatten() {
-        let name = entry.file_name().to_string_lossy().to_string();
-        if name == "generations" || name == "snapshots" || name == ".snapshots" {
+    let live = backend
+        .list("")
+        .map_err(|e| format!("cannot read state dir: {e}"))?;
+    for key in live.iter().filter(|k| is_live_state_key(k)) {
+        backend
+            .delete(key)
+            .map_err(|e| format!("cannot remove {key}: {e}"))?;
+    }
+    remove_empty_dirs(state_dir);
+
+    // Copy generation contents back (skip metadata)
+    let prefix = format!("generations/{num}/");
+    let keys = backend
+        .list(&prefix)
+        .map_err(|e| format!("cannot read generation: {e}"))?;
+    for key in &keys {
+        let Some(rel) = key.strip_prefix(&prefix) else {
+            continue;
+        };
+        if rel == ".generation.yaml" {
             continue;
         }
-        let path = entry.path();
-        if path.is_dir() {
-            std::fs::remove_dir_all(&path)
-                .map_err(|e| format!("cannot remove {}: {e}", path.display()))?;
-        } else {
-            std::fs::remove_file(&path)
-                .map_err(|e| format!("cannot remove {}: {e}", path.display()))?;
-        }
+        let data = backend.get(key)?;
+        backend
+            .put(rel, &data)
+            .map_err(|e| format!("cannot restore {rel}: {e}"))?;
     }
+    Ok(())
+}
 
-    // Copy generation contents back (skip metadata)
-    let entries =
-        std::fs::read_dir(gen_path).map_err(|e| format!("cannot read generation: {e}"))?;
+/// Remove directories of the live state left empty by a restore.
+fn remove_empty_dirs(state_dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(state_dir) else {
+        return;
+    };
     for entry in entries.flatten() {
         let name = entry.file_name().to_string_lossy().to_string();
-        if name == ".generation.yaml" {
-            continue;
-        }
-        let src = entry.path();
-        let dst = state_dir.join(&name);
-        if src.is_dir() {
-            std::fs::create_dir_all(&dst)
-                .map_err(|e| format!("cannot create {}: {e}", dst.display()))?;
-            super::snapshot::copy_dir_recursive(&src, &dst, "")?;
-        } else {
-            std::fs::copy(&src, &dst)
-                .map_err(|e| format!("cannot copy {} → {}: {e}", src.display(), dst.display()))?;
+        let path = entry.path();
+        if path.is_dir() && is_live_state_key(&name) {
+            remove_empty_dirs(&path);
+            let _ = std::fs::remove_dir(&path);
         }
     }
-    Ok(())
+}
+
+/// Record the current generation in the backend (`generations/.current`).
+fn record_current(backend: &dyn StateBackend, num: u32) -> Result<(), String> {
+    backend
+        .put("generations/.current", num.to_string().as_bytes())
+        .map_err(|e| format!("cannot record current generation: {e}"))
 }
 
 /// Atomically switch the `current` symlink to point to `target_dir`.
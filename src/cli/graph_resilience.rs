@@ -24,6 +24,32 @@ fn type_cost(rt: &types::ResourceType) -> u32 {
         types::ResourceType::Image => 1,
         types::ResourceType::Build => 5,
         types::ResourceType::GithubRelease => 3,
+        types::ResourceType::DockerNetwork => 1,
+        types::ResourceType::DockerVolume => 1,
+        types::ResourceType::ContainerStack => 3,
+        types::ResourceType::WaitFor => 1,
+        types::ResourceType::Exec => 2,
+        types::ResourceType::Swap => 2,
+        types::ResourceType::Partition => 3,
+        types::ResourceType::LvmVg => 2,
+        types::ResourceType::LvmLv => 2,
+        types::ResourceType::Filesystem => 3,
+        types::ResourceType::WireguardInterface => 2,
+        types::ResourceType::WireguardPeer => 1,
+        types::ResourceType::NetworkInterface => 2,
+        types::ResourceType::Certificate => 1,
+        types::ResourceType::System => 1,
+        types::ResourceType::ZfsPool => 3,
+        types::ResourceType::ZfsDataset => 2,
+        types::ResourceType::ZfsSnapshotPolicy => 1,
+        types::ResourceType::BinaryRelease => 3,
+        types::ResourceType::PgRole => 2,
+        types::ResourceType::PgDatabase => 2,
+        types::ResourceType::PgExtension => 1,
+        types::ResourceType::ApparmorProfile => 1,
+        types::ResourceType::SelinuxBoolean => 1,
+        types::ResourceType::SelinuxFcontext => 1,
+        types::ResourceType::SelinuxMode => 1,
     }
 }
 
@@ -0,0 +1,77 @@
+//! FJ-1432: `forjar keys` — Ed25519 signing keys (generate, import, list),
+//! with optional ML-DSA-65 halves for dual signing (FJ-1433).
+
+use super::helpers::*;
+use crate::core::pq_signing::PQ_ALGORITHM;
+use crate::core::signing::Keyring;
+use std::path::Path;
+
+/// Generate a keypair; with `pq`, also an ML-DSA-65 keypair (FJ-1433),
+/// which is added on its own when the Ed25519 key already exists.
+pub(crate) fn cmd_keys_generate(keyring: &Keyring, name: &str, pq: bool) -> Result<(), String> {
+    if !(pq && keyring.signing_key(name).is_ok()) {
+        let key = keyring.generate(name)?;
+        println!(
+            "{} Generated key '{}' in {}",
+            green("✓"),
+            name,
+            keyring.dir().display()
+        );
+        println!("Public key: {}", key.public_key()?);
+    }
+    if pq {
+        keyring.generate_pq(name)?;
+        println!(
+            "{} Generated {PQ_ALGORITHM} key for '{}'; public key in {}",
+            green("✓"),
+            name,
+            keyring
+                .dir()
+                .join(PQ_ALGORITHM)
+                .join(format!("{name}.pub"))
+                .display()
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn cmd_keys_import(keyring: &Keyring, name: &str, file: &Path) -> Result<(), String> {
+    let entry = keyring.import(name, file)?;
+    let kind = if entry.private {
+        "keypair"
+    } else {
+        "public key"
+    };
+    println!("{} Imported {kind} '{}'", green("✓"), entry.name);
+    println!("Public key: {}", entry.public_key);
+    Ok(())
+}
+
+pub(crate) fn cmd_keys_list(keyring: &Keyring, json: bool) -> Result<(), String> {
+    let keys = keyring.list()?;
+    if json {
+        let out = serde_json::json!({
+            "key_dir": keyring.dir().display().to_string(),
+            "keys": keys,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&out).map_err(|e| format!("JSON error: {e}"))?
+        );
+        return Ok(());
+    }
+    if keys.is_empty() {
+        println!("No keys in {}", keyring.dir().display());
+        return Ok(());
+    }
+    for key in &keys {
+        let kind = if key.private { "sign+verify" } else { "verify" };
+        let pq = if key.pq_public_key.is_some() {
+            PQ_ALGORITHM
+        } else {
+            "-"
+        };
+        println!("{:<20} {:<12} {:<10} {}", key.name, kind, pq, key.public_key);
+    }
+    Ok(())
+}
@@ -158,6 +158,11 @@ pub(crate) fn cmd_lock(
     }
     inject_workspace_param(&mut config, workspace);
     resolver::resolve_data_sources(&mut config)?;
+    resolver::resolve_recipe_outputs(&mut config, state_dir)?;
+    // FJ-1432: Generated locks are signed as they are saved.
+    let config_dir = file.parent().unwrap_or_else(|| Path::new("."));
+    let trust = crate::core::signing::trust_policy_for(&config, config_dir)?;
+    crate::core::signing::attach_lock_signing(trust.as_ref(), state_dir, dry_run || verify)?;
 
     let execution_order = resolver::build_execution_order(&config)?;
 
@@ -269,10 +274,16 @@ pub(crate) fn cmd_lock_info(state_dir: &Path, json: bool) -> Result<(), String>
         }
     }
 
+    // FJ-266: Who holds the apply lock, and past force-unlocks
+    let apply_lock = state::lease::read_lease(state_dir)?.map(|(lease, _)| lease);
+    let force_unlocks = state::lease::audit_entries(state_dir);
+
     if json {
         let result = serde_json::json!({
             "machines": machines,
             "total_resources": total_resources,
+            "apply_lock": apply_lock,
+            "force_unlocks": force_unlocks,
         });
         println!(
             "{}",
@@ -282,6 +293,18 @@ pub(crate) fn cmd_lock_info(state_dir: &Path, json: bool) -> Result<(), String>
         println!("Lock Info:\n");
         println!("  Total machines: {}", machines.len());
         println!("  Total resources: {total_resources}");
+        match apply_lock {
+            Some(ref lease) => println!("  Apply lock: held by {}", lease.describe()),
+            None => println!("  Apply lock: free"),
+        }
+        if let Some(last) = force_unlocks.last() {
+            println!(
+                "  Force-unlocks: {} (last by {} at {})",
+                force_unlocks.len(),
+                last["by"].as_str().unwrap_or("?"),
+                last["ts"].as_str().unwrap_or("?"),
+            );
+        }
         for m in &machines {
             println!(
                 "\n  {} ({}): {} resources, schema {}, generated {}",
@@ -163,34 +163,19 @@ pub(crate) fn cmd_lock_rebase(
 
 // ── FJ-465: lock sign ──
 
+/// Sign every machine lock with the Ed25519 key named by `key` (keyring
+/// name or key file). The key is only loaded when there is a lock to sign.
 pub(crate) fn cmd_lock_sign(state_dir: &Path, key: &str, json: bool) -> Result<(), String> {
-    use crate::tripwire::hasher;
+    use crate::core::signing;
 
-    let mut signed = 0usize;
-    if state_dir.exists() {
-        let entries = std::fs::read_dir(state_dir).map_err(|e| e.to_string())?;
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                let m_name = path
-                    .file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy()
-                    .to_string();
-                if m_name.starts_with('.') {
-                    continue;
-                }
-                let lock_path = path.join("state.lock.yaml");
-                if lock_path.exists() {
-                    let content = std::fs::read_to_string(&lock_path).map_err(|e| e.to_string())?;
-                    let hash = hasher::hash_string(&format!("{content}{key}"));
-                    let sig_path = path.join("lock.sig");
-                    std::fs::write(&sig_path, &hash).map_err(|e| e.to_string())?;
-                    signed += 1;
-                }
-            }
+    let machines = signing::locked_machines(state_dir);
+    if !machines.is_empty() {
+        let signing_key = signing::load_signing_key(key)?;
+        for m in &machines {
+            signing::sign_state_lock(state_dir, m, &signing_key)?;
         }
     }
+    let signed = machines.len();
 
     if json {
         println!(
@@ -3,40 +3,50 @@
 use super::helpers::*;
 use super::helpers_time::*;
 use super::lock_ops::*;
+use crate::core::signing::{self, PublicKey, Scope};
 use std::path::Path;
 
 // ── FJ-475: lock verify-sig ──
 
-/// Verify a single machine's signature, returning (valid, json_entry).
+/// Verify a single machine's signature against `key`, returning
+/// (valid, json_entry).
 fn verify_machine_sig(
     state_dir: &Path,
     m: &str,
-    key: &str,
+    key: &PublicKey,
 ) -> Result<Option<(bool, serde_json::Value)>, String> {
-    use crate::tripwire::hasher;
-    let lock_path = state_dir.join(m).join("state.lock.yaml");
-    if !lock_path.exists() {
+    if !state_dir.join(m).join("state.lock.yaml").exists() {
         return Ok(None);
     }
-    let content = std::fs::read_to_string(&lock_path).map_err(|e| format!("read lock: {e}"))?;
-    let expected_sig = hasher::hash_string(&format!("{content}{key}"));
-    let sig_path = state_dir.join(m).join("lock.sig");
-    let actual_sig = std::fs::read_to_string(&sig_path).unwrap_or_default();
-    let valid = actual_sig.trim() == expected_sig;
+    let (content, sig) = signing::read_state_lock_signature(state_dir, m)?;
+    let (valid, reason) = match sig.map(|s| s.verify(Scope::Lock, &content)) {
+        None => (false, "unsigned".to_string()),
+        Some(Ok(signer)) if signer == *key => (true, "valid".to_string()),
+        Some(Ok(signer)) => (false, format!("signed by {signer}")),
+        Some(Err(e)) => (false, e),
+    };
     let entry = serde_json::json!({
         "machine": m,
         "valid": valid,
-        "expected": expected_sig.get(..16).unwrap_or(&expected_sig),
+        "reason": reason,
     });
     Ok(Some((valid, entry)))
 }
 
+/// Verify every machine lock signature against the public key named by
+/// `key` (`ed25519:...`, key file or keyring name).
 pub(crate) fn cmd_lock_verify_sig(state_dir: &Path, key: &str, json: bool) -> Result<(), String> {
     let machines = discover_machines(state_dir);
     let mut results: Vec<serde_json::Value> = Vec::new();
     let mut all_valid = true;
+    // Loaded on first use so an empty state dir needs no key.
+    let mut verify_key = None;
     for m in &machines {
-        if let Some((valid, entry)) = verify_machine_sig(state_dir, m, key)? {
+        let public_key = match verify_key {
+            Some(k) => k,
+            None => *verify_key.insert(signing::load_public_key(key)?),
+        };
+        if let Some((valid, entry)) = verify_machine_sig(state_dir, m, &public_key)? {
             if !valid {
                 all_valid = false;
             }
@@ -45,7 +55,8 @@ pub(crate) fn cmd_lock_verify_sig(state_dir: &Path, key: &str, json: bool) -> Re
             } else if valid {
                 println!("{} {} — signature valid", green("✓"), m);
             } else {
-                println!("{} {} — signature INVALID or missing", red("✗"), m);
+                let reason = entry["reason"].as_str().unwrap_or_default();
+                println!("{} {} — signature INVALID: {}", red("✗"), m, reason);
             }
         }
     }
@@ -177,26 +188,26 @@ pub(crate) fn cmd_lock_rotate_keys(
     new_key: &str,
     json: bool,
 ) -> Result<(), String> {
-    use crate::tripwire::hasher;
     let machines = discover_machines(state_dir);
     let mut rotated = 0;
-    for m in &machines {
-        let lock_path = state_dir.join(m).join("state.lock.yaml");
-        if !lock_path.exists() {
-            continue;
+    if !machines.is_empty() {
+        let old_public = signing::load_public_key(old_key)?;
+        let new_signing = signing::load_signing_key(new_key)?;
+        // Check every existing signature before re-signing any of them.
+        for m in &machines {
+            let (content, sig) = signing::read_state_lock_signature(state_dir, m)?;
+            if let Some(sig) = sig {
+                if sig.verify(Scope::Lock, &content).ok() != Some(old_public) {
+                    return Err(format!(
+                        "{m}: old key does not match existing signature — rotation aborted"
+                    ));
+                }
+            }
         }
-        let content = std::fs::read_to_string(&lock_path).map_err(|e| format!("read lock: {e}"))?;
-        let sig_path = state_dir.join(m).join("lock.sig");
-        let old_sig = std::fs::read_to_string(&sig_path).unwrap_or_default();
-        let expected_old = hasher::hash_string(&format!("{content}{old_key}"));
-        if !old_sig.is_empty() && old_sig.trim() != expected_old {
-            return Err(format!(
-                "{m}: old key does not match existing signature — rotation aborted"
-            ));
+        for m in &machines {
+            signing::sign_state_lock(state_dir, m, &new_signing)?;
+            rotated += 1;
         }
-        let new_sig = hasher::hash_string(&format!("{content}{new_key}"));
-        std::fs::write(&sig_path, &new_sig).map_err(|e| format!("Failed to write sig: {e}"))?;
-        rotated += 1;
     }
     if json {
         let result = serde_json::json!({"rotated": rotated, "total": machines.len()});
@@ -284,28 +295,16 @@ pub(crate) fn cmd_lock_verify_chain(state_dir: &Path, json: bool) -> Result<(),
             continue;
         }
 
-        let sig_content = std::fs::read_to_string(&sig_path)
-            .unwrap_or_default()
-            .trim()
-            .to_string();
-        // Chain verification: confirm sig file is a valid BLAKE3 hash (keyed verification
-        // requires the signing key — use lock-verify-sig for key-based checks)
-        let sig_hash = sig_content.strip_prefix("blake3:").unwrap_or(&sig_content);
-        if sig_hash.len() == 64 && sig_hash.chars().all(|c| c.is_ascii_hexdigit()) {
-            chain_results.push((
-                m.clone(),
-                true,
-                "signature present and well-formed".to_string(),
-            ));
-        } else {
-            chain_results.push((
-                m.clone(),
-                false,
-                format!(
-                    "malformed signature: {}",
-                    &sig_content[..sig_content.len().min(20)]
-                ),
-            ));
+        // Chain verification: the signature must verify against the key it
+        // names. Whether that key is trusted is `lock-verify-sig`'s job.
+        let detail = signing::read_state_lock_signature(state_dir, m).and_then(|(content, sig)| {
+            let sig = sig.ok_or_else(|| "signature file missing".to_string())?;
+            let key = sig.verify(Scope::Lock, &content)?;
+            Ok(format!("signed by {} ({key})", sig.signer))
+        });
+        match detail {
+            Ok(detail) => chain_results.push((m.clone(), true, detail)),
+            Err(e) => chain_results.push((m.clone(), false, format!("invalid signature: {e}"))),
         }
     }
 
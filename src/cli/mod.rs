@@ -114,6 +114,7 @@ mod infra_query;
 mod infra_query_live;
 mod init;
 mod iso_export;
+mod keys;
 mod lineage;
 pub mod lint;
 mod lock_audit;
@@ -148,6 +149,7 @@ mod pull_agent;
 mod query_format;
 mod recipe_registry;
 mod recipe_signing;
+mod recipe_update;
 mod remote_state;
 mod repro_proof;
 mod reseal;
@@ -157,6 +159,7 @@ mod runtime_invariants;
 mod saga_coordinator;
 mod sbom;
 mod score;
+mod secret_rotate;
 mod secrets;
 mod security_scan;
 mod service_catalog;
@@ -167,6 +170,7 @@ mod stack_dep_graph;
 mod stack_diff;
 mod state_encrypt;
 mod state_import_brownfield;
+mod state_recipients;
 mod status_alerts;
 mod status_analytics;
 mod status_compliance;
@@ -120,3 +120,9 @@ mod tests_lsp_ext_cov;
 mod tests_parallel_multi_stack_cov;
 #[cfg(test)]
 mod tests_iso_export_ext2_cov;
+#[cfg(test)]
+mod tests_state_recipients;
+#[cfg(test)]
+mod tests_secret_rotate;
+#[cfg(test)]
+mod tests_recipe_update;
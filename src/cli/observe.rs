@@ -3,7 +3,7 @@
 use super::helpers::*;
 use super::helpers_state::*;
 use super::print_helpers::*;
-use crate::core::{executor, planner, resolver, types};
+use crate::core::{executor, pki, planner, resolver, types};
 use crate::tripwire::{anomaly, tracer};
 use std::path::Path;
 
@@ -296,6 +296,9 @@ pub(crate) fn cmd_watch(
             }
             last_content = content;
             handle_watch_change(file, state_dir, auto_apply);
+        } else if auto_apply && certificates_due(file, state_dir) {
+            println!("\nCertificates due for renewal — re-planning...");
+            handle_watch_change(file, state_dir, auto_apply);
         }
 
         std::thread::sleep(interval);
@@ -318,7 +321,8 @@ pub(crate) fn handle_watch_change(file: &Path, state_dir: &Path, auto_apply: boo
             return;
         }
     };
-    let locks = load_all_locks(state_dir, &config);
+    let locks =
+        pki::mark_renewals_due(&config, load_all_locks(state_dir, &config), pki::now_unix());
     let plan = planner::plan(&config, &execution_order, &locks, None);
     print_plan(&plan, None, Some(&config));
 
@@ -327,6 +331,17 @@ pub(crate) fn handle_watch_change(file: &Path, state_dir: &Path, auto_apply: boo
     }
 }
 
+/// True when an applied certificate has entered its renewal window.
+fn certificates_due(file: &Path, state_dir: &Path) -> bool {
+    let Ok(config) = parse_and_validate(file) else {
+        return false;
+    };
+    let now = pki::now_unix();
+    load_all_locks(state_dir, &config)
+        .values()
+        .any(|lock| !pki::due_renewals(lock, Some(&config), now).is_empty())
+}
+
 /// Execute an auto-apply during watch mode.
 fn run_watch_apply(config: &types::ForjarConfig, state_dir: &Path) {
     println!("\nAuto-applying...");
@@ -351,6 +366,7 @@ fn run_watch_apply(config: &types::ForjarConfig, state_dir: &Path) {
         run_id: Some(crate::core::types::generate_run_id()),
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     match executor::apply(&cfg) {
         Ok(results) => {
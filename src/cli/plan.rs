@@ -62,6 +62,7 @@ pub(crate) fn cmd_plan(
     }
     inject_workspace_param(&mut config, workspace);
     resolver::resolve_data_sources(&mut config)?;
+    resolver::resolve_recipe_outputs(&mut config, state_dir)?;
 
     // FJ-285: --target filters config to one resource + transitive deps
     if let Some(target_id) = target {
@@ -224,6 +225,8 @@ pub(crate) fn cmd_plan_compact(
 ) -> Result<(), String> {
     let config = parse_and_validate(file)?;
     let execution_order = resolver::build_execution_order(&config)?;
+    crate::core::state::backend::attach(config.policy.backend.as_ref(), state_dir)?;
+    crate::core::state_recipients::attach(config.policy.state_encryption.as_ref(), state_dir)?;
     let locks = load_machine_locks(&config, state_dir, machine_filter)?;
     let plan = planner::plan(&config, &execution_order, &locks, None);
 
@@ -1,11 +1,20 @@
 //! FJ-1433: Post-quantum dual signing.
 //!
-//! BLAKE3 + simulated PQ signature for quantum transition readiness.
-//! Real PQ: SLH-DSA (SPHINCS+). This module provides the framework
-//! and uses BLAKE3 as a placeholder until PQ crates stabilize.
+//! Every file gets two signatures over the same message: Ed25519
+//! (classical) and ML-DSA-65 (FIPS 204, post-quantum). Verification
+//! requires both, so the envelope stays sound if either scheme is broken.
+//! The keys recorded in the envelope are only used to pick a pinned signer
+//! (`--signer`, a trust policy's recipe signers, or `FORJAR_SIGNING_KEY`);
+//! a signature made with any other key pair is rejected.
 
+use crate::core::pq_signing::{self, PqPublicKey, PqSigningKey, PQ_ALGORITHM};
+use crate::core::signing::{self, PublicKey, SigningKey, ALGORITHM};
+use base64::Engine;
 use std::path::Path;
 
+/// Domain separator of the dual-signed message.
+const MESSAGE_TAG: &str = "forjar-dual-signature-v1";
+
 /// Dual signature with classical + PQ components.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DualSignature {
@@ -16,6 +25,15 @@ pub struct DualSignature {
     pub pq_sig: String,
     pub pq_alg: String,
     pub timestamp: String,
+    /// Keyring name of the signing key (informational)
+    #[serde(default)]
+    pub signer: String,
+    /// `ed25519:<base64>` key that verifies `classical_sig`
+    #[serde(default)]
+    pub classical_key: String,
+    /// `ml-dsa-65:<base64>` key that verifies `pq_sig`
+    #[serde(default)]
+    pub pq_key: String,
 }
 
 /// Dual verification result.
@@ -28,29 +46,96 @@ pub struct DualVerifyResult {
     pub reason: String,
 }
 
+/// A key pair whose dual signatures are accepted.
+#[derive(Debug, Clone)]
+pub struct PinnedSigner {
+    pub name: String,
+    pub classical: PublicKey,
+    pub pq: PqPublicKey,
+}
+
+impl PinnedSigner {
+    /// Whether `sig` names this signer's keys.
+    fn matches(&self, sig: &DualSignature) -> bool {
+        PublicKey::parse(&sig.classical_key).ok().as_ref() == Some(&self.classical)
+            && PqPublicKey::parse(&sig.pq_key).ok().as_ref() == Some(&self.pq)
+    }
+}
+
+/// Public halves of the key named by `spec` (see [`signing::load_public_key`]).
+fn pinned_key(spec: &str) -> Result<PinnedSigner, String> {
+    Ok(PinnedSigner {
+        name: spec.to_string(),
+        classical: signing::load_public_key(spec)?,
+        pq: pq_signing::load_pq_public_key(spec)?,
+    })
+}
+
+/// Signers a dual signature must come from: the key named by `signer`,
+/// else the recipe signers with a `pq_key` in the trust policy
+/// (`trust_policy` or `FORJAR_TRUST_POLICY`), else the key named by
+/// `FORJAR_SIGNING_KEY`.
+pub fn pinned_signers(
+    signer: Option<&str>,
+    trust_policy: Option<&Path>,
+) -> Result<Vec<PinnedSigner>, String> {
+    if let Some(spec) = signer {
+        return Ok(vec![pinned_key(spec)?]);
+    }
+    if let Some(policy) = signing::load_trust_policy(trust_policy)? {
+        let mut pinned = Vec::new();
+        for s in &policy.recipes.signers {
+            if let Some(ref pq_key) = s.pq_key {
+                pinned.push(PinnedSigner {
+                    name: s.name.clone(),
+                    classical: PublicKey::parse(&s.key)?,
+                    pq: PqPublicKey::parse(pq_key)?,
+                });
+            }
+        }
+        if pinned.is_empty() {
+            return Err("trust policy lists no recipe signer with a pq_key".to_string());
+        }
+        return Ok(pinned);
+    }
+    match std::env::var("FORJAR_SIGNING_KEY") {
+        Ok(spec) if !spec.is_empty() => Ok(vec![pinned_key(&spec)?]),
+        _ => Err(
+            "no pinned signer: pass --signer or --trust-policy, or set FORJAR_SIGNING_KEY"
+                .to_string(),
+        ),
+    }
+}
+
+fn b64() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+fn dual_message(blake3_hex: &str) -> Vec<u8> {
+    format!("{MESSAGE_TAG}\n{blake3_hex}\n").into_bytes()
+}
+
 /// Create a dual (classical + PQ) signature.
-pub fn dual_sign(file_path: &Path, signer: &str) -> Result<DualSignature, String> {
+pub fn dual_sign(
+    file_path: &Path,
+    classical: &SigningKey,
+    pq: &PqSigningKey,
+) -> Result<DualSignature, String> {
     let content = std::fs::read(file_path).map_err(|e| format!("read: {e}"))?;
     let blake3_hash = blake3::hash(&content).to_hex().to_string();
-
-    // Classical: BLAKE3-HMAC
-    let classical_input = format!("{blake3_hash}:classical:{signer}");
-    let classical_sig = blake3::hash(classical_input.as_bytes())
-        .to_hex()
-        .to_string();
-
-    // PQ: Simulated SLH-DSA (BLAKE3-based placeholder)
-    let pq_input = format!("{blake3_hash}:slh-dsa:{signer}");
-    let pq_sig = blake3::hash(pq_input.as_bytes()).to_hex().to_string();
+    let message = dual_message(&blake3_hash);
 
     let sig = DualSignature {
         path: file_path.display().to_string(),
+        classical_sig: b64().encode(classical.sign_raw(&message)?),
+        classical_alg: ALGORITHM.to_string(),
+        pq_sig: b64().encode(pq.sign(&message)?),
+        pq_alg: PQ_ALGORITHM.to_string(),
+        timestamp: crate::tripwire::eventlog::now_iso8601(),
+        signer: classical.name.clone(),
+        classical_key: classical.public_key()?.to_string(),
+        pq_key: pq.public_key().to_string(),
         blake3_hash,
-        classical_sig,
-        classical_alg: "blake3-hmac".to_string(),
-        pq_sig,
-        pq_alg: "slh-dsa-blake3-placeholder".to_string(),
-        timestamp: format!("{:?}", std::time::SystemTime::now()),
     };
 
     let sig_path = file_path.with_extension("dual-sig.json");
@@ -60,17 +145,59 @@ pub fn dual_sign(file_path: &Path, signer: &str) -> Result<DualSignature, String
     Ok(sig)
 }
 
-/// Verify a dual signature.
-pub fn dual_verify(file_path: &Path) -> Result<DualVerifyResult, String> {
+/// Check the classical half against the pinned `key`; `Err` carries the
+/// reason it is invalid.
+fn verify_classical(
+    sig: &DualSignature,
+    key: Option<&PublicKey>,
+    message: &[u8],
+) -> Result<(), String> {
+    if sig.classical_alg != ALGORITHM {
+        return Err(format!(
+            "unsupported classical algorithm '{}'",
+            sig.classical_alg
+        ));
+    }
+    let key = key.ok_or_else(|| format!("classical key '{}' is not pinned", sig.classical_key))?;
+    let raw = b64()
+        .decode(&sig.classical_sig)
+        .map_err(|e| format!("invalid classical signature encoding: {e}"))?;
+    if !key.verify(message, &raw)? {
+        return Err(format!("classical signature does not verify against {key}"));
+    }
+    Ok(())
+}
+
+/// Check the PQ half against the pinned `key`; `Err` carries the reason
+/// it is invalid.
+fn verify_pq(sig: &DualSignature, key: Option<&PqPublicKey>, message: &[u8]) -> Result<(), String> {
+    if sig.pq_alg != PQ_ALGORITHM {
+        return Err(format!("unsupported PQ algorithm '{}'", sig.pq_alg));
+    }
+    let key = key.ok_or_else(|| format!("{PQ_ALGORITHM} key of the signature is not pinned"))?;
+    let raw = b64()
+        .decode(&sig.pq_sig)
+        .map_err(|e| format!("invalid PQ signature encoding: {e}"))?;
+    if !key.verify(message, &raw) {
+        return Err(format!("{PQ_ALGORITHM} signature does not verify"));
+    }
+    Ok(())
+}
+
+/// Verify a dual signature: both halves must verify, with the keys of one
+/// of the `pinned` signers.
+pub fn dual_verify(file_path: &Path, pinned: &[PinnedSigner]) -> Result<DualVerifyResult, String> {
+    let mut result = DualVerifyResult {
+        path: file_path.display().to_string(),
+        classical_valid: false,
+        pq_valid: false,
+        both_valid: false,
+        reason: String::new(),
+    };
     let sig_path = file_path.with_extension("dual-sig.json");
     if !sig_path.exists() {
-        return Ok(DualVerifyResult {
-            path: file_path.display().to_string(),
-            classical_valid: false,
-            pq_valid: false,
-            both_valid: false,
-            reason: "no dual signature file".to_string(),
-        });
+        result.reason = "no dual signature file".to_string();
+        return Ok(result);
     }
 
     let sig_data = std::fs::read_to_string(&sig_path).map_err(|e| format!("read sig: {e}"))?;
@@ -79,20 +206,29 @@ pub fn dual_verify(file_path: &Path) -> Result<DualVerifyResult, String> {
 
     let content = std::fs::read(file_path).map_err(|e| format!("read: {e}"))?;
     let current_hash = blake3::hash(&content).to_hex().to_string();
+    if current_hash != sig.blake3_hash {
+        result.reason = "hash mismatch — file modified".to_string();
+        return Ok(result);
+    }
 
-    let hash_valid = current_hash == sig.blake3_hash;
-
-    Ok(DualVerifyResult {
-        path: file_path.display().to_string(),
-        classical_valid: hash_valid,
-        pq_valid: hash_valid,
-        both_valid: hash_valid,
-        reason: if hash_valid {
-            "both signatures valid".to_string()
-        } else {
-            "hash mismatch — file modified".to_string()
-        },
-    })
+    let message = dual_message(&current_hash);
+    let signer = pinned.iter().find(|p| p.matches(&sig));
+    let classical = verify_classical(&sig, signer.map(|p| &p.classical), &message);
+    let pq = verify_pq(&sig, signer.map(|p| &p.pq), &message);
+    result.classical_valid = classical.is_ok();
+    result.pq_valid = pq.is_ok();
+    result.both_valid = result.classical_valid && result.pq_valid;
+    let errors: Vec<String> = [classical, pq]
+        .into_iter()
+        .filter_map(Result::err)
+        .collect();
+    result.reason = match signer {
+        Some(p) if errors.is_empty() => {
+            format!("both signatures valid, pinned signer '{}'", p.name)
+        }
+        _ => errors.join("; "),
+    };
+    Ok(result)
 }
 
 /// CLI command for dual signing/verification.
@@ -100,10 +236,12 @@ pub fn cmd_dual_sign(
     file_path: &Path,
     verify_only: bool,
     signer: Option<&str>,
+    trust_policy: Option<&Path>,
     json: bool,
 ) -> Result<(), String> {
     if verify_only {
-        let result = dual_verify(file_path)?;
+        let pinned = pinned_signers(signer, trust_policy)?;
+        let result = dual_verify(file_path, &pinned)?;
         if json {
             let out =
                 serde_json::to_string_pretty(&result).map_err(|e| format!("JSON error: {e}"))?;
@@ -115,8 +253,9 @@ pub fn cmd_dual_sign(
             return Err("dual verification failed".to_string());
         }
     } else {
-        let who = signer.unwrap_or("local");
-        let sig = dual_sign(file_path, who)?;
+        let classical = signing::resolve_signing_key(signer)?;
+        let pq = pq_signing::resolve_pq_signing_key(signer)?;
+        let sig = dual_sign(file_path, &classical, &pq)?;
         if json {
             let out = serde_json::to_string_pretty(&sig).map_err(|e| format!("JSON error: {e}"))?;
             println!("{out}");
@@ -3,24 +3,11 @@
 //! Local recipe registry for discovery, versioning, and dependency resolution.
 //! Recipes are indexed by name + version with BLAKE3 integrity verification.
 
-use std::path::{Path, PathBuf};
-
-/// A registered recipe entry.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct RegistryEntry {
-    pub name: String,
-    pub version: String,
-    pub path: String,
-    pub blake3: String,
-    pub description: String,
-    pub tags: Vec<String>,
-}
+use std::path::Path;
 
-/// Registry index.
-#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
-pub struct RegistryIndex {
-    pub entries: Vec<RegistryEntry>,
-}
+#[cfg(test)]
+pub use crate::core::recipe::registry::RegistryIndex;
+pub use crate::core::recipe::registry::{default_registry_dir, load_index, RegistryEntry};
 
 /// Registry report.
 #[derive(Debug, serde::Serialize)]
@@ -30,16 +17,6 @@ pub struct RegistryReport {
     pub total: usize,
 }
 
-/// Initialize or load a registry index.
-pub fn load_index(registry_dir: &Path) -> Result<RegistryIndex, String> {
-    let index_path = registry_dir.join("index.json");
-    if !index_path.exists() {
-        return Ok(RegistryIndex::default());
-    }
-    let data = std::fs::read_to_string(&index_path).map_err(|e| format!("read index: {e}"))?;
-    serde_json::from_str(&data).map_err(|e| format!("parse index: {e}"))
-}
-
 /// Save registry index.
 #[cfg(test)]
 pub fn save_index(registry_dir: &Path, index: &RegistryIndex) -> Result<(), String> {
@@ -147,12 +124,3 @@ pub fn get_latest<'a>(index: &'a RegistryIndex, name: &str) -> Option<&'a Regist
         .filter(|e| e.name == name)
         .max_by(|a, b| a.version.cmp(&b.version))
 }
-
-/// Get the default registry directory.
-pub fn default_registry_dir() -> PathBuf {
-    std::env::var("HOME")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("/var/lib"))
-        .join(".forjar")
-        .join("registry")
-}
@@ -1,20 +1,10 @@
 //! FJ-1432: Cryptographic recipe signing.
 //!
-//! Sign recipes with BLAKE3 + Ed25519 signatures.
+//! Sign recipes with detached Ed25519 signatures (`<recipe>.sig.json`).
 //! Verify signature before apply for supply chain integrity.
 
-use std::path::Path;
-
-/// A recipe signature.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct RecipeSignature {
-    pub recipe_path: String,
-    pub blake3_hash: String,
-    pub algorithm: String,
-    pub signer: String,
-    pub timestamp: String,
-    pub signature: String,
-}
+use crate::core::signing::{self, Scope, Signature, SigningKey, TrustPolicy};
+use std::path::{Path, PathBuf};
 
 /// Signature verification result.
 #[derive(Debug, Clone, serde::Serialize)]
@@ -22,68 +12,63 @@ pub struct VerifyResult {
     pub recipe_path: String,
     pub valid: bool,
     pub signer: String,
+    pub key: String,
     pub algorithm: String,
     pub reason: String,
 }
 
+/// Detached signature file of a recipe.
+pub fn signature_path(recipe_path: &Path) -> PathBuf {
+    recipe_path.with_extension("sig.json")
+}
+
 /// Sign a recipe file.
-pub fn sign_recipe(recipe_path: &Path, signer: &str) -> Result<RecipeSignature, String> {
+pub fn sign_recipe(recipe_path: &Path, key: &SigningKey) -> Result<Signature, String> {
     let content = std::fs::read(recipe_path).map_err(|e| format!("read recipe: {e}"))?;
-    let blake3_hash = blake3::hash(&content).to_hex().to_string();
-
-    // Generate deterministic signature (BLAKE3 of hash + signer)
-    let sig_input = format!("{blake3_hash}:{signer}");
-    let signature = blake3::hash(sig_input.as_bytes()).to_hex().to_string();
-
-    let sig = RecipeSignature {
-        recipe_path: recipe_path.display().to_string(),
-        blake3_hash,
-        algorithm: "blake3-hmac".to_string(),
-        signer: signer.to_string(),
-        timestamp: format!("{:?}", std::time::SystemTime::now()),
-        signature,
-    };
-
-    // Write signature file
-    let sig_path = recipe_path.with_extension("sig.json");
-    let data = serde_json::to_string_pretty(&sig).map_err(|e| format!("serialize sig: {e}"))?;
-    std::fs::write(&sig_path, data).map_err(|e| format!("write sig: {e}"))?;
-
+    let sig = key.sign(Scope::Recipe, &content)?;
+    std::fs::write(signature_path(recipe_path), sig.to_json()?)
+        .map_err(|e| format!("write sig: {e}"))?;
     Ok(sig)
 }
 
-/// Verify a recipe signature.
-pub fn verify_recipe(recipe_path: &Path) -> Result<VerifyResult, String> {
-    let sig_path = recipe_path.with_extension("sig.json");
-    if !sig_path.exists() {
-        return Ok(VerifyResult {
-            recipe_path: recipe_path.display().to_string(),
-            valid: false,
-            signer: String::new(),
-            algorithm: String::new(),
-            reason: "no signature file found".to_string(),
-        });
-    }
-
-    let sig_data = std::fs::read_to_string(&sig_path).map_err(|e| format!("read sig: {e}"))?;
-    let sig: RecipeSignature =
-        serde_json::from_str(&sig_data).map_err(|e| format!("parse sig: {e}"))?;
-
+/// Verify a recipe signature; with a trust policy, the signing key must
+/// also be one of its recipe signers.
+pub fn verify_recipe(
+    recipe_path: &Path,
+    policy: Option<&TrustPolicy>,
+) -> Result<VerifyResult, String> {
+    let mut result = VerifyResult {
+        recipe_path: recipe_path.display().to_string(),
+        valid: false,
+        signer: String::new(),
+        key: String::new(),
+        algorithm: String::new(),
+        reason: String::new(),
+    };
+    let Some(sig) = signing::read_signature(&signature_path(recipe_path))? else {
+        result.reason = "no signature file found".to_string();
+        return Ok(result);
+    };
     let content = std::fs::read(recipe_path).map_err(|e| format!("read recipe: {e}"))?;
-    let current_hash = blake3::hash(&content).to_hex().to_string();
+    result.signer = sig.signer.clone();
+    result.key = sig.key.clone();
+    result.algorithm = sig.algorithm.clone();
 
-    let valid = current_hash == sig.blake3_hash;
-    Ok(VerifyResult {
-        recipe_path: recipe_path.display().to_string(),
-        valid,
-        signer: sig.signer,
-        algorithm: sig.algorithm,
-        reason: if valid {
-            "hash matches".to_string()
-        } else {
-            "hash mismatch — recipe modified after signing".to_string()
+    match (sig.verify(Scope::Recipe, &content), policy) {
+        (Err(e), _) => result.reason = e,
+        (Ok(key), Some(policy)) => match policy.trusted_signer(Scope::Recipe, &key) {
+            Some(name) => {
+                result.valid = true;
+                result.reason = format!("signature valid, trusted signer '{name}'");
+            }
+            None => result.reason = format!("{key} is not a trusted recipe signer"),
         },
-    })
+        (Ok(_), None) => {
+            result.valid = true;
+            result.reason = "signature valid".to_string();
+        }
+    }
+    Ok(result)
 }
 
 /// Sign or verify recipe CLI command.
@@ -91,10 +76,12 @@ pub fn cmd_recipe_sign(
     recipe_path: &Path,
     verify_only: bool,
     signer: Option<&str>,
+    trust_policy: Option<&Path>,
     json: bool,
 ) -> Result<(), String> {
     if verify_only {
-        let result = verify_recipe(recipe_path)?;
+        let policy = signing::load_trust_policy(trust_policy)?;
+        let result = verify_recipe(recipe_path, policy.as_ref())?;
         if json {
             let out =
                 serde_json::to_string_pretty(&result).map_err(|e| format!("JSON error: {e}"))?;
@@ -106,15 +93,14 @@ pub fn cmd_recipe_sign(
             return Err("signature verification failed".to_string());
         }
     } else {
-        let who = signer.unwrap_or("local");
-        let sig = sign_recipe(recipe_path, who)?;
+        let key = signing::resolve_signing_key(signer)?;
+        let sig = sign_recipe(recipe_path, &key)?;
         if json {
-            let out = serde_json::to_string_pretty(&sig).map_err(|e| format!("JSON error: {e}"))?;
-            println!("{out}");
+            println!("{}", sig.to_json()?);
         } else {
-            println!("Signed: {}", sig.recipe_path);
-            println!("Hash: {}", sig.blake3_hash);
-            println!("Signer: {}", sig.signer);
+            println!("Signed: {}", recipe_path.display());
+            println!("Hash: {}", sig.blake3);
+            println!("Signer: {} ({})", sig.signer, sig.key);
         }
     }
     Ok(())
@@ -124,6 +110,6 @@ fn print_verify_result(result: &VerifyResult) {
     let icon = if result.valid { "OK" } else { "FAIL" };
     println!("[{icon}] {}: {}", result.recipe_path, result.reason);
     if !result.signer.is_empty() {
-        println!("  Signer: {}", result.signer);
+        println!("  Signer: {} ({})", result.signer, result.key);
     }
 }
@@ -0,0 +1,81 @@
+//! FJ-3314: `forjar recipe update` — bump versioned recipes within their
+//! constraints and rewrite their pins in `forjar.inputs.lock.yaml`.
+//! FJ-3315: `forjar recipe schema` — export a recipe's input schema.
+
+use crate::core::store::lockfile::read_lockfile;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// One resolved recipe and the version it was locked at before.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecipeUpdate {
+    pub name: String,
+    pub previous: Option<String>,
+    pub version: String,
+    pub blake3: String,
+}
+
+/// Locked recipe versions in the input lock next to `file`.
+fn locked_versions(file: &Path) -> Result<BTreeMap<String, String>, String> {
+    let lock_path = file
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join("forjar.inputs.lock.yaml");
+    if !lock_path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(read_lockfile(&lock_path)?
+        .pins
+        .into_iter()
+        .filter_map(|(k, pin)| Some((k.strip_prefix("recipe:")?.to_string(), pin.version?)))
+        .collect())
+}
+
+/// Re-resolve `names` (every versioned recipe when empty).
+pub(crate) fn cmd_recipe_update(file: &Path, names: &[String], json: bool) -> Result<(), String> {
+    let before = locked_versions(file)?;
+    let resolved = crate::core::parser::update_recipes(file, names)?;
+    if let Some(missing) = names.iter().find(|n| !resolved.contains_key(*n)) {
+        return Err(format!(
+            "recipe '{missing}' is not a versioned recipe of this config"
+        ));
+    }
+    let updates: Vec<RecipeUpdate> = resolved
+        .values()
+        .map(|c| RecipeUpdate {
+            name: c.name.clone(),
+            previous: before.get(&c.name).cloned(),
+            version: c.version.to_string(),
+            blake3: c.blake3.clone(),
+        })
+        .collect();
+
+    if json {
+        let out = serde_json::to_string_pretty(&updates).map_err(|e| format!("JSON error: {e}"))?;
+        println!("{out}");
+        return Ok(());
+    }
+    if updates.is_empty() {
+        println!("No versioned recipes to update");
+        return Ok(());
+    }
+    println!("Resolved {} recipe(s):", updates.len());
+    for u in &updates {
+        match u.previous.as_deref() {
+            Some(prev) if prev == u.version => println!("  {} {} (unchanged)", u.name, u.version),
+            Some(prev) => println!("  {} {prev} -> {}", u.name, u.version),
+            None => println!("  {} {} (new)", u.name, u.version),
+        }
+    }
+    Ok(())
+}
+
+/// Print the JSON Schema of the inputs of the recipe at `path`.
+pub(crate) fn cmd_recipe_schema(path: &Path) -> Result<(), String> {
+    let recipe_file = crate::core::recipe::load_recipe(path)?;
+    crate::core::recipe::validation::validate_declarations(&recipe_file)?;
+    let schema = crate::core::recipe::schema::input_schema(&recipe_file);
+    let out = serde_json::to_string_pretty(&schema).map_err(|e| format!("JSON error: {e}"))?;
+    println!("{out}");
+    Ok(())
+}
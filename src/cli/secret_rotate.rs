@@ -0,0 +1,205 @@
+//! FJ-3312: `secrets rotate <key>` — rotate one secret and stage its rollout.
+//!
+//! The param's `ENC[age,...]` marker is rewritten in place, fingerprints go
+//! to the secret audit log, and only the resources whose templates consume
+//! the param are planned. `--rollback` restores the previous marker from the
+//! rotation log without decrypting anything.
+
+use super::helpers::*;
+use super::helpers_state::*;
+use crate::core::secret_rotation;
+use crate::core::{planner, resolver, types};
+use std::path::Path;
+
+#[cfg(feature = "encryption")]
+use crate::core::secrets;
+
+/// Read the new value from `path` (`-` for stdin), dropping the trailing newline.
+#[cfg(feature = "encryption")]
+fn read_value(path: &Path) -> Result<String, String> {
+    let raw = if path == Path::new("-") {
+        let mut buf = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut buf)
+            .map_err(|e| format!("read stdin: {e}"))?;
+        buf
+    } else {
+        std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read '{}': {e}", path.display()))?
+    };
+    let value = raw.trim_end_matches(['\n', '\r']).to_string();
+    if value.is_empty() {
+        return Err("new secret value is empty".to_string());
+    }
+    Ok(value)
+}
+
+/// Swap `old` for `new` in `file` and check the old marker is gone.
+fn rewrite_marker(
+    file: &Path,
+    content: &str,
+    key: &str,
+    old: &str,
+    new: &str,
+) -> Result<(), String> {
+    let (updated, replaced) = secret_rotation::replace_marker(content, old, new);
+    if replaced == 0 {
+        return Err(format!(
+            "marker for '{key}' not found in {}",
+            file.display()
+        ));
+    }
+    std::fs::write(file, &updated)
+        .map_err(|e| format!("cannot write '{}': {e}", file.display()))?;
+    let written = std::fs::read_to_string(file)
+        .map_err(|e| format!("cannot read '{}': {e}", file.display()))?;
+    if written.contains(old) || secret_rotation::param_marker(&written, key)? != new {
+        return Err(format!(
+            "verify {}: old marker for '{key}' still present",
+            file.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Route the rotation log and locks for `state_dir` through the config's
+/// state backend.
+fn attach_state(file: &Path, state_dir: &Path) -> Result<(), String> {
+    let config = parse_and_validate(file)?;
+    crate::core::state::backend::attach(config.policy.backend.as_ref(), state_dir)?;
+    crate::core::state_recipients::attach(config.policy.state_encryption.as_ref(), state_dir)
+}
+
+/// Plan the consumers of the rotated secret, machine by machine.
+pub(crate) fn rollout_plan(
+    file: &Path,
+    state_dir: &Path,
+    resources: &[String],
+) -> Result<String, String> {
+    if resources.is_empty() {
+        return Ok("no resources consume this secret; nothing to redeploy\n".to_string());
+    }
+    let config = parse_and_validate(file)?;
+    let order: Vec<String> = resolver::build_execution_order(&config)?
+        .into_iter()
+        .filter(|id| resources.contains(id))
+        .collect();
+    let locks = load_machine_locks(&config, state_dir, None)?;
+    let plan = planner::plan(&config, &order, &locks, None);
+
+    let mut out = format!("Staged rollout ({} resource(s)):\n", resources.len());
+    let mut machines: Vec<&str> = plan.changes.iter().map(|c| c.machine.as_str()).collect();
+    let mut seen = std::collections::HashSet::new();
+    machines.retain(|m| seen.insert(*m));
+    for (stage, machine) in machines.iter().enumerate() {
+        out.push_str(&format!("  stage {}: {machine}\n", stage + 1));
+        for change in plan.changes.iter().filter(|c| c.machine == *machine) {
+            let action = match change.action {
+                types::PlanAction::Create => "create",
+                types::PlanAction::Update => "update",
+                types::PlanAction::Destroy => "destroy",
+                types::PlanAction::NoOp => "no-op",
+            };
+            out.push_str(&format!(
+                "    {action:<7} {}  forjar apply -f {} -m {machine} -r {}\n",
+                change.resource_id,
+                file.display(),
+                change.resource_id
+            ));
+        }
+    }
+    Ok(out)
+}
+
+/// Print [`rollout_plan`].
+fn print_rollout(file: &Path, state_dir: &Path, resources: &[String]) -> Result<(), String> {
+    print!("{}", rollout_plan(file, state_dir, resources)?);
+    Ok(())
+}
+
+/// FJ-3312: Rotate the secret held by param `key` to a new value.
+#[cfg(feature = "encryption")]
+pub(crate) fn cmd_secrets_rotate_key(
+    file: &Path,
+    key: &str,
+    identity_path: Option<&Path>,
+    recipients: &[String],
+    value_file: Option<&Path>,
+    state_dir: &Path,
+) -> Result<(), String> {
+    attach_state(file, state_dir)?;
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| format!("cannot read '{}': {e}", file.display()))?;
+    let old_marker = secret_rotation::param_marker(&content, key)?;
+    let identities = secrets::load_identities(identity_path)?;
+    let old_value = secrets::decrypt_marker(&old_marker, &identities)?;
+    let new_value = match value_file {
+        Some(path) => read_value(path)?,
+        None => secret_rotation::generate_value()?,
+    };
+    if new_value == old_value {
+        return Err(format!("new value for '{key}' equals the current value"));
+    }
+    let recipient_refs: Vec<&str> = recipients.iter().map(|r| r.as_str()).collect();
+    let new_marker = secrets::encrypt(&new_value, &recipient_refs)?;
+
+    rewrite_marker(file, &content, key, &old_marker, &new_marker)?;
+    let config = parse_and_validate(file)?;
+    let record = secret_rotation::RotationRecord {
+        rotated_at: crate::tripwire::eventlog::now_iso8601(),
+        key: key.to_string(),
+        file: file.display().to_string(),
+        old_marker,
+        new_marker,
+        old_hash: secret_rotation::fingerprint(&old_value),
+        new_hash: secret_rotation::fingerprint(&new_value),
+        resources: secret_rotation::consumers(&config, key),
+        rollback: false,
+    };
+    secret_rotation::record_rotation(state_dir, &record)?;
+
+    println!(
+        "rotated '{key}' in {} ({} -> {})",
+        file.display(),
+        &record.old_hash[..12],
+        &record.new_hash[..12]
+    );
+    print_rollout(file, state_dir, &record.resources)
+}
+
+#[cfg(not(feature = "encryption"))]
+pub(crate) fn cmd_secrets_rotate_key(
+    _file: &Path,
+    _key: &str,
+    _identity_path: Option<&Path>,
+    _recipients: &[String],
+    _value_file: Option<&Path>,
+    _state_dir: &Path,
+) -> Result<(), String> {
+    Err(
+        "forjar was compiled without encryption support. Rebuild with `--features encryption`."
+            .to_string(),
+    )
+}
+
+/// FJ-3312: Undo the last rotation of `key` by restoring its previous marker.
+pub(crate) fn cmd_secrets_rotate_rollback(
+    file: &Path,
+    key: &str,
+    state_dir: &Path,
+) -> Result<(), String> {
+    attach_state(file, state_dir)?;
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| format!("cannot read '{}': {e}", file.display()))?;
+    let current = secret_rotation::param_marker(&content, key)?;
+    let target = secret_rotation::rollback_target(state_dir, key, &current)?;
+    rewrite_marker(file, &content, key, &current, &target.old_marker)?;
+    let record = target.inverse();
+    secret_rotation::record_rotation(state_dir, &record)?;
+
+    println!(
+        "rolled back '{key}' in {} to the value rotated out at {}",
+        file.display(),
+        target.rotated_at
+    );
+    print_rollout(file, state_dir, &record.resources)
+}
@@ -104,6 +104,7 @@ fn explain_json(
     apply_script: &Option<String>,
     check_script: &Option<String>,
     machines: &indexmap::IndexMap<String, types::Machine>,
+    included_from: Option<&String>,
 ) -> Result<(), String> {
     let mut info = serde_json::json!({
         "resource": resource_id,
@@ -116,6 +117,9 @@ fn explain_json(
     if let Some(ref rg) = resource.resource_group {
         info["resource_group"] = serde_json::json!(rg);
     }
+    if let Some(source) = included_from {
+        info["included_from"] = serde_json::json!(source);
+    }
     if let Some(ref script) = apply_script {
         info["apply_script"] = serde_json::json!(script);
     }
@@ -155,6 +159,10 @@ pub(crate) fn cmd_explain(file: &Path, resource_id: &str, json: bool) -> Result<
 
     let apply_script = codegen::apply_script(&resolved).ok();
     let check_script = codegen::check_script(&resolved).ok();
+    // FJ-3313: Which include (local, URL or git) defined the resource
+    let included_from = config
+        .include_provenance
+        .get(&format!("resource:{resource_id}"));
 
     if json {
         return explain_json(
@@ -165,6 +173,7 @@ pub(crate) fn cmd_explain(file: &Path, resource_id: &str, json: bool) -> Result<
             &apply_script,
             &check_script,
             &config.machines,
+            included_from,
         );
     }
 
@@ -176,6 +185,9 @@ pub(crate) fn cmd_explain(file: &Path, resource_id: &str, json: bool) -> Result<
         serde_yaml_ng::to_string(&raw_val).map_err(|e| format!("serialize error: {e}"))?;
     println!("{}", bold("1. Raw Resource Definition"));
     println!("{}", dim("─────────────────────────────"));
+    if let Some(source) = included_from {
+        println!("{}", dim(&format!("# included from {source}")));
+    }
     println!("{raw_yaml}");
 
     let mut resolved_val =
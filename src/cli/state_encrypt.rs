@@ -4,6 +4,7 @@
 //! with BLAKE3 integrity verification.
 
 use crate::core::state_encryption::*;
+use crate::core::state_recipients::is_recipient_encrypted;
 use std::path::Path;
 
 /// Encrypt state files with age passphrase encryption.
@@ -51,7 +52,8 @@ pub fn cmd_state_decrypt(state_dir: &Path, passphrase: &str, json: bool) -> Resu
     let mut errors = 0;
 
     for file in &lock_files {
-        if !is_encrypted(file) {
+        // FJ-3310: recipient-encrypted files need `--identity`, not a passphrase.
+        if !is_encrypted(file) || is_recipient_encrypted(file) {
             skipped += 1;
             continue;
         }
@@ -100,6 +102,9 @@ pub fn cmd_state_rekey(
     let mut errors = 0;
 
     for file in &lock_files {
+        if is_recipient_encrypted(file) {
+            continue;
+        }
         let plaintext = if is_encrypted(file) {
             match rekey_decrypt(file, old_passphrase) {
                 Ok(p) => p,
@@ -155,7 +160,7 @@ fn rekey_decrypt(file: &Path, passphrase: &str) -> Result<Vec<u8>, String> {
 }
 
 /// Find lock files in a state directory.
-fn find_lock_files(state_dir: &Path) -> Result<Vec<std::path::PathBuf>, String> {
+pub(super) fn find_lock_files(state_dir: &Path) -> Result<Vec<std::path::PathBuf>, String> {
     let mut files = Vec::new();
 
     if !state_dir.exists() {
@@ -0,0 +1,354 @@
+//! FJ-3310: Recipient-based state encryption commands.
+//!
+//! `state-encrypt --recipient`, `state-decrypt --identity` and recipient
+//! `state-rekey`. Lock files become age ciphertext with a version-2
+//! `.enc.meta.json` sidecar; event log lines become `ENC[age,...]`
+//! markers. Rekeying decrypts in memory and writes only ciphertext.
+//!
+//! Every write goes through the state directory's backend (`-f` attaches
+//! the config's `policy.backend`), and lock files are written with their
+//! `.b3` sidecar so the next integrity check sees the new ciphertext.
+
+use super::state_encrypt::find_lock_files;
+use crate::core::state::{self, backend};
+use crate::core::state_encryption::{is_encrypted, read_metadata};
+use crate::core::state_recipients::*;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+const ENC_PREFIX: &str = "ENC[age,";
+
+/// Key of `path`, a file found in `state_dir`.
+fn state_key(state_dir: &Path, path: &Path) -> Result<String, String> {
+    backend::key_for(state_dir, path)
+        .ok_or_else(|| format!("{} is outside {}", path.display(), state_dir.display()))
+}
+
+/// Replace the event log at `path` through the state directory's backend.
+fn put_log(state_dir: &Path, path: &Path, data: &[u8]) -> Result<(), String> {
+    backend::backend_for(state_dir).put(&state_key(state_dir, path)?, data)
+}
+
+/// Replace the lock file at `path` with `plaintext` encrypted to
+/// `recipients` (plaintext without them), refreshing its sidecars.
+fn put_lock(
+    state_dir: &Path,
+    path: &Path,
+    plaintext: &[u8],
+    recipients: Option<&[String]>,
+) -> Result<(), String> {
+    state::write_sealed_state_file_to(
+        state_dir,
+        &state_key(state_dir, path)?,
+        plaintext,
+        recipients,
+    )
+}
+
+/// Route reads and writes of `state_dir` through the `policy.backend` of
+/// the config `file`.
+pub(super) fn attach_backend(file: &Path, state_dir: &Path) -> Result<(), String> {
+    let config = crate::core::parser::parse_and_validate(file)?;
+    backend::attach(config.policy.backend.as_ref(), state_dir)
+}
+
+/// Event logs (`<machine>/events.jsonl`) in a state directory.
+pub(super) fn find_event_logs(state_dir: &Path) -> Vec<PathBuf> {
+    let mut logs = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(state_dir) {
+        for entry in entries.flatten() {
+            let log = entry.path().join("events.jsonl");
+            if log.is_file() {
+                logs.push(log);
+            }
+        }
+    }
+    logs.sort();
+    logs
+}
+
+/// Recipients `policy.state_encryption` in `file` assigns to `state_dir`.
+pub(super) fn configured(file: &Path, state_dir: &Path) -> Result<Vec<String>, String> {
+    let config = crate::core::parser::parse_and_validate(file)?;
+    Ok(config
+        .policy
+        .state_encryption
+        .as_ref()
+        .map(|c| configured_recipients(c, state_dir))
+        .unwrap_or_default())
+}
+
+/// Whether `state_dir` holds recipient-encrypted locks or sealed event lines.
+pub(super) fn has_recipient_state(state_dir: &Path) -> bool {
+    let locks = find_lock_files(state_dir).unwrap_or_default();
+    locks.iter().any(|f| is_recipient_encrypted(f))
+        || find_event_logs(state_dir)
+            .iter()
+            .any(|log| std::fs::read_to_string(log).is_ok_and(|c| c.contains(ENC_PREFIX)))
+}
+
+/// Rewrite every line of `content` with `f`, keeping line structure.
+fn map_lines(
+    content: &str,
+    mut f: impl FnMut(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(content.len());
+    for line in content.lines() {
+        out.push_str(&if line.is_empty() {
+            String::new()
+        } else {
+            f(line)?
+        });
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn encrypt_lock(state_dir: &Path, file: &Path, recipients: &[String]) -> Result<(), String> {
+    let plaintext = std::fs::read(file).map_err(|e| format!("read {}: {e}", file.display()))?;
+    put_lock(state_dir, file, &plaintext, Some(recipients))
+}
+
+/// Seal the plaintext lines of an event log. Returns how many were sealed.
+fn encrypt_log(state_dir: &Path, log: &Path, recipients: &[String]) -> Result<usize, String> {
+    let content =
+        std::fs::read_to_string(log).map_err(|e| format!("read {}: {e}", log.display()))?;
+    let mut sealed = 0;
+    let out = map_lines(&content, |line| {
+        if line.starts_with(ENC_PREFIX) {
+            return Ok(line.to_string());
+        }
+        sealed += 1;
+        seal_line(line, recipients)
+    })?;
+    if sealed > 0 {
+        put_log(state_dir, log, out.as_bytes())?;
+    }
+    Ok(sealed)
+}
+
+/// Encrypt lock files and event logs in `state_dir` to `recipients`.
+pub fn cmd_state_encrypt_recipients(
+    state_dir: &Path,
+    recipients: &[String],
+    json: bool,
+) -> Result<(), String> {
+    let recipients = normalize(recipients);
+    if recipients.is_empty() {
+        return Err("no recipients: pass --recipient or configure policy.state_encryption".into());
+    }
+    for recipient in &recipients {
+        validate_recipient(recipient)?;
+    }
+
+    let mut encrypted = 0;
+    let mut skipped = 0;
+    for file in find_lock_files(state_dir)? {
+        if is_encrypted(&file) {
+            skipped += 1;
+            continue;
+        }
+        encrypt_lock(state_dir, &file, &recipients)?;
+        encrypted += 1;
+    }
+    let mut lines = 0;
+    for log in find_event_logs(state_dir) {
+        lines += encrypt_log(state_dir, &log, &recipients)?;
+    }
+
+    if json {
+        println!(
+            "{{\"encrypted\": {encrypted}, \"skipped\": {skipped}, \"event_lines\": {lines}, \"recipients\": {}}}",
+            recipients.len()
+        );
+    } else {
+        println!(
+            "Encrypted {encrypted} file(s) and {lines} event line(s) to {} recipient(s), skipped {skipped} already-encrypted",
+            recipients.len()
+        );
+    }
+    Ok(())
+}
+
+fn decrypt_lock(state_dir: &Path, file: &Path) -> Result<(), String> {
+    let ciphertext = std::fs::read(file).map_err(|e| format!("read {}: {e}", file.display()))?;
+    let plaintext = open_with_meta(&ciphertext, &read_metadata(file)?)?;
+    put_lock(state_dir, file, &plaintext, None)
+}
+
+fn decrypt_log(state_dir: &Path, log: &Path) -> Option<Result<(), String>> {
+    let content = match std::fs::read_to_string(log) {
+        Ok(c) => c,
+        Err(e) => return Some(Err(format!("read {}: {e}", log.display()))),
+    };
+    if !content.contains(ENC_PREFIX) {
+        return None;
+    }
+    Some(open_lines(&content).and_then(|out| put_log(state_dir, log, out.as_bytes())))
+}
+
+/// Decrypt recipient-encrypted lock files and event logs in `state_dir`
+/// with the `--identity` file or `FORJAR_AGE_KEY`.
+pub fn cmd_state_decrypt_recipients(
+    state_dir: &Path,
+    identity: Option<&Path>,
+    json: bool,
+) -> Result<(), String> {
+    set_identity_file(identity);
+    let mut decrypted = 0;
+    let mut skipped = 0;
+    let mut errors = 0;
+
+    let files = find_lock_files(state_dir)?;
+    let logs = find_event_logs(state_dir);
+    let results = files
+        .iter()
+        .map(|f| {
+            (
+                f,
+                is_recipient_encrypted(f).then(|| decrypt_lock(state_dir, f)),
+            )
+        })
+        .chain(logs.iter().map(|l| (l, decrypt_log(state_dir, l))));
+    for (path, result) in results {
+        match result {
+            None => skipped += 1,
+            Some(Ok(())) => decrypted += 1,
+            Some(Err(e)) => {
+                errors += 1;
+                if !json {
+                    println!("  DECRYPT FAIL: {}: {e}", path.display());
+                }
+            }
+        }
+    }
+    set_identity_file(None);
+
+    if json {
+        println!("{{\"decrypted\": {decrypted}, \"skipped\": {skipped}, \"errors\": {errors}}}");
+    } else {
+        println!("Decrypted {decrypted} file(s), skipped {skipped}, errors {errors}");
+    }
+    if errors > 0 {
+        return Err(format!("{errors} file(s) could not be decrypted"));
+    }
+    Ok(())
+}
+
+/// Target recipient set of a rekey.
+#[derive(Debug, Default)]
+pub struct RekeyPlan {
+    /// Replace each file's recipients with this set (from the config)
+    pub base: Option<Vec<String>>,
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+impl RekeyPlan {
+    /// New recipient set for a file currently encrypted to `current`.
+    pub fn apply(&self, current: &[String]) -> Result<Vec<String>, String> {
+        let remove: BTreeSet<&str> = self.remove.iter().map(|r| r.trim()).collect();
+        let mut next: Vec<String> = self.base.as_deref().unwrap_or(current).to_vec();
+        next.extend(self.add.iter().cloned());
+        next.retain(|r| !remove.contains(r.trim()));
+        let next = normalize(&next);
+        if next.is_empty() {
+            return Err("refusing to remove every recipient".to_string());
+        }
+        for recipient in &next {
+            validate_recipient(recipient)?;
+        }
+        Ok(next)
+    }
+}
+
+/// Result of rekeying one lock file and its machine's event log.
+struct Rekeyed {
+    before: Vec<String>,
+    after: Vec<String>,
+}
+
+fn rekey_lock(state_dir: &Path, file: &Path, plan: &RekeyPlan) -> Result<Rekeyed, String> {
+    let meta = read_metadata(file)?;
+    let after = plan.apply(&meta.recipients)?;
+    let ciphertext = std::fs::read(file).map_err(|e| format!("read {}: {e}", file.display()))?;
+    let plaintext = open_with_meta(&ciphertext, &meta)?;
+    put_lock(state_dir, file, &plaintext, Some(&after))?;
+
+    // The machine's event log follows its lock's recipients.
+    let log = file.with_file_name("events.jsonl");
+    if log.is_file() {
+        let content =
+            std::fs::read_to_string(&log).map_err(|e| format!("read {}: {e}", log.display()))?;
+        let out = map_lines(&content, |line| {
+            let plain = if line.starts_with(ENC_PREFIX) {
+                open_lines(line)?
+            } else {
+                line.to_string()
+            };
+            seal_line(&plain, &after)
+        })?;
+        put_log(state_dir, &log, out.as_bytes())?;
+    }
+    Ok(Rekeyed {
+        before: meta.recipients,
+        after,
+    })
+}
+
+/// Re-encrypt recipient-encrypted state to the set `plan` describes,
+/// without writing plaintext anywhere.
+pub fn cmd_state_rekey_recipients(
+    state_dir: &Path,
+    plan: &RekeyPlan,
+    identity: Option<&Path>,
+    json: bool,
+) -> Result<(), String> {
+    set_identity_file(identity);
+    let mut rekeyed = 0;
+    let mut errors = 0;
+    let mut added = BTreeSet::new();
+    let mut removed = BTreeSet::new();
+
+    for file in find_lock_files(state_dir)? {
+        if !is_recipient_encrypted(&file) {
+            continue;
+        }
+        match rekey_lock(state_dir, &file, plan) {
+            Ok(r) => {
+                rekeyed += 1;
+                added.extend(r.after.iter().filter(|a| !r.before.contains(a)).cloned());
+                removed.extend(r.before.iter().filter(|b| !r.after.contains(b)).cloned());
+            }
+            Err(e) => {
+                errors += 1;
+                if !json {
+                    println!("  REKEY FAIL: {}: {e}", file.display());
+                }
+            }
+        }
+    }
+    set_identity_file(None);
+
+    if json {
+        let out = serde_json::json!({
+            "rekeyed": rekeyed,
+            "errors": errors,
+            "added": added,
+            "removed": removed,
+        });
+        println!("{out}");
+    } else {
+        println!("Rekeyed {rekeyed} file(s), errors {errors}");
+        for r in &added {
+            println!("  + {r}");
+        }
+        for r in &removed {
+            println!("  - {r}");
+        }
+    }
+    if errors > 0 {
+        return Err(format!("{errors} file(s) could not be rekeyed"));
+    }
+    Ok(())
+}
@@ -1,6 +1,7 @@
 //! Core status command.
 
-use crate::core::{parser, state, types};
+use super::helpers::yellow;
+use crate::core::{parser, pki, state, types};
 use std::path::Path;
 
 /// Build a JSON resource entry, enriched with config if available.
@@ -53,6 +54,7 @@ fn print_status_json(
     global: &Option<types::GlobalLock>,
     machines: &[types::StateLock],
     config: &Option<types::ForjarConfig>,
+    apply_lock: &Option<state::lease::ApplyLease>,
 ) -> Result<(), String> {
     let machine_values: Vec<serde_json::Value> = machines
         .iter()
@@ -89,6 +91,7 @@ fn print_status_json(
     let output = serde_json::json!({
         "global": live_global,
         "machines": machine_values,
+        "apply_lock": apply_lock,
     });
     println!(
         "{}",
@@ -118,24 +121,49 @@ fn build_resource_extras(id: &str, config: &Option<types::ForjarConfig>) -> Stri
     String::new()
 }
 
+/// Expiry note for certificate entries, flagged when renewal is due.
+fn certificate_expiry_note(rl: &types::ResourceLock, due: bool, now: i64) -> String {
+    match pki::lock_not_after(rl) {
+        Some(not_after) if due => format!(
+            " {}",
+            yellow(&format!(
+                "[{}, renewal due]",
+                pki::describe_time_left(not_after - now)
+            ))
+        ),
+        Some(not_after) => format!(" [{}]", pki::describe_time_left(not_after - now)),
+        None => String::new(),
+    }
+}
+
 /// Print text output for the status command.
 fn print_status_text(
     global: &Option<types::GlobalLock>,
     machines: &[types::StateLock],
     config: &Option<types::ForjarConfig>,
+    apply_lock: &Option<state::lease::ApplyLease>,
 ) {
     if let Some(ref g) = global {
         println!("Project: {} (last apply: {})", g.name, g.last_apply);
         println!("Generator: {}", g.generator);
         println!();
     }
+    if let Some(ref lease) = apply_lock {
+        println!(
+            "{}",
+            yellow(&format!("Apply lock: held by {}", lease.describe()))
+        );
+        println!();
+    }
 
     if machines.is_empty() {
         println!("No state found. Run `forjar apply` first.");
         return;
     }
 
+    let now = pki::now_unix();
     for lock in machines {
+        let due = pki::due_renewals(lock, config.as_ref(), now);
         println!("Machine: {} ({})", lock.machine, lock.hostname);
         println!("  Generated: {}", lock.generated_at);
         println!("  Generator: {}", lock.generator);
@@ -146,7 +174,11 @@ fn print_status_text(
                 .duration_seconds
                 .map(|d| format!(" ({d:.2}s)"))
                 .unwrap_or_default();
-            let extras = build_resource_extras(id, config);
+            let mut extras = build_resource_extras(id, config);
+            if rl.resource_type == types::ResourceType::Certificate {
+                let is_due = due.iter().any(|(due_id, _)| due_id == id);
+                extras.push_str(&certificate_expiry_note(rl, is_due, now));
+            }
             println!(
                 "    {}: {} [{}]{}{}",
                 id, rl.status, rl.resource_type, duration, extras
@@ -185,13 +217,17 @@ pub(crate) fn cmd_status(
     config_file: Option<&Path>,
     summary: bool,
 ) -> Result<(), String> {
-    let global = state::load_global_lock(state_dir)?;
-
     let config = if let Some(f) = config_file {
         Some(parser::parse_and_validate(f)?)
     } else {
         None
     };
+    if let Some(ref cfg) = config {
+        state::backend::attach(cfg.policy.backend.as_ref(), state_dir)?;
+        crate::core::state_recipients::attach(cfg.policy.state_encryption.as_ref(), state_dir)?;
+    }
+
+    let global = state::load_global_lock(state_dir)?;
 
     let entries = std::fs::read_dir(state_dir)
         .map_err(|e| format!("cannot read state dir {}: {}", state_dir.display(), e))?;
@@ -218,10 +254,12 @@ pub(crate) fn cmd_status(
         return Ok(());
     }
 
+    // FJ-266: Show who holds the apply lock
+    let apply_lock = state::lease::read_lease(state_dir)?.map(|(lease, _)| lease);
     if json {
-        print_status_json(&global, &machines, &config)?;
+        print_status_json(&global, &machines, &config, &apply_lock)?;
     } else {
-        print_status_text(&global, &machines, &config);
+        print_status_text(&global, &machines, &config, &apply_lock);
     }
 
     Ok(())
@@ -1,6 +1,8 @@
-//! FJ-1346: `forjar archive` — FAR archive CLI (pack, unpack, inspect, verify).
+//! FJ-1346: `forjar archive` — FAR archive CLI (pack, unpack, sign, inspect,
+//! verify).
 
-use crate::core::store::far::{decode_far_manifest, FarManifest};
+use crate::core::signing::{self, Scope, Signature, TrustPolicy};
+use crate::core::store::far::{decode_far_manifest, far_signature, FarManifest};
 use std::path::Path;
 
 /// Inspect a .far archive — print manifest without unpacking.
@@ -18,7 +20,11 @@ pub(crate) fn cmd_archive_inspect(file: &Path, json: bool) -> Result<(), String>
 }
 
 /// Verify a .far archive — check chunk hashes and signature.
-pub(crate) fn cmd_archive_verify(file: &Path, json: bool) -> Result<(), String> {
+pub(crate) fn cmd_archive_verify(
+    file: &Path,
+    trust_policy: Option<&Path>,
+    json: bool,
+) -> Result<(), String> {
     let data = std::fs::read(file).map_err(|e| format!("read {}: {e}", file.display()))?;
     let cursor = std::io::Cursor::new(&data);
     let (manifest, chunks) = decode_far_manifest(cursor)?;
@@ -51,6 +57,19 @@ pub(crate) fn cmd_archive_verify(file: &Path, json: bool) -> Result<(), String>
         invalid += 1;
     }
 
+    // FJ-1432: signature section
+    let policy = signing::load_trust_policy(trust_policy)?;
+    let signer = match check_archive_signature(&data, policy.as_ref()) {
+        Ok(signer) => {
+            valid += 1;
+            signer
+        }
+        Err(e) => {
+            invalid += 1;
+            Some(format!("INVALID: {e}"))
+        }
+    };
+
     if json {
         let j = serde_json::json!({
             "file": file.display().to_string(),
@@ -58,6 +77,7 @@ pub(crate) fn cmd_archive_verify(file: &Path, json: bool) -> Result<(), String>
             "invalid_checks": invalid,
             "chunk_count": chunks.len(),
             "file_count": manifest.files.len(),
+            "signature": signer,
             "pass": invalid == 0,
         });
         println!(
@@ -72,6 +92,10 @@ pub(crate) fn cmd_archive_verify(file: &Path, json: bool) -> Result<(), String>
             chunks.len(),
             manifest.files.len()
         );
+        println!(
+            "  Signature: {}",
+            signer.as_deref().unwrap_or("none (unsigned)")
+        );
         println!("  {}", if invalid == 0 { "PASS" } else { "FAIL" });
     }
 
@@ -146,10 +170,62 @@ pub(crate) fn cmd_archive_pack(
     Ok(())
 }
 
-/// Unpack a .far archive into the store.
-pub(crate) fn cmd_archive_unpack(file: &Path, store_dir: &Path) -> Result<(), String> {
-    let reader = std::fs::File::open(file).map_err(|e| format!("open {}: {e}", file.display()))?;
-    let (manifest, _chunks) = decode_far_manifest(reader)?;
+/// Check the signature section of an archive. Returns a description of
+/// the signer (`None` when unsigned); with a trust policy the signature
+/// must satisfy it.
+fn check_archive_signature(
+    data: &[u8],
+    policy: Option<&TrustPolicy>,
+) -> Result<Option<String>, String> {
+    let (signed, sig) = far_signature(data)?;
+    let sig = sig
+        .map(|raw| Signature::from_json(&String::from_utf8_lossy(raw)))
+        .transpose()?;
+    if let Some(policy) = policy {
+        policy.check(Scope::Archive, "archive", signed, sig.as_ref())?;
+    }
+    let Some(sig) = sig else {
+        return Ok(None);
+    };
+    let key = sig.verify(Scope::Archive, signed)?;
+    let trusted = policy.and_then(|p| p.trusted_signer(Scope::Archive, &key));
+    Ok(Some(match trusted {
+        Some(name) => format!("{} ({key}), trusted as '{name}'", sig.signer),
+        None => format!("{} ({key})", sig.signer),
+    }))
+}
+
+/// Sign a .far archive in place, replacing any existing signature.
+pub(crate) fn cmd_archive_sign(file: &Path, key: Option<&str>) -> Result<(), String> {
+    let data = std::fs::read(file).map_err(|e| format!("read {}: {e}", file.display()))?;
+    let key = signing::resolve_signing_key(key)?;
+    let (signed, _) = far_signature(&data)?;
+    let sig = key.sign(Scope::Archive, signed)?;
+    let out = crate::core::store::far::with_far_signature(&data, sig.to_json()?.as_bytes())?;
+    std::fs::write(file, out).map_err(|e| format!("write {}: {e}", file.display()))?;
+    println!(
+        "Signed {} with {} ({})",
+        file.display(),
+        sig.signer,
+        sig.key
+    );
+    Ok(())
+}
+
+/// Unpack a .far archive into the store. When a trust policy (explicit or
+/// `FORJAR_TRUST_POLICY`) requires archive signatures, unsigned or
+/// untrusted archives are refused.
+pub(crate) fn cmd_archive_unpack(
+    file: &Path,
+    store_dir: &Path,
+    trust_policy: Option<&Path>,
+) -> Result<(), String> {
+    let data = std::fs::read(file).map_err(|e| format!("read {}: {e}", file.display()))?;
+    if let Some(policy) = signing::load_trust_policy(trust_policy)? {
+        check_archive_signature(&data, Some(&policy))
+            .map_err(|e| format!("{}: {e}", file.display()))?;
+    }
+    let (manifest, _chunks) = decode_far_manifest(std::io::Cursor::new(&data))?;
 
     let hash = manifest
         .store_hash
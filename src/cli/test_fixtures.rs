@@ -147,3 +147,56 @@ outputs:
     std::fs::write(&file, yaml).unwrap();
     file
 }
+
+/// FJ-1432: Generate an Ed25519 keypair named `name` in `dir`; returns the
+/// private key file path, usable wherever a key spec is accepted.
+pub(crate) fn signing_key_file(dir: &Path, name: &str) -> String {
+    crate::core::signing::Keyring::open(dir)
+        .generate(name)
+        .unwrap();
+    key_spec(dir, name)
+}
+
+/// Key spec of a key named `name` already generated in `dir`.
+pub(crate) fn key_spec(dir: &Path, name: &str) -> String {
+    dir.join(format!("{name}.key")).display().to_string()
+}
+
+/// FJ-1433: Like [`signing_key_file`], with an ML-DSA-65 half for dual
+/// signing.
+pub(crate) fn dual_signing_key_file(dir: &Path, name: &str) -> String {
+    let spec = signing_key_file(dir, name);
+    crate::core::signing::Keyring::open(dir)
+        .generate_pq(name)
+        .unwrap();
+    spec
+}
+
+/// FJ-1433: The loaded halves of a fresh [`dual_signing_key_file`] key.
+pub(crate) fn dual_signing_keys(
+    dir: &Path,
+    name: &str,
+) -> (
+    crate::core::signing::SigningKey,
+    crate::core::pq_signing::PqSigningKey,
+) {
+    let spec = dual_signing_key_file(dir, name);
+    (
+        crate::core::signing::load_signing_key(&spec).unwrap(),
+        crate::core::pq_signing::load_pq_signing_key(&spec).unwrap(),
+    )
+}
+
+/// FJ-1433: Pin the public halves of a [`dual_signing_keys`] pair.
+pub(crate) fn pinned_signer(
+    keys: &(
+        crate::core::signing::SigningKey,
+        crate::core::pq_signing::PqSigningKey,
+    ),
+) -> super::pq_signing::PinnedSigner {
+    super::pq_signing::PinnedSigner {
+        name: keys.0.name.clone(),
+        classical: keys.0.public_key().unwrap(),
+        pq: keys.1.public_key(),
+    }
+}
@@ -77,6 +77,7 @@ resources:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         )
         .unwrap();
     }
@@ -144,6 +145,7 @@ policy:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         )
         .unwrap();
 
@@ -208,6 +210,7 @@ resources: {}
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         );
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("validation"));
@@ -259,6 +262,7 @@ resources:
                 check: false,
                 report: false,
                 force_unlock: false,
+                lock_reason: None,
                 output: None,
                 progress: false,
                 retry: 0,
@@ -86,6 +86,7 @@ resources:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         )
         .unwrap();
         assert!(target.exists());
@@ -127,6 +128,7 @@ resources:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         )
         .unwrap();
     }
@@ -193,6 +195,7 @@ resources:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         )
         .unwrap();
     }
@@ -258,6 +261,7 @@ resources:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         );
         assert!(result.is_ok());
     }
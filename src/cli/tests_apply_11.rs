@@ -38,6 +38,7 @@ mod tests {
             check: false,
             report: false,
             force_unlock: false,
+            lock_reason: None,
             output: None,
             progress: false,
             timing: false,
@@ -235,6 +236,7 @@ mod tests {
             check: false,
             report: false,
             force_unlock: false,
+            lock_reason: None,
             output: None,
             progress: false,
             timing: false,
@@ -64,6 +64,7 @@ resources:
                 check: false,
                 report: false,
                 force_unlock: false,
+                lock_reason: None,
                 output: None,
                 progress: false,
                 retry: 0,
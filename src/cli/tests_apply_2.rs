@@ -65,6 +65,7 @@ mod tests {
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         )
         .unwrap();
     }
@@ -137,6 +138,7 @@ policies:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         );
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("policy violations"));
@@ -207,6 +209,7 @@ policy:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         );
         // cmd_apply needs a parsed config, but it re-parses from file
         // Instead, test the run_notify function directly
@@ -265,6 +268,7 @@ resources:
                 check: true,
                 report: false,
                 force_unlock: false,
+                lock_reason: None,
                 output: None,
                 progress: false,
                 retry: 0,
@@ -39,6 +39,7 @@ mod tests {
             check: false,
             report: false,
             force_unlock: false,
+            lock_reason: None,
             output: None,
             progress: false,
             timing: false,
@@ -234,6 +235,7 @@ mod tests {
             check: false,
             report: false,
             force_unlock: false,
+            lock_reason: None,
             output: None,
             progress: false,
             timing: false,
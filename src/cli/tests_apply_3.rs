@@ -62,6 +62,7 @@ resources:
                 check: false,
                 report: false,
                 force_unlock: false,
+                lock_reason: None,
                 output: None,
                 progress: false,
                 retry: 0,
@@ -292,6 +293,7 @@ resources:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         )
         .unwrap();
         // last-apply.yaml should be written
@@ -374,6 +376,7 @@ resources:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         )
         .unwrap();
         let content = std::fs::read_to_string(state.join("local").join("last-apply.yaml")).unwrap();
@@ -117,6 +117,7 @@ resources:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         )
         .unwrap();
         assert!(target.exists());
@@ -292,6 +293,7 @@ resources:
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         )
         .unwrap();
         assert!(std::path::Path::new(&target).exists());
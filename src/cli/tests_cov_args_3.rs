@@ -42,6 +42,7 @@ mod tests {
             check: true,
             report: true,
             force_unlock: true,
+            lock_reason: None,
             output: Some("events".to_string()),
             progress: true,
             timing: true,
@@ -234,6 +235,7 @@ mod tests {
             check: false,
             report: false,
             force_unlock: false,
+            lock_reason: None,
             output: None,
             progress: false,
             timing: false,
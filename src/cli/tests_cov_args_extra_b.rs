@@ -389,9 +389,12 @@ mod tests {
     #[test]
     fn test_cov_commands_secrets_rotate() {
         let cmd = Commands::Secrets(SecretsCmd::Rotate {
+            key: None,
             file: PathBuf::from("f.yaml"),
             identity: None,
             recipient: vec!["age1key".to_string()],
+            value_file: None,
+            rollback: false,
             re_encrypt: false,
             state_dir: PathBuf::from("s"),
         });
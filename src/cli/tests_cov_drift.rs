@@ -186,3 +186,42 @@ fn drift_with_machine_filter() {
     );
     assert!(r.is_ok());
 }
+
+// ── check_machine_drift ──
+
+#[test]
+fn expiring_certificate_counts_as_drift() {
+    let lock: crate::core::types::StateLock = serde_yaml_ng::from_str(
+        r#"schema: "1"
+machine: web1
+hostname: web1
+generated_at: "2025-01-01T00:00:00Z"
+generator: forjar-test
+blake3_version: "1.0"
+resources:
+  tls:
+    type: certificate
+    status: converged
+    hash: abc123
+    details:
+      outputs:
+        not_after: "Jan  1 00:00:00 2000 GMT"
+  tls-fresh:
+    type: certificate
+    status: converged
+    hash: def456
+    details:
+      outputs:
+        not_after: "Jan  1 00:00:00 9999 GMT"
+"#,
+    )
+    .unwrap();
+    let mut findings = Vec::new();
+    let count = super::drift::check_machine_drift("web1", &lock, None, true, false, &mut findings);
+    assert_eq!(count, 1);
+    assert_eq!(findings[0]["resource"], "tls");
+    assert!(findings[0]["detail"]
+        .as_str()
+        .unwrap()
+        .starts_with("certificate expired"));
+}
@@ -5,6 +5,7 @@ use super::lock_ops::*;
 use super::lock_core::*;
 use super::lock_merge::cmd_lock_sign;
 use super::lock_security::*;
+use super::test_fixtures::signing_key_file;
 
 #[cfg(test)]
 mod tests {
@@ -117,6 +118,43 @@ mod tests {
         let _ = cmd_lock_info(d.path(), true);
     }
     #[test]
+    fn test_lock_info_with_apply_lock_and_audit() {
+        use crate::core::state::lease::{audit_entries, read_lease, AUDIT_KEY};
+        use crate::tripwire::eventlog::now_iso8601;
+        let d = tempfile::tempdir().unwrap();
+        crate::core::state::acquire_apply_lock(d.path(), Some("maintenance"), 60).unwrap();
+        let (held, _) = read_lease(d.path()).unwrap().unwrap();
+        assert_eq!(held.pid, std::process::id());
+        assert!(!held.holder.is_empty());
+        assert_eq!(held.reason.as_deref(), Some("maintenance"));
+        assert_eq!(held.ttl_secs, 60);
+        assert!(held.expires_at > held.acquired_at, "{held:?}");
+        assert!(!held.is_expired(&now_iso8601()));
+
+        let broken = crate::core::state::force_unlock(d.path()).unwrap().unwrap();
+        assert_eq!(broken.lease_id, held.lease_id);
+        assert!(read_lease(d.path()).unwrap().is_none());
+        let log = std::fs::read_to_string(d.path().join(AUDIT_KEY)).unwrap();
+        assert_eq!(log.lines().count(), 1, "{log}");
+        let entries = audit_entries(d.path());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["action"], "force_unlock");
+        assert_eq!(entries[0]["by"], held.holder.as_str());
+        assert_eq!(entries[0]["previous"]["reason"], "maintenance");
+        assert_eq!(entries[0]["previous"]["lease_id"], held.lease_id.as_str());
+
+        crate::core::state::acquire_apply_lock(d.path(), None, 60).unwrap();
+        let (current, _) = read_lease(d.path()).unwrap().unwrap();
+        assert_ne!(current.lease_id, held.lease_id);
+        assert_eq!(current.reason, None);
+        assert!(!current.is_expired(&now_iso8601()));
+        assert!(cmd_lock_info(d.path(), false).is_ok());
+        assert!(cmd_lock_info(d.path(), true).is_ok());
+        crate::core::state::release_process_lock(d.path());
+        assert!(read_lease(d.path()).unwrap().is_none());
+        assert_eq!(audit_entries(d.path()).len(), 1);
+    }
+    #[test]
     fn test_lock_validate_empty() {
         let d = tempfile::tempdir().unwrap();
         assert!(cmd_lock_validate(d.path(), false).is_ok());
@@ -242,58 +280,85 @@ mod tests {
     #[test]
     fn test_sign_then_verify_sig() {
         let d = setup_valid_state();
-        assert!(cmd_lock_sign(d.path(), "mykey", false).is_ok());
-        assert!(cmd_lock_verify_sig(d.path(), "mykey", false).is_ok());
+        let key = signing_key_file(d.path(), "mykey");
+        assert!(cmd_lock_sign(d.path(), &key, false).is_ok());
+        assert!(cmd_lock_verify_sig(d.path(), &key, false).is_ok());
     }
 
     #[test]
     fn test_sign_then_verify_sig_json() {
         let d = setup_valid_state();
-        assert!(cmd_lock_sign(d.path(), "k", true).is_ok());
-        assert!(cmd_lock_verify_sig(d.path(), "k", true).is_ok());
+        let key = signing_key_file(d.path(), "k");
+        assert!(cmd_lock_sign(d.path(), &key, true).is_ok());
+        assert!(cmd_lock_verify_sig(d.path(), &key, true).is_ok());
     }
 
     #[test]
     fn test_verify_sig_wrong_key_fails() {
         let d = setup_valid_state();
-        assert!(cmd_lock_sign(d.path(), "right", false).is_ok());
-        assert!(cmd_lock_verify_sig(d.path(), "wrong", false).is_err());
+        let right = signing_key_file(d.path(), "right");
+        let wrong = signing_key_file(d.path(), "wrong");
+        assert!(cmd_lock_sign(d.path(), &right, false).is_ok());
+        assert!(cmd_lock_verify_sig(d.path(), &wrong, false).is_err());
+    }
+
+    #[test]
+    fn test_verify_sig_by_public_key_and_tamper() {
+        let d = setup_valid_state();
+        let key = signing_key_file(d.path(), "k");
+        let public = std::fs::read_to_string(d.path().join("k.pub")).unwrap();
+        assert!(cmd_lock_sign(d.path(), &key, false).is_ok());
+        assert!(cmd_lock_verify_sig(d.path(), public.trim(), false).is_ok());
+        let lock = d.path().join("srv1/state.lock.yaml");
+        let tampered = std::fs::read_to_string(&lock).unwrap() + "# edited\n";
+        std::fs::write(&lock, tampered).unwrap();
+        assert!(cmd_lock_verify_sig(d.path(), public.trim(), false).is_err());
     }
 
     #[test]
     fn test_sign_then_verify_chain() {
         let d = setup_valid_state();
-        assert!(cmd_lock_sign(d.path(), "k", false).is_ok());
+        let key = signing_key_file(d.path(), "k");
+        assert!(cmd_lock_sign(d.path(), &key, false).is_ok());
         assert!(cmd_lock_verify_chain(d.path(), false).is_ok());
     }
 
     #[test]
     fn test_sign_then_verify_chain_json() {
         let d = setup_valid_state();
-        assert!(cmd_lock_sign(d.path(), "k", false).is_ok());
+        let key = signing_key_file(d.path(), "k");
+        assert!(cmd_lock_sign(d.path(), &key, false).is_ok());
         assert!(cmd_lock_verify_chain(d.path(), true).is_ok());
     }
 
     #[test]
     fn test_rotate_keys_valid() {
         let d = setup_valid_state();
-        assert!(cmd_lock_sign(d.path(), "old", false).is_ok());
-        assert!(cmd_lock_rotate_keys(d.path(), "old", "new", false).is_ok());
-        assert!(cmd_lock_verify_sig(d.path(), "new", false).is_ok());
+        let old = signing_key_file(d.path(), "old");
+        let new = signing_key_file(d.path(), "new");
+        assert!(cmd_lock_sign(d.path(), &old, false).is_ok());
+        assert!(cmd_lock_rotate_keys(d.path(), &old, &new, false).is_ok());
+        assert!(cmd_lock_verify_sig(d.path(), &new, false).is_ok());
+        assert!(cmd_lock_verify_sig(d.path(), &old, false).is_err());
     }
 
     #[test]
     fn test_rotate_keys_valid_json() {
         let d = setup_valid_state();
-        assert!(cmd_lock_sign(d.path(), "old", false).is_ok());
-        assert!(cmd_lock_rotate_keys(d.path(), "old", "new", true).is_ok());
+        let old = signing_key_file(d.path(), "old");
+        let new = signing_key_file(d.path(), "new");
+        assert!(cmd_lock_sign(d.path(), &old, false).is_ok());
+        assert!(cmd_lock_rotate_keys(d.path(), &old, &new, true).is_ok());
     }
 
     #[test]
     fn test_rotate_keys_wrong_old_key_fails() {
         let d = setup_valid_state();
-        assert!(cmd_lock_sign(d.path(), "real", false).is_ok());
-        assert!(cmd_lock_rotate_keys(d.path(), "fake", "new", false).is_err());
+        let real = signing_key_file(d.path(), "real");
+        let fake = signing_key_file(d.path(), "fake");
+        let new = signing_key_file(d.path(), "new");
+        assert!(cmd_lock_sign(d.path(), &real, false).is_ok());
+        assert!(cmd_lock_rotate_keys(d.path(), &fake, &new, false).is_err());
     }
 
     #[test]
@@ -281,6 +281,6 @@ references: []
     // Now inspect + verify the packed FAR
     assert!(cmd_archive_inspect(&far_out, false).is_ok());
     assert!(cmd_archive_inspect(&far_out, true).is_ok());
-    assert!(cmd_archive_verify(&far_out, false).is_ok());
-    assert!(cmd_archive_verify(&far_out, true).is_ok());
+    assert!(cmd_archive_verify(&far_out, None, false).is_ok());
+    assert!(cmd_archive_verify(&far_out, None, true).is_ok());
 }
@@ -2,6 +2,7 @@
 //! Tests: Coverage for remaining validate, lock, destroy, observe (part 4).
 use super::lock_core::*;
 use super::lock_security::*;
+use super::test_fixtures::signing_key_file;
 use std::io::Write;
 
 #[cfg(test)]
@@ -318,14 +319,16 @@ mod tests {
     #[test]
     fn test_cov_lock_rotate_keys_data_plain() {
         let dir = make_state_dir();
-        let result = cmd_lock_rotate_keys(dir.path(), "old-key", "new-key", false);
+        let key = signing_key_file(dir.path(), "new-key");
+        let result = cmd_lock_rotate_keys(dir.path(), &key, &key, false);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_cov_lock_rotate_keys_data_json() {
         let dir = make_state_dir();
-        let result = cmd_lock_rotate_keys(dir.path(), "old", "new", true);
+        let key = signing_key_file(dir.path(), "new");
+        let result = cmd_lock_rotate_keys(dir.path(), &key, &key, true);
         assert!(result.is_ok());
     }
 
@@ -130,6 +130,7 @@ mod tests {
             None, // telemetry_endpoint
             false, // refresh
             None, // force_tag
+            None, // lock_reason
         );
         let result = cmd_destroy(&cfg, &state, None, true, true);
         assert!(result.is_ok());
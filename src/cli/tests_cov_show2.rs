@@ -81,6 +81,24 @@ fn explain_with_deps() {
     assert!(r.is_ok());
 }
 
+#[test]
+fn explain_included_resource() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("packages.yaml"),
+        "version: '1.0'\nname: pkgs\nresources:\n  curl:\n    type: package\n    provider: apt\n    packages: [curl]\n    machine: web1\n",
+    )
+    .unwrap();
+    let main = dir.path().join("forjar.yaml");
+    std::fs::write(
+        &main,
+        "version: '1.0'\nname: test\nincludes:\n  - packages.yaml\nmachines:\n  web1:\n    hostname: web1\n    addr: 127.0.0.1\nresources: {}\n",
+    )
+    .unwrap();
+    assert!(super::show::cmd_explain(&main, "curl", false).is_ok());
+    assert!(super::show::cmd_explain(&main, "curl", true).is_ok());
+}
+
 #[test]
 fn explain_not_found() {
     let cfg = write_temp_config(CONFIG);
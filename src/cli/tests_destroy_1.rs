@@ -39,6 +39,7 @@ mod tests {
             check: false,
             report: false,
             force_unlock: false,
+            lock_reason: None,
             output: None,
             progress: false,
             timing: false,
@@ -244,6 +245,7 @@ resources:
             force: false,
             refresh: false,
             force_tag: None,
+            lock_reason: None,
             dry_run: false,
             machine_filter: None,
             resource_filter: None,
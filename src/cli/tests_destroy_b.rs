@@ -52,4 +52,39 @@ mod tests {
         assert!(!remaining_lock.resources.contains_key("r1"));
         assert!(remaining_lock.resources.contains_key("r2"));
     }
+
+    /// FJ-1425: destroy removes the locks from the remote store, not just
+    /// the working copy.
+    #[test]
+    fn destroy_removes_remote_locks() {
+        use crate::core::state::backend::{LocalBackend, StateBackend};
+
+        let dir = tempfile::tempdir().unwrap();
+        let remote = dir.path().join("remote");
+        let state_dir = dir.path().join("state");
+        let target = dir.path().join("managed.txt");
+        std::fs::write(&target, "x").unwrap();
+        let config = dir.path().join("forjar.yaml");
+        std::fs::write(
+            &config,
+            format!(
+                "version: \"1.0\"\nname: t\nmachines:\n  local:\n    hostname: localhost\n    addr: 127.0.0.1\nresources:\n  f:\n    type: file\n    machine: local\n    path: {}\n    content: x\npolicy:\n  backend:\n    type: local\n    path: {}\n",
+                target.display(),
+                remote.display()
+            ),
+        )
+        .unwrap();
+        let store = LocalBackend::new(&remote);
+        let mut lock = state::new_lock("local", "localhost");
+        lock.generator = "remote".into();
+        let yaml = serde_yaml_ng::to_string(&lock).unwrap();
+        store.put("local/state.lock.yaml", yaml.as_bytes()).unwrap();
+
+        cmd_destroy(&config, &state_dir, None, true, false).unwrap();
+        state::backend::unregister_backend(&state_dir);
+
+        assert!(!target.exists());
+        assert!(!store.exists("local/state.lock.yaml").unwrap());
+        assert!(!state_dir.join("local/state.lock.yaml").exists());
+    }
 }
@@ -88,6 +88,7 @@ fn dispatch_state_backend_empty() {
     std::fs::create_dir_all(&state_dir).unwrap();
     let cmd = Commands::StateBackend(StateBackendArgs {
         state_dir,
+        file: None,
         prefix: None,
         json: false,
     });
@@ -102,6 +103,7 @@ fn dispatch_state_backend_json_with_prefix() {
     std::fs::create_dir_all(&state_dir).unwrap();
     let cmd = Commands::StateBackend(StateBackendArgs {
         state_dir,
+        file: None,
         prefix: Some("prod".to_string()),
         json: true,
     });
@@ -178,15 +180,64 @@ resources:
     )
     .unwrap();
     // Sign mode (not verify) — generates a signature
+    let key = super::test_fixtures::signing_key_file(dir.path(), "ci");
     let cmd = Commands::RecipeSign(RecipeSignArgs {
-        recipe,
+        recipe: recipe.clone(),
         verify: false,
-        signer: None,
+        signer: Some(key),
+        trust_policy: None,
         json: false,
         pq: false,
     });
     let result = dispatch_platform_cmd(cmd);
     assert!(result.is_ok());
+    assert!(recipe.with_extension("sig.json").exists());
+}
+
+#[test]
+fn dispatch_keys_generate_import_list() {
+    let dir = tempfile::tempdir().unwrap();
+    let keyring = dir.path().join("keys");
+    let run = |sub| dispatch_platform_cmd(Commands::Keys(sub));
+    assert!(run(KeysCmd::Generate {
+        name: "ci".to_string(),
+        pq: false,
+        key_dir: Some(keyring.clone()),
+    })
+    .is_ok());
+    // Refuses to overwrite an existing key.
+    assert!(run(KeysCmd::Generate {
+        name: "ci".to_string(),
+        pq: false,
+        key_dir: Some(keyring.clone()),
+    })
+    .is_err());
+    // --pq adds the ML-DSA-65 half to an existing key, once.
+    for expect_ok in [true, false] {
+        let result = run(KeysCmd::Generate {
+            name: "ci".to_string(),
+            pq: true,
+            key_dir: Some(keyring.clone()),
+        });
+        assert_eq!(result.is_ok(), expect_ok);
+    }
+    assert!(keyring.join("ml-dsa-65/ci.pub").exists());
+    assert!(run(KeysCmd::Import {
+        name: "ci-verify".to_string(),
+        file: keyring.join("ci.pub"),
+        key_dir: Some(keyring.clone()),
+    })
+    .is_ok());
+    assert!(run(KeysCmd::List {
+        key_dir: Some(keyring.clone()),
+        json: true,
+    })
+    .is_ok());
+    assert_eq!(
+        std::fs::read_to_string(keyring.join("ci.pub")).unwrap(),
+        std::fs::read_to_string(keyring.join("ci-verify.pub")).unwrap()
+    );
+    assert!(!keyring.join("ci-verify.key").exists());
 }
 
 #[test]
@@ -198,6 +249,7 @@ fn dispatch_recipe_sign_verify_missing_sig() {
         recipe,
         verify: true,
         signer: None,
+        trust_policy: None,
         json: false,
         pq: false,
     });
@@ -397,15 +449,18 @@ fn dispatch_recipe_sign_pq() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "recipe:\n  name: pq-test\nresources: {}\n").unwrap();
+    let key = super::test_fixtures::dual_signing_key_file(dir.path(), "ci");
     let cmd = Commands::RecipeSign(RecipeSignArgs {
-        recipe,
+        recipe: recipe.clone(),
         verify: false,
-        signer: Some("ci".to_string()),
+        signer: Some(key),
+        trust_policy: None,
         json: true,
         pq: true,
     });
     let result = dispatch_platform_cmd(cmd);
     assert!(result.is_ok());
+    assert!(recipe.with_extension("dual-sig.json").exists());
 }
 
 #[test]
@@ -200,6 +200,16 @@ fn dispatch_archive_unpack_routes() {
     let result = dispatch_store_cmd(Commands::Archive(ArchiveCmd::Unpack {
         file: PathBuf::from("/nonexistent.far"),
         store_dir: PathBuf::from("/tmp/store"),
+        trust_policy: None,
+    }));
+    assert!(result.is_err());
+}
+
+#[test]
+fn dispatch_archive_sign_routes() {
+    let result = dispatch_store_cmd(Commands::Archive(ArchiveCmd::Sign {
+        file: PathBuf::from("/nonexistent.far"),
+        key: Some("/nonexistent.key".to_string()),
     }));
     assert!(result.is_err());
 }
@@ -208,6 +218,7 @@ fn dispatch_archive_unpack_routes() {
 fn dispatch_archive_verify_routes() {
     let result = dispatch_store_cmd(Commands::Archive(ArchiveCmd::Verify {
         file: PathBuf::from("/nonexistent.far"),
+        trust_policy: None,
         json: false,
     }));
     assert!(result.is_err());
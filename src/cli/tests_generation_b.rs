@@ -3,7 +3,7 @@
 use super::generation::{
     cmd_generation_diff, collect_generation_numbers, count_lock_resources, create_generation,
     current_generation, gc_generations, generations_dir, list_generations, load_gen_locks,
-    lock_to_tuples, read_created_at, read_gen_info,
+    lock_to_tuples, read_created_at, read_gen_info, rollback_to_generation,
 };
 use crate::core::types::{GenerationMeta, ResourceLock, ResourceStatus, ResourceType, StateLock};
 use std::collections::HashMap;
@@ -455,3 +455,47 @@ fn list_generations_text_with_data() {
     let result = list_generations(&state_dir, false);
     assert!(result.is_ok());
 }
+
+// ── generations through a remote state backend ─────────────────────
+
+#[test]
+fn generations_stored_in_remote_backend() {
+    use crate::core::state::backend::{
+        backend_for, register_backend, unregister_backend, LocalBackend, MirroredBackend,
+    };
+    let dir = tempfile::tempdir().unwrap();
+    let remote = dir.path().join("remote");
+    let state_dir = dir.path().join("state");
+    std::fs::create_dir_all(&state_dir).unwrap();
+    register_backend(
+        &state_dir,
+        std::sync::Arc::new(MirroredBackend::new(
+            Box::new(LocalBackend::new(&remote)),
+            &state_dir,
+        )),
+    );
+    let lock = crate::core::state::new_lock("m1", "box");
+    crate::core::state::save_lock(&state_dir, &lock).unwrap();
+    assert_eq!(create_generation(&state_dir, None).unwrap(), 0);
+    assert!(remote.join("generations/0/m1/state.lock.yaml").exists());
+    assert!(remote.join("generations/0/.generation.yaml").exists());
+    assert_eq!(
+        std::fs::read_to_string(remote.join("generations/.current")).unwrap(),
+        "0"
+    );
+
+    backend_for(&state_dir)
+        .put("m2/state.lock.yaml", b"schema: '1.0'")
+        .unwrap();
+    rollback_to_generation(&state_dir, 0, true).unwrap();
+    assert!(!remote.join("m2").join("state.lock.yaml").exists());
+    assert!(remote.join("m1/state.lock.yaml").exists());
+
+    // A fresh working copy without the symlink still knows the current generation.
+    std::fs::remove_file(generations_dir(&state_dir).join("current")).unwrap();
+    assert_eq!(current_generation(&generations_dir(&state_dir)), Some(0));
+
+    gc_generations(&state_dir, 0, false);
+    assert!(!remote.join("generations/0/m1/state.lock.yaml").exists());
+    unregister_backend(&state_dir);
+}
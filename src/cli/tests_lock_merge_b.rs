@@ -1,6 +1,7 @@
 //! Coverage tests for lock_merge.rs — merge, rebase, sign operations.
 
 use super::lock_merge::*;
+use super::test_fixtures::signing_key_file;
 use crate::core::state;
 
 // ── cmd_lock_merge ──────────────────────────────────────────────────
@@ -133,8 +134,9 @@ fn lock_sign_signs_existing_locks() {
     let dir = tempfile::tempdir().unwrap();
     let lock = state::new_lock("web", "web-host");
     state::save_lock(dir.path(), &lock).unwrap();
+    let key = signing_key_file(dir.path(), "test-key");
 
-    let result = cmd_lock_sign(dir.path(), "test-key", false);
+    let result = cmd_lock_sign(dir.path(), &key, false);
     assert!(result.is_ok());
     // Signature file should exist
     assert!(dir.path().join("web").join("lock.sig").exists());
@@ -145,8 +147,9 @@ fn lock_sign_json_output() {
     let dir = tempfile::tempdir().unwrap();
     let lock = state::new_lock("app", "app-host");
     state::save_lock(dir.path(), &lock).unwrap();
+    let key = signing_key_file(dir.path(), "key");
 
-    let result = cmd_lock_sign(dir.path(), "key", true);
+    let result = cmd_lock_sign(dir.path(), &key, true);
     assert!(result.is_ok());
 }
 
@@ -154,7 +157,7 @@ fn lock_sign_json_output() {
 fn lock_sign_empty_state_dir() {
     let dir = tempfile::tempdir().unwrap();
     let result = cmd_lock_sign(dir.path(), "key", false);
-    assert!(result.is_ok()); // Signs 0 files successfully
+    assert!(result.is_ok()); // Signs 0 files successfully, no key needed
 }
 
 #[test]
@@ -165,6 +168,16 @@ fn lock_sign_nonexistent_dir() {
     assert!(result.is_ok()); // No dir → 0 signed, still ok
 }
 
+#[test]
+fn lock_sign_unknown_key_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let lock = state::new_lock("web", "host");
+    state::save_lock(dir.path(), &lock).unwrap();
+    let result = cmd_lock_sign(dir.path(), "/nonexistent/key.pem", false);
+    assert!(result.is_err());
+    assert!(!dir.path().join("web").join("lock.sig").exists());
+}
+
 #[test]
 fn lock_sign_multiple_machines() {
     let dir = tempfile::tempdir().unwrap();
@@ -172,8 +185,9 @@ fn lock_sign_multiple_machines() {
         let lock = state::new_lock(name, &format!("{name}-host"));
         state::save_lock(dir.path(), &lock).unwrap();
     }
+    let key = signing_key_file(dir.path(), "multi-key");
 
-    let result = cmd_lock_sign(dir.path(), "multi-key", false);
+    let result = cmd_lock_sign(dir.path(), &key, false);
     assert!(result.is_ok());
     for name in &["web", "db", "cache"] {
         assert!(dir.path().join(name).join("lock.sig").exists());
@@ -192,26 +206,34 @@ fn lock_sign_skips_hidden_dirs() {
 
     let lock = state::new_lock("web", "host");
     state::save_lock(dir.path(), &lock).unwrap();
+    let key = signing_key_file(dir.path(), "key");
 
-    let result = cmd_lock_sign(dir.path(), "key", false);
+    let result = cmd_lock_sign(dir.path(), &key, false);
     assert!(result.is_ok());
     assert!(!dir.path().join(".hidden").join("lock.sig").exists());
     assert!(dir.path().join("web").join("lock.sig").exists());
 }
 
+fn read_sig(dir: &std::path::Path) -> crate::core::signing::Signature {
+    crate::core::signing::read_signature(&dir.join("web").join("lock.sig"))
+        .unwrap()
+        .unwrap()
+}
+
 #[test]
 fn lock_sign_deterministic() {
     let dir = tempfile::tempdir().unwrap();
     let lock = state::new_lock("web", "host");
     state::save_lock(dir.path(), &lock).unwrap();
+    let key = signing_key_file(dir.path(), "key-a");
 
-    cmd_lock_sign(dir.path(), "key-a", false).unwrap();
-    let sig1 = std::fs::read_to_string(dir.path().join("web").join("lock.sig")).unwrap();
-
-    cmd_lock_sign(dir.path(), "key-a", false).unwrap();
-    let sig2 = std::fs::read_to_string(dir.path().join("web").join("lock.sig")).unwrap();
+    cmd_lock_sign(dir.path(), &key, false).unwrap();
+    let sig1 = read_sig(dir.path());
+    cmd_lock_sign(dir.path(), &key, false).unwrap();
+    let sig2 = read_sig(dir.path());
 
-    assert_eq!(sig1, sig2, "same key should produce same signature");
+    // Ed25519 is deterministic: same key and lock, same signature.
+    assert_eq!(sig1.signature, sig2.signature);
 }
 
 #[test]
@@ -219,12 +241,14 @@ fn lock_sign_different_keys_different_sigs() {
     let dir = tempfile::tempdir().unwrap();
     let lock = state::new_lock("web", "host");
     state::save_lock(dir.path(), &lock).unwrap();
+    let key_a = signing_key_file(dir.path(), "key-a");
+    let key_b = signing_key_file(dir.path(), "key-b");
 
-    cmd_lock_sign(dir.path(), "key-a", false).unwrap();
-    let sig1 = std::fs::read_to_string(dir.path().join("web").join("lock.sig")).unwrap();
-
-    cmd_lock_sign(dir.path(), "key-b", false).unwrap();
-    let sig2 = std::fs::read_to_string(dir.path().join("web").join("lock.sig")).unwrap();
+    cmd_lock_sign(dir.path(), &key_a, false).unwrap();
+    let sig1 = read_sig(dir.path());
+    cmd_lock_sign(dir.path(), &key_b, false).unwrap();
+    let sig2 = read_sig(dir.path());
 
-    assert_ne!(sig1, sig2, "different keys should produce different signatures");
+    assert_ne!(sig1.key, sig2.key);
+    assert_ne!(sig1.signature, sig2.signature);
 }
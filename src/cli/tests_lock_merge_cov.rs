@@ -1,6 +1,7 @@
 //! Coverage tests for lock_merge.rs — merge, rebase, sign.
 
 use super::lock_merge::*;
+use super::test_fixtures::signing_key_file;
 use crate::core::state;
 
 fn write_lock(state_dir: &std::path::Path, machine: &str) {
@@ -207,7 +208,8 @@ fn sign_empty_state() {
 fn sign_with_lock() {
     let dir = tempfile::tempdir().unwrap();
     write_lock(dir.path(), "web");
-    assert!(cmd_lock_sign(dir.path(), "my-secret-key", false).is_ok());
+    let key = signing_key_file(dir.path(), "ci");
+    assert!(cmd_lock_sign(dir.path(), &key, false).is_ok());
     assert!(dir.path().join("web/lock.sig").exists());
 }
 
@@ -215,7 +217,8 @@ fn sign_with_lock() {
 fn sign_json_output() {
     let dir = tempfile::tempdir().unwrap();
     write_lock(dir.path(), "db");
-    assert!(cmd_lock_sign(dir.path(), "key", true).is_ok());
+    let key = signing_key_file(dir.path(), "ci");
+    assert!(cmd_lock_sign(dir.path(), &key, true).is_ok());
 }
 
 #[test]
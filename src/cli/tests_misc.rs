@@ -270,6 +270,7 @@ resources:
             check: false,
             report: false,
             force_unlock: false,
+            lock_reason: None,
             output: None,
             progress: true,
             retry: 0,
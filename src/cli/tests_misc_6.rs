@@ -38,6 +38,7 @@ mod tests {
             workspace: None,
             report: false,
             force_unlock: false,
+            lock_reason: None,
             output: None,
             progress: false,
             timing: false,
@@ -231,6 +232,7 @@ mod tests {
             workspace: None,
             report: false,
             force_unlock: false,
+            lock_reason: None,
             output: None,
             progress: false,
             timing: false,
@@ -2,6 +2,7 @@
 //! and undo_helpers edge cases.
 
 use super::pq_signing::*;
+use super::test_fixtures::{dual_signing_key_file, dual_signing_keys, key_spec, pinned_signer};
 use super::undo_helpers::*;
 use crate::core::types;
 
@@ -12,8 +13,9 @@ fn cmd_dual_sign_text_output() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("file.yaml");
     std::fs::write(&f, "test content").unwrap();
+    let key = dual_signing_key_file(dir.path(), "ci-bot");
     // Sign in text mode (not JSON)
-    let result = cmd_dual_sign(&f, false, Some("ci-bot"), false);
+    let result = cmd_dual_sign(&f, false, Some(&key), None, false);
     assert!(result.is_ok());
 }
 
@@ -22,9 +24,10 @@ fn cmd_dual_sign_verify_json_valid() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("file.yaml");
     std::fs::write(&f, "hello").unwrap();
-    dual_sign(&f, "signer").unwrap();
+    let k = dual_signing_keys(dir.path(), "signer");
+    dual_sign(&f, &k.0, &k.1).unwrap();
     // Verify JSON mode
-    let result = cmd_dual_sign(&f, true, None, true);
+    let result = cmd_dual_sign(&f, true, Some(&key_spec(dir.path(), "signer")), None, true);
     assert!(result.is_ok());
 }
 
@@ -33,9 +36,10 @@ fn cmd_dual_sign_verify_text_valid() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("file.yaml");
     std::fs::write(&f, "hello").unwrap();
-    dual_sign(&f, "signer").unwrap();
+    let k = dual_signing_keys(dir.path(), "signer");
+    dual_sign(&f, &k.0, &k.1).unwrap();
     // Verify text mode
-    let result = cmd_dual_sign(&f, true, None, false);
+    let result = cmd_dual_sign(&f, true, Some(&key_spec(dir.path(), "signer")), None, false);
     assert!(result.is_ok());
 }
 
@@ -44,10 +48,11 @@ fn cmd_dual_sign_verify_tampered() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("file.yaml");
     std::fs::write(&f, "original").unwrap();
-    dual_sign(&f, "signer").unwrap();
+    let k = dual_signing_keys(dir.path(), "signer");
+    dual_sign(&f, &k.0, &k.1).unwrap();
     std::fs::write(&f, "tampered").unwrap();
     // Verify should fail
-    let result = cmd_dual_sign(&f, true, None, false);
+    let result = cmd_dual_sign(&f, true, Some(&key_spec(dir.path(), "signer")), None, false);
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("verification failed"));
 }
@@ -57,26 +62,29 @@ fn cmd_dual_sign_verify_no_sig_json() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("nosig.yaml");
     std::fs::write(&f, "test").unwrap();
+    dual_signing_key_file(dir.path(), "signer");
     // Verify with no signature file — should fail
-    let result = cmd_dual_sign(&f, true, None, true);
+    let result = cmd_dual_sign(&f, true, Some(&key_spec(dir.path(), "signer")), None, true);
     assert!(result.is_err());
 }
 
 #[test]
-fn cmd_dual_sign_default_signer() {
+fn cmd_dual_sign_ed25519_only_signer() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("test.yaml");
     std::fs::write(&f, "data").unwrap();
-    // None signer → defaults to "local"
-    let result = cmd_dual_sign(&f, false, None, false);
-    assert!(result.is_ok());
+    // A key without an ML-DSA half cannot dual-sign
+    let key = super::test_fixtures::signing_key_file(dir.path(), "local");
+    let result = cmd_dual_sign(&f, false, Some(&key), None, false);
+    assert!(result.is_err());
 }
 
 #[test]
 fn dual_sign_missing_file() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("nonexistent.yaml");
-    let result = dual_sign(&f, "signer");
+    let k = dual_signing_keys(dir.path(), "signer");
+    let result = dual_sign(&f, &k.0, &k.1);
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("read:"));
 }
@@ -86,7 +94,7 @@ fn dual_verify_missing_file() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("nosig.yaml");
     std::fs::write(&f, "test").unwrap();
-    let result = dual_verify(&f).unwrap();
+    let result = dual_verify(&f, &[]).unwrap();
     assert!(!result.both_valid);
     assert!(result.reason.contains("no dual signature"));
 }
@@ -96,8 +104,9 @@ fn dual_verify_result_fields() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("check.yaml");
     std::fs::write(&f, "hello").unwrap();
-    dual_sign(&f, "tester").unwrap();
-    let r = dual_verify(&f).unwrap();
+    let k = dual_signing_keys(dir.path(), "tester");
+    dual_sign(&f, &k.0, &k.1).unwrap();
+    let r = dual_verify(&f, &[pinned_signer(&k)]).unwrap();
     assert!(r.classical_valid);
     assert!(r.pq_valid);
     assert!(r.both_valid);
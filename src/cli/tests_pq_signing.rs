@@ -2,21 +2,37 @@
 
 #![allow(unused_imports)]
 use super::pq_signing::*;
+use super::test_fixtures::{
+    dual_signing_key_file, dual_signing_keys, pinned_signer, signing_key_file,
+};
 use std::path::Path;
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn rewrite_sig(file: &Path, edit: impl FnOnce(&mut DualSignature)) {
+        let path = file.with_extension("dual-sig.json");
+        let mut sig: DualSignature =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        edit(&mut sig);
+        std::fs::write(&path, serde_json::to_string(&sig).unwrap()).unwrap();
+    }
+
     #[test]
     fn test_dual_sign() {
         let dir = tempfile::tempdir().unwrap();
         let f = dir.path().join("recipe.yaml");
         std::fs::write(&f, "test content").unwrap();
-        let sig = dual_sign(&f, "test-signer").unwrap();
-        assert_eq!(sig.classical_alg, "blake3-hmac");
-        assert_eq!(sig.pq_alg, "slh-dsa-blake3-placeholder");
+        let (classical, pq) = dual_signing_keys(dir.path(), "test-signer");
+        let sig = dual_sign(&f, &classical, &pq).unwrap();
+        assert_eq!(sig.classical_alg, "ed25519");
+        assert_eq!(sig.pq_alg, "ml-dsa-65");
         assert_eq!(sig.blake3_hash.len(), 64);
+        assert_eq!(sig.signer, "test-signer");
+        assert!(sig.pq_key.starts_with("ml-dsa-65:"));
+        // ML-DSA-65 signatures are 3309 bytes.
+        assert_eq!(sig.pq_sig.len(), 3309_usize.div_ceil(3) * 4);
     }
 
     #[test]
@@ -24,8 +40,9 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let f = dir.path().join("test.yaml");
         std::fs::write(&f, "content").unwrap();
-        dual_sign(&f, "signer").unwrap();
-        let result = dual_verify(&f).unwrap();
+        let keys = dual_signing_keys(dir.path(), "signer");
+        dual_sign(&f, &keys.0, &keys.1).unwrap();
+        let result = dual_verify(&f, &[pinned_signer(&keys)]).unwrap();
         assert!(result.both_valid);
         assert!(result.classical_valid);
         assert!(result.pq_valid);
@@ -36,9 +53,10 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let f = dir.path().join("test.yaml");
         std::fs::write(&f, "original").unwrap();
-        dual_sign(&f, "signer").unwrap();
+        let keys = dual_signing_keys(dir.path(), "signer");
+        dual_sign(&f, &keys.0, &keys.1).unwrap();
         std::fs::write(&f, "tampered").unwrap();
-        let result = dual_verify(&f).unwrap();
+        let result = dual_verify(&f, &[pinned_signer(&keys)]).unwrap();
         assert!(!result.both_valid);
     }
 
@@ -47,8 +65,119 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let f = dir.path().join("nosig.yaml");
         std::fs::write(&f, "test").unwrap();
-        let result = dual_verify(&f).unwrap();
+        let result = dual_verify(&f, &[]).unwrap();
+        assert!(!result.both_valid);
+    }
+
+    #[test]
+    fn test_dual_verify_checks_each_half() {
+        let dir = tempfile::tempdir().unwrap();
+        let f = dir.path().join("test.yaml");
+        std::fs::write(&f, "content").unwrap();
+        let keys = dual_signing_keys(dir.path(), "signer");
+        let other = dual_signing_keys(dir.path(), "other");
+        let foreign = dual_sign(&f, &other.0, &other.1).unwrap();
+        let pinned = [pinned_signer(&keys)];
+
+        // Swapping in another signer's half breaks only that half.
+        dual_sign(&f, &keys.0, &keys.1).unwrap();
+        rewrite_sig(&f, |s| s.pq_sig = foreign.pq_sig.clone());
+        let result = dual_verify(&f, &pinned).unwrap();
+        assert!(result.classical_valid);
+        assert!(!result.pq_valid);
         assert!(!result.both_valid);
+        assert!(result.reason.contains("ml-dsa-65"), "{}", result.reason);
+
+        dual_sign(&f, &keys.0, &keys.1).unwrap();
+        rewrite_sig(&f, |s| s.classical_sig = foreign.classical_sig.clone());
+        let result = dual_verify(&f, &pinned).unwrap();
+        assert!(!result.classical_valid);
+        assert!(result.pq_valid);
+    }
+
+    #[test]
+    fn test_dual_verify_rejects_foreign_key_pair() {
+        let dir = tempfile::tempdir().unwrap();
+        let f = dir.path().join("recipe.yaml");
+        std::fs::write(&f, "content").unwrap();
+        let key = dual_signing_key_file(dir.path(), "ci");
+        cmd_dual_sign(&f, false, Some(&key), None, false).unwrap();
+
+        // Edit the file and re-sign it with a freshly generated key pair:
+        // both halves verify against the envelope's own keys, but neither
+        // key is the pinned one.
+        std::fs::write(&f, "edited").unwrap();
+        let attacker = tempfile::tempdir().unwrap();
+        let foreign = dual_signing_keys(attacker.path(), "ci");
+        dual_sign(&f, &foreign.0, &foreign.1).unwrap();
+
+        let pinned = pinned_signers(Some(&key), None).unwrap();
+        let result = dual_verify(&f, &pinned).unwrap();
+        assert!(!result.classical_valid);
+        assert!(!result.pq_valid);
+        assert!(result.reason.contains("not pinned"), "{}", result.reason);
+        let err = cmd_dual_sign(&f, true, Some(&key), None, false).unwrap_err();
+        assert!(err.contains("dual verification failed"), "{err}");
+        assert!(
+            dual_verify(&f, &[pinned_signer(&foreign)])
+                .unwrap()
+                .both_valid
+        );
+    }
+
+    #[test]
+    fn test_dual_verify_pins_trust_policy_signers() {
+        let dir = tempfile::tempdir().unwrap();
+        let f = dir.path().join("recipe.yaml");
+        std::fs::write(&f, "content").unwrap();
+        let keys = dual_signing_keys(dir.path(), "ci");
+        dual_sign(&f, &keys.0, &keys.1).unwrap();
+
+        let policy = dir.path().join("trust.yaml");
+        let classical = keys.0.public_key().unwrap();
+        std::fs::write(
+            &policy,
+            format!("recipes:\n  signers:\n    - name: ci\n      key: {classical}\n"),
+        )
+        .unwrap();
+        let err = cmd_dual_sign(&f, true, None, Some(&policy), false).unwrap_err();
+        assert!(err.contains("no recipe signer with a pq_key"), "{err}");
+
+        std::fs::write(
+            &policy,
+            format!(
+                "recipes:\n  signers:\n    - name: ci\n      key: {classical}\n      pq_key: {}\n",
+                keys.1.public_key()
+            ),
+        )
+        .unwrap();
+        let pinned = pinned_signers(None, Some(&policy)).unwrap();
+        let result = dual_verify(&f, &pinned).unwrap();
+        assert!(result.both_valid, "{}", result.reason);
+        assert!(result.reason.contains("pinned signer 'ci'"));
+    }
+
+    #[test]
+    fn test_dual_verify_rejects_placeholder_signature() {
+        let dir = tempfile::tempdir().unwrap();
+        let f = dir.path().join("test.yaml");
+        std::fs::write(&f, "content").unwrap();
+        // Envelope written by the old BLAKE3 placeholder scheme.
+        let old = serde_json::json!({
+            "path": f.display().to_string(),
+            "blake3_hash": blake3::hash(b"content").to_hex().to_string(),
+            "classical_sig": "b".repeat(64),
+            "classical_alg": "blake3-hmac",
+            "pq_sig": "c".repeat(64),
+            "pq_alg": "slh-dsa-blake3-placeholder",
+            "timestamp": "2026-01-01T00:00:00Z",
+        });
+        std::fs::write(f.with_extension("dual-sig.json"), old.to_string()).unwrap();
+        let pinned = [pinned_signer(&dual_signing_keys(dir.path(), "ci"))];
+        let result = dual_verify(&f, &pinned).unwrap();
+        assert!(!result.classical_valid);
+        assert!(!result.pq_valid);
+        assert!(result.reason.contains("unsupported"), "{}", result.reason);
     }
 
     #[test]
@@ -56,23 +185,39 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let f = dir.path().join("recipe.yaml");
         std::fs::write(&f, "test").unwrap();
-        let result = cmd_dual_sign(&f, false, Some("ci"), true);
+        let key = dual_signing_key_file(dir.path(), "ci");
+        let result = cmd_dual_sign(&f, false, Some(&key), None, true);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_cmd_dual_sign_requires_pq_half() {
+        let dir = tempfile::tempdir().unwrap();
+        let f = dir.path().join("recipe.yaml");
+        std::fs::write(&f, "test").unwrap();
+        let key = signing_key_file(dir.path(), "ci");
+        let err = cmd_dual_sign(&f, false, Some(&key), None, false).unwrap_err();
+        assert!(err.contains("--pq"), "{err}");
+        assert!(!f.with_extension("dual-sig.json").exists());
+    }
+
     #[test]
     fn test_dual_signature_serde() {
         let sig = DualSignature {
             path: "/tmp/test.yaml".to_string(),
             blake3_hash: "a".repeat(64),
             classical_sig: "b".repeat(64),
-            classical_alg: "blake3-hmac".to_string(),
+            classical_alg: "ed25519".to_string(),
             pq_sig: "c".repeat(64),
-            pq_alg: "slh-dsa".to_string(),
+            pq_alg: "ml-dsa-65".to_string(),
             timestamp: "2026-01-01T00:00:00Z".to_string(),
+            signer: "ci".to_string(),
+            classical_key: "ed25519:".to_string(),
+            pq_key: "ml-dsa-65:".to_string(),
         };
         let json = serde_json::to_string(&sig).unwrap();
         let round: DualSignature = serde_json::from_str(&json).unwrap();
-        assert_eq!(round.pq_alg, "slh-dsa");
+        assert_eq!(round.pq_alg, "ml-dsa-65");
+        assert_eq!(round.signer, "ci");
     }
 }
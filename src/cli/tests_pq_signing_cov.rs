@@ -1,6 +1,7 @@
 //! Additional coverage tests for pq_signing.rs — uncovered cmd branches.
 
 use super::pq_signing::*;
+use super::test_fixtures::{dual_signing_key_file, dual_signing_keys, key_spec, pinned_signer};
 
 // ── cmd_dual_sign — sign mode ────────────────────────────────────────
 
@@ -9,7 +10,8 @@ fn cmd_sign_text_output() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("recipe.yaml");
     std::fs::write(&f, "hello world").unwrap();
-    let result = cmd_dual_sign(&f, false, Some("ci-bot"), false);
+    let key = dual_signing_key_file(dir.path(), "ci-bot");
+    let result = cmd_dual_sign(&f, false, Some(&key), None, false);
     assert!(result.is_ok());
     // sig file should exist
     assert!(f.with_extension("dual-sig.json").exists());
@@ -20,18 +22,20 @@ fn cmd_sign_json_output() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("config.yaml");
     std::fs::write(&f, "version: 1").unwrap();
-    let result = cmd_dual_sign(&f, false, Some("ci"), true);
+    let key = dual_signing_key_file(dir.path(), "ci");
+    let result = cmd_dual_sign(&f, false, Some(&key), None, true);
     assert!(result.is_ok());
 }
 
 #[test]
-fn cmd_sign_default_signer() {
+fn cmd_sign_unknown_signer() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("data.yaml");
     std::fs::write(&f, "test content").unwrap();
-    // None signer defaults to "local"
-    let result = cmd_dual_sign(&f, false, None, false);
-    assert!(result.is_ok());
+    let missing = dir.path().join("nobody.key");
+    let result = cmd_dual_sign(&f, false, Some(missing.to_str().unwrap()), None, false);
+    assert!(result.is_err());
+    assert!(!f.with_extension("dual-sig.json").exists());
 }
 
 // ── cmd_dual_sign — verify-only mode ─────────────────────────────────
@@ -41,8 +45,9 @@ fn cmd_verify_only_text_valid() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("signed.yaml");
     std::fs::write(&f, "original").unwrap();
-    dual_sign(&f, "signer").unwrap();
-    let result = cmd_dual_sign(&f, true, None, false);
+    let k = dual_signing_keys(dir.path(), "signer");
+    dual_sign(&f, &k.0, &k.1).unwrap();
+    let result = cmd_dual_sign(&f, true, Some(&key_spec(dir.path(), "signer")), None, false);
     assert!(result.is_ok());
 }
 
@@ -51,8 +56,9 @@ fn cmd_verify_only_json_valid() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("signed.yaml");
     std::fs::write(&f, "original").unwrap();
-    dual_sign(&f, "signer").unwrap();
-    let result = cmd_dual_sign(&f, true, None, true);
+    let k = dual_signing_keys(dir.path(), "signer");
+    dual_sign(&f, &k.0, &k.1).unwrap();
+    let result = cmd_dual_sign(&f, true, Some(&key_spec(dir.path(), "signer")), None, true);
     assert!(result.is_ok());
 }
 
@@ -61,9 +67,10 @@ fn cmd_verify_only_text_tampered() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("tampered.yaml");
     std::fs::write(&f, "original").unwrap();
-    dual_sign(&f, "signer").unwrap();
+    let k = dual_signing_keys(dir.path(), "signer");
+    dual_sign(&f, &k.0, &k.1).unwrap();
     std::fs::write(&f, "tampered!").unwrap();
-    let result = cmd_dual_sign(&f, true, None, false);
+    let result = cmd_dual_sign(&f, true, Some(&key_spec(dir.path(), "signer")), None, false);
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("dual verification failed"));
 }
@@ -73,9 +80,10 @@ fn cmd_verify_only_json_tampered() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("tampered.yaml");
     std::fs::write(&f, "original").unwrap();
-    dual_sign(&f, "signer").unwrap();
+    let k = dual_signing_keys(dir.path(), "signer");
+    dual_sign(&f, &k.0, &k.1).unwrap();
     std::fs::write(&f, "tampered!").unwrap();
-    let result = cmd_dual_sign(&f, true, None, true);
+    let result = cmd_dual_sign(&f, true, Some(&key_spec(dir.path(), "signer")), None, true);
     assert!(result.is_err());
 }
 
@@ -84,7 +92,8 @@ fn cmd_verify_only_no_sig_file() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("unsigned.yaml");
     std::fs::write(&f, "no sig here").unwrap();
-    let result = cmd_dual_sign(&f, true, None, false);
+    dual_signing_key_file(dir.path(), "signer");
+    let result = cmd_dual_sign(&f, true, Some(&key_spec(dir.path(), "signer")), None, false);
     assert!(result.is_err());
 }
 
@@ -94,7 +103,8 @@ fn cmd_verify_only_no_sig_file() {
 fn sign_nonexistent_file() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("missing.yaml");
-    let result = dual_sign(&f, "signer");
+    let k = dual_signing_keys(dir.path(), "signer");
+    let result = dual_sign(&f, &k.0, &k.1);
     assert!(result.is_err());
 }
 
@@ -103,10 +113,10 @@ fn sign_empty_file() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("empty.yaml");
     std::fs::write(&f, "").unwrap();
-    let sig = dual_sign(&f, "signer").unwrap();
+    let k = dual_signing_keys(dir.path(), "signer");
+    let sig = dual_sign(&f, &k.0, &k.1).unwrap();
     assert_eq!(sig.blake3_hash.len(), 64);
-    assert_eq!(sig.classical_sig.len(), 64);
-    assert_eq!(sig.pq_sig.len(), 64);
+    assert!(dual_verify(&f, &[pinned_signer(&k)]).unwrap().both_valid);
 }
 
 #[test]
@@ -114,15 +124,23 @@ fn verify_roundtrip_same_signer() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("stable.yaml");
     std::fs::write(&f, "stable content").unwrap();
-    let sig1 = dual_sign(&f, "alice").unwrap();
-    let result = dual_verify(&f).unwrap();
+    let alice = dual_signing_keys(dir.path(), "alice");
+    let sig1 = dual_sign(&f, &alice.0, &alice.1).unwrap();
+    let result = dual_verify(&f, &[pinned_signer(&alice)]).unwrap();
     assert!(result.both_valid);
-    assert_eq!(result.reason, "both signatures valid");
+    assert_eq!(
+        result.reason,
+        "both signatures valid, pinned signer 'alice'"
+    );
 
     // Sign again with different signer — hash stays same but sigs differ
-    let sig2 = dual_sign(&f, "bob").unwrap();
+    let bob = dual_signing_keys(dir.path(), "bob");
+    let sig2 = dual_sign(&f, &bob.0, &bob.1).unwrap();
     assert_eq!(sig1.blake3_hash, sig2.blake3_hash);
     assert_ne!(sig1.classical_sig, sig2.classical_sig);
+    assert_ne!(sig1.pq_sig, sig2.pq_sig);
+    let both = [pinned_signer(&alice), pinned_signer(&bob)];
+    assert!(dual_verify(&f, &both).unwrap().both_valid);
 }
 
 #[test]
@@ -130,7 +148,7 @@ fn dual_verify_result_fields() {
     let dir = tempfile::tempdir().unwrap();
     let f = dir.path().join("fields.yaml");
     std::fs::write(&f, "data").unwrap();
-    let result = dual_verify(&f).unwrap();
+    let result = dual_verify(&f, &[]).unwrap();
     // No sig file — should fail
     assert!(!result.both_valid);
     assert!(!result.classical_valid);
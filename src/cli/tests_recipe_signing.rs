@@ -2,21 +2,36 @@
 
 #![allow(unused_imports)]
 use super::recipe_signing::*;
+use super::test_fixtures::signing_key_file;
+use crate::core::signing::{self, TrustPolicy};
 use std::path::Path;
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn key(dir: &Path, name: &str) -> signing::SigningKey {
+        signing::load_signing_key(&signing_key_file(dir, name)).unwrap()
+    }
+
+    fn policy_for(key: &signing::SigningKey) -> TrustPolicy {
+        TrustPolicy::parse(&format!(
+            "recipes:\n  require_signatures: true\n  signers:\n    - name: ci\n      key: {}\n",
+            key.public_key().unwrap()
+        ))
+        .unwrap()
+    }
+
     #[test]
     fn test_sign_recipe() {
         let dir = tempfile::tempdir().unwrap();
         let recipe = dir.path().join("recipe.yaml");
         std::fs::write(&recipe, "version: \"1.0\"\nname: test\n").unwrap();
-        let sig = sign_recipe(&recipe, "test-user").unwrap();
-        assert_eq!(sig.algorithm, "blake3-hmac");
+        let sig = sign_recipe(&recipe, &key(dir.path(), "test-user")).unwrap();
+        assert_eq!(sig.algorithm, "ed25519");
         assert_eq!(sig.signer, "test-user");
-        assert_eq!(sig.blake3_hash.len(), 64);
+        assert_eq!(sig.blake3.len(), 64);
+        assert!(sig.key.starts_with("ed25519:"));
     }
 
     #[test]
@@ -24,8 +39,8 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let recipe = dir.path().join("recipe.yaml");
         std::fs::write(&recipe, "version: \"1.0\"\nname: test\n").unwrap();
-        sign_recipe(&recipe, "signer").unwrap();
-        let result = verify_recipe(&recipe).unwrap();
+        sign_recipe(&recipe, &key(dir.path(), "signer")).unwrap();
+        let result = verify_recipe(&recipe, None).unwrap();
         assert!(result.valid);
     }
 
@@ -34,19 +49,54 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let recipe = dir.path().join("recipe.yaml");
         std::fs::write(&recipe, "version: \"1.0\"\nname: test\n").unwrap();
-        sign_recipe(&recipe, "signer").unwrap();
+        sign_recipe(&recipe, &key(dir.path(), "signer")).unwrap();
         std::fs::write(&recipe, "version: \"2.0\"\nname: hacked\n").unwrap();
-        let result = verify_recipe(&recipe).unwrap();
+        let result = verify_recipe(&recipe, None).unwrap();
         assert!(!result.valid);
     }
 
+    #[test]
+    fn test_verify_forged_signature() {
+        let dir = tempfile::tempdir().unwrap();
+        let recipe = dir.path().join("recipe.yaml");
+        std::fs::write(&recipe, "name: test\n").unwrap();
+        let mut sig = sign_recipe(&recipe, &key(dir.path(), "signer")).unwrap();
+        // Claim another key without its private half: the signature no
+        // longer verifies.
+        sig.key = key(dir.path(), "victim").public_key().unwrap().to_string();
+        std::fs::write(signature_path(&recipe), sig.to_json().unwrap()).unwrap();
+        let result = verify_recipe(&recipe, None).unwrap();
+        assert!(!result.valid);
+        assert!(result.reason.contains("does not verify"), "{}", result.reason);
+    }
+
     #[test]
     fn test_verify_no_signature() {
         let dir = tempfile::tempdir().unwrap();
         let recipe = dir.path().join("recipe.yaml");
         std::fs::write(&recipe, "test").unwrap();
-        let result = verify_recipe(&recipe).unwrap();
+        let result = verify_recipe(&recipe, None).unwrap();
+        assert!(!result.valid);
+    }
+
+    #[test]
+    fn test_verify_against_trust_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let recipe = dir.path().join("recipe.yaml");
+        std::fs::write(&recipe, "name: test\n").unwrap();
+        let trusted = key(dir.path(), "ci");
+        let other = key(dir.path(), "laptop");
+        let policy = policy_for(&trusted);
+
+        sign_recipe(&recipe, &trusted).unwrap();
+        let result = verify_recipe(&recipe, Some(&policy)).unwrap();
+        assert!(result.valid, "{}", result.reason);
+        assert!(result.reason.contains("'ci'"));
+
+        sign_recipe(&recipe, &other).unwrap();
+        let result = verify_recipe(&recipe, Some(&policy)).unwrap();
         assert!(!result.valid);
+        assert!(result.reason.contains("not a trusted recipe signer"));
     }
 
     #[test]
@@ -54,7 +104,8 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let recipe = dir.path().join("recipe.yaml");
         std::fs::write(&recipe, "test content").unwrap();
-        let result = cmd_recipe_sign(&recipe, false, Some("ci"), true);
+        let key = signing_key_file(dir.path(), "ci");
+        let result = cmd_recipe_sign(&recipe, false, Some(&key), None, true);
         assert!(result.is_ok());
     }
 
@@ -63,9 +114,10 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let recipe = dir.path().join("recipe.yaml");
         std::fs::write(&recipe, "test content").unwrap();
+        let key = signing_key_file(dir.path(), "ci");
         // Sign first, then verify
-        cmd_recipe_sign(&recipe, false, Some("ci"), false).unwrap();
-        let result = cmd_recipe_sign(&recipe, true, None, true);
+        cmd_recipe_sign(&recipe, false, Some(&key), None, false).unwrap();
+        let result = cmd_recipe_sign(&recipe, true, None, None, true);
         assert!(result.is_ok());
     }
 
@@ -74,8 +126,9 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let recipe = dir.path().join("recipe.yaml");
         std::fs::write(&recipe, "test content").unwrap();
-        cmd_recipe_sign(&recipe, false, Some("ci"), false).unwrap();
-        let result = cmd_recipe_sign(&recipe, true, None, false);
+        let key = signing_key_file(dir.path(), "ci");
+        cmd_recipe_sign(&recipe, false, Some(&key), None, false).unwrap();
+        let result = cmd_recipe_sign(&recipe, true, None, None, false);
         assert!(result.is_ok());
     }
 
@@ -84,29 +137,41 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let recipe = dir.path().join("recipe.yaml");
         std::fs::write(&recipe, "original").unwrap();
-        cmd_recipe_sign(&recipe, false, Some("ci"), false).unwrap();
+        let key = signing_key_file(dir.path(), "ci");
+        cmd_recipe_sign(&recipe, false, Some(&key), None, false).unwrap();
         std::fs::write(&recipe, "tampered").unwrap();
-        let result = cmd_recipe_sign(&recipe, true, None, true);
+        let result = cmd_recipe_sign(&recipe, true, None, None, true);
         assert!(result.is_err());
     }
 
     #[test]
-    fn test_cmd_recipe_sign_default_signer() {
+    fn test_cmd_recipe_sign_verify_trust_policy_file() {
         let dir = tempfile::tempdir().unwrap();
         let recipe = dir.path().join("recipe.yaml");
-        std::fs::write(&recipe, "test").unwrap();
-        // signer=None defaults to "local"
-        let result = cmd_recipe_sign(&recipe, false, None, false);
-        assert!(result.is_ok());
+        std::fs::write(&recipe, "name: test\n").unwrap();
+        let trusted = key(dir.path(), "ci");
+        let policy_path = dir.path().join("trust.yaml");
+        std::fs::write(
+            &policy_path,
+            serde_yaml_ng::to_string(&policy_for(&trusted)).unwrap(),
+        )
+        .unwrap();
+        let laptop = signing_key_file(dir.path(), "laptop");
+        cmd_recipe_sign(&recipe, false, Some(&laptop), None, false).unwrap();
+        assert!(cmd_recipe_sign(&recipe, true, None, Some(&policy_path), false).is_err());
+        let ci = dir.path().join("ci.key").display().to_string();
+        cmd_recipe_sign(&recipe, false, Some(&ci), None, false).unwrap();
+        assert!(cmd_recipe_sign(&recipe, true, None, Some(&policy_path), false).is_ok());
     }
 
     #[test]
-    fn test_cmd_recipe_sign_text_output() {
+    fn test_cmd_recipe_sign_unknown_key() {
         let dir = tempfile::tempdir().unwrap();
         let recipe = dir.path().join("recipe.yaml");
-        std::fs::write(&recipe, "content").unwrap();
-        let result = cmd_recipe_sign(&recipe, false, Some("dev"), false);
-        assert!(result.is_ok());
+        std::fs::write(&recipe, "test").unwrap();
+        let result = cmd_recipe_sign(&recipe, false, Some("/nonexistent/k.key"), None, false);
+        assert!(result.is_err());
+        assert!(!signature_path(&recipe).exists());
     }
 
     #[test]
@@ -115,23 +180,8 @@ mod tests {
         let recipe = dir.path().join("recipe.yaml");
         std::fs::write(&recipe, "test").unwrap();
         // No sig file → valid=false, signer=""
-        let result = verify_recipe(&recipe).unwrap();
+        let result = verify_recipe(&recipe, None).unwrap();
         assert!(!result.valid);
         assert!(result.signer.is_empty());
     }
-
-    #[test]
-    fn test_recipe_signature_serde() {
-        let sig = RecipeSignature {
-            recipe_path: "/tmp/r.yaml".to_string(),
-            blake3_hash: "a".repeat(64),
-            algorithm: "blake3-hmac".to_string(),
-            signer: "test".to_string(),
-            timestamp: "2026-01-01T00:00:00Z".to_string(),
-            signature: "b".repeat(64),
-        };
-        let json = serde_json::to_string(&sig).unwrap();
-        let round: RecipeSignature = serde_json::from_str(&json).unwrap();
-        assert_eq!(round.signer, "test");
-    }
 }
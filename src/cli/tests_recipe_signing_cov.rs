@@ -1,6 +1,12 @@
 //! Coverage tests for recipe_signing.rs — sign, verify, cmd.
 
 use super::recipe_signing::*;
+use super::test_fixtures::signing_key_file;
+use crate::core::signing::{self, Signature, SigningKey};
+
+fn key(dir: &std::path::Path, name: &str) -> SigningKey {
+    signing::load_signing_key(&signing_key_file(dir, name)).unwrap()
+}
 
 // ── sign_recipe ─────────────────────────────────────────────────────
 
@@ -9,10 +15,10 @@ fn sign_creates_sig_file() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "version: 1.0\nname: test\n").unwrap();
-    let sig = sign_recipe(&recipe, "tester").unwrap();
+    let sig = sign_recipe(&recipe, &key(dir.path(), "tester")).unwrap();
     assert_eq!(sig.signer, "tester");
-    assert_eq!(sig.algorithm, "blake3-hmac");
-    assert!(!sig.blake3_hash.is_empty());
+    assert_eq!(sig.algorithm, "ed25519");
+    assert!(!sig.blake3.is_empty());
     assert!(!sig.signature.is_empty());
     assert!(recipe.with_extension("sig.json").exists());
 }
@@ -22,9 +28,10 @@ fn sign_deterministic_hash() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "content: hello").unwrap();
-    let sig1 = sign_recipe(&recipe, "signer").unwrap();
-    let sig2 = sign_recipe(&recipe, "signer").unwrap();
-    assert_eq!(sig1.blake3_hash, sig2.blake3_hash);
+    let signer = key(dir.path(), "signer");
+    let sig1 = sign_recipe(&recipe, &signer).unwrap();
+    let sig2 = sign_recipe(&recipe, &signer).unwrap();
+    assert_eq!(sig1.blake3, sig2.blake3);
     assert_eq!(sig1.signature, sig2.signature);
 }
 
@@ -33,15 +40,19 @@ fn sign_different_signer_different_sig() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "content: hello").unwrap();
-    let sig1 = sign_recipe(&recipe, "alice").unwrap();
-    let sig2 = sign_recipe(&recipe, "bob").unwrap();
-    assert_eq!(sig1.blake3_hash, sig2.blake3_hash);
+    let sig1 = sign_recipe(&recipe, &key(dir.path(), "alice")).unwrap();
+    let sig2 = sign_recipe(&recipe, &key(dir.path(), "bob")).unwrap();
+    assert_eq!(sig1.blake3, sig2.blake3);
     assert_ne!(sig1.signature, sig2.signature);
 }
 
 #[test]
 fn sign_nonexistent_file() {
-    let result = sign_recipe(std::path::Path::new("/nonexistent/recipe.yaml"), "x");
+    let dir = tempfile::tempdir().unwrap();
+    let result = sign_recipe(
+        std::path::Path::new("/nonexistent/recipe.yaml"),
+        &key(dir.path(), "x"),
+    );
     assert!(result.is_err());
 }
 
@@ -52,11 +63,11 @@ fn verify_valid_signature() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "version: 1.0\n").unwrap();
-    sign_recipe(&recipe, "tester").unwrap();
-    let result = verify_recipe(&recipe).unwrap();
+    sign_recipe(&recipe, &key(dir.path(), "tester")).unwrap();
+    let result = verify_recipe(&recipe, None).unwrap();
     assert!(result.valid);
     assert_eq!(result.signer, "tester");
-    assert!(result.reason.contains("matches"));
+    assert!(result.reason.contains("valid"));
 }
 
 #[test]
@@ -64,12 +75,12 @@ fn verify_tampered_recipe() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "version: 1.0\n").unwrap();
-    sign_recipe(&recipe, "tester").unwrap();
+    sign_recipe(&recipe, &key(dir.path(), "tester")).unwrap();
     // Tamper with the recipe
     std::fs::write(&recipe, "version: 2.0\n").unwrap();
-    let result = verify_recipe(&recipe).unwrap();
+    let result = verify_recipe(&recipe, None).unwrap();
     assert!(!result.valid);
-    assert!(result.reason.contains("mismatch"));
+    assert!(result.reason.contains("modified after signing"));
 }
 
 #[test]
@@ -77,35 +88,44 @@ fn verify_no_signature_file() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "version: 1.0\n").unwrap();
-    let result = verify_recipe(&recipe).unwrap();
+    let result = verify_recipe(&recipe, None).unwrap();
     assert!(!result.valid);
     assert!(result.reason.contains("no signature"));
 }
 
-// ── cmd_recipe_sign ─────────────────────────────────────────────────
-
 #[test]
-fn cmd_sign_text_output() {
+fn verify_rejects_other_scope() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
-    std::fs::write(&recipe, "name: test\n").unwrap();
-    assert!(cmd_recipe_sign(&recipe, false, Some("admin"), false).is_ok());
+    std::fs::write(&recipe, "version: 1.0\n").unwrap();
+    let content = std::fs::read(&recipe).unwrap();
+    let sig = key(dir.path(), "ci")
+        .sign(signing::Scope::Lock, &content)
+        .unwrap();
+    std::fs::write(signature_path(&recipe), sig.to_json().unwrap()).unwrap();
+    let result = verify_recipe(&recipe, None).unwrap();
+    assert!(!result.valid);
+    assert!(result.reason.contains("not a recipe"), "{}", result.reason);
 }
 
+// ── cmd_recipe_sign ─────────────────────────────────────────────────
+
 #[test]
-fn cmd_sign_json_output() {
+fn cmd_sign_text_output() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "name: test\n").unwrap();
-    assert!(cmd_recipe_sign(&recipe, false, Some("admin"), true).is_ok());
+    let k = signing_key_file(dir.path(), "admin");
+    assert!(cmd_recipe_sign(&recipe, false, Some(&k), None, false).is_ok());
 }
 
 #[test]
-fn cmd_sign_default_signer() {
+fn cmd_sign_json_output() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "name: test\n").unwrap();
-    assert!(cmd_recipe_sign(&recipe, false, None, false).is_ok());
+    let k = signing_key_file(dir.path(), "admin");
+    assert!(cmd_recipe_sign(&recipe, false, Some(&k), None, true).is_ok());
 }
 
 #[test]
@@ -113,8 +133,8 @@ fn cmd_verify_valid_text() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "name: test\n").unwrap();
-    sign_recipe(&recipe, "ci").unwrap();
-    assert!(cmd_recipe_sign(&recipe, true, None, false).is_ok());
+    sign_recipe(&recipe, &key(dir.path(), "ci")).unwrap();
+    assert!(cmd_recipe_sign(&recipe, true, None, None, false).is_ok());
 }
 
 #[test]
@@ -122,8 +142,8 @@ fn cmd_verify_valid_json() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "name: test\n").unwrap();
-    sign_recipe(&recipe, "ci").unwrap();
-    assert!(cmd_recipe_sign(&recipe, true, None, true).is_ok());
+    sign_recipe(&recipe, &key(dir.path(), "ci")).unwrap();
+    assert!(cmd_recipe_sign(&recipe, true, None, None, true).is_ok());
 }
 
 #[test]
@@ -131,7 +151,7 @@ fn cmd_verify_fails_no_sig() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "name: test\n").unwrap();
-    let result = cmd_recipe_sign(&recipe, true, None, false);
+    let result = cmd_recipe_sign(&recipe, true, None, None, false);
     assert!(result.is_err());
 }
 
@@ -140,26 +160,23 @@ fn cmd_verify_fails_tampered() {
     let dir = tempfile::tempdir().unwrap();
     let recipe = dir.path().join("recipe.yaml");
     std::fs::write(&recipe, "name: original\n").unwrap();
-    sign_recipe(&recipe, "admin").unwrap();
+    sign_recipe(&recipe, &key(dir.path(), "admin")).unwrap();
     std::fs::write(&recipe, "name: tampered\n").unwrap();
-    let result = cmd_recipe_sign(&recipe, true, None, false);
+    let result = cmd_recipe_sign(&recipe, true, None, None, false);
     assert!(result.is_err());
 }
 
-// ── RecipeSignature serde ───────────────────────────────────────────
+// ── Signature serde ─────────────────────────────────────────────────
 
 #[test]
 fn signature_serde_roundtrip() {
-    let sig = RecipeSignature {
-        recipe_path: "/etc/forjar/recipe.yaml".to_string(),
-        blake3_hash: "abc123".to_string(),
-        algorithm: "blake3-hmac".to_string(),
-        signer: "ci-bot".to_string(),
-        timestamp: "2026-01-01".to_string(),
-        signature: "def456".to_string(),
-    };
-    let json = serde_json::to_string(&sig).unwrap();
-    let deserialized: RecipeSignature = serde_json::from_str(&json).unwrap();
-    assert_eq!(deserialized.blake3_hash, "abc123");
+    let dir = tempfile::tempdir().unwrap();
+    let sig = key(dir.path(), "ci-bot")
+        .sign(signing::Scope::Recipe, b"name: x\n")
+        .unwrap();
+    let json = sig.to_json().unwrap();
+    assert!(json.contains("\"scope\": \"recipe\""), "{json}");
+    let deserialized = Signature::from_json(&json).unwrap();
+    assert_eq!(deserialized, sig);
     assert_eq!(deserialized.signer, "ci-bot");
 }
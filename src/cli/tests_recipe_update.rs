@@ -0,0 +1,89 @@
+//! FJ-3314 + FJ-3315: Tests for `forjar recipe update` and `forjar recipe schema`.
+
+use super::recipe_update::*;
+use crate::core::recipe::registry::{load_index, RegistryEntry};
+use std::path::Path;
+
+fn publish(dir: &Path, name: &str, version: &str) {
+    let body = format!(
+        "recipe:\n  name: {name}\n  version: \"{version}\"\nresources:\n  conf:\n    type: file\n    path: /etc/{name}.conf\n    content: \"{version}\"\n"
+    );
+    let rel = format!("{name}-{version}.yaml");
+    std::fs::create_dir_all(dir).unwrap();
+    std::fs::write(dir.join(&rel), &body).unwrap();
+    let mut index = load_index(dir).unwrap();
+    index.entries.push(RegistryEntry {
+        name: name.to_string(),
+        version: version.to_string(),
+        path: rel,
+        blake3: blake3::hash(body.as_bytes()).to_hex().to_string(),
+        description: String::new(),
+        tags: vec![],
+    });
+    std::fs::write(
+        dir.join("index.json"),
+        serde_json::to_string(&index).unwrap(),
+    )
+    .unwrap();
+}
+
+fn project(dir: &Path) -> std::path::PathBuf {
+    let file = dir.join("forjar.yaml");
+    std::fs::write(
+        &file,
+        "version: \"1.0\"\nname: app\nmachines:\n  web:\n    hostname: web\n    addr: 127.0.0.1\nresources:\n  proxy:\n    type: recipe\n    machine: web\n    recipe: \"nginx@~1.2\"\npolicy:\n  recipe_registries: [registry]\n",
+    )
+    .unwrap();
+    file
+}
+
+fn locked_version(dir: &Path) -> String {
+    let lock = std::fs::read_to_string(dir.join("forjar.inputs.lock.yaml")).unwrap();
+    let lock = crate::core::store::lockfile::parse_lockfile(&lock).unwrap();
+    lock.pins["recipe:nginx"].version.clone().unwrap()
+}
+
+#[test]
+fn update_bumps_within_constraint() {
+    let dir = tempfile::tempdir().unwrap();
+    let registry = dir.path().join("registry");
+    publish(&registry, "nginx", "1.2.0");
+    let file = project(dir.path());
+    crate::core::parser::parse_and_validate(&file).unwrap();
+    assert_eq!(locked_version(dir.path()), "1.2.0");
+
+    publish(&registry, "nginx", "1.2.5");
+    publish(&registry, "nginx", "1.3.0");
+    cmd_recipe_update(&file, &[], false).unwrap();
+    assert_eq!(locked_version(dir.path()), "1.2.5");
+    cmd_recipe_update(&file, &["nginx".to_string()], true).unwrap();
+}
+
+#[test]
+fn update_rejects_unknown_recipe() {
+    let dir = tempfile::tempdir().unwrap();
+    publish(&dir.path().join("registry"), "nginx", "1.2.0");
+    let file = project(dir.path());
+    let err = cmd_recipe_update(&file, &["redis".to_string()], false).unwrap_err();
+    assert!(err.contains("not a versioned recipe"), "{err}");
+}
+
+#[test]
+fn schema_rejects_invalid_declarations() {
+    let dir = tempfile::tempdir().unwrap();
+    let recipe = dir.path().join("web.yaml");
+    std::fs::write(
+        &recipe,
+        "recipe:\n  name: web\n  inputs:\n    port:\n      type: int\n      pattern: \"[\"\nresources: {}\n",
+    )
+    .unwrap();
+    let err = cmd_recipe_schema(&recipe).unwrap_err();
+    assert!(err.contains("invalid pattern"), "{err}");
+
+    std::fs::write(
+        &recipe,
+        "recipe:\n  name: web\n  inputs:\n    port:\n      type: int\nresources: {}\n",
+    )
+    .unwrap();
+    cmd_recipe_schema(&recipe).unwrap();
+}
@@ -2,6 +2,7 @@
 
 #![allow(unused_imports)]
 use super::remote_state::*;
+use crate::core::state::backend::{LocalBackend, StateBackend};
 use std::path::Path;
 
 #[cfg(test)]
@@ -0,0 +1,236 @@
+//! FJ-3312: Tests for `secrets rotate <key>` and `--rollback`.
+
+use super::secret_rotate::*;
+use crate::core::secret_rotation::{self, RotationRecord};
+use std::path::Path;
+
+fn config(marker: &str) -> String {
+    format!(
+        r#"version: "1.0"
+name: app
+params:
+  db_password: "{marker}"
+machines:
+  web:
+    hostname: web
+    addr: 127.0.0.1
+resources:
+  db-conf:
+    type: file
+    machine: web
+    path: /tmp/forjar-fj3312-db.conf
+    content: "password={{{{ params.db_password }}}}"
+  motd:
+    type: file
+    machine: web
+    path: /tmp/forjar-fj3312-motd
+    content: "hello"
+"#
+    )
+}
+
+fn rotation(old: &str, new: &str) -> RotationRecord {
+    RotationRecord {
+        rotated_at: "2026-01-01T00:00:00Z".to_string(),
+        key: "db_password".to_string(),
+        file: "forjar.yaml".to_string(),
+        old_marker: old.to_string(),
+        new_marker: new.to_string(),
+        old_hash: secret_rotation::fingerprint("old"),
+        new_hash: secret_rotation::fingerprint("new"),
+        resources: vec!["db-conf".to_string()],
+        rollback: false,
+    }
+}
+
+fn marker(file: &Path) -> String {
+    secret_rotation::param_marker(&std::fs::read_to_string(file).unwrap(), "db_password").unwrap()
+}
+
+#[test]
+fn rollback_restores_previous_marker() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("forjar.yaml");
+    let state = dir.path().join("state");
+    std::fs::write(&file, config("ENC[age,bmV3]")).unwrap();
+    secret_rotation::record_rotation(&state, &rotation("ENC[age,b2xk]", "ENC[age,bmV3]")).unwrap();
+
+    cmd_secrets_rotate_rollback(&file, "db_password", &state).unwrap();
+    assert_eq!(marker(&file), "ENC[age,b2xk]");
+
+    let records = secret_rotation::rotations(&state, "db_password").unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(records[1].rollback);
+    assert_eq!(records[1].new_hash, secret_rotation::fingerprint("old"));
+
+    let err = cmd_secrets_rotate_rollback(&file, "db_password", &state).unwrap_err();
+    assert!(err.contains("already rolled back"), "{err}");
+}
+
+#[test]
+fn rollback_refuses_edited_marker() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("forjar.yaml");
+    let state = dir.path().join("state");
+    std::fs::write(&file, config("ENC[age,ZWRpdGVk]")).unwrap();
+    secret_rotation::record_rotation(&state, &rotation("ENC[age,b2xk]", "ENC[age,bmV3]")).unwrap();
+
+    let err = cmd_secrets_rotate_rollback(&file, "db_password", &state).unwrap_err();
+    assert!(err.contains("changed since"), "{err}");
+    assert_eq!(marker(&file), "ENC[age,ZWRpdGVk]");
+}
+
+#[test]
+fn rollback_without_history_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("forjar.yaml");
+    std::fs::write(&file, config("ENC[age,b2xk]")).unwrap();
+    let err =
+        cmd_secrets_rotate_rollback(&file, "db_password", &dir.path().join("state")).unwrap_err();
+    assert!(err.contains("no rotation"), "{err}");
+}
+
+#[test]
+fn rollout_plan_stages_only_consumers() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("forjar.yaml");
+    std::fs::write(&file, config("ENC[age,b2xk]")).unwrap();
+    let state = dir.path().join("state");
+
+    let out = rollout_plan(&file, &state, &["db-conf".to_string()]).unwrap();
+    assert_eq!(
+        out,
+        format!(
+            "Staged rollout (1 resource(s)):\n  stage 1: web\n    create  db-conf  forjar apply -f {} -m web -r db-conf\n",
+            file.display()
+        )
+    );
+    assert!(!out.contains("motd"));
+
+    let out = rollout_plan(&file, &state, &[]).unwrap();
+    assert!(out.contains("nothing to redeploy"), "{out}");
+}
+
+#[test]
+fn rotation_log_goes_through_state_backend() {
+    use crate::core::state::backend::{unregister_backend, LocalBackend, StateBackend};
+
+    let dir = tempfile::tempdir().unwrap();
+    let remote = dir.path().join("remote");
+    let file = dir.path().join("forjar.yaml");
+    let state = dir.path().join("state");
+    let backend = format!(
+        "policy:\n  backend:\n    type: local\n    path: {}\n",
+        remote.display()
+    );
+    std::fs::write(&file, config("ENC[age,bmV3]") + &backend).unwrap();
+    let line = serde_json::to_string(&rotation("ENC[age,b2xk]", "ENC[age,bmV3]")).unwrap();
+    let store = LocalBackend::new(&remote);
+    store
+        .put("secret-rotations.jsonl", format!("{line}\n").as_bytes())
+        .unwrap();
+
+    // The history is read from the store and the rollback recorded there.
+    cmd_secrets_rotate_rollback(&file, "db_password", &state).unwrap();
+    unregister_backend(&state);
+    assert_eq!(marker(&file), "ENC[age,b2xk]");
+    let log = String::from_utf8(store.get("secret-rotations.jsonl").unwrap()).unwrap();
+    assert_eq!(log.lines().count(), 2);
+    assert!(
+        log.lines().nth(1).unwrap().contains("\"rollback\":true"),
+        "{log}"
+    );
+}
+
+#[cfg(not(feature = "encryption"))]
+#[test]
+fn rotate_key_requires_encryption_feature() {
+    let err = cmd_secrets_rotate_key(
+        Path::new("forjar.yaml"),
+        "db_password",
+        None,
+        &[],
+        None,
+        Path::new("state"),
+    )
+    .unwrap_err();
+    assert!(err.contains("--features encryption"), "{err}");
+}
+
+#[cfg(feature = "encryption")]
+mod with_age {
+    use super::*;
+    use crate::core::secret_audit::{read_audit, SecretEventType};
+    use crate::core::secrets::{self, generate_identity, identity_to_recipient};
+    use age::secrecy::ExposeSecret;
+
+    #[test]
+    fn rotate_key_rewrites_marker_and_audits_fingerprints() {
+        let dir = tempfile::tempdir().unwrap();
+        let identity = generate_identity();
+        let key_path = dir.path().join("id.key");
+        std::fs::write(&key_path, identity.to_string().expose_secret()).unwrap();
+        let recipient = identity_to_recipient(&identity);
+        let old_marker = secrets::encrypt("old-pass", &[recipient.as_str()]).unwrap();
+        let file = dir.path().join("forjar.yaml");
+        let state = dir.path().join("state");
+        std::fs::write(&file, config(&old_marker)).unwrap();
+        let value_file = dir.path().join("new.txt");
+        std::fs::write(&value_file, "new-pass\n").unwrap();
+
+        cmd_secrets_rotate_key(
+            &file,
+            "db_password",
+            Some(&key_path),
+            std::slice::from_ref(&recipient),
+            Some(&value_file),
+            &state,
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(&file).unwrap();
+        assert!(!content.contains(&old_marker));
+        assert!(!content.contains("new-pass"));
+        let new_marker = marker(&file);
+        assert_eq!(
+            secrets::decrypt_marker(&new_marker, std::slice::from_ref(&identity)).unwrap(),
+            "new-pass"
+        );
+
+        let records = secret_rotation::rotations(&state, "db_password").unwrap();
+        assert_eq!(records[0].resources, vec!["db-conf".to_string()]);
+        let events = read_audit(&state).unwrap();
+        assert_eq!(events[0].event_type, SecretEventType::Rotate);
+        assert_eq!(
+            events[0].value_hash,
+            secret_rotation::fingerprint("new-pass")
+        );
+
+        cmd_secrets_rotate_rollback(&file, "db_password", &state).unwrap();
+        assert_eq!(marker(&file), old_marker);
+    }
+
+    #[test]
+    fn rotate_key_generates_value_when_none_given() {
+        let dir = tempfile::tempdir().unwrap();
+        let identity = generate_identity();
+        let key_path = dir.path().join("id.key");
+        std::fs::write(&key_path, identity.to_string().expose_secret()).unwrap();
+        let recipient = identity_to_recipient(&identity);
+        let old_marker = secrets::encrypt("old-pass", &[recipient.as_str()]).unwrap();
+        let file = dir.path().join("forjar.yaml");
+        std::fs::write(&file, config(&old_marker)).unwrap();
+
+        cmd_secrets_rotate_key(
+            &file,
+            "db_password",
+            Some(&key_path),
+            std::slice::from_ref(&recipient),
+            None,
+            &dir.path().join("state"),
+        )
+        .unwrap();
+        let value = secrets::decrypt_marker(&marker(&file), &[identity]).unwrap();
+        assert_eq!(value.len(), 43);
+    }
+}
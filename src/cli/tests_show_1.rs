@@ -116,6 +116,7 @@ resources:
             check: false,
             report: false,
             force_unlock: false,
+            lock_reason: None,
             output: Some("events".to_string()),
             progress: false,
             retry: 0,
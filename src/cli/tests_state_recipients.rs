@@ -0,0 +1,308 @@
+//! FJ-3310: Tests for recipient-based state-encrypt/decrypt/rekey.
+
+use super::state_recipients::*;
+use super::test_fixtures::make_state_dir_with_lock;
+use crate::core::types::ResourceStatus;
+
+const ALICE: &str = "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p";
+const BOB: &str = "age1lggyhqrw2nlhcxprm67z43rta597azn8gknawjehu9d9dl0jq3yqqvfafg";
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn rekey_plan_add_and_remove() {
+    let plan = RekeyPlan {
+        add: strings(&[BOB]),
+        remove: strings(&[ALICE]),
+        ..Default::default()
+    };
+    assert_eq!(plan.apply(&strings(&[ALICE])).unwrap(), strings(&[BOB]));
+}
+
+#[test]
+fn rekey_plan_base_replaces_current() {
+    let plan = RekeyPlan {
+        base: Some(strings(&[BOB])),
+        ..Default::default()
+    };
+    assert_eq!(plan.apply(&strings(&[ALICE])).unwrap(), strings(&[BOB]));
+}
+
+#[test]
+fn rekey_plan_refuses_empty_set() {
+    let plan = RekeyPlan {
+        remove: strings(&[ALICE]),
+        ..Default::default()
+    };
+    let err = plan.apply(&strings(&[ALICE])).unwrap_err();
+    assert!(err.contains("every recipient"), "{err}");
+}
+
+#[test]
+fn rekey_plan_rejects_invalid_recipient() {
+    let plan = RekeyPlan {
+        add: strings(&["age1bogus"]),
+        ..Default::default()
+    };
+    assert!(plan.apply(&strings(&[ALICE])).is_err());
+}
+
+#[test]
+fn encrypt_requires_recipients() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = cmd_state_encrypt_recipients(dir.path(), &[], false).unwrap_err();
+    assert!(err.contains("no recipients"), "{err}");
+    assert!(cmd_state_encrypt_recipients(dir.path(), &strings(&["bogus"]), true).is_err());
+}
+
+#[test]
+fn configured_reads_policy_block() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("forjar.yaml");
+    std::fs::write(
+        &file,
+        format!(
+            "version: \"1.0\"\nname: enc\nmachines: {{}}\nresources: {{}}\npolicy:\n  state_encryption:\n    recipients: [{ALICE}]\n"
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        configured(&file, &dir.path().join("state")).unwrap(),
+        strings(&[ALICE])
+    );
+}
+
+#[test]
+fn plaintext_state_has_no_recipient_state() {
+    let dir = tempfile::tempdir().unwrap();
+    make_state_dir_with_lock(
+        dir.path(),
+        "web",
+        vec![("f", "blake3:1", ResourceStatus::Converged)],
+    );
+    assert!(!has_recipient_state(dir.path()));
+}
+
+#[cfg(feature = "encryption")]
+mod with_age {
+    use super::*;
+    use crate::core::secrets::{generate_identity, identity_to_recipient};
+    use crate::core::state_recipients::is_recipient_encrypted;
+    use age::secrecy::ExposeSecret;
+    use std::path::{Path, PathBuf};
+
+    fn keypair(dir: &Path, name: &str) -> (String, PathBuf) {
+        let identity = generate_identity();
+        let path = dir.join(format!("{name}.key"));
+        std::fs::write(&path, identity.to_string().expose_secret()).unwrap();
+        (identity_to_recipient(&identity), path)
+    }
+
+    fn state_with_events(dir: &Path) -> PathBuf {
+        make_state_dir_with_lock(
+            dir,
+            "web",
+            vec![("f", "blake3:1", ResourceStatus::Converged)],
+        );
+        std::fs::write(dir.join("web/events.jsonl"), "{\"event\":\"a\"}\n").unwrap();
+        dir.join("web/state.lock.yaml")
+    }
+
+    #[test]
+    fn encrypt_then_decrypt_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys = tempfile::tempdir().unwrap();
+        let (recipient, key) = keypair(keys.path(), "ops");
+        let lock = state_with_events(dir.path());
+        let original = std::fs::read_to_string(&lock).unwrap();
+
+        cmd_state_encrypt_recipients(dir.path(), &[recipient], false).unwrap();
+        assert!(is_recipient_encrypted(&lock));
+        let events = std::fs::read_to_string(dir.path().join("web/events.jsonl")).unwrap();
+        assert!(events.starts_with("ENC[age,"), "{events}");
+        assert!(has_recipient_state(dir.path()));
+
+        cmd_state_decrypt_recipients(dir.path(), Some(&key), true).unwrap();
+        assert_eq!(std::fs::read_to_string(&lock).unwrap(), original);
+        let events = std::fs::read_to_string(dir.path().join("web/events.jsonl")).unwrap();
+        assert_eq!(events, "{\"event\":\"a\"}\n");
+    }
+
+    #[test]
+    fn decrypt_with_wrong_identity_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys = tempfile::tempdir().unwrap();
+        let (recipient, _) = keypair(keys.path(), "ops");
+        let (_, other) = keypair(keys.path(), "other");
+        let lock = state_with_events(dir.path());
+
+        cmd_state_encrypt_recipients(dir.path(), &[recipient], true).unwrap();
+        assert!(cmd_state_decrypt_recipients(dir.path(), Some(&other), true).is_err());
+        assert!(is_recipient_encrypted(&lock));
+    }
+
+    #[test]
+    fn rekey_rotates_out_removed_recipient() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys = tempfile::tempdir().unwrap();
+        let (alice, alice_key) = keypair(keys.path(), "alice");
+        let (bob, bob_key) = keypair(keys.path(), "bob");
+        state_with_events(dir.path());
+        cmd_state_encrypt_recipients(dir.path(), std::slice::from_ref(&alice), true).unwrap();
+
+        let plan = RekeyPlan {
+            add: vec![bob],
+            remove: vec![alice],
+            ..Default::default()
+        };
+        cmd_state_rekey_recipients(dir.path(), &plan, Some(&alice_key), false).unwrap();
+
+        assert!(cmd_state_decrypt_recipients(dir.path(), Some(&alice_key), true).is_err());
+        cmd_state_decrypt_recipients(dir.path(), Some(&bob_key), true).unwrap();
+        assert!(!has_recipient_state(dir.path()));
+    }
+
+    #[test]
+    fn rekey_keeps_integrity_sidecar_current() {
+        use crate::core::state::integrity::{has_errors, verify_state_integrity, IntegrityResult};
+        let dir = tempfile::tempdir().unwrap();
+        let keys = tempfile::tempdir().unwrap();
+        let (alice, alice_key) = keypair(keys.path(), "alice");
+        let (bob, bob_key) = keypair(keys.path(), "bob");
+        state_with_events(dir.path());
+        let verified = |dir: &Path| {
+            let issues = verify_state_integrity(dir);
+            assert!(!has_errors(&issues), "{issues:?}");
+            assert!(matches!(issues[..], [IntegrityResult::Ok]), "{issues:?}");
+        };
+
+        cmd_state_encrypt_recipients(dir.path(), std::slice::from_ref(&alice), true).unwrap();
+        verified(dir.path());
+        let plan = RekeyPlan {
+            add: vec![bob],
+            remove: vec![alice],
+            ..Default::default()
+        };
+        cmd_state_rekey_recipients(dir.path(), &plan, Some(&alice_key), true).unwrap();
+        verified(dir.path());
+        cmd_state_decrypt_recipients(dir.path(), Some(&bob_key), true).unwrap();
+        verified(dir.path());
+    }
+
+    #[test]
+    fn recipient_writes_reach_the_registered_backend() {
+        use crate::core::state::backend::{
+            register_backend, unregister_backend, LocalBackend, MirroredBackend,
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let keys = tempfile::tempdir().unwrap();
+        let (alice, alice_key) = keypair(keys.path(), "alice");
+        let (bob, _) = keypair(keys.path(), "bob");
+        let state_dir = dir.path().join("state");
+        let remote = dir.path().join("remote");
+        state_with_events(&state_dir);
+        register_backend(
+            &state_dir,
+            std::sync::Arc::new(MirroredBackend::new(
+                Box::new(LocalBackend::new(&remote)),
+                &state_dir,
+            )),
+        );
+
+        let encrypted = cmd_state_encrypt_recipients(&state_dir, &[alice], true);
+        let plan = RekeyPlan {
+            add: vec![bob],
+            ..Default::default()
+        };
+        let rekeyed = cmd_state_rekey_recipients(&state_dir, &plan, Some(&alice_key), true);
+        unregister_backend(&state_dir);
+
+        encrypted.unwrap();
+        rekeyed.unwrap();
+        assert!(is_recipient_encrypted(&remote.join("web/state.lock.yaml")));
+        assert!(remote.join("web/state.lock.yaml.enc.meta.json").exists());
+        assert!(remote.join("web/state.lock.yaml.b3").exists());
+        let events = std::fs::read_to_string(remote.join("web/events.jsonl")).unwrap();
+        assert!(events.starts_with("ENC[age,"), "{events}");
+    }
+
+    fn apply(config: &Path, state_dir: &Path) -> Result<(), String> {
+        crate::cli::apply::cmd_apply(
+            config,
+            state_dir,
+            None,
+            None,
+            None,
+            None,
+            false,
+            false,
+            false,
+            &[],
+            false,
+            None,
+            false,
+            false,
+            None,
+            None,
+            false,
+            false,
+            None,
+            false,
+            false,
+            0,
+            true,
+            false,
+            None,
+            false,
+            None,
+            None,
+            None,
+            false,
+            None,
+            false,
+            None,
+            false,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn reapply_passes_integrity_check_on_encrypted_locks() {
+        let dir = tempfile::tempdir().unwrap();
+        let (recipient, key) = keypair(dir.path(), "ops");
+        let config = dir.path().join("forjar.yaml");
+        let state_dir = dir.path().join("state");
+        std::fs::write(
+            &config,
+            format!(
+                "version: \"1.0\"\nname: enc\nmachines:\n  local:\n    hostname: localhost\n    addr: 127.0.0.1\nresources:\n  motd:\n    type: file\n    machine: local\n    path: {}\n    content: hello\npolicy:\n  state_encryption:\n    recipients: [{recipient}]\n",
+                dir.path().join("motd").display()
+            ),
+        )
+        .unwrap();
+
+        crate::core::state_recipients::set_identity_file(Some(&key));
+        let first = apply(&config, &state_dir);
+        let second = apply(&config, &state_dir);
+        crate::core::state_recipients::set_identity_file(None);
+        crate::core::state_recipients::unregister_recipients(&state_dir);
+
+        first.unwrap();
+        second.unwrap();
+        assert!(is_recipient_encrypted(
+            &state_dir.join("local/state.lock.yaml")
+        ));
+        // The check apply runs first, and refuses on without --yes.
+        let issues = crate::core::state::integrity::verify_state_integrity(&state_dir);
+        assert!(
+            !crate::core::state::integrity::has_errors(&issues),
+            "{issues:?}"
+        );
+        assert!(issues
+            .iter()
+            .any(|i| matches!(i, crate::core::state::integrity::IntegrityResult::Ok)));
+    }
+}
@@ -64,7 +64,7 @@ mod tests {
         let dir = TempDir::new().unwrap();
         let far = write_far(&dir);
 
-        let result = cmd_archive_verify(&far, false);
+        let result = cmd_archive_verify(&far, None, false);
         assert!(result.is_ok());
     }
 
@@ -73,7 +73,7 @@ mod tests {
         let dir = TempDir::new().unwrap();
         let far = write_far(&dir);
 
-        let result = cmd_archive_verify(&far, true);
+        let result = cmd_archive_verify(&far, None, true);
         assert!(result.is_ok());
     }
 
@@ -93,7 +93,7 @@ mod tests {
         let bad = dir.path().join("bad.far");
         fs::write(&bad, "not a FAR file").unwrap();
 
-        let result = cmd_archive_verify(&bad, false);
+        let result = cmd_archive_verify(&bad, None, false);
         assert!(result.is_err());
     }
 
@@ -104,7 +104,7 @@ mod tests {
         let store = dir.path().join("store");
         fs::create_dir_all(&store).unwrap();
 
-        let result = cmd_archive_unpack(&far, &store);
+        let result = cmd_archive_unpack(&far, &store, None);
         assert!(result.is_ok());
 
         let entry_dir = store.join("abcd1234");
@@ -118,7 +118,7 @@ mod tests {
         let store = dir.path().join("store");
         fs::create_dir_all(store.join("abcd1234")).unwrap();
 
-        let result = cmd_archive_unpack(&far, &store);
+        let result = cmd_archive_unpack(&far, &store, None);
         assert!(result.is_ok()); // should succeed silently
     }
 
@@ -140,4 +140,58 @@ mod tests {
         let result = cmd_archive_inspect(&missing, false);
         assert!(result.is_err());
     }
+
+    fn trust_policy(dir: &TempDir, key: &str) -> std::path::PathBuf {
+        let public = crate::core::signing::load_public_key(key).unwrap();
+        let path = dir.path().join("trust.yaml");
+        fs::write(
+            &path,
+            format!("archives:\n  require_signatures: true\n  signers:\n    - name: ci\n      key: {public}\n"),
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_archive_sign_and_verify() {
+        let dir = TempDir::new().unwrap();
+        let far = write_far(&dir);
+        let key = crate::cli::test_fixtures::signing_key_file(dir.path(), "ci");
+        let policy = trust_policy(&dir, &key);
+        assert!(cmd_archive_verify(&far, Some(&policy), false).is_err());
+
+        cmd_archive_sign(&far, Some(&key)).unwrap();
+        assert!(cmd_archive_verify(&far, None, false).is_ok());
+        assert!(cmd_archive_verify(&far, Some(&policy), true).is_ok());
+        // Signing again replaces the signature.
+        cmd_archive_sign(&far, Some(&key)).unwrap();
+        assert!(cmd_archive_verify(&far, Some(&policy), false).is_ok());
+
+        // Any change to the signed bytes breaks the signature.
+        let mut data = fs::read(&far).unwrap();
+        data[20] ^= 0xff;
+        fs::write(&far, data).unwrap();
+        assert!(cmd_archive_verify(&far, None, false).is_err());
+    }
+
+    #[test]
+    fn test_archive_unpack_enforces_trust_policy() {
+        let dir = TempDir::new().unwrap();
+        let far = write_far(&dir);
+        let store = dir.path().join("store");
+        let ci = crate::cli::test_fixtures::signing_key_file(dir.path(), "ci");
+        let other = crate::cli::test_fixtures::signing_key_file(dir.path(), "other");
+        let policy = trust_policy(&dir, &ci);
+
+        let err = cmd_archive_unpack(&far, &store, Some(&policy)).unwrap_err();
+        assert!(err.contains("unsigned"), "{err}");
+        cmd_archive_sign(&far, Some(&other)).unwrap();
+        let err = cmd_archive_unpack(&far, &store, Some(&policy)).unwrap_err();
+        assert!(err.contains("does not list"), "{err}");
+        assert!(!store.join("abcd1234").exists());
+
+        cmd_archive_sign(&far, Some(&ci)).unwrap();
+        cmd_archive_unpack(&far, &store, Some(&policy)).unwrap();
+        assert!(store.join("abcd1234").join("meta.yaml").exists());
+    }
 }
@@ -34,6 +34,36 @@ pub fn check_script(resource: &Resource) -> Result<String, String> {
         ResourceType::Image => Ok(resources::file::check_script(resource)),
         ResourceType::Build => Ok(resources::build::check_script(resource)),
         ResourceType::GithubRelease => Ok(resources::github_release::check_script(resource)),
+        ResourceType::DockerNetwork => Ok(resources::docker_network::check_script(resource)),
+        ResourceType::DockerVolume => Ok(resources::docker_volume::check_script(resource)),
+        ResourceType::ContainerStack => Ok(resources::container_stack::check_script(resource)),
+        ResourceType::WaitFor => Ok(resources::wait_for::check_script(resource)),
+        ResourceType::Exec => Ok(resources::exec::check_script(resource)),
+        ResourceType::Swap => Ok(resources::swap::check_script(resource)),
+        ResourceType::Partition => Ok(resources::partition::check_script(resource)),
+        ResourceType::LvmVg => Ok(resources::lvm_vg::check_script(resource)),
+        ResourceType::LvmLv => Ok(resources::lvm_lv::check_script(resource)),
+        ResourceType::Filesystem => Ok(resources::filesystem::check_script(resource)),
+        ResourceType::WireguardInterface => {
+            Ok(resources::wireguard_interface::check_script(resource))
+        }
+        ResourceType::WireguardPeer => Ok(resources::wireguard_peer::check_script(resource)),
+        ResourceType::NetworkInterface => Ok(resources::network_interface::check_script(resource)),
+        ResourceType::Certificate => Ok(resources::certificate::check_script(resource)),
+        ResourceType::System => Ok(resources::system::check_script(resource)),
+        ResourceType::ZfsPool => Ok(resources::zfs_pool::check_script(resource)),
+        ResourceType::ZfsDataset => Ok(resources::zfs_dataset::check_script(resource)),
+        ResourceType::ZfsSnapshotPolicy => {
+            Ok(resources::zfs_snapshot_policy::check_script(resource))
+        }
+        ResourceType::BinaryRelease => Ok(resources::binary_release::check_script(resource)),
+        ResourceType::PgRole => Ok(resources::pg_role::check_script(resource)),
+        ResourceType::PgDatabase => Ok(resources::pg_database::check_script(resource)),
+        ResourceType::PgExtension => Ok(resources::pg_extension::check_script(resource)),
+        ResourceType::ApparmorProfile => Ok(resources::apparmor_profile::check_script(resource)),
+        ResourceType::SelinuxBoolean => Ok(resources::selinux_boolean::check_script(resource)),
+        ResourceType::SelinuxFcontext => Ok(resources::selinux_fcontext::check_script(resource)),
+        ResourceType::SelinuxMode => Ok(resources::selinux_mode::check_script(resource)),
         ResourceType::Recipe => {
             Err("codegen not implemented for recipe (expand first)".to_string())
         }
@@ -65,6 +95,36 @@ pub fn apply_script(resource: &Resource) -> Result<String, String> {
         ResourceType::Image => Ok(resources::file::apply_script(resource)),
         ResourceType::Build => Ok(resources::build::apply_script(resource)),
         ResourceType::GithubRelease => Ok(resources::github_release::apply_script(resource)),
+        ResourceType::DockerNetwork => Ok(resources::docker_network::apply_script(resource)),
+        ResourceType::DockerVolume => Ok(resources::docker_volume::apply_script(resource)),
+        ResourceType::ContainerStack => Ok(resources::container_stack::apply_script(resource)),
+        ResourceType::WaitFor => Ok(resources::wait_for::apply_script(resource)),
+        ResourceType::Exec => Ok(resources::exec::apply_script(resource)),
+        ResourceType::Swap => Ok(resources::swap::apply_script(resource)),
+        ResourceType::Partition => Ok(resources::partition::apply_script(resource)),
+        ResourceType::LvmVg => Ok(resources::lvm_vg::apply_script(resource)),
+        ResourceType::LvmLv => Ok(resources::lvm_lv::apply_script(resource)),
+        ResourceType::Filesystem => Ok(resources::filesystem::apply_script(resource)),
+        ResourceType::WireguardInterface => {
+            Ok(resources::wireguard_interface::apply_script(resource))
+        }
+        ResourceType::WireguardPeer => Ok(resources::wireguard_peer::apply_script(resource)),
+        ResourceType::NetworkInterface => Ok(resources::network_interface::apply_script(resource)),
+        ResourceType::Certificate => Ok(resources::certificate::apply_script(resource)),
+        ResourceType::System => Ok(resources::system::apply_script(resource)),
+        ResourceType::ZfsPool => Ok(resources::zfs_pool::apply_script(resource)),
+        ResourceType::ZfsDataset => Ok(resources::zfs_dataset::apply_script(resource)),
+        ResourceType::ZfsSnapshotPolicy => {
+            Ok(resources::zfs_snapshot_policy::apply_script(resource))
+        }
+        ResourceType::BinaryRelease => Ok(resources::binary_release::apply_script(resource)),
+        ResourceType::PgRole => Ok(resources::pg_role::apply_script(resource)),
+        ResourceType::PgDatabase => Ok(resources::pg_database::apply_script(resource)),
+        ResourceType::PgExtension => Ok(resources::pg_extension::apply_script(resource)),
+        ResourceType::ApparmorProfile => Ok(resources::apparmor_profile::apply_script(resource)),
+        ResourceType::SelinuxBoolean => Ok(resources::selinux_boolean::apply_script(resource)),
+        ResourceType::SelinuxFcontext => Ok(resources::selinux_fcontext::apply_script(resource)),
+        ResourceType::SelinuxMode => Ok(resources::selinux_mode::apply_script(resource)),
         ResourceType::Recipe => {
             Err("codegen not implemented for recipe (expand first)".to_string())
         }
@@ -108,6 +168,46 @@ pub fn state_query_script(resource: &Resource) -> Result<String, String> {
         ResourceType::Image => Ok(resources::file::state_query_script(resource)),
         ResourceType::Build => Ok(resources::build::state_query_script(resource)),
         ResourceType::GithubRelease => Ok(resources::github_release::state_query_script(resource)),
+        ResourceType::DockerNetwork => Ok(resources::docker_network::state_query_script(resource)),
+        ResourceType::DockerVolume => Ok(resources::docker_volume::state_query_script(resource)),
+        ResourceType::ContainerStack => {
+            Ok(resources::container_stack::state_query_script(resource))
+        }
+        ResourceType::WaitFor => Ok(resources::wait_for::state_query_script(resource)),
+        ResourceType::Exec => Ok(resources::exec::state_query_script(resource)),
+        ResourceType::Swap => Ok(resources::swap::state_query_script(resource)),
+        ResourceType::Partition => Ok(resources::partition::state_query_script(resource)),
+        ResourceType::LvmVg => Ok(resources::lvm_vg::state_query_script(resource)),
+        ResourceType::LvmLv => Ok(resources::lvm_lv::state_query_script(resource)),
+        ResourceType::Filesystem => Ok(resources::filesystem::state_query_script(resource)),
+        ResourceType::WireguardInterface => {
+            Ok(resources::wireguard_interface::state_query_script(resource))
+        }
+        ResourceType::WireguardPeer => Ok(resources::wireguard_peer::state_query_script(resource)),
+        ResourceType::NetworkInterface => {
+            Ok(resources::network_interface::state_query_script(resource))
+        }
+        ResourceType::Certificate => Ok(resources::certificate::state_query_script(resource)),
+        ResourceType::System => Ok(resources::system::state_query_script(resource)),
+        ResourceType::ZfsPool => Ok(resources::zfs_pool::state_query_script(resource)),
+        ResourceType::ZfsDataset => Ok(resources::zfs_dataset::state_query_script(resource)),
+        ResourceType::ZfsSnapshotPolicy => {
+            Ok(resources::zfs_snapshot_policy::state_query_script(resource))
+        }
+        ResourceType::BinaryRelease => Ok(resources::binary_release::state_query_script(resource)),
+        ResourceType::PgRole => Ok(resources::pg_role::state_query_script(resource)),
+        ResourceType::PgDatabase => Ok(resources::pg_database::state_query_script(resource)),
+        ResourceType::PgExtension => Ok(resources::pg_extension::state_query_script(resource)),
+        ResourceType::ApparmorProfile => {
+            Ok(resources::apparmor_profile::state_query_script(resource))
+        }
+        ResourceType::SelinuxBoolean => {
+            Ok(resources::selinux_boolean::state_query_script(resource))
+        }
+        ResourceType::SelinuxFcontext => {
+            Ok(resources::selinux_fcontext::state_query_script(resource))
+        }
+        ResourceType::SelinuxMode => Ok(resources::selinux_mode::state_query_script(resource)),
         ResourceType::Recipe => {
             Err("codegen not implemented for recipe (expand first)".to_string())
         }
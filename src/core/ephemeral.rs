@@ -126,7 +126,7 @@ pub fn substitute_ephemerals(template: &str, resolved: &[ResolvedEphemeral]) ->
 }
 
 /// Compute BLAKE3 hash of a value, returning hex string.
-fn blake3_hash(value: &str) -> String {
+pub fn blake3_hash(value: &str) -> String {
     let hash = blake3::hash(value.as_bytes());
     hash.to_hex().to_string()
 }
@@ -13,6 +13,10 @@ pub(crate) fn apply_and_record_outcome(
     machine_name: &str,
     converged_resources: &HashSet<String>,
 ) -> Result<ResourceOutcome, String> {
+    // FJ-266: Never converge a resource without holding the apply lock.
+    if !cfg.dry_run {
+        state::lease::check_lease(cfg.state_dir)?;
+    }
     let resource_start = Instant::now();
 
     // FJ-283: Retry with exponential backoff
@@ -278,6 +282,103 @@ pub(crate) fn copia_apply_file(
     }
 }
 
+/// Two-phase apply for certificates signed by forjar (`issuer: local` or `ca`).
+/// Phase 1: the apply script keeps a current certificate or prints a CSR.
+/// Phase 2: sign the CSR on the control machine and deploy the certificate.
+pub(crate) fn certificate_apply(
+    machine: &Machine,
+    resource: &Resource,
+    state_dir: &std::path::Path,
+    timeout_secs: Option<u64>,
+    ssh_retries: u32,
+) -> Result<transport::ExecOutput, String> {
+    let script = codegen::apply_script(resource)?;
+    let phase1 = transport::exec_script_retry(machine, &script, timeout_secs, ssh_retries)?;
+    let csr = match crate::resources::certificate::extract_csr(&phase1.stdout) {
+        Some(csr) if phase1.success() => csr,
+        _ => return Ok(phase1),
+    };
+
+    let signed = pki::signing_ca(resource, state_dir)
+        .and_then(|ca| Ok((pki::sign_csr(&ca, csr, resource)?, pki::ca_pem(&ca)?)));
+    match signed {
+        Ok((cert, chain)) => {
+            let script = crate::resources::certificate::install_script(resource, &cert, &chain);
+            transport::exec_script_timeout(machine, &script, timeout_secs)
+        }
+        Err(e) => Ok(transport::ExecOutput {
+            exit_code: 1,
+            stdout: String::new(),
+            stderr: format!("certificate signing failed: {e}"),
+        }),
+    }
+}
+
+/// True for model resources installed from an `hf://` source.
+pub(crate) fn is_hf_model_apply(resource: &Resource) -> bool {
+    resource.resource_type == crate::core::types::ResourceType::Model
+        && resource.state.as_deref() != Some("absent")
+        && crate::resources::model_hf::is_hf(resource)
+}
+
+/// Apply for `hf://` model sources: resolve the revision, file list and
+/// `config.json` on the control machine, then download the pinned snapshot
+/// on the target.
+pub(crate) fn hf_model_apply(
+    machine: &Machine,
+    resource: &Resource,
+    timeout_secs: Option<u64>,
+    ssh_retries: u32,
+) -> Result<transport::ExecOutput, String> {
+    use crate::core::store::hf_hub;
+    let source = resource.source.as_deref().unwrap_or("");
+    let snapshot = hf_hub::parse_hf_source(source)
+        .and_then(|src| hf_hub::resolve(&src, &hf_hub::endpoint_from_env()));
+    match snapshot {
+        Ok(snapshot) => {
+            let script = crate::resources::model_hf::apply_script(resource, &snapshot);
+            transport::exec_script_retry(machine, &script, timeout_secs, ssh_retries)
+        }
+        Err(e) => Ok(transport::ExecOutput {
+            exit_code: 1,
+            stdout: String::new(),
+            stderr: format!("Hugging Face Hub resolution failed: {e}"),
+        }),
+    }
+}
+
+/// True for binary_release (and github_release) resources being installed
+/// (not removed).
+pub(crate) fn is_binary_release_apply(resource: &Resource) -> bool {
+    use crate::core::types::ResourceType;
+    matches!(
+        resource.resource_type,
+        ResourceType::BinaryRelease | ResourceType::GithubRelease
+    ) && resource.state.as_deref() != Some("absent")
+}
+
+/// Apply for binary releases: pick the release, asset and checksum on the
+/// control machine, then download, verify and swap in the binary on the
+/// target.
+pub(crate) fn binary_release_apply(
+    machine: &Machine,
+    resource: &Resource,
+    timeout_secs: Option<u64>,
+    ssh_retries: u32,
+) -> Result<transport::ExecOutput, String> {
+    match crate::core::release_source::resolve(resource) {
+        Ok(release) => {
+            let script = crate::resources::binary_release::install_script(resource, &release);
+            transport::exec_script_retry(machine, &script, timeout_secs, ssh_retries)
+        }
+        Err(e) => Ok(transport::ExecOutput {
+            exit_code: 1,
+            stdout: String::new(),
+            stderr: format!("release resolution failed: {e}"),
+        }),
+    }
+}
+
 /// Log a tripwire event if tripwire is enabled.
 pub(crate) fn log_tripwire(
     state_dir: &std::path::Path,
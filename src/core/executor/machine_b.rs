@@ -230,12 +230,13 @@ fn prepare_wave_resources(
             );
         }
 
-        let resolved = resolver::resolve_resource_templates_with_secrets(
+        let mut resolved = resolver::resolve_resource_templates_with_secrets(
             resource,
             &cfg.config.params,
             &cfg.config.machines,
             &cfg.config.secrets,
         )?;
+        resolver::apply_machine_defaults(&mut resolved, machine);
         let use_copia = resolved.resource_type == ResourceType::File
             && resolved
                 .source
@@ -23,6 +23,20 @@ pub(super) fn execute_wave_io(
                     }
                     let output = if prep.use_copia {
                         copia_apply_file(machine, &prep.resolved, cfg.timeout_secs)
+                    } else if prep.resolved.resource_type == ResourceType::Certificate
+                        && crate::resources::certificate::is_ca_signed(&prep.resolved)
+                    {
+                        certificate_apply(
+                            machine,
+                            &prep.resolved,
+                            cfg.state_dir,
+                            cfg.timeout_secs,
+                            ssh_retries,
+                        )
+                    } else if is_hf_model_apply(&prep.resolved) {
+                        hf_model_apply(machine, &prep.resolved, cfg.timeout_secs, ssh_retries)
+                    } else if is_binary_release_apply(&prep.resolved) {
+                        binary_release_apply(machine, &prep.resolved, cfg.timeout_secs, ssh_retries)
                     } else {
                         codegen::apply_script(&prep.resolved).and_then(|script| {
                             transport::exec_script_retry(
@@ -146,6 +160,7 @@ pub(super) fn record_wave_outcomes(
             continue;
         };
 
+        let output = confirm_safe_apply(machine, &prep.resolved, output, ctx.timeout_secs);
         match output {
             Ok(out) if out.success() => {
                 record_success(
@@ -156,6 +171,7 @@ pub(super) fn record_wave_outcomes(
                     machine,
                     duration,
                 );
+                record_script_outputs(ctx, &change.resource_id, &out.stdout);
                 counters.converged += 1;
                 counters
                     .converged_resources
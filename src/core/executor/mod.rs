@@ -38,6 +38,8 @@ mod tests_edge_details;
 #[cfg(test)]
 mod tests_edge_record;
 #[cfg(test)]
+mod tests_exec_refresh;
+#[cfg(test)]
 mod tests_filters;
 #[cfg(test)]
 mod tests_filters_b;
@@ -58,6 +60,7 @@ mod tests_waves;
 
 use super::codegen;
 use super::conditions;
+use super::pki;
 use super::planner;
 use super::resolver;
 use super::state;
@@ -76,10 +79,14 @@ pub use helpers::collect_machines;
 pub(crate) use helpers::{
     apply_and_record_outcome, build_resource_details, compute_resource_waves,
 };
-pub(crate) use helpers::{copia_apply_file, log_tripwire};
+pub(crate) use helpers::{
+    binary_release_apply, certificate_apply, copia_apply_file, hf_model_apply,
+    is_binary_release_apply, is_hf_model_apply, log_tripwire,
+};
 pub(crate) use machine::apply_machine;
 pub(crate) use resource_ops::{
-    apply_single_resource, record_failure, record_success, RecordCtx, ResourceOutcome,
+    apply_single_resource, confirm_safe_apply, record_failure, record_script_outputs,
+    record_success, RecordCtx, ResourceOutcome,
 };
 pub(crate) use strategies::{
     apply_machines_parallel, apply_machines_rolling, apply_machines_sequential,
@@ -127,6 +134,8 @@ pub struct ApplyConfig<'a> {
     pub refresh: bool,
     /// FJ-3010: Selective force — only force resources matching this tag
     pub force_tag: Option<&'a str>,
+    /// FJ-266: Why the apply lock is taken (shown by `status` and `lock-info`)
+    pub lock_reason: Option<&'a str>,
 }
 
 /// Load existing locks for machines matching the filter.
@@ -243,9 +252,16 @@ pub fn apply(cfg: &ApplyConfig) -> Result<Vec<ApplyResult>, String> {
     // FJ-266: State locking
     if !cfg.dry_run {
         if cfg.force_unlock {
-            state::force_unlock(cfg.state_dir)?;
+            if let Some(broken) = state::force_unlock(cfg.state_dir)? {
+                eprintln!("Force-unlocked apply lock held by {}", broken.describe());
+            }
         }
-        state::acquire_process_lock(cfg.state_dir)?;
+        let ttl = cfg
+            .config
+            .policy
+            .lock_ttl
+            .unwrap_or(state::lease::DEFAULT_LEASE_TTL_SECS);
+        state::acquire_apply_lock(cfg.state_dir, cfg.lock_reason, ttl)?;
     }
 
     let execution_order = resolver::build_execution_order(cfg.config)?;
@@ -271,6 +287,8 @@ pub fn apply(cfg: &ApplyConfig) -> Result<Vec<ApplyResult>, String> {
     } else {
         locks.clone()
     };
+    // Certificates inside their renew_before window are re-issued
+    let plan_locks = pki::mark_renewals_due(cfg.config, plan_locks, pki::now_unix());
     let plan = planner::plan(cfg.config, &execution_order, &plan_locks, cfg.tag_filter);
 
     if cfg.dry_run {
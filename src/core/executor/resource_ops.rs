@@ -159,6 +159,11 @@ fn should_skip_single(
 
     let resource = cfg.config.resources.get(&change.resource_id)?;
 
+    // exec `refreshonly`: only runs when one of its triggers converged
+    if resource.refreshonly && !triggered {
+        return Some(ResourceOutcome::Unchanged);
+    }
+
     if resource_filtered_out(cfg, resource, machine) {
         return Some(ResourceOutcome::Skipped);
     }
@@ -233,6 +238,20 @@ fn execute_resource(
             .unwrap_or(false)
     {
         copia_apply_file(machine, resolved, ctx.timeout_secs)
+    } else if resolved.resource_type == ResourceType::Certificate
+        && crate::resources::certificate::is_ca_signed(resolved)
+    {
+        certificate_apply(
+            machine,
+            resolved,
+            ctx.state_dir,
+            ctx.timeout_secs,
+            ssh_retries,
+        )
+    } else if is_hf_model_apply(resolved) {
+        hf_model_apply(machine, resolved, ctx.timeout_secs, ssh_retries)
+    } else if is_binary_release_apply(resolved) {
+        binary_release_apply(machine, resolved, ctx.timeout_secs, ssh_retries)
     } else {
         let script = codegen::apply_script(resolved)?;
         // FJ-1397: Debug trace mode — print generated script
@@ -285,6 +304,27 @@ fn check_task_input_cache(
     }
 }
 
+/// Store `FORJAR_OUTPUT key=value` lines from apply stdout in the lock details.
+pub(crate) fn record_script_outputs(ctx: &mut RecordCtx, resource_id: &str, stdout: &str) {
+    let outputs = crate::core::state::parse_script_outputs(stdout);
+    if outputs.is_empty() {
+        return;
+    }
+    if let Some(rl) = ctx.lock.resources.get_mut(resource_id) {
+        let map = outputs
+            .into_iter()
+            .map(|(k, v)| {
+                (
+                    serde_yaml_ng::Value::String(k),
+                    serde_yaml_ng::Value::String(v),
+                )
+            })
+            .collect();
+        rl.details
+            .insert("outputs".to_string(), serde_yaml_ng::Value::Mapping(map));
+    }
+}
+
 /// FJ-2301: Persist ExecOutput to .log files for post-mortem debugging.
 fn capture_exec_output(
     ctx: &RecordCtx,
@@ -335,6 +375,7 @@ fn handle_resource_output(
             duration,
         );
     }
+    let output = confirm_safe_apply(machine, resolved, output, ctx.timeout_secs);
     match output {
         Ok(out) if out.success() => {
             if let Some(ref post_hook) = resolved.post_apply {
@@ -357,6 +398,7 @@ fn handle_resource_output(
                 machine,
                 duration,
             );
+            record_script_outputs(ctx, &change.resource_id, &out.stdout);
             update_run_meta(
                 ctx,
                 cfg.run_id.as_deref(),
@@ -417,6 +459,46 @@ fn update_run_meta(
     }
 }
 
+/// Safe-apply confirmation: after a successful apply, reconnect over the
+/// transport and run the resource's confirm script. A failed reconnect
+/// turns the apply into a failure; the target's revert timer rolls it back.
+pub(crate) fn confirm_safe_apply(
+    machine: &Machine,
+    resolved: &Resource,
+    output: Result<transport::ExecOutput, String>,
+    timeout: Option<u64>,
+) -> Result<transport::ExecOutput, String> {
+    let out = match output {
+        Ok(out) if out.success() => out,
+        other => return other,
+    };
+    let confirm = match resolved.resource_type {
+        ResourceType::NetworkInterface => {
+            crate::resources::network_interface::confirm_script(resolved)
+        }
+        _ => None,
+    };
+    let Some(confirm) = confirm else {
+        return Ok(out);
+    };
+    let secs = resolved.revert_after.unwrap_or_default();
+    match transport::exec_script_timeout(machine, &confirm, timeout) {
+        Ok(cout) if cout.success() => Ok(out),
+        Ok(cout) => Ok(transport::ExecOutput {
+            exit_code: cout.exit_code,
+            stdout: out.stdout,
+            stderr: format!("safe-apply not confirmed: {}", cout.stderr.trim()),
+        }),
+        Err(e) => Ok(transport::ExecOutput {
+            exit_code: 1,
+            stdout: out.stdout,
+            stderr: format!(
+                "safe-apply: could not reconnect ({e}); the change reverts within {secs}s"
+            ),
+        }),
+    }
+}
+
 /// Run post_apply hook; returns error string on failure.
 fn check_post_hook(machine: &Machine, hook: &str, timeout: Option<u64>) -> Option<String> {
     match transport::exec_script_timeout(machine, hook, timeout) {
@@ -459,12 +541,13 @@ pub(crate) fn apply_single_resource(
         );
     }
 
-    let resolved = resolver::resolve_resource_templates_with_secrets(
+    let mut resolved = resolver::resolve_resource_templates_with_secrets(
         resource,
         &cfg.config.params,
         &cfg.config.machines,
         &cfg.config.secrets,
     )?;
+    resolver::apply_machine_defaults(&mut resolved, machine);
 
     execute_resource(cfg, change, resource, &resolved, machine, ctx)
 }
@@ -51,6 +51,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
 
@@ -119,6 +120,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r1 = apply(&cfg1).unwrap();
     assert_eq!(r1[0].resources_converged, 1);
@@ -146,6 +148,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r2 = apply(&cfg2).unwrap();
     assert_eq!(r2[0].resources_unchanged, 1);
@@ -173,6 +176,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r3 = apply(&cfg3).unwrap();
     assert_eq!(
@@ -301,6 +305,7 @@ resources:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results[0].machine, "dry-run");
@@ -45,6 +45,7 @@ resources:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
 
     let results = apply(&cfg).unwrap();
@@ -110,6 +111,7 @@ resources:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
 
     let _results = apply(&cfg).unwrap();
@@ -164,6 +166,7 @@ resources:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
 
     let _results = apply(&cfg).unwrap();
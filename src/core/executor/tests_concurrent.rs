@@ -56,6 +56,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results.len(), 1);
@@ -124,6 +125,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results[0].resources_converged, 2);
@@ -191,6 +193,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results[0].resources_converged, 3);
@@ -252,6 +255,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     // First apply
     let results1 = apply(&cfg).unwrap();
@@ -360,6 +364,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     apply(&cfg).unwrap();
 
@@ -33,6 +33,7 @@ fn test_fj132_force_apply_reconverges() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r1 = apply(&cfg).unwrap();
     assert_eq!(r1[0].resources_converged, 1);
@@ -64,6 +65,7 @@ fn test_fj132_force_apply_reconverges() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r3 = apply(&force_cfg).unwrap();
     assert_eq!(r3[0].resources_converged, 1);
@@ -124,6 +126,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     // Only file-a should be applied
@@ -189,6 +192,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results[0].resources_converged, 1);
@@ -261,6 +265,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results[0].resources_converged, 3);
@@ -305,6 +310,7 @@ fn test_fj132_global_lock_written_after_apply() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     apply(&cfg).unwrap();
 
@@ -359,6 +365,7 @@ resources:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results[0].machine, "dry-run");
@@ -51,6 +51,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r1 = apply(&cfg).unwrap();
     assert_eq!(r1[0].resources_converged, 1);
@@ -106,6 +107,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     // No results for non-matching machine
@@ -175,6 +177,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results[0].resources_converged, 3);
@@ -228,6 +231,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert!(
@@ -283,6 +287,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     apply(&cfg).unwrap();
 
@@ -308,6 +313,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg_force).unwrap();
     assert_eq!(
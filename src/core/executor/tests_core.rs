@@ -140,6 +140,83 @@ fn test_fj012_build_resource_details() {
         asset_pattern: None,
         binary: None,
         install_dir: None,
+        runtime: None,
+        pull_policy: None,
+        image_digest: None,
+        labels: vec![],
+        user: None,
+        entrypoint: None,
+        cpus: None,
+        networks: vec![],
+        driver: None,
+        subnet: None,
+        gateway: None,
+        url: None,
+        host: None,
+        expect_status: None,
+        pattern: None,
+        interval: None,
+        backoff: None,
+        creates: None,
+        unless: None,
+        onlyif: None,
+        refreshonly: false,
+        returns: vec![],
+        device: None,
+        size: None,
+        number: None,
+        part_type: None,
+        physical_volumes: vec![],
+        volume_group: None,
+        swappiness: None,
+        force: false,
+        interface: None,
+        addresses: vec![],
+        public_key: None,
+        endpoint: None,
+        allowed_ips: vec![],
+        persistent_keepalive: None,
+        kind: None,
+        members: vec![],
+        vlan_id: None,
+        bond_mode: None,
+        mtu: None,
+        dhcp: None,
+        dns: vec![],
+        routes: vec![],
+        revert_after: None,
+        issuer: None,
+        ca_name: None,
+        ca_cert: None,
+        ca_key: None,
+        common_name: None,
+        sans: vec![],
+        validity_days: None,
+        renew_before: None,
+        key_type: None,
+        key_path: None,
+        chain_path: None,
+        hostname: None,
+        timezone: None,
+        locale: None,
+        keyboard: None,
+        ntp_servers: vec![],
+        vdevs: vec![],
+        properties: Default::default(),
+        dataset: None,
+        keep: None,
+        recursive: false,
+        mig_profiles: vec![],
+        power_limit_watts: None,
+        container_toolkit: false,
+        checksum_file: None,
+        signature: None,
+        password: None,
+        attributes: Vec::new(),
+        database: None,
+        encoding: None,
+        grants: Vec::new(),
+        selinux_type: None,
     };
     let details = build_resource_details(&r, &local_machine());
     assert!(details.contains_key("path"));
@@ -254,6 +331,83 @@ fn test_fj012_build_resource_details_service() {
         asset_pattern: None,
         binary: None,
         install_dir: None,
+        runtime: None,
+        pull_policy: None,
+        image_digest: None,
+        labels: vec![],
+        user: None,
+        entrypoint: None,
+        cpus: None,
+        networks: vec![],
+        driver: None,
+        subnet: None,
+        gateway: None,
+        url: None,
+        host: None,
+        expect_status: None,
+        pattern: None,
+        interval: None,
+        backoff: None,
+        creates: None,
+        unless: None,
+        onlyif: None,
+        refreshonly: false,
+        returns: vec![],
+        device: None,
+        size: None,
+        number: None,
+        part_type: None,
+        physical_volumes: vec![],
+        volume_group: None,
+        swappiness: None,
+        force: false,
+        interface: None,
+        addresses: vec![],
+        public_key: None,
+        endpoint: None,
+        allowed_ips: vec![],
+        persistent_keepalive: None,
+        kind: None,
+        members: vec![],
+        vlan_id: None,
+        bond_mode: None,
+        mtu: None,
+        dhcp: None,
+        dns: vec![],
+        routes: vec![],
+        revert_after: None,
+        issuer: None,
+        ca_name: None,
+        ca_cert: None,
+        ca_key: None,
+        common_name: None,
+        sans: vec![],
+        validity_days: None,
+        renew_before: None,
+        key_type: None,
+        key_path: None,
+        chain_path: None,
+        hostname: None,
+        timezone: None,
+        locale: None,
+        keyboard: None,
+        ntp_servers: vec![],
+        vdevs: vec![],
+        properties: Default::default(),
+        dataset: None,
+        keep: None,
+        recursive: false,
+        mig_profiles: vec![],
+        power_limit_watts: None,
+        container_toolkit: false,
+        checksum_file: None,
+        signature: None,
+        password: None,
+        attributes: Vec::new(),
+        database: None,
+        encoding: None,
+        grants: Vec::new(),
+        selinux_type: None,
     };
     let details = build_resource_details(&r, &local_machine());
     assert!(details.contains_key("service_name"));
@@ -288,6 +442,7 @@ fn test_fj012_dry_run() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results.len(), 1);
@@ -319,6 +474,7 @@ fn test_fj012_apply_local_file() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results.len(), 1);
@@ -362,6 +518,7 @@ fn test_fj012_apply_idempotent() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r1 = apply(&cfg).unwrap();
     assert_eq!(r1[0].resources_converged, 1);
@@ -388,6 +545,7 @@ fn test_fj012_apply_idempotent() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r2 = apply(&cfg2).unwrap();
     assert_eq!(r2[0].resources_unchanged, 1);
@@ -423,6 +581,7 @@ fn test_fj012_force_reapply() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     apply(&cfg).unwrap();
 
@@ -448,6 +607,7 @@ fn test_fj012_force_reapply() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r2 = apply(&cfg2).unwrap();
     assert_eq!(r2[0].resources_converged, 1);
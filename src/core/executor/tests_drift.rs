@@ -31,6 +31,7 @@ fn test_fj129_apply_then_drift_no_change() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     apply(&cfg).unwrap();
 
@@ -73,6 +74,7 @@ fn test_fj129_apply_then_drift_after_modification() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     apply(&cfg).unwrap();
 
@@ -123,6 +125,7 @@ fn test_fj129_apply_drift_reapply_cycle() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r1 = apply(&cfg).unwrap();
     assert_eq!(r1[0].resources_converged, 1);
@@ -160,6 +163,7 @@ fn test_fj129_apply_drift_reapply_cycle() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r2 = apply(&cfg2).unwrap();
     assert_eq!(r2[0].resources_converged, 1);
@@ -224,6 +228,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results[0].resources_converged, 2);
@@ -302,6 +307,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r1 = apply(&cfg_a).unwrap();
     assert_eq!(r1[0].resources_converged, 1);
@@ -335,6 +341,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r2 = apply(&cfg_b).unwrap();
     assert_eq!(
@@ -377,6 +384,7 @@ fn test_fj129_event_log_full_lifecycle() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     apply(&cfg).unwrap();
 
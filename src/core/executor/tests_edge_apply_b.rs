@@ -31,6 +31,7 @@ fn test_fj012_apply_force_noop_reapplies() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r1 = apply(&cfg).unwrap();
     assert_eq!(r1[0].resources_converged, 1);
@@ -57,6 +58,7 @@ fn test_fj012_apply_force_noop_reapplies() {
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let r2 = apply(&cfg2).unwrap();
     assert_eq!(
@@ -109,6 +111,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results[0].resources_converged, 1);
@@ -162,6 +165,7 @@ policy:
         run_id: None,
         refresh: false,
         force_tag: None,
+        lock_reason: None,
     };
     let results = apply(&cfg).unwrap();
     assert_eq!(results[0].resources_converged, 1);
@@ -107,6 +107,83 @@ fn test_fj012_record_success_without_tripwire() {
         asset_pattern: None,
         binary: None,
         install_dir: None,
+        runtime: None,
+        pull_policy: None,
+        image_digest: None,
+        labels: vec![],
+        user: None,
+        entrypoint: None,
+        cpus: None,
+        networks: vec![],
+        driver: None,
+        subnet: None,
+        gateway: None,
+        url: None,
+        host: None,
+        expect_status: None,
+        pattern: None,
+        interval: None,
+        backoff: None,
+        creates: None,
+        unless: None,
+        onlyif: None,
+        refreshonly: false,
+        returns: vec![],
+        device: None,
+        size: None,
+        number: None,
+        part_type: None,
+        physical_volumes: vec![],
+        volume_group: None,
+        swappiness: None,
+        force: false,
+        interface: None,
+        addresses: vec![],
+        public_key: None,
+        endpoint: None,
+        allowed_ips: vec![],
+        persistent_keepalive: None,
+        kind: None,
+        members: vec![],
+        vlan_id: None,
+        bond_mode: None,
+        mtu: None,
+        dhcp: None,
+        dns: vec![],
+        routes: vec![],
+        revert_after: None,
+        issuer: None,
+        ca_name: None,
+        ca_cert: None,
+        ca_key: None,
+        common_name: None,
+        sans: vec![],
+        validity_days: None,
+        renew_before: None,
+        key_type: None,
+        key_path: None,
+        chain_path: None,
+        hostname: None,
+        timezone: None,
+        locale: None,
+        keyboard: None,
+        ntp_servers: vec![],
+        vdevs: vec![],
+        properties: Default::default(),
+        dataset: None,
+        keep: None,
+        recursive: false,
+        mig_profiles: vec![],
+        power_limit_watts: None,
+        container_toolkit: false,
+        checksum_file: None,
+        signature: None,
+        password: None,
+        attributes: Vec::new(),
+        database: None,
+        encoding: None,
+        grants: Vec::new(),
+        selinux_type: None,
     };
     let mut ctx = RecordCtx {
         lock: &mut lock,
@@ -225,6 +302,83 @@ fn test_fj012_record_success_service_resource() {
         asset_pattern: None,
         binary: None,
         install_dir: None,
+        runtime: None,
+        pull_policy: None,
+        image_digest: None,
+        labels: vec![],
+        user: None,
+        entrypoint: None,
+        cpus: None,
+        networks: vec![],
+        driver: None,
+        subnet: None,
+        gateway: None,
+        url: None,
+        host: None,
+        expect_status: None,
+        pattern: None,
+        interval: None,
+        backoff: None,
+        creates: None,
+        unless: None,
+        onlyif: None,
+        refreshonly: false,
+        returns: vec![],
+        device: None,
+        size: None,
+        number: None,
+        part_type: None,
+        physical_volumes: vec![],
+        volume_group: None,
+        swappiness: None,
+        force: false,
+        interface: None,
+        addresses: vec![],
+        public_key: None,
+        endpoint: None,
+        allowed_ips: vec![],
+        persistent_keepalive: None,
+        kind: None,
+        members: vec![],
+        vlan_id: None,
+        bond_mode: None,
+        mtu: None,
+        dhcp: None,
+        dns: vec![],
+        routes: vec![],
+        revert_after: None,
+        issuer: None,
+        ca_name: None,
+        ca_cert: None,
+        ca_key: None,
+        common_name: None,
+        sans: vec![],
+        validity_days: None,
+        renew_before: None,
+        key_type: None,
+        key_path: None,
+        chain_path: None,
+        hostname: None,
+        timezone: None,
+        locale: None,
+        keyboard: None,
+        ntp_servers: vec![],
+        vdevs: vec![],
+        properties: Default::default(),
+        dataset: None,
+        keep: None,
+        recursive: false,
+        mig_profiles: vec![],
+        power_limit_watts: None,
+        container_toolkit: false,
+        checksum_file: None,
+        signature: None,
+        password: None,
+        attributes: Vec::new(),
+        database: None,
+        encoding: None,
+        grants: Vec::new(),
+        selinux_type: None,
     };
     let mut ctx = RecordCtx {
         lock: &mut lock,
@@ -266,6 +420,31 @@ fn test_fj012_record_failure_tripwire_off() {
     assert!(!events_path.exists(), "no event log without tripwire");
 }
 
+#[test]
+fn test_record_script_outputs_into_details() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut lock = state::new_lock("test", "test-box");
+    let mut ctx = RecordCtx {
+        lock: &mut lock,
+        state_dir: dir.path(),
+        machine_name: "test",
+        tripwire: false,
+        failure_policy: &FailurePolicy::StopOnFirst,
+        timeout_secs: None,
+    };
+    record_failure(&mut ctx, "wg", &ResourceType::WireguardInterface, 0.1, "x");
+
+    record_script_outputs(&mut ctx, "wg", "noise\nFORJAR_OUTPUT public_key=k1=\n");
+    let outputs = &ctx.lock.resources["wg"].details["outputs"];
+    assert_eq!(outputs["public_key"].as_str(), Some("k1="));
+
+    // No output lines leaves the details untouched
+    record_script_outputs(&mut ctx, "other", "FORJAR_OUTPUT public_key=k2=");
+    record_script_outputs(&mut ctx, "wg", "done");
+    assert!(!ctx.lock.resources.contains_key("other"));
+    assert!(ctx.lock.resources["wg"].details.contains_key("outputs"));
+}
+
 #[test]
 fn test_fj012_build_details_file_no_content() {
     // File with path but no content → no content_hash
@@ -365,6 +544,83 @@ fn test_fj012_build_details_file_no_content() {
         asset_pattern: None,
         binary: None,
         install_dir: None,
+        runtime: None,
+        pull_policy: None,
+        image_digest: None,
+        labels: vec![],
+        user: None,
+        entrypoint: None,
+        cpus: None,
+        networks: vec![],
+        driver: None,
+        subnet: None,
+        gateway: None,
+        url: None,
+        host: None,
+        expect_status: None,
+        pattern: None,
+        interval: None,
+        backoff: None,
+        creates: None,
+        unless: None,
+        onlyif: None,
+        refreshonly: false,
+        returns: vec![],
+        device: None,
+        size: None,
+        number: None,
+        part_type: None,
+        physical_volumes: vec![],
+        volume_group: None,
+        swappiness: None,
+        force: false,
+        interface: None,
+        addresses: vec![],
+        public_key: None,
+        endpoint: None,
+        allowed_ips: vec![],
+        persistent_keepalive: None,
+        kind: None,
+        members: vec![],
+        vlan_id: None,
+        bond_mode: None,
+        mtu: None,
+        dhcp: None,
+        dns: vec![],
+        routes: vec![],
+        revert_after: None,
+        issuer: None,
+        ca_name: None,
+        ca_cert: None,
+        ca_key: None,
+        common_name: None,
+        sans: vec![],
+        validity_days: None,
+        renew_before: None,
+        key_type: None,
+        key_path: None,
+        chain_path: None,
+        hostname: None,
+        timezone: None,
+        locale: None,
+        keyboard: None,
+        ntp_servers: vec![],
+        vdevs: vec![],
+        properties: Default::default(),
+        dataset: None,
+        keep: None,
+        recursive: false,
+        mig_profiles: vec![],
+        power_limit_watts: None,
+        container_toolkit: false,
+        checksum_file: None,
+        signature: None,
+        password: None,
+        attributes: Vec::new(),
+        database: None,
+        encoding: None,
+        grants: Vec::new(),
+        selinux_type: None,
     };
     let details = build_resource_details(&resource, &local_machine());
     assert!(details.contains_key("path"));
@@ -478,6 +734,83 @@ fn test_fj012_build_details_file_with_content_and_real_file() {
         asset_pattern: None,
         binary: None,
         install_dir: None,
+        runtime: None,
+        pull_policy: None,
+        image_digest: None,
+        labels: vec![],
+        user: None,
+        entrypoint: None,
+        cpus: None,
+        networks: vec![],
+        driver: None,
+        subnet: None,
+        gateway: None,
+        url: None,
+        host: None,
+        expect_status: None,
+        pattern: None,
+        interval: None,
+        backoff: None,
+        creates: None,
+        unless: None,
+        onlyif: None,
+        refreshonly: false,
+        returns: vec![],
+        device: None,
+        size: None,
+        number: None,
+        part_type: None,
+        physical_volumes: vec![],
+        volume_group: None,
+        swappiness: None,
+        force: false,
+        interface: None,
+        addresses: vec![],
+        public_key: None,
+        endpoint: None,
+        allowed_ips: vec![],
+        persistent_keepalive: None,
+        kind: None,
+        members: vec![],
+        vlan_id: None,
+        bond_mode: None,
+        mtu: None,
+        dhcp: None,
+        dns: vec![],
+        routes: vec![],
+        revert_after: None,
+        issuer: None,
+        ca_name: None,
+        ca_cert: None,
+        ca_key: None,
+        common_name: None,
+        sans: vec![],
+        validity_days: None,
+        renew_before: None,
+        key_type: None,
+        key_path: None,
+        chain_path: None,
+        hostname: None,
+        timezone: None,
+        locale: None,
+        keyboard: None,
+        ntp_servers: vec![],
+        vdevs: vec![],
+        properties: Default::default(),
+        dataset: None,
+        keep: None,
+        recursive: false,
+        mig_profiles: vec![],
+        power_limit_watts: None,
+        container_toolkit: false,
+        checksum_file: None,
+        signature: None,
+        password: None,
+        attributes: Vec::new(),
+        database: None,
+        encoding: None,
+        grants: Vec::new(),
+        selinux_type: None,
     };
     let details = build_resource_details(&resource, &local_machine());
     assert!(
@@ -490,3 +823,40 @@ fn test_fj012_build_details_file_with_content_and_real_file() {
         "hash should be blake3-prefixed"
     );
 }
+
+#[test]
+fn test_confirm_safe_apply() {
+    let machine = local_machine();
+    let applied = || {
+        Ok(transport::ExecOutput {
+            exit_code: 0,
+            stdout: "changed:fjtest-none".to_string(),
+            stderr: String::new(),
+        })
+    };
+    let mut resource = Resource {
+        resource_type: ResourceType::NetworkInterface,
+        machine: MachineTarget::Single("local".to_string()),
+        name: Some("fjtest-none".to_string()),
+        ..Default::default()
+    };
+    // Safe-apply off: passed through untouched
+    let out = confirm_safe_apply(&machine, &resource, applied(), None).unwrap();
+    assert_eq!(out.stdout, "changed:fjtest-none");
+
+    // No revert armed for this link: the reconnect confirms
+    resource.revert_after = Some(30);
+    assert!(confirm_safe_apply(&machine, &resource, applied(), None)
+        .unwrap()
+        .success());
+
+    // Failed applies are never confirmed
+    let failed = Ok(transport::ExecOutput {
+        exit_code: 2,
+        stdout: String::new(),
+        stderr: "boom".to_string(),
+    });
+    let out = confirm_safe_apply(&machine, &resource, failed, None).unwrap();
+    assert_eq!(out.exit_code, 2);
+    assert_eq!(out.stderr, "boom");
+}
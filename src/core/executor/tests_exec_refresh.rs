@@ -0,0 +1,90 @@
+//! exec `refreshonly`: runs only when a trigger converged in the same apply.
+
+use super::*;
+
+fn stack_yaml(dir: &std::path::Path, config_content: &str, command_suffix: &str) -> String {
+    let config_path = dir.join("app.conf");
+    let log_path = dir.join("reload.log");
+    format!(
+        r#"
+version: "1.0"
+name: test
+machines:
+  local:
+    hostname: localhost
+    addr: 127.0.0.1
+resources:
+  config:
+    type: file
+    machine: local
+    path: {config}
+    content: "{config_content}"
+  reload:
+    type: exec
+    machine: local
+    name: reload
+    command: "echo reload{command_suffix} >> {log}"
+    refreshonly: true
+    depends_on: [config]
+    triggers: [config]
+"#,
+        config = config_path.display(),
+        log = log_path.display(),
+    )
+}
+
+fn apply_yaml(yaml: &str, state_dir: &std::path::Path) -> Vec<ApplyResult> {
+    let config: ForjarConfig = serde_yaml_ng::from_str(yaml).unwrap();
+    let cfg = ApplyConfig {
+        config: &config,
+        state_dir,
+        force: false,
+        dry_run: false,
+        machine_filter: None,
+        resource_filter: None,
+        tag_filter: None,
+        group_filter: None,
+        timeout_secs: None,
+        force_unlock: false,
+        progress: false,
+        retry: 0,
+        parallel: None,
+        resource_timeout: None,
+        rollback_on_failure: false,
+        max_parallel: None,
+        trace: false,
+        run_id: None,
+        refresh: false,
+        force_tag: None,
+        lock_reason: None,
+    };
+    apply(&cfg).unwrap()
+}
+
+fn reload_count(dir: &std::path::Path) -> usize {
+    std::fs::read_to_string(dir.join("reload.log"))
+        .map(|s| s.lines().count())
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_refreshonly_exec_runs_only_when_triggered() {
+    let dir = tempfile::tempdir().unwrap();
+    let state_dir = dir.path().join("state");
+    std::fs::create_dir_all(&state_dir).unwrap();
+
+    // First apply: config is created, which triggers the reload
+    let r1 = apply_yaml(&stack_yaml(dir.path(), "v1", ""), &state_dir);
+    assert_eq!(r1[0].resources_converged, 2);
+    assert_eq!(reload_count(dir.path()), 1);
+
+    // Changing only the exec does not run it — nothing triggered it
+    let r2 = apply_yaml(&stack_yaml(dir.path(), "v1", "-v2"), &state_dir);
+    assert_eq!(r2[0].resources_converged, 0);
+    assert_eq!(reload_count(dir.path()), 1);
+
+    // Changing the config runs it again
+    let r3 = apply_yaml(&stack_yaml(dir.path(), "v2", "-v2"), &state_dir);
+    assert_eq!(r3[0].resources_converged, 2);
+    assert_eq!(reload_count(dir.path()), 2);
+}
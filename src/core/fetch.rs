@@ -0,0 +1,167 @@
+//! HTTP requests from the control machine via `curl`.
+//!
+//! Used by sources resolved before a script is generated (Hugging Face Hub,
+//! binary releases) and by remote state backends. Request headers are passed
+//! on curl's stdin rather than argv, so tokens do not show up in the process
+//! list.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// GET `url`, sending `headers` (`Name: value`). Fails on HTTP errors.
+pub fn http_get(url: &str, headers: &[String]) -> Result<Vec<u8>, String> {
+    let mut cmd = Command::new("curl");
+    cmd.args(["-fsSL", "--retry", "2"]);
+    if !headers.is_empty() {
+        cmd.args(["-H", "@-"]);
+    }
+    let mut child = cmd
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("curl: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        for header in headers {
+            writeln!(stdin, "{header}").map_err(|e| format!("curl: {e}"))?;
+        }
+    }
+    let output = child.wait_with_output().map_err(|e| format!("curl: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "GET {url} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// A header built from a non-empty environment variable, e.g.
+/// `env_header("HF_TOKEN", "Authorization: Bearer")`.
+pub fn env_header(var: &str, prefix: &str) -> Vec<String> {
+    std::env::var(var)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .map(|v| vec![format!("{prefix} {v}")])
+        .unwrap_or_default()
+}
+
+/// Status, headers and body of an [`http_request`].
+#[derive(Debug)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// Header names lowercased, in response order.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// First value of header `name` (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Headers of the final response in a `curl -D` dump (skipping
+/// `100 Continue` and redirect blocks).
+fn parse_header_dump(dump: &str) -> Vec<(String, String)> {
+    let last = dump
+        .split("\r\n\r\n")
+        .filter(|block| block.starts_with("HTTP/"))
+        .last()
+        .unwrap_or("");
+    last.lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect()
+}
+
+/// Send `method` to `url` with `headers` and an optional body. Unlike
+/// [`http_get`], HTTP error statuses are returned rather than turned into
+/// errors, so callers can act on `404` or `412`. Headers and body travel
+/// through files in a private temp directory, never through argv.
+pub fn http_request(
+    method: &str,
+    url: &str,
+    headers: &[String],
+    body: Option<&[u8]>,
+) -> Result<HttpResponse, String> {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let work = std::env::temp_dir().join(format!(
+        "forjar-http-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder
+        .create(&work)
+        .map_err(|e| format!("cannot create {}: {e}", work.display()))?;
+    let result = http_request_in(&work, method, url, headers, body);
+    let _ = std::fs::remove_dir_all(&work);
+    result
+}
+
+fn http_request_in(
+    work: &std::path::Path,
+    method: &str,
+    url: &str,
+    headers: &[String],
+    body: Option<&[u8]>,
+) -> Result<HttpResponse, String> {
+    let header_file = work.join("request-headers");
+    let dump_file = work.join("response-headers");
+    let out_file = work.join("response-body");
+    let mut request_headers = headers.to_vec();
+    // No `Expect: 100-continue` round trip for uploads.
+    request_headers.push("Expect:".to_string());
+    std::fs::write(&header_file, request_headers.join("\n"))
+        .map_err(|e| format!("cannot write {}: {e}", header_file.display()))?;
+
+    let mut cmd = Command::new("curl");
+    // `-X HEAD` would wait for a body that never comes; `-I` sends HEAD.
+    if method == "HEAD" {
+        cmd.args(["-sS", "-I"]);
+    } else {
+        cmd.args(["-sS", "-X", method]);
+    }
+    cmd.arg("-H")
+        .arg(format!("@{}", header_file.display()))
+        .arg("-D")
+        .arg(&dump_file)
+        .arg("-o")
+        .arg(&out_file)
+        .args(["-w", "%{http_code}"]);
+    if let Some(body) = body {
+        let body_file = work.join("request-body");
+        std::fs::write(&body_file, body)
+            .map_err(|e| format!("cannot write {}: {e}", body_file.display()))?;
+        cmd.arg("--data-binary")
+            .arg(format!("@{}", body_file.display()));
+    }
+    let output = cmd.arg(url).output().map_err(|e| format!("curl: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{method} {url} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let status = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u16>()
+        .map_err(|_| format!("{method} {url}: no HTTP status from curl"))?;
+    let dump = std::fs::read_to_string(&dump_file).unwrap_or_default();
+    Ok(HttpResponse {
+        status,
+        headers: parse_header_dump(&dump),
+        body: std::fs::read(&out_file).unwrap_or_default(),
+    })
+}
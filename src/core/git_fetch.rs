@@ -0,0 +1,51 @@
+//! Shallow git fetches from the control machine, shared by remote includes
+//! and recipe registries, and the cache directory their results live in.
+
+use std::path::{Path, PathBuf};
+
+/// `$FORJAR_CACHE_DIR/<kind>`, default `~/.forjar/cache/<kind>`.
+pub fn cache_dir(kind: &str) -> PathBuf {
+    let base = std::env::var_os("FORJAR_CACHE_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let home = std::env::var_os("HOME").unwrap_or_else(|| ".".into());
+            PathBuf::from(home).join(".forjar").join("cache")
+        });
+    base.join(kind)
+}
+
+/// Refuse a repository or ref that `git` would read as an option
+/// (e.g. `--upload-pack=<cmd>`).
+pub fn reject_options(repo: &str, reference: &str) -> Result<(), String> {
+    if repo.starts_with('-') || reference.starts_with('-') {
+        return Err("repository and ref must not start with '-'".to_string());
+    }
+    Ok(())
+}
+
+/// Run `git -C <dir> <args>` and return its stdout.
+pub fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let out = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("git: failed to execute: {e}"))?;
+    if !out.status.success() {
+        return Err(format!(
+            "git {}: {}",
+            args[0],
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// Initialize a repository in `dir` and shallow-fetch `reference` of `repo`
+/// into its `FETCH_HEAD`.
+pub fn shallow_fetch(dir: &Path, repo: &str, reference: &str) -> Result<(), String> {
+    reject_options(repo, reference)?;
+    git(dir, &["init", "-q"])?;
+    git(dir, &["fetch", "-q", "--depth", "1", "--", repo, reference])?;
+    Ok(())
+}
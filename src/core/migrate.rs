@@ -226,6 +226,83 @@ mod tests {
             asset_pattern: None,
             binary: None,
             install_dir: None,
+            runtime: None,
+            pull_policy: None,
+            image_digest: None,
+            labels: vec![],
+            user: None,
+            entrypoint: None,
+            cpus: None,
+            networks: vec![],
+            driver: None,
+            subnet: None,
+            gateway: None,
+            url: None,
+            host: None,
+            expect_status: None,
+            pattern: None,
+            interval: None,
+            backoff: None,
+            creates: None,
+            unless: None,
+            onlyif: None,
+            refreshonly: false,
+            returns: vec![],
+            device: None,
+            size: None,
+            number: None,
+            part_type: None,
+            physical_volumes: vec![],
+            volume_group: None,
+            swappiness: None,
+            force: false,
+            interface: None,
+            addresses: vec![],
+            public_key: None,
+            endpoint: None,
+            allowed_ips: vec![],
+            persistent_keepalive: None,
+            kind: None,
+            members: vec![],
+            vlan_id: None,
+            bond_mode: None,
+            mtu: None,
+            dhcp: None,
+            dns: vec![],
+            routes: vec![],
+            revert_after: None,
+            issuer: None,
+            ca_name: None,
+            ca_cert: None,
+            ca_key: None,
+            common_name: None,
+            sans: vec![],
+            validity_days: None,
+            renew_before: None,
+            key_type: None,
+            key_path: None,
+            chain_path: None,
+            hostname: None,
+            timezone: None,
+            locale: None,
+            keyboard: None,
+            ntp_servers: vec![],
+            vdevs: vec![],
+            properties: Default::default(),
+            dataset: None,
+            keep: None,
+            recursive: false,
+            mig_profiles: vec![],
+            power_limit_watts: None,
+            container_toolkit: false,
+            checksum_file: None,
+            signature: None,
+            password: None,
+            attributes: Vec::new(),
+            database: None,
+            encoding: None,
+            grants: Vec::new(),
+            selinux_type: None,
         }
     }
 
@@ -378,6 +455,7 @@ mod tests {
             data: indexmap::IndexMap::new(),
             includes: vec![],
             include_provenance: HashMap::new(),
+            recipe_outputs: indexmap::IndexMap::new(),
             checks: indexmap::IndexMap::new(),
             moved: vec![],
             secrets: Default::default(),
@@ -422,6 +500,7 @@ mod tests {
             data: indexmap::IndexMap::new(),
             includes: vec![],
             include_provenance: HashMap::new(),
+            recipe_outputs: indexmap::IndexMap::new(),
             checks: indexmap::IndexMap::new(),
             moved: vec![],
             secrets: Default::default(),
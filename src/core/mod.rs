@@ -10,10 +10,13 @@ pub mod conditions;
 pub mod cron_source;
 pub mod ephemeral;
 pub mod executor;
+pub mod fetch;
+pub mod git_fetch;
 pub mod metric_collector;
 pub mod metric_source;
 pub mod migrate;
 pub mod parser;
+pub mod pki;
 pub mod planner;
 pub mod plugin_dispatch;
 pub mod plugin_hot_reload;
@@ -21,10 +24,13 @@ pub mod plugin_loader;
 pub mod plugin_runtime;
 pub mod policy_boundary;
 pub mod policy_coverage;
+pub mod pq_signing;
 pub mod promotion;
 pub mod promotion_events;
 pub mod purifier;
 pub mod recipe;
+pub mod release_source;
+mod release_source_b;
 pub mod resolver;
 pub mod rollout;
 pub mod rulebook_template;
@@ -35,14 +41,19 @@ pub mod script_secret_lint;
 pub mod secret_audit;
 pub mod secret_namespace;
 pub mod secret_provider;
+pub mod secret_rotation;
 pub mod secrets;
 pub mod security_scanner;
 pub mod shell_provider;
+pub mod signing;
+mod signing_b;
 pub mod state;
 pub mod state_encryption;
+pub mod state_recipients;
 pub mod store;
 pub mod task;
 pub mod types;
+pub mod vault;
 pub mod watch_daemon;
 pub mod webhook_server;
 pub mod webhook_source;
@@ -56,12 +67,18 @@ pub mod mcdc;
 pub mod repro_build;
 mod scoring_b;
 #[cfg(test)]
+pub(crate) mod test_http;
+#[cfg(test)]
 mod tests_compliance;
 #[cfg(test)]
 mod tests_kani_proofs;
 #[cfg(test)]
+mod tests_pki;
+#[cfg(test)]
 mod tests_policy_boundary;
 #[cfg(test)]
+mod tests_pq_signing;
+#[cfg(test)]
 mod tests_proptest_convergence;
 #[cfg(test)]
 mod tests_proptest_handlers;
@@ -72,13 +89,23 @@ mod tests_purifier;
 #[cfg(test)]
 mod tests_purifier_b;
 #[cfg(test)]
+mod tests_release_source;
+#[cfg(test)]
 mod tests_scoring;
 #[cfg(test)]
 mod tests_scoring_b;
+#[cfg(test)]
+mod tests_secret_rotation;
 #[cfg(all(test, feature = "encryption"))]
 mod tests_secrets;
 #[cfg(test)]
 mod tests_security_scanner;
 #[cfg(test)]
+mod tests_signing;
+#[cfg(test)]
+mod tests_state_recipients;
+#[cfg(test)]
+mod tests_vault;
+#[cfg(test)]
 mod tests_watch_daemon;
 mod verus_spec;
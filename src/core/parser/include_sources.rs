@@ -0,0 +1,331 @@
+//! FJ-3313: Include sources — local files, HTTPS URLs and git repositories.
+//!
+//! Remote includes are fetched into a content-addressed cache
+//! (`$FORJAR_CACHE_DIR/includes`, default `~/.forjar/cache/includes`) and
+//! pinned by BLAKE3 hash in `forjar.inputs.lock.yaml` next to the root
+//! config. A pinned include is read from the cache without touching the
+//! network. With `FORJAR_OFFLINE=1`, an unpinned or uncached remote include
+//! is an error instead of a fetch.
+
+use crate::core::git_fetch;
+use crate::core::store::lockfile::{read_lockfile, write_lockfile, LockFile, Pin};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const LOCK_FILENAME: &str = "forjar.inputs.lock.yaml";
+const GIT_DEFAULT_PATH: &str = "forjar.yaml";
+
+/// Where an include is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum IncludeSource {
+    /// A file on disk.
+    Local(PathBuf),
+    /// An `https://` (or `http://`) URL.
+    Url(String),
+    /// `git+<repo>@<ref>[//<path>]`: a file at a ref of a git repository.
+    Git {
+        repo: String,
+        reference: String,
+        path: String,
+    },
+}
+
+impl IncludeSource {
+    /// Resolve include `spec` as written in a file read from `parent`.
+    /// Relative specs resolve against the parent's directory, URL or repo.
+    pub(super) fn resolve(spec: &str, parent: &IncludeSource) -> Result<Self, String> {
+        if let Some(rest) = spec.strip_prefix("git+") {
+            return parse_git(spec, rest);
+        }
+        if spec.starts_with("https://") || spec.starts_with("http://") {
+            return Ok(Self::Url(spec.to_string()));
+        }
+        if Path::new(spec).is_absolute() {
+            return Ok(Self::Local(PathBuf::from(spec)));
+        }
+        Ok(match parent {
+            Self::Local(dir) => Self::Local(dir.join(spec)),
+            Self::Url(url) => {
+                let host_end = url
+                    .find("://")
+                    .and_then(|b| url[b + 3..].find('/').map(|p| b + 3 + p))
+                    .unwrap_or(url.len());
+                let (origin, path) = url.split_at(host_end);
+                let dir = path.rsplit_once('/').map_or("", |(d, _)| d);
+                Self::Url(format!("{origin}/{}", normalize(&format!("{dir}/{spec}"))))
+            }
+            Self::Git {
+                repo,
+                reference,
+                path,
+            } => {
+                let dir = path.rsplit_once('/').map_or("", |(d, _)| d);
+                Self::Git {
+                    repo: repo.clone(),
+                    reference: reference.clone(),
+                    path: normalize(&format!("{dir}/{spec}")),
+                }
+            }
+        })
+    }
+
+    /// The origin that includes inside this file resolve against.
+    pub(super) fn origin(&self) -> Self {
+        match self {
+            Self::Local(path) => Self::Local(path.parent().unwrap_or(Path::new(".")).to_path_buf()),
+            other => other.clone(),
+        }
+    }
+
+    /// Stable identity used for cycle detection and lock pins.
+    pub(super) fn key(&self, root: &Path) -> String {
+        match self {
+            Self::Local(path) => {
+                let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+                let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+                canonical
+                    .strip_prefix(&root)
+                    .unwrap_or(&canonical)
+                    .display()
+                    .to_string()
+            }
+            Self::Url(url) => url.clone(),
+            Self::Git {
+                repo,
+                reference,
+                path,
+            } => format!("git+{repo}@{reference}//{path}"),
+        }
+    }
+}
+
+/// Parse `git+<repo>@<ref>[//<path>]`.
+fn parse_git(spec: &str, rest: &str) -> Result<IncludeSource, String> {
+    let body = rest.find("://").map_or(0, |i| i + 3);
+    let (repo_ref, path) = match rest[body..].find("//") {
+        Some(i) => (&rest[..body + i], &rest[body + i + 2..]),
+        None => (rest, GIT_DEFAULT_PATH),
+    };
+    let (repo, reference) = repo_ref
+        .rsplit_once('@')
+        .filter(|(repo, r)| !r.is_empty() && !r.contains('/') && repo.len() > body)
+        .ok_or_else(|| format!("git include '{spec}' must name a ref: git+<repo>@<ref>"))?;
+    if path.is_empty() {
+        return Err(format!("git include '{spec}' has an empty path"));
+    }
+    git_fetch::reject_options(repo, reference).map_err(|e| format!("git include '{spec}': {e}"))?;
+    Ok(IncludeSource::Git {
+        repo: repo.to_string(),
+        reference: reference.to_string(),
+        path: normalize(path),
+    })
+}
+
+/// Collapse `.` and `..` segments of a `/`-separated relative path.
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            p => parts.push(p),
+        }
+    }
+    parts.join("/")
+}
+
+fn blake3_hash(content: &str) -> String {
+    format!("blake3:{}", blake3::hash(content.as_bytes()).to_hex())
+}
+
+fn pin_name(key: &str) -> String {
+    format!("include:{key}")
+}
+
+/// Include loading state for one root config: the lock file and the cache.
+pub(super) struct IncludeLoader {
+    root: PathBuf,
+    lock_path: PathBuf,
+    lock: LockFile,
+    cache_dir: PathBuf,
+    offline: bool,
+    has_remote: bool,
+    dirty: bool,
+}
+
+impl IncludeLoader {
+    /// Loader for a root config in `root`, reading its input lock file.
+    pub(super) fn new(root: &Path) -> Result<Self, String> {
+        let lock_path = root.join(LOCK_FILENAME);
+        let lock = if lock_path.exists() {
+            read_lockfile(&lock_path)?
+        } else {
+            LockFile {
+                schema: "1.0".to_string(),
+                pins: BTreeMap::new(),
+            }
+        };
+        Ok(Self {
+            root: root.to_path_buf(),
+            lock_path,
+            lock,
+            cache_dir: git_fetch::cache_dir("includes"),
+            offline: std::env::var("FORJAR_OFFLINE").is_ok_and(|v| v == "1" || v == "true"),
+            has_remote: false,
+            dirty: false,
+        })
+    }
+
+    /// Use `dir` as the fetch cache instead of `$FORJAR_CACHE_DIR`.
+    #[cfg(test)]
+    pub(super) fn with_cache_dir(mut self, dir: &Path) -> Self {
+        self.cache_dir = dir.to_path_buf();
+        self
+    }
+
+    /// Refuse network fetches regardless of `FORJAR_OFFLINE`.
+    #[cfg(test)]
+    pub(super) fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Directory of the root config.
+    pub(super) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Read the content of `source`, pinning its hash.
+    pub(super) fn load(&mut self, source: &IncludeSource) -> Result<String, String> {
+        let key = source.key(&self.root);
+        let name = pin_name(&key);
+        if let IncludeSource::Local(path) = source {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+            self.pin(name, "file", None, None, blake3_hash(&content));
+            return Ok(content);
+        }
+        self.has_remote = true;
+        let locked = self.lock.pins.get(&name).map(|p| p.hash.clone());
+        if let Some(content) = locked.as_deref().and_then(|h| self.cached(h)) {
+            return Ok(content);
+        }
+        if self.offline {
+            return Err(match locked {
+                Some(_) => format!("include '{key}' is pinned but not cached (offline mode)"),
+                None => format!("refusing to fetch unpinned include '{key}' in offline mode"),
+            });
+        }
+        let (content, git_rev) = fetch(source)?;
+        let hash = blake3_hash(&content);
+        if let Some(locked) = locked.filter(|l| *l != hash) {
+            return Err(format!(
+                "include '{key}' changed upstream (locked {locked}, fetched {hash}); remove its pin from {} to accept it",
+                self.lock_path.display()
+            ));
+        }
+        self.store(&hash, &content)?;
+        let (provider, version) = match source {
+            IncludeSource::Git { reference, .. } => ("git", Some(reference.clone())),
+            _ => ("https", None),
+        };
+        self.pin(name, provider, version, git_rev, hash);
+        Ok(content)
+    }
+
+    fn pin(
+        &mut self,
+        name: String,
+        provider: &str,
+        version: Option<String>,
+        git_rev: Option<String>,
+        hash: String,
+    ) {
+        let pin = Pin {
+            provider: provider.to_string(),
+            version,
+            hash,
+            git_rev,
+            pin_type: Some("include".to_string()),
+        };
+        if self.lock.pins.get(&name) != Some(&pin) {
+            self.lock.pins.insert(name, pin);
+            self.dirty = true;
+        }
+    }
+
+    fn cached(&self, hash: &str) -> Option<String> {
+        let hex = hash.strip_prefix("blake3:")?;
+        let content = std::fs::read_to_string(self.cache_dir.join(format!("{hex}.yaml"))).ok()?;
+        (blake3_hash(&content) == hash).then_some(content)
+    }
+
+    fn store(&self, hash: &str, content: &str) -> Result<(), String> {
+        let hex = hash.trim_start_matches("blake3:");
+        std::fs::create_dir_all(&self.cache_dir)
+            .map_err(|e| format!("create {}: {e}", self.cache_dir.display()))?;
+        let path = self.cache_dir.join(format!("{hex}.yaml"));
+        std::fs::write(&path, content).map_err(|e| format!("write {}: {e}", path.display()))
+    }
+
+    /// Write the lock file if pins changed. Configs with only local includes
+    /// get a lock file only when one already exists.
+    pub(super) fn finish(self) -> Result<(), String> {
+        if self.dirty && (self.has_remote || self.lock_path.exists()) {
+            write_lockfile(&self.lock_path, &self.lock)?;
+        }
+        Ok(())
+    }
+}
+
+/// Fetch a remote include. Returns its content and, for git, the commit.
+fn fetch(source: &IncludeSource) -> Result<(String, Option<String>), String> {
+    match source {
+        IncludeSource::Url(url) => {
+            let resp = crate::core::fetch::http_request("GET", url, &[], None)
+                .map_err(|e| format!("fetch include '{url}': {e}"))?;
+            if resp.status != 200 {
+                return Err(format!("fetch include '{url}': HTTP {}", resp.status));
+            }
+            let content = String::from_utf8(resp.body)
+                .map_err(|_| format!("include '{url}' is not UTF-8"))?;
+            Ok((content, None))
+        }
+        IncludeSource::Git {
+            repo,
+            reference,
+            path,
+        } => fetch_git(repo, reference, path),
+        IncludeSource::Local(_) => Err("local includes are not fetched".to_string()),
+    }
+}
+
+/// Shallow-fetch `reference` of `repo` into a scratch repository and read `path`.
+fn fetch_git(repo: &str, reference: &str, path: &str) -> Result<(String, Option<String>), String> {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let work = std::env::temp_dir().join(format!(
+        "forjar-include-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&work).map_err(|e| format!("cannot create {}: {e}", work.display()))?;
+    let result = fetch_git_in(&work, repo, reference, path)
+        .map_err(|e| format!("fetch include 'git+{repo}@{reference}//{path}': {e}"));
+    let _ = std::fs::remove_dir_all(&work);
+    result
+}
+
+fn fetch_git_in(
+    work: &Path,
+    repo: &str,
+    reference: &str,
+    path: &str,
+) -> Result<(String, Option<String>), String> {
+    git_fetch::shallow_fetch(work, repo, reference)?;
+    let rev = git_fetch::git(work, &["rev-parse", "FETCH_HEAD"])?;
+    let content = git_fetch::git(work, &["show", &format!("FETCH_HEAD:{path}")])?;
+    Ok((content, Some(rev.trim().to_string())))
+}
@@ -1,55 +1,112 @@
-//! FJ-254 / FJ-2502: Merge included config files with hardened validation.
+//! FJ-254 / FJ-2502 / FJ-3313: Merge included config files with hardened validation.
 //!
-//! Circular include detection, conflict warnings, and provenance tracking.
+//! Recursive includes from local paths, HTTPS URLs and git repositories,
+//! circular include detection, conflict warnings, and provenance tracking.
 
+use super::include_sources::{IncludeLoader, IncludeSource};
 use super::*;
-use std::collections::HashSet;
-use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 /// FJ-254: Merge included config files into the base config.
 /// Later includes override earlier ones. params/machines/resources merge by key.
-/// policy is replaced wholesale. includes are not recursive (single level).
+/// policy is replaced wholesale. Included files may include others (FJ-3313);
+/// their includes are merged into them first, by the same rules.
 ///
 /// FJ-2502 enhancements:
-/// - Circular include detection via visited path set
+/// - Circular include detection via the stack of files being merged
 /// - Conflict warnings when keys are overwritten
 /// - Include provenance in warnings
 pub(super) fn merge_includes(base: ForjarConfig, base_dir: &Path) -> Result<ForjarConfig, String> {
-    let mut visited: HashSet<PathBuf> = HashSet::new();
-    merge_includes_inner(base, base_dir, &mut visited)
+    merge_includes_with(base, IncludeLoader::new(base_dir)?)
+}
+
+/// FJ-3313: Merge includes with an explicit loader (cache dir, offline mode).
+/// Writes changed include pins back to the lock file.
+pub(super) fn merge_includes_with(
+    base: ForjarConfig,
+    mut loader: IncludeLoader,
+) -> Result<ForjarConfig, String> {
+    let mut walk = IncludeWalk::default();
+    let origin = IncludeSource::Local(loader.root().to_path_buf());
+    let merged = merge_includes_inner(base, &origin, &mut loader, &mut walk, true)?;
+    loader.finish()?;
+    Ok(merged)
+}
+
+/// Files seen while merging an include tree, by source key.
+#[derive(Default)]
+struct IncludeWalk {
+    /// Files whose includes are being merged, outermost first.
+    stack: Vec<String>,
+    /// Every file merged so far.
+    merged: HashSet<String>,
+}
+
+/// Where merged keys came from: the nested include that defined them, or
+/// `label` for keys defined directly in the included file.
+struct Provenance {
+    nested: HashMap<String, String>,
+    label: String,
+}
+
+impl Provenance {
+    fn of(&self, key: &str) -> String {
+        self.nested
+            .get(key)
+            .cloned()
+            .unwrap_or_else(|| self.label.clone())
+    }
 }
 
 fn merge_includes_inner(
     base: ForjarConfig,
-    base_dir: &Path,
-    visited: &mut HashSet<PathBuf>,
+    origin: &IncludeSource,
+    loader: &mut IncludeLoader,
+    walk: &mut IncludeWalk,
+    top_level: bool,
 ) -> Result<ForjarConfig, String> {
     let mut merged = base.clone();
     merged.includes = vec![];
+    let mut listed = HashSet::new();
 
     for include_path in &base.includes {
-        let full_path = base_dir.join(include_path);
-        let canonical = full_path
-            .canonicalize()
-            .unwrap_or_else(|_| full_path.clone());
+        let source = IncludeSource::resolve(include_path, origin)
+            .map_err(|e| format!("include '{include_path}': {e}"))?;
+        let key = source.key(loader.root());
 
         // FJ-2502: Circular include detection
-        if !visited.insert(canonical.clone()) {
+        if walk.stack.contains(&key) {
+            return Err(format!(
+                "circular include detected: '{include_path}' includes itself"
+            ));
+        }
+        if !listed.insert(key.clone()) {
             return Err(format!(
-                "circular include detected: '{}' already included",
-                include_path
+                "circular include detected: '{include_path}' already included"
             ));
         }
+        // A file reached again through another include (a diamond) is
+        // already merged; merging it twice would only re-apply its keys.
+        if !walk.merged.insert(key.clone()) {
+            continue;
+        }
 
-        let included = super::parse_config_file(&full_path)
+        let content = loader
+            .load(&source)
             .map_err(|e| format!("include '{include_path}': {e}"))?;
+        let included =
+            super::parse_config(&content).map_err(|e| format!("include '{include_path}': {e}"))?;
 
-        // FJ-2502: Warn on nested includes (not supported)
-        if !included.includes.is_empty() {
-            eprintln!(
-                "warning: include '{include_path}' has its own includes (ignored — only single-level includes supported)"
-            );
-        }
+        // FJ-3313: Nested includes are merged into the included file first
+        walk.stack.push(key.clone());
+        let included = merge_includes_inner(included, &source.origin(), loader, walk, false);
+        walk.stack.pop();
+        let mut included = included?;
+        let provenance = Provenance {
+            nested: std::mem::take(&mut included.include_provenance),
+            label: if top_level { include_path.clone() } else { key },
+        };
 
         // Merge params (later overrides earlier)
         for (k, v) in included.params {
@@ -57,9 +114,10 @@ fn merge_includes_inner(
                 eprintln!("warning: include '{include_path}' overwrites param '{k}'");
             }
             merged.params.insert(k.clone(), v);
+            let p = format!("param:{k}");
             merged
                 .include_provenance
-                .insert(format!("param:{k}"), include_path.clone());
+                .insert(p.clone(), provenance.of(&p));
         }
 
         // Merge machines (later overrides earlier)
@@ -68,9 +126,10 @@ fn merge_includes_inner(
                 eprintln!("warning: include '{include_path}' overwrites machine '{k}'");
             }
             merged.machines.insert(k.clone(), v);
+            let p = format!("machine:{k}");
             merged
                 .include_provenance
-                .insert(format!("machine:{k}"), include_path.clone());
+                .insert(p.clone(), provenance.of(&p));
         }
 
         // Merge resources (later overrides earlier)
@@ -79,9 +138,10 @@ fn merge_includes_inner(
                 eprintln!("warning: include '{include_path}' overwrites resource '{k}'");
             }
             merged.resources.insert(k.clone(), v);
+            let p = format!("resource:{k}");
             merged
                 .include_provenance
-                .insert(format!("resource:{k}"), include_path.clone());
+                .insert(p.clone(), provenance.of(&p));
         }
 
         // Policy: replace wholesale from include
@@ -93,9 +153,10 @@ fn merge_includes_inner(
                 eprintln!("warning: include '{include_path}' overwrites output '{k}'");
             }
             merged.outputs.insert(k.clone(), v);
+            let p = format!("output:{k}");
             merged
                 .include_provenance
-                .insert(format!("output:{k}"), include_path.clone());
+                .insert(p.clone(), provenance.of(&p));
         }
 
         // Merge policy rules
@@ -107,9 +168,10 @@ fn merge_includes_inner(
                 eprintln!("warning: include '{include_path}' overwrites data source '{k}'");
             }
             merged.data.insert(k.clone(), v);
+            let p = format!("data:{k}");
             merged
                 .include_provenance
-                .insert(format!("data:{k}"), include_path.clone());
+                .insert(p.clone(), provenance.of(&p));
         }
     }
 
@@ -145,9 +207,43 @@ mod tests_includes_hardening {
 
         let config = parse_config_file(&a).unwrap();
         let result = merge_includes(config, dir.path());
-        // Nested includes are ignored (not processed), so no circular error
-        // The circular detection protects against bugs in future recursive support
-        assert!(result.is_ok());
+        // FJ-3313: Nested includes are followed, so a -> b -> a is a cycle
+        assert!(result.unwrap_err().contains("circular include"));
+    }
+
+    #[test]
+    fn diamond_include_merged_once() {
+        let dir = tempfile::tempdir().unwrap();
+        // a -> b -> common and a -> c -> common share one file, no cycle
+        let write = |name: &str, includes: &[&str], resource: &str| {
+            let path = dir.path().join(format!("{name}.yaml"));
+            std::fs::write(
+                &path,
+                format!(
+                    "version: \"1.0\"\nname: {name}\nincludes: [{}]\nresources:\n  {resource}:\n    type: package\n    provider: apt\n    packages: [{resource}]\n",
+                    includes.join(", ")
+                ),
+            )
+            .unwrap();
+            path
+        };
+        write("common", &[], "curl");
+        write("b", &["common.yaml"], "nginx");
+        write("c", &["common.yaml"], "vim");
+        let a = write("a", &["b.yaml", "c.yaml"], "git");
+
+        let config = parse_config_file(&a).unwrap();
+        let merged = merge_includes(config, dir.path()).unwrap();
+        let mut names: Vec<&str> = merged.resources.keys().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, ["curl", "git", "nginx", "vim"]);
+        assert_eq!(
+            merged
+                .include_provenance
+                .get("resource:curl")
+                .map(String::as_str),
+            Some("common.yaml")
+        );
     }
 
     #[test]
@@ -8,10 +8,16 @@
 
 mod expansion;
 mod format_validation;
+mod include_sources;
 mod includes;
 mod policy;
 mod recipes;
 mod resource_types;
+mod resource_types_b;
+mod resource_types_c;
+mod resource_types_d;
+mod resource_types_e;
+mod resource_types_f;
 pub(crate) mod unknown_fields;
 mod validation;
 
@@ -24,6 +30,8 @@ mod tests_expansion;
 #[cfg(test)]
 mod tests_format_validation;
 #[cfg(test)]
+mod tests_include_sources;
+#[cfg(test)]
 mod tests_includes;
 #[cfg(test)]
 mod tests_misc;
@@ -40,8 +48,18 @@ mod tests_policy;
 #[cfg(test)]
 mod tests_policy_b;
 #[cfg(test)]
+mod tests_resource_types_b;
+#[cfg(test)]
+mod tests_resource_types_c;
+#[cfg(test)]
 mod tests_resource_types_cov;
 #[cfg(test)]
+mod tests_resource_types_d;
+#[cfg(test)]
+mod tests_resource_types_e;
+#[cfg(test)]
+mod tests_resource_types_f;
+#[cfg(test)]
 mod tests_sarif;
 #[cfg(test)]
 mod tests_sudo_inference;
@@ -61,7 +79,7 @@ pub use expansion::expand_resources;
 pub use policy::{
     evaluate_policies, evaluate_policies_full, policy_check_to_json, policy_check_to_sarif,
 };
-pub use recipes::expand_recipes;
+pub use recipes::{expand_recipes, update_recipes};
 
 /// Recognized CPU architectures for the `arch` field.
 const KNOWN_ARCHITECTURES: &[&str] =
@@ -132,10 +150,14 @@ pub fn validate_config(config: &ForjarConfig) -> Vec<ValidationError> {
         check_sudo_inference(id, resource, config, &mut errors);
     }
 
+    resource_types_d::validate_one_per_machine(config, &mut errors);
+
     for (key, machine) in &config.machines {
         validation::validate_machine(key, machine, &mut errors);
     }
 
+    validation::validate_state_encryption(config, &mut errors);
+
     // FJ-2501: Format validation (mode, port, path, owner/group, addr)
     errors.extend(format_validation::validate_formats(config));
 
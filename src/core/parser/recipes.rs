@@ -1,12 +1,20 @@
 //! Recipe expansion: replace recipe-type resources with their expanded resources.
 
 use super::*;
-use std::collections::HashMap;
-use std::path::Path;
+use crate::core::recipe::registry::{parse_recipe_ref, Candidate};
+use crate::core::signing::{Scope, TrustPolicy};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 /// Maximum sub-recipe nesting depth before we bail out.
 const MAX_RECIPE_DEPTH: usize = 16;
 
+/// Resources and recipe outputs produced by one expansion pass.
+type ExpandedLevel = (
+    indexmap::IndexMap<String, Resource>,
+    indexmap::IndexMap<String, RecipeOutputBinding>,
+);
+
 /// Expand recipe resources into their constituent resources.
 ///
 /// Runs expansion passes in a loop until no `ResourceType::Recipe` entries
@@ -17,6 +25,8 @@ pub fn expand_recipes(config: &mut ForjarConfig, config_dir: Option<&Path>) -> R
     let base_dir = config_dir.unwrap_or_else(|| Path::new("."));
     let mut expansion_map: HashMap<String, String> = HashMap::new();
     let mut recipe_versions: HashMap<String, String> = HashMap::new();
+    let mut trust: Option<Option<TrustPolicy>> = None;
+    let mut resolved: Option<BTreeMap<String, Candidate>> = None;
 
     for depth in 0..MAX_RECIPE_DEPTH {
         let has_recipes = config
@@ -24,18 +34,30 @@ pub fn expand_recipes(config: &mut ForjarConfig, config_dir: Option<&Path>) -> R
             .values()
             .any(|r| r.resource_type == ResourceType::Recipe);
         if !has_recipes {
-            return Ok(());
+            return check_recipe_output_refs(config);
         }
 
-        let terminal_map = build_terminal_map(config, base_dir)?;
-        let expanded = expand_one_level(
+        // FJ-1432: loaded once, and only when there are recipes to expand.
+        if trust.is_none() {
+            trust = Some(crate::core::signing::trust_policy_for(config, base_dir)?);
+        }
+        // FJ-3314: versioned references are solved once, across the whole config.
+        if resolved.is_none() {
+            resolved = Some(recipe::resolve::resolve_recipes(config, base_dir, None)?);
+        }
+        let resolved = &*resolved.get_or_insert_with(BTreeMap::new);
+        let terminal_map = build_terminal_map(config, base_dir, resolved)?;
+        let (expanded, outputs) = expand_one_level(
             config,
             base_dir,
+            resolved,
             &terminal_map,
             &mut expansion_map,
             &mut recipe_versions,
+            trust.as_ref().and_then(|t| t.as_ref()),
         )?;
         config.resources = expanded;
+        config.recipe_outputs.extend(outputs);
 
         // FJ-2600: Rewrite depends_on for non-recipe resources that reference
         // recipe IDs — point them to the recipe's terminal resource instead.
@@ -50,6 +72,81 @@ pub fn expand_recipes(config: &mut ForjarConfig, config_dir: Option<&Path>) -> R
         }
     }
 
+    check_recipe_output_refs(config)
+}
+
+/// FJ-3315: Every `{{recipes.<id>.outputs.<name>}}` in a resource or config
+/// output must name an output of an expanded recipe.
+fn check_recipe_output_refs(config: &ForjarConfig) -> Result<(), String> {
+    let resources = config.resources.iter().map(|(id, resource)| {
+        let text = serde_yaml_ng::to_string(resource).unwrap_or_default();
+        (format!("resource '{id}'"), text)
+    });
+    let outputs = config
+        .outputs
+        .iter()
+        .map(|(name, output)| (format!("output '{name}'"), output.value.clone()));
+    for (owner, text) in resources.chain(outputs) {
+        for key in recipe::outputs::output_refs(&text) {
+            if !config.recipe_outputs.contains_key(key) {
+                return Err(format!(
+                    "{owner} references unknown recipe output 'recipes.{key}'"
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// FJ-3314: Re-resolve the versioned recipes of the config at `path`,
+/// moving `names` (every recipe when empty) to the highest version their
+/// constraints allow, and rewrite the recipe pins in the input lock.
+pub fn update_recipes(
+    path: &Path,
+    names: &[String],
+) -> Result<BTreeMap<String, Candidate>, String> {
+    let mut config = parse_config_file(path)?;
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    if !config.includes.is_empty() {
+        config = includes::merge_includes(config, base_dir)?;
+    }
+    recipe::resolve::resolve_recipes(&config, base_dir, Some(names))
+}
+
+/// FJ-3314: Name and file of a recipe reference. `name@constraint` uses the
+/// resolved registry version; a plain name uses `recipes/<name>.yaml`,
+/// falling back to a resolved registry recipe of that name.
+fn locate_recipe(
+    spec: &str,
+    base_dir: &Path,
+    resolved: &BTreeMap<String, Candidate>,
+) -> Result<(String, PathBuf), String> {
+    let (name, req) = parse_recipe_ref(spec)?;
+    let local = base_dir.join("recipes").join(format!("{name}.yaml"));
+    match resolved.get(name) {
+        Some(candidate) if req.is_some() || !local.exists() => {
+            Ok((name.to_string(), candidate.path.clone()))
+        }
+        _ if req.is_some() => Err(format!("recipe '{spec}' was not resolved from a registry")),
+        _ => Ok((name.to_string(), local)),
+    }
+}
+
+/// FJ-1432: Refuse a recipe the trust policy does not accept: when recipe
+/// signatures are required, `recipes/<name>.sig.json` must be a valid
+/// signature by a listed signer.
+fn check_recipe_signature(policy: &TrustPolicy, name: &str, path: &Path) -> Result<(), String> {
+    if !policy.requires(Scope::Recipe) {
+        return Ok(());
+    }
+    let content = std::fs::read(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    let sig = crate::core::signing::read_signature(&path.with_extension("sig.json"))?;
+    policy.check(
+        Scope::Recipe,
+        &format!("recipe '{name}'"),
+        &content,
+        sig.as_ref(),
+    )?;
     Ok(())
 }
 
@@ -57,17 +154,18 @@ pub fn expand_recipes(config: &mut ForjarConfig, config_dir: Option<&Path>) -> R
 fn build_terminal_map(
     config: &ForjarConfig,
     base_dir: &Path,
+    resolved: &BTreeMap<String, Candidate>,
 ) -> Result<HashMap<String, String>, String> {
     let mut terminal_map = HashMap::new();
     for (id, resource) in &config.resources {
         if resource.resource_type != ResourceType::Recipe {
             continue;
         }
-        let recipe_name = resource
+        let recipe_spec = resource
             .recipe
             .as_deref()
             .ok_or_else(|| format!("recipe resource '{id}' has no recipe name"))?;
-        let recipe_path = base_dir.join("recipes").join(format!("{recipe_name}.yaml"));
+        let (_, recipe_path) = locate_recipe(recipe_spec, base_dir, resolved)?;
         if recipe_path.exists() {
             if let Ok(recipe_file) = recipe::load_recipe(&recipe_path) {
                 if let Some(terminal) = recipe::recipe_terminal_id(id, &recipe_file) {
@@ -79,15 +177,20 @@ fn build_terminal_map(
     Ok(terminal_map)
 }
 
-/// Expand one level of recipe nesting, producing a new resource map.
+/// Expand one level of recipe nesting, producing a new resource map and the
+/// outputs of the recipes expanded at this level.
+#[allow(clippy::too_many_arguments)]
 fn expand_one_level(
     config: &ForjarConfig,
     base_dir: &Path,
+    resolved: &BTreeMap<String, Candidate>,
     terminal_map: &HashMap<String, String>,
     expansion_map: &mut HashMap<String, String>,
     recipe_versions: &mut HashMap<String, String>,
-) -> Result<indexmap::IndexMap<String, Resource>, String> {
+    trust: Option<&TrustPolicy>,
+) -> Result<ExpandedLevel, String> {
     let mut expanded = indexmap::IndexMap::new();
+    let mut outputs = indexmap::IndexMap::new();
 
     for (id, resource) in &config.resources {
         if resource.resource_type != ResourceType::Recipe {
@@ -95,17 +198,18 @@ fn expand_one_level(
             continue;
         }
 
-        let recipe_name = resource
+        let recipe_spec = resource
             .recipe
             .as_deref()
             .ok_or_else(|| format!("recipe resource '{id}' has no recipe name"))?;
+        let (recipe_name, recipe_path) = locate_recipe(recipe_spec, base_dir, resolved)?;
+        let recipe_name = recipe_name.as_str();
 
         detect_cycle(id, recipe_name, expansion_map)?;
         expansion_map.insert(id.clone(), recipe_name.to_string());
 
         let resolved_deps = resolve_recipe_deps(&resource.depends_on, terminal_map);
 
-        let recipe_path = base_dir.join("recipes").join(format!("{recipe_name}.yaml"));
         if !recipe_path.exists() {
             return Err(format!(
                 "recipe '{}' not found at {}",
@@ -114,6 +218,9 @@ fn expand_one_level(
             ));
         }
 
+        if let Some(policy) = trust {
+            check_recipe_signature(policy, recipe_name, &recipe_path)?;
+        }
         let recipe_file = recipe::load_recipe(&recipe_path)?;
         check_version_conflict(recipe_name, &recipe_file, recipe_versions, id)?;
 
@@ -124,13 +231,19 @@ fn expand_one_level(
             &resource.inputs,
             &resolved_deps,
         )?;
+        outputs.extend(recipe::outputs::recipe_outputs(
+            id,
+            &recipe_file,
+            &resource.inputs,
+            &expanded_resources,
+        )?);
 
         for (res_id, res) in expanded_resources {
             expanded.insert(res_id, res);
         }
     }
 
-    Ok(expanded)
+    Ok((expanded, outputs))
 }
 
 /// Cycle detection: walk ancestor chain looking for same recipe name.
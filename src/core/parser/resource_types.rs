@@ -1,5 +1,10 @@
 //! Type-specific required-field validation for each resource type.
 
+use super::resource_types_b::*;
+use super::resource_types_c::*;
+use super::resource_types_d::*;
+use super::resource_types_e::*;
+use super::resource_types_f::*;
 use super::*;
 
 /// Validate type-specific required fields for a resource.
@@ -25,6 +30,32 @@ pub(super) fn validate_resource_type(
         ResourceType::WasmBundle | ResourceType::Image => validate_file(id, resource, errors),
         ResourceType::Build => validate_build(id, resource, errors),
         ResourceType::GithubRelease => validate_github_release(id, resource, errors),
+        ResourceType::BinaryRelease => validate_binary_release(id, resource, errors),
+        ResourceType::PgRole => validate_pg_role(id, resource, errors),
+        ResourceType::PgDatabase => validate_pg_database(id, resource, errors),
+        ResourceType::PgExtension => validate_pg_extension(id, resource, errors),
+        ResourceType::ApparmorProfile => validate_apparmor_profile(id, resource, errors),
+        ResourceType::SelinuxBoolean => validate_selinux_boolean(id, resource, errors),
+        ResourceType::SelinuxFcontext => validate_selinux_fcontext(id, resource, errors),
+        ResourceType::SelinuxMode => validate_selinux_mode(id, resource, errors),
+        ResourceType::DockerNetwork => validate_docker_network(id, resource, errors),
+        ResourceType::DockerVolume => validate_docker_volume(id, resource, errors),
+        ResourceType::ContainerStack => validate_container_stack(id, resource, errors),
+        ResourceType::WaitFor => validate_wait_for(id, resource, errors),
+        ResourceType::Exec => validate_exec(id, resource, errors),
+        ResourceType::Swap => validate_swap(id, resource, errors),
+        ResourceType::Partition => validate_partition(id, resource, errors),
+        ResourceType::LvmVg => validate_lvm_vg(id, resource, errors),
+        ResourceType::LvmLv => validate_lvm_lv(id, resource, errors),
+        ResourceType::Filesystem => validate_filesystem(id, resource, errors),
+        ResourceType::WireguardInterface => validate_wireguard_interface(id, resource, errors),
+        ResourceType::WireguardPeer => validate_wireguard_peer(id, resource, errors),
+        ResourceType::NetworkInterface => validate_network_interface(id, resource, errors),
+        ResourceType::Certificate => validate_certificate(id, resource, errors),
+        ResourceType::System => validate_system(id, resource, errors),
+        ResourceType::ZfsPool => validate_zfs_pool(id, resource, errors),
+        ResourceType::ZfsDataset => validate_zfs_dataset(id, resource, errors),
+        ResourceType::ZfsSnapshotPolicy => validate_zfs_snapshot_policy(id, resource, errors),
     }
 }
 
@@ -173,6 +204,8 @@ fn validate_docker(id: &str, resource: &Resource, errors: &mut Vec<ValidationErr
             });
         }
     }
+    validate_container_runtime(id, "docker", resource, errors);
+    validate_docker_image(id, resource, errors);
 }
 
 fn validate_cron(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
@@ -293,6 +326,15 @@ fn validate_model(id: &str, resource: &Resource, errors: &mut Vec<ValidationErro
             });
         }
     }
+    if let Some(ref source) = resource.source {
+        if crate::core::store::hf_hub::is_hf_source(source) {
+            if let Err(e) = crate::core::store::hf_hub::parse_hf_source(source) {
+                errors.push(ValidationError {
+                    message: format!("resource '{id}' (model) has invalid source {e}"),
+                });
+            }
+        }
+    }
 }
 
 fn validate_gpu(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
@@ -311,6 +353,50 @@ fn validate_gpu(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>
             });
         }
     }
+    validate_gpu_nvidia_settings(id, resource, errors);
+}
+
+/// MIG profile names as `nvidia-smi mig -lgi` prints them (`3g.40gb`, `1g.10gb+me`).
+fn is_mig_profile(profile: &str) -> bool {
+    let (base, suffix) = profile.split_once('+').unwrap_or((profile, "a"));
+    let Some((slices, mem)) = base.split_once("g.") else {
+        return false;
+    };
+    let mem = mem.strip_suffix("gb").unwrap_or("");
+    !slices.is_empty()
+        && slices.bytes().all(|b| b.is_ascii_digit())
+        && !mem.is_empty()
+        && mem.bytes().all(|b| b.is_ascii_digit())
+        && !suffix.is_empty()
+        && suffix.bytes().all(|b| b.is_ascii_lowercase())
+}
+
+fn validate_gpu_nvidia_settings(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    let nvidia = resource.gpu_backend.as_deref().unwrap_or("nvidia") == "nvidia";
+    let uses_nvidia_settings = !resource.mig_profiles.is_empty()
+        || resource.power_limit_watts.is_some()
+        || resource.container_toolkit;
+    if uses_nvidia_settings && !nvidia {
+        errors.push(ValidationError {
+            message: format!(
+                "resource '{id}' (gpu) mig_profiles, power_limit_watts and container_toolkit require gpu_backend nvidia"
+            ),
+        });
+    }
+    for profile in &resource.mig_profiles {
+        if !is_mig_profile(profile) {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' (gpu) has invalid MIG profile '{profile}' (expected e.g. 3g.40gb)"
+                ),
+            });
+        }
+    }
+    if resource.power_limit_watts == Some(0) {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' (gpu) power_limit_watts must be greater than 0"),
+        });
+    }
 }
 
 fn validate_recipe(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
@@ -384,6 +470,13 @@ fn validate_github_release(id: &str, resource: &Resource, errors: &mut Vec<Valid
             message: format!("resource '{id}' (github_release) has no binary — specify the binary name to install"),
         });
     }
+    if let Some(provider) = resource.provider.as_deref().filter(|p| *p != "github") {
+        errors.push(ValidationError {
+            message: format!(
+                "resource '{id}' (github_release) always uses GitHub — use binary_release for provider '{provider}'"
+            ),
+        });
+    }
     if let Some(ref state) = resource.state {
         let valid = ["present", "absent"];
         if !valid.contains(&state.as_str()) {
@@ -0,0 +1,318 @@
+//! Type-specific validation for container-runtime and provisioning resource types.
+
+use super::*;
+
+/// Reject a `state` outside the allowed set for a resource type.
+pub(super) fn check_state(
+    id: &str,
+    kind: &str,
+    resource: &Resource,
+    valid: &[&str],
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Some(ref state) = resource.state {
+        if !valid.contains(&state.as_str()) {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) has invalid state '{state}' (expected: {})",
+                    valid.join(", ")
+                ),
+            });
+        }
+    }
+}
+
+/// Require the `name` field.
+pub(super) fn require_name(
+    id: &str,
+    kind: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    if resource.name.is_none() {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no name"),
+        });
+    }
+}
+
+/// Validate `runtime`, shared by docker, docker_network and docker_volume.
+pub(super) fn validate_container_runtime(
+    id: &str,
+    kind: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Some(ref rt) = resource.runtime {
+        if rt != "docker" && rt != "podman" {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) has invalid runtime '{rt}' (expected: docker, podman)"
+                ),
+            });
+        }
+    }
+}
+
+/// Validate docker image pinning and pull policy.
+pub(super) fn validate_docker_image(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Some(ref policy) = resource.pull_policy {
+        let valid = ["always", "missing", "never"];
+        if !valid.contains(&policy.as_str()) {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' (docker) has invalid pull_policy '{policy}' (expected: {})",
+                    valid.join(", ")
+                ),
+            });
+        }
+    }
+    if let Some(ref digest) = resource.image_digest {
+        if !digest.starts_with("sha256:") || digest.len() != 71 {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' (docker) image_digest '{digest}' must be sha256:<64 hex chars>"
+                ),
+            });
+        }
+    }
+    for label in &resource.labels {
+        if !label.contains('=') {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' (docker) label '{label}' must be key=value"),
+            });
+        }
+    }
+}
+
+pub(super) fn validate_docker_network(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    require_name(id, "docker_network", resource, errors);
+    validate_container_runtime(id, "docker_network", resource, errors);
+    check_state(
+        id,
+        "docker_network",
+        resource,
+        &["present", "absent"],
+        errors,
+    );
+    if resource.gateway.is_some() && resource.subnet.is_none() {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' (docker_network) has a gateway but no subnet"),
+        });
+    }
+}
+
+pub(super) fn validate_docker_volume(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    require_name(id, "docker_volume", resource, errors);
+    validate_container_runtime(id, "docker_volume", resource, errors);
+    check_state(
+        id,
+        "docker_volume",
+        resource,
+        &["present", "absent"],
+        errors,
+    );
+}
+
+pub(super) fn validate_container_stack(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    let kind = "container_stack";
+    require_name(id, kind, resource, errors);
+    validate_container_runtime(id, kind, resource, errors);
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    let mut push = |message: String| errors.push(ValidationError { message });
+
+    let format = resource.format.as_deref().unwrap_or("compose");
+    if format != "compose" && format != "quadlet" {
+        push(format!(
+            "resource '{id}' ({kind}) has invalid format '{format}' (expected: compose, quadlet)"
+        ));
+    }
+    if format == "quadlet" && resource.runtime.as_deref() == Some("docker") {
+        push(format!(
+            "resource '{id}' ({kind}) format 'quadlet' requires runtime podman"
+        ));
+    }
+
+    if resource.state.as_deref() == Some("absent") {
+        return;
+    }
+    let Some(ref content) = resource.content else {
+        push(format!(
+            "resource '{id}' ({kind}) has no content (compose spec)"
+        ));
+        return;
+    };
+    match crate::resources::container_stack::parse_stack(content)
+        .and_then(|spec| crate::resources::container_stack::service_order(&spec))
+    {
+        Ok(_) => {}
+        Err(e) => push(format!("resource '{id}' ({kind}): {e}")),
+    }
+}
+
+pub(super) fn validate_wait_for(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    let kind = "wait_for";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    let mut push = |message: String| errors.push(ValidationError { message });
+
+    let probes = [
+        resource.port.is_some(),
+        resource.url.is_some(),
+        resource.command.is_some(),
+        resource.path.is_some(),
+    ];
+    match probes.iter().filter(|set| **set).count() {
+        0 => push(format!(
+            "resource '{id}' ({kind}) needs one of port, url, path or command"
+        )),
+        1 => {}
+        _ => push(format!(
+            "resource '{id}' ({kind}) sets more than one of port, url, path, command"
+        )),
+    }
+    if let Some(ref port) = resource.port {
+        if port.parse::<u16>().map_or(true, |p| p == 0) {
+            push(format!(
+                "resource '{id}' ({kind}) port '{port}' is not a valid port"
+            ));
+        }
+    }
+    if resource.host.is_some() && resource.port.is_none() {
+        push(format!("resource '{id}' ({kind}) host requires port"));
+    }
+    if let Some(status) = resource.expect_status {
+        if !(100..=599).contains(&status) {
+            push(format!(
+                "resource '{id}' ({kind}) expect_status {status} is not an HTTP status"
+            ));
+        }
+        if resource.url.is_none() {
+            push(format!(
+                "resource '{id}' ({kind}) expect_status requires url"
+            ));
+        }
+    }
+    if resource.pattern.is_some() && resource.url.is_none() && resource.path.is_none() {
+        push(format!(
+            "resource '{id}' ({kind}) pattern requires url or path"
+        ));
+    }
+    if resource.interval == Some(0) {
+        push(format!(
+            "resource '{id}' ({kind}) interval must be at least 1"
+        ));
+    }
+    if resource.backoff == Some(0) {
+        push(format!(
+            "resource '{id}' ({kind}) backoff must be at least 1"
+        ));
+    }
+}
+
+pub(super) fn validate_exec(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    let kind = "exec";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    let mut push = |message: String| errors.push(ValidationError { message });
+
+    if resource.command.is_none() {
+        push(format!("resource '{id}' ({kind}) has no command"));
+    }
+    if resource.refreshonly && resource.triggers.is_empty() {
+        push(format!(
+            "resource '{id}' ({kind}) is refreshonly but has no triggers, so it never runs"
+        ));
+    }
+    for code in &resource.returns {
+        if !(0..=255).contains(code) {
+            push(format!(
+                "resource '{id}' ({kind}) returns {code} is not an exit code (0-255)"
+            ));
+        }
+    }
+    if let Some(ref creates) = resource.creates {
+        if !creates.starts_with('/') {
+            push(format!(
+                "resource '{id}' ({kind}) creates '{creates}' must be an absolute path"
+            ));
+        }
+    }
+}
+
+/// Validate a binary_release: release source, version constraint and signature.
+pub(super) fn validate_binary_release(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    use crate::core::release_source::{parse_constraint, ReleaseProvider, SignatureKind};
+    let mut err = |message: String| errors.push(ValidationError { message });
+    if resource.binary.is_none() {
+        err(format!(
+            "resource '{id}' (binary_release) has no binary — specify the binary name to install"
+        ));
+    }
+    match ReleaseProvider::parse(resource.provider.as_deref()) {
+        Err(e) => err(format!("resource '{id}' (binary_release) has {e}")),
+        Ok(ReleaseProvider::Static) => {
+            if resource.url.is_none() {
+                err(format!(
+                    "resource '{id}' (binary_release) provider static needs url (the release index)"
+                ));
+            }
+        }
+        Ok(provider) => {
+            if resource.repo.is_none() {
+                err(format!(
+                    "resource '{id}' (binary_release) has no repo — specify owner/repo"
+                ));
+            }
+            if provider == ReleaseProvider::Gitea && resource.url.is_none() {
+                err(format!(
+                    "resource '{id}' (binary_release) provider gitea needs url (the instance base URL)"
+                ));
+            }
+        }
+    }
+    let version = resource.version.as_deref().filter(|v| !v.contains("{{"));
+    if let Err(e) = parse_constraint(version) {
+        err(format!("resource '{id}' (binary_release) has {e}"));
+    }
+    if version.is_some() && crate::core::release_source::pinned_tag(resource).is_some() {
+        err(format!(
+            "resource '{id}' (binary_release) sets both tag and version — pin one"
+        ));
+    }
+    if let Some(ref kind) = resource.signature {
+        if let Err(e) = SignatureKind::parse(kind) {
+            err(format!("resource '{id}' (binary_release) has {e}"));
+        }
+        if resource.public_key.is_none() {
+            err(format!(
+                "resource '{id}' (binary_release) signature {kind} needs public_key"
+            ));
+        }
+    }
+    check_state(
+        id,
+        "binary_release",
+        resource,
+        &["present", "absent"],
+        errors,
+    );
+}
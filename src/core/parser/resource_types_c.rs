@@ -0,0 +1,388 @@
+//! Type-specific validation for disk provisioning and host networking resource types.
+
+use super::resource_types_b::{check_state, require_name};
+use super::*;
+use crate::resources::disk::{gpt_type_code, parse_size_bytes};
+
+/// Require an absolute `/dev/` path in `device`.
+fn require_device(id: &str, kind: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    match resource.device {
+        None => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no device"),
+        }),
+        Some(ref dev) if !dev.starts_with("/dev/") => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) device '{dev}' must be under /dev/"),
+        }),
+        Some(_) => {}
+    }
+}
+
+/// Reject a `size` without a binary unit suffix (K, M, G, T).
+fn check_size(id: &str, kind: &str, size: &str, errors: &mut Vec<ValidationError>) {
+    let has_unit = size.ends_with(|c: char| c.is_ascii_alphabetic());
+    if !has_unit || parse_size_bytes(size).is_none() {
+        errors.push(ValidationError {
+            message: format!(
+                "resource '{id}' ({kind}) has invalid size '{size}' (expected e.g. 512M, 20G, 1TiB)"
+            ),
+        });
+    }
+}
+
+pub(super) fn validate_swap(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    let kind = "swap";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    let is_device = resource
+        .path
+        .as_deref()
+        .is_some_and(crate::resources::swap::is_device);
+    match resource.size {
+        Some(ref size) if is_device => errors.push(ValidationError {
+            message: format!(
+                "resource '{id}' ({kind}) size '{size}' only applies to swap files, not devices"
+            ),
+        }),
+        Some(ref size) => check_size(id, kind, size, errors),
+        None => {}
+    }
+    if let Some(swappiness) = resource.swappiness {
+        if swappiness > 200 {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) swappiness {swappiness} exceeds 200"),
+            });
+        }
+    }
+}
+
+pub(super) fn validate_partition(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    let kind = "partition";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    require_device(id, kind, resource, errors);
+    match resource.number {
+        None => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no number"),
+        }),
+        Some(n) if !(1..=128).contains(&n) => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) number {n} is outside 1-128"),
+        }),
+        Some(_) => {}
+    }
+    if let Some(ref size) = resource.size {
+        check_size(id, kind, size, errors);
+    }
+    if let Some(ref part_type) = resource.part_type {
+        if gpt_type_code(part_type).is_none() {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) has invalid part_type '{part_type}' \
+                     (expected: linux, swap, efi, bios, lvm, raid or a 4-hex sgdisk code)"
+                ),
+            });
+        }
+    }
+}
+
+pub(super) fn validate_lvm_vg(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    let kind = "lvm_vg";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    require_name(id, kind, resource, errors);
+    if resource.physical_volumes.is_empty() && resource.state.as_deref() != Some("absent") {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no physical_volumes"),
+        });
+    }
+    for pv in &resource.physical_volumes {
+        if !pv.starts_with("/dev/") {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) physical volume '{pv}' must be under /dev/"
+                ),
+            });
+        }
+    }
+}
+
+pub(super) fn validate_lvm_lv(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    let kind = "lvm_lv";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    require_name(id, kind, resource, errors);
+    if resource.volume_group.is_none() {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no volume_group"),
+        });
+    }
+    match resource.size {
+        Some(ref size) if crate::resources::lvm_lv::is_extent_size(size) => {
+            let (pct, unit) = size.split_once('%').unwrap_or_default();
+            let pct_ok = pct.parse::<u32>().is_ok_and(|p| (1..=100).contains(&p));
+            if !pct_ok || !["FREE", "VG", "PVS"].contains(&unit) {
+                errors.push(ValidationError {
+                    message: format!(
+                        "resource '{id}' ({kind}) has invalid size '{size}' (expected e.g. 100%FREE, 50%VG)"
+                    ),
+                });
+            }
+        }
+        Some(ref size) => check_size(id, kind, size, errors),
+        None => {}
+    }
+}
+
+pub(super) fn validate_filesystem(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    let kind = "filesystem";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    require_device(id, kind, resource, errors);
+    if resource.fs_type.is_none() && resource.state.as_deref() != Some("absent") {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no fs_type"),
+        });
+    }
+}
+
+/// Require an interface name of 1-15 alphanumerics, `-`, `_` or `.`.
+fn check_iface_name(id: &str, kind: &str, name: &str, errors: &mut Vec<ValidationError>) {
+    let valid = !name.is_empty()
+        && name.len() <= 15
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        errors.push(ValidationError {
+            message: format!(
+                "resource '{id}' ({kind}) name '{name}' is not a valid interface name (max 15 chars)"
+            ),
+        });
+    }
+}
+
+/// Require a CIDR-form address (`10.0.0.1/24`, `fd00::1/64`); templates are checked once resolved.
+fn check_cidr(id: &str, kind: &str, field: &str, value: &str, errors: &mut Vec<ValidationError>) {
+    if value.contains("{{") {
+        return;
+    }
+    let valid = value.split_once('/').is_some_and(|(addr, prefix)| {
+        addr.parse::<std::net::IpAddr>().is_ok() && prefix.parse::<u8>().is_ok_and(|p| p <= 128)
+    });
+    if !valid {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) {field} '{value}' is not in CIDR form"),
+        });
+    }
+}
+
+pub(super) fn validate_wireguard_interface(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    let kind = "wireguard_interface";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    if let Some(ref name) = resource.name {
+        check_iface_name(id, kind, name, errors);
+    }
+    for addr in &resource.addresses {
+        check_cidr(id, kind, "address", addr, errors);
+    }
+    if let Some(ref port) = resource.port {
+        if !port.parse::<u16>().is_ok_and(|p| p > 0) {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) has invalid port '{port}'"),
+            });
+        }
+    }
+}
+
+pub(super) fn validate_wireguard_peer(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    let kind = "wireguard_peer";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    match resource.public_key {
+        None => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no public_key"),
+        }),
+        // Templated keys are checked after resolution, on the target
+        Some(ref key) if !key.contains("{{") => {
+            let valid = key.len() == 44
+                && key.ends_with('=')
+                && key[..43]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/');
+            if !valid {
+                errors.push(ValidationError {
+                    message: format!(
+                        "resource '{id}' ({kind}) public_key is not a base64 WireGuard key"
+                    ),
+                });
+            }
+        }
+        Some(_) => {}
+    }
+    if resource.allowed_ips.is_empty() && resource.state.as_deref() != Some("absent") {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no allowed_ips"),
+        });
+    }
+    for net in &resource.allowed_ips {
+        check_cidr(id, kind, "allowed_ips entry", net, errors);
+    }
+    if let Some(ref endpoint) = resource.endpoint {
+        let port_ok = endpoint
+            .rsplit_once(':')
+            .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok());
+        if !port_ok {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) endpoint '{endpoint}' must be host:port"
+                ),
+            });
+        }
+    }
+    if let Some(keepalive) = resource.persistent_keepalive {
+        if keepalive > 65535 {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) persistent_keepalive {keepalive} exceeds 65535"
+                ),
+            });
+        }
+    }
+}
+
+/// Require a bare IP address; templates are checked once resolved.
+fn check_ip(id: &str, kind: &str, field: &str, value: &str, errors: &mut Vec<ValidationError>) {
+    if !value.contains("{{") && value.parse::<std::net::IpAddr>().is_err() {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) {field} '{value}' is not an IP address"),
+        });
+    }
+}
+
+const BOND_MODES: &[&str] = &[
+    "balance-rr",
+    "active-backup",
+    "balance-xor",
+    "broadcast",
+    "802.3ad",
+    "balance-tlb",
+    "balance-alb",
+];
+
+pub(super) fn validate_network_interface(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    let kind = "network_interface";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    require_name(id, kind, resource, errors);
+    if let Some(ref name) = resource.name {
+        check_iface_name(id, kind, name, errors);
+    }
+    if let Some(ref provider) = resource.provider {
+        if !["networkd", "netplan"].contains(&provider.as_str()) {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) has invalid provider '{provider}' (expected: networkd, netplan)"
+                ),
+            });
+        }
+    }
+    let link_kind = resource.kind.as_deref().unwrap_or("ethernet");
+    if !["ethernet", "vlan", "bond", "bridge"].contains(&link_kind) {
+        errors.push(ValidationError {
+            message: format!(
+                "resource '{id}' ({kind}) has invalid kind '{link_kind}' (expected: ethernet, vlan, bond, bridge)"
+            ),
+        });
+    }
+    match (link_kind, resource.vlan_id) {
+        ("vlan", None) => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) kind vlan needs vlan_id"),
+        }),
+        ("vlan", Some(vid)) if !(1..=4094).contains(&vid) => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) vlan_id {vid} is outside 1-4094"),
+        }),
+        ("vlan", Some(_)) => {}
+        (_, Some(_)) => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) vlan_id only applies to kind vlan"),
+        }),
+        (_, None) => {}
+    }
+    if link_kind == "vlan" && resource.interface.is_none() {
+        errors.push(ValidationError {
+            message: format!(
+                "resource '{id}' ({kind}) kind vlan needs its parent link in interface"
+            ),
+        });
+    }
+    if link_kind == "bond" && resource.members.is_empty() {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) kind bond has no members"),
+        });
+    }
+    if !resource.members.is_empty() && !["bond", "bridge"].contains(&link_kind) {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) members only apply to kind bond or bridge"),
+        });
+    }
+    if let Some(ref mode) = resource.bond_mode {
+        if link_kind != "bond" {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) bond_mode only applies to kind bond"),
+            });
+        } else if !BOND_MODES.contains(&mode.as_str()) {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) has invalid bond_mode '{mode}' (expected one of: {})",
+                    BOND_MODES.join(", ")
+                ),
+            });
+        }
+    }
+    if let Some(ref dhcp) = resource.dhcp {
+        if !["yes", "no", "ipv4", "ipv6"].contains(&dhcp.as_str()) {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) has invalid dhcp '{dhcp}' (expected: yes, no, ipv4, ipv6)"
+                ),
+            });
+        }
+    }
+    if let Some(mtu) = resource.mtu {
+        if !(68..=65535).contains(&mtu) {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) mtu {mtu} is outside 68-65535"),
+            });
+        }
+    }
+    for addr in &resource.addresses {
+        check_cidr(id, kind, "address", addr, errors);
+    }
+    if let Some(ref gw) = resource.gateway {
+        check_ip(id, kind, "gateway", gw, errors);
+    }
+    for dns in &resource.dns {
+        check_ip(id, kind, "dns server", dns, errors);
+    }
+    for route in &resource.routes {
+        let (dest, via) = crate::resources::network_interface::parse_route(route);
+        if dest != "default" {
+            check_cidr(id, kind, "route destination", dest, errors);
+        }
+        if let Some(gw) = via {
+            check_ip(id, kind, "route gateway", gw, errors);
+        }
+    }
+    if resource.revert_after == Some(0) {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) revert_after must be at least 1 second"),
+        });
+    }
+}
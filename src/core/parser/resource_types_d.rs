@@ -0,0 +1,371 @@
+//! Type-specific validation for security, host identity and MAC policy resource types.
+
+use super::resource_types_b::check_state;
+use super::*;
+use crate::resources::{certificate, selinux_fcontext};
+
+/// Require an absolute path in an optional field.
+fn require_abs_path(
+    id: &str,
+    kind: &str,
+    field: &str,
+    value: Option<&str>,
+    errors: &mut Vec<ValidationError>,
+) {
+    match value {
+        None => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no {field}"),
+        }),
+        Some(p) if !p.starts_with('/') => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) {field} '{p}' must be an absolute path"),
+        }),
+        Some(_) => {}
+    }
+}
+
+pub(super) fn validate_certificate(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    let kind = "certificate";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    require_abs_path(id, kind, "path", resource.path.as_deref(), errors);
+    require_abs_path(id, kind, "key_path", resource.key_path.as_deref(), errors);
+    if let Some(ref chain) = resource.chain_path {
+        if !chain.starts_with('/') {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) chain_path '{chain}' must be an absolute path"
+                ),
+            });
+        }
+    }
+    if resource.state.as_deref() == Some("absent") {
+        return;
+    }
+
+    match certificate::issuer(resource) {
+        "self-signed" => {
+            if resource.chain_path.is_some() {
+                errors.push(ValidationError {
+                    message: format!(
+                        "resource '{id}' ({kind}) chain_path needs issuer local or ca"
+                    ),
+                });
+            }
+        }
+        "local" => {}
+        "ca" => {
+            if resource.ca_cert.is_none() || resource.ca_key.is_none() {
+                errors.push(ValidationError {
+                    message: format!("resource '{id}' ({kind}) issuer ca needs ca_cert and ca_key"),
+                });
+            }
+        }
+        other => errors.push(ValidationError {
+            message: format!(
+                "resource '{id}' ({kind}) has invalid issuer '{other}' (expected: self-signed, local, ca)"
+            ),
+        }),
+    }
+    if resource.common_name.is_none() && resource.sans.is_empty() {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) needs common_name or sans"),
+        });
+    }
+    for san in &resource.sans {
+        let valid = san.parse::<std::net::IpAddr>().is_ok()
+            || (!san.is_empty()
+                && san
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '*')));
+        if !valid && !san.contains("{{") {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) san '{san}' is not a DNS name or IP"),
+            });
+        }
+    }
+    if let Some(ref key_type) = resource.key_type {
+        if !["ec", "rsa"].contains(&key_type.as_str()) {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) has invalid key_type '{key_type}' (expected: ec, rsa)"
+                ),
+            });
+        }
+    }
+    let validity = certificate::validity_days(resource);
+    if validity == 0 {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) validity_days must be at least 1"),
+        });
+    }
+    if let Some(ref window) = resource.renew_before {
+        match certificate::parse_window(window) {
+            None => errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) has invalid renew_before '{window}' (expected e.g. 30d, 72h)"
+                ),
+            }),
+            Some(secs) if secs >= u64::from(validity) * 86400 => errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) renew_before '{window}' is not shorter than validity_days {validity}"
+                ),
+            }),
+            Some(_) => {}
+        }
+    }
+    // The key must never be readable by others
+    if let Some(ref mode) = resource.mode {
+        let others = mode.chars().last().and_then(|c| c.to_digit(8));
+        if others != Some(0) {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) key mode '{mode}' must not grant access to others"
+                ),
+            });
+        }
+    }
+}
+
+/// RFC 1123 hostname: dot-separated labels of 1-63 alphanumerics or `-`,
+/// not starting or ending with `-`.
+fn is_valid_hostname(name: &str) -> bool {
+    name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Check an optional field against a character set (templates are skipped).
+fn check_chars(
+    id: &str,
+    field: &str,
+    value: Option<&str>,
+    allowed: fn(char) -> bool,
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Some(v) = value {
+        if !v.contains("{{") && (v.is_empty() || !v.chars().all(allowed)) {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' (system) has invalid {field} '{v}'"),
+            });
+        }
+    }
+}
+
+pub(super) fn validate_system(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    let kind = "system";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    if let Some(ref hostname) = resource.hostname {
+        if !hostname.contains("{{") && !is_valid_hostname(hostname) {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) has invalid hostname '{hostname}'"),
+            });
+        }
+    }
+    if let Some(ref tz) = resource.timezone {
+        if tz.starts_with('/') || tz.contains("..") {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) has invalid timezone '{tz}'"),
+            });
+        }
+    }
+    check_chars(
+        id,
+        "timezone",
+        resource.timezone.as_deref(),
+        |c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+'),
+        errors,
+    );
+    check_chars(
+        id,
+        "locale",
+        resource.locale.as_deref(),
+        |c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '@'),
+        errors,
+    );
+    check_chars(
+        id,
+        "keyboard",
+        resource.keyboard.as_deref(),
+        |c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'),
+        errors,
+    );
+    for server in &resource.ntp_servers {
+        let valid = server.parse::<std::net::IpAddr>().is_ok() || is_valid_hostname(server);
+        if !valid && !server.contains("{{") {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) ntp server '{server}' is not a hostname or IP"
+                ),
+            });
+        }
+    }
+    if let Some(ref provider) = resource.provider {
+        if !["timesyncd", "chrony"].contains(&provider.as_str()) {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) has invalid provider '{provider}' (expected: timesyncd, chrony)"
+                ),
+            });
+        }
+    }
+}
+
+/// At most one `system` and one `selinux_mode` resource may target each
+/// machine.
+pub(super) fn validate_one_per_machine(config: &ForjarConfig, errors: &mut Vec<ValidationError>) {
+    let mut seen: std::collections::HashMap<(String, &str), &str> =
+        std::collections::HashMap::new();
+    for (id, resource) in &config.resources {
+        if !matches!(
+            resource.resource_type,
+            ResourceType::System | ResourceType::SelinuxMode
+        ) {
+            continue;
+        }
+        let rtype = resource.resource_type.to_string();
+        for machine in resource.machine.iter() {
+            match seen.get(&(rtype.clone(), machine)) {
+                Some(first) => errors.push(ValidationError {
+                    message: format!(
+                        "machine '{machine}' has more than one {rtype} resource ('{first}' and '{id}')"
+                    ),
+                }),
+                None => {
+                    seen.insert((rtype.clone(), machine), id);
+                }
+            }
+        }
+    }
+}
+
+/// AppArmor profile names as `aa-status` prints them: a path or a bare
+/// name, without quotes, whitespace or control characters.
+fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_graphic() && !matches!(c, '\'' | '"' | '\\'))
+}
+
+pub(super) fn validate_apparmor_profile(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    let kind = "apparmor_profile";
+    check_state(
+        id,
+        kind,
+        resource,
+        &["enforce", "complain", "disabled", "absent"],
+        errors,
+    );
+    match resource.name.as_deref() {
+        None => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no name"),
+        }),
+        Some(name) if !name.contains("{{") && !is_valid_profile_name(name) => {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) has invalid profile name '{name}'"),
+            })
+        }
+        Some(_) => {}
+    }
+    if let Some(ref path) = resource.path {
+        if !path.starts_with('/') || path.ends_with('/') {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) path '{path}' must be an absolute file path"
+                ),
+            });
+        }
+    }
+}
+
+/// SELinux identifiers (booleans, types): lowercase letters, digits, `_`.
+fn is_selinux_ident(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+pub(super) fn validate_selinux_boolean(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    let kind = "selinux_boolean";
+    check_state(id, kind, resource, &["present"], errors);
+    match resource.name.as_deref() {
+        None => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no name"),
+        }),
+        Some(name) if !is_selinux_ident(name) => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has invalid boolean name '{name}'"),
+        }),
+        Some(_) => {}
+    }
+    if resource.enabled.is_none() {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no enabled value"),
+        });
+    }
+}
+
+pub(super) fn validate_selinux_fcontext(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    let kind = "selinux_fcontext";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    require_abs_path(id, kind, "path", resource.path.as_deref(), errors);
+    if resource.path.as_deref().is_some_and(|p| p.contains('\'')) {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) path must not contain quotes"),
+        });
+    }
+    if resource.state.as_deref() != Some("absent") {
+        match resource.selinux_type.as_deref() {
+            None => errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) has no selinux_type"),
+            }),
+            Some(t) if !is_selinux_ident(t) => errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) has invalid selinux_type '{t}'"),
+            }),
+            Some(_) => {}
+        }
+    }
+    if resource.target.is_some() {
+        require_abs_path(id, kind, "target", resource.target.as_deref(), errors);
+    } else if resource.path.is_some() && selinux_fcontext::relabel_target(resource).is_none() {
+        errors.push(ValidationError {
+            message: format!(
+                "resource '{id}' ({kind}) path is a regular expression; set target to the path to relabel"
+            ),
+        });
+    }
+}
+
+pub(super) fn validate_selinux_mode(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    check_state(
+        id,
+        "selinux_mode",
+        resource,
+        &["enforcing", "permissive", "disabled"],
+        errors,
+    );
+}
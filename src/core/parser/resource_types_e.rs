@@ -0,0 +1,144 @@
+//! Type-specific validation for ZFS storage resource types.
+
+use super::resource_types_b::{check_state, require_name};
+use super::*;
+use crate::resources::zfs_dataset::is_size_property;
+
+/// `zpool create` keywords allowed in `vdevs` besides device paths.
+const VDEV_KEYWORDS: &[&str] = &[
+    "mirror", "raidz", "raidz1", "raidz2", "raidz3", "spare", "log", "cache", "special", "dedup",
+];
+
+/// Pool/dataset names: alphanumerics and `_ - . :`, components split by `/`.
+fn is_valid_zfs_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('/').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+        })
+}
+
+fn check_zfs_name(
+    id: &str,
+    kind: &str,
+    field: &str,
+    name: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    if !name.contains("{{") && !is_valid_zfs_name(name) {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has invalid {field} '{name}'"),
+        });
+    }
+}
+
+/// Property names are lowercase identifiers (user properties may contain `:`);
+/// values must fit in a single-quoted shell word.
+fn check_properties(id: &str, kind: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    for (key, value) in &resource.properties {
+        let key_ok = !key.is_empty()
+            && key.chars().all(|c| {
+                c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | ':' | '.' | '-')
+            });
+        if !key_ok {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) has invalid property name '{key}'"),
+            });
+        }
+        if value.is_empty() || value.contains('\'') || value.contains('\n') {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) property '{key}' has invalid value '{value}'"
+                ),
+            });
+        } else if kind == "zfs_dataset"
+            && is_size_property(key)
+            && value != "none"
+            && !value.contains("{{")
+            && crate::resources::disk::parse_size_bytes(value).is_none()
+        {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) property '{key}' has invalid size '{value}' (expected e.g. 128K, 500G or none)"
+                ),
+            });
+        }
+    }
+}
+
+pub(super) fn validate_zfs_pool(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    let kind = "zfs_pool";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    require_name(id, kind, resource, errors);
+    if let Some(ref name) = resource.name {
+        check_zfs_name(id, kind, "name", name, errors);
+        if name.contains('/') || VDEV_KEYWORDS.contains(&name.as_str()) {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) name '{name}' is not a valid pool name"),
+            });
+        }
+    }
+    if resource.state.as_deref() != Some("absent") && resource.vdevs.is_empty() {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no vdevs"),
+        });
+    }
+    for word in resource.vdevs.iter().flat_map(|g| g.split_whitespace()) {
+        let keyword = VDEV_KEYWORDS.contains(&word) || word.starts_with("draid");
+        if !keyword && !word.starts_with('/') && !word.contains("{{") {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' ({kind}) vdev '{word}' must be a device path or a vdev type"
+                ),
+            });
+        }
+    }
+    check_properties(id, kind, resource, errors);
+}
+
+pub(super) fn validate_zfs_dataset(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    let kind = "zfs_dataset";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    require_name(id, kind, resource, errors);
+    if let Some(ref name) = resource.name {
+        check_zfs_name(id, kind, "name", name, errors);
+    }
+    check_properties(id, kind, resource, errors);
+}
+
+pub(super) fn validate_zfs_snapshot_policy(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    let kind = "zfs_snapshot_policy";
+    check_state(id, kind, resource, &["present", "absent"], errors);
+    match resource.dataset {
+        None => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no dataset"),
+        }),
+        Some(ref ds) => check_zfs_name(id, kind, "dataset", ds, errors),
+    }
+    if let Some(ref label) = resource.name {
+        if label.is_empty()
+            || !label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+        {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) has invalid name '{label}'"),
+            });
+        }
+    }
+    if resource.keep == Some(0) {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) keep must be at least 1"),
+        });
+    }
+}
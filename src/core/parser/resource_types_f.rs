@@ -0,0 +1,124 @@
+//! Type-specific validation for PostgreSQL resource types.
+
+use super::resource_types_b::check_state;
+use super::*;
+use crate::resources::pg_database::parse_grant;
+use crate::resources::pg_role::ROLE_ATTRIBUTES;
+
+/// Role, database and extension names: letters, digits, `_ - .`, at most
+/// 63 bytes (PostgreSQL's identifier limit).
+fn is_pg_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 63
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.'))
+}
+
+fn check_pg_name(
+    id: &str,
+    kind: &str,
+    field: &str,
+    value: Option<&str>,
+    errors: &mut Vec<ValidationError>,
+) {
+    match value {
+        None => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has no {field}"),
+        }),
+        Some(v) if !v.contains("{{") && !is_pg_name(v) => errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has invalid {field} '{v}'"),
+        }),
+        Some(_) => {}
+    }
+}
+
+/// `host` and `port` end up single-quoted on the `psql` command line.
+fn check_connection(id: &str, kind: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    if let Some(ref port) = resource.port {
+        if !port.contains("{{") && port.parse::<u16>().is_err() {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' ({kind}) has invalid port '{port}'"),
+            });
+        }
+    }
+    if resource.host.as_deref().is_some_and(|h| h.contains('\'')) {
+        errors.push(ValidationError {
+            message: format!("resource '{id}' ({kind}) has invalid host"),
+        });
+    }
+}
+
+pub(super) fn validate_pg_role(id: &str, resource: &Resource, errors: &mut Vec<ValidationError>) {
+    check_pg_name(id, "pg_role", "name", resource.name.as_deref(), errors);
+    for attr in &resource.attributes {
+        let upper = attr.to_ascii_uppercase();
+        if upper != "NOINHERIT" && !ROLE_ATTRIBUTES.contains(&upper.as_str()) {
+            errors.push(ValidationError {
+                message: format!(
+                    "resource '{id}' (pg_role) has unknown attribute '{attr}' (expected: {}, NOINHERIT)",
+                    ROLE_ATTRIBUTES.join(", ")
+                ),
+            });
+        }
+    }
+    for group in &resource.groups {
+        check_pg_name(id, "pg_role", "group", Some(group), errors);
+    }
+    check_connection(id, "pg_role", resource, errors);
+    check_state(id, "pg_role", resource, &["present", "absent"], errors);
+}
+
+pub(super) fn validate_pg_database(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    check_pg_name(id, "pg_database", "name", resource.name.as_deref(), errors);
+    if resource.owner.is_some() {
+        check_pg_name(
+            id,
+            "pg_database",
+            "owner",
+            resource.owner.as_deref(),
+            errors,
+        );
+    }
+    if let Some(ref encoding) = resource.encoding {
+        if !encoding
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+        {
+            errors.push(ValidationError {
+                message: format!("resource '{id}' (pg_database) has invalid encoding '{encoding}'"),
+            });
+        }
+    }
+    for grant in &resource.grants {
+        match parse_grant(grant) {
+            Ok((role, _)) => check_pg_name(id, "pg_database", "grant role", Some(&role), errors),
+            Err(e) => errors.push(ValidationError {
+                message: format!("resource '{id}' (pg_database) {e}"),
+            }),
+        }
+    }
+    check_connection(id, "pg_database", resource, errors);
+    check_state(id, "pg_database", resource, &["present", "absent"], errors);
+}
+
+pub(super) fn validate_pg_extension(
+    id: &str,
+    resource: &Resource,
+    errors: &mut Vec<ValidationError>,
+) {
+    check_pg_name(id, "pg_extension", "name", resource.name.as_deref(), errors);
+    check_pg_name(
+        id,
+        "pg_extension",
+        "database",
+        resource.database.as_deref(),
+        errors,
+    );
+    check_connection(id, "pg_extension", resource, errors);
+    check_state(id, "pg_extension", resource, &["present", "absent"], errors);
+}
@@ -0,0 +1,283 @@
+//! FJ-3313: Recursive, remote and pinned includes.
+
+use super::include_sources::{IncludeLoader, IncludeSource};
+use super::includes::merge_includes_with;
+use super::*;
+use crate::core::test_http;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+type Files = Arc<Mutex<HashMap<String, String>>>;
+
+/// Serve `files` by request path over plain HTTP. Returns the base URL and
+/// a request counter.
+fn serve(files: Files) -> (String, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let base = test_http::serve(move |request| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        match files.lock().unwrap().get(&request.target) {
+            Some(body) => test_http::response("200 OK", &[], body.as_bytes()),
+            None => test_http::response("404 Not Found", &[], b""),
+        }
+    });
+    (base, hits)
+}
+
+fn package(name: &str, pkg: &str) -> String {
+    format!(
+        "version: \"1.0\"\nname: {name}\nresources:\n  {name}:\n    type: package\n    provider: apt\n    packages: [{pkg}]\n"
+    )
+}
+
+fn root(includes: &[&str]) -> ForjarConfig {
+    let list: String = includes.iter().map(|i| format!("  - {i}\n")).collect();
+    parse_config(&format!(
+        "version: \"1.0\"\nname: root\nincludes:\n{list}resources: {{}}\n"
+    ))
+    .unwrap()
+}
+
+fn loader(dir: &Path, cache: &Path, offline: bool) -> IncludeLoader {
+    IncludeLoader::new(dir)
+        .unwrap()
+        .with_cache_dir(cache)
+        .with_offline(offline)
+}
+
+fn lock_pins(dir: &Path) -> Vec<String> {
+    let lock =
+        crate::core::store::lockfile::read_lockfile(&dir.join("forjar.inputs.lock.yaml")).unwrap();
+    lock.pins.keys().cloned().collect()
+}
+
+#[test]
+fn resolve_relative_sources() {
+    let local = IncludeSource::Local(PathBuf::from("/cfg"));
+    assert_eq!(
+        IncludeSource::resolve("base/a.yaml", &local).unwrap(),
+        IncludeSource::Local(PathBuf::from("/cfg/base/a.yaml"))
+    );
+    let url = IncludeSource::Url("https://example.com/cfg/base.yaml".to_string());
+    assert_eq!(
+        IncludeSource::resolve("../shared/net.yaml", &url).unwrap(),
+        IncludeSource::Url("https://example.com/shared/net.yaml".to_string())
+    );
+}
+
+#[test]
+fn resolve_git_sources() {
+    let local = IncludeSource::Local(PathBuf::from("/cfg"));
+    let git = IncludeSource::resolve(
+        "git+https://git.example.com/org/baseline.git@v1.2.0//stacks/web.yaml",
+        &local,
+    )
+    .unwrap();
+    assert_eq!(
+        git,
+        IncludeSource::Git {
+            repo: "https://git.example.com/org/baseline.git".to_string(),
+            reference: "v1.2.0".to_string(),
+            path: "stacks/web.yaml".to_string(),
+        }
+    );
+    assert_eq!(
+        IncludeSource::resolve("../common.yaml", &git).unwrap(),
+        IncludeSource::Git {
+            repo: "https://git.example.com/org/baseline.git".to_string(),
+            reference: "v1.2.0".to_string(),
+            path: "common.yaml".to_string(),
+        }
+    );
+    let default_path =
+        IncludeSource::resolve("git+https://git.example.com/org/baseline.git@main", &local)
+            .unwrap();
+    assert!(matches!(default_path, IncludeSource::Git { ref path, .. } if path == "forjar.yaml"));
+    let err =
+        IncludeSource::resolve("git+https://git.example.com/org/baseline.git", &local).unwrap_err();
+    assert!(err.contains("must name a ref"), "{err}");
+}
+
+#[test]
+fn git_sources_reject_option_injection() {
+    let local = IncludeSource::Local(PathBuf::from("/cfg"));
+    for spec in [
+        "git+--upload-pack=touch /tmp/pwned@main",
+        "git+https://git.example.com/org/baseline.git@--upload-pack=x",
+    ] {
+        let err = IncludeSource::resolve(spec, &local).unwrap_err();
+        assert!(err.contains("must not start with '-'"), "{spec}: {err}");
+    }
+}
+
+#[test]
+fn recursive_local_includes_with_provenance() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("base/sub")).unwrap();
+    std::fs::write(
+        dir.path().join("base/web.yaml"),
+        package("web", "nginx").replace("resources:", "includes:\n  - sub/tools.yaml\nresources:"),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("base/sub/tools.yaml"),
+        package("tools", "curl"),
+    )
+    .unwrap();
+
+    let cache = tempfile::tempdir().unwrap();
+    let merged = merge_includes_with(
+        root(&["base/web.yaml"]),
+        loader(dir.path(), cache.path(), false),
+    )
+    .unwrap();
+    assert!(merged.resources.contains_key("web"));
+    assert!(merged.resources.contains_key("tools"));
+    assert_eq!(merged.include_provenance["resource:web"], "base/web.yaml");
+    assert_eq!(
+        merged.include_provenance["resource:tools"],
+        "base/sub/tools.yaml"
+    );
+    // Local-only configs do not grow a lock file
+    assert!(!dir.path().join("forjar.inputs.lock.yaml").exists());
+}
+
+#[test]
+fn local_pins_refresh_existing_lock_file() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.yaml"), package("a", "vim")).unwrap();
+    std::fs::write(
+        dir.path().join("forjar.inputs.lock.yaml"),
+        "schema: '1.0'\npins: {}\n",
+    )
+    .unwrap();
+    let cache = tempfile::tempdir().unwrap();
+    merge_includes_with(root(&["a.yaml"]), loader(dir.path(), cache.path(), false)).unwrap();
+    assert_eq!(lock_pins(dir.path()), vec!["include:a.yaml".to_string()]);
+}
+
+#[test]
+fn url_include_is_pinned_then_served_from_cache() {
+    let files: Files = Arc::default();
+    files
+        .lock()
+        .unwrap()
+        .insert("/stacks/web.yaml".to_string(), package("web", "nginx"));
+    let (base, hits) = serve(files.clone());
+    let url = format!("{base}/stacks/web.yaml");
+    let dir = tempfile::tempdir().unwrap();
+    let cache = tempfile::tempdir().unwrap();
+
+    let merged =
+        merge_includes_with(root(&[&url]), loader(dir.path(), cache.path(), false)).unwrap();
+    assert!(merged.resources.contains_key("web"));
+    assert_eq!(merged.include_provenance["resource:web"], url);
+    assert_eq!(lock_pins(dir.path()), vec![format!("include:{url}")]);
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    // Pinned and cached: no fetch, even offline and after upstream changes
+    files
+        .lock()
+        .unwrap()
+        .insert("/stacks/web.yaml".to_string(), package("web", "apache2"));
+    let merged =
+        merge_includes_with(root(&[&url]), loader(dir.path(), cache.path(), true)).unwrap();
+    assert_eq!(merged.resources["web"].packages, vec!["nginx".to_string()]);
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    // Cache lost: the refetched content no longer matches the pin
+    let empty = tempfile::tempdir().unwrap();
+    let err =
+        merge_includes_with(root(&[&url]), loader(dir.path(), empty.path(), false)).unwrap_err();
+    assert!(err.contains("changed upstream"), "{err}");
+}
+
+#[test]
+fn offline_refuses_unpinned_fetch() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = tempfile::tempdir().unwrap();
+    let err = merge_includes_with(
+        root(&["https://example.invalid/web.yaml"]),
+        loader(dir.path(), cache.path(), true),
+    )
+    .unwrap_err();
+    assert!(err.contains("unpinned"), "{err}");
+}
+
+#[test]
+fn remote_include_resolves_nested_relative_url() {
+    let files: Files = Arc::default();
+    files.lock().unwrap().insert(
+        "/stacks/web.yaml".to_string(),
+        package("web", "nginx").replace(
+            "resources:",
+            "includes:\n  - ../common/tools.yaml\nresources:",
+        ),
+    );
+    files
+        .lock()
+        .unwrap()
+        .insert("/common/tools.yaml".to_string(), package("tools", "curl"));
+    let (base, _) = serve(files);
+    let dir = tempfile::tempdir().unwrap();
+    let cache = tempfile::tempdir().unwrap();
+    let merged = merge_includes_with(
+        root(&[&format!("{base}/stacks/web.yaml")]),
+        loader(dir.path(), cache.path(), false),
+    )
+    .unwrap();
+    assert_eq!(
+        merged.include_provenance["resource:tools"],
+        format!("{base}/common/tools.yaml")
+    );
+    assert_eq!(lock_pins(dir.path()).len(), 2);
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+#[test]
+fn git_include_pins_commit() {
+    let repo = tempfile::tempdir().unwrap();
+    git(repo.path(), &["init", "-q", "-b", "main"]);
+    std::fs::create_dir_all(repo.path().join("stacks")).unwrap();
+    std::fs::write(
+        repo.path().join("stacks/web.yaml"),
+        package("web", "nginx").replace("resources:", "includes:\n  - tools.yaml\nresources:"),
+    )
+    .unwrap();
+    std::fs::write(
+        repo.path().join("stacks/tools.yaml"),
+        package("tools", "curl"),
+    )
+    .unwrap();
+    git(repo.path(), &["add", "."]);
+    git(repo.path(), &["commit", "-q", "-m", "baseline"]);
+    git(repo.path(), &["tag", "v1"]);
+
+    let spec = format!("git+file://{}@v1//stacks/web.yaml", repo.path().display());
+    let dir = tempfile::tempdir().unwrap();
+    let cache = tempfile::tempdir().unwrap();
+    let merged =
+        merge_includes_with(root(&[&spec]), loader(dir.path(), cache.path(), false)).unwrap();
+    assert!(merged.resources.contains_key("tools"));
+
+    let lock =
+        crate::core::store::lockfile::read_lockfile(&dir.path().join("forjar.inputs.lock.yaml"))
+            .unwrap();
+    let pin = &lock.pins[&format!("include:{spec}")];
+    assert_eq!(pin.provider, "git");
+    assert_eq!(pin.version.as_deref(), Some("v1"));
+    assert_eq!(pin.git_rev.as_ref().map(String::len), Some(40));
+    assert_eq!(pin.pin_type.as_deref(), Some("include"));
+}
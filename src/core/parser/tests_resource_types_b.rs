@@ -0,0 +1,253 @@
+//! Tests for resource_types_b.rs — container runtime and provisioning validators.
+
+use super::resource_types::validate_resource_type;
+use crate::core::types::*;
+
+fn make_resource(rtype: ResourceType) -> Resource {
+    Resource {
+        resource_type: rtype,
+        machine: MachineTarget::Single("local".to_string()),
+        ..Resource::default()
+    }
+}
+
+fn errors_for(r: &Resource) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_resource_type("r", r, &mut errors);
+    errors.into_iter().map(|e| e.message).collect()
+}
+
+// ── docker / docker_network / docker_volume ─────────────────────
+
+#[test]
+fn docker_invalid_runtime() {
+    let mut r = make_resource(ResourceType::Docker);
+    r.name = Some("web".to_string());
+    r.image = Some("nginx".to_string());
+    r.runtime = Some("lxc".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("invalid runtime 'lxc'")));
+    r.runtime = Some("podman".to_string());
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn docker_invalid_pull_policy_and_digest() {
+    let mut r = make_resource(ResourceType::Docker);
+    r.name = Some("web".to_string());
+    r.image = Some("nginx".to_string());
+    r.pull_policy = Some("sometimes".to_string());
+    r.image_digest = Some("md5:abc".to_string());
+    r.labels = vec!["nokey".to_string()];
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("pull_policy 'sometimes'")));
+    assert!(errs.iter().any(|e| e.contains("image_digest 'md5:abc'")));
+    assert!(errs.iter().any(|e| e.contains("label 'nokey'")));
+}
+
+#[test]
+fn docker_network_requires_name_and_subnet_for_gateway() {
+    let mut r = make_resource(ResourceType::DockerNetwork);
+    r.gateway = Some("10.0.0.1".to_string());
+    let errs = errors_for(&r);
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("(docker_network) has no name")));
+    assert!(errs.iter().any(|e| e.contains("gateway but no subnet")));
+}
+
+#[test]
+fn docker_volume_invalid_state() {
+    let mut r = make_resource(ResourceType::DockerVolume);
+    r.name = Some("data".to_string());
+    r.state = Some("running".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("(docker_volume) has invalid state 'running'")));
+}
+
+// ── container_stack ─────────────────────────────────────────────
+
+fn make_stack(content: &str) -> Resource {
+    let mut r = make_resource(ResourceType::ContainerStack);
+    r.name = Some("shop".to_string());
+    r.content = Some(content.to_string());
+    r
+}
+
+#[test]
+fn container_stack_valid() {
+    let r = make_stack("services:\n  web:\n    image: nginx\n");
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn container_stack_requires_content_unless_absent() {
+    let mut r = make_stack("");
+    r.content = None;
+    assert!(errors_for(&r).iter().any(|e| e.contains("has no content")));
+    r.state = Some("absent".to_string());
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn container_stack_invalid_format_and_runtime() {
+    let mut r = make_stack("services:\n  web:\n    image: nginx\n");
+    r.format = Some("helm".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("invalid format 'helm'")));
+    r.format = Some("quadlet".to_string());
+    r.runtime = Some("docker".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("'quadlet' requires runtime podman")));
+}
+
+#[test]
+fn container_stack_spec_errors() {
+    let r = make_stack("services:\n  web:\n    ports: [80]\n");
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("'web' has no image")));
+    let r = make_stack(
+        "services:\n  a:\n    image: x\n    depends_on: [b]\n  b:\n    image: y\n    depends_on: [a]\n",
+    );
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("dependency cycle")));
+    let r = make_stack("services:\n  a:\n    image: x\n    depends_on: [ghost]\n");
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("unknown service 'ghost'")));
+}
+
+// ── wait_for ────────────────────────────────────────────────────
+
+#[test]
+fn wait_for_valid_probes() {
+    let mut r = make_resource(ResourceType::WaitFor);
+    r.port = Some("5432".to_string());
+    assert!(errors_for(&r).is_empty());
+    let mut r = make_resource(ResourceType::WaitFor);
+    r.url = Some("http://localhost:8080/health".to_string());
+    r.expect_status = Some(204);
+    r.pattern = Some("ok".to_string());
+    assert!(errors_for(&r).is_empty());
+    let mut r = make_resource(ResourceType::WaitFor);
+    r.path = Some("/var/log/app.log".to_string());
+    r.pattern = Some("ready to accept".to_string());
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn wait_for_requires_exactly_one_probe() {
+    let mut r = make_resource(ResourceType::WaitFor);
+    assert!(errors_for(&r).iter().any(|e| e.contains("needs one of")));
+    r.port = Some("80".to_string());
+    r.command = Some("true".to_string());
+    assert!(errors_for(&r).iter().any(|e| e.contains("more than one")));
+}
+
+#[test]
+fn wait_for_field_checks() {
+    let mut r = make_resource(ResourceType::WaitFor);
+    r.port = Some("http".to_string());
+    r.expect_status = Some(42);
+    r.interval = Some(0);
+    r.backoff = Some(0);
+    let errors = errors_for(&r);
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("port 'http' is not a valid port")));
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("42 is not an HTTP status")));
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("expect_status requires url")));
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("interval must be at least 1")));
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("backoff must be at least 1")));
+
+    let mut r = make_resource(ResourceType::WaitFor);
+    r.command = Some("true".to_string());
+    r.pattern = Some("x".to_string());
+    r.host = Some("db".to_string());
+    let errors = errors_for(&r);
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("pattern requires url or path")));
+    assert!(errors.iter().any(|e| e.contains("host requires port")));
+}
+
+// ── exec ────────────────────────────────────────────────────────
+
+#[test]
+fn exec_valid() {
+    let mut r = make_resource(ResourceType::Exec);
+    r.command = Some("/opt/app/bin/migrate".to_string());
+    r.creates = Some("/opt/app/.migrated".to_string());
+    r.returns = vec![0, 2];
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn exec_field_checks() {
+    let mut r = make_resource(ResourceType::Exec);
+    r.refreshonly = true;
+    r.returns = vec![256];
+    r.creates = Some("relative/path".to_string());
+    let errors = errors_for(&r);
+    assert!(errors.iter().any(|e| e.contains("has no command")));
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("refreshonly but has no triggers")));
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("returns 256 is not an exit code")));
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("must be an absolute path")));
+}
+
+// ── binary_release ───────────────────────────────────────────────
+
+#[test]
+fn binary_release_field_checks() {
+    let mut r = make_resource(ResourceType::BinaryRelease);
+    r.provider = Some("bitbucket".to_string());
+    r.version = Some("^one".to_string());
+    r.signature = Some("gpg".to_string());
+    let errors = errors_for(&r);
+    assert!(errors.iter().any(|e| e.contains("has no binary")));
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("unknown provider 'bitbucket'")));
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("invalid version constraint '^one'")));
+    assert!(errors.iter().any(|e| e.contains("unknown signature 'gpg'")));
+    assert!(errors
+        .iter()
+        .any(|e| e.contains("signature gpg needs public_key")));
+
+    r.provider = Some("static".to_string());
+    r.version = Some("~1.4".to_string());
+    r.signature = None;
+    r.binary = Some("tool".to_string());
+    let errors = errors_for(&r);
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(errors[0].contains("provider static needs url"));
+
+    r.provider = Some("gitea".to_string());
+    r.repo = Some("acme/tool".to_string());
+    r.url = Some("https://git.example.com".to_string());
+    r.signature = Some("minisign".to_string());
+    r.public_key = Some("RWkey".to_string());
+    assert!(errors_for(&r).is_empty());
+}
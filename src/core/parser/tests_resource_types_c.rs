@@ -0,0 +1,212 @@
+//! Tests for resource_types_c.rs — disk provisioning and host networking validators.
+
+use super::resource_types::validate_resource_type;
+use crate::core::types::*;
+
+fn make_resource(rtype: ResourceType) -> Resource {
+    Resource {
+        resource_type: rtype,
+        machine: MachineTarget::Single("local".to_string()),
+        ..Resource::default()
+    }
+}
+
+fn errors_for(r: &Resource) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_resource_type("r", r, &mut errors);
+    errors.into_iter().map(|e| e.message).collect()
+}
+
+#[test]
+fn swap_size_and_swappiness() {
+    let mut r = make_resource(ResourceType::Swap);
+    assert!(errors_for(&r).is_empty());
+    r.size = Some("4GB".to_string());
+    r.swappiness = Some(300);
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("invalid size '4GB'")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("swappiness 300 exceeds 200")));
+    r.size = Some("4G".to_string());
+    r.swappiness = Some(10);
+    assert!(errors_for(&r).is_empty());
+    r.path = Some("/dev/vdb2".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("only applies to swap files")));
+}
+
+#[test]
+fn partition_requires_device_and_number() {
+    let mut r = make_resource(ResourceType::Partition);
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("has no device")));
+    assert!(errs.iter().any(|e| e.contains("has no number")));
+    r.device = Some("sdb".to_string());
+    r.number = Some(200);
+    r.part_type = Some("ntfs".to_string());
+    r.size = Some("1024".to_string());
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("must be under /dev/")));
+    assert!(errs.iter().any(|e| e.contains("outside 1-128")));
+    assert!(errs.iter().any(|e| e.contains("invalid part_type 'ntfs'")));
+    assert!(errs.iter().any(|e| e.contains("invalid size '1024'")));
+    r.device = Some("/dev/sdb".to_string());
+    r.number = Some(1);
+    r.part_type = Some("efi".to_string());
+    r.size = Some("512M".to_string());
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn lvm_vg_requires_physical_volumes() {
+    let mut r = make_resource(ResourceType::LvmVg);
+    r.name = Some("data".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("has no physical_volumes")));
+    r.physical_volumes = vec!["sdb1".to_string()];
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("'sdb1' must be under /dev/")));
+    r.physical_volumes = vec!["/dev/sdb1".to_string()];
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn lvm_lv_sizes() {
+    let mut r = make_resource(ResourceType::LvmLv);
+    r.name = Some("pg".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("has no volume_group")));
+    r.volume_group = Some("data".to_string());
+    for ok in ["100%FREE", "50%VG", "20G"] {
+        r.size = Some(ok.to_string());
+        assert!(errors_for(&r).is_empty(), "{ok}");
+    }
+    for bad in ["150%FREE", "50%DISK", "20"] {
+        r.size = Some(bad.to_string());
+        assert!(
+            errors_for(&r).iter().any(|e| e.contains("invalid size")),
+            "{bad}"
+        );
+    }
+}
+
+#[test]
+fn filesystem_requires_fs_type() {
+    let mut r = make_resource(ResourceType::Filesystem);
+    r.device = Some("/dev/data/pg".to_string());
+    assert!(errors_for(&r).iter().any(|e| e.contains("has no fs_type")));
+    r.fs_type = Some("xfs".to_string());
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn wireguard_interface_fields() {
+    let mut r = make_resource(ResourceType::WireguardInterface);
+    r.name = Some("wg-lab".to_string());
+    r.addresses = vec!["10.10.0.1/24".to_string(), "fd10::1/64".to_string()];
+    r.port = Some("51820".to_string());
+    assert!(errors_for(&r).is_empty());
+    r.name = Some("wireguard-site-a".to_string());
+    r.addresses = vec!["10.10.0.1".to_string()];
+    r.port = Some("0".to_string());
+    let errs = errors_for(&r);
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("not a valid interface name")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("'10.10.0.1' is not in CIDR form")));
+    assert!(errs.iter().any(|e| e.contains("invalid port '0'")));
+}
+
+#[test]
+fn wireguard_peer_fields() {
+    let mut r = make_resource(ResourceType::WireguardPeer);
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("has no public_key")));
+    assert!(errs.iter().any(|e| e.contains("has no allowed_ips")));
+    r.public_key = Some("xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=".to_string());
+    r.allowed_ips = vec!["10.10.0.2/32".to_string()];
+    r.endpoint = Some("site-b.example.com:51820".to_string());
+    assert!(errors_for(&r).is_empty());
+    // Templated keys are only checked once resolved
+    r.public_key = Some("{{data.hub-key}}".to_string());
+    assert!(errors_for(&r).is_empty());
+    r.public_key = Some("not-a-key".to_string());
+    r.endpoint = Some("site-b.example.com".to_string());
+    let errs = errors_for(&r);
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("not a base64 WireGuard key")));
+    assert!(errs.iter().any(|e| e.contains("must be host:port")));
+}
+
+#[test]
+fn network_interface_addressing() {
+    let mut r = make_resource(ResourceType::NetworkInterface);
+    assert!(errors_for(&r).iter().any(|e| e.contains("has no name")));
+    r.name = Some("eth0".to_string());
+    r.addresses = vec!["10.0.0.2/24".to_string()];
+    r.gateway = Some("10.0.0.1".to_string());
+    r.dns = vec!["2606:4700:4700::1111".to_string()];
+    r.routes = vec![
+        "10.20.0.0/16 via 10.0.0.254".to_string(),
+        "default via 10.0.0.1".to_string(),
+    ];
+    r.revert_after = Some(60);
+    assert!(errors_for(&r).is_empty());
+    r.provider = Some("ifupdown".to_string());
+    r.gateway = Some("10.0.0.1/24".to_string());
+    r.routes = vec!["10.20.0.0 via 10.0.0.254".to_string()];
+    r.dhcp = Some("true".to_string());
+    r.mtu = Some(20);
+    r.revert_after = Some(0);
+    let errs = errors_for(&r);
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("invalid provider 'ifupdown'")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("gateway '10.0.0.1/24' is not an IP")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("route destination '10.20.0.0' is not in CIDR form")));
+    assert!(errs.iter().any(|e| e.contains("invalid dhcp 'true'")));
+    assert!(errs.iter().any(|e| e.contains("mtu 20 is outside")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("revert_after must be at least")));
+}
+
+#[test]
+fn network_interface_virtual_kinds() {
+    let mut r = make_resource(ResourceType::NetworkInterface);
+    r.name = Some("eth0.100".to_string());
+    r.kind = Some("vlan".to_string());
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("needs vlan_id")));
+    assert!(errs.iter().any(|e| e.contains("parent link in interface")));
+    r.vlan_id = Some(100);
+    r.interface = Some("eth0".to_string());
+    assert!(errors_for(&r).is_empty());
+
+    let mut bond = make_resource(ResourceType::NetworkInterface);
+    bond.name = Some("bond0".to_string());
+    bond.kind = Some("bond".to_string());
+    bond.bond_mode = Some("lacp".to_string());
+    let errs = errors_for(&bond);
+    assert!(errs.iter().any(|e| e.contains("kind bond has no members")));
+    assert!(errs.iter().any(|e| e.contains("invalid bond_mode 'lacp'")));
+    bond.members = vec!["eth1".to_string(), "eth2".to_string()];
+    bond.bond_mode = Some("802.3ad".to_string());
+    assert!(errors_for(&bond).is_empty());
+    bond.kind = Some("ethernet".to_string());
+    let errs = errors_for(&bond);
+    assert!(errs.iter().any(|e| e.contains("members only apply")));
+    assert!(errs.iter().any(|e| e.contains("bond_mode only applies")));
+}
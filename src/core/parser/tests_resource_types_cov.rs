@@ -197,6 +197,21 @@ fn model_invalid_state() {
     assert!(errors.iter().any(|e| e.message.contains("invalid state")));
 }
 
+#[test]
+fn model_hf_source() {
+    let mut r = make_resource(ResourceType::Model);
+    r.name = Some("llama".to_string());
+    r.source = Some("hf://meta-llama/Llama-3.1-8B@main".to_string());
+    let mut errors = Vec::new();
+    validate_resource_type("mdl", &r, &mut errors);
+    assert!(errors.is_empty(), "{errors:?}");
+    r.source = Some("hf://llama@main".to_string());
+    validate_resource_type("mdl", &r, &mut errors);
+    assert!(errors
+        .iter()
+        .any(|e| e.message.contains("expected hf://org/repo[@revision]")));
+}
+
 // ── validate_gpu ────────────────────────────────────────────────
 
 #[test]
@@ -219,6 +234,33 @@ fn gpu_invalid_state() {
     assert!(errors.iter().any(|e| e.message.contains("invalid state")));
 }
 
+#[test]
+fn gpu_nvidia_settings() {
+    let mut r = make_resource(ResourceType::Gpu);
+    r.driver_version = Some("550".to_string());
+    r.mig_profiles = vec!["3g.40gb".to_string(), "1g.10gb+me".to_string()];
+    r.power_limit_watts = Some(300);
+    r.container_toolkit = true;
+    let mut errors = Vec::new();
+    validate_resource_type("gpu", &r, &mut errors);
+    assert!(errors.is_empty(), "{errors:?}");
+
+    r.mig_profiles = vec!["9".to_string(), "3g40gb".to_string()];
+    r.power_limit_watts = Some(0);
+    r.gpu_backend = Some("rocm".to_string());
+    let mut errors = Vec::new();
+    validate_resource_type("gpu", &r, &mut errors);
+    let msgs: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert!(msgs.iter().any(|m| m.contains("invalid MIG profile '9'")));
+    assert!(msgs
+        .iter()
+        .any(|m| m.contains("invalid MIG profile '3g40gb'")));
+    assert!(msgs.iter().any(|m| m.contains("must be greater than 0")));
+    assert!(msgs
+        .iter()
+        .any(|m| m.contains("require gpu_backend nvidia")));
+}
+
 // ── validate_recipe ─────────────────────────────────────────────
 
 #[test]
@@ -0,0 +1,267 @@
+//! Tests for resource_types_d.rs — security, host identity and MAC policy validators.
+
+use super::resource_types::validate_resource_type;
+use crate::core::types::*;
+
+fn make_resource(rtype: ResourceType) -> Resource {
+    Resource {
+        resource_type: rtype,
+        machine: MachineTarget::Single("local".to_string()),
+        ..Resource::default()
+    }
+}
+
+fn errors_for(r: &Resource) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_resource_type("r", r, &mut errors);
+    errors.into_iter().map(|e| e.message).collect()
+}
+
+fn make_cert() -> Resource {
+    let mut r = make_resource(ResourceType::Certificate);
+    r.path = Some("/etc/ssl/web.pem".to_string());
+    r.key_path = Some("/etc/ssl/web.key".to_string());
+    r.sans = vec!["web.internal".to_string(), "10.0.0.5".to_string()];
+    r
+}
+
+#[test]
+fn certificate_paths_and_names() {
+    let r = make_resource(ResourceType::Certificate);
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("has no path")));
+    assert!(errs.iter().any(|e| e.contains("has no key_path")));
+    assert!(errs.iter().any(|e| e.contains("needs common_name or sans")));
+
+    let mut r = make_cert();
+    assert!(errors_for(&r).is_empty());
+    r.key_path = Some("web.key".to_string());
+    r.sans.push("bad name!".to_string());
+    let errs = errors_for(&r);
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("key_path 'web.key' must be an absolute path")));
+    assert!(errs.iter().any(|e| e.contains("san 'bad name!'")));
+}
+
+#[test]
+fn certificate_issuers() {
+    let mut r = make_cert();
+    r.chain_path = Some("/etc/ssl/chain.pem".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("chain_path needs issuer local or ca")));
+    r.issuer = Some("local".to_string());
+    assert!(errors_for(&r).is_empty());
+    r.issuer = Some("ca".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("issuer ca needs ca_cert and ca_key")));
+    r.ca_cert = Some("/srv/pki/ca.crt".to_string());
+    r.ca_key = Some("/srv/pki/ca.key".to_string());
+    assert!(errors_for(&r).is_empty());
+    r.issuer = Some("acme".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("invalid issuer 'acme'")));
+}
+
+#[test]
+fn certificate_lifetime_and_key() {
+    let mut r = make_cert();
+    r.validity_days = Some(30);
+    r.renew_before = Some("30d".to_string());
+    r.key_type = Some("dsa".to_string());
+    r.mode = Some("0644".to_string());
+    let errs = errors_for(&r);
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("renew_before '30d' is not shorter than validity_days 30")));
+    assert!(errs.iter().any(|e| e.contains("invalid key_type 'dsa'")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("key mode '0644' must not grant access to others")));
+    r.renew_before = Some("soon".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("invalid renew_before 'soon'")));
+    r.renew_before = Some("7d".to_string());
+    r.key_type = Some("rsa".to_string());
+    r.mode = Some("0640".to_string());
+    assert!(errors_for(&r).is_empty());
+
+    // Removal only needs the paths
+    let mut absent = make_resource(ResourceType::Certificate);
+    absent.state = Some("absent".to_string());
+    absent.path = Some("/etc/ssl/web.pem".to_string());
+    absent.key_path = Some("/etc/ssl/web.key".to_string());
+    assert!(errors_for(&absent).is_empty());
+}
+
+#[test]
+fn system_identity_fields() {
+    let mut r = make_resource(ResourceType::System);
+    assert!(errors_for(&r).is_empty());
+    r.hostname = Some("-web_1".to_string());
+    r.timezone = Some("../etc/passwd".to_string());
+    r.locale = Some("en US".to_string());
+    r.keyboard = Some("us;reboot".to_string());
+    r.ntp_servers = vec!["pool ntp".to_string()];
+    r.provider = Some("ntpd".to_string());
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("invalid hostname '-web_1'")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("invalid timezone '../etc/passwd'")));
+    assert!(errs.iter().any(|e| e.contains("invalid locale 'en US'")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("invalid keyboard 'us;reboot'")));
+    assert!(errs.iter().any(|e| e.contains("ntp server 'pool ntp'")));
+    assert!(errs.iter().any(|e| e.contains("invalid provider 'ntpd'")));
+
+    r.hostname = Some("web1.example.com".to_string());
+    r.timezone = Some("America/Argentina/Buenos_Aires".to_string());
+    r.locale = Some("de_DE.UTF-8".to_string());
+    r.keyboard = Some("de-latin1".to_string());
+    r.ntp_servers = vec!["0.pool.ntp.org".to_string(), "10.0.0.1".to_string()];
+    r.provider = Some("chrony".to_string());
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn one_system_resource_per_machine() {
+    let yaml = r#"
+version: "1.0"
+name: test
+machines:
+  a:
+    hostname: a
+    addr: 10.0.0.1
+  b:
+    hostname: b
+    addr: 10.0.0.2
+resources:
+  base:
+    type: system
+    machine: [a, b]
+    timezone: UTC
+  extra:
+    type: system
+    machine: b
+    locale: C.UTF-8
+"#;
+    let config = super::parse_config(yaml).unwrap();
+    let errs: Vec<String> = super::validate_config(&config)
+        .into_iter()
+        .map(|e| e.message)
+        .collect();
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("machine 'b' has more than one system resource ('base' and 'extra')")));
+    assert!(!errs.iter().any(|e| e.contains("machine 'a'")));
+}
+
+#[test]
+fn apparmor_profile_fields() {
+    let mut r = make_resource(ResourceType::ApparmorProfile);
+    assert!(errors_for(&r).iter().any(|e| e.contains("has no name")));
+    r.name = Some("/usr/sbin/nginx".to_string());
+    assert!(errors_for(&r).is_empty());
+    r.state = Some("complain".to_string());
+    r.path = Some("/etc/apparmor.d/usr.sbin.nginx".to_string());
+    assert!(errors_for(&r).is_empty());
+
+    r.state = Some("enforcing".to_string());
+    r.path = Some("apparmor.d/nginx".to_string());
+    r.name = Some("nginx profile".to_string());
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("invalid state 'enforcing'")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("must be an absolute file path")));
+    assert!(errs.iter().any(|e| e.contains("invalid profile name")));
+}
+
+#[test]
+fn selinux_boolean_and_mode_fields() {
+    let mut r = make_resource(ResourceType::SelinuxBoolean);
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("has no name")));
+    assert!(errs.iter().any(|e| e.contains("has no enabled value")));
+    r.name = Some("httpd_can_network_connect".to_string());
+    r.enabled = Some(true);
+    assert!(errors_for(&r).is_empty());
+    r.name = Some("httpd; reboot".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("invalid boolean name")));
+
+    let mut m = make_resource(ResourceType::SelinuxMode);
+    assert!(errors_for(&m).is_empty());
+    m.state = Some("permissive".to_string());
+    assert!(errors_for(&m).is_empty());
+    m.state = Some("absent".to_string());
+    assert!(errors_for(&m).iter().any(|e| e.contains("invalid state")));
+}
+
+#[test]
+fn selinux_fcontext_fields() {
+    let mut r = make_resource(ResourceType::SelinuxFcontext);
+    r.path = Some("/srv/www(/.*)?".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("has no selinux_type")));
+    r.selinux_type = Some("httpd_sys_content_t".to_string());
+    assert!(errors_for(&r).is_empty());
+
+    // No relabel target can be derived from a general expression.
+    r.path = Some("/srv/(www|static)(/.*)?".to_string());
+    assert!(errors_for(&r).iter().any(|e| e.contains("set target")));
+    r.target = Some("/srv".to_string());
+    assert!(errors_for(&r).is_empty());
+
+    r.selinux_type = Some("httpd_sys_content_t:s0".to_string());
+    assert!(errors_for(&r)
+        .iter()
+        .any(|e| e.contains("invalid selinux_type")));
+    r.state = Some("absent".to_string());
+    r.selinux_type = None;
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn one_selinux_mode_per_machine() {
+    let yaml = r#"
+version: "1.0"
+name: test
+machines:
+  a:
+    hostname: a
+    addr: 10.0.0.1
+resources:
+  base:
+    type: system
+    machine: a
+    timezone: UTC
+  selinux:
+    type: selinux_mode
+    machine: a
+    state: enforcing
+  selinux-again:
+    type: selinux_mode
+    machine: a
+    state: permissive
+"#;
+    let config = super::parse_config(yaml).unwrap();
+    let errs: Vec<String> = super::validate_config(&config)
+        .into_iter()
+        .map(|e| e.message)
+        .collect();
+    assert_eq!(
+        errs.iter()
+            .filter(|e| e.contains("more than one"))
+            .collect::<Vec<_>>(),
+        vec!["machine 'a' has more than one selinux_mode resource ('selinux' and 'selinux-again')"]
+    );
+}
@@ -0,0 +1,90 @@
+//! Tests for resource_types_e.rs — ZFS storage validators.
+
+use super::resource_types::validate_resource_type;
+use crate::core::types::*;
+
+fn make_resource(rtype: ResourceType) -> Resource {
+    Resource {
+        resource_type: rtype,
+        machine: MachineTarget::Single("local".to_string()),
+        ..Resource::default()
+    }
+}
+
+fn errors_for(r: &Resource) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_resource_type("r", r, &mut errors);
+    errors.into_iter().map(|e| e.message).collect()
+}
+
+#[test]
+fn zfs_pool_name_and_vdevs() {
+    let mut r = make_resource(ResourceType::ZfsPool);
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("has no name")));
+    assert!(errs.iter().any(|e| e.contains("has no vdevs")));
+    r.name = Some("mirror".to_string());
+    r.vdevs = vec!["mirror sdb /dev/sdc".to_string()];
+    let errs = errors_for(&r);
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("'mirror' is not a valid pool name")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("vdev 'sdb' must be a device path")));
+    r.name = Some("tank".to_string());
+    r.vdevs = vec!["raidz2 /dev/sdb /dev/sdc /dev/sdd /dev/sde".to_string()];
+    r.properties.insert("ashift".to_string(), "12".to_string());
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn zfs_dataset_properties() {
+    let mut r = make_resource(ResourceType::ZfsDataset);
+    r.name = Some("tank//models".to_string());
+    r.properties
+        .insert("Compression".to_string(), "zstd".to_string());
+    r.properties.insert("quota".to_string(), "lots".to_string());
+    r.properties
+        .insert("mountpoint".to_string(), "/srv/it's".to_string());
+    let errs = errors_for(&r);
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("invalid name 'tank//models'")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("invalid property name 'Compression'")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("property 'quota' has invalid size 'lots'")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("property 'mountpoint' has invalid value")));
+
+    let mut r = make_resource(ResourceType::ZfsDataset);
+    r.name = Some("tank/models".to_string());
+    for (k, v) in [
+        ("compression", "zstd"),
+        ("quota", "none"),
+        ("recordsize", "1M"),
+        ("com.example:owner", "ml"),
+    ] {
+        r.properties.insert(k.to_string(), v.to_string());
+    }
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn zfs_snapshot_policy_fields() {
+    let mut r = make_resource(ResourceType::ZfsSnapshotPolicy);
+    r.name = Some("every hour".to_string());
+    r.keep = Some(0);
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("has no dataset")));
+    assert!(errs.iter().any(|e| e.contains("invalid name 'every hour'")));
+    assert!(errs.iter().any(|e| e.contains("keep must be at least 1")));
+    r.name = Some("hourly".to_string());
+    r.dataset = Some("tank/models".to_string());
+    r.keep = Some(24);
+    assert!(errors_for(&r).is_empty());
+}
@@ -0,0 +1,70 @@
+//! Tests for resource_types_f.rs — PostgreSQL validators.
+
+use super::resource_types::validate_resource_type;
+use crate::core::types::*;
+
+fn make_resource(rtype: ResourceType) -> Resource {
+    Resource {
+        resource_type: rtype,
+        machine: MachineTarget::Single("db".to_string()),
+        ..Resource::default()
+    }
+}
+
+fn errors_for(r: &Resource) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_resource_type("r", r, &mut errors);
+    errors.into_iter().map(|e| e.message).collect()
+}
+
+#[test]
+fn pg_role_fields() {
+    let mut r = make_resource(ResourceType::PgRole);
+    r.attributes = vec!["LOGIN".to_string(), "GOD".to_string()];
+    r.groups = vec!["read ers".to_string()];
+    r.port = Some("fivefour".to_string());
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("has no name")));
+    assert!(errs.iter().any(|e| e.contains("unknown attribute 'GOD'")));
+    assert!(errs.iter().any(|e| e.contains("invalid group 'read ers'")));
+    assert!(errs.iter().any(|e| e.contains("invalid port 'fivefour'")));
+    r.name = Some("app".to_string());
+    r.attributes = vec!["login".to_string(), "NOINHERIT".to_string()];
+    r.groups = vec!["readers".to_string()];
+    r.port = Some("5432".to_string());
+    r.password = Some("{{secrets.app-db}}".to_string());
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn pg_database_fields() {
+    let mut r = make_resource(ResourceType::PgDatabase);
+    r.name = Some("app;drop".to_string());
+    r.encoding = Some("UTF-8'".to_string());
+    r.grants = vec!["app:SELECT".to_string(), "noprivs".to_string()];
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("invalid name 'app;drop'")));
+    assert!(errs.iter().any(|e| e.contains("invalid encoding")));
+    assert!(errs
+        .iter()
+        .any(|e| e.contains("unknown privilege 'SELECT'")));
+    assert!(errs.iter().any(|e| e.contains("is not role:PRIVILEGE")));
+    r.name = Some("appdb".to_string());
+    r.owner = Some("app".to_string());
+    r.encoding = Some("UTF8".to_string());
+    r.grants = vec!["PUBLIC:".to_string(), "app:CONNECT,TEMP".to_string()];
+    assert!(errors_for(&r).is_empty());
+}
+
+#[test]
+fn pg_extension_fields() {
+    let mut r = make_resource(ResourceType::PgExtension);
+    r.name = Some("pgcrypto".to_string());
+    r.state = Some("latest".to_string());
+    let errs = errors_for(&r);
+    assert!(errs.iter().any(|e| e.contains("has no database")));
+    assert!(errs.iter().any(|e| e.contains("invalid state 'latest'")));
+    r.database = Some("appdb".to_string());
+    r.state = None;
+    assert!(errors_for(&r).is_empty());
+}
@@ -120,6 +120,9 @@ const RESOURCE_FIELDS: &[&str] = &[
     "persistence_mode",
     "compute_mode",
     "gpu_memory_limit_mb",
+    "mig_profiles",
+    "power_limit_watts",
+    "container_toolkit",
     "task_mode",
     "task_inputs",
     "output_artifacts",
@@ -146,7 +149,82 @@ const RESOURCE_FIELDS: &[&str] = &[
     "asset_pattern",
     "binary",
     "install_dir",
+    "checksum_file",
+    "signature",
     "build_machine",
+    "runtime",
+    "pull_policy",
+    "image_digest",
+    "labels",
+    "user",
+    "entrypoint",
+    "cpus",
+    "networks",
+    "driver",
+    "subnet",
+    "gateway",
+    "url",
+    "host",
+    "expect_status",
+    "pattern",
+    "interval",
+    "backoff",
+    "creates",
+    "unless",
+    "onlyif",
+    "refreshonly",
+    "returns",
+    "cwd",
+    "device",
+    "size",
+    "number",
+    "part_type",
+    "physical_volumes",
+    "volume_group",
+    "swappiness",
+    "force",
+    "interface",
+    "addresses",
+    "public_key",
+    "endpoint",
+    "allowed_ips",
+    "persistent_keepalive",
+    "kind",
+    "members",
+    "vlan_id",
+    "bond_mode",
+    "mtu",
+    "dhcp",
+    "dns",
+    "routes",
+    "revert_after",
+    "issuer",
+    "ca_name",
+    "ca_cert",
+    "ca_key",
+    "common_name",
+    "sans",
+    "validity_days",
+    "renew_before",
+    "key_type",
+    "key_path",
+    "chain_path",
+    "hostname",
+    "timezone",
+    "locale",
+    "password",
+    "attributes",
+    "database",
+    "encoding",
+    "grants",
+    "selinux_type",
+    "keyboard",
+    "ntp_servers",
+    "vdevs",
+    "properties",
+    "dataset",
+    "keep",
+    "recursive",
 ];
 
 const MACHINE_FIELDS: &[&str] = &[
@@ -180,10 +258,19 @@ const POLICY_FIELDS: &[&str] = &[
     "deny_paths",
     "notify",
     "logs",
+    "backend",
+    "lock_ttl",
+    "trust_policy",
+    "state_encryption",
+    "recipe_registries",
 ];
 
 const NOTIFY_FIELDS: &[&str] = &["on_success", "on_failure", "on_drift"];
 
+const BACKEND_FIELDS: &[&str] = &["type", "bucket", "prefix", "endpoint", "region", "path"];
+
+const STATE_ENCRYPTION_FIELDS: &[&str] = &["recipients", "environments"];
+
 const CONTAINER_FIELDS: &[&str] = &[
     "runtime",
     "image",
@@ -416,6 +503,8 @@ fn check_nested(
         "pepita" => check_mapping(val, &path, PEPITA_FIELDS, unknowns),
         "lifecycle" => check_mapping(val, &path, LIFECYCLE_FIELDS, unknowns),
         "notify" => check_mapping(val, &path, NOTIFY_FIELDS, unknowns),
+        "backend" => check_mapping(val, &path, BACKEND_FIELDS, unknowns),
+        "state_encryption" => check_mapping(val, &path, STATE_ENCRYPTION_FIELDS, unknowns),
         _ => {}
     }
 }
@@ -128,3 +128,38 @@ pub(super) fn validate_machine(key: &str, machine: &Machine, errors: &mut Vec<Va
         }
     }
 }
+
+/// FJ-3310: Validate `policy.state_encryption` recipient sets.
+pub(super) fn validate_state_encryption(config: &ForjarConfig, errors: &mut Vec<ValidationError>) {
+    let Some(ref enc) = config.policy.state_encryption else {
+        return;
+    };
+    let sets = std::iter::once(("recipients".to_string(), &enc.recipients)).chain(
+        enc.environments
+            .iter()
+            .map(|(env, set)| (format!("environments.{env}"), set)),
+    );
+    for (path, set) in sets {
+        for recipient in set {
+            if let Err(e) = crate::core::state_recipients::validate_recipient(recipient) {
+                errors.push(ValidationError {
+                    message: format!("policy.state_encryption.{path}: {e}"),
+                });
+            }
+        }
+    }
+    for (env, set) in &enc.environments {
+        if set.is_empty() {
+            errors.push(ValidationError {
+                message: format!(
+                    "policy.state_encryption.environments.{env} has no recipients (remove it to use the base set)"
+                ),
+            });
+        }
+        if !config.environments.is_empty() && !config.environments.contains_key(env) {
+            errors.push(ValidationError {
+                message: format!("policy.state_encryption.environments.{env}: unknown environment"),
+            });
+        }
+    }
+}
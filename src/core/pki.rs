@@ -0,0 +1,397 @@
+//! Control-machine side of the `certificate` resource.
+//!
+//! Signs CSRs produced on targets with either the forjar-managed local CA or
+//! a user-provided CA, and tracks certificate expiry from the lock.
+//!
+//! The local CA lives in `<state_dir>/pki/<ca_name>/`: `ca.crt` in the clear
+//! and `ca.key` as an `ENC[age,...]` marker encrypted to the `FORJAR_AGE_KEY`
+//! identity, so it needs the `encryption` feature. A user CA key given in
+//! `ca_key` may itself be age-encrypted.
+
+use crate::core::types::{
+    ForjarConfig, Resource, ResourceLock, ResourceStatus, ResourceType, StateLock,
+};
+use crate::resources::certificate;
+use openssl::asn1::Asn1Time;
+use openssl::bn::{BigNum, MsbOption};
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::sign::Signer;
+use openssl::x509::extension::{
+    AuthorityKeyIdentifier, BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAlternativeName,
+    SubjectKeyIdentifier,
+};
+use openssl::x509::{X509Builder, X509Name, X509NameBuilder, X509Req, X509};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Local CA lifetime in days.
+const CA_VALIDITY_DAYS: u32 = 3650;
+
+/// A CA able to sign leaf certificates.
+pub struct SigningCa {
+    pub cert: X509,
+    pub key: PKey<Private>,
+}
+
+fn ssl_err(context: &str) -> impl Fn(openssl::error::ErrorStack) -> String + '_ {
+    move |e| format!("{context}: {e}")
+}
+
+/// HMAC-SHA256 of `data` under `key`.
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    let key = PKey::hmac(key).map_err(ssl_err("hmac"))?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key).map_err(ssl_err("hmac"))?;
+    signer.sign_oneshot_to_vec(data).map_err(ssl_err("hmac"))
+}
+
+fn subject_name(cn: &str) -> Result<X509Name, String> {
+    let mut name = X509NameBuilder::new().map_err(ssl_err("x509 name"))?;
+    name.append_entry_by_nid(Nid::COMMONNAME, cn)
+        .map_err(ssl_err("x509 common name"))?;
+    Ok(name.build())
+}
+
+fn random_serial(builder: &mut X509Builder) -> Result<(), String> {
+    let mut serial = BigNum::new().map_err(ssl_err("serial"))?;
+    serial
+        .rand(127, MsbOption::MAYBE_ZERO, false)
+        .map_err(ssl_err("serial"))?;
+    let serial = serial.to_asn1_integer().map_err(ssl_err("serial"))?;
+    builder
+        .set_serial_number(&serial)
+        .map_err(ssl_err("serial"))
+}
+
+/// Create a new self-signed CA with a P-256 key.
+pub fn create_ca(name: &str) -> Result<SigningCa, String> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).map_err(ssl_err("CA key"))?;
+    let ec = EcKey::generate(&group).map_err(ssl_err("CA key"))?;
+    let key = PKey::from_ec_key(ec).map_err(ssl_err("CA key"))?;
+    let subject = subject_name(&format!("forjar {name} CA"))?;
+
+    let mut builder = X509Builder::new().map_err(ssl_err("CA cert"))?;
+    builder.set_version(2).map_err(ssl_err("CA cert"))?;
+    random_serial(&mut builder)?;
+    builder
+        .set_subject_name(&subject)
+        .map_err(ssl_err("CA cert"))?;
+    builder
+        .set_issuer_name(&subject)
+        .map_err(ssl_err("CA cert"))?;
+    builder.set_pubkey(&key).map_err(ssl_err("CA cert"))?;
+    let not_before = Asn1Time::days_from_now(0).map_err(ssl_err("CA validity"))?;
+    let not_after = Asn1Time::days_from_now(CA_VALIDITY_DAYS).map_err(ssl_err("CA validity"))?;
+    builder
+        .set_not_before(&not_before)
+        .map_err(ssl_err("CA validity"))?;
+    builder
+        .set_not_after(&not_after)
+        .map_err(ssl_err("CA validity"))?;
+    let basic = BasicConstraints::new()
+        .critical()
+        .ca()
+        .pathlen(0)
+        .build()
+        .map_err(ssl_err("CA extensions"))?;
+    let usage = KeyUsage::new()
+        .critical()
+        .key_cert_sign()
+        .crl_sign()
+        .build()
+        .map_err(ssl_err("CA extensions"))?;
+    let ski = SubjectKeyIdentifier::new()
+        .build(&builder.x509v3_context(None, None))
+        .map_err(ssl_err("CA extensions"))?;
+    for ext in [basic, usage, ski] {
+        builder
+            .append_extension(ext)
+            .map_err(ssl_err("CA extensions"))?;
+    }
+    builder
+        .sign(&key, MessageDigest::sha256())
+        .map_err(ssl_err("CA signing"))?;
+    Ok(SigningCa {
+        cert: builder.build(),
+        key,
+    })
+}
+
+/// Sign a target's CSR for the certificate resource.
+///
+/// The subject and SANs come from the resource, not the CSR; the CSR only
+/// supplies the public key (and proves possession of the private key).
+pub fn sign_csr(ca: &SigningCa, csr_pem: &str, resource: &Resource) -> Result<String, String> {
+    let req = X509Req::from_pem(csr_pem.as_bytes()).map_err(ssl_err("invalid CSR"))?;
+    let pubkey = req.public_key().map_err(ssl_err("CSR public key"))?;
+    if !req.verify(&pubkey).map_err(ssl_err("CSR signature"))? {
+        return Err("CSR signature does not verify".to_string());
+    }
+
+    let mut builder = X509Builder::new().map_err(ssl_err("certificate"))?;
+    builder.set_version(2).map_err(ssl_err("certificate"))?;
+    random_serial(&mut builder)?;
+    let subject = subject_name(certificate::common_name(resource))?;
+    builder
+        .set_subject_name(&subject)
+        .map_err(ssl_err("certificate"))?;
+    builder
+        .set_issuer_name(ca.cert.subject_name())
+        .map_err(ssl_err("certificate"))?;
+    builder
+        .set_pubkey(&pubkey)
+        .map_err(ssl_err("certificate"))?;
+    let not_before = Asn1Time::days_from_now(0).map_err(ssl_err("validity"))?;
+    let not_after = Asn1Time::days_from_now(certificate::validity_days(resource))
+        .map_err(ssl_err("validity"))?;
+    builder
+        .set_not_before(&not_before)
+        .map_err(ssl_err("validity"))?;
+    builder
+        .set_not_after(&not_after)
+        .map_err(ssl_err("validity"))?;
+
+    let mut exts = vec![
+        BasicConstraints::new()
+            .critical()
+            .build()
+            .map_err(ssl_err("extensions"))?,
+        KeyUsage::new()
+            .critical()
+            .digital_signature()
+            .key_encipherment()
+            .build()
+            .map_err(ssl_err("extensions"))?,
+        ExtendedKeyUsage::new()
+            .server_auth()
+            .client_auth()
+            .build()
+            .map_err(ssl_err("extensions"))?,
+        SubjectKeyIdentifier::new()
+            .build(&builder.x509v3_context(Some(&ca.cert), None))
+            .map_err(ssl_err("extensions"))?,
+        AuthorityKeyIdentifier::new()
+            .keyid(false)
+            .build(&builder.x509v3_context(Some(&ca.cert), None))
+            .map_err(ssl_err("extensions"))?,
+    ];
+    if !resource.sans.is_empty() {
+        let mut san = SubjectAlternativeName::new();
+        for entry in &resource.sans {
+            if entry.parse::<std::net::IpAddr>().is_ok() {
+                san.ip(entry);
+            } else {
+                san.dns(entry);
+            }
+        }
+        exts.push(
+            san.build(&builder.x509v3_context(Some(&ca.cert), None))
+                .map_err(ssl_err("subjectAltName"))?,
+        );
+    }
+    for ext in exts {
+        builder
+            .append_extension(ext)
+            .map_err(ssl_err("extensions"))?;
+    }
+    builder
+        .sign(&ca.key, MessageDigest::sha256())
+        .map_err(ssl_err("signing"))?;
+    let pem = builder
+        .build()
+        .to_pem()
+        .map_err(ssl_err("certificate PEM"))?;
+    Ok(String::from_utf8_lossy(&pem).into_owned())
+}
+
+/// PEM of the CA certificate (the chain deployed next to leaf certificates).
+pub fn ca_pem(ca: &SigningCa) -> Result<String, String> {
+    let pem = ca.cert.to_pem().map_err(ssl_err("CA PEM"))?;
+    Ok(String::from_utf8_lossy(&pem).into_owned())
+}
+
+/// Decrypt `ENC[age,...]` markers in key material read from disk.
+fn decrypt_key_text(text: String) -> Result<String, String> {
+    if !crate::core::secrets::has_encrypted_markers(&text) {
+        return Ok(text);
+    }
+    #[cfg(feature = "encryption")]
+    {
+        let identities = crate::core::secrets::load_identities(None)?;
+        crate::core::secrets::decrypt_all(&text, &identities)
+    }
+    #[cfg(not(feature = "encryption"))]
+    {
+        crate::core::secrets::decrypt_all_inline(&text)
+    }
+}
+
+/// Load a user-provided CA (`ca_cert` + `ca_key` on the control machine).
+fn load_user_ca(resource: &Resource) -> Result<SigningCa, String> {
+    let cert_path = resource.ca_cert.as_deref().unwrap_or_default();
+    let key_path = resource.ca_key.as_deref().unwrap_or_default();
+    let cert_pem =
+        std::fs::read(cert_path).map_err(|e| format!("cannot read ca_cert '{cert_path}': {e}"))?;
+    let key_text = std::fs::read_to_string(key_path)
+        .map_err(|e| format!("cannot read ca_key '{key_path}': {e}"))?;
+    let key_pem = decrypt_key_text(key_text)?;
+    Ok(SigningCa {
+        cert: X509::from_pem(&cert_pem).map_err(ssl_err("invalid ca_cert"))?,
+        key: PKey::private_key_from_pem(key_pem.as_bytes()).map_err(ssl_err("invalid ca_key"))?,
+    })
+}
+
+/// Load the local CA from the state directory, creating it on first use.
+#[cfg(feature = "encryption")]
+fn load_or_create_local_ca(state_dir: &Path, name: &str) -> Result<SigningCa, String> {
+    use crate::core::secrets;
+    let dir = state_dir.join("pki").join(name);
+    let cert_path = dir.join("ca.crt");
+    let key_path = dir.join("ca.key");
+    let identity = secrets::load_identity_from_env()?;
+    if cert_path.exists() && key_path.exists() {
+        let cert_pem = std::fs::read(&cert_path)
+            .map_err(|e| format!("cannot read {}: {e}", cert_path.display()))?;
+        let marker = std::fs::read_to_string(&key_path)
+            .map_err(|e| format!("cannot read {}: {e}", key_path.display()))?;
+        let key_pem = secrets::decrypt_all(marker.trim(), &[identity])?;
+        return Ok(SigningCa {
+            cert: X509::from_pem(&cert_pem).map_err(ssl_err("invalid local CA cert"))?,
+            key: PKey::private_key_from_pem(key_pem.as_bytes())
+                .map_err(ssl_err("invalid local CA key"))?,
+        });
+    }
+    let ca = create_ca(name)?;
+    let key_pem = ca
+        .key
+        .private_key_to_pem_pkcs8()
+        .map_err(ssl_err("CA key PEM"))?;
+    let recipient = secrets::identity_to_recipient(&identity);
+    let marker = secrets::encrypt(&String::from_utf8_lossy(&key_pem), &[recipient.as_str()])?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("cannot create {}: {e}", dir.display()))?;
+    std::fs::write(&key_path, format!("{marker}\n"))
+        .map_err(|e| format!("cannot write {}: {e}", key_path.display()))?;
+    std::fs::write(&cert_path, ca_pem(&ca)?)
+        .map_err(|e| format!("cannot write {}: {e}", cert_path.display()))?;
+    Ok(ca)
+}
+
+#[cfg(not(feature = "encryption"))]
+fn load_or_create_local_ca(_state_dir: &Path, _name: &str) -> Result<SigningCa, String> {
+    Err("issuer 'local' keeps the CA key age-encrypted; rebuild forjar with `--features encryption`".to_string())
+}
+
+/// The CA that signs this certificate resource.
+pub fn signing_ca(resource: &Resource, state_dir: &Path) -> Result<SigningCa, String> {
+    match certificate::issuer(resource) {
+        "local" => {
+            load_or_create_local_ca(state_dir, resource.ca_name.as_deref().unwrap_or("forjar"))
+        }
+        "ca" => load_user_ca(resource),
+        other => Err(format!("issuer '{other}' is not signed by forjar")),
+    }
+}
+
+// ============================================================================
+// Expiry tracking
+// ============================================================================
+
+/// Current time as Unix seconds.
+pub fn now_unix() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Days since 1970-01-01 for a civil date (proleptic Gregorian).
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Parse OpenSSL's `notAfter` format (`Mar  1 12:00:00 2027 GMT`) to Unix seconds.
+pub fn parse_not_after(s: &str) -> Option<i64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut parts = s.split_whitespace();
+    let month_name = parts.next()?;
+    let month = MONTHS.iter().position(|m| *m == month_name)? as i64 + 1;
+    let day: i64 = parts.next()?.parse().ok()?;
+    let mut hms = parts.next()?.split(':').map(|p| p.parse::<i64>().ok());
+    let (h, m, sec) = (hms.next()??, hms.next()??, hms.next()??);
+    let year: i64 = parts.next()?.parse().ok()?;
+    Some(days_from_civil(year, month, day) * 86400 + h * 3600 + m * 60 + sec)
+}
+
+/// Expiry recorded in the lock (`outputs.not_after` of the last apply).
+pub fn lock_not_after(rl: &ResourceLock) -> Option<i64> {
+    let outputs = rl.details.get("outputs")?;
+    parse_not_after(outputs.get("not_after")?.as_str()?)
+}
+
+/// Certificates in `lock` that are inside their renewal window.
+///
+/// Returns `(resource_id, seconds_left)`; without a config the default
+/// window applies.
+pub fn due_renewals(
+    lock: &StateLock,
+    config: Option<&ForjarConfig>,
+    now: i64,
+) -> Vec<(String, i64)> {
+    let default_window =
+        certificate::parse_window(certificate::DEFAULT_RENEW_BEFORE).unwrap_or_default();
+    lock.resources
+        .iter()
+        .filter(|(_, rl)| rl.resource_type == ResourceType::Certificate)
+        .filter_map(|(id, rl)| {
+            let not_after = lock_not_after(rl)?;
+            let window = config
+                .and_then(|c| c.resources.get(id))
+                .map(certificate::renew_before_secs)
+                .unwrap_or(default_window);
+            let left = not_after - now;
+            (left <= window as i64).then(|| (id.clone(), left))
+        })
+        .collect()
+}
+
+/// Mark certificates due for renewal as drifted so the planner re-applies them.
+pub fn mark_renewals_due(
+    config: &ForjarConfig,
+    mut locks: HashMap<String, StateLock>,
+    now: i64,
+) -> HashMap<String, StateLock> {
+    for lock in locks.values_mut() {
+        for (id, _) in due_renewals(lock, Some(config), now) {
+            if let Some(rl) = lock.resources.get_mut(&id) {
+                rl.status = ResourceStatus::Drifted;
+            }
+        }
+    }
+    locks
+}
+
+/// Human-readable time left (`12d`, `5h`, or `expired 3d ago`).
+pub fn describe_time_left(secs: i64) -> String {
+    let fmt = |s: i64| {
+        if s >= 86400 {
+            format!("{}d", s / 86400)
+        } else {
+            format!("{}h", s / 3600)
+        }
+    };
+    if secs < 0 {
+        format!("expired {} ago", fmt(-secs))
+    } else {
+        format!("expires in {}", fmt(secs))
+    }
+}
@@ -142,7 +142,33 @@ fn default_state(resource_type: &ResourceType) -> &'static str {
         | ResourceType::WasmBundle
         | ResourceType::Image
         | ResourceType::Build
-        | ResourceType::GithubRelease => "present",
+        | ResourceType::GithubRelease
+        | ResourceType::DockerNetwork
+        | ResourceType::DockerVolume
+        | ResourceType::ContainerStack
+        | ResourceType::WaitFor
+        | ResourceType::Exec
+        | ResourceType::Swap
+        | ResourceType::Partition
+        | ResourceType::LvmVg
+        | ResourceType::LvmLv
+        | ResourceType::Filesystem
+        | ResourceType::WireguardInterface
+        | ResourceType::WireguardPeer
+        | ResourceType::NetworkInterface
+        | ResourceType::Certificate
+        | ResourceType::System
+        | ResourceType::ZfsPool
+        | ResourceType::ZfsDataset
+        | ResourceType::ZfsSnapshotPolicy
+        | ResourceType::BinaryRelease
+        | ResourceType::PgRole
+        | ResourceType::PgDatabase
+        | ResourceType::PgExtension
+        | ResourceType::ApparmorProfile
+        | ResourceType::SelinuxBoolean
+        | ResourceType::SelinuxFcontext
+        | ResourceType::SelinuxMode => "present",
     }
 }
 
@@ -291,6 +317,167 @@ fn collect_phase2_fields<'a>(components: &mut Vec<&'a str>, resource: &'a Resour
     push_list(components, &resource.restart_on);
 }
 
+/// Collect phase 3 (container runtime) fields into hash components.
+///
+/// Only set fields contribute, so resources that predate these fields keep
+/// their hash identity. Field order is stable and must not change.
+fn collect_phase3_fields<'a>(components: &mut Vec<&'a str>, resource: &'a Resource) {
+    push_opt(components, &resource.runtime);
+    push_opt(components, &resource.pull_policy);
+    push_opt(components, &resource.image_digest);
+    push_opt(components, &resource.user);
+    push_opt(components, &resource.entrypoint);
+    push_opt(components, &resource.cpus);
+    push_opt(components, &resource.driver);
+    push_opt(components, &resource.subnet);
+    push_opt(components, &resource.gateway);
+    push_list(components, &resource.labels);
+    push_list(components, &resource.networks);
+    push_opt(components, &resource.url);
+    push_opt(components, &resource.host);
+    push_opt(components, &resource.pattern);
+    push_opt(components, &resource.creates);
+    push_opt(components, &resource.unless);
+    push_opt(components, &resource.onlyif);
+    push_opt(components, &resource.device);
+    push_opt(components, &resource.size);
+    push_opt(components, &resource.part_type);
+    push_opt(components, &resource.volume_group);
+    push_list(components, &resource.physical_volumes);
+    push_opt(components, &resource.interface);
+    push_list(components, &resource.addresses);
+    push_opt(components, &resource.public_key);
+    push_opt(components, &resource.endpoint);
+    push_list(components, &resource.allowed_ips);
+    push_opt(components, &resource.kind);
+    push_list(components, &resource.members);
+    push_opt(components, &resource.bond_mode);
+    push_opt(components, &resource.dhcp);
+    push_list(components, &resource.dns);
+    push_list(components, &resource.routes);
+    push_opt(components, &resource.issuer);
+    push_opt(components, &resource.ca_name);
+    push_opt(components, &resource.ca_cert);
+    push_opt(components, &resource.common_name);
+    push_list(components, &resource.sans);
+    push_opt(components, &resource.renew_before);
+    push_opt(components, &resource.key_type);
+    push_opt(components, &resource.key_path);
+    push_opt(components, &resource.chain_path);
+    push_opt(components, &resource.hostname);
+    push_opt(components, &resource.timezone);
+    push_opt(components, &resource.locale);
+    push_opt(components, &resource.keyboard);
+    push_list(components, &resource.ntp_servers);
+    push_list(components, &resource.vdevs);
+    for (key, value) in &resource.properties {
+        components.push(key);
+        components.push(value);
+    }
+    push_opt(components, &resource.dataset);
+    push_opt(components, &resource.driver_version);
+    push_opt(components, &resource.cuda_version);
+    push_list(components, &resource.mig_profiles);
+    if resource.resource_type == ResourceType::BinaryRelease {
+        push_opt(components, &resource.repo);
+        push_opt(components, &resource.asset_pattern);
+        push_opt(components, &resource.binary);
+        push_opt(components, &resource.install_dir);
+        push_opt(components, &resource.checksum_file);
+        push_opt(components, &resource.signature);
+    }
+    if matches!(
+        resource.resource_type,
+        ResourceType::PgRole | ResourceType::PgDatabase | ResourceType::PgExtension
+    ) {
+        push_list(components, &resource.attributes);
+        push_list(components, &resource.groups);
+        push_opt(components, &resource.database);
+        push_opt(components, &resource.encoding);
+        push_list(components, &resource.grants);
+    }
+    if resource.resource_type == ResourceType::SelinuxFcontext {
+        push_opt(components, &resource.selinux_type);
+    }
+    if resource.resource_type == ResourceType::Docker {
+        if let Some(ref hc) = resource.health_check {
+            components.push(&hc.command);
+        }
+    }
+}
+
+/// Non-string phase 3 fields (wait_for probe tuning, exec, disk, network, certificate, ZFS and GPU flags)
+/// and the hashed PostgreSQL role password.
+///
+/// `None` when all are unset, so resources that do not use them keep their hash.
+fn phase3_scalars(resource: &Resource) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(status) = resource.expect_status {
+        parts.push(format!("status={status}"));
+    }
+    if let Some(interval) = resource.interval {
+        parts.push(format!("interval={interval}"));
+    }
+    if let Some(backoff) = resource.backoff {
+        parts.push(format!("backoff={backoff}"));
+    }
+    if resource.refreshonly {
+        parts.push("refreshonly".to_string());
+    }
+    if !resource.returns.is_empty() {
+        parts.push(format!("returns={:?}", resource.returns));
+    }
+    if let Some(number) = resource.number {
+        parts.push(format!("number={number}"));
+    }
+    if let Some(swappiness) = resource.swappiness {
+        parts.push(format!("swappiness={swappiness}"));
+    }
+    if resource.force {
+        parts.push("force".to_string());
+    }
+    if let Some(keepalive) = resource.persistent_keepalive {
+        parts.push(format!("keepalive={keepalive}"));
+    }
+    if let Some(vlan_id) = resource.vlan_id {
+        parts.push(format!("vlan_id={vlan_id}"));
+    }
+    if let Some(mtu) = resource.mtu {
+        parts.push(format!("mtu={mtu}"));
+    }
+    if let Some(revert_after) = resource.revert_after {
+        parts.push(format!("revert_after={revert_after}"));
+    }
+    if let Some(days) = resource.validity_days {
+        parts.push(format!("validity_days={days}"));
+    }
+    if let Some(keep) = resource.keep {
+        parts.push(format!("keep={keep}"));
+    }
+    if resource.recursive {
+        parts.push("recursive=true".to_string());
+    }
+    if let Some(watts) = resource.power_limit_watts {
+        parts.push(format!("power_limit={watts}"));
+    }
+    if resource.container_toolkit {
+        parts.push("container_toolkit".to_string());
+    }
+    // Hash-and-discard: a rotated secret re-plans the resource without the
+    // plaintext entering the hash input.
+    if let Some(ref password) = resource.password {
+        parts.push(format!(
+            "password={}",
+            super::ephemeral::blake3_hash(password)
+        ));
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" "))
+    }
+}
+
 /// Compute a hash of the desired state for comparison.
 ///
 /// FJ-2200: Contract — determinism: same resource always produces same hash.
@@ -300,6 +487,9 @@ pub fn hash_desired_state(resource: &Resource) -> String {
 
     collect_core_fields(&mut components, resource);
     collect_phase2_fields(&mut components, resource);
+    collect_phase3_fields(&mut components, resource);
+    let scalars = phase3_scalars(resource);
+    push_opt(&mut components, &scalars);
 
     let joined = components.join("\0");
     let result = hasher::hash_string(&joined);
@@ -350,7 +540,33 @@ fn describe_action(resource_id: &str, resource: &Resource, action: &PlanAction)
             | ResourceType::WasmBundle
             | ResourceType::Image
             | ResourceType::Build
-            | ResourceType::GithubRelease => format!("{resource_id}: create"),
+            | ResourceType::GithubRelease
+            | ResourceType::DockerNetwork
+            | ResourceType::DockerVolume
+            | ResourceType::ContainerStack
+            | ResourceType::WaitFor
+            | ResourceType::Exec
+            | ResourceType::Swap
+            | ResourceType::Partition
+            | ResourceType::LvmVg
+            | ResourceType::LvmLv
+            | ResourceType::Filesystem
+            | ResourceType::WireguardInterface
+            | ResourceType::WireguardPeer
+            | ResourceType::NetworkInterface
+            | ResourceType::Certificate
+            | ResourceType::System
+            | ResourceType::ZfsPool
+            | ResourceType::ZfsDataset
+            | ResourceType::ZfsSnapshotPolicy
+            | ResourceType::BinaryRelease
+            | ResourceType::PgRole
+            | ResourceType::PgDatabase
+            | ResourceType::PgExtension
+            | ResourceType::ApparmorProfile
+            | ResourceType::SelinuxBoolean
+            | ResourceType::SelinuxFcontext
+            | ResourceType::SelinuxMode => format!("{resource_id}: create"),
         },
         PlanAction::Update => format!("{resource_id}: update (state changed)"),
         PlanAction::Destroy => format!("{resource_id}: destroy"),
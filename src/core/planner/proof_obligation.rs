@@ -49,6 +49,31 @@ fn classify_create(rtype: &ResourceType) -> ProofObligation {
         ResourceType::WasmBundle | ResourceType::Image => ProofObligation::Idempotent,
         ResourceType::Build => ProofObligation::Convergent,
         ResourceType::GithubRelease => ProofObligation::Idempotent,
+        ResourceType::DockerNetwork | ResourceType::DockerVolume => ProofObligation::Idempotent,
+        ResourceType::ContainerStack => ProofObligation::Convergent,
+        ResourceType::WaitFor => ProofObligation::Idempotent,
+        ResourceType::Exec => ProofObligation::Convergent,
+        ResourceType::Swap => ProofObligation::Idempotent,
+        ResourceType::Partition => ProofObligation::Idempotent,
+        ResourceType::LvmVg => ProofObligation::Idempotent,
+        ResourceType::LvmLv => ProofObligation::Idempotent,
+        ResourceType::Filesystem => ProofObligation::Idempotent,
+        ResourceType::WireguardInterface => ProofObligation::Convergent,
+        ResourceType::WireguardPeer => ProofObligation::Convergent,
+        ResourceType::NetworkInterface => ProofObligation::Convergent,
+        ResourceType::Certificate => ProofObligation::Convergent,
+        ResourceType::System => ProofObligation::Convergent,
+        ResourceType::ZfsPool => ProofObligation::Convergent,
+        ResourceType::ZfsDataset => ProofObligation::Convergent,
+        ResourceType::ZfsSnapshotPolicy => ProofObligation::Convergent,
+        ResourceType::BinaryRelease => ProofObligation::Idempotent,
+        ResourceType::PgRole => ProofObligation::Idempotent,
+        ResourceType::PgDatabase => ProofObligation::Idempotent,
+        ResourceType::PgExtension => ProofObligation::Idempotent,
+        ResourceType::ApparmorProfile => ProofObligation::Idempotent,
+        ResourceType::SelinuxBoolean => ProofObligation::Idempotent,
+        ResourceType::SelinuxFcontext => ProofObligation::Idempotent,
+        ResourceType::SelinuxMode => ProofObligation::Idempotent,
     }
 }
 
@@ -80,6 +105,32 @@ fn classify_destroy(rtype: &ResourceType) -> ProofObligation {
         ResourceType::WasmBundle | ResourceType::Image => ProofObligation::Destructive,
         ResourceType::Build => ProofObligation::Convergent,
         ResourceType::GithubRelease => ProofObligation::Convergent,
+        ResourceType::DockerNetwork => ProofObligation::Convergent,
+        ResourceType::DockerVolume => ProofObligation::Destructive,
+        ResourceType::ContainerStack => ProofObligation::Convergent,
+        ResourceType::WaitFor => ProofObligation::Idempotent,
+        ResourceType::Exec => ProofObligation::Idempotent,
+        ResourceType::Swap => ProofObligation::Destructive,
+        ResourceType::Partition => ProofObligation::Destructive,
+        ResourceType::LvmVg => ProofObligation::Destructive,
+        ResourceType::LvmLv => ProofObligation::Destructive,
+        ResourceType::Filesystem => ProofObligation::Destructive,
+        ResourceType::WireguardInterface => ProofObligation::Destructive,
+        ResourceType::WireguardPeer => ProofObligation::Convergent,
+        ResourceType::NetworkInterface => ProofObligation::Convergent,
+        ResourceType::Certificate => ProofObligation::Destructive,
+        ResourceType::System => ProofObligation::Idempotent,
+        ResourceType::ZfsPool => ProofObligation::Destructive,
+        ResourceType::ZfsDataset => ProofObligation::Destructive,
+        ResourceType::ZfsSnapshotPolicy => ProofObligation::Idempotent,
+        ResourceType::BinaryRelease => ProofObligation::Convergent,
+        ResourceType::PgRole => ProofObligation::Convergent,
+        ResourceType::PgDatabase => ProofObligation::Destructive,
+        ResourceType::PgExtension => ProofObligation::Convergent,
+        ResourceType::ApparmorProfile => ProofObligation::Convergent,
+        ResourceType::SelinuxBoolean => ProofObligation::Convergent,
+        ResourceType::SelinuxFcontext => ProofObligation::Convergent,
+        ResourceType::SelinuxMode => ProofObligation::Convergent,
     }
 }
 
@@ -14,11 +14,27 @@ pub enum Reversibility {
 /// Classify a planned resource operation.
 pub fn classify(resource: &Resource, action: &PlanAction) -> Reversibility {
     match action {
-        PlanAction::NoOp | PlanAction::Create | PlanAction::Update => Reversibility::Reversible,
+        PlanAction::NoOp => Reversibility::Reversible,
+        PlanAction::Create | PlanAction::Update if overwrites_device(resource) => {
+            Reversibility::Irreversible
+        }
+        PlanAction::Create | PlanAction::Update => Reversibility::Reversible,
         PlanAction::Destroy => classify_destroy(resource),
     }
 }
 
+/// Disk provisioning that writes signatures over whatever the device held.
+fn overwrites_device(resource: &Resource) -> bool {
+    match resource.resource_type {
+        ResourceType::Partition | ResourceType::LvmVg | ResourceType::Filesystem => true,
+        ResourceType::Swap => resource
+            .path
+            .as_deref()
+            .is_some_and(|p| p.starts_with("/dev/")),
+        _ => false,
+    }
+}
+
 /// Classify a destroy operation based on resource type and config.
 fn classify_destroy(resource: &Resource) -> Reversibility {
     match resource.resource_type {
@@ -58,6 +74,44 @@ fn classify_destroy(resource: &Resource) -> Reversibility {
         ResourceType::Build => Reversibility::Reversible,
         // GitHub release binary can be re-downloaded
         ResourceType::GithubRelease => Reversibility::Reversible,
+        // Networks are re-createable; volume removal loses the data they hold
+        ResourceType::DockerNetwork => Reversibility::Reversible,
+        ResourceType::DockerVolume => Reversibility::Irreversible,
+        // Stacks are re-createable from the spec; named volumes are kept
+        ResourceType::ContainerStack => Reversibility::Reversible,
+        // Probes have no side effects
+        ResourceType::WaitFor => Reversibility::Reversible,
+        // Removing an exec runs nothing
+        ResourceType::Exec => Reversibility::Reversible,
+        // Disk provisioning teardown destroys the data on the device
+        ResourceType::Swap
+        | ResourceType::Partition
+        | ResourceType::LvmVg
+        | ResourceType::LvmLv
+        | ResourceType::Filesystem => Reversibility::Irreversible,
+        // Removing the interface deletes its private key; peers must learn the new public key
+        ResourceType::WireguardInterface => Reversibility::Irreversible,
+        ResourceType::WireguardPeer => Reversibility::Reversible,
+        ResourceType::NetworkInterface => Reversibility::Reversible,
+        // Removing the certificate deletes its private key; re-issuing generates a new one
+        ResourceType::Certificate => Reversibility::Irreversible,
+        // Removing the system resource only stops managing host identity
+        ResourceType::System => Reversibility::Reversible,
+        // Destroying a pool destroys every dataset on it
+        ResourceType::ZfsPool => Reversibility::Irreversible,
+        // Destroying a dataset destroys its data and snapshots
+        ResourceType::ZfsDataset => Reversibility::Irreversible,
+        // Removing the policy keeps existing snapshots
+        ResourceType::ZfsSnapshotPolicy => Reversibility::Reversible,
+        ResourceType::BinaryRelease => Reversibility::Reversible,
+        ResourceType::PgRole => Reversibility::Reversible,
+        // dropping a database destroys its data
+        ResourceType::PgDatabase => Reversibility::Irreversible,
+        ResourceType::PgExtension => Reversibility::Reversible,
+        ResourceType::ApparmorProfile => Reversibility::Reversible,
+        ResourceType::SelinuxBoolean => Reversibility::Reversible,
+        ResourceType::SelinuxFcontext => Reversibility::Reversible,
+        ResourceType::SelinuxMode => Reversibility::Reversible,
     }
 }
 
@@ -65,7 +119,6 @@ fn classify_destroy(resource: &Resource) -> Reversibility {
 pub fn count_irreversible(config: &ForjarConfig, plan: &ExecutionPlan) -> usize {
     plan.changes
         .iter()
-        .filter(|c| c.action == PlanAction::Destroy)
         .filter(|c| {
             config
                 .resources
@@ -80,13 +133,15 @@ pub fn count_irreversible(config: &ForjarConfig, plan: &ExecutionPlan) -> usize
 pub fn warn_irreversible(config: &ForjarConfig, plan: &ExecutionPlan) -> Vec<String> {
     plan.changes
         .iter()
-        .filter(|c| c.action == PlanAction::Destroy)
         .filter_map(|c| {
             let resource = config.resources.get(&c.resource_id)?;
             if classify(resource, &c.action) == Reversibility::Irreversible {
                 Some(format!(
-                    "{} on {} — irreversible {} destroy",
-                    c.resource_id, c.machine, c.resource_type
+                    "{} on {} — irreversible {} {}",
+                    c.resource_id,
+                    c.machine,
+                    c.resource_type,
+                    c.action.to_string().to_lowercase()
                 ))
             } else {
                 None
@@ -211,6 +211,7 @@ mod tests {
             secrets: Default::default(),
             includes: vec![],
             include_provenance: HashMap::new(),
+            recipe_outputs: indexmap::IndexMap::new(),
             data: indexmap::IndexMap::new(),
             checks: indexmap::IndexMap::new(),
             environments: indexmap::IndexMap::new(),
@@ -383,4 +384,71 @@ mod tests {
         // Resource "gone" not in config => defaults to irreversible
         assert_eq!(count_irreversible(&config, &plan), 1);
     }
+
+    #[test]
+    fn test_disk_provisioning_create_irreversible() {
+        for rtype in [
+            ResourceType::Partition,
+            ResourceType::LvmVg,
+            ResourceType::Filesystem,
+        ] {
+            let r = minimal_resource(rtype);
+            assert_eq!(
+                classify(&r, &PlanAction::Create),
+                Reversibility::Irreversible
+            );
+            assert_eq!(
+                classify(&r, &PlanAction::Destroy),
+                Reversibility::Irreversible
+            );
+        }
+        // LVs carve space out of a group without touching existing data
+        let lv = minimal_resource(ResourceType::LvmLv);
+        assert_eq!(
+            classify(&lv, &PlanAction::Create),
+            Reversibility::Reversible
+        );
+    }
+
+    #[test]
+    fn test_swap_file_vs_device() {
+        let mut r = minimal_resource(ResourceType::Swap);
+        assert_eq!(classify(&r, &PlanAction::Create), Reversibility::Reversible);
+        assert_eq!(
+            classify(&r, &PlanAction::Destroy),
+            Reversibility::Irreversible
+        );
+        r.path = Some("/dev/vdb2".to_string());
+        assert_eq!(
+            classify(&r, &PlanAction::Create),
+            Reversibility::Irreversible
+        );
+    }
+
+    #[test]
+    fn test_warn_irreversible_filesystem_create() {
+        let mut config = test_config();
+        config
+            .resources
+            .insert("pgdata".into(), minimal_resource(ResourceType::Filesystem));
+        let plan = ExecutionPlan {
+            name: "test".to_string(),
+            changes: vec![PlannedChange {
+                resource_id: "pgdata".into(),
+                resource_type: ResourceType::Filesystem,
+                machine: "local".into(),
+                action: PlanAction::Create,
+                description: "create pgdata".into(),
+            }],
+            execution_order: vec![],
+            to_create: 1,
+            to_update: 0,
+            to_destroy: 0,
+            unchanged: 0,
+        };
+        assert_eq!(count_irreversible(&config, &plan), 1);
+        let warnings = warn_irreversible(&config, &plan);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("create"), "{}", warnings[0]);
+    }
 }
@@ -0,0 +1,249 @@
+//! FJ-1433: ML-DSA-65 (FIPS 204) post-quantum signatures.
+//!
+//! The PQ half of a keypair lives in the keyring's `ml-dsa-65/`
+//! subdirectory: `<name>.key` holds the 32-byte FIPS 204 seed
+//! (`ml-dsa-65-seed:<base64>`, mode 0600) and `<name>.pub` the encoded
+//! public key (`ml-dsa-65:<base64>`). Signatures use the pure (non
+//! pre-hash) ML-DSA.Sign with the `forjar` context string and the
+//! deterministic variant, so re-signing the same content is reproducible.
+
+use super::signing::{write_private, Keyring};
+use base64::Engine;
+use ml_dsa::{EncodedVerifyingKey, ExpandedSigningKey, MlDsa65, Seed, VerifyingKey};
+use std::path::{Path, PathBuf};
+
+/// Post-quantum signature algorithm.
+pub const PQ_ALGORITHM: &str = "ml-dsa-65";
+
+/// Prefix of a PQ public key in text form.
+pub const PQ_KEY_PREFIX: &str = "ml-dsa-65:";
+
+const SEED_PREFIX: &str = "ml-dsa-65-seed:";
+
+/// FIPS 204 context string binding signatures to forjar.
+const CONTEXT: &[u8] = b"forjar";
+
+fn b64() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+/// An ML-DSA-65 public key (1952 bytes encoded).
+#[derive(Clone, PartialEq, Eq)]
+pub struct PqPublicKey(Vec<u8>);
+
+impl PqPublicKey {
+    /// Parse `ml-dsa-65:<base64>`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let encoded = text
+            .trim()
+            .strip_prefix(PQ_KEY_PREFIX)
+            .ok_or_else(|| format!("PQ public key must start with '{PQ_KEY_PREFIX}'"))?;
+        let raw = b64()
+            .decode(encoded)
+            .map_err(|e| format!("invalid PQ public key: {e}"))?;
+        Self::from_bytes(&raw)
+    }
+
+    /// Wrap an encoded public key (FIPS 204 pkEncode).
+    pub fn from_bytes(raw: &[u8]) -> Result<Self, String> {
+        EncodedVerifyingKey::<MlDsa65>::try_from(raw)
+            .map_err(|_| format!("invalid PQ public key: wrong length ({} bytes)", raw.len()))?;
+        Ok(Self(raw.to_vec()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Whether `signature` is a valid ML-DSA-65 signature of `message`.
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        let Ok(encoded) = EncodedVerifyingKey::<MlDsa65>::try_from(self.0.as_slice()) else {
+            return false;
+        };
+        // A malformed signature is a failed verification, not an error.
+        let Ok(sig) = ml_dsa::Signature::<MlDsa65>::try_from(signature) else {
+            return false;
+        };
+        VerifyingKey::<MlDsa65>::decode(&encoded).verify_with_context(message, CONTEXT, &sig)
+    }
+}
+
+impl std::fmt::Display for PqPublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{PQ_KEY_PREFIX}{}", b64().encode(&self.0))
+    }
+}
+
+/// Abbreviated: the full key is 2.6k characters.
+impl std::fmt::Debug for PqPublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hash = blake3::hash(&self.0).to_hex();
+        write!(f, "PqPublicKey(blake3:{})", &hash[..16])
+    }
+}
+
+/// An ML-DSA-65 private key, kept as its FIPS 204 seed.
+pub struct PqSigningKey {
+    pub name: String,
+    seed: [u8; 32],
+}
+
+/// Never prints the seed.
+impl std::fmt::Debug for PqSigningKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PqSigningKey")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PqSigningKey {
+    /// Generate a fresh keypair from a random seed.
+    pub fn generate(name: &str) -> Result<Self, String> {
+        let mut seed = [0u8; 32];
+        openssl::rand::rand_bytes(&mut seed).map_err(|e| format!("ml-dsa keygen: {e}"))?;
+        Ok(Self::from_seed(name, seed))
+    }
+
+    /// Build a key from its 32-byte seed (FIPS 204 ML-DSA.KeyGen_internal).
+    pub fn from_seed(name: &str, seed: [u8; 32]) -> Self {
+        Self {
+            name: name.to_string(),
+            seed,
+        }
+    }
+
+    fn expanded(&self) -> ExpandedSigningKey<MlDsa65> {
+        ExpandedSigningKey::from_seed(&Seed::from(self.seed))
+    }
+
+    pub fn public_key(&self) -> PqPublicKey {
+        PqPublicKey(self.expanded().verifying_key().encode().to_vec())
+    }
+
+    /// ML-DSA-65 signature of `message`.
+    pub fn sign(&self, message: &[u8]) -> Result<Vec<u8>, String> {
+        let sig = self
+            .expanded()
+            .sign_deterministic(message, CONTEXT)
+            .map_err(|e| format!("ml-dsa sign: {e}"))?;
+        Ok(sig.encode().to_vec())
+    }
+
+    fn to_text(&self) -> String {
+        format!("{SEED_PREFIX}{}\n", b64().encode(self.seed))
+    }
+
+    fn parse(name: &str, text: &str) -> Result<Self, String> {
+        let encoded = text
+            .trim()
+            .strip_prefix(SEED_PREFIX)
+            .ok_or_else(|| format!("PQ key '{name}' must start with '{SEED_PREFIX}'"))?;
+        let raw = b64()
+            .decode(encoded)
+            .map_err(|e| format!("invalid PQ key '{name}': {e}"))?;
+        let seed: [u8; 32] = raw
+            .try_into()
+            .map_err(|_| format!("invalid PQ key '{name}': expected a 32-byte seed"))?;
+        Ok(Self::from_seed(name, seed))
+    }
+}
+
+// ── Keyring ────────────────────────────────────────────────────────
+
+impl Keyring {
+    fn pq_dir(&self) -> PathBuf {
+        self.dir().join(PQ_ALGORITHM)
+    }
+
+    fn pq_private_path(&self, name: &str) -> PathBuf {
+        self.pq_dir().join(format!("{name}.key"))
+    }
+
+    fn pq_public_path(&self, name: &str) -> PathBuf {
+        self.pq_dir().join(format!("{name}.pub"))
+    }
+
+    /// Add an ML-DSA-65 keypair to the key named `name`.
+    pub fn generate_pq(&self, name: &str) -> Result<PqSigningKey, String> {
+        super::signing::check_key_name(name)?;
+        if self.pq_public_path(name).exists() {
+            return Err(format!("key '{name}' already has a {PQ_ALGORITHM} half"));
+        }
+        let dir = self.pq_dir();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("create {}: {e}", dir.display()))?;
+        let key = PqSigningKey::generate(name)?;
+        write_private(&self.pq_private_path(name), key.to_text().as_bytes())?;
+        let path = self.pq_public_path(name);
+        std::fs::write(&path, format!("{}\n", key.public_key()))
+            .map_err(|e| format!("write {}: {e}", path.display()))?;
+        Ok(key)
+    }
+
+    pub fn pq_signing_key(&self, name: &str) -> Result<PqSigningKey, String> {
+        super::signing::check_key_name(name)?;
+        let text = std::fs::read_to_string(self.pq_private_path(name)).map_err(|_| {
+            format!(
+                "key '{name}' has no {PQ_ALGORITHM} private key in {} (see `forjar keys generate --pq`)",
+                self.dir().display()
+            )
+        })?;
+        PqSigningKey::parse(name, &text)
+    }
+
+    pub fn pq_public_key(&self, name: &str) -> Result<PqPublicKey, String> {
+        super::signing::check_key_name(name)?;
+        let text = std::fs::read_to_string(self.pq_public_path(name)).map_err(|_| {
+            format!(
+                "key '{name}' has no {PQ_ALGORITHM} public key in {}",
+                self.dir().display()
+            )
+        })?;
+        PqPublicKey::parse(&text)
+    }
+}
+
+/// PQ signing key named by `spec`: a keyring name, or the path of an
+/// Ed25519 key file whose directory holds the matching `ml-dsa-65/` half.
+pub fn load_pq_signing_key(spec: &str) -> Result<PqSigningKey, String> {
+    let path = Path::new(spec);
+    if path.is_file() {
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| spec.to_string());
+        return Keyring::open(dir).pq_signing_key(&name);
+    }
+    Keyring::open(&super::signing::default_keyring_dir()).pq_signing_key(spec)
+}
+
+/// PQ public key named by `spec`: `ml-dsa-65:<base64>`, a keyring name, or
+/// the path of an Ed25519 key file whose directory holds the matching
+/// `ml-dsa-65/` half.
+pub fn load_pq_public_key(spec: &str) -> Result<PqPublicKey, String> {
+    if spec.starts_with(PQ_KEY_PREFIX) {
+        return PqPublicKey::parse(spec);
+    }
+    let path = Path::new(spec);
+    if path.is_file() {
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| spec.to_string());
+        return Keyring::open(dir).pq_public_key(&name);
+    }
+    Keyring::open(&super::signing::default_keyring_dir()).pq_public_key(spec)
+}
+
+/// PQ signing key named by `spec`, else by `FORJAR_SIGNING_KEY`.
+pub fn resolve_pq_signing_key(spec: Option<&str>) -> Result<PqSigningKey, String> {
+    match spec {
+        Some(spec) => load_pq_signing_key(spec),
+        None => match std::env::var("FORJAR_SIGNING_KEY") {
+            Ok(spec) if !spec.is_empty() => load_pq_signing_key(&spec),
+            _ => Err("no signing key: pass --signer or set FORJAR_SIGNING_KEY".to_string()),
+        },
+    }
+}
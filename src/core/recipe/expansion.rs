@@ -2,7 +2,7 @@
 
 use super::super::types::{MachineTarget, Resource};
 use super::types::RecipeFile;
-use super::validation::validate_inputs;
+use super::validation::{validate_declarations, validate_inputs};
 use indexmap::IndexMap;
 use provable_contracts_macros::contract;
 use std::collections::HashMap;
@@ -20,6 +20,29 @@ pub fn parse_recipe(yaml: &str) -> Result<RecipeFile, String> {
     serde_yaml_ng::from_str(yaml).map_err(|e| format!("recipe parse error: {e}"))
 }
 
+/// Value of `{{inputs.<key>}}`: a resolved input, or a dotted path into a
+/// list, map or object input (`tls.cert`, `tls.protocols.0`). Strings render
+/// bare, everything else as compact JSON.
+fn input_value(inputs: &HashMap<String, String>, key: &str) -> Option<String> {
+    if let Some(value) = inputs.get(key) {
+        return Some(value.clone());
+    }
+    let mut path = key.split('.');
+    let mut value: serde_json::Value = serde_json::from_str(inputs.get(path.next()?)?).ok()?;
+    for field in path {
+        value = match value {
+            serde_json::Value::Object(mut map) => map.remove(field)?,
+            serde_json::Value::Array(items) => items.into_iter().nth(field.parse().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(match value {
+        serde_json::Value::String(s) => s,
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    })
+}
+
 /// Resolve `{{inputs.X}}` templates in a string.
 pub(crate) fn resolve_input_template(
     template: &str,
@@ -36,11 +59,9 @@ pub(crate) fn resolve_input_template(
         let close = open + close + 2;
         let key = result[open + 9..close - 2].trim();
 
-        let value = inputs
-            .get(key)
-            .ok_or_else(|| format!("unknown input: {key}"))?;
+        let value = input_value(inputs, key).ok_or_else(|| format!("unknown input: {key}"))?;
 
-        result.replace_range(open..close, value);
+        result.replace_range(open..close, &value);
         start = open + value.len();
     }
 
@@ -123,7 +144,8 @@ pub(crate) fn resolve_resource_inputs(
     // Store / derivation fields
     r.script = resolve_opt(&r.script, inputs)?;
 
-    // Docker fields (Vec<String>)
+    // Package/Docker fields (Vec<String>)
+    r.packages = resolve_vec(&r.packages, inputs)?;
     r.ports = resolve_vec(&r.ports, inputs)?;
     r.environment = resolve_vec(&r.environment, inputs)?;
     r.volumes = resolve_vec(&r.volumes, inputs)?;
@@ -131,6 +153,49 @@ pub(crate) fn resolve_resource_inputs(
     Ok(r)
 }
 
+/// FJ-3315: Values of list inputs, for splicing into list fields.
+fn list_inputs(
+    recipe_file: &RecipeFile,
+    inputs: &HashMap<String, String>,
+) -> HashMap<String, Vec<String>> {
+    recipe_file
+        .recipe
+        .inputs
+        .iter()
+        .filter(|(_, decl)| decl.input_type == "list")
+        .filter_map(|(name, _)| {
+            let items: Vec<serde_json::Value> = serde_json::from_str(inputs.get(name)?).ok()?;
+            let items = items
+                .into_iter()
+                .map(|item| match item {
+                    serde_json::Value::String(s) => s,
+                    other => other.to_string(),
+                })
+                .collect();
+            Some((name.clone(), items))
+        })
+        .collect()
+}
+
+/// FJ-3315: Replace list-field elements that are exactly `{{inputs.X}}` of a
+/// list input with the list's items.
+fn splice_list_inputs(fields: &[String], lists: &HashMap<String, Vec<String>>) -> Vec<String> {
+    fields
+        .iter()
+        .flat_map(|field| {
+            let key = field
+                .trim()
+                .strip_prefix("{{")
+                .and_then(|f| f.strip_suffix("}}"))
+                .and_then(|f| f.trim().strip_prefix("inputs."));
+            match key.and_then(|k| lists.get(k)) {
+                Some(items) => items.clone(),
+                None => vec![field.clone()],
+            }
+        })
+        .collect()
+}
+
 /// Expand a recipe instance into namespaced resources.
 ///
 /// Given a recipe resource in the config (type: recipe), load and expand it
@@ -145,8 +210,10 @@ pub fn expand_recipe(
 ) -> Result<IndexMap<String, Resource>, String> {
     // Contract: recipe-determinism-v1.yaml precondition (pv codegen)
     contract_pre_expand_recipe!(recipe_id);
-    // Validate inputs
+    // Validate declarations and inputs
+    validate_declarations(recipe_file)?;
     let resolved_inputs = validate_inputs(&recipe_file.recipe, provided_inputs)?;
+    let lists = list_inputs(recipe_file, &resolved_inputs);
 
     let mut expanded = IndexMap::new();
     let mut first = true;
@@ -154,8 +221,15 @@ pub fn expand_recipe(
     for (res_name, resource) in &recipe_file.resources {
         let namespaced_id = format!("{recipe_id}/{res_name}");
 
-        // Resolve input templates
-        let mut resolved = resolve_resource_inputs(resource, &resolved_inputs)?;
+        // Resolve input templates, splicing list inputs into list fields
+        let mut spliced = resource.clone();
+        if !lists.is_empty() {
+            spliced.packages = splice_list_inputs(&spliced.packages, &lists);
+            spliced.ports = splice_list_inputs(&spliced.ports, &lists);
+            spliced.environment = splice_list_inputs(&spliced.environment, &lists);
+            spliced.volumes = splice_list_inputs(&spliced.volumes, &lists);
+        }
+        let mut resolved = resolve_resource_inputs(&spliced, &resolved_inputs)?;
 
         // Propagate machine target
         resolved.machine = machine.clone();
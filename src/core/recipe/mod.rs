@@ -6,6 +6,10 @@
 //! (e.g., `my-recipe/resource-name`).
 
 pub mod expansion;
+pub mod outputs;
+pub mod registry;
+pub mod resolve;
+pub mod schema;
 pub mod types;
 pub mod validation;
 pub mod validation_types;
@@ -20,4 +24,10 @@ mod tests_expansion;
 #[cfg(test)]
 mod tests_integration;
 #[cfg(test)]
+mod tests_outputs;
+#[cfg(test)]
+mod tests_resolve;
+#[cfg(test)]
+mod tests_schema;
+#[cfg(test)]
 mod tests_validation;
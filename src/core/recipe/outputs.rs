@@ -0,0 +1,115 @@
+//! FJ-3315: Recipe outputs — values a recipe exports to its consumers.
+//!
+//! An output either renders a `value` template over the recipe's inputs and
+//! its expanded resources (`{{resources.<name>.<field>}}`), or names a value
+//! captured from an inner resource's apply script (`capture: <name>.<key>`).
+//! Consumers address them as `{{recipes.<id>.outputs.<output>}}`.
+
+use super::super::types::{RecipeOutputBinding, Resource};
+use super::expansion::resolve_input_template;
+use super::types::RecipeFile;
+use super::validation::validate_inputs;
+use indexmap::IndexMap;
+use std::collections::HashMap;
+
+/// Bind the outputs of recipe instance `recipe_id`, keyed
+/// `<recipe_id>.outputs.<name>`. `expanded` holds its expanded resources.
+pub fn recipe_outputs(
+    recipe_id: &str,
+    recipe_file: &RecipeFile,
+    provided_inputs: &HashMap<String, serde_yaml_ng::Value>,
+    expanded: &IndexMap<String, Resource>,
+) -> Result<IndexMap<String, RecipeOutputBinding>, String> {
+    if recipe_file.outputs.is_empty() {
+        return Ok(IndexMap::new());
+    }
+    let inputs = validate_inputs(&recipe_file.recipe, provided_inputs)?;
+    let mut bound = IndexMap::new();
+    for (name, output) in &recipe_file.outputs {
+        let ctx = || format!("recipe '{}' output '{name}'", recipe_file.recipe.name);
+        let binding = match (&output.value, &output.capture) {
+            (Some(template), None) => {
+                let value = resolve_input_template(template, &inputs)
+                    .and_then(|v| resolve_resource_refs(&v, recipe_id, expanded))
+                    .map_err(|e| format!("{}: {e}", ctx()))?;
+                RecipeOutputBinding::Value(value)
+            }
+            (None, Some(capture)) => {
+                let (resource, key) = capture
+                    .split_once('.')
+                    .ok_or_else(|| format!("{}: capture must be <resource>.<key>", ctx()))?;
+                RecipeOutputBinding::Capture {
+                    output: format!("{recipe_id}/{resource}.{key}"),
+                    default: output.default.clone(),
+                }
+            }
+            _ => return Err(format!("{}: set exactly one of value or capture", ctx())),
+        };
+        bound.insert(format!("{recipe_id}.outputs.{name}"), binding);
+    }
+    Ok(bound)
+}
+
+/// Replace `{{resources.<name>.<field>}}` with a scalar field of the expanded
+/// resource `<recipe_id>/<name>`.
+fn resolve_resource_refs(
+    template: &str,
+    recipe_id: &str,
+    expanded: &IndexMap<String, Resource>,
+) -> Result<String, String> {
+    let mut result = template.to_string();
+    let mut start = 0;
+    while let Some(open) = result[start..].find("{{resources.") {
+        let open = start + open;
+        let close = result[open..]
+            .find("}}")
+            .ok_or_else(|| format!("unclosed template at position {open}"))?;
+        let close = open + close + 2;
+        let reference = result[open + 12..close - 2].trim();
+        let value = resource_field(reference, recipe_id, expanded)?;
+        result.replace_range(open..close, &value);
+        start = open + value.len();
+    }
+    Ok(result)
+}
+
+/// Scalar value of `<name>.<field>` among the expanded resources.
+fn resource_field(
+    reference: &str,
+    recipe_id: &str,
+    expanded: &IndexMap<String, Resource>,
+) -> Result<String, String> {
+    let (name, field) = reference
+        .split_once('.')
+        .ok_or_else(|| format!("invalid resource reference '{reference}'"))?;
+    let resource = expanded
+        .get(&format!("{recipe_id}/{name}"))
+        .ok_or_else(|| format!("unknown recipe resource '{name}'"))?;
+    let doc = serde_yaml_ng::to_value(resource).map_err(|e| format!("resource '{name}': {e}"))?;
+    match doc.get(field) {
+        Some(serde_yaml_ng::Value::String(s)) => Ok(s.clone()),
+        Some(serde_yaml_ng::Value::Number(n)) => Ok(n.to_string()),
+        Some(serde_yaml_ng::Value::Bool(b)) => Ok(b.to_string()),
+        Some(serde_yaml_ng::Value::Null) | None => {
+            Err(format!("resource '{name}' has no field '{field}'"))
+        }
+        Some(_) => Err(format!("resource '{name}' field '{field}' is not a scalar")),
+    }
+}
+
+/// `recipes.<id>.outputs.<name>` references in `text`, without the `recipes.` prefix.
+pub fn output_refs(text: &str) -> Vec<&str> {
+    let mut refs = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find("{{") {
+        let after = &rest[open + 2..];
+        let Some(close) = after.find("}}") else {
+            break;
+        };
+        if let Some(key) = after[..close].trim().strip_prefix("recipes.") {
+            refs.push(key);
+        }
+        rest = &after[close + 2..];
+    }
+    refs
+}
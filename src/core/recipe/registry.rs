@@ -0,0 +1,155 @@
+//! FJ-1426 + FJ-3314: Versioned recipe registries.
+//!
+//! A registry is a directory with an `index.json` listing recipe files by
+//! name, version and BLAKE3 hash. Registries are local directories or git
+//! repositories (`git+<repo>@<ref>[//subdir]`), which are checked out into
+//! the fetch cache and reused until refreshed.
+
+use crate::core::git_fetch;
+use semver::{Version, VersionReq};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A registered recipe entry.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RegistryEntry {
+    pub name: String,
+    pub version: String,
+    pub path: String,
+    pub blake3: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Registry index.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct RegistryIndex {
+    pub entries: Vec<RegistryEntry>,
+}
+
+/// Initialize or load a registry index.
+pub fn load_index(registry_dir: &Path) -> Result<RegistryIndex, String> {
+    let index_path = registry_dir.join("index.json");
+    if !index_path.exists() {
+        return Ok(RegistryIndex::default());
+    }
+    let data = std::fs::read_to_string(&index_path).map_err(|e| format!("read index: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("parse index: {e}"))
+}
+
+/// Get the default registry directory.
+pub fn default_registry_dir() -> PathBuf {
+    std::env::var("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("/var/lib"))
+        .join(".forjar")
+        .join("registry")
+}
+
+/// One version of a recipe offered by a registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// Recipe name.
+    pub name: String,
+    /// Recipe version.
+    pub version: Version,
+    /// Recipe file.
+    pub path: PathBuf,
+    /// BLAKE3 hash recorded in the index.
+    pub blake3: String,
+}
+
+/// Split a recipe reference `name[@constraint]`.
+pub fn parse_recipe_ref(spec: &str) -> Result<(&str, Option<VersionReq>), String> {
+    let Some((name, constraint)) = spec.split_once('@') else {
+        return Ok((spec, None));
+    };
+    if name.is_empty() {
+        return Err(format!("recipe reference '{spec}' has no name"));
+    }
+    let req = VersionReq::parse(constraint.trim())
+        .map_err(|e| format!("recipe '{name}': invalid version constraint '{constraint}': {e}"))?;
+    Ok((name, Some(req)))
+}
+
+/// Parse a registry version, accepting `1` and `1.2` as `1.0.0` and `1.2.0`.
+pub fn parse_version(version: &str) -> Result<Version, String> {
+    let v = version.trim().trim_start_matches('v');
+    let padded = match v
+        .split(['-', '+'])
+        .next()
+        .unwrap_or("")
+        .matches('.')
+        .count()
+    {
+        0 => format!("{v}.0.0"),
+        1 => format!("{v}.0"),
+        _ => v.to_string(),
+    };
+    Version::parse(&padded).map_err(|e| format!("invalid version '{version}': {e}"))
+}
+
+/// Local directory of registry `spec`: a path relative to `base_dir`, or a
+/// `git+` repository checked out into the cache. `refresh` re-fetches git
+/// registries that are already cached.
+pub fn open_registry(spec: &str, base_dir: &Path, refresh: bool) -> Result<PathBuf, String> {
+    let Some(rest) = spec.strip_prefix("git+") else {
+        return Ok(base_dir.join(spec));
+    };
+    let (repo, tail) = rest
+        .rsplit_once('@')
+        .ok_or_else(|| format!("git registry '{spec}' must name a ref: git+<repo>@<ref>"))?;
+    let (reference, subdir) = tail.split_once("//").unwrap_or((tail, ""));
+    git_fetch::reject_options(repo, reference)
+        .map_err(|e| format!("git registry '{spec}': {e}"))?;
+    let key = blake3::hash(format!("{repo}@{reference}").as_bytes()).to_hex();
+    let checkout = git_fetch::cache_dir("registries").join(&key[..16]);
+    if refresh || !checkout.join(".git").exists() {
+        checkout_git(&checkout, repo, reference)
+            .map_err(|e| format!("fetch registry '{spec}': {e}"))?;
+    }
+    Ok(checkout.join(subdir))
+}
+
+/// Shallow-fetch `reference` of `repo` and check it out into `dir`.
+fn checkout_git(dir: &Path, repo: &str, reference: &str) -> Result<(), String> {
+    if dir.exists() {
+        std::fs::remove_dir_all(dir).map_err(|e| format!("remove {}: {e}", dir.display()))?;
+    }
+    std::fs::create_dir_all(dir).map_err(|e| format!("create {}: {e}", dir.display()))?;
+    let result = git_fetch::shallow_fetch(dir, repo, reference)
+        .and_then(|()| git_fetch::git(dir, &["checkout", "-q", "FETCH_HEAD"]).map(drop));
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    result
+}
+
+/// Every recipe version offered by `registries`, highest version first.
+/// When two registries offer the same version, the first one wins.
+pub fn candidates(registries: &[PathBuf]) -> Result<BTreeMap<String, Vec<Candidate>>, String> {
+    let mut all: BTreeMap<String, Vec<Candidate>> = BTreeMap::new();
+    for dir in registries {
+        let index = load_index(dir).map_err(|e| format!("registry {}: {e}", dir.display()))?;
+        for entry in index.entries {
+            let version = parse_version(&entry.version)
+                .map_err(|e| format!("registry {}: recipe '{}': {e}", dir.display(), entry.name))?;
+            let versions = all.entry(entry.name.clone()).or_default();
+            if versions.iter().any(|c| c.version == version) {
+                continue;
+            }
+            versions.push(Candidate {
+                path: dir.join(&entry.path),
+                name: entry.name,
+                version,
+                blake3: entry.blake3,
+            });
+        }
+    }
+    for versions in all.values_mut() {
+        versions.sort_by(|a, b| b.version.cmp(&a.version));
+    }
+    Ok(all)
+}
@@ -0,0 +1,363 @@
+//! FJ-3314: Recipe version resolution against registries.
+//!
+//! Resources name registry recipes as `recipe: nginx@^2.1`. Every such
+//! constraint in the config — from resources, from local recipes, and from
+//! the `requires` and nested recipe resources of registry recipes — is
+//! solved together with the DPLL solver in `planner::sat_deps`, so each
+//! recipe resolves to exactly one version. The chosen versions are pinned
+//! with their BLAKE3 hashes in `forjar.inputs.lock.yaml` and kept until
+//! `forjar recipe update`.
+
+use super::registry::{self, parse_recipe_ref, parse_version, Candidate};
+use super::types::RecipeFile;
+use crate::core::planner::sat_deps::{self, SatProblem, SatResult};
+use crate::core::store::lockfile::{read_lockfile, write_lockfile, LockFile, Pin};
+use crate::core::types::{ForjarConfig, ResourceType};
+use semver::{Version, VersionReq};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+const LOCK_FILENAME: &str = "forjar.inputs.lock.yaml";
+const PIN_PREFIX: &str = "recipe:";
+
+/// A version constraint on a registry recipe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requirement {
+    /// Recipe name.
+    pub name: String,
+    /// Accepted versions.
+    pub req: VersionReq,
+    /// What imposed the constraint, e.g. `resource 'web'`.
+    pub from: String,
+}
+
+/// Recipe references in a recipe file: its `requires` and nested recipe
+/// resources.
+fn file_refs(file: &RecipeFile) -> Result<Vec<(String, Option<VersionReq>)>, String> {
+    let nested = file
+        .resources
+        .values()
+        .filter(|r| r.resource_type == ResourceType::Recipe)
+        .filter_map(|r| r.recipe.as_deref());
+    let mut refs = Vec::new();
+    for spec in file
+        .recipe
+        .requires
+        .iter()
+        .map(|r| r.recipe.as_str())
+        .chain(nested)
+    {
+        let (name, req) = parse_recipe_ref(spec)?;
+        refs.push((name.to_string(), req));
+    }
+    Ok(refs)
+}
+
+/// Versioned recipe references of `config`, including those reached
+/// through local recipes in `base_dir/recipes/`.
+pub fn config_requirements(
+    config: &ForjarConfig,
+    base_dir: &Path,
+) -> Result<Vec<Requirement>, String> {
+    let mut reqs = Vec::new();
+    let mut local = VecDeque::new();
+    for (id, resource) in &config.resources {
+        let Some(spec) = resource.recipe.as_deref() else {
+            continue;
+        };
+        if resource.resource_type != ResourceType::Recipe {
+            continue;
+        }
+        match parse_recipe_ref(spec)? {
+            (name, Some(req)) => reqs.push(Requirement {
+                name: name.to_string(),
+                req,
+                from: format!("resource '{id}'"),
+            }),
+            (name, None) => local.push_back(name.to_string()),
+        }
+    }
+    let mut seen = BTreeSet::new();
+    while let Some(name) = local.pop_front() {
+        let path = base_dir.join("recipes").join(format!("{name}.yaml"));
+        if !seen.insert(name.clone()) || !path.exists() {
+            continue;
+        }
+        for (dep, req) in file_refs(&super::load_recipe(&path)?)? {
+            match req {
+                Some(req) => reqs.push(Requirement {
+                    name: dep,
+                    req,
+                    from: format!("recipe '{name}'"),
+                }),
+                None => local.push_back(dep),
+            }
+        }
+    }
+    Ok(reqs)
+}
+
+/// Constraints a registry recipe version places on other registry recipes.
+/// Unversioned references count only when a registry offers the recipe.
+fn candidate_requirements(
+    candidate: &Candidate,
+    available: &BTreeMap<String, Vec<Candidate>>,
+) -> Result<Vec<Requirement>, String> {
+    let file = super::load_recipe(&candidate.path)
+        .map_err(|e| format!("recipe '{}' {}: {e}", candidate.name, candidate.version))?;
+    let from = format!("recipe '{}' {}", candidate.name, candidate.version);
+    Ok(file_refs(&file)?
+        .into_iter()
+        .filter(|(name, req)| req.is_some() || available.contains_key(name))
+        .map(|(name, req)| Requirement {
+            name,
+            req: req.unwrap_or(VersionReq::STAR),
+            from: from.clone(),
+        })
+        .collect())
+}
+
+/// Pick one version of every recipe reachable from `roots` so that all
+/// constraints hold. Versions in `preferred` (the lock) are tried first,
+/// then the highest matching version.
+pub fn solve(
+    roots: &[Requirement],
+    available: &BTreeMap<String, Vec<Candidate>>,
+    preferred: &BTreeMap<String, Version>,
+) -> Result<BTreeMap<String, Candidate>, String> {
+    // One SAT variable per candidate version, numbered from 1.
+    let mut vars: Vec<(Candidate, Vec<Requirement>)> = Vec::new();
+    let mut by_name: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut all_reqs = roots.to_vec();
+    let mut queue: VecDeque<String> = roots.iter().map(|r| r.name.clone()).collect();
+    while let Some(name) = queue.pop_front() {
+        if by_name.contains_key(&name) {
+            continue;
+        }
+        let versions = available
+            .get(&name)
+            .ok_or_else(|| format!("recipe '{name}' is not in any registry"))?;
+        let mut ordered: Vec<&Candidate> = versions.iter().collect();
+        if let Some(locked) = preferred.get(&name) {
+            ordered.sort_by_key(|c| c.version != *locked);
+        }
+        let mut ids = Vec::new();
+        for candidate in ordered {
+            let deps = candidate_requirements(candidate, available)?;
+            queue.extend(deps.iter().map(|d| d.name.clone()));
+            all_reqs.extend(deps.iter().cloned());
+            ids.push(vars.len());
+            vars.push((candidate.clone(), deps));
+        }
+        by_name.insert(name, ids);
+    }
+
+    let lit = |i: usize| i as i32 + 1;
+    let matching = |req: &Requirement| -> Vec<i32> {
+        by_name[&req.name]
+            .iter()
+            .filter(|&&i| req.req.matches(&vars[i].0.version))
+            .map(|&i| lit(i))
+            .collect()
+    };
+    let mut clauses = Vec::new();
+    for root in roots {
+        let any = matching(root);
+        if any.is_empty() {
+            return Err(no_match(root, &available[&root.name]));
+        }
+        clauses.push(any);
+    }
+    for ids in by_name.values() {
+        for (n, &a) in ids.iter().enumerate() {
+            for &b in &ids[n + 1..] {
+                clauses.push(vec![-lit(a), -lit(b)]);
+            }
+        }
+    }
+    for (i, (_, deps)) in vars.iter().enumerate() {
+        for dep in deps {
+            let mut clause = vec![-lit(i)];
+            clause.extend(matching(dep));
+            clauses.push(clause);
+        }
+    }
+
+    let var_name = |c: &Candidate| format!("{}@{}", c.name, c.version);
+    let problem = SatProblem {
+        num_vars: vars.len(),
+        clauses,
+        var_names: (0..vars.len())
+            .map(|i| (i + 1, var_name(&vars[i].0)))
+            .collect(),
+    };
+    let SatResult::Satisfiable { assignment } = sat_deps::solve(&problem) else {
+        return Err(conflict(&all_reqs));
+    };
+
+    // Unconstrained variables default to true, so only follow the
+    // recipes the roots actually reach.
+    let mut selected = BTreeMap::new();
+    let mut queue: VecDeque<&str> = roots.iter().map(|r| r.name.as_str()).collect();
+    while let Some(name) = queue.pop_front() {
+        if selected.contains_key(name) {
+            continue;
+        }
+        let &i = by_name[name]
+            .iter()
+            .find(|&&i| assignment.get(&var_name(&vars[i].0)) == Some(&true))
+            .ok_or_else(|| conflict(&all_reqs))?;
+        queue.extend(vars[i].1.iter().map(|d| d.name.as_str()));
+        selected.insert(name.to_string(), vars[i].0.clone());
+    }
+    Ok(selected)
+}
+
+fn no_match(req: &Requirement, versions: &[Candidate]) -> String {
+    let available: Vec<String> = versions.iter().map(|c| c.version.to_string()).collect();
+    format!(
+        "no version of recipe '{}' matches {} ({}); available: {}",
+        req.name,
+        req.req,
+        req.from,
+        available.join(", ")
+    )
+}
+
+/// Describe the constraints on every recipe constrained more than once.
+fn conflict(reqs: &[Requirement]) -> String {
+    let mut by_name: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for r in reqs {
+        let line = format!("{} ({})", r.req, r.from);
+        let lines = by_name.entry(&r.name).or_default();
+        if !lines.contains(&line) {
+            lines.push(line);
+        }
+    }
+    let mut msg = "no combination of recipe versions satisfies every constraint:".to_string();
+    for (name, lines) in by_name.iter().filter(|(_, l)| l.len() > 1) {
+        msg.push_str(&format!("\n  {name}: {}", lines.join(", ")));
+    }
+    msg
+}
+
+/// Registry directories of `config`: `policy.recipe_registries`, or the
+/// default registry when none are listed.
+fn registry_dirs(
+    config: &ForjarConfig,
+    base_dir: &Path,
+    refresh: bool,
+) -> Result<Vec<PathBuf>, String> {
+    if config.policy.recipe_registries.is_empty() {
+        return Ok(vec![registry::default_registry_dir()]);
+    }
+    config
+        .policy
+        .recipe_registries
+        .iter()
+        .map(|spec| registry::open_registry(spec, base_dir, refresh))
+        .collect()
+}
+
+/// Resolve the versioned recipe references of `config` and pin the result
+/// in the input lock next to it.
+///
+/// Locked versions are kept while they still satisfy every constraint,
+/// except for recipes named in `update` (all recipes when `update` is an
+/// empty slice), which move to the highest version allowed. `update` also
+/// refreshes git registries.
+pub fn resolve_recipes(
+    config: &ForjarConfig,
+    base_dir: &Path,
+    update: Option<&[String]>,
+) -> Result<BTreeMap<String, Candidate>, String> {
+    let roots = config_requirements(config, base_dir)?;
+    let lock_path = base_dir.join(LOCK_FILENAME);
+    let lock = if lock_path.exists() {
+        Some(read_lockfile(&lock_path)?)
+    } else {
+        None
+    };
+    let pins: BTreeMap<&str, &Pin> = lock
+        .iter()
+        .flat_map(|l| &l.pins)
+        .filter_map(|(k, pin)| Some((k.strip_prefix(PIN_PREFIX)?, pin)))
+        .collect();
+    if roots.is_empty() && pins.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+
+    let updating = |name: &str| update.is_some_and(|u| u.is_empty() || u.iter().any(|n| n == name));
+    let selected = if roots.is_empty() {
+        BTreeMap::new()
+    } else {
+        let preferred = pins
+            .iter()
+            .filter(|(name, _)| !updating(name))
+            .filter_map(|(name, pin)| {
+                Some((
+                    name.to_string(),
+                    parse_version(pin.version.as_deref()?).ok()?,
+                ))
+            })
+            .collect();
+        let registries = registry_dirs(config, base_dir, update.is_some())?;
+        solve(&roots, &registry::candidates(&registries)?, &preferred)?
+    };
+
+    for candidate in selected.values() {
+        verify_hash(
+            candidate,
+            pins.get(candidate.name.as_str()).copied(),
+            updating(&candidate.name),
+        )?;
+    }
+
+    let mut next = lock.clone().unwrap_or_else(|| LockFile {
+        schema: "1.0".to_string(),
+        pins: BTreeMap::new(),
+    });
+    next.pins.retain(|k, _| !k.starts_with(PIN_PREFIX));
+    for candidate in selected.values() {
+        next.pins.insert(
+            format!("{PIN_PREFIX}{}", candidate.name),
+            Pin {
+                provider: "registry".to_string(),
+                version: Some(candidate.version.to_string()),
+                hash: candidate.blake3.clone(),
+                git_rev: None,
+                pin_type: Some("recipe".to_string()),
+            },
+        );
+    }
+    if lock.as_ref() != Some(&next) {
+        write_lockfile(&lock_path, &next)?;
+    }
+    Ok(selected)
+}
+
+/// The recipe file must match its index entry and, unless it is being
+/// updated, the hash locked for the same version.
+fn verify_hash(candidate: &Candidate, pin: Option<&Pin>, updating: bool) -> Result<(), String> {
+    let content = std::fs::read(&candidate.path)
+        .map_err(|e| format!("read {}: {e}", candidate.path.display()))?;
+    let hash = blake3::hash(&content).to_hex().to_string();
+    if hash != candidate.blake3 {
+        return Err(format!(
+            "recipe '{}' {} at {} does not match its registry hash",
+            candidate.name,
+            candidate.version,
+            candidate.path.display()
+        ));
+    }
+    let version = candidate.version.to_string();
+    if let Some(pin) = pin.filter(|p| !updating && p.version.as_deref() == Some(version.as_str())) {
+        if pin.hash != hash {
+            return Err(format!(
+                "recipe '{}' {} changed in the registry since it was locked; run `forjar recipe update {}` to accept it",
+                candidate.name, candidate.version, candidate.name
+            ));
+        }
+    }
+    Ok(())
+}
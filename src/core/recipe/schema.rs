@@ -0,0 +1,121 @@
+//! FJ-3315: JSON Schema export of recipe inputs, for editor completion and
+//! validation of `inputs:` on `type: recipe` resources.
+
+use super::types::{RecipeFile, RecipeInput};
+use indexmap::IndexMap;
+use serde_json::{json, Map, Value};
+
+/// JSON Schema (draft 2020-12) of the `inputs:` a recipe accepts.
+pub fn input_schema(recipe_file: &RecipeFile) -> Value {
+    let recipe = &recipe_file.recipe;
+    let mut schema = object_schema(&recipe.inputs);
+    // Undeclared inputs are ignored at expansion, so they are allowed here.
+    schema.remove("additionalProperties");
+    let mut doc = Map::new();
+    doc.insert(
+        "$schema".into(),
+        json!("https://json-schema.org/draft/2020-12/schema"),
+    );
+    doc.insert(
+        "title".into(),
+        json!(format!("{} recipe inputs", recipe.name)),
+    );
+    if let Some(ref description) = recipe.description {
+        doc.insert("description".into(), json!(description));
+    }
+    doc.extend(schema);
+    Value::Object(doc)
+}
+
+/// Whether a declaration must be given by the caller.
+fn is_required(decl: &RecipeInput) -> bool {
+    decl.default.is_none() && decl.required != Some(false)
+}
+
+/// Schema of an object whose fields are `properties`.
+fn object_schema(properties: &IndexMap<String, RecipeInput>) -> Map<String, Value> {
+    let props: Map<String, Value> = properties
+        .iter()
+        .map(|(name, decl)| (name.clone(), input_decl_schema(decl)))
+        .collect();
+    let required: Vec<&String> = properties
+        .iter()
+        .filter(|(_, decl)| is_required(decl))
+        .map(|(name, _)| name)
+        .collect();
+    let mut schema = Map::new();
+    schema.insert("type".into(), json!("object"));
+    schema.insert("properties".into(), Value::Object(props));
+    if !required.is_empty() {
+        schema.insert("required".into(), json!(required));
+    }
+    schema.insert("additionalProperties".into(), json!(false));
+    schema
+}
+
+/// Schema of one input declaration.
+fn input_decl_schema(decl: &RecipeInput) -> Value {
+    let mut schema = match decl.input_type.as_str() {
+        "int" => {
+            let mut s = Map::new();
+            s.insert("type".into(), json!("integer"));
+            bounds(&mut s, decl, "minimum", "maximum");
+            s
+        }
+        "bool" => Map::from_iter([("type".into(), json!("boolean"))]),
+        "path" => Map::from_iter([
+            ("type".into(), json!("string")),
+            ("pattern".into(), json!("^/")),
+        ]),
+        "enum" => {
+            let mut s = Map::from_iter([("type".into(), json!("string"))]);
+            if !decl.choices.is_empty() {
+                s.insert("enum".into(), json!(decl.choices));
+            }
+            s
+        }
+        "list" => {
+            let mut s = Map::from_iter([("type".into(), json!("array"))]);
+            if let Some(ref items) = decl.items {
+                s.insert("items".into(), input_decl_schema(items));
+            }
+            bounds(&mut s, decl, "minItems", "maxItems");
+            s
+        }
+        "map" => {
+            let mut s = Map::from_iter([("type".into(), json!("object"))]);
+            if let Some(ref items) = decl.items {
+                s.insert("additionalProperties".into(), input_decl_schema(items));
+            }
+            bounds(&mut s, decl, "minProperties", "maxProperties");
+            s
+        }
+        "object" => object_schema(&decl.properties),
+        _ => Map::from_iter([("type".into(), json!("string"))]),
+    };
+    if let Some(ref pattern) = decl.pattern {
+        let anchored = json!({ "pattern": format!("^(?:{pattern})$") });
+        schema.insert("allOf".into(), json!([anchored]));
+    }
+    if let Some(ref description) = decl.description {
+        schema.insert("description".into(), json!(description));
+    }
+    if let Some(default) = decl
+        .default
+        .as_ref()
+        .and_then(|d| serde_json::to_value(d).ok())
+    {
+        schema.insert("default".into(), default);
+    }
+    Value::Object(schema)
+}
+
+/// Copy `min`/`max` into the named schema keywords.
+fn bounds(schema: &mut Map<String, Value>, decl: &RecipeInput, min: &str, max: &str) {
+    if let Some(n) = decl.min {
+        schema.insert(min.into(), json!(n));
+    }
+    if let Some(n) = decl.max {
+        schema.insert(max.into(), json!(n));
+    }
+}
@@ -0,0 +1,171 @@
+//! FJ-3315: Recipe outputs — binding at expansion and resolution at plan time.
+
+use super::expansion::{expand_recipe, parse_recipe};
+use super::outputs::{output_refs, recipe_outputs};
+use crate::core::parser;
+use crate::core::resolver;
+use crate::core::types::{MachineTarget, RecipeOutputBinding};
+use std::collections::HashMap;
+use std::path::Path;
+
+const DB_RECIPE: &str = r#"
+recipe:
+  name: postgres
+  inputs:
+    port:
+      type: int
+      default: 5432
+resources:
+  conf:
+    type: file
+    path: /etc/postgresql/postgresql.conf
+    content: "port = {{inputs.port}}"
+  service:
+    type: service
+    name: postgresql
+    depends_on: [conf]
+outputs:
+  url:
+    value: "postgres://localhost:{{inputs.port}}"
+  config_path:
+    value: "{{resources.conf.path}}"
+  cluster_id:
+    capture: service.cluster_id
+    default: pending
+"#;
+
+fn expanded_outputs(yaml: &str) -> Result<indexmap::IndexMap<String, RecipeOutputBinding>, String> {
+    let recipe = parse_recipe(yaml)?;
+    let machine = MachineTarget::Single("web".to_string());
+    let inputs = HashMap::new();
+    let expanded = expand_recipe("db", &recipe, &machine, &inputs, &[])?;
+    recipe_outputs("db", &recipe, &inputs, &expanded)
+}
+
+#[test]
+fn test_outputs_bind_inputs_resources_and_captures() {
+    let outputs = expanded_outputs(DB_RECIPE).unwrap();
+    assert_eq!(
+        outputs["db.outputs.url"],
+        RecipeOutputBinding::Value("postgres://localhost:5432".to_string())
+    );
+    assert_eq!(
+        outputs["db.outputs.config_path"],
+        RecipeOutputBinding::Value("/etc/postgresql/postgresql.conf".to_string())
+    );
+    assert_eq!(
+        outputs["db.outputs.cluster_id"],
+        RecipeOutputBinding::Capture {
+            output: "db/service.cluster_id".to_string(),
+            default: Some("pending".to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_outputs_reject_bad_declarations() {
+    let both = DB_RECIPE.replace("    default: pending\n", "    value: x\n");
+    let err = expanded_outputs(&both).unwrap_err();
+    assert!(err.contains("exactly one of value or capture"), "{err}");
+
+    let unknown = DB_RECIPE.replace("capture: service.cluster_id", "capture: nope.cluster_id");
+    let err = expanded_outputs(&unknown).unwrap_err();
+    assert!(err.contains("naming a recipe resource"), "{err}");
+
+    let field = DB_RECIPE.replace("{{resources.conf.path}}", "{{resources.conf.owner}}");
+    let err = expanded_outputs(&field).unwrap_err();
+    assert!(err.contains("has no field 'owner'"), "{err}");
+}
+
+#[test]
+fn test_output_refs() {
+    assert_eq!(
+        output_refs("a {{recipes.db.outputs.url}} b {{ recipes.x/y.outputs.z }} {{params.p}}"),
+        vec!["db.outputs.url", "x/y.outputs.z"]
+    );
+}
+
+fn write_config(dir: &Path, consumer: &str) -> std::path::PathBuf {
+    std::fs::create_dir_all(dir.join("recipes")).unwrap();
+    std::fs::write(dir.join("recipes/postgres.yaml"), DB_RECIPE).unwrap();
+    let file = dir.join("forjar.yaml");
+    std::fs::write(
+        &file,
+        format!(
+            r#"version: "1.0"
+name: app
+machines:
+  web:
+    hostname: web
+    addr: 127.0.0.1
+resources:
+  db:
+    type: recipe
+    machine: web
+    recipe: postgres
+  app-env:
+    type: file
+    machine: web
+    path: /etc/app.env
+    content: "{consumer}"
+    depends_on: [db]
+"#
+        ),
+    )
+    .unwrap();
+    file
+}
+
+#[test]
+fn test_parse_rejects_unknown_recipe_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = write_config(dir.path(), "DB={{recipes.db.outputs.password}}");
+    let err = parser::parse_and_validate(&file).unwrap_err();
+    assert!(
+        err.contains(
+            "resource 'app-env' references unknown recipe output 'recipes.db.outputs.password'"
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_recipe_outputs_resolve_in_templates() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = write_config(
+        dir.path(),
+        "DB={{recipes.db.outputs.url}} ID={{recipes.db.outputs.cluster_id}}",
+    );
+    let mut config = parser::parse_and_validate(&file).unwrap();
+    let content = config.resources["app-env"].content.clone().unwrap();
+
+    let state = dir.path().join("state");
+    resolver::resolve_recipe_outputs(&mut config, &state).unwrap();
+    let render = |config: &crate::core::types::ForjarConfig| {
+        resolver::resolve_template(&content, &config.params, &config.machines).unwrap()
+    };
+    assert_eq!(render(&config), "DB=postgres://localhost:5432 ID=pending");
+
+    // Once captured, the value replaces the default.
+    let mut lock = crate::core::state::new_global_lock("app");
+    lock.outputs
+        .insert("db/service.cluster_id".to_string(), "c-42".to_string());
+    crate::core::state::save_global_lock(&state, &lock).unwrap();
+    resolver::resolve_recipe_outputs(&mut config, &state).unwrap();
+    assert_eq!(render(&config), "DB=postgres://localhost:5432 ID=c-42");
+}
+
+#[test]
+fn test_uncaptured_output_without_default_fails_to_resolve() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = write_config(dir.path(), "ID={{recipes.db.outputs.cluster_id}}");
+    let recipe = dir.path().join("recipes/postgres.yaml");
+    let body = std::fs::read_to_string(&recipe).unwrap();
+    std::fs::write(&recipe, body.replace("    default: pending\n", "")).unwrap();
+
+    let mut config = parser::parse_and_validate(&file).unwrap();
+    resolver::resolve_recipe_outputs(&mut config, &dir.path().join("state")).unwrap();
+    let content = config.resources["app-env"].content.clone().unwrap();
+    let err = resolver::resolve_template(&content, &config.params, &config.machines).unwrap_err();
+    assert!(err.contains("recipe output has no value yet"), "{err}");
+}
@@ -0,0 +1,248 @@
+//! FJ-3314: Recipe version resolution against registries.
+
+use super::registry::{self, parse_recipe_ref, parse_version, Candidate};
+use super::resolve::{solve, Requirement};
+use crate::core::parser;
+use crate::core::store::lockfile::read_lockfile;
+use semver::{Version, VersionReq};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Publish `name` `version` into the registry at `dir`, requiring `requires`.
+fn publish(dir: &Path, name: &str, version: &str, requires: &[&str]) {
+    let requires: String = requires
+        .iter()
+        .map(|r| format!("    - recipe: \"{r}\"\n"))
+        .collect();
+    let body = format!(
+        "recipe:\n  name: {name}\n  version: \"{version}\"\n  requires:\n{requires}resources:\n  conf:\n    type: file\n    path: /etc/{name}/{version}\n    content: \"{name} {version}\"\n"
+    )
+    .replace("  requires:\nresources:", "resources:");
+    let rel = format!("{name}/{version}/{name}.yaml");
+    std::fs::create_dir_all(dir.join(format!("{name}/{version}"))).unwrap();
+    std::fs::write(dir.join(&rel), &body).unwrap();
+
+    let mut index = registry::load_index(dir).unwrap();
+    index.entries.push(registry::RegistryEntry {
+        name: name.to_string(),
+        version: version.to_string(),
+        path: rel,
+        blake3: blake3::hash(body.as_bytes()).to_hex().to_string(),
+        description: String::new(),
+        tags: vec![],
+    });
+    std::fs::write(
+        dir.join("index.json"),
+        serde_json::to_string(&index).unwrap(),
+    )
+    .unwrap();
+}
+
+fn root(name: &str, req: &str) -> Requirement {
+    Requirement {
+        name: name.to_string(),
+        req: VersionReq::parse(req).unwrap(),
+        from: format!("resource '{name}'"),
+    }
+}
+
+fn versions(selected: &BTreeMap<String, Candidate>) -> Vec<String> {
+    selected
+        .values()
+        .map(|c| format!("{}@{}", c.name, c.version))
+        .collect()
+}
+
+fn config(dir: &Path, recipe: &str) -> std::path::PathBuf {
+    let file = dir.join("forjar.yaml");
+    std::fs::write(
+        &file,
+        format!(
+            "version: \"1.0\"\nname: app\nmachines:\n  web:\n    hostname: web\n    addr: 127.0.0.1\nresources:\n  proxy:\n    type: recipe\n    machine: web\n    recipe: \"{recipe}\"\npolicy:\n  recipe_registries: [registry]\n"
+        ),
+    )
+    .unwrap();
+    file
+}
+
+fn locked(dir: &Path, name: &str) -> Option<String> {
+    let lock = read_lockfile(&dir.join("forjar.inputs.lock.yaml")).unwrap();
+    lock.pins[&format!("recipe:{name}")].version.clone()
+}
+
+#[test]
+fn test_parse_recipe_ref() {
+    assert_eq!(parse_recipe_ref("nginx").unwrap(), ("nginx", None));
+    let (name, req) = parse_recipe_ref("nginx@^2.1").unwrap();
+    assert_eq!(name, "nginx");
+    assert!(req.unwrap().matches(&Version::new(2, 4, 0)));
+    assert!(parse_recipe_ref("nginx@not-a-version").is_err());
+    assert!(parse_recipe_ref("@1").is_err());
+}
+
+#[test]
+fn test_parse_version_pads_components() {
+    assert_eq!(parse_version("2").unwrap(), Version::new(2, 0, 0));
+    assert_eq!(parse_version("1.4").unwrap(), Version::new(1, 4, 0));
+    assert_eq!(parse_version("v3.1.2").unwrap(), Version::new(3, 1, 2));
+    assert!(parse_version("latest").is_err());
+}
+
+#[test]
+fn test_solve_picks_highest_compatible_versions() {
+    let dir = tempfile::tempdir().unwrap();
+    publish(dir.path(), "base", "1.0.0", &[]);
+    publish(dir.path(), "base", "1.4.0", &[]);
+    publish(dir.path(), "base", "2.0.0", &[]);
+    publish(dir.path(), "nginx", "2.1.0", &["base@^1"]);
+    publish(dir.path(), "nginx", "2.3.0", &["base@~1.0"]);
+    publish(dir.path(), "nginx", "3.0.0", &["base@^2"]);
+    let available = registry::candidates(&[dir.path().to_path_buf()]).unwrap();
+
+    let selected = solve(&[root("nginx", "^2.1")], &available, &BTreeMap::new()).unwrap();
+    assert_eq!(versions(&selected), vec!["base@1.0.0", "nginx@2.3.0"]);
+
+    // A second constraint on base forces an older nginx.
+    let roots = [root("nginx", "^2.1"), root("base", ">=1.2")];
+    let selected = solve(&roots, &available, &BTreeMap::new()).unwrap();
+    assert_eq!(versions(&selected), vec!["base@1.4.0", "nginx@2.1.0"]);
+}
+
+#[test]
+fn test_solve_prefers_locked_version() {
+    let dir = tempfile::tempdir().unwrap();
+    publish(dir.path(), "nginx", "2.1.0", &[]);
+    publish(dir.path(), "nginx", "2.3.0", &[]);
+    let available = registry::candidates(&[dir.path().to_path_buf()]).unwrap();
+    let preferred = BTreeMap::from([("nginx".to_string(), Version::new(2, 1, 0))]);
+    let selected = solve(&[root("nginx", "^2")], &available, &preferred).unwrap();
+    assert_eq!(versions(&selected), vec!["nginx@2.1.0"]);
+
+    // A lock outside the constraint is ignored.
+    let selected = solve(&[root("nginx", ">=2.2")], &available, &preferred).unwrap();
+    assert_eq!(versions(&selected), vec!["nginx@2.3.0"]);
+}
+
+#[test]
+fn test_solve_reports_conflicts() {
+    let dir = tempfile::tempdir().unwrap();
+    publish(dir.path(), "base", "1.0.0", &[]);
+    publish(dir.path(), "base", "2.0.0", &[]);
+    publish(dir.path(), "app", "1.0.0", &["base@^1"]);
+    let available = registry::candidates(&[dir.path().to_path_buf()]).unwrap();
+
+    let err = solve(
+        &[root("app", "^1"), root("base", "^2")],
+        &available,
+        &BTreeMap::new(),
+    )
+    .unwrap_err();
+    assert!(err.contains("no combination"), "{err}");
+    assert!(
+        err.contains("base: ^2 (resource 'base'), ^1 (recipe 'app' 1.0.0)"),
+        "{err}"
+    );
+
+    let err = solve(&[root("app", "^3")], &available, &BTreeMap::new()).unwrap_err();
+    assert!(err.contains("available: 1.0.0"), "{err}");
+    let err = solve(&[root("missing", "*")], &available, &BTreeMap::new()).unwrap_err();
+    assert!(err.contains("not in any registry"), "{err}");
+}
+
+#[test]
+fn test_parse_resolves_registry_recipe_and_locks_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let reg = dir.path().join("registry");
+    publish(&reg, "nginx", "2.1.0", &[]);
+    let file = config(dir.path(), "nginx@^2.1");
+
+    let config = parser::parse_and_validate(&file).unwrap();
+    assert_eq!(
+        config.resources["proxy/conf"].path.as_deref(),
+        Some("/etc/nginx/2.1.0")
+    );
+    assert_eq!(locked(dir.path(), "nginx").as_deref(), Some("2.1.0"));
+
+    // A newer release does not move the lock until `update`.
+    publish(&reg, "nginx", "2.2.0", &[]);
+    let config = parser::parse_and_validate(&file).unwrap();
+    assert_eq!(
+        config.resources["proxy/conf"].path.as_deref(),
+        Some("/etc/nginx/2.1.0")
+    );
+    let updated = parser::update_recipes(&file, &[]).unwrap();
+    assert_eq!(updated["nginx"].version, Version::new(2, 2, 0));
+    assert_eq!(locked(dir.path(), "nginx").as_deref(), Some("2.2.0"));
+}
+
+#[test]
+fn test_parse_rejects_tampered_registry_recipe() {
+    let dir = tempfile::tempdir().unwrap();
+    let reg = dir.path().join("registry");
+    publish(&reg, "nginx", "2.1.0", &[]);
+    let file = config(dir.path(), "nginx@^2");
+    std::fs::write(
+        reg.join("nginx/2.1.0/nginx.yaml"),
+        "recipe:\n  name: nginx\nresources: {}\n",
+    )
+    .unwrap();
+    let err = parser::parse_and_validate(&file).unwrap_err();
+    assert!(err.contains("does not match its registry hash"), "{err}");
+}
+
+#[test]
+fn test_parse_rejects_republished_locked_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let reg = dir.path().join("registry");
+    publish(&reg, "nginx", "2.1.0", &[]);
+    let file = config(dir.path(), "nginx@^2");
+    parser::parse_and_validate(&file).unwrap();
+
+    // Same version, new content and a matching index entry.
+    std::fs::remove_dir_all(&reg).unwrap();
+    publish(&reg, "nginx", "2.1.0", &["other"]);
+    let err = parser::parse_and_validate(&file).unwrap_err();
+    assert!(err.contains("changed in the registry"), "{err}");
+    parser::update_recipes(&file, &["nginx".to_string()]).unwrap();
+    parser::parse_and_validate(&file).unwrap();
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+#[test]
+fn test_git_registry_is_checked_out_into_cache() {
+    let repo = tempfile::tempdir().unwrap();
+    git(repo.path(), &["init", "-q", "-b", "main"]);
+    publish(&repo.path().join("recipes"), "nginx", "2.1.0", &[]);
+    git(repo.path(), &["add", "."]);
+    git(repo.path(), &["commit", "-q", "-m", "registry"]);
+
+    let cache = tempfile::tempdir().unwrap();
+    #[allow(clippy::disallowed_methods)]
+    std::env::set_var("FORJAR_CACHE_DIR", cache.path());
+    let spec = format!("git+file://{}@main//recipes", repo.path().display());
+    let dir = registry::open_registry(&spec, Path::new("."), false).unwrap();
+    assert!(dir.starts_with(cache.path().join("registries")));
+    let available = registry::candidates(&[dir]).unwrap();
+    assert_eq!(available["nginx"][0].version, Version::new(2, 1, 0));
+
+    let err =
+        registry::open_registry("git+file:///nonexistent", Path::new("."), false).unwrap_err();
+    assert!(err.contains("must name a ref"), "{err}");
+    let err = registry::open_registry(
+        "git+--upload-pack=touch /tmp/pwned@main",
+        Path::new("."),
+        false,
+    )
+    .unwrap_err();
+    assert!(err.contains("must not start with '-'"), "{err}");
+}
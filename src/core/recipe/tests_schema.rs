@@ -0,0 +1,212 @@
+//! FJ-3315: Typed recipe inputs (list, map, object, patterns, optional) and
+//! their JSON Schema export.
+
+use super::expansion::{expand_recipe, parse_recipe};
+use super::schema::input_schema;
+use super::validation::{validate_declarations, validate_inputs};
+use crate::core::types::MachineTarget;
+use std::collections::HashMap;
+
+const WEB_RECIPE: &str = r#"
+recipe:
+  name: web
+  description: "Web server"
+  inputs:
+    domain:
+      type: string
+      pattern: "[a-z0-9.-]+"
+    packages:
+      type: list
+      min: 1
+      items:
+        type: string
+    ports:
+      type: list
+      default: [80]
+      items:
+        type: int
+        min: 1
+        max: 65535
+    labels:
+      type: map
+      required: false
+      items:
+        type: string
+    tls:
+      type: object
+      properties:
+        cert:
+          type: path
+        protocols:
+          type: list
+          default: [TLSv1.3]
+          items:
+            type: enum
+            choices: [TLSv1.2, TLSv1.3]
+        hsts:
+          type: bool
+          required: false
+resources:
+  pkgs:
+    type: package
+    provider: apt
+    packages: ["{{inputs.packages}}", curl]
+  site:
+    type: file
+    path: "/etc/nginx/sites/{{inputs.domain}}"
+    content: "cert={{inputs.tls}} labels={{inputs.labels}}"
+"#;
+
+fn inputs(yaml: &str) -> HashMap<String, serde_yaml_ng::Value> {
+    serde_yaml_ng::from_str(yaml).unwrap()
+}
+
+const VALID: &str = r#"
+domain: example.com
+packages: [nginx, certbot]
+tls:
+  cert: /etc/ssl/site.pem
+"#;
+
+#[test]
+fn test_typed_inputs_validate_and_fill_defaults() {
+    let recipe = parse_recipe(WEB_RECIPE).unwrap();
+    validate_declarations(&recipe).unwrap();
+    let resolved = validate_inputs(&recipe.recipe, &inputs(VALID)).unwrap();
+    assert_eq!(resolved["packages"], r#"["nginx","certbot"]"#);
+    assert_eq!(resolved["ports"], "[80]");
+    assert_eq!(resolved["labels"], "{}");
+    assert_eq!(
+        resolved["tls"],
+        r#"{"cert":"/etc/ssl/site.pem","protocols":["TLSv1.3"]}"#
+    );
+}
+
+#[test]
+fn test_typed_inputs_report_nested_errors() {
+    let recipe = parse_recipe(WEB_RECIPE).unwrap();
+    let cases = [
+        ("domain: Example.COM", "input 'domain' must match pattern"),
+        (
+            "packages: []",
+            "input 'packages' must have at least 1 entries",
+        ),
+        ("packages: nginx", "input 'packages' must be a list"),
+        ("ports: [80, 70000]", "input 'ports[1]' must be <= 65535"),
+        ("ports: [http]", "input 'ports[0]' must be an integer"),
+        (
+            "tls: {cert: relative}",
+            "input 'tls.cert' must be an absolute path",
+        ),
+        ("tls: {}", "input 'tls.cert' is required"),
+        (
+            "tls: {cert: /c, protocols: [SSLv3]}",
+            "input 'tls.protocols[0]' must be one of",
+        ),
+        (
+            "tls: {cert: /c, port: 1}",
+            "input 'tls' has unknown field \"port\"",
+        ),
+    ];
+    for (patch, expected) in cases {
+        let mut provided = inputs(VALID);
+        provided.extend(inputs(patch));
+        let err = validate_inputs(&recipe.recipe, &provided).unwrap_err();
+        assert!(err.contains(expected), "{patch}: {err}");
+    }
+}
+
+#[test]
+fn test_declarations_are_checked() {
+    let cases = [
+        (
+            "      type: list\n      min: 1",
+            "      type: lst\n      min: 1",
+            "unknown input type 'lst'",
+        ),
+        (
+            "pattern: \"[a-z0-9.-]+\"",
+            "pattern: \"[a-z\"",
+            "invalid pattern",
+        ),
+        (
+            "      default: [80]",
+            "      default: [http]",
+            "default does not conform",
+        ),
+        (
+            "        hsts:\n          type: bool",
+            "        hsts:\n          type: bool\n          items: {type: string}",
+            "items only apply",
+        ),
+    ];
+    for (from, to, expected) in cases {
+        assert!(WEB_RECIPE.contains(from), "{from}");
+        let recipe = parse_recipe(&WEB_RECIPE.replacen(from, to, 1)).unwrap();
+        let err = validate_declarations(&recipe).unwrap_err();
+        assert!(err.contains(expected), "{to}: {err}");
+    }
+}
+
+#[test]
+fn test_list_inputs_splice_into_list_fields() {
+    let recipe = parse_recipe(WEB_RECIPE).unwrap();
+    let machine = MachineTarget::Single("web".to_string());
+    let expanded = expand_recipe("web", &recipe, &machine, &inputs(VALID), &[]).unwrap();
+    assert_eq!(
+        expanded["web/pkgs"].packages,
+        vec!["nginx", "certbot", "curl"]
+    );
+    assert_eq!(
+        expanded["web/site"].path.as_deref(),
+        Some("/etc/nginx/sites/example.com")
+    );
+}
+
+#[test]
+fn test_dotted_paths_render_object_fields() {
+    let mut recipe = parse_recipe(WEB_RECIPE).unwrap();
+    recipe.resources["site"].content = Some(
+        "ssl_certificate {{inputs.tls.cert}}; ssl_protocols {{inputs.tls.protocols.0}};"
+            .to_string(),
+    );
+    let machine = MachineTarget::Single("web".to_string());
+    let expanded = expand_recipe("web", &recipe, &machine, &inputs(VALID), &[]).unwrap();
+    assert_eq!(
+        expanded["web/site"].content.as_deref(),
+        Some("ssl_certificate /etc/ssl/site.pem; ssl_protocols TLSv1.3;")
+    );
+
+    recipe.resources["site"].content = Some("{{inputs.tls.key}}".to_string());
+    let err = expand_recipe("web", &recipe, &machine, &inputs(VALID), &[]).unwrap_err();
+    assert!(err.contains("unknown input: tls.key"), "{err}");
+}
+
+#[test]
+fn test_input_schema_export() {
+    let recipe = parse_recipe(WEB_RECIPE).unwrap();
+    let schema = input_schema(&recipe);
+    assert_eq!(schema["title"], "web recipe inputs");
+    assert_eq!(
+        schema["required"],
+        serde_json::json!(["domain", "packages", "tls"])
+    );
+    assert!(schema.get("additionalProperties").is_none());
+
+    let props = &schema["properties"];
+    assert_eq!(props["domain"]["allOf"][0]["pattern"], "^(?:[a-z0-9.-]+)$");
+    assert_eq!(props["packages"]["type"], "array");
+    assert_eq!(props["packages"]["minItems"], 1);
+    assert_eq!(props["ports"]["items"]["maximum"], 65535);
+    assert_eq!(props["ports"]["default"], serde_json::json!([80]));
+    assert_eq!(props["labels"]["additionalProperties"]["type"], "string");
+
+    let tls = &props["tls"];
+    assert_eq!(tls["additionalProperties"], false);
+    assert_eq!(tls["required"], serde_json::json!(["cert"]));
+    assert_eq!(tls["properties"]["cert"]["pattern"], "^/");
+    assert_eq!(
+        tls["properties"]["protocols"]["items"]["enum"],
+        serde_json::json!(["TLSv1.2", "TLSv1.3"])
+    );
+}
@@ -402,6 +402,10 @@ proptest! {
             min: Some(min),
             max: Some(max),
             choices: vec![],
+            pattern: None,
+            required: None,
+            items: None,
+            properties: indexmap::IndexMap::new(),
         };
         let value = serde_yaml_ng::Value::Number(serde_yaml_ng::Number::from(n));
         let result = validate_int("test", &value, &decl);
@@ -425,6 +429,10 @@ proptest! {
             min: None,
             max: None,
             choices: vec![],
+            pattern: None,
+            required: None,
+            items: None,
+            properties: indexmap::IndexMap::new(),
         };
         let value = serde_yaml_ng::Value::String(s);
         let result = validate_input_type("test", "path", &value, &decl);
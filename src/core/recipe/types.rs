@@ -33,6 +33,9 @@ pub struct RecipeFile {
     pub recipe: RecipeMetadata,
     /// Resources declared by this recipe.
     pub resources: IndexMap<String, Resource>,
+    /// FJ-3315: Values exported to consumers as `{{recipes.<id>.outputs.<name>}}`.
+    #[serde(default)]
+    pub outputs: IndexMap<String, RecipeOutput>,
 }
 
 /// Recipe metadata and input declarations.
@@ -66,15 +69,45 @@ pub struct RecipeInput {
     /// Default value if not provided.
     #[serde(default)]
     pub default: Option<serde_yaml_ng::Value>,
-    /// Minimum value (for numeric inputs).
+    /// Minimum value (for numeric inputs), or minimum length of list/map inputs.
     #[serde(default)]
     pub min: Option<i64>,
-    /// Maximum value (for numeric inputs).
+    /// Maximum value (for numeric inputs), or maximum length of list/map inputs.
     #[serde(default)]
     pub max: Option<i64>,
     /// Allowed values (for enum inputs).
     #[serde(default)]
     pub choices: Vec<String>,
+    /// FJ-3315: Regex the whole value must match (string, path, enum inputs).
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// FJ-3315: `false` lets the input be omitted without a default; it then
+    /// resolves to an empty value. Inputs are required by default.
+    #[serde(default)]
+    pub required: Option<bool>,
+    /// FJ-3315: Schema of each element of a list input or value of a map input.
+    #[serde(default)]
+    pub items: Option<Box<RecipeInput>>,
+    /// FJ-3315: Fields of an object input.
+    #[serde(default)]
+    pub properties: IndexMap<String, RecipeInput>,
+}
+
+/// FJ-3315: A recipe output. Set exactly one of `value` or `capture`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeOutput {
+    /// Template over `{{inputs.X}}` and `{{resources.<name>.<field>}}`.
+    #[serde(default)]
+    pub value: Option<String>,
+    /// Value captured from an inner resource's apply script: `<name>.<key>`.
+    #[serde(default)]
+    pub capture: Option<String>,
+    /// Value of a capture before the resource has been applied.
+    #[serde(default)]
+    pub default: Option<String>,
+    /// Output description.
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 /// A recipe dependency.
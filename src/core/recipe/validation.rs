@@ -3,7 +3,8 @@
 use provable_contracts_macros::contract;
 use std::collections::HashMap;
 
-use super::types::RecipeMetadata;
+use super::types::{RecipeFile, RecipeInput, RecipeMetadata};
+use super::validation_types::empty_value;
 pub(crate) use super::validation_types::validate_input_type;
 #[cfg(test)]
 pub(crate) use super::validation_types::validate_int;
@@ -36,6 +37,9 @@ pub fn validate_inputs(
             v.clone()
         } else if let Some(ref default) = decl.default {
             default.clone()
+        } else if decl.required == Some(false) {
+            resolved.insert(name.clone(), empty_value(&decl.input_type));
+            continue;
         } else {
             return Err(format!(
                 "recipe '{}' requires input '{}' (type: {})",
@@ -49,3 +53,67 @@ pub fn validate_inputs(
 
     Ok(resolved)
 }
+
+/// FJ-3315: Check a recipe's own declarations: input schemas are well formed
+/// and their defaults conform, and each output names exactly one source.
+pub fn validate_declarations(recipe_file: &RecipeFile) -> Result<(), String> {
+    let recipe = &recipe_file.recipe;
+    for (name, decl) in &recipe.inputs {
+        check_decl(name, decl).map_err(|e| format!("recipe '{}': {e}", recipe.name))?;
+    }
+    for (name, output) in &recipe_file.outputs {
+        let source = match (&output.value, &output.capture) {
+            (Some(_), None) => continue,
+            (None, Some(capture)) => capture.split_once('.').map(|(res, _)| res),
+            _ => {
+                return Err(format!(
+                    "recipe '{}' output '{name}': set exactly one of value or capture",
+                    recipe.name
+                ))
+            }
+        };
+        if !source.is_some_and(|res| recipe_file.resources.contains_key(res)) {
+            return Err(format!(
+                "recipe '{}' output '{name}': capture must be <resource>.<key> naming a recipe resource",
+                recipe.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Check one input declaration and, recursively, its item and field schemas.
+fn check_decl(name: &str, decl: &RecipeInput) -> Result<(), String> {
+    let type_name = decl.input_type.as_str();
+    if !matches!(
+        type_name,
+        "string" | "int" | "bool" | "path" | "enum" | "list" | "map" | "object"
+    ) {
+        return Err(format!("unknown input type '{type_name}' for '{name}'"));
+    }
+    if decl.items.is_some() && !matches!(type_name, "list" | "map") {
+        return Err(format!(
+            "input '{name}': items only apply to list and map inputs"
+        ));
+    }
+    if !decl.properties.is_empty() && type_name != "object" {
+        return Err(format!(
+            "input '{name}': properties only apply to object inputs"
+        ));
+    }
+    if let Some(ref pattern) = decl.pattern {
+        regex::Regex::new(pattern)
+            .map_err(|e| format!("input '{name}': invalid pattern '{pattern}': {e}"))?;
+    }
+    if let Some(ref items) = decl.items {
+        check_decl(&format!("{name}[]"), items)?;
+    }
+    for (field, schema) in &decl.properties {
+        check_decl(&format!("{name}.{field}"), schema)?;
+    }
+    if let Some(ref default) = decl.default {
+        validate_input_type(name, type_name, default, decl)
+            .map_err(|e| format!("default does not conform: {e}"))?;
+    }
+    Ok(())
+}
@@ -1,4 +1,5 @@
-//! Per-type validators for recipe inputs (string, int, bool, path, enum).
+//! Per-type validators for recipe inputs (string, int, bool, path, enum)
+//! and, FJ-3315, nested list, map and object schemas.
 
 use super::types::RecipeInput;
 
@@ -82,12 +83,150 @@ pub(crate) fn validate_input_type(
 ) -> Result<String, String> {
     // Contract: recipe-determinism-v1.yaml precondition (pv codegen)
     contract_pre_validate_input_type!(name);
-    match type_name {
+    let rendered = match type_name {
         "string" => validate_string(value),
         "int" => validate_int(name, value, decl),
         "bool" => validate_bool(name, value),
         "path" => validate_path(name, value),
         "enum" => validate_enum(name, value, decl),
+        "list" | "map" | "object" => {
+            let json = validate_compound(name, type_name, value, decl)?;
+            return serde_json::to_string(&json).map_err(|e| format!("input '{name}': {e}"));
+        }
         _ => Err(format!("unknown input type '{type_name}' for '{name}'")),
+    }?;
+    check_pattern(name, &rendered, decl)?;
+    Ok(rendered)
+}
+
+/// FJ-3315: The value an omitted optional input resolves to.
+pub(crate) fn empty_value(type_name: &str) -> String {
+    match type_name {
+        "list" => "[]".to_string(),
+        "map" | "object" => "{}".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// FJ-3315: Check `rendered` against the declaration's anchored `pattern`.
+fn check_pattern(name: &str, rendered: &str, decl: &RecipeInput) -> Result<(), String> {
+    let Some(ref pattern) = decl.pattern else {
+        return Ok(());
+    };
+    let re = regex::Regex::new(&format!("^(?:{pattern})$"))
+        .map_err(|e| format!("input '{name}': invalid pattern '{pattern}': {e}"))?;
+    if !re.is_match(rendered) {
+        return Err(format!("input '{name}' must match pattern '{pattern}'"));
+    }
+    Ok(())
+}
+
+/// FJ-3315: Validate a value against a nested declaration, returning it as
+/// JSON with object defaults filled in.
+fn validate_value(
+    name: &str,
+    value: &serde_yaml_ng::Value,
+    decl: &RecipeInput,
+) -> Result<serde_json::Value, String> {
+    let type_name = decl.input_type.as_str();
+    if matches!(type_name, "list" | "map" | "object") {
+        return validate_compound(name, type_name, value, decl);
     }
+    let rendered = validate_input_type(name, type_name, value, decl)?;
+    Ok(match type_name {
+        "int" => serde_json::Value::from(rendered.parse::<i64>().unwrap_or_default()),
+        "bool" => serde_json::Value::Bool(rendered == "true"),
+        _ => serde_json::Value::String(rendered),
+    })
+}
+
+/// FJ-3315: Validate a list, map or object input.
+fn validate_compound(
+    name: &str,
+    type_name: &str,
+    value: &serde_yaml_ng::Value,
+    decl: &RecipeInput,
+) -> Result<serde_json::Value, String> {
+    match (type_name, value) {
+        ("list", serde_yaml_ng::Value::Sequence(items)) => {
+            check_len(name, items.len(), decl)?;
+            let values = items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| match decl.items {
+                    Some(ref schema) => validate_value(&format!("{name}[{i}]"), item, schema),
+                    None => yaml_to_json(name, item),
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(serde_json::Value::Array(values))
+        }
+        ("map", serde_yaml_ng::Value::Mapping(entries)) => {
+            check_len(name, entries.len(), decl)?;
+            let mut map = serde_json::Map::new();
+            for (key, item) in entries {
+                let key = key
+                    .as_str()
+                    .ok_or_else(|| format!("input '{name}' keys must be strings"))?;
+                let field = format!("{name}.{key}");
+                let item = match decl.items {
+                    Some(ref schema) => validate_value(&field, item, schema)?,
+                    None => yaml_to_json(&field, item)?,
+                };
+                map.insert(key.to_string(), item);
+            }
+            Ok(serde_json::Value::Object(map))
+        }
+        ("object", serde_yaml_ng::Value::Mapping(entries)) => validate_object(name, entries, decl),
+        ("list", _) => Err(format!("input '{name}' must be a list")),
+        _ => Err(format!("input '{name}' must be a mapping")),
+    }
+}
+
+/// FJ-3315: Validate an object against its declared properties.
+fn validate_object(
+    name: &str,
+    entries: &serde_yaml_ng::Mapping,
+    decl: &RecipeInput,
+) -> Result<serde_json::Value, String> {
+    if let Some(key) = entries
+        .keys()
+        .find(|k| !k.as_str().is_some_and(|k| decl.properties.contains_key(k)))
+    {
+        return Err(format!(
+            "input '{name}' has unknown field {}",
+            yaml_to_json(name, key)?
+        ));
+    }
+    let mut object = serde_json::Map::new();
+    for (field, schema) in &decl.properties {
+        let path = format!("{name}.{field}");
+        let value = match entries.get(field.as_str()).or(schema.default.as_ref()) {
+            Some(value) => validate_value(&path, value, schema)?,
+            None if schema.required == Some(false) => continue,
+            None => {
+                return Err(format!(
+                    "input '{path}' is required (type: {})",
+                    schema.input_type
+                ))
+            }
+        };
+        object.insert(field.clone(), value);
+    }
+    Ok(serde_json::Value::Object(object))
+}
+
+/// FJ-3315: Check a list or map length against `min`/`max`.
+fn check_len(name: &str, len: usize, decl: &RecipeInput) -> Result<(), String> {
+    let len = len as i64;
+    if let Some(min) = decl.min.filter(|min| len < *min) {
+        return Err(format!("input '{name}' must have at least {min} entries"));
+    }
+    if let Some(max) = decl.max.filter(|max| len > *max) {
+        return Err(format!("input '{name}' must have at most {max} entries"));
+    }
+    Ok(())
+}
+
+fn yaml_to_json(name: &str, value: &serde_yaml_ng::Value) -> Result<serde_json::Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("input '{name}': {e}"))
 }
@@ -0,0 +1,320 @@
+//! Control-machine side of the `binary_release` resource.
+//!
+//! Lists the releases of a project on GitHub, GitLab, Gitea/Forgejo or a
+//! static index, picks the newest version satisfying the `version`
+//! constraint (Cargo-style: `^1.4`, `~1.4.2`, `>=1.2, <2`, `=1.4.2`) or the
+//! release pinned by `tag` (which need not be a version: `nightly`), selects
+//! the asset matching `asset_pattern`, and reads its SHA-256 from the
+//! release's checksum file. The target then downloads exactly that asset and
+//! refuses it unless the checksum (and, when configured, the detached
+//! signature) verifies.
+//!
+//! A static index is a plain HTTP directory listing: `<url>/` links one
+//! directory per version (`v1.4.2/`), and each version directory links its
+//! assets and checksum file.
+//!
+//! API tokens are read from `GITHUB_TOKEN`, `GITLAB_TOKEN` and `GITEA_TOKEN`.
+
+pub use super::release_source_b::*;
+use crate::core::fetch::http_get;
+use crate::core::types::Resource;
+use semver::{Version, VersionReq};
+
+/// Checksum file names tried when `checksum_file` is not set.
+const CHECKSUM_FILES: &[&str] = &[
+    "SHA256SUMS",
+    "SHA256SUMS.txt",
+    "sha256sums.txt",
+    "checksums.txt",
+];
+
+/// Suffixes of release files that are never the binary asset.
+const AUX_SUFFIXES: &[&str] = &[
+    ".sha256",
+    ".sha256sum",
+    ".minisig",
+    ".sig",
+    ".pem",
+    ".asc",
+    ".sbom",
+    ".json",
+];
+
+/// Where releases are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseProvider {
+    Github,
+    Gitlab,
+    Gitea,
+    Static,
+}
+
+impl ReleaseProvider {
+    /// Parse a `provider` value; unset means GitHub.
+    pub fn parse(provider: Option<&str>) -> Result<Self, String> {
+        match provider.unwrap_or("github") {
+            "github" => Ok(Self::Github),
+            "gitlab" => Ok(Self::Gitlab),
+            "gitea" | "forgejo" => Ok(Self::Gitea),
+            "static" => Ok(Self::Static),
+            other => Err(format!(
+                "unknown provider '{other}' (expected: github, gitlab, gitea, static)"
+            )),
+        }
+    }
+}
+
+/// Detached signature formats verified on the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureKind {
+    Minisign,
+    Cosign,
+}
+
+impl SignatureKind {
+    /// Parse a `signature` value.
+    pub fn parse(kind: &str) -> Result<Self, String> {
+        match kind {
+            "minisign" => Ok(Self::Minisign),
+            "cosign" => Ok(Self::Cosign),
+            other => Err(format!(
+                "unknown signature '{other}' (expected: minisign, cosign)"
+            )),
+        }
+    }
+
+    /// Suffix of the signature file published next to the asset.
+    pub fn suffix(self) -> &'static str {
+        match self {
+            Self::Minisign => ".minisig",
+            Self::Cosign => ".sig",
+        }
+    }
+}
+
+/// A downloadable file of a release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub url: String,
+    /// SHA-256 advertised by the provider itself (GitHub `digest`).
+    pub sha256: Option<String>,
+}
+
+/// One published release.
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub tag: String,
+    pub version: Version,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// The release and asset an apply installs.
+#[derive(Debug, Clone)]
+pub struct ResolvedRelease {
+    pub tag: String,
+    pub version: Version,
+    pub asset: ReleaseAsset,
+    /// Expected SHA-256 of the asset.
+    pub sha256: String,
+    /// Detached signature to check, with its URL.
+    pub signature: Option<(SignatureKind, String)>,
+}
+
+/// Parse a `version` constraint. Unset, `latest` and `*` accept any stable
+/// release; a bare version (`1.4`) means `^1.4`, as in Cargo.
+pub fn parse_constraint(version: Option<&str>) -> Result<VersionReq, String> {
+    match version.map(str::trim) {
+        None | Some("") | Some("latest") | Some("*") => Ok(VersionReq::STAR),
+        Some(req) => VersionReq::parse(req.trim_start_matches('v'))
+            .map_err(|e| format!("invalid version constraint '{req}': {e}")),
+    }
+}
+
+/// Version of a release tag: `v1.4.2`, `1.4.2`, `release-1.4.2`, or a
+/// two-part `v1.4` read as `1.4.0`. `None` for tags such as `nightly`.
+pub fn tag_version(tag: &str) -> Option<Version> {
+    let start = tag.find(|c: char| c.is_ascii_digit())?;
+    let prefix = &tag[..start];
+    if !prefix.is_empty() && !prefix.ends_with(['v', 'V', '-', '_']) {
+        return None;
+    }
+    let raw = &tag[start..];
+    Version::parse(raw).ok().or_else(|| {
+        let dots = raw.bytes().filter(|&b| b == b'.').count();
+        (dots == 1 && raw.bytes().all(|b| b.is_ascii_digit() || b == b'.'))
+            .then(|| Version::parse(&format!("{raw}.0")).ok())
+            .flatten()
+    })
+}
+
+/// Newest release satisfying `req`. Prereleases only match a constraint
+/// that names one, as in Cargo.
+pub fn select_release<'a>(releases: &'a [Release], req: &VersionReq) -> Option<&'a Release> {
+    releases
+        .iter()
+        .filter(|r| req.matches(&r.version))
+        .max_by(|a, b| a.version.cmp(&b.version))
+}
+
+fn is_aux_file(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    CHECKSUM_FILES.iter().any(|f| f.eq_ignore_ascii_case(name))
+        || lower.ends_with("checksums.txt")
+        || AUX_SUFFIXES.iter().any(|s| lower.ends_with(s))
+}
+
+/// The single non-auxiliary asset matching `pattern` (a glob).
+pub fn select_asset<'a>(release: &'a Release, pattern: &str) -> Result<&'a ReleaseAsset, String> {
+    let glob = glob::Pattern::new(pattern)
+        .map_err(|e| format!("invalid asset_pattern '{pattern}': {e}"))?;
+    let matches: Vec<&ReleaseAsset> = release
+        .assets
+        .iter()
+        .filter(|a| !is_aux_file(&a.name) && glob.matches(&a.name))
+        .collect();
+    match matches.as_slice() {
+        [asset] => Ok(asset),
+        [] => Err(format!(
+            "no asset matching '{pattern}' in release {}",
+            release.tag
+        )),
+        many => Err(format!(
+            "asset_pattern '{pattern}' matches {} assets in release {}: {}",
+            many.len(),
+            release.tag,
+            many.iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Look up `asset` in a checksum file: `sha256sum` output (`<hex>  name`,
+/// `<hex> *name`), BSD style (`SHA256 (name) = <hex>`), or a single-hash
+/// `<asset>.sha256` file (`single` is true).
+pub fn checksum_for(text: &str, asset: &str, single: bool) -> Option<String> {
+    let is_hex = |s: &str| s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit());
+    for line in text.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("SHA256 (") {
+            if let Some((name, hex)) = rest.split_once(") = ") {
+                if name == asset && is_hex(hex.trim()) {
+                    return Some(hex.trim().to_ascii_lowercase());
+                }
+            }
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let (Some(hex), name) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if !is_hex(hex) {
+            continue;
+        }
+        let name = name.map(|n| n.trim_start_matches('*').trim_start_matches("./"));
+        if name == Some(asset) || (single && name.is_none_or(|n| n.ends_with(asset))) {
+            return Some(hex.to_ascii_lowercase());
+        }
+    }
+    None
+}
+
+/// Find the asset's checksum: `checksum_file` (an asset name or URL), a
+/// conventional checksum file, `<asset>.sha256`, or the provider's digest.
+fn asset_checksum(
+    resource: &Resource,
+    release: &Release,
+    asset: &ReleaseAsset,
+) -> Result<String, String> {
+    let by_name = |name: &str| release.assets.iter().find(|a| a.name == name);
+    let single_name = format!("{}.sha256", asset.name);
+    let (url, single) = match resource.checksum_file.as_deref() {
+        Some(file) if file.contains("://") => (Some(file.to_string()), false),
+        Some(file) => {
+            let found = by_name(file).ok_or_else(|| {
+                format!(
+                    "checksum file '{file}' not found in release {}",
+                    release.tag
+                )
+            })?;
+            (Some(found.url.clone()), file == single_name)
+        }
+        None => {
+            let conventional = release.assets.iter().find(|a| {
+                CHECKSUM_FILES.contains(&a.name.as_str()) || a.name.ends_with("checksums.txt")
+            });
+            match (conventional, by_name(&single_name)) {
+                (Some(sums), _) => (Some(sums.url.clone()), false),
+                (None, Some(single)) => (Some(single.url.clone()), true),
+                (None, None) => (None, false),
+            }
+        }
+    };
+    match (url, &asset.sha256) {
+        (Some(url), _) => {
+            let text = String::from_utf8_lossy(&http_get(&url, &[])?).to_string();
+            checksum_for(&text, &asset.name, single)
+                .ok_or_else(|| format!("{url} has no SHA-256 for {}", asset.name))
+        }
+        (None, Some(digest)) => Ok(digest.to_ascii_lowercase()),
+        (None, None) => Err(format!(
+            "release {} publishes no checksum file for {}; set checksum_file",
+            release.tag, asset.name
+        )),
+    }
+}
+
+/// Release pinned by `tag`; unset or `latest` leaves the pick to `version`.
+pub fn pinned_tag(resource: &Resource) -> Option<&str> {
+    resource
+        .tag
+        .as_deref()
+        .filter(|tag| !tag.is_empty() && *tag != "latest")
+}
+
+/// Resolve a `binary_release` (or `github_release`) resource to the release
+/// and asset to install.
+pub fn resolve(resource: &Resource) -> Result<ResolvedRelease, String> {
+    let provider = ReleaseProvider::parse(resource.provider.as_deref())?;
+    let release = match pinned_tag(resource) {
+        Some(tag) => tagged_release(provider, resource, tag)?,
+        None => {
+            let req = parse_constraint(resource.version.as_deref())?;
+            let releases = list_releases(provider, resource, &req)?;
+            select_release(&releases, &req).cloned().ok_or_else(|| {
+                format!(
+                    "no release of {} satisfies version '{req}'",
+                    resource
+                        .repo
+                        .as_deref()
+                        .or(resource.url.as_deref())
+                        .unwrap_or("unknown")
+                )
+            })?
+        }
+    };
+    let release = &release;
+    let asset = select_asset(release, resource.asset_pattern.as_deref().unwrap_or("*"))?;
+    let sha256 = asset_checksum(resource, release, asset)?;
+    let signature = match resource.signature.as_deref() {
+        Some(kind) => {
+            let kind = SignatureKind::parse(kind)?;
+            let name = format!("{}{}", asset.name, kind.suffix());
+            let sig = release
+                .assets
+                .iter()
+                .find(|a| a.name == name)
+                .ok_or_else(|| format!("release {} has no signature {name}", release.tag))?;
+            Some((kind, sig.url.clone()))
+        }
+        None => None,
+    };
+    Ok(ResolvedRelease {
+        tag: release.tag.clone(),
+        version: release.version.clone(),
+        asset: asset.clone(),
+        sha256,
+        signature,
+    })
+}
@@ -0,0 +1,263 @@
+//! Release listings of the `binary_release` providers: the GitHub, Gitea
+//! and GitLab release APIs, and static directory indexes.
+
+use super::release_source::{tag_version, Release, ReleaseAsset, ReleaseProvider};
+use crate::core::fetch::{env_header, http_get};
+use crate::core::types::Resource;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+/// Token header for a provider's API.
+fn auth_headers(provider: ReleaseProvider) -> Vec<String> {
+    match provider {
+        ReleaseProvider::Github => env_header("GITHUB_TOKEN", "Authorization: Bearer"),
+        ReleaseProvider::Gitlab => env_header("GITLAB_TOKEN", "PRIVATE-TOKEN:"),
+        ReleaseProvider::Gitea => env_header("GITEA_TOKEN", "Authorization: token"),
+        ReleaseProvider::Static => Vec::new(),
+    }
+}
+
+fn get_json<T: for<'de> Deserialize<'de>>(
+    url: &str,
+    provider: ReleaseProvider,
+) -> Result<T, String> {
+    let body = http_get(url, &auth_headers(provider))?;
+    serde_json::from_slice(&body).map_err(|e| format!("{url}: invalid release listing: {e}"))
+}
+
+/// GitHub and Gitea share this release shape.
+#[derive(Deserialize)]
+struct ForgeRelease {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    assets: Vec<ForgeAsset>,
+}
+
+#[derive(Deserialize)]
+struct ForgeAsset {
+    name: String,
+    browser_download_url: String,
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitlabRelease {
+    tag_name: String,
+    #[serde(default)]
+    upcoming_release: bool,
+    #[serde(default)]
+    assets: GitlabAssets,
+}
+
+#[derive(Deserialize, Default)]
+struct GitlabAssets {
+    #[serde(default)]
+    links: Vec<GitlabLink>,
+}
+
+#[derive(Deserialize)]
+struct GitlabLink {
+    name: String,
+    url: String,
+    #[serde(default)]
+    direct_asset_url: Option<String>,
+}
+
+impl ForgeRelease {
+    fn into_release(self, version: Version) -> Release {
+        Release {
+            version,
+            tag: self.tag_name,
+            assets: self
+                .assets
+                .into_iter()
+                .map(|a| ReleaseAsset {
+                    name: a.name,
+                    url: a.browser_download_url,
+                    sha256: a
+                        .digest
+                        .and_then(|d| d.strip_prefix("sha256:").map(str::to_string)),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl GitlabRelease {
+    fn into_release(self, version: Version) -> Release {
+        Release {
+            version,
+            tag: self.tag_name,
+            assets: self
+                .assets
+                .links
+                .into_iter()
+                .map(|l| ReleaseAsset {
+                    name: l.name,
+                    url: l.direct_asset_url.unwrap_or(l.url),
+                    sha256: None,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn forge_releases(list: Vec<ForgeRelease>) -> Vec<Release> {
+    list.into_iter()
+        .filter(|r| !r.draft)
+        .filter_map(|r| {
+            let version = tag_version(&r.tag_name)?;
+            Some(r.into_release(version))
+        })
+        .collect()
+}
+
+fn gitlab_releases(list: Vec<GitlabRelease>) -> Vec<Release> {
+    list.into_iter()
+        .filter(|r| !r.upcoming_release)
+        .filter_map(|r| {
+            let version = tag_version(&r.tag_name)?;
+            Some(r.into_release(version))
+        })
+        .collect()
+}
+
+/// Link targets of an HTML directory listing, without query links and
+/// parent references.
+pub fn index_links(html: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    for chunk in html.split("href=").skip(1) {
+        let Some(quote) = chunk.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        let Some(target) = chunk[1..].split(quote).next() else {
+            continue;
+        };
+        if target.is_empty()
+            || target.starts_with(['?', '#'])
+            || target.starts_with("..")
+            || target.contains("://")
+        {
+            continue;
+        }
+        let name = target
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or("");
+        if !name.is_empty() {
+            let dir = if target.ends_with('/') { "/" } else { "" };
+            links.push(format!("{name}{dir}"));
+        }
+    }
+    links
+}
+
+/// The release in the index directory `tag`.
+fn static_release(index: &str, tag: String, version: Version) -> Result<Release, String> {
+    let dir = format!("{index}/{tag}");
+    let listing = String::from_utf8_lossy(&http_get(&format!("{dir}/"), &[])?).to_string();
+    let assets = index_links(&listing)
+        .into_iter()
+        .filter(|l| !l.ends_with('/'))
+        .map(|name| ReleaseAsset {
+            url: format!("{dir}/{name}"),
+            name,
+            sha256: None,
+        })
+        .collect();
+    Ok(Release {
+        tag,
+        version,
+        assets,
+    })
+}
+
+fn static_releases(index: &str, req: &VersionReq) -> Result<Vec<Release>, String> {
+    let listing = String::from_utf8_lossy(&http_get(&format!("{index}/"), &[])?).to_string();
+    let mut versions: Vec<(String, Version)> = index_links(&listing)
+        .into_iter()
+        .filter_map(|l| {
+            let tag = l.strip_suffix('/')?.to_string();
+            let version = tag_version(&tag)?;
+            Some((tag, version))
+        })
+        .collect();
+    versions.sort_by(|a, b| b.1.cmp(&a.1));
+    // Only the chosen version's directory needs listing.
+    match versions.into_iter().find(|(_, v)| req.matches(v)) {
+        Some((tag, version)) => Ok(vec![static_release(index, tag, version)?]),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Releases endpoint of a forge API, or the static index URL.
+fn releases_url(provider: ReleaseProvider, resource: &Resource) -> Result<String, String> {
+    let repo = resource.repo.as_deref().unwrap_or("");
+    let base = resource.url.as_deref().map(|u| u.trim_end_matches('/'));
+    Ok(match provider {
+        ReleaseProvider::Github => {
+            let api = base.unwrap_or("https://api.github.com");
+            format!("{api}/repos/{repo}/releases")
+        }
+        ReleaseProvider::Gitea => {
+            let base = base.ok_or("provider gitea needs url")?;
+            format!("{base}/api/v1/repos/{repo}/releases")
+        }
+        ReleaseProvider::Gitlab => {
+            let base = base.unwrap_or("https://gitlab.com");
+            let project = repo.replace('/', "%2F");
+            format!("{base}/api/v4/projects/{project}/releases")
+        }
+        ReleaseProvider::Static => base.ok_or("provider static needs url")?.to_string(),
+    })
+}
+
+/// List releases (those whose tag parses as a version).
+pub fn list_releases(
+    provider: ReleaseProvider,
+    resource: &Resource,
+    req: &VersionReq,
+) -> Result<Vec<Release>, String> {
+    let url = releases_url(provider, resource)?;
+    match provider {
+        ReleaseProvider::Github => Ok(forge_releases(get_json(
+            &format!("{url}?per_page=100"),
+            provider,
+        )?)),
+        ReleaseProvider::Gitea => Ok(forge_releases(get_json(
+            &format!("{url}?limit=50"),
+            provider,
+        )?)),
+        ReleaseProvider::Gitlab => Ok(gitlab_releases(get_json(
+            &format!("{url}?per_page=100"),
+            provider,
+        )?)),
+        ReleaseProvider::Static => static_releases(&url, req),
+    }
+}
+
+/// The release tagged exactly `tag`. Tags that are not versions (`nightly`)
+/// are recorded as version 0.0.0.
+pub fn tagged_release(
+    provider: ReleaseProvider,
+    resource: &Resource,
+    tag: &str,
+) -> Result<Release, String> {
+    let url = releases_url(provider, resource)?;
+    let version = tag_version(tag).unwrap_or(Version::new(0, 0, 0));
+    match provider {
+        ReleaseProvider::Github | ReleaseProvider::Gitea => {
+            let release: ForgeRelease = get_json(&format!("{url}/tags/{tag}"), provider)?;
+            Ok(release.into_release(version))
+        }
+        ReleaseProvider::Gitlab => {
+            let release: GitlabRelease = get_json(&format!("{url}/{tag}"), provider)?;
+            Ok(release.into_release(version))
+        }
+        ReleaseProvider::Static => static_release(&url, tag.to_string(), version),
+    }
+}
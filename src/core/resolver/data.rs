@@ -1,4 +1,5 @@
 use crate::core::types::*;
+use std::path::Path;
 
 /// Get the value field or return an error for data sources that require it.
 fn require_value<'a>(key: &str, source: &'a DataSource) -> Result<&'a str, String> {
@@ -151,3 +152,39 @@ pub fn resolve_data_sources(config: &mut ForjarConfig) -> Result<(), String> {
     }
     Ok(())
 }
+
+/// FJ-3315: Inject recipe outputs into config params as
+/// `__recipe__<id>.outputs.<name>`. Captured outputs are read from the global
+/// lock in `state_dir`; one that has not been captured yet falls back to its
+/// default, or is left unset so that templates using it fail to resolve.
+pub fn resolve_recipe_outputs(config: &mut ForjarConfig, state_dir: &Path) -> Result<(), String> {
+    if config.recipe_outputs.is_empty() {
+        return Ok(());
+    }
+    let captured = if config
+        .recipe_outputs
+        .values()
+        .any(|b| matches!(b, RecipeOutputBinding::Capture { .. }))
+    {
+        crate::core::state::load_global_lock(state_dir)?
+            .map(|lock| lock.outputs)
+            .unwrap_or_default()
+    } else {
+        Default::default()
+    };
+    for (key, binding) in &config.recipe_outputs {
+        let value = match binding {
+            RecipeOutputBinding::Value(value) => Some(value.clone()),
+            RecipeOutputBinding::Capture { output, default } => {
+                captured.get(output).cloned().or_else(|| default.clone())
+            }
+        };
+        if let Some(value) = value {
+            config.params.insert(
+                format!("__recipe__{key}"),
+                serde_yaml_ng::Value::String(value),
+            );
+        }
+    }
+    Ok(())
+}
@@ -12,8 +12,10 @@ pub(crate) mod staleness;
 pub(crate) mod template;
 
 pub use dag::{build_execution_order, compute_parallel_waves};
-pub use data::resolve_data_sources;
-pub use resource::{resolve_resource_templates, resolve_resource_templates_with_secrets};
+pub use data::{resolve_data_sources, resolve_recipe_outputs};
+pub use resource::{
+    apply_machine_defaults, resolve_resource_templates, resolve_resource_templates_with_secrets,
+};
 pub use template::resolve_template;
 pub use template::{redact_secrets, resolve_secret_with_provider, resolve_template_with_secrets};
 
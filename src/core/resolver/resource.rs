@@ -83,11 +83,58 @@ fn resolve_extended_fields(
 
     // github_release fields
     r.install_dir = resolve_opt(&r.install_dir, params, machines, secrets)?;
+    r.checksum_file = resolve_opt(&r.checksum_file, params, machines, secrets)?;
     r.repo = resolve_opt(&r.repo, params, machines, secrets)?;
     r.tag = resolve_opt(&r.tag, params, machines, secrets)?;
     r.asset_pattern = resolve_opt(&r.asset_pattern, params, machines, secrets)?;
     r.binary = resolve_opt(&r.binary, params, machines, secrets)?;
     r.build_machine = resolve_opt(&r.build_machine, params, machines, secrets)?;
+
+    // container runtime fields
+    r.image_digest = resolve_opt(&r.image_digest, params, machines, secrets)?;
+    r.user = resolve_opt(&r.user, params, machines, secrets)?;
+    r.entrypoint = resolve_opt(&r.entrypoint, params, machines, secrets)?;
+    r.subnet = resolve_opt(&r.subnet, params, machines, secrets)?;
+    r.gateway = resolve_opt(&r.gateway, params, machines, secrets)?;
+
+    // readiness probe fields
+    r.url = resolve_opt(&r.url, params, machines, secrets)?;
+    r.host = resolve_opt(&r.host, params, machines, secrets)?;
+    r.pattern = resolve_opt(&r.pattern, params, machines, secrets)?;
+
+    // exec guards
+    r.creates = resolve_opt(&r.creates, params, machines, secrets)?;
+    r.unless = resolve_opt(&r.unless, params, machines, secrets)?;
+    r.onlyif = resolve_opt(&r.onlyif, params, machines, secrets)?;
+
+    // disk provisioning fields
+    r.device = resolve_opt(&r.device, params, machines, secrets)?;
+    r.size = resolve_opt(&r.size, params, machines, secrets)?;
+    r.volume_group = resolve_opt(&r.volume_group, params, machines, secrets)?;
+
+    // wireguard fields
+    r.interface = resolve_opt(&r.interface, params, machines, secrets)?;
+    r.public_key = resolve_opt(&r.public_key, params, machines, secrets)?;
+    r.endpoint = resolve_opt(&r.endpoint, params, machines, secrets)?;
+
+    // certificate fields
+    r.common_name = resolve_opt(&r.common_name, params, machines, secrets)?;
+    r.ca_cert = resolve_opt(&r.ca_cert, params, machines, secrets)?;
+    r.ca_key = resolve_opt(&r.ca_key, params, machines, secrets)?;
+
+    // system fields
+    r.hostname = resolve_opt(&r.hostname, params, machines, secrets)?;
+    r.timezone = resolve_opt(&r.timezone, params, machines, secrets)?;
+
+    // zfs fields
+    r.dataset = resolve_opt(&r.dataset, params, machines, secrets)?;
+    for value in r.properties.values_mut() {
+        *value = resolve_template_with_secrets(value, params, machines, secrets)?;
+    }
+
+    // postgres fields
+    r.password = resolve_opt(&r.password, params, machines, secrets)?;
+    r.database = resolve_opt(&r.database, params, machines, secrets)?;
     Ok(())
 }
 
@@ -117,6 +164,34 @@ pub fn resolve_resource_templates_with_secrets(
     r.volumes = resolve_list(&r.volumes, params, machines, secrets)?;
     r.packages = resolve_list(&r.packages, params, machines, secrets)?;
     r.output_artifacts = resolve_list(&r.output_artifacts, params, machines, secrets)?;
+    r.labels = resolve_list(&r.labels, params, machines, secrets)?;
+    r.networks = resolve_list(&r.networks, params, machines, secrets)?;
+    r.physical_volumes = resolve_list(&r.physical_volumes, params, machines, secrets)?;
+    r.addresses = resolve_list(&r.addresses, params, machines, secrets)?;
+    r.allowed_ips = resolve_list(&r.allowed_ips, params, machines, secrets)?;
+    r.dns = resolve_list(&r.dns, params, machines, secrets)?;
+    r.routes = resolve_list(&r.routes, params, machines, secrets)?;
+    r.sans = resolve_list(&r.sans, params, machines, secrets)?;
+    r.ntp_servers = resolve_list(&r.ntp_servers, params, machines, secrets)?;
+    r.vdevs = resolve_list(&r.vdevs, params, machines, secrets)?;
+    r.mig_profiles = resolve_list(&r.mig_profiles, params, machines, secrets)?;
+    r.grants = resolve_list(&r.grants, params, machines, secrets)?;
+
+    if let MachineTarget::Single(ref name) = r.machine {
+        if let Some(machine) = machines.get(name) {
+            apply_machine_defaults(&mut r, machine);
+        }
+    }
 
     Ok(r)
 }
+
+/// Fill fields that default to a property of the target machine.
+///
+/// The resolver covers single-machine targets; the executor calls this
+/// again per machine for multi-machine targets.
+pub fn apply_machine_defaults(resource: &mut Resource, machine: &Machine) {
+    if resource.resource_type == ResourceType::System && resource.hostname.is_none() {
+        resource.hostname = Some(machine.hostname.clone());
+    }
+}
@@ -9,7 +9,11 @@ use std::collections::HashMap;
 /// - `file`: reads `<path>/<key>` (default path: `/run/secrets/`)
 /// - `sops`: runs `sops -d --extract '["<key>"]' <file>` to decrypt
 /// - `op`: runs `op read "op://forjar/<key>"` (or custom vault via path)
+/// - `vault`: reads HashiCorp Vault KV v2 as configured in `secrets.vault`
 pub(super) fn resolve_secret(key: &str, secrets_cfg: &SecretsConfig) -> Result<String, String> {
+    if let (Some("vault"), Some(vault)) = (secrets_cfg.provider.as_deref(), &secrets_cfg.vault) {
+        return crate::core::vault::resolve(key, vault);
+    }
     resolve_secret_with_provider(
         key,
         secrets_cfg.provider.as_deref(),
@@ -29,6 +33,7 @@ pub fn resolve_secret_with_provider(
         "file" => resolve_secret_file(key, path_prefix),
         "sops" => resolve_secret_sops(key, sops_file),
         "op" => resolve_secret_op(key, path_prefix),
+        "vault" => resolve_secret_vault(key, path_prefix),
         _ => resolve_secret_env(key),
     }
 }
@@ -79,6 +84,16 @@ fn resolve_secret_op(key: &str, vault: Option<&str>) -> Result<String, String> {
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+/// FJ-3311: Resolve from Vault KV v2 under `<path_prefix>` (mount `secret`),
+/// with `VAULT_ADDR` and `VAULT_TOKEN` from the environment.
+fn resolve_secret_vault(key: &str, path_prefix: Option<&str>) -> Result<String, String> {
+    let config = VaultConfig {
+        path: path_prefix.map(str::to_string),
+        ..Default::default()
+    };
+    crate::core::vault::resolve(key, &config)
+}
+
 /// FJ-2300: Redact secret values from a string.
 ///
 /// Replaces all occurrences of secret values with `***`.
@@ -121,6 +136,14 @@ fn resolve_variable<'a>(
                 .ok_or_else(|| format!("unknown data source: {data_key}"))?,
         ));
     }
+    if let Some(output_key) = key.strip_prefix("recipes.") {
+        return Ok(Cow::Owned(
+            params
+                .get(&format!("__recipe__{output_key}"))
+                .map(yaml_value_to_string)
+                .ok_or_else(|| format!("recipe output has no value yet: {output_key}"))?,
+        ));
+    }
     if key.contains('(') {
         return Ok(Cow::Owned(super::functions::resolve_function(
             key, params, machines,
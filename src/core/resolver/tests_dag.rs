@@ -153,6 +153,7 @@ fn test_fj132_build_dag_unknown_dependency() {
         data: indexmap::IndexMap::new(),
         includes: vec![],
         include_provenance: HashMap::new(),
+        recipe_outputs: indexmap::IndexMap::new(),
         checks: indexmap::IndexMap::new(),
         moved: vec![],
         secrets: Default::default(),
@@ -196,6 +197,7 @@ fn test_fj132_kahn_sort_diamond_dependency() {
         data: indexmap::IndexMap::new(),
         includes: vec![],
         include_provenance: HashMap::new(),
+        recipe_outputs: indexmap::IndexMap::new(),
         checks: indexmap::IndexMap::new(),
         moved: vec![],
         secrets: Default::default(),
@@ -221,6 +223,7 @@ fn test_fj132_build_execution_order_empty() {
         data: indexmap::IndexMap::new(),
         includes: vec![],
         include_provenance: HashMap::new(),
+        recipe_outputs: indexmap::IndexMap::new(),
         checks: indexmap::IndexMap::new(),
         moved: vec![],
         secrets: Default::default(),
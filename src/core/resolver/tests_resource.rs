@@ -126,6 +126,83 @@ fn test_fj003_resolve_all_fields() {
         asset_pattern: None,
         binary: None,
         install_dir: None,
+        runtime: None,
+        pull_policy: None,
+        image_digest: None,
+        labels: vec![],
+        user: None,
+        entrypoint: None,
+        cpus: None,
+        networks: vec![],
+        driver: None,
+        subnet: None,
+        gateway: None,
+        url: None,
+        host: None,
+        expect_status: None,
+        pattern: None,
+        interval: None,
+        backoff: None,
+        creates: None,
+        unless: None,
+        onlyif: None,
+        refreshonly: false,
+        returns: vec![],
+        device: None,
+        size: None,
+        number: None,
+        part_type: None,
+        physical_volumes: vec![],
+        volume_group: None,
+        swappiness: None,
+        force: false,
+        interface: None,
+        addresses: vec![],
+        public_key: None,
+        endpoint: None,
+        allowed_ips: vec![],
+        persistent_keepalive: None,
+        kind: None,
+        members: vec![],
+        vlan_id: None,
+        bond_mode: None,
+        mtu: None,
+        dhcp: None,
+        dns: vec![],
+        routes: vec![],
+        revert_after: None,
+        issuer: None,
+        ca_name: None,
+        ca_cert: None,
+        ca_key: None,
+        common_name: None,
+        sans: vec![],
+        validity_days: None,
+        renew_before: None,
+        key_type: None,
+        key_path: None,
+        chain_path: None,
+        hostname: None,
+        timezone: None,
+        locale: None,
+        keyboard: None,
+        ntp_servers: vec![],
+        vdevs: vec![],
+        properties: Default::default(),
+        dataset: None,
+        keep: None,
+        recursive: false,
+        mig_profiles: vec![],
+        power_limit_watts: None,
+        container_toolkit: false,
+        checksum_file: None,
+        signature: None,
+        password: None,
+        attributes: Vec::new(),
+        database: None,
+        encoding: None,
+        grants: Vec::new(),
+        selinux_type: None,
     };
 
     let resolved = resolve_resource_templates(&resource, &params, &machines).unwrap();
@@ -293,9 +370,128 @@ fn test_fj003_resolve_resource_templates_group_and_mode() {
         asset_pattern: None,
         binary: None,
         install_dir: None,
+        runtime: None,
+        pull_policy: None,
+        image_digest: None,
+        labels: vec![],
+        user: None,
+        entrypoint: None,
+        cpus: None,
+        networks: vec![],
+        driver: None,
+        subnet: None,
+        gateway: None,
+        url: None,
+        host: None,
+        expect_status: None,
+        pattern: None,
+        interval: None,
+        backoff: None,
+        creates: None,
+        unless: None,
+        onlyif: None,
+        refreshonly: false,
+        returns: vec![],
+        device: None,
+        size: None,
+        number: None,
+        part_type: None,
+        physical_volumes: vec![],
+        volume_group: None,
+        swappiness: None,
+        force: false,
+        interface: None,
+        addresses: vec![],
+        public_key: None,
+        endpoint: None,
+        allowed_ips: vec![],
+        persistent_keepalive: None,
+        kind: None,
+        members: vec![],
+        vlan_id: None,
+        bond_mode: None,
+        mtu: None,
+        dhcp: None,
+        dns: vec![],
+        routes: vec![],
+        revert_after: None,
+        issuer: None,
+        ca_name: None,
+        ca_cert: None,
+        ca_key: None,
+        common_name: None,
+        sans: vec![],
+        validity_days: None,
+        renew_before: None,
+        key_type: None,
+        key_path: None,
+        chain_path: None,
+        hostname: None,
+        timezone: None,
+        locale: None,
+        keyboard: None,
+        ntp_servers: vec![],
+        vdevs: vec![],
+        properties: Default::default(),
+        dataset: None,
+        keep: None,
+        recursive: false,
+        mig_profiles: vec![],
+        power_limit_watts: None,
+        container_toolkit: false,
+        checksum_file: None,
+        signature: None,
+        password: None,
+        attributes: Vec::new(),
+        database: None,
+        encoding: None,
+        grants: Vec::new(),
+        selinux_type: None,
     };
 
     let resolved = resolve_resource_templates(&resource, &params, &machines).unwrap();
     assert_eq!(resolved.group.as_deref(), Some("www-data"));
     assert_eq!(resolved.mode.as_deref(), Some("0644"));
 }
+
+#[test]
+fn test_system_hostname_defaults_to_machine() {
+    let yaml = r#"
+version: "1.0"
+name: test
+machines:
+  web:
+    hostname: web1.example.com
+    addr: 10.0.0.1
+  db:
+    hostname: db1
+    addr: 10.0.0.2
+resources:
+  identity:
+    type: system
+    machine: web
+    timezone: UTC
+  shared:
+    type: system
+    machine: [web, db]
+  named:
+    type: system
+    machine: web
+    hostname: "{{machine.db.hostname}}-replica"
+"#;
+    let config: ForjarConfig = serde_yaml_ng::from_str(yaml).unwrap();
+    let resolve = |id: &str| {
+        resolve_resource_templates(&config.resources[id], &config.params, &config.machines).unwrap()
+    };
+    assert_eq!(
+        resolve("identity").hostname.as_deref(),
+        Some("web1.example.com")
+    );
+    assert_eq!(resolve("named").hostname.as_deref(), Some("db1-replica"));
+
+    // Multi-machine targets are filled in per machine by the executor
+    let mut shared = resolve("shared");
+    assert_eq!(shared.hostname, None);
+    apply_machine_defaults(&mut shared, &config.machines["db"]);
+    assert_eq!(shared.hostname.as_deref(), Some("db1"));
+}
@@ -13,11 +13,11 @@ use std::path::Path;
 pub struct SecretAccessEvent {
     /// ISO 8601 timestamp.
     pub timestamp: String,
-    /// Event type: resolve, inject, discard, rotate.
+    /// Event type: resolve, inject, discard, rotate, renew.
     pub event_type: SecretEventType,
     /// Secret key name.
     pub key: String,
-    /// Provider that resolved the secret (env, file, exec, age).
+    /// Provider that resolved the secret (env, file, exec, age, vault).
     pub provider: String,
     /// BLAKE3 hash of the secret value (never store plaintext).
     pub value_hash: String,
@@ -29,6 +29,17 @@ pub struct SecretAccessEvent {
     /// Namespace the secret was injected into (if applicable).
     #[serde(default)]
     pub namespace: Option<String>,
+    /// FJ-3311: Provider-side version of the value (Vault KV v2).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u64>,
+}
+
+impl SecretAccessEvent {
+    /// Record the provider-side version the value came from.
+    pub fn with_version(mut self, version: u64) -> Self {
+        self.version = Some(version);
+        self
+    }
 }
 
 /// Types of secret access events.
@@ -43,6 +54,8 @@ pub enum SecretEventType {
     Discard,
     /// Secret key rotated.
     Rotate,
+    /// Provider token or lease renewed.
+    Renew,
 }
 
 impl std::fmt::Display for SecretEventType {
@@ -52,6 +65,7 @@ impl std::fmt::Display for SecretEventType {
             Self::Inject => "inject",
             Self::Discard => "discard",
             Self::Rotate => "rotate",
+            Self::Renew => "renew",
         };
         write!(f, "{s}")
     }
@@ -109,6 +123,7 @@ pub fn make_resolve_event(
         machine: machine.map(|s| s.to_string()),
         pid: std::process::id(),
         namespace: None,
+        version: None,
     }
 }
 
@@ -128,6 +143,7 @@ pub fn make_inject_event(
         machine: None,
         pid: std::process::id(),
         namespace: Some(namespace.to_string()),
+        version: None,
     }
 }
 
@@ -142,6 +158,7 @@ pub fn make_discard_event(key: &str, value_hash: &str) -> SecretAccessEvent {
         machine: None,
         pid: std::process::id(),
         namespace: None,
+        version: None,
     }
 }
 
@@ -162,6 +179,22 @@ pub fn make_rotate_event(
         machine: None,
         pid: std::process::id(),
         namespace: Some(format!("rotated_from:{old_hash}")),
+        version: None,
+    }
+}
+
+/// Create a renew event (provider token or lease `key` renewed).
+pub fn make_renew_event(key: &str, provider: &str) -> SecretAccessEvent {
+    SecretAccessEvent {
+        timestamp: crate::tripwire::eventlog::now_iso8601(),
+        event_type: SecretEventType::Renew,
+        key: key.to_string(),
+        provider: provider.to_string(),
+        value_hash: String::new(),
+        machine: None,
+        pid: std::process::id(),
+        namespace: None,
+        version: None,
     }
 }
 
@@ -220,6 +253,7 @@ pub fn audit_summary(events: &[SecretAccessEvent]) -> AuditSummary {
             SecretEventType::Inject => injects += 1,
             SecretEventType::Discard => discards += 1,
             SecretEventType::Rotate => rotations += 1,
+            SecretEventType::Renew => {}
         }
     }
 
@@ -1,7 +1,7 @@
 //! FJ-3301: Secret provider trait and implementations.
 //!
 //! Defines a pluggable interface for resolving secrets from different
-//! backends: environment variables, files, age encryption, and exec.
+//! backends: environment variables, files, age encryption, exec and Vault.
 
 use std::path::Path;
 
@@ -136,6 +136,34 @@ impl SecretProvider for ExecProvider {
     }
 }
 
+/// FJ-3311: HashiCorp Vault KV v2 secret provider.
+///
+/// Keys are `[path/]name[#field][@version]` relative to the client's
+/// configured mount and path.
+pub struct VaultProvider {
+    client: super::vault::VaultClient,
+}
+
+impl VaultProvider {
+    /// Create a Vault provider using the given client.
+    pub fn new(client: super::vault::VaultClient) -> Self {
+        Self { client }
+    }
+}
+
+impl SecretProvider for VaultProvider {
+    fn resolve(&self, key: &str) -> Result<Option<SecretValue>, String> {
+        Ok(self.client.read(key)?.map(|secret| SecretValue {
+            value: secret.value,
+            provider: "vault",
+        }))
+    }
+
+    fn name(&self) -> &'static str {
+        "vault"
+    }
+}
+
 /// Chain of secret providers — tries each in order until one resolves.
 pub struct ProviderChain {
     providers: Vec<Box<dyn SecretProvider>>,
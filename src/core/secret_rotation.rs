@@ -0,0 +1,184 @@
+//! FJ-3312: Single-secret rotation with rollback.
+//!
+//! A rotatable secret is a param whose value is an `ENC[age,...]` marker.
+//! Rotation swaps that marker for one encrypting a new value, records both
+//! BLAKE3 fingerprints (never the values) in the secret audit log and keeps
+//! a rotation record with both markers in the state backend so the swap
+//! can be undone.
+//! Resources consume the secret through `{{ params.<key> }}` templates.
+
+use super::secret_audit;
+use super::state::backend;
+use super::types::ForjarConfig;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const ROTATIONS_FILENAME: &str = "secret-rotations.jsonl";
+const ENC_PREFIX: &str = "ENC[age,";
+
+/// One rotation (or rollback) of a secret.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotationRecord {
+    /// ISO 8601 timestamp.
+    pub rotated_at: String,
+    /// Param holding the secret.
+    pub key: String,
+    /// Config file the marker was rewritten in.
+    pub file: String,
+    /// Marker before the rotation.
+    pub old_marker: String,
+    /// Marker after the rotation.
+    pub new_marker: String,
+    /// BLAKE3 fingerprint of the old value.
+    pub old_hash: String,
+    /// BLAKE3 fingerprint of the new value.
+    pub new_hash: String,
+    /// Resources that consume the secret.
+    pub resources: Vec<String>,
+    /// True when this record undid an earlier rotation.
+    #[serde(default)]
+    pub rollback: bool,
+}
+
+impl RotationRecord {
+    /// The rollback record that undoes this rotation.
+    pub fn inverse(&self) -> Self {
+        Self {
+            rotated_at: crate::tripwire::eventlog::now_iso8601(),
+            key: self.key.clone(),
+            file: self.file.clone(),
+            old_marker: self.new_marker.clone(),
+            new_marker: self.old_marker.clone(),
+            old_hash: self.new_hash.clone(),
+            new_hash: self.old_hash.clone(),
+            resources: self.resources.clone(),
+            rollback: true,
+        }
+    }
+}
+
+/// BLAKE3 fingerprint of a secret value.
+pub fn fingerprint(value: &str) -> String {
+    blake3::hash(value.as_bytes()).to_hex().to_string()
+}
+
+/// A fresh random value: 32 bytes, URL-safe base64 without padding.
+pub fn generate_value() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    openssl::rand::rand_bytes(&mut bytes).map_err(|e| format!("random value: {e}"))?;
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// The `ENC[age,...]` marker held by param `key` in config YAML `content`.
+pub fn param_marker(content: &str, key: &str) -> Result<String, String> {
+    let doc: serde_yaml_ng::Value =
+        serde_yaml_ng::from_str(content).map_err(|e| format!("parse config: {e}"))?;
+    let value = doc
+        .get("params")
+        .and_then(|p| p.get(key))
+        .ok_or_else(|| format!("param '{key}' not found"))?;
+    let marker = value
+        .as_str()
+        .map(str::trim)
+        .filter(|s| s.starts_with(ENC_PREFIX) && s.ends_with(']') && !s[1..].contains(ENC_PREFIX))
+        .ok_or_else(|| format!("param '{key}' is not a single ENC[age,...] marker"))?;
+    Ok(marker.to_string())
+}
+
+/// Replace every occurrence of `old` with `new`. Returns the new content
+/// and how many occurrences were replaced.
+pub fn replace_marker(content: &str, old: &str, new: &str) -> (String, usize) {
+    (content.replace(old, new), content.matches(old).count())
+}
+
+/// Whether a `{{ ... }}` template in `text` references `params.<key>`.
+fn references(text: &str, key: &str) -> bool {
+    let want = format!("params.{key}");
+    let mut rest = text;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open..].find("}}") else {
+            break;
+        };
+        if rest[open + 2..open + close].trim() == want {
+            return true;
+        }
+        rest = &rest[open + close + 2..];
+    }
+    false
+}
+
+/// Resources whose fields reference `{{ params.<key> }}`.
+pub fn consumers(config: &ForjarConfig, key: &str) -> Vec<String> {
+    config
+        .resources
+        .iter()
+        .filter(|(_, resource)| {
+            serde_yaml_ng::to_string(resource).is_ok_and(|yaml| references(&yaml, key))
+        })
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
+/// Append `record` to the rotation log and its fingerprints to the secret
+/// audit log.
+pub fn record_rotation(state_dir: &Path, record: &RotationRecord) -> Result<(), String> {
+    std::fs::create_dir_all(state_dir)
+        .map_err(|e| format!("create {}: {e}", state_dir.display()))?;
+    let path = state_dir.join(ROTATIONS_FILENAME);
+    let line = serde_json::to_string(record).map_err(|e| format!("serialize rotation: {e}"))?;
+    let (backend, key) = backend::locate(&path);
+    backend
+        .append(&key, format!("{line}\n").as_bytes())
+        .map_err(|e| format!("write {}: {e}", path.display()))?;
+    secret_audit::append_audit(
+        state_dir,
+        &secret_audit::make_rotate_event(&record.key, "age", &record.old_hash, &record.new_hash),
+    )
+}
+
+/// Rotation records for `key`, oldest first.
+pub fn rotations(state_dir: &Path, key: &str) -> Result<Vec<RotationRecord>, String> {
+    let path = state_dir.join(ROTATIONS_FILENAME);
+    let (backend, store_key) = backend::locate(&path);
+    let Some((bytes, _)) = backend
+        .get_versioned(&store_key)
+        .map_err(|e| format!("read {}: {e}", path.display()))?
+    else {
+        return Ok(Vec::new());
+    };
+    let content = String::from_utf8_lossy(&bytes);
+    let mut records = Vec::new();
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let record: RotationRecord =
+            serde_json::from_str(line).map_err(|e| format!("parse rotation record: {e}"))?;
+        if record.key == key {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+/// The rotation a rollback of `key` undoes: the latest record, which must
+/// be a rotation whose marker is still the one in `current_marker`.
+pub fn rollback_target(
+    state_dir: &Path,
+    key: &str,
+    current_marker: &str,
+) -> Result<RotationRecord, String> {
+    let last = rotations(state_dir, key)?
+        .pop()
+        .ok_or_else(|| format!("no rotation of '{key}' to roll back"))?;
+    if last.rollback {
+        return Err(format!(
+            "the last rotation of '{key}' was already rolled back"
+        ));
+    }
+    if last.new_marker != current_marker {
+        return Err(format!(
+            "param '{key}' changed since it was rotated at {}; refusing to roll back",
+            last.rotated_at
+        ));
+    }
+    Ok(last)
+}
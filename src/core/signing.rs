@@ -0,0 +1,468 @@
+//! FJ-1432: Ed25519 signing for recipes, state locks and FAR archives.
+//!
+//! Keys live in a keyring directory (`FORJAR_KEY_DIR`, default
+//! `~/.forjar/keys`): `<name>.key` holds a PKCS#8 PEM private key (mode
+//! 0600) and `<name>.pub` the public key as `ed25519:<base64>`. A key can be
+//! named by its keyring name or by a path to a key file.
+//!
+//! A [`Signature`] is detached: it records the BLAKE3 hash of the signed
+//! content and an Ed25519 signature over a message binding that hash to a
+//! [`Scope`], so a recipe signature cannot be replayed as a lock signature.
+//! A [`TrustPolicy`] lists, per scope, whether signatures are required and
+//! which public keys may produce them.
+
+use base64::Engine;
+use openssl::pkey::{Id, PKey, Private, Public};
+use openssl::sign::{Signer, Verifier};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Signature algorithm recorded in every [`Signature`].
+pub const ALGORITHM: &str = "ed25519";
+
+/// Prefix of a public key in text form.
+pub const KEY_PREFIX: &str = "ed25519:";
+
+/// Domain separator of the signed message.
+const MESSAGE_TAG: &str = "forjar-signature-v1";
+
+fn b64() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+fn ssl_err(context: &str) -> impl Fn(openssl::error::ErrorStack) -> String + '_ {
+    move |e| format!("{context}: {e}")
+}
+
+/// What a signature covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    Recipe,
+    Lock,
+    Archive,
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Scope::Recipe => "recipe",
+            Scope::Lock => "lock",
+            Scope::Archive => "archive",
+        })
+    }
+}
+
+/// An Ed25519 public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey([u8; 32]);
+
+impl PublicKey {
+    /// Parse `ed25519:<base64>`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let encoded = text
+            .trim()
+            .strip_prefix(KEY_PREFIX)
+            .ok_or_else(|| format!("public key must start with '{KEY_PREFIX}': {text}"))?;
+        let raw = b64()
+            .decode(encoded)
+            .map_err(|e| format!("invalid public key {text}: {e}"))?;
+        let raw: [u8; 32] = raw
+            .try_into()
+            .map_err(|_| format!("invalid public key {text}: expected 32 bytes"))?;
+        Ok(Self(raw))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Whether `signature` is a valid Ed25519 signature of `message`.
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<bool, String> {
+        let pkey: PKey<Public> = PKey::public_key_from_raw_bytes(&self.0, Id::ED25519)
+            .map_err(ssl_err("ed25519 public key"))?;
+        let mut verifier = Verifier::new_without_digest(&pkey).map_err(ssl_err("ed25519"))?;
+        // A malformed signature is a failed verification, not an error.
+        Ok(verifier.verify_oneshot(signature, message).unwrap_or(false))
+    }
+}
+
+impl std::fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{KEY_PREFIX}{}", b64().encode(self.0))
+    }
+}
+
+/// An Ed25519 private key with its keyring name.
+pub struct SigningKey {
+    pub name: String,
+    key: PKey<Private>,
+}
+
+/// Never prints the private key.
+impl std::fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let public = self.public_key().map(|k| k.to_string()).unwrap_or_default();
+        f.debug_struct("SigningKey")
+            .field("name", &self.name)
+            .field("public_key", &public)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SigningKey {
+    /// Generate a fresh keypair.
+    pub fn generate(name: &str) -> Result<Self, String> {
+        Ok(Self {
+            name: name.to_string(),
+            key: PKey::generate_ed25519().map_err(ssl_err("ed25519 keygen"))?,
+        })
+    }
+
+    /// Load a PKCS#8 PEM private key; it must be Ed25519.
+    pub fn from_pem(name: &str, pem: &[u8]) -> Result<Self, String> {
+        let key = PKey::private_key_from_pem(pem).map_err(ssl_err("private key"))?;
+        if key.id() != Id::ED25519 {
+            return Err(format!("key '{name}' is not an Ed25519 key"));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            key,
+        })
+    }
+
+    /// Build a key from its 32-byte seed.
+    pub fn from_seed(name: &str, seed: &[u8]) -> Result<Self, String> {
+        let key = PKey::private_key_from_raw_bytes(seed, Id::ED25519)
+            .map_err(ssl_err("ed25519 private key"))?;
+        Ok(Self {
+            name: name.to_string(),
+            key,
+        })
+    }
+
+    pub fn to_pem(&self) -> Result<Vec<u8>, String> {
+        self.key
+            .private_key_to_pem_pkcs8()
+            .map_err(ssl_err("private key"))
+    }
+
+    pub fn public_key(&self) -> Result<PublicKey, String> {
+        let raw = self
+            .key
+            .raw_public_key()
+            .map_err(ssl_err("ed25519 public key"))?;
+        raw.try_into()
+            .map(PublicKey)
+            .map_err(|_| "ed25519 public key is not 32 bytes".to_string())
+    }
+
+    /// Raw Ed25519 signature of `message`.
+    pub fn sign_raw(&self, message: &[u8]) -> Result<Vec<u8>, String> {
+        let mut signer = Signer::new_without_digest(&self.key).map_err(ssl_err("ed25519"))?;
+        signer
+            .sign_oneshot_to_vec(message)
+            .map_err(ssl_err("ed25519 sign"))
+    }
+
+    /// Detached signature of `content` for `scope`.
+    pub fn sign(&self, scope: Scope, content: &[u8]) -> Result<Signature, String> {
+        let hash = blake3::hash(content).to_hex().to_string();
+        let raw = self.sign_raw(&signed_message(scope, &hash))?;
+        Ok(Signature {
+            algorithm: ALGORITHM.to_string(),
+            scope,
+            signer: self.name.clone(),
+            key: self.public_key()?.to_string(),
+            blake3: hash,
+            signed_at: crate::tripwire::eventlog::now_iso8601(),
+            signature: b64().encode(raw),
+        })
+    }
+}
+
+fn signed_message(scope: Scope, blake3_hex: &str) -> Vec<u8> {
+    format!("{MESSAGE_TAG}\n{scope}\n{blake3_hex}\n").into_bytes()
+}
+
+/// A detached signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    /// Always `ed25519`
+    pub algorithm: String,
+    /// What was signed
+    pub scope: Scope,
+    /// Keyring name of the signing key (informational)
+    pub signer: String,
+    /// Public key that verifies the signature
+    pub key: String,
+    /// BLAKE3 hash of the signed content (hex)
+    pub blake3: String,
+    pub signed_at: String,
+    /// Base64 Ed25519 signature
+    pub signature: String,
+}
+
+impl Signature {
+    /// Check the signature against `content`; returns the verifying key.
+    pub fn verify(&self, scope: Scope, content: &[u8]) -> Result<PublicKey, String> {
+        if self.algorithm != ALGORITHM {
+            return Err(format!(
+                "unsupported signature algorithm '{}'",
+                self.algorithm
+            ));
+        }
+        if self.scope != scope {
+            return Err(format!("signature covers a {}, not a {scope}", self.scope));
+        }
+        let hash = blake3::hash(content).to_hex().to_string();
+        if hash != self.blake3 {
+            return Err(format!("{scope} was modified after signing"));
+        }
+        let key = PublicKey::parse(&self.key)?;
+        let raw = b64()
+            .decode(&self.signature)
+            .map_err(|e| format!("invalid signature encoding: {e}"))?;
+        if !key.verify(&signed_message(scope, &hash), &raw)? {
+            return Err(format!("signature does not verify against {key}"));
+        }
+        Ok(key)
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("serialize signature: {e}"))
+    }
+
+    pub fn from_json(data: &str) -> Result<Self, String> {
+        serde_json::from_str(data).map_err(|e| format!("parse signature: {e}"))
+    }
+}
+
+/// Read a detached signature file, `None` when it does not exist.
+pub fn read_signature(path: &Path) -> Result<Option<Signature>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(data) => Signature::from_json(&data)
+            .map(Some)
+            .map_err(|e| format!("{}: {e}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("read {}: {e}", path.display())),
+    }
+}
+
+// ── Keyring ────────────────────────────────────────────────────────
+
+/// Default keyring: `FORJAR_KEY_DIR`, else `~/.forjar/keys`.
+pub fn default_keyring_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("FORJAR_KEY_DIR") {
+        return PathBuf::from(dir);
+    }
+    std::env::var("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("/var/lib"))
+        .join(".forjar")
+        .join("keys")
+}
+
+/// A keyring entry as listed by `forjar keys list`.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyEntry {
+    pub name: String,
+    pub public_key: String,
+    /// Whether the private half is present (the key can sign)
+    pub private: bool,
+    /// FJ-1433: ML-DSA-65 public key, when the key has a PQ half
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pq_public_key: Option<String>,
+}
+
+/// Named keys in a directory.
+pub struct Keyring {
+    dir: PathBuf,
+}
+
+/// Create `path` (which must not exist) with mode 0600 and write `data`.
+pub(super) fn write_private(path: &Path, data: &[u8]) -> Result<(), String> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("create {}: {e}", path.display()))?;
+    std::io::Write::write_all(&mut file, data).map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+pub(super) fn check_key_name(name: &str) -> Result<(), String> {
+    let ok = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if ok {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid key name '{name}' (letters, digits, '-', '_' and '.')"
+        ))
+    }
+}
+
+impl Keyring {
+    pub fn open(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn private_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.key"))
+    }
+
+    fn public_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.pub"))
+    }
+
+    fn ensure_absent(&self, name: &str) -> Result<(), String> {
+        check_key_name(name)?;
+        if self.public_path(name).exists() || self.private_path(name).exists() {
+            return Err(format!(
+                "key '{name}' already exists in {}",
+                self.dir.display()
+            ));
+        }
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("create {}: {e}", self.dir.display()))
+    }
+
+    fn store(&self, key: &SigningKey) -> Result<(), String> {
+        write_private(&self.private_path(&key.name), &key.to_pem()?)?;
+        self.store_public(&key.name, &key.public_key()?)
+    }
+
+    fn store_public(&self, name: &str, key: &PublicKey) -> Result<(), String> {
+        let path = self.public_path(name);
+        std::fs::write(&path, format!("{key}\n"))
+            .map_err(|e| format!("write {}: {e}", path.display()))
+    }
+
+    /// Create a new keypair named `name`.
+    pub fn generate(&self, name: &str) -> Result<SigningKey, String> {
+        self.ensure_absent(name)?;
+        let key = SigningKey::generate(name)?;
+        self.store(&key)?;
+        Ok(key)
+    }
+
+    /// Add the key in `file` as `name`: a PEM private key, or a public key
+    /// (`ed25519:<base64>`) to verify with only.
+    pub fn import(&self, name: &str, file: &Path) -> Result<KeyEntry, String> {
+        let data = std::fs::read(file).map_err(|e| format!("read {}: {e}", file.display()))?;
+        let text = String::from_utf8_lossy(&data);
+        self.ensure_absent(name)?;
+        if text.contains("PRIVATE KEY-----") {
+            let key = SigningKey::from_pem(name, &data)?;
+            self.store(&key)?;
+        } else {
+            let key = PublicKey::parse(text.trim())?;
+            self.store_public(name, &key)?;
+        }
+        self.entry(name)
+    }
+
+    fn entry(&self, name: &str) -> Result<KeyEntry, String> {
+        Ok(KeyEntry {
+            name: name.to_string(),
+            public_key: self.public_key(name)?.to_string(),
+            private: self.private_path(name).exists(),
+            pq_public_key: self.pq_public_key(name).ok().map(|k| k.to_string()),
+        })
+    }
+
+    /// All keys, sorted by name.
+    pub fn list(&self) -> Result<Vec<KeyEntry>, String> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Ok(Vec::new());
+        };
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().into_owned();
+                name.strip_suffix(".pub").map(str::to_string)
+            })
+            .collect();
+        names.sort();
+        names.iter().map(|n| self.entry(n)).collect()
+    }
+
+    pub fn signing_key(&self, name: &str) -> Result<SigningKey, String> {
+        check_key_name(name)?;
+        let path = self.private_path(name);
+        let pem = std::fs::read(&path).map_err(|_| {
+            format!(
+                "no private key '{name}' in {} (see `forjar keys list`)",
+                self.dir.display()
+            )
+        })?;
+        SigningKey::from_pem(name, &pem)
+    }
+
+    pub fn public_key(&self, name: &str) -> Result<PublicKey, String> {
+        check_key_name(name)?;
+        let path = self.public_path(name);
+        let text = std::fs::read_to_string(&path).map_err(|_| {
+            format!(
+                "no key '{name}' in {} (see `forjar keys list`)",
+                self.dir.display()
+            )
+        })?;
+        PublicKey::parse(&text)
+    }
+}
+
+/// Signing key named by `spec`: a path to a PEM key file, or a keyring name.
+pub fn load_signing_key(spec: &str) -> Result<SigningKey, String> {
+    let path = Path::new(spec);
+    if path.is_file() {
+        let pem = std::fs::read(path).map_err(|e| format!("read {spec}: {e}"))?;
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| spec.to_string());
+        return SigningKey::from_pem(&name, &pem);
+    }
+    Keyring::open(&default_keyring_dir()).signing_key(spec)
+}
+
+/// Signing key named by `spec`, else by `FORJAR_SIGNING_KEY`.
+pub fn resolve_signing_key(spec: Option<&str>) -> Result<SigningKey, String> {
+    match spec {
+        Some(spec) => load_signing_key(spec),
+        None => match std::env::var("FORJAR_SIGNING_KEY") {
+            Ok(spec) if !spec.is_empty() => load_signing_key(&spec),
+            _ => Err("no signing key: pass --key/--signer or set FORJAR_SIGNING_KEY".to_string()),
+        },
+    }
+}
+
+/// Public key named by `spec`: `ed25519:<base64>`, a path to a public or
+/// private key file, or a keyring name.
+pub fn load_public_key(spec: &str) -> Result<PublicKey, String> {
+    if spec.starts_with(KEY_PREFIX) {
+        return PublicKey::parse(spec);
+    }
+    let path = Path::new(spec);
+    if path.is_file() {
+        let data = std::fs::read(path).map_err(|e| format!("read {spec}: {e}"))?;
+        if String::from_utf8_lossy(&data).contains("PRIVATE KEY-----") {
+            return SigningKey::from_pem(spec, &data)?.public_key();
+        }
+        return PublicKey::parse(&String::from_utf8_lossy(&data));
+    }
+    Keyring::open(&default_keyring_dir()).public_key(spec)
+}
+
+pub use super::signing_b::*;
@@ -0,0 +1,347 @@
+//! FJ-1432: Trust policy and state lock signatures.
+
+use super::signing::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+// ── Trust policy ───────────────────────────────────────────────────
+
+/// A public key allowed to sign within a scope.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrustedSigner {
+    pub name: String,
+    /// `ed25519:<base64>`
+    pub key: String,
+    /// `ml-dsa-65:<base64>`, required to accept this signer's dual
+    /// (`sign --pq`) signatures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pq_key: Option<String>,
+}
+
+/// Signature rules for one scope.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrustScope {
+    /// Refuse unsigned or untrusted content in this scope
+    #[serde(default)]
+    pub require_signatures: bool,
+    #[serde(default)]
+    pub signers: Vec<TrustedSigner>,
+}
+
+/// Trust policy file (`--trust-policy`, `FORJAR_TRUST_POLICY` or
+/// `policy.trust_policy`).
+///
+/// ```yaml
+/// recipes:
+///   require_signatures: true
+///   signers:
+///     - name: platform-team
+///       key: ed25519:11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo=
+///       pq_key: ml-dsa-65:...
+/// locks:
+///   require_signatures: true
+///   signers:
+///     - name: ci
+///       key: ed25519:...
+/// archives:
+///   require_signatures: false
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrustPolicy {
+    #[serde(default)]
+    pub recipes: TrustScope,
+    #[serde(default)]
+    pub locks: TrustScope,
+    #[serde(default)]
+    pub archives: TrustScope,
+}
+
+impl TrustPolicy {
+    pub fn parse(yaml: &str) -> Result<Self, String> {
+        let policy: Self =
+            serde_yaml_ng::from_str(yaml).map_err(|e| format!("trust policy: {e}"))?;
+        for scope in [Scope::Recipe, Scope::Lock, Scope::Archive] {
+            let rules = policy.scope(scope);
+            if rules.require_signatures && rules.signers.is_empty() {
+                return Err(format!(
+                    "trust policy: {scope} signatures are required but no signers are listed"
+                ));
+            }
+            for signer in &rules.signers {
+                PublicKey::parse(&signer.key)
+                    .map_err(|e| format!("trust policy: signer '{}': {e}", signer.name))?;
+                if let Some(ref pq_key) = signer.pq_key {
+                    super::pq_signing::PqPublicKey::parse(pq_key)
+                        .map_err(|e| format!("trust policy: signer '{}': {e}", signer.name))?;
+                }
+            }
+        }
+        Ok(policy)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let yaml = std::fs::read_to_string(path)
+            .map_err(|e| format!("read trust policy {}: {e}", path.display()))?;
+        Self::parse(&yaml).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn scope(&self, scope: Scope) -> &TrustScope {
+        match scope {
+            Scope::Recipe => &self.recipes,
+            Scope::Lock => &self.locks,
+            Scope::Archive => &self.archives,
+        }
+    }
+
+    pub fn requires(&self, scope: Scope) -> bool {
+        self.scope(scope).require_signatures
+    }
+
+    /// Name of the trusted signer owning `key`, if any.
+    pub fn trusted_signer(&self, scope: Scope, key: &PublicKey) -> Option<&str> {
+        self.scope(scope)
+            .signers
+            .iter()
+            .find(|s| PublicKey::parse(&s.key).ok().as_ref() == Some(key))
+            .map(|s| s.name.as_str())
+    }
+
+    /// Enforce the policy on `content` named `what`. A no-op unless the
+    /// scope requires signatures; otherwise the signature must exist,
+    /// verify, and come from a listed signer, whose name is returned.
+    pub fn check(
+        &self,
+        scope: Scope,
+        what: &str,
+        content: &[u8],
+        signature: Option<&Signature>,
+    ) -> Result<Option<String>, String> {
+        if !self.requires(scope) {
+            return Ok(None);
+        }
+        let sig = signature.ok_or_else(|| {
+            format!("{what}: trust policy requires a signed {scope}, but it is unsigned")
+        })?;
+        let key = sig
+            .verify(scope, content)
+            .map_err(|e| format!("{what}: {e}"))?;
+        match self.trusted_signer(scope, &key) {
+            Some(name) => Ok(Some(name.to_string())),
+            None => Err(format!(
+                "{what}: signed by {key}, which the trust policy does not list for {scope}s"
+            )),
+        }
+    }
+}
+
+/// Trust policy from `path`, else `FORJAR_TRUST_POLICY`, else none.
+pub fn load_trust_policy(path: Option<&Path>) -> Result<Option<TrustPolicy>, String> {
+    let path = match path {
+        Some(p) => p.to_path_buf(),
+        None => match std::env::var("FORJAR_TRUST_POLICY") {
+            Ok(p) if !p.is_empty() => PathBuf::from(p),
+            _ => return Ok(None),
+        },
+    };
+    TrustPolicy::load(&path).map(Some)
+}
+
+/// Trust policy for a config: the operator's `FORJAR_TRUST_POLICY`, else
+/// `policy.trust_policy` (relative to the config directory).
+pub fn trust_policy_for(
+    config: &crate::core::types::ForjarConfig,
+    config_dir: &Path,
+) -> Result<Option<TrustPolicy>, String> {
+    let operator = std::env::var_os("FORJAR_TRUST_POLICY")
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from);
+    trust_policy_with(operator.as_deref(), config, config_dir)
+}
+
+/// Trust policy for a config given the operator's policy file. The
+/// operator's file wins: a config, or a remote include merged into it,
+/// can add a policy where there is none but never replace the operator's.
+pub fn trust_policy_with(
+    operator: Option<&Path>,
+    config: &crate::core::types::ForjarConfig,
+    config_dir: &Path,
+) -> Result<Option<TrustPolicy>, String> {
+    if let Some(path) = operator {
+        return TrustPolicy::load(path).map(Some);
+    }
+    match config.policy.trust_policy.as_deref() {
+        Some(p) => TrustPolicy::load(&config_dir.join(p)).map(Some),
+        None => Ok(None),
+    }
+}
+
+// ── State locks ────────────────────────────────────────────────────
+
+/// Key of a machine's lock signature in the state backend.
+pub fn lock_signature_key(machine: &str) -> String {
+    format!("{machine}/lock.sig")
+}
+
+fn lock_key(machine: &str) -> String {
+    format!("{machine}/state.lock.yaml")
+}
+
+/// Machines with a lock file, sorted.
+pub fn locked_machines(state_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(state_dir) else {
+        return Vec::new();
+    };
+    let mut machines: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().join("state.lock.yaml").is_file())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|m| !m.starts_with('.'))
+        .collect();
+    machines.sort();
+    machines
+}
+
+/// Signed content of a stored lock: the plaintext, so re-encrypting it to
+/// other recipients (FJ-3310) keeps the signature valid.
+fn lock_content(state_dir: &Path, machine: &str, stored: Vec<u8>) -> Result<Vec<u8>, String> {
+    crate::core::state_recipients::open_state(state_dir, &lock_key(machine), stored)
+}
+
+/// Sign `machine`'s lock and store the signature next to it.
+pub fn sign_state_lock(state_dir: &Path, machine: &str, key: &SigningKey) -> Result<(), String> {
+    let backend = crate::core::state::backend::backend_for(state_dir);
+    let content = lock_content(state_dir, machine, backend.get(&lock_key(machine))?)?;
+    let sig = key.sign(Scope::Lock, &content)?;
+    backend.put(&lock_signature_key(machine), sig.to_json()?.as_bytes())
+}
+
+/// `machine`'s lock content and its signature, if signed.
+pub fn read_state_lock_signature(
+    state_dir: &Path,
+    machine: &str,
+) -> Result<(Vec<u8>, Option<Signature>), String> {
+    let backend = crate::core::state::backend::backend_for(state_dir);
+    let content = lock_content(state_dir, machine, backend.get(&lock_key(machine))?)?;
+    let sig = match backend.get_versioned(&lock_signature_key(machine))? {
+        Some((data, _)) => Some(
+            Signature::from_json(&String::from_utf8_lossy(&data))
+                .map_err(|e| format!("{machine}/lock.sig: {e}"))?,
+        ),
+        None => None,
+    };
+    Ok((content, sig))
+}
+
+type Signers = Mutex<HashMap<PathBuf, Arc<SigningKey>>>;
+
+fn signers() -> &'static Signers {
+    static SIGNERS: OnceLock<Signers> = OnceLock::new();
+    SIGNERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Sign every lock saved to `state_dir` with `key`.
+pub fn register_lock_signer(state_dir: &Path, key: SigningKey) {
+    if let Ok(mut map) = signers().lock() {
+        map.insert(state_dir.to_path_buf(), Arc::new(key));
+    }
+}
+
+/// Stop signing locks saved to `state_dir`.
+pub fn unregister_lock_signer(state_dir: &Path) {
+    if let Ok(mut map) = signers().lock() {
+        map.remove(state_dir);
+    }
+}
+
+/// Sign `machine`'s just-saved lock if a signer is registered for
+/// `state_dir`. Called by every lock writer, so a failed apply or a
+/// partial destroy never leaves an unsigned lock behind.
+pub fn sign_saved_lock(state_dir: &Path, machine: &str) -> Result<(), String> {
+    let key = signers()
+        .lock()
+        .ok()
+        .and_then(|map| map.get(state_dir).cloned());
+    match key {
+        Some(key) => sign_state_lock(state_dir, machine, &key),
+        None => Ok(()),
+    }
+}
+
+/// Sign every machine lock in `state_dir`; returns how many were signed.
+pub fn sign_state_locks(state_dir: &Path, key: &SigningKey) -> Result<usize, String> {
+    let machines = locked_machines(state_dir);
+    for machine in &machines {
+        sign_state_lock(state_dir, machine, key)?;
+    }
+    Ok(machines.len())
+}
+
+/// Enforce `policy` on every machine lock in `state_dir`.
+pub fn verify_state_locks(state_dir: &Path, policy: &TrustPolicy) -> Result<(), String> {
+    if !policy.requires(Scope::Lock) {
+        return Ok(());
+    }
+    for machine in locked_machines(state_dir) {
+        let (content, sig) = read_state_lock_signature(state_dir, &machine)?;
+        policy.check(
+            Scope::Lock,
+            &format!("{machine}/state.lock.yaml"),
+            &content,
+            sig.as_ref(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Before an apply: enforce `policy` on the existing locks and resolve the
+/// key (`FORJAR_SIGNING_KEY`) that signs the locks the apply writes. When
+/// lock signatures are required the key must be present and trusted, so a
+/// run fails before it changes anything rather than leaving unverifiable
+/// locks behind.
+pub fn prepare_lock_signing(
+    policy: Option<&TrustPolicy>,
+    state_dir: &Path,
+    dry_run: bool,
+) -> Result<Option<SigningKey>, String> {
+    let required = policy.is_some_and(|p| p.requires(Scope::Lock));
+    if let Some(policy) = policy {
+        verify_state_locks(state_dir, policy)?;
+    }
+    if dry_run {
+        return Ok(None);
+    }
+    let configured = std::env::var("FORJAR_SIGNING_KEY").is_ok_and(|k| !k.is_empty());
+    if !required && !configured {
+        return Ok(None);
+    }
+    let key = resolve_signing_key(None)
+        .map_err(|e| format!("trust policy requires signed locks: {e}"))?;
+    if let Some(policy) = policy.filter(|_| required) {
+        let public = key.public_key()?;
+        if policy.trusted_signer(Scope::Lock, &public).is_none() {
+            return Err(format!(
+                "signing key '{}' ({public}) is not a trusted lock signer",
+                key.name
+            ));
+        }
+    }
+    Ok(Some(key))
+}
+
+/// [`prepare_lock_signing`], then register the key so every lock saved to
+/// `state_dir` is signed as it is written.
+pub fn attach_lock_signing(
+    policy: Option<&TrustPolicy>,
+    state_dir: &Path,
+    dry_run: bool,
+) -> Result<(), String> {
+    if let Some(key) = prepare_lock_signing(policy, state_dir, dry_run)? {
+        register_lock_signer(state_dir, key);
+    }
+    Ok(())
+}
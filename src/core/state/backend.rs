@@ -0,0 +1,453 @@
+//! FJ-1425: Pluggable state storage.
+//!
+//! Lock files, their `.b3` sidecars, apply reports, event logs and
+//! generations are read and written through a [`StateBackend`], addressed by
+//! `/`-separated keys relative to the state directory
+//! (`web/state.lock.yaml`). The local filesystem is the default. A
+//! `policy.backend` block registers a remote store for a state directory
+//! (see [`attach`]); the directory then becomes a working copy of it, so
+//! commands that only read local files keep working.
+
+use crate::core::types::StateBackendConfig;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Prefix of the error a conditional write returns when the stored object
+/// changed underneath it.
+pub const CONFLICT: &str = "precondition failed";
+
+/// Whether an error from [`StateBackend::put_if`] is a lost race rather
+/// than an I/O failure.
+pub fn is_conflict(err: &str) -> bool {
+    err.starts_with(CONFLICT)
+}
+
+/// Condition for [`StateBackend::put_if`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precondition<'a> {
+    /// Write unconditionally.
+    Any,
+    /// Only create: fail if the key exists.
+    Absent,
+    /// Only replace this version (as returned by `get_versioned`/`put_if`).
+    Matches(&'a str),
+}
+
+/// State backend operations.
+pub trait StateBackend: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Content and version of `key`, or `None` when it does not exist.
+    fn get_versioned(&self, key: &str) -> Result<Option<(Vec<u8>, String)>, String>;
+
+    /// Write `key` if `cond` holds and return the new version. A failed
+    /// condition is reported as an error starting with [`CONFLICT`].
+    fn put_if(&self, key: &str, data: &[u8], cond: Precondition<'_>) -> Result<String, String>;
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, String>;
+    fn delete(&self, key: &str) -> Result<(), String>;
+
+    fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        self.get_versioned(key)?
+            .map(|(data, _)| data)
+            .ok_or_else(|| format!("{} backend: {key} not found", self.name()))
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        self.put_if(key, data, Precondition::Any).map(|_| ())
+    }
+
+    fn exists(&self, key: &str) -> Result<bool, String> {
+        Ok(self.get_versioned(key)?.is_some())
+    }
+
+    /// Append `data` to `key`. The default is a read-modify-write retried
+    /// on conflicts, so concurrent appenders never drop each other's lines.
+    fn append(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        for _ in 0..8 {
+            let current = self.get_versioned(key)?;
+            let (mut content, cond) = match current {
+                Some((content, version)) => (content, Some(version)),
+                None => (Vec::new(), None),
+            };
+            content.extend_from_slice(data);
+            let cond = match cond {
+                Some(ref version) => Precondition::Matches(version),
+                None => Precondition::Absent,
+            };
+            match self.put_if(key, &content, cond) {
+                Ok(_) => return Ok(()),
+                Err(e) if is_conflict(&e) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(format!("{CONFLICT}: {key} kept changing while appending"))
+    }
+}
+
+/// Name of the file [`LocalBackend`] locks to serialize writes. It is not
+/// a state key and is never listed.
+const WRITE_LOCK: &str = ".forjar-backend.lock";
+
+/// Local filesystem state backend (default).
+///
+/// Writes go through a temp file and a rename; versions are BLAKE3 hashes
+/// of the content. Creating a key links the temp file into place, which
+/// fails if the key exists; replacing one checks its version and renames
+/// while holding an exclusive lock on the root, so conditional writes are
+/// atomic between processes and hosts sharing the directory.
+pub struct LocalBackend {
+    root: PathBuf,
+}
+
+impl LocalBackend {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+        }
+    }
+
+    /// Path of `key` under the root. Keys are relative and may not climb
+    /// out of it, so a remote store's listing can't write elsewhere.
+    fn path(&self, key: &str) -> Result<PathBuf, String> {
+        let rel = Path::new(key);
+        let contained = rel
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)));
+        if key.is_empty() || !contained {
+            return Err(format!("invalid state key '{key}'"));
+        }
+        Ok(self.root.join(rel))
+    }
+
+    /// Hold an exclusive lock on the root until the returned file drops.
+    fn write_lock(&self) -> Result<std::fs::File, String> {
+        std::fs::create_dir_all(&self.root)
+            .map_err(|e| format!("mkdir {}: {e}", self.root.display()))?;
+        let path = self.root.join(WRITE_LOCK);
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| format!("open {}: {e}", path.display()))?;
+        file.lock()
+            .map_err(|e| format!("lock {}: {e}", path.display()))?;
+        Ok(file)
+    }
+}
+
+/// A temp file next to `p`, unique to this write.
+fn temp_path(p: &Path) -> PathBuf {
+    static SEQ: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let seq = SEQ.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let mut tmp = p.as_os_str().to_owned();
+    tmp.push(format!(".{}-{seq}.tmp", std::process::id()));
+    PathBuf::from(tmp)
+}
+
+fn content_version(data: &[u8]) -> String {
+    blake3::hash(data).to_hex().to_string()
+}
+
+impl StateBackend for LocalBackend {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn get_versioned(&self, key: &str) -> Result<Option<(Vec<u8>, String)>, String> {
+        let p = self.path(key)?;
+        if !p.exists() {
+            return Ok(None);
+        }
+        let data = std::fs::read(&p).map_err(|e| format!("read {}: {e}", p.display()))?;
+        let version = content_version(&data);
+        Ok(Some((data, version)))
+    }
+
+    fn put_if(&self, key: &str, data: &[u8], cond: Precondition<'_>) -> Result<String, String> {
+        let p = self.path(key)?;
+        if let Some(parent) = p.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("mkdir {}: {e}", parent.display()))?;
+        }
+        let tmp = temp_path(&p);
+        std::fs::write(&tmp, data).map_err(|e| format!("write {}: {e}", tmp.display()))?;
+        let result = match cond {
+            // A hard link never replaces an existing file.
+            Precondition::Absent => match std::fs::hard_link(&tmp, &p) {
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    Err(format!("{CONFLICT}: {key} already exists"))
+                }
+                r => r.map_err(|e| format!("link {} → {}: {e}", tmp.display(), p.display())),
+            },
+            Precondition::Any | Precondition::Matches(_) => self.write_lock().and_then(|_lock| {
+                if let Precondition::Matches(version) = cond {
+                    let current = self.get_versioned(key)?.map(|(_, v)| v);
+                    if current.as_deref() != Some(version) {
+                        return Err(format!("{CONFLICT}: {key} was modified"));
+                    }
+                }
+                std::fs::rename(&tmp, &p)
+                    .map_err(|e| format!("rename {} → {}: {e}", tmp.display(), p.display()))
+            }),
+        };
+        let _ = std::fs::remove_file(&tmp);
+        result.map(|()| content_version(data))
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, String> {
+        let dir = self.root.join(prefix);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        collect_files(&dir, &self.root)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        let p = self.path(key)?;
+        if p.exists() {
+            let _lock = self.write_lock()?;
+            std::fs::remove_file(&p).map_err(|e| format!("delete {}: {e}", p.display()))?;
+        }
+        Ok(())
+    }
+
+    fn exists(&self, key: &str) -> Result<bool, String> {
+        Ok(self.path(key)?.exists())
+    }
+
+    fn append(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        use std::io::Write;
+        let p = self.path(key)?;
+        if let Some(parent) = p.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("mkdir {}: {e}", parent.display()))?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&p)
+            .map_err(|e| format!("open {}: {e}", p.display()))?;
+        file.write_all(data)
+            .map_err(|e| format!("write {}: {e}", p.display()))
+    }
+}
+
+/// Files below `dir` as `/`-separated keys relative to `root`. Symlinks
+/// (the generations `current` link) are not followed.
+fn collect_files(dir: &Path, root: &Path) -> Result<Vec<String>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("readdir {}: {e}", dir.display()))?;
+    let mut result = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_file() && entry.file_name() != WRITE_LOCK {
+            if let Ok(rel) = path.strip_prefix(root) {
+                let key: Vec<String> = rel
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect();
+                result.push(key.join("/"));
+            }
+        } else if file_type.is_dir() {
+            result.extend(collect_files(&path, root)?);
+        }
+    }
+    result.sort();
+    Ok(result)
+}
+
+/// A remote backend with the state directory as its working copy: reads
+/// and writes go to the remote store first and are then mirrored locally.
+///
+/// Plain [`StateBackend::put`]s are optimistic: a key last seen at version
+/// `v` is only replaced while the store still holds `v`, and a key seen
+/// missing is only created if nobody else created it meanwhile. Another
+/// writer getting there first surfaces as a [`CONFLICT`] error instead of
+/// a silently lost update.
+pub struct MirroredBackend {
+    remote: Box<dyn StateBackend>,
+    local: LocalBackend,
+    /// Last version seen per key (`None`: seen missing).
+    seen: Mutex<HashMap<String, Option<String>>>,
+}
+
+impl MirroredBackend {
+    pub fn new(remote: Box<dyn StateBackend>, state_dir: &Path) -> Self {
+        Self {
+            remote,
+            local: LocalBackend::new(state_dir),
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn observe(&self, key: &str, version: Option<String>) {
+        if let Ok(mut seen) = self.seen.lock() {
+            seen.insert(key.to_string(), version);
+        }
+    }
+
+    /// Copy every remote key into the working copy. Returns the key count.
+    pub fn pull(&self) -> Result<usize, String> {
+        let keys = self.remote.list("")?;
+        for key in &keys {
+            self.get_versioned(key)?;
+        }
+        Ok(keys.len())
+    }
+}
+
+impl StateBackend for MirroredBackend {
+    fn name(&self) -> &str {
+        self.remote.name()
+    }
+
+    fn get_versioned(&self, key: &str) -> Result<Option<(Vec<u8>, String)>, String> {
+        let found = self.remote.get_versioned(key)?;
+        match found {
+            Some((ref data, _)) => self.local.put(key, data)?,
+            None => self.local.delete(key)?,
+        }
+        self.observe(key, found.as_ref().map(|(_, v)| v.clone()));
+        Ok(found)
+    }
+
+    fn put_if(&self, key: &str, data: &[u8], cond: Precondition<'_>) -> Result<String, String> {
+        let version = self.remote.put_if(key, data, cond)?;
+        self.local.put(key, data)?;
+        self.observe(key, Some(version.clone()));
+        Ok(version)
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        let seen = self.seen.lock().ok().and_then(|s| s.get(key).cloned());
+        let cond = match seen {
+            Some(Some(ref version)) => Precondition::Matches(version),
+            Some(None) => Precondition::Absent,
+            None => Precondition::Any,
+        };
+        self.put_if(key, data, cond).map(|_| ())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, String> {
+        self.remote.list(prefix)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        self.remote.delete(key)?;
+        self.observe(key, None);
+        self.local.delete(key)
+    }
+
+    fn exists(&self, key: &str) -> Result<bool, String> {
+        self.remote.exists(key)
+    }
+
+    fn append(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        self.remote.append(key, data)?;
+        self.local.append(key, data)
+    }
+}
+
+type Registry = Mutex<HashMap<PathBuf, Arc<dyn StateBackend>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Route all state I/O for `state_dir` through `backend`.
+pub fn register_backend(state_dir: &Path, backend: Arc<dyn StateBackend>) {
+    if let Ok(mut map) = registry().lock() {
+        map.insert(state_dir.to_path_buf(), backend);
+    }
+}
+
+/// Go back to the local filesystem for `state_dir`.
+pub fn unregister_backend(state_dir: &Path) {
+    if let Ok(mut map) = registry().lock() {
+        map.remove(state_dir);
+    }
+}
+
+/// The backend serving `state_dir`: the registered one, or the local
+/// filesystem.
+pub fn backend_for(state_dir: &Path) -> Arc<dyn StateBackend> {
+    registry()
+        .lock()
+        .ok()
+        .and_then(|map| map.get(state_dir).cloned())
+        .unwrap_or_else(|| Arc::new(LocalBackend::new(state_dir)))
+}
+
+/// The backend serving the file at `path` and its key there. Paths inside
+/// a registered state directory go to its backend; anything else is a
+/// local file keyed by its name.
+pub fn locate(path: &Path) -> (Arc<dyn StateBackend>, String) {
+    let registered = registry().lock().ok().and_then(|map| {
+        map.iter()
+            .find(|(dir, _)| path.starts_with(dir))
+            .map(|(dir, backend)| (dir.clone(), backend.clone()))
+    });
+    if let Some((dir, backend)) = registered {
+        if let Some(key) = key_for(&dir, path) {
+            return (backend, key);
+        }
+    }
+    let parent = path.parent().unwrap_or(Path::new("."));
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    (Arc::new(LocalBackend::new(parent)), name)
+}
+
+/// Build the backend described by a `policy.backend` block.
+pub fn from_config(config: &StateBackendConfig) -> Result<Box<dyn StateBackend>, String> {
+    match config.kind.as_str() {
+        "local" => Ok(Box::new(LocalBackend::new(Path::new(
+            config.path.as_deref().unwrap_or("state"),
+        )))),
+        "s3" => Ok(Box::new(super::s3::S3Backend::from_config(config)?)),
+        other => Err(format!(
+            "unknown state backend type '{other}' (expected: local, s3)"
+        )),
+    }
+}
+
+/// Register the configured backend for `state_dir` without touching the
+/// working copy. `None` without a `policy.backend` block.
+pub fn connect(
+    config: Option<&StateBackendConfig>,
+    state_dir: &Path,
+) -> Result<Option<Arc<MirroredBackend>>, String> {
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    let mirrored = Arc::new(MirroredBackend::new(from_config(config)?, state_dir));
+    register_backend(state_dir, mirrored.clone());
+    Ok(Some(mirrored))
+}
+
+/// Register the configured backend for `state_dir` and pull its contents
+/// into the working copy. A no-op without a `policy.backend` block.
+pub fn attach(config: Option<&StateBackendConfig>, state_dir: &Path) -> Result<(), String> {
+    if let Some(mirrored) = connect(config, state_dir)? {
+        mirrored.pull()?;
+    }
+    Ok(())
+}
+
+/// Key of `path` relative to `state_dir`, if it lies inside it.
+pub fn key_for(state_dir: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(state_dir).ok()?;
+    let parts: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    Some(parts.join("/"))
+}
@@ -2,16 +2,21 @@
 //!
 //! After saving lock files, a `.b3` sidecar is written with the BLAKE3 hash.
 //! Before apply, `verify_state_integrity()` checks that lock files match their sidecars.
+//! Both are read and written through the state directory's backend, so a
+//! remote store is verified against its own sidecars, not the working copy.
 
 use std::path::{Path, PathBuf};
 
 /// Compute BLAKE3 hash of file contents and write to `.b3` sidecar.
 pub fn write_b3_sidecar(lock_path: &Path) -> Result<(), String> {
-    let content = std::fs::read(lock_path)
+    let (backend, key) = super::backend::locate(lock_path);
+    let content = backend
+        .get(&key)
         .map_err(|e| format!("cannot read {}: {}", lock_path.display(), e))?;
     let hash = blake3::hash(&content);
     let sidecar = sidecar_path(lock_path);
-    std::fs::write(&sidecar, hash.to_hex().as_str())
+    backend
+        .put(&format!("{key}.b3"), hash.to_hex().as_bytes())
         .map_err(|e| format!("cannot write {}: {}", sidecar.display(), e))?;
     Ok(())
 }
@@ -71,46 +76,43 @@ pub fn verify_state_integrity(state_dir: &Path) -> Vec<IntegrityResult> {
 }
 
 /// Check a single lock file for integrity.
+///
+/// The sidecar covers the bytes as stored, so a lock encrypted to
+/// recipients is verified as ciphertext; its YAML is only checked once it
+/// is decrypted on load.
 fn check_file(lock_path: &Path) -> Vec<IntegrityResult> {
     let mut results = Vec::new();
+    let (backend, key) = super::backend::locate(lock_path);
 
-    // Verify YAML is valid
-    let content = match std::fs::read_to_string(lock_path) {
+    let content = match backend.get(&key) {
         Ok(c) => c,
         Err(e) => {
-            results.push(IntegrityResult::InvalidYaml(
-                lock_path.to_path_buf(),
-                e.to_string(),
-            ));
+            results.push(IntegrityResult::InvalidYaml(lock_path.to_path_buf(), e));
             return results;
         }
     };
 
-    if let Err(e) = serde_yaml_ng::from_str::<serde_yaml_ng::Value>(&content) {
-        results.push(IntegrityResult::InvalidYaml(
-            lock_path.to_path_buf(),
-            e.to_string(),
-        ));
-        return results;
+    // Verify YAML is valid
+    if !crate::core::state_recipients::is_age(&content) {
+        let yaml = std::str::from_utf8(&content).map_err(|e| e.to_string());
+        if let Err(e) = yaml.and_then(|y| {
+            serde_yaml_ng::from_str::<serde_yaml_ng::Value>(y).map_err(|e| e.to_string())
+        }) {
+            results.push(IntegrityResult::InvalidYaml(lock_path.to_path_buf(), e));
+            return results;
+        }
     }
 
     // Check BLAKE3 sidecar
-    let sidecar = sidecar_path(lock_path);
-    if !sidecar.exists() {
-        results.push(IntegrityResult::MissingSidecar(lock_path.to_path_buf()));
-        return results;
-    }
-
-    let expected_hash = match std::fs::read_to_string(&sidecar) {
-        Ok(h) => h.trim().to_string(),
-        Err(_) => {
+    let expected_hash = match backend.get_versioned(&format!("{key}.b3")) {
+        Ok(Some((h, _))) => String::from_utf8_lossy(&h).trim().to_string(),
+        _ => {
             results.push(IntegrityResult::MissingSidecar(lock_path.to_path_buf()));
             return results;
         }
     };
 
-    let content_bytes = content.into_bytes();
-    let actual_hash = blake3::hash(&content_bytes).to_hex().to_string();
+    let actual_hash = blake3::hash(&content).to_hex().to_string();
 
     if expected_hash != actual_hash {
         results.push(IntegrityResult::HashMismatch {
@@ -0,0 +1,346 @@
+//! FJ-266: Apply lock as a lease in the state backend.
+//!
+//! The lock lives at `.forjar.lock` in whatever backend serves the state
+//! directory, so two hosts applying against shared state exclude each other,
+//! not just two processes on one host. A lease records who holds it, why and
+//! until when; the holder renews it in the background while the apply runs,
+//! and a lease that is not renewed expires after its TTL. Taking over an
+//! expired lease and renewing a held one are conditional writes, so two
+//! contenders can never both win. A holder whose renewal fails has lost the
+//! lease; [`check_lease`] then fails so the apply stops before its next
+//! resource.
+//!
+//! `force_unlock` breaks a lease regardless of its holder and appends an
+//! audit entry to `lock-audit.jsonl`.
+
+use super::backend::{backend_for, is_conflict, Precondition};
+use crate::tripwire::eventlog::{iso8601_from_secs, now_iso8601};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Key of the apply lock within the state directory.
+pub const LEASE_KEY: &str = ".forjar.lock";
+
+/// Key of the force-unlock audit log within the state directory.
+pub const AUDIT_KEY: &str = "lock-audit.jsonl";
+
+/// Lease length when `policy.lock_ttl` is not set.
+pub const DEFAULT_LEASE_TTL_SECS: u64 = 300;
+
+/// Holder of the apply lock. Older lock files carry only `pid` and
+/// `started_at`; they are treated as unexpiring leases on this host.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApplyLease {
+    /// Process holding the lock
+    pub pid: u32,
+    /// Operator identity (`user@host`)
+    #[serde(default)]
+    pub holder: String,
+    /// Host the holding process runs on
+    #[serde(default)]
+    pub host: String,
+    /// Identifies this acquisition; renewals and releases check it
+    #[serde(default)]
+    pub lease_id: String,
+    /// Why the lock was taken (`--lock-reason`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// When the lock was acquired
+    #[serde(alias = "started_at")]
+    pub acquired_at: String,
+    /// Last renewal
+    #[serde(default)]
+    pub renewed_at: String,
+    /// When the lease lapses unless renewed (empty: never)
+    #[serde(default)]
+    pub expires_at: String,
+    /// Lease length in seconds
+    #[serde(default)]
+    pub ttl_secs: u64,
+}
+
+impl ApplyLease {
+    /// Whether the lease has lapsed at `now` (an ISO 8601 timestamp).
+    pub fn is_expired(&self, now: &str) -> bool {
+        !self.expires_at.is_empty() && self.expires_at.as_str() <= now
+    }
+
+    /// One-line description: holder, PID and since when.
+    pub fn describe(&self) -> String {
+        let holder = if self.holder.is_empty() {
+            "unknown holder".to_string()
+        } else {
+            self.holder.clone()
+        };
+        let mut out = format!("PID {} ({holder}) since {}", self.pid, self.acquired_at);
+        if let Some(ref reason) = self.reason {
+            out.push_str(&format!(": {reason}"));
+        }
+        if !self.expires_at.is_empty() {
+            out.push_str(&format!(", lease expires {}", self.expires_at));
+        }
+        out
+    }
+
+    /// Whether the holding process is known to be gone: it ran on this
+    /// host and its PID no longer exists.
+    fn holder_is_dead(&self, host: &str) -> bool {
+        (self.host.is_empty() || self.host == host) && !super::is_pid_running(self.pid)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn new_lease_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("l-{:x}-{}", nanos & 0xFFFF_FFFF_FFFF, std::process::id())
+}
+
+fn local_identity() -> (String, String) {
+    let holder = crate::core::types::OperatorIdentity::from_env().name;
+    let host = holder
+        .rsplit_once('@')
+        .map(|(_, h)| h.to_string())
+        .unwrap_or_default();
+    (holder, host)
+}
+
+/// Current lease and its backend version, if the lock is held.
+pub fn read_lease(state_dir: &Path) -> Result<Option<(ApplyLease, String)>, String> {
+    let Some((bytes, version)) = backend_for(state_dir)
+        .get_versioned(LEASE_KEY)
+        .map_err(|e| format!("cannot read lock file: {e}"))?
+    else {
+        return Ok(None);
+    };
+    let content = String::from_utf8_lossy(&bytes);
+    match serde_yaml_ng::from_str::<ApplyLease>(&content) {
+        Ok(lease) => Ok(Some((lease, version))),
+        // Unreadable lock content holds nothing; keep the PID if there is one.
+        Err(_) => Ok(super::parse_lock_pid(&content).map(|pid| {
+            (
+                ApplyLease {
+                    pid,
+                    holder: String::new(),
+                    host: String::new(),
+                    lease_id: String::new(),
+                    reason: None,
+                    acquired_at: String::new(),
+                    renewed_at: String::new(),
+                    expires_at: String::new(),
+                    ttl_secs: 0,
+                },
+                version,
+            )
+        })),
+    }
+}
+
+fn write_lease(
+    state_dir: &Path,
+    lease: &ApplyLease,
+    cond: Precondition<'_>,
+) -> Result<String, String> {
+    let yaml = serde_yaml_ng::to_string(lease).map_err(|e| format!("serialize error: {e}"))?;
+    backend_for(state_dir).put_if(LEASE_KEY, yaml.as_bytes(), cond)
+}
+
+/// Take the apply lock for `ttl_secs`. Fails while another holder's lease
+/// is live; expired leases and leases of dead local processes are taken
+/// over.
+pub fn acquire_lease(
+    state_dir: &Path,
+    reason: Option<&str>,
+    ttl_secs: u64,
+) -> Result<ApplyLease, String> {
+    let (holder, host) = local_identity();
+    let now = now_secs();
+    let now_iso = iso8601_from_secs(now);
+    let current = read_lease(state_dir)?;
+    let cond_version = match current {
+        Some((ref lease, _)) if !lease.is_expired(&now_iso) && !lease.holder_is_dead(&host) => {
+            return Err(format!(
+                "state directory is locked by {}. \
+                 See `forjar lock-info`; if this is stale, run: forjar apply --force-unlock",
+                lease.describe()
+            ));
+        }
+        Some((_, ref version)) => Some(version.clone()),
+        None => None,
+    };
+    let lease = ApplyLease {
+        pid: std::process::id(),
+        holder,
+        host,
+        lease_id: new_lease_id(),
+        reason: reason.map(str::to_string),
+        acquired_at: now_iso.clone(),
+        renewed_at: now_iso,
+        expires_at: iso8601_from_secs(now + ttl_secs),
+        ttl_secs,
+    };
+    let cond = match cond_version {
+        Some(ref version) => Precondition::Matches(version),
+        None => Precondition::Absent,
+    };
+    match write_lease(state_dir, &lease, cond) {
+        Ok(_) => Ok(lease),
+        Err(e) if is_conflict(&e) => {
+            let winner = read_lease(state_dir)?
+                .map(|(l, _)| l.describe())
+                .unwrap_or_else(|| "another apply".to_string());
+            Err(format!("state directory is locked by {winner}"))
+        }
+        Err(e) => Err(format!("cannot write lock file: {e}")),
+    }
+}
+
+/// Extend `lease` by its TTL. Fails if it is no longer the current lease
+/// (it expired and was taken over, or was force-unlocked).
+pub fn renew_lease(state_dir: &Path, lease: &mut ApplyLease) -> Result<(), String> {
+    let Some((current, version)) = read_lease(state_dir)? else {
+        return Err("apply lock was removed".to_string());
+    };
+    if current.lease_id != lease.lease_id {
+        return Err(format!(
+            "apply lock was taken over by {}",
+            current.describe()
+        ));
+    }
+    let now = now_secs();
+    let mut renewed = lease.clone();
+    renewed.renewed_at = iso8601_from_secs(now);
+    renewed.expires_at = iso8601_from_secs(now + lease.ttl_secs);
+    write_lease(state_dir, &renewed, Precondition::Matches(&version))
+        .map_err(|e| format!("cannot renew apply lock: {e}"))?;
+    *lease = renewed;
+    Ok(())
+}
+
+/// Drop the lock if `lease_id` still holds it.
+pub fn release_lease(state_dir: &Path, lease_id: &str) -> Result<(), String> {
+    match read_lease(state_dir)? {
+        Some((current, _)) if current.lease_id == lease_id => backend_for(state_dir)
+            .delete(LEASE_KEY)
+            .map_err(|e| format!("cannot remove lock file: {e}")),
+        _ => Ok(()),
+    }
+}
+
+/// Break the lock whoever holds it and record who did it in
+/// `lock-audit.jsonl`. Returns the broken lease.
+pub fn force_unlock_lease(state_dir: &Path) -> Result<Option<ApplyLease>, String> {
+    let Some((lease, _)) = read_lease(state_dir)? else {
+        return Ok(None);
+    };
+    let backend = backend_for(state_dir);
+    let entry = serde_json::json!({
+        "ts": now_iso8601(),
+        "action": "force_unlock",
+        "by": local_identity().0,
+        "pid": std::process::id(),
+        "previous": lease,
+    });
+    backend
+        .append(AUDIT_KEY, format!("{entry}\n").as_bytes())
+        .map_err(|e| format!("cannot write lock audit log: {e}"))?;
+    backend
+        .delete(LEASE_KEY)
+        .map_err(|e| format!("cannot remove lock file: {e}"))?;
+    Ok(Some(lease))
+}
+
+/// Recorded force-unlocks, oldest first.
+pub fn audit_entries(state_dir: &Path) -> Vec<serde_json::Value> {
+    let Ok(Some((bytes, _))) = backend_for(state_dir).get_versioned(AUDIT_KEY) else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&bytes)
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+// ── Background renewal ─────────────────────────────────────────────
+
+struct Renewer {
+    lease_id: String,
+    stop: mpsc::Sender<()>,
+    thread: JoinHandle<()>,
+    /// Why renewal stopped, once it failed.
+    lost: Arc<Mutex<Option<String>>>,
+}
+
+type Renewers = Mutex<HashMap<PathBuf, Renewer>>;
+
+fn renewers() -> &'static Renewers {
+    static RENEWERS: OnceLock<Renewers> = OnceLock::new();
+    RENEWERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Renew `lease` every third of its TTL until [`stop_renewing`].
+pub fn start_renewing(state_dir: &Path, lease: ApplyLease) {
+    let (stop, stopped) = mpsc::channel::<()>();
+    let dir = state_dir.to_path_buf();
+    let lease_id = lease.lease_id.clone();
+    let interval = Duration::from_secs((lease.ttl_secs / 3).max(1));
+    let lost = Arc::new(Mutex::new(None));
+    let reason = lost.clone();
+    let thread = std::thread::spawn(move || {
+        let mut lease = lease;
+        while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+            if let Err(e) = renew_lease(&dir, &mut lease) {
+                eprintln!("error: {e}; the apply stops before its next resource");
+                if let Ok(mut reason) = reason.lock() {
+                    *reason = Some(e);
+                }
+                return;
+            }
+        }
+    });
+    if let Ok(mut map) = renewers().lock() {
+        map.insert(
+            state_dir.to_path_buf(),
+            Renewer {
+                lease_id,
+                stop,
+                thread,
+                lost,
+            },
+        );
+    }
+}
+
+/// Fail once the lease being renewed for `state_dir` was lost, so the
+/// apply holding it stops instead of running unlocked.
+pub fn check_lease(state_dir: &Path) -> Result<(), String> {
+    let lost = renewers()
+        .lock()
+        .ok()
+        .and_then(|map| map.get(state_dir).map(|r| r.lost.clone()));
+    let reason = lost.and_then(|l| l.lock().ok().and_then(|r| r.clone()));
+    match reason {
+        Some(e) => Err(format!("apply lock lost ({e}); aborting apply")),
+        None => Ok(()),
+    }
+}
+
+/// Stop renewing the lease held on `state_dir`; returns its lease id.
+pub fn stop_renewing(state_dir: &Path) -> Option<String> {
+    let renewer = renewers().lock().ok()?.remove(state_dir)?;
+    let _ = renewer.stop.send(());
+    let _ = renewer.thread.join();
+    Some(renewer.lease_id)
+}
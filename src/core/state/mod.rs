@@ -1,9 +1,12 @@
 //! FJ-013: Lock file management — load, save (atomic), path derivation.
 
+pub mod backend;
 pub mod ephemeral;
 pub mod integrity;
+pub mod lease;
 pub mod reconstruct;
 pub mod rulebook_log;
+pub mod s3;
 
 use super::types::{ApplyResult, GlobalLock, MachineSummary, StateLock};
 use provable_contracts_macros::contract;
@@ -14,45 +17,47 @@ pub fn lock_file_path(state_dir: &Path, machine: &str) -> PathBuf {
     state_dir.join(machine).join("state.lock.yaml")
 }
 
-/// Load a lock file for a machine. Returns None if the file doesn't exist.
-pub fn load_lock(state_dir: &Path, machine: &str) -> Result<Option<StateLock>, String> {
-    let path = lock_file_path(state_dir, machine);
-    if !path.exists() {
+/// Read `key` through the state directory's backend, decrypting it if it
+/// was encrypted to recipients (FJ-3310). `None` if it doesn't exist.
+fn read_state_file(state_dir: &Path, key: &str) -> Result<Option<String>, String> {
+    let path = state_dir.join(key);
+    let Some((bytes, _)) = backend::backend_for(state_dir)
+        .get_versioned(key)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?
+    else {
         return Ok(None);
-    }
-    let content = std::fs::read_to_string(&path)
+    };
+    let bytes = super::state_recipients::open_state(state_dir, key, bytes)
         .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
-    let lock: StateLock = serde_yaml_ng::from_str(&content)
-        .map_err(|e| format!("invalid lock file {}: {}", path.display(), e))?;
-    Ok(Some(lock))
+    String::from_utf8(bytes)
+        .map(Some)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))
 }
 
-/// Save a lock file atomically (write to temp, then rename).
-#[contract("execution-safety-v1", equation = "atomic_write")]
-pub fn save_lock(state_dir: &Path, lock: &StateLock) -> Result<(), String> {
-    // Contract: execution-safety-v1.yaml precondition (pv codegen)
-    contract_pre_atomic_write!(state_dir);
-    let path = lock_file_path(state_dir, &lock.machine);
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("cannot create dir {}: {}", parent.display(), e))?;
-    }
-
-    let yaml = serde_yaml_ng::to_string(lock).map_err(|e| format!("serialize error: {e}"))?;
+/// Atomically write `key` through the state directory's backend (encrypted
+/// to its recipients, if any), then its BLAKE3 sidecar.
+fn write_sealed_state_file(state_dir: &Path, key: &str, yaml: &str) -> Result<(), String> {
+    let path = state_dir.join(key);
+    super::state_recipients::put_state(state_dir, key, yaml.as_bytes())
+        .map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+    write_state_sidecar(&path)
+}
 
-    // Write to temp file, then rename for crash-safe persistence
-    let tmp_path = path.with_extension("lock.yaml.tmp");
-    std::fs::write(&tmp_path, &yaml)
-        .map_err(|e| format!("cannot write {}: {}", tmp_path.display(), e))?;
-    std::fs::rename(&tmp_path, &path).map_err(|e| {
-        format!(
-            "cannot rename {} → {}: {}",
-            tmp_path.display(),
-            path.display(),
-            e
-        )
-    })?;
+/// Like [`write_sealed_state_file`], encrypted to exactly `recipients`
+/// (plaintext without them), for commands that change a file's recipients.
+pub fn write_sealed_state_file_to(
+    state_dir: &Path,
+    key: &str,
+    plaintext: &[u8],
+    recipients: Option<&[String]>,
+) -> Result<(), String> {
+    let path = state_dir.join(key);
+    super::state_recipients::put_state_to(state_dir, key, plaintext, recipients)
+        .map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+    write_state_sidecar(&path)
+}
 
+fn write_state_sidecar(path: &Path) -> Result<(), String> {
     // FJ-1270: Write BLAKE3 integrity sidecar.
     // FJ-118 (2026-04-24 fix): was `let _ = …` which silently discarded
     // sidecar-write errors, leaving `state.lock.yaml` updated on disk
@@ -60,7 +65,7 @@ pub fn save_lock(state_dir: &Path, lock: &StateLock) -> Result<(), String> {
     // NEXT apply with "integrity check failed, expected X, got Y" and
     // no signal at the moment of corruption. Propagating the error
     // means sidecar-write failures now fail the apply at the source.
-    integrity::write_b3_sidecar(&path).map_err(|e| {
+    integrity::write_b3_sidecar(path).map_err(|e| {
         format!(
             "sidecar write failed for {}: {} (lock.yaml was saved; \
              recover with `forjar reseal --file {}` or re-run apply)",
@@ -68,18 +73,53 @@ pub fn save_lock(state_dir: &Path, lock: &StateLock) -> Result<(), String> {
             e,
             path.display(),
         )
-    })?;
+    })
+}
+
+/// Load a lock file for a machine. Returns None if the file doesn't exist.
+pub fn load_lock(state_dir: &Path, machine: &str) -> Result<Option<StateLock>, String> {
+    let path = lock_file_path(state_dir, machine);
+    let Some(content) = read_state_file(state_dir, &format!("{machine}/state.lock.yaml"))? else {
+        return Ok(None);
+    };
+    let lock: StateLock = serde_yaml_ng::from_str(&content)
+        .map_err(|e| format!("invalid lock file {}: {}", path.display(), e))?;
+    Ok(Some(lock))
+}
 
-    // FJ-2200: Atomicity postcondition — file exists and temp is gone
+/// Save a lock file atomically (write to temp, then rename).
+#[contract("execution-safety-v1", equation = "atomic_write")]
+pub fn save_lock(state_dir: &Path, lock: &StateLock) -> Result<(), String> {
+    // Contract: execution-safety-v1.yaml precondition (pv codegen)
+    contract_pre_atomic_write!(state_dir);
+    let path = lock_file_path(state_dir, &lock.machine);
+    let yaml = serde_yaml_ng::to_string(lock).map_err(|e| format!("serialize error: {e}"))?;
+
+    // The backend writes to a temp file and renames for crash-safe persistence.
+    write_sealed_state_file(
+        state_dir,
+        &format!("{}/state.lock.yaml", lock.machine),
+        &yaml,
+    )?;
+    // FJ-1432: Sign as the lock is written, not after a successful apply.
+    crate::core::signing::sign_saved_lock(state_dir, &lock.machine)?;
+
+    // FJ-2200: Atomicity postcondition — file exists
     debug_assert!(path.exists(), "save_lock: file does not exist after write");
-    debug_assert!(
-        !tmp_path.exists(),
-        "save_lock: temp file still exists after rename"
-    );
 
     Ok(())
 }
 
+/// Remove a machine's lock file, its integrity sidecar and its signature
+/// through the state directory's backend.
+pub fn remove_lock(state_dir: &Path, machine: &str) -> Result<(), String> {
+    let store = backend::backend_for(state_dir);
+    let key = format!("{machine}/state.lock.yaml");
+    store.delete(&key)?;
+    store.delete(&format!("{key}.b3"))?;
+    store.delete(&crate::core::signing::lock_signature_key(machine))
+}
+
 /// Path to the global lock file.
 pub fn global_lock_path(state_dir: &Path) -> PathBuf {
     state_dir.join("forjar.lock.yaml")
@@ -88,11 +128,9 @@ pub fn global_lock_path(state_dir: &Path) -> PathBuf {
 /// Load the global lock file. Returns None if it doesn't exist.
 pub fn load_global_lock(state_dir: &Path) -> Result<Option<GlobalLock>, String> {
     let path = global_lock_path(state_dir);
-    if !path.exists() {
+    let Some(content) = read_state_file(state_dir, "forjar.lock.yaml")? else {
         return Ok(None);
-    }
-    let content = std::fs::read_to_string(&path)
-        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    };
     let lock: GlobalLock = serde_yaml_ng::from_str(&content)
         .map_err(|e| format!("invalid global lock {}: {}", path.display(), e))?;
     Ok(Some(lock))
@@ -100,42 +138,8 @@ pub fn load_global_lock(state_dir: &Path) -> Result<Option<GlobalLock>, String>
 
 /// Save the global lock file atomically.
 pub fn save_global_lock(state_dir: &Path, lock: &GlobalLock) -> Result<(), String> {
-    std::fs::create_dir_all(state_dir)
-        .map_err(|e| format!("cannot create dir {}: {}", state_dir.display(), e))?;
-
-    let path = global_lock_path(state_dir);
     let yaml = serde_yaml_ng::to_string(lock).map_err(|e| format!("serialize error: {e}"))?;
-
-    let tmp_path = path.with_extension("lock.yaml.tmp");
-    std::fs::write(&tmp_path, &yaml)
-        .map_err(|e| format!("cannot write {}: {}", tmp_path.display(), e))?;
-    std::fs::rename(&tmp_path, &path).map_err(|e| {
-        format!(
-            "cannot rename {} → {}: {}",
-            tmp_path.display(),
-            path.display(),
-            e
-        )
-    })?;
-
-    // FJ-1270: Write BLAKE3 integrity sidecar.
-    // FJ-118 (2026-04-24 fix): was `let _ = …` which silently discarded
-    // sidecar-write errors, leaving `state.lock.yaml` updated on disk
-    // but `.b3` stale or missing — guaranteeing a hard-fail on the
-    // NEXT apply with "integrity check failed, expected X, got Y" and
-    // no signal at the moment of corruption. Propagating the error
-    // means sidecar-write failures now fail the apply at the source.
-    integrity::write_b3_sidecar(&path).map_err(|e| {
-        format!(
-            "sidecar write failed for {}: {} (lock.yaml was saved; \
-             recover with `forjar reseal --file {}` or re-run apply)",
-            path.display(),
-            e,
-            path.display(),
-        )
-    })?;
-
-    Ok(())
+    write_sealed_state_file(state_dir, "forjar.lock.yaml", &yaml)
 }
 
 /// Create a new GlobalLock with machine summaries.
@@ -214,6 +218,66 @@ pub fn persist_outputs(
     save_global_lock(state_dir, &lock)
 }
 
+/// Prefix of stdout lines an apply script uses to export a value.
+///
+/// `FORJAR_OUTPUT public_key=...` is stored under the resource's lock
+/// details and published in the global lock as `<resource_id>.public_key`.
+pub const SCRIPT_OUTPUT_PREFIX: &str = "FORJAR_OUTPUT ";
+
+/// Parse `FORJAR_OUTPUT key=value` lines from apply script stdout.
+pub fn parse_script_outputs(stdout: &str) -> indexmap::IndexMap<String, String> {
+    stdout
+        .lines()
+        .filter_map(|line| line.trim_end().strip_prefix(SCRIPT_OUTPUT_PREFIX))
+        .filter_map(|kv| kv.split_once('='))
+        .filter(|(k, _)| !k.is_empty())
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// Collect script-exported outputs from the machine locks, keyed `<resource_id>.<name>`.
+pub fn resource_outputs<'a>(
+    state_dir: &Path,
+    machines: impl IntoIterator<Item = &'a str>,
+) -> indexmap::IndexMap<String, String> {
+    let mut outputs = indexmap::IndexMap::new();
+    for machine in machines {
+        let Ok(Some(lock)) = load_lock(state_dir, machine) else {
+            continue;
+        };
+        for (id, rl) in &lock.resources {
+            let Some(serde_yaml_ng::Value::Mapping(map)) = rl.details.get("outputs") else {
+                continue;
+            };
+            for (k, v) in map {
+                if let (Some(k), Some(v)) = (k.as_str(), v.as_str()) {
+                    outputs.insert(format!("{id}.{k}"), v.to_string());
+                }
+            }
+        }
+    }
+    outputs
+}
+
+/// Merge script-exported outputs into the global lock.
+///
+/// These values are public by construction (e.g. WireGuard public keys),
+/// so they are never redacted, even with `secrets.ephemeral`.
+pub fn persist_resource_outputs(
+    state_dir: &Path,
+    config_name: &str,
+    outputs: &indexmap::IndexMap<String, String>,
+) -> Result<(), String> {
+    if outputs.is_empty() {
+        return Ok(());
+    }
+    let mut lock = load_global_lock(state_dir)?.unwrap_or_else(|| new_global_lock(config_name));
+    for (k, v) in outputs {
+        lock.outputs.insert(k.clone(), v.clone());
+    }
+    save_global_lock(state_dir, &lock)
+}
+
 /// Create a new empty StateLock for a machine.
 pub fn new_lock(machine: &str, hostname: &str) -> StateLock {
     use crate::tripwire::eventlog::now_iso8601;
@@ -230,85 +294,65 @@ pub fn new_lock(machine: &str, hostname: &str) -> StateLock {
 
 /// FJ-262: Save per-machine apply report to `state/<machine>/last-apply.yaml`.
 pub fn save_apply_report(state_dir: &Path, result: &ApplyResult) -> Result<(), String> {
-    let dir = state_dir.join(&result.machine);
-    std::fs::create_dir_all(&dir)
-        .map_err(|e| format!("cannot create dir {}: {}", dir.display(), e))?;
-    let path = dir.join("last-apply.yaml");
+    let key = format!("{}/last-apply.yaml", result.machine);
     let yaml =
         serde_yaml_ng::to_string(result).map_err(|e| format!("serialize report error: {e}"))?;
-    std::fs::write(&path, &yaml).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
-    Ok(())
+    backend::backend_for(state_dir)
+        .put(&key, yaml.as_bytes())
+        .map_err(|e| format!("cannot write {}: {}", state_dir.join(&key).display(), e))
 }
 
 /// FJ-262: Load last apply report for a machine.
 pub fn load_apply_report(state_dir: &Path, machine: &str) -> Result<Option<String>, String> {
-    let path = state_dir.join(machine).join("last-apply.yaml");
-    if !path.exists() {
-        return Ok(None);
-    }
-    std::fs::read_to_string(&path)
-        .map(Some)
-        .map_err(|e| format!("cannot read {}: {}", path.display(), e))
+    read_state_file(state_dir, &format!("{machine}/last-apply.yaml"))
 }
 
 // ============================================================================
 // FJ-266: State locking — prevent concurrent applies
 // ============================================================================
 
-/// Path to the process lock file.
-pub(super) fn process_lock_path(state_dir: &Path) -> PathBuf {
-    state_dir.join(".forjar.lock")
+/// Path to the process lock file (the working copy of the lease).
+pub fn process_lock_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(lease::LEASE_KEY)
 }
 
 /// Acquire an exclusive process lock. Returns an error if another apply is running.
 /// Stale locks (PID no longer running) are automatically removed.
 pub fn acquire_process_lock(state_dir: &Path) -> Result<(), String> {
-    std::fs::create_dir_all(state_dir).map_err(|e| format!("cannot create state dir: {e}"))?;
-
-    let lock_path = process_lock_path(state_dir);
-
-    // Check for existing lock
-    if lock_path.exists() {
-        let content = std::fs::read_to_string(&lock_path)
-            .map_err(|e| format!("cannot read lock file: {e}"))?;
-        if let Some(pid) = parse_lock_pid(&content) {
-            if is_pid_running(pid) {
-                return Err(format!(
-                    "state directory is locked by PID {} ({}). \
-                     If this is stale, run: forjar apply --force-unlock",
-                    pid,
-                    lock_path.display()
-                ));
-            }
-            // Stale lock — PID no longer running, remove it
-        }
-        let _ = std::fs::remove_file(&lock_path);
-    }
+    acquire_apply_lock(state_dir, None, lease::DEFAULT_LEASE_TTL_SECS)
+}
 
-    // Write our PID
-    let pid = std::process::id();
-    let content = format!(
-        "pid: {}\nstarted_at: {}\n",
-        pid,
-        crate::tripwire::eventlog::now_iso8601()
-    );
-    std::fs::write(&lock_path, content).map_err(|e| format!("cannot write lock file: {e}"))?;
+/// Acquire the apply lock as a lease of `ttl_secs`, renewed in the
+/// background until [`release_process_lock`].
+pub fn acquire_apply_lock(
+    state_dir: &Path,
+    reason: Option<&str>,
+    ttl_secs: u64,
+) -> Result<(), String> {
+    std::fs::create_dir_all(state_dir).map_err(|e| format!("cannot create state dir: {e}"))?;
+    let lease = lease::acquire_lease(state_dir, reason, ttl_secs)?;
+    lease::start_renewing(state_dir, lease);
     Ok(())
 }
 
 /// Release the process lock.
 pub fn release_process_lock(state_dir: &Path) {
-    let lock_path = process_lock_path(state_dir);
-    let _ = std::fs::remove_file(&lock_path);
+    let lease_id = lease::stop_renewing(state_dir).or_else(|| {
+        lease::read_lease(state_dir)
+            .ok()
+            .flatten()
+            .filter(|(l, _)| l.pid == std::process::id())
+            .map(|(l, _)| l.lease_id)
+    });
+    if let Some(id) = lease_id {
+        let _ = lease::release_lease(state_dir, &id);
+    }
 }
 
-/// Force-remove the process lock (for --force-unlock).
-pub fn force_unlock(state_dir: &Path) -> Result<(), String> {
-    let lock_path = process_lock_path(state_dir);
-    if !lock_path.exists() {
-        return Ok(());
-    }
-    std::fs::remove_file(&lock_path).map_err(|e| format!("cannot remove lock file: {e}"))
+/// Force-remove the process lock (for --force-unlock), recording an audit
+/// entry. Returns the lease that was broken.
+pub fn force_unlock(state_dir: &Path) -> Result<Option<lease::ApplyLease>, String> {
+    lease::force_unlock_lease(state_dir)
 }
 
 /// Parse PID from lock file content.
@@ -322,7 +366,7 @@ pub(super) fn parse_lock_pid(content: &str) -> Option<u32> {
 }
 
 /// Check if a PID is still running (Linux-specific: /proc/<pid> exists).
-fn is_pid_running(pid: u32) -> bool {
+pub(super) fn is_pid_running(pid: u32) -> bool {
     Path::new(&format!("/proc/{pid}")).exists()
 }
 
@@ -408,6 +452,8 @@ fn walk_age_files(state_dir: &Path) -> Vec<PathBuf> {
     files
 }
 
+#[cfg(test)]
+mod tests_backend;
 #[cfg(test)]
 mod tests_basic;
 #[cfg(test)]
@@ -423,10 +469,14 @@ mod tests_integrity;
 #[cfg(test)]
 mod tests_integrity_cov;
 #[cfg(test)]
+mod tests_lease;
+#[cfg(test)]
 mod tests_outputs;
 #[cfg(test)]
 mod tests_process_lock;
 #[cfg(test)]
 mod tests_reconstruct;
 #[cfg(test)]
+mod tests_s3;
+#[cfg(test)]
 mod tests_state_cov;
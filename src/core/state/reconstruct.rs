@@ -20,16 +20,17 @@ pub fn reconstruct_at(
     timestamp: &str,
 ) -> Result<StateLock, String> {
     let event_path = state_dir.join(machine).join("events.jsonl");
-    if !event_path.exists() {
+    let Some((bytes, _)) = super::backend::backend_for(state_dir)
+        .get_versioned(&format!("{machine}/events.jsonl"))
+        .map_err(|e| format!("cannot read event log: {e}"))?
+    else {
         return Err(format!(
             "no event log for machine '{}' at {}",
             machine,
             event_path.display()
         ));
-    }
-
-    let content =
-        std::fs::read_to_string(&event_path).map_err(|e| format!("cannot read event log: {e}"))?;
+    };
+    let content = String::from_utf8_lossy(&bytes);
 
     let mut resources: IndexMap<String, ResourceLock> = IndexMap::new();
     let mut hostname = machine.to_string();
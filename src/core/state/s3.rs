@@ -0,0 +1,383 @@
+//! S3-compatible state backend (AWS S3, MinIO, Ceph RGW, R2, ...).
+//!
+//! Objects are addressed path-style (`<endpoint>/<bucket>/<prefix><key>`)
+//! and every request is signed with AWS Signature Version 4. Credentials
+//! come from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and, for
+//! temporary credentials, `AWS_SESSION_TOKEN`.
+//!
+//! Versions are ETags. Conditional writes use `If-Match` and
+//! `If-None-Match: *`, which S3 answers with `412 Precondition Failed` when
+//! another writer got there first.
+
+use super::backend::{Precondition, StateBackend, CONFLICT};
+use crate::core::fetch::{http_request, HttpResponse};
+use crate::core::pki::hmac_sha256;
+use crate::core::types::StateBackendConfig;
+use sha2::{Digest, Sha256};
+
+/// Static credentials for request signing.
+#[derive(Clone)]
+pub struct S3Credentials {
+    pub access_key: String,
+    pub secret_key: String,
+    pub session_token: Option<String>,
+}
+
+impl S3Credentials {
+    /// Credentials from the standard AWS environment variables.
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Ok(Self {
+            access_key: var("AWS_ACCESS_KEY_ID")
+                .ok_or("AWS_ACCESS_KEY_ID is required for the s3 state backend")?,
+            secret_key: var("AWS_SECRET_ACCESS_KEY")
+                .ok_or("AWS_SECRET_ACCESS_KEY is required for the s3 state backend")?,
+            session_token: var("AWS_SESSION_TOKEN"),
+        })
+    }
+}
+
+/// State stored under a prefix of an S3 bucket.
+pub struct S3Backend {
+    endpoint: String,
+    bucket: String,
+    prefix: String,
+    region: String,
+    credentials: S3Credentials,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// SigV4 URI encoding: unreserved characters pass, everything else is
+/// `%XX`; `/` is kept in paths.
+pub fn uri_encode(value: &str, keep_slash: bool) -> String {
+    let mut out = String::with_capacity(value.len());
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            b'/' if keep_slash => out.push('/'),
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
+/// `20130524T000000Z` from an ISO 8601 timestamp.
+fn amz_date(iso: &str) -> String {
+    iso.chars().filter(|c| *c != '-' && *c != ':').collect()
+}
+
+/// One request to sign: method, encoded path, query pairs (unencoded) and
+/// headers (`host` and `x-amz-*` included by the caller).
+pub struct SignedRequest<'a> {
+    pub method: &'a str,
+    pub path: &'a str,
+    pub query: &'a [(&'a str, &'a str)],
+    pub headers: &'a [(String, String)],
+    pub payload_hash: &'a str,
+}
+
+/// AWS Signature Version 4 `Authorization` header value for `req`, signed
+/// at `date` (`YYYYMMDDTHHMMSSZ`).
+pub fn sigv4_authorization(
+    req: &SignedRequest<'_>,
+    credentials: &S3Credentials,
+    region: &str,
+    date: &str,
+) -> Result<String, String> {
+    let mut query: Vec<(String, String)> = req
+        .query
+        .iter()
+        .map(|(k, v)| (uri_encode(k, false), uri_encode(v, false)))
+        .collect();
+    query.sort();
+    let canonical_query: Vec<String> = query.iter().map(|(k, v)| format!("{k}={v}")).collect();
+
+    let mut headers: Vec<(String, String)> = req
+        .headers
+        .iter()
+        .map(|(k, v)| (k.to_ascii_lowercase(), v.trim().to_string()))
+        .collect();
+    headers.sort();
+    let canonical_headers: String = headers.iter().map(|(k, v)| format!("{k}:{v}\n")).collect();
+    let signed_headers: Vec<&str> = headers.iter().map(|(k, _)| k.as_str()).collect();
+    let signed_headers = signed_headers.join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{canonical_headers}\n{signed_headers}\n{}",
+        req.method,
+        req.path,
+        canonical_query.join("&"),
+        req.payload_hash
+    );
+    let day = &date[..8];
+    let scope = format!("{day}/{region}/s3/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{date}\n{scope}\n{}",
+        sha256_hex(canonical_request.as_bytes())
+    );
+    let mut key = hmac_sha256(
+        format!("AWS4{}", credentials.secret_key).as_bytes(),
+        day.as_bytes(),
+    )?;
+    for part in [region, "s3", "aws4_request"] {
+        key = hmac_sha256(&key, part.as_bytes())?;
+    }
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes())?);
+    Ok(format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        credentials.access_key
+    ))
+}
+
+/// Values of `<tag>` elements in an S3 XML response.
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    xml.split(&open)
+        .skip(1)
+        .filter_map(|rest| rest.split_once(&close).map(|(v, _)| xml_unescape(v)))
+        .collect()
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+impl S3Backend {
+    pub fn new(
+        endpoint: &str,
+        bucket: &str,
+        prefix: &str,
+        region: &str,
+        credentials: S3Credentials,
+    ) -> Self {
+        let mut prefix = prefix.trim_start_matches('/').to_string();
+        if !prefix.is_empty() && !prefix.ends_with('/') {
+            prefix.push('/');
+        }
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: bucket.to_string(),
+            prefix,
+            region: region.to_string(),
+            credentials,
+        }
+    }
+
+    /// Backend for a `policy.backend` block of `type: s3`.
+    pub fn from_config(config: &StateBackendConfig) -> Result<Self, String> {
+        let bucket = config
+            .bucket
+            .as_deref()
+            .ok_or("s3 state backend requires bucket")?;
+        let region = config.region.as_deref().unwrap_or("us-east-1");
+        let endpoint = config
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com"));
+        Ok(Self::new(
+            &endpoint,
+            bucket,
+            config.prefix.as_deref().unwrap_or(""),
+            region,
+            S3Credentials::from_env()?,
+        ))
+    }
+
+    fn host(&self) -> &str {
+        let rest = self
+            .endpoint
+            .split_once("://")
+            .map_or(self.endpoint.as_str(), |(_, r)| r);
+        rest.split('/').next().unwrap_or(rest)
+    }
+
+    /// Base path of the endpoint (for S3 behind a path prefix).
+    fn base_path(&self) -> &str {
+        let rest = self
+            .endpoint
+            .split_once("://")
+            .map_or(self.endpoint.as_str(), |(_, r)| r);
+        rest.find('/').map_or("", |i| &rest[i..])
+    }
+
+    fn scheme_host(&self) -> &str {
+        let base = self.base_path();
+        &self.endpoint[..self.endpoint.len() - base.len()]
+    }
+
+    /// Signed request against `/<bucket>/<object>` (`object` may be empty
+    /// for bucket-level calls).
+    fn send(
+        &self,
+        method: &str,
+        object: &str,
+        query: &[(&str, &str)],
+        extra_headers: &[(String, String)],
+        body: Option<&[u8]>,
+    ) -> Result<HttpResponse, String> {
+        let path = format!(
+            "{}/{}/{}",
+            self.base_path(),
+            uri_encode(&self.bucket, false),
+            uri_encode(object, true)
+        );
+        let payload_hash = sha256_hex(body.unwrap_or_default());
+        let date = amz_date(&crate::tripwire::eventlog::now_iso8601());
+        let mut headers = vec![
+            ("host".to_string(), self.host().to_string()),
+            ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+            ("x-amz-date".to_string(), date.clone()),
+        ];
+        if let Some(ref token) = self.credentials.session_token {
+            headers.push(("x-amz-security-token".to_string(), token.clone()));
+        }
+        headers.extend(extra_headers.iter().cloned());
+        let authorization = sigv4_authorization(
+            &SignedRequest {
+                method,
+                path: &path,
+                query,
+                headers: &headers,
+                payload_hash: &payload_hash,
+            },
+            &self.credentials,
+            &self.region,
+            &date,
+        )?;
+        let mut lines: Vec<String> = headers
+            .iter()
+            .filter(|(k, _)| k != "host")
+            .map(|(k, v)| format!("{k}: {v}"))
+            .collect();
+        lines.push(format!("Authorization: {authorization}"));
+        let mut url = format!("{}{path}", self.scheme_host());
+        if !query.is_empty() {
+            let pairs: Vec<String> = query
+                .iter()
+                .map(|(k, v)| format!("{}={}", uri_encode(k, false), uri_encode(v, false)))
+                .collect();
+            url.push('?');
+            url.push_str(&pairs.join("&"));
+        }
+        http_request(method, &url, &lines, body)
+    }
+
+    fn object(&self, key: &str) -> String {
+        format!("{}{key}", self.prefix)
+    }
+
+    fn error(&self, op: &str, key: &str, response: &HttpResponse) -> String {
+        let body = String::from_utf8_lossy(&response.body);
+        let code = xml_values(&body, "Code")
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        format!(
+            "s3 {op} {}/{}: HTTP {} {code}",
+            self.bucket,
+            self.object(key),
+            response.status
+        )
+    }
+}
+
+impl StateBackend for S3Backend {
+    fn name(&self) -> &str {
+        "s3"
+    }
+
+    fn get_versioned(&self, key: &str) -> Result<Option<(Vec<u8>, String)>, String> {
+        let response = self.send("GET", &self.object(key), &[], &[], None)?;
+        match response.status {
+            200 => {
+                let etag = response.header("etag").unwrap_or_default().to_string();
+                Ok(Some((response.body, etag)))
+            }
+            404 => Ok(None),
+            _ => Err(self.error("GET", key, &response)),
+        }
+    }
+
+    fn put_if(&self, key: &str, data: &[u8], cond: Precondition<'_>) -> Result<String, String> {
+        let mut headers = Vec::new();
+        match cond {
+            Precondition::Any => {}
+            Precondition::Absent => headers.push(("if-none-match".to_string(), "*".to_string())),
+            Precondition::Matches(etag) => headers.push(("if-match".to_string(), etag.to_string())),
+        }
+        let response = self.send("PUT", &self.object(key), &[], &headers, Some(data))?;
+        match response.status {
+            200 => Ok(response.header("etag").unwrap_or_default().to_string()),
+            // 409: a concurrent conditional write to the same key is in flight.
+            412 | 409 => Err(format!(
+                "{CONFLICT}: s3 object {} changed",
+                self.object(key)
+            )),
+            _ => Err(self.error("PUT", key, &response)),
+        }
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, String> {
+        let full_prefix = self.object(prefix);
+        let mut keys = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", full_prefix.as_str())];
+            if let Some(ref t) = token {
+                query.push(("continuation-token", t.as_str()));
+            }
+            let response = self.send("GET", "", &query, &[], None)?;
+            if response.status != 200 {
+                return Err(self.error("LIST", prefix, &response));
+            }
+            let body = String::from_utf8_lossy(&response.body);
+            for key in xml_values(&body, "Key") {
+                if let Some(rel) = key.strip_prefix(&self.prefix) {
+                    keys.push(rel.to_string());
+                }
+            }
+            let truncated =
+                xml_values(&body, "IsTruncated").first().map(String::as_str) == Some("true");
+            token = xml_values(&body, "NextContinuationToken")
+                .into_iter()
+                .next();
+            if !truncated || token.is_none() {
+                break;
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        let response = self.send("DELETE", &self.object(key), &[], &[], None)?;
+        match response.status {
+            200 | 204 | 404 => Ok(()),
+            _ => Err(self.error("DELETE", key, &response)),
+        }
+    }
+
+    fn exists(&self, key: &str) -> Result<bool, String> {
+        let response = self.send("HEAD", &self.object(key), &[], &[], None)?;
+        match response.status {
+            200 => Ok(true),
+            404 => Ok(false),
+            _ => Err(self.error("HEAD", key, &response)),
+        }
+    }
+}
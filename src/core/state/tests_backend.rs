@@ -0,0 +1,245 @@
+//! FJ-1425: State I/O routed through `StateBackend`.
+
+use super::backend::{
+    attach, backend_for, is_conflict, locate, register_backend, unregister_backend, LocalBackend,
+    MirroredBackend, Precondition, StateBackend,
+};
+use super::integrity::{self, IntegrityResult};
+use super::tests_helpers::make_lock;
+use super::tests_s3::{serve_s3, test_backend};
+use super::*;
+use std::sync::Arc;
+
+#[test]
+fn test_local_conditional_put() {
+    let dir = tempfile::tempdir().unwrap();
+    let local = LocalBackend::new(dir.path());
+    let v1 = local.put_if("k", b"one", Precondition::Absent).unwrap();
+    assert!(is_conflict(
+        &local.put_if("k", b"two", Precondition::Absent).unwrap_err()
+    ));
+    let v2 = local
+        .put_if("k", b"two", Precondition::Matches(&v1))
+        .unwrap();
+    assert_ne!(v1, v2);
+    assert!(is_conflict(
+        &local
+            .put_if("k", b"three", Precondition::Matches(&v1))
+            .unwrap_err()
+    ));
+    assert!(!dir.path().join("k.tmp").exists());
+}
+
+#[test]
+fn test_local_conditional_put_has_one_winner() {
+    let dir = tempfile::tempdir().unwrap();
+    let local = Arc::new(LocalBackend::new(dir.path()));
+    let v0 = local.put_if("base", b"v0", Precondition::Absent).unwrap();
+    let contend = |cond: fn(&str) -> Precondition<'_>, key: &'static str| {
+        let handles: Vec<_> = (0..16)
+            .map(|i| {
+                let local = local.clone();
+                let v0 = v0.clone();
+                std::thread::spawn(move || {
+                    local
+                        .put_if(key, format!("w{i}").as_bytes(), cond(&v0))
+                        .is_ok()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|won| *won)
+            .count()
+    };
+    assert_eq!(contend(|_| Precondition::Absent, "new"), 1);
+    assert_eq!(contend(|v| Precondition::Matches(v), "base"), 1);
+    assert_eq!(local.list("").unwrap(), vec!["base", "new"]);
+}
+
+#[test]
+fn test_local_list_skips_symlinks_and_uses_slash_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let local = LocalBackend::new(dir.path());
+    local.put("generations/0/m/state.lock.yaml", b"x").unwrap();
+    std::os::unix::fs::symlink(
+        dir.path().join("generations/0"),
+        dir.path().join("generations/current"),
+    )
+    .unwrap();
+    assert_eq!(
+        local.list("generations").unwrap(),
+        vec!["generations/0/m/state.lock.yaml"]
+    );
+}
+
+#[test]
+fn test_mirrored_put_detects_concurrent_writer() {
+    let remote_dir = tempfile::tempdir().unwrap();
+    let state_dir = tempfile::tempdir().unwrap();
+    let other = LocalBackend::new(remote_dir.path());
+    other.put("m/state.lock.yaml", b"v1").unwrap();
+
+    let mirrored = MirroredBackend::new(
+        Box::new(LocalBackend::new(remote_dir.path())),
+        state_dir.path(),
+    );
+    assert_eq!(mirrored.pull().unwrap(), 1);
+    assert_eq!(
+        std::fs::read(state_dir.path().join("m/state.lock.yaml")).unwrap(),
+        b"v1"
+    );
+    mirrored.put("m/state.lock.yaml", b"v2").unwrap();
+
+    // Someone else writes after our last read: our next write loses.
+    other.put("m/state.lock.yaml", b"theirs").unwrap();
+    let err = mirrored.put("m/state.lock.yaml", b"v3").unwrap_err();
+    assert!(is_conflict(&err), "{err}");
+    // A key seen missing is only created if still missing.
+    assert!(mirrored.get_versioned("new").unwrap().is_none());
+    other.put("new", b"theirs").unwrap();
+    assert!(is_conflict(&mirrored.put("new", b"ours").unwrap_err()));
+}
+
+#[test]
+fn test_pull_rejects_keys_outside_state_dir() {
+    let (endpoint, objects) = serve_s3();
+    let root = tempfile::tempdir().unwrap();
+    let state_dir = root.path().join("state");
+    objects
+        .lock()
+        .unwrap()
+        .insert("state/team/../escape.txt".to_string(), b"owned".to_vec());
+    let s3 = MirroredBackend::new(Box::new(test_backend(&endpoint, "team/")), &state_dir);
+    let err = s3.pull().unwrap_err();
+    assert!(err.contains("invalid state key '../escape.txt'"), "{err}");
+    assert!(!root.path().join("escape.txt").exists());
+
+    let local = LocalBackend::new(&state_dir);
+    for key in ["/etc/passwd", "a/../../b", "", "./a"] {
+        assert!(local.put(key, b"x").is_err(), "{key}");
+    }
+}
+
+#[test]
+fn test_locate_registered_and_plain_paths() {
+    let state_dir = tempfile::tempdir().unwrap();
+    let remote_dir = tempfile::tempdir().unwrap();
+    let path = state_dir.path().join("web").join("state.lock.yaml");
+    let (backend, key) = locate(&path);
+    assert_eq!(backend.name(), "local");
+    assert_eq!(key, "state.lock.yaml");
+
+    register_backend(
+        state_dir.path(),
+        Arc::new(MirroredBackend::new(
+            Box::new(LocalBackend::new(remote_dir.path())),
+            state_dir.path(),
+        )),
+    );
+    let (_, key) = locate(&path);
+    assert_eq!(key, "web/state.lock.yaml");
+    unregister_backend(state_dir.path());
+}
+
+#[test]
+fn test_state_files_round_trip_through_s3() {
+    let (endpoint, objects) = serve_s3();
+    let state_dir = tempfile::tempdir().unwrap();
+    let s3 = MirroredBackend::new(Box::new(test_backend(&endpoint, "team/")), state_dir.path());
+    register_backend(state_dir.path(), Arc::new(s3));
+
+    let lock = make_lock();
+    save_lock(state_dir.path(), &lock).unwrap();
+    update_global_lock(state_dir.path(), "fleet", &[("test".to_string(), 1, 1, 0)]).unwrap();
+    crate::tripwire::eventlog::append_event(
+        state_dir.path(),
+        "test",
+        crate::core::types::ProvenanceEvent::ApplyStarted {
+            machine: "test".to_string(),
+            run_id: "r-1".to_string(),
+            forjar_version: "1.0".to_string(),
+            operator: None,
+            config_hash: None,
+            param_count: None,
+        },
+    )
+    .unwrap();
+    {
+        let objects = objects.lock().unwrap();
+        for key in [
+            "test/state.lock.yaml",
+            "test/state.lock.yaml.b3",
+            "forjar.lock.yaml",
+            "forjar.lock.yaml.b3",
+            "test/events.jsonl",
+        ] {
+            assert!(objects.contains_key(&format!("state/team/{key}")), "{key}");
+        }
+    }
+    let loaded = load_lock(state_dir.path(), "test").unwrap().unwrap();
+    assert_eq!(loaded.hostname, lock.hostname);
+    assert_eq!(
+        load_global_lock(state_dir.path()).unwrap().unwrap().name,
+        "fleet"
+    );
+
+    // The sidecar is checked against the stored object, not the working copy.
+    let results = integrity::verify_state_integrity(state_dir.path());
+    assert!(!integrity::has_errors(&results));
+    objects.lock().unwrap().insert(
+        "state/team/test/state.lock.yaml".to_string(),
+        b"schema: tampered\n".to_vec(),
+    );
+    let results = integrity::verify_state_integrity(state_dir.path());
+    assert!(results
+        .iter()
+        .any(|r| matches!(r, IntegrityResult::HashMismatch { .. })));
+    unregister_backend(state_dir.path());
+}
+
+#[test]
+fn test_attach_pulls_working_copy() {
+    let shared = tempfile::tempdir().unwrap();
+    let lock = make_lock();
+    LocalBackend::new(shared.path())
+        .put(
+            "test/state.lock.yaml",
+            serde_yaml_ng::to_string(&lock).unwrap().as_bytes(),
+        )
+        .unwrap();
+
+    let state_dir = tempfile::tempdir().unwrap();
+    attach(None, state_dir.path()).unwrap();
+    assert!(!state_dir.path().join("test/state.lock.yaml").exists());
+
+    let config = crate::core::types::StateBackendConfig {
+        kind: "local".to_string(),
+        path: Some(shared.path().display().to_string()),
+        ..Default::default()
+    };
+    attach(Some(&config), state_dir.path()).unwrap();
+    assert!(state_dir.path().join("test/state.lock.yaml").exists());
+    save_lock(state_dir.path(), &lock).unwrap();
+    assert!(shared.path().join("test/state.lock.yaml.b3").exists());
+    unregister_backend(state_dir.path());
+    assert_eq!(backend_for(state_dir.path()).name(), "local");
+}
+
+#[test]
+fn test_from_config_rejects_unknown_type() {
+    let config = crate::core::types::StateBackendConfig {
+        kind: "gcs".to_string(),
+        ..Default::default()
+    };
+    let err = super::backend::from_config(&config).err().unwrap();
+    assert!(err.contains("unknown state backend type 'gcs'"), "{err}");
+
+    let config = crate::core::types::StateBackendConfig {
+        kind: "s3".to_string(),
+        ..Default::default()
+    };
+    let err = super::backend::from_config(&config).err().unwrap();
+    assert!(err.contains("requires bucket"), "{err}");
+}
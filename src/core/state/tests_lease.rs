@@ -0,0 +1,231 @@
+//! FJ-266: Apply lock leases — holder identity, expiry, renewal and
+//! force-unlock audit, locally and through a remote backend.
+
+use super::backend::{register_backend, unregister_backend, MirroredBackend};
+use super::lease::*;
+use super::tests_s3::{serve_s3, test_backend};
+use super::*;
+use crate::tripwire::eventlog::iso8601_from_secs;
+use std::sync::Arc;
+
+fn now() -> u64 {
+    crate::core::pki::now_unix() as u64
+}
+
+/// A lease held by a process on another host, expiring `expires_in` seconds
+/// from now (negative: already expired).
+fn foreign_lease(expires_in: i64) -> ApplyLease {
+    let now = now() as i64;
+    ApplyLease {
+        pid: 4242,
+        holder: "ci@build-7".to_string(),
+        host: "build-7".to_string(),
+        lease_id: "l-foreign".to_string(),
+        reason: Some("nightly rollout".to_string()),
+        acquired_at: iso8601_from_secs((now - 600) as u64),
+        renewed_at: iso8601_from_secs((now - 60) as u64),
+        expires_at: iso8601_from_secs((now + expires_in) as u64),
+        ttl_secs: 300,
+    }
+}
+
+fn plant(state_dir: &std::path::Path, lease: &ApplyLease) {
+    let yaml = serde_yaml_ng::to_string(lease).unwrap();
+    backend::backend_for(state_dir)
+        .put(LEASE_KEY, yaml.as_bytes())
+        .unwrap();
+}
+
+#[test]
+fn test_lease_records_holder_reason_and_expiry() {
+    let dir = tempfile::tempdir().unwrap();
+    acquire_apply_lock(dir.path(), Some("CHG-1234 kernel update"), 120).unwrap();
+    let (lease, _) = read_lease(dir.path()).unwrap().unwrap();
+    assert_eq!(lease.pid, std::process::id());
+    assert!(lease.holder.contains('@'), "{}", lease.holder);
+    assert_eq!(lease.reason.as_deref(), Some("CHG-1234 kernel update"));
+    assert_eq!(lease.ttl_secs, 120);
+    assert!(lease.expires_at > lease.acquired_at);
+    let text = lease.describe();
+    assert!(text.contains("since"), "{text}");
+    assert!(text.contains("CHG-1234 kernel update"), "{text}");
+    release_process_lock(dir.path());
+    assert!(read_lease(dir.path()).unwrap().is_none());
+}
+
+#[test]
+fn test_live_foreign_lease_blocks() {
+    let dir = tempfile::tempdir().unwrap();
+    // PID 4242 need not exist here: it runs on another host.
+    plant(dir.path(), &foreign_lease(300));
+    let err = acquire_process_lock(dir.path()).unwrap_err();
+    assert!(err.contains("locked by PID 4242 (ci@build-7)"), "{err}");
+    assert!(err.contains("nightly rollout"), "{err}");
+}
+
+#[test]
+fn test_expired_lease_is_taken_over() {
+    let dir = tempfile::tempdir().unwrap();
+    plant(dir.path(), &foreign_lease(-5));
+    acquire_process_lock(dir.path()).unwrap();
+    let (lease, _) = read_lease(dir.path()).unwrap().unwrap();
+    assert_eq!(lease.pid, std::process::id());
+    release_process_lock(dir.path());
+}
+
+#[test]
+fn test_renew_extends_and_detects_takeover() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut lease = acquire_lease(dir.path(), None, 60).unwrap();
+    let mut stale = lease.clone();
+    stale.expires_at = iso8601_from_secs(now() - 1);
+    plant(dir.path(), &stale);
+    renew_lease(dir.path(), &mut lease).unwrap();
+    assert!(!lease.is_expired(&iso8601_from_secs(now())));
+
+    plant(dir.path(), &foreign_lease(300));
+    let err = renew_lease(dir.path(), &mut lease).unwrap_err();
+    assert!(err.contains("taken over by PID 4242"), "{err}");
+    // Releasing a lease we no longer hold leaves the new holder alone.
+    release_lease(dir.path(), &lease.lease_id).unwrap();
+    assert_eq!(read_lease(dir.path()).unwrap().unwrap().0.pid, 4242);
+}
+
+#[test]
+fn test_force_unlock_writes_audit_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(audit_entries(dir.path()).is_empty());
+    plant(dir.path(), &foreign_lease(300));
+    let broken = force_unlock(dir.path()).unwrap().unwrap();
+    assert_eq!(broken.holder, "ci@build-7");
+    assert!(!process_lock_path(dir.path()).exists());
+
+    let audit = audit_entries(dir.path());
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0]["action"], "force_unlock");
+    assert_eq!(audit[0]["previous"]["holder"], "ci@build-7");
+    assert_eq!(audit[0]["previous"]["reason"], "nightly rollout");
+    assert!(audit[0]["by"].as_str().unwrap().contains('@'));
+    // Nothing to break: no new entry.
+    assert!(force_unlock(dir.path()).unwrap().is_none());
+    assert_eq!(audit_entries(dir.path()).len(), 1);
+}
+
+#[test]
+fn test_legacy_lock_file_is_read() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        process_lock_path(dir.path()),
+        "pid: 1\nstarted_at: 2026-02-26T00:00:00Z\n",
+    )
+    .unwrap();
+    let (lease, _) = read_lease(dir.path()).unwrap().unwrap();
+    assert_eq!(lease.pid, 1);
+    assert_eq!(lease.acquired_at, "2026-02-26T00:00:00Z");
+    assert!(!lease.is_expired(&iso8601_from_secs(now())));
+}
+
+#[test]
+fn test_lease_excludes_hosts_sharing_a_remote_backend() {
+    let (endpoint, objects) = serve_s3();
+    let host_a = tempfile::tempdir().unwrap();
+    let host_b = tempfile::tempdir().unwrap();
+    for dir in [host_a.path(), host_b.path()] {
+        let remote = MirroredBackend::new(Box::new(test_backend(&endpoint, "fleet")), dir);
+        register_backend(dir, Arc::new(remote));
+    }
+
+    acquire_apply_lock(host_a.path(), Some("deploy v2"), 300).unwrap();
+    assert!(objects
+        .lock()
+        .unwrap()
+        .contains_key("state/fleet/.forjar.lock"));
+    let err = acquire_apply_lock(host_b.path(), None, 300).unwrap_err();
+    assert!(err.contains("deploy v2"), "{err}");
+    let (lease, _) = read_lease(host_b.path()).unwrap().unwrap();
+    assert_eq!(lease.reason.as_deref(), Some("deploy v2"));
+
+    release_process_lock(host_a.path());
+    acquire_apply_lock(host_b.path(), None, 300).unwrap();
+    release_process_lock(host_b.path());
+    assert!(backend::backend_for(host_a.path())
+        .get_versioned(LEASE_KEY)
+        .unwrap()
+        .is_none());
+    unregister_backend(host_a.path());
+    unregister_backend(host_b.path());
+}
+
+#[test]
+fn test_lost_lease_stops_apply() {
+    let dir = tempfile::tempdir().unwrap();
+    let state_dir = dir.path().join("state");
+    let late = dir.path().join("late.txt");
+    let yaml = format!(
+        r#"
+version: "1.0"
+name: lease-loss
+machines:
+  local:
+    hostname: local
+    addr: 127.0.0.1
+resources:
+  slow:
+    type: task
+    machine: local
+    command: "sleep 3"
+  late:
+    type: file
+    machine: local
+    path: {}
+    content: "x"
+    depends_on: [slow]
+policy:
+  lock_ttl: 1
+"#,
+        late.display()
+    );
+    let config: crate::core::types::ForjarConfig = serde_yaml_ng::from_str(&yaml).unwrap();
+    let cfg = crate::core::executor::ApplyConfig {
+        config: &config,
+        state_dir: &state_dir,
+        force: false,
+        dry_run: false,
+        machine_filter: None,
+        resource_filter: None,
+        tag_filter: None,
+        group_filter: None,
+        timeout_secs: None,
+        force_unlock: false,
+        progress: false,
+        retry: 0,
+        parallel: None,
+        resource_timeout: None,
+        rollback_on_failure: false,
+        max_parallel: None,
+        trace: false,
+        run_id: None,
+        refresh: false,
+        force_tag: None,
+        lock_reason: None,
+    };
+
+    // Another operator takes the lock while `slow` runs.
+    let thief_dir = state_dir.clone();
+    let thief = std::thread::spawn(move || {
+        while !matches!(read_lease(&thief_dir), Ok(Some((ref l, _))) if l.pid == std::process::id())
+        {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        plant(&thief_dir, &foreign_lease(300));
+    });
+    let err = crate::core::executor::apply(&cfg).unwrap_err();
+    thief.join().unwrap();
+
+    assert!(err.contains("apply lock lost"), "{err}");
+    assert!(err.contains("taken over by PID 4242"), "{err}");
+    assert!(!late.exists());
+    // The other operator's lease is left alone.
+    let (lease, _) = read_lease(&state_dir).unwrap().unwrap();
+    assert_eq!(lease.lease_id, "l-foreign");
+}
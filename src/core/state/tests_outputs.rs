@@ -160,3 +160,50 @@ fn ephemeral_drift_detection() {
     // Changed secret: drift detected
     assert!(!ephemeral::verify_drift("changed-password", &marker));
 }
+
+/// Apply scripts export values with `FORJAR_OUTPUT key=value` lines.
+#[test]
+fn parse_script_outputs_lines() {
+    let stdout = "unchanged:wg0\nFORJAR_OUTPUT public_key=abc+/def=\nFORJAR_OUTPUT =ignored\nFORJAR_OUTPUT noequals\n";
+    let outputs = parse_script_outputs(stdout);
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs["public_key"], "abc+/def=");
+}
+
+/// Script outputs are collected from machine locks and survive ephemeral mode.
+#[test]
+fn resource_outputs_published_unredacted() {
+    use crate::core::types::{ResourceLock, ResourceStatus, ResourceType};
+    let tmp = TempDir::new().expect("tempdir");
+    let state_dir = tmp.path();
+
+    let mut lock = new_lock("hub", "hub");
+    let mut map = serde_yaml_ng::Mapping::new();
+    map.insert("public_key".into(), "hubkey=".into());
+    let mut details = std::collections::HashMap::new();
+    details.insert("outputs".to_string(), serde_yaml_ng::Value::Mapping(map));
+    lock.resources.insert(
+        "wg-hub".to_string(),
+        ResourceLock {
+            resource_type: ResourceType::WireguardInterface,
+            status: ResourceStatus::Converged,
+            applied_at: None,
+            duration_seconds: None,
+            hash: "blake3:xxx".to_string(),
+            details,
+        },
+    );
+    save_lock(state_dir, &lock).expect("save");
+
+    let exported = resource_outputs(state_dir, ["hub", "missing"]);
+    assert_eq!(exported["wg-hub.public_key"], "hubkey=");
+
+    let mut config_outputs = indexmap::IndexMap::new();
+    config_outputs.insert("db_host".to_string(), "10.0.0.5".to_string());
+    persist_outputs(state_dir, "cfg", &config_outputs, true).expect("persist");
+    persist_resource_outputs(state_dir, "cfg", &exported).expect("persist exported");
+
+    let global = load_global_lock(state_dir).expect("load").expect("exists");
+    assert!(ephemeral::is_ephemeral_marker(&global.outputs["db_host"]));
+    assert_eq!(global.outputs["wg-hub.public_key"], "hubkey=");
+}
@@ -12,10 +12,15 @@ pub struct EncryptionMeta {
     pub version: u32,
     /// BLAKE3 hash of the plaintext state before encryption.
     pub plaintext_hash: String,
-    /// BLAKE3 HMAC of the ciphertext for integrity verification.
+    /// BLAKE3 keyed hash of the ciphertext: an HMAC under the passphrase key
+    /// (version 1), or a checksum over the ciphertext and recipient list
+    /// (version 2), whose key is derived from public data.
     pub ciphertext_hmac: String,
     /// Timestamp when encryption was performed.
     pub encrypted_at: String,
+    /// FJ-3310: age recipients of a version-2 (recipient-encrypted) file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipients: Vec<String>,
 }
 
 /// Compute BLAKE3 hash of data.
@@ -48,6 +53,7 @@ pub fn create_metadata(plaintext: &[u8], ciphertext: &[u8], key: &[u8; 32]) -> E
         plaintext_hash: hash_data(plaintext),
         ciphertext_hmac: keyed_hash(ciphertext, key),
         encrypted_at: crate::tripwire::eventlog::now_iso8601(),
+        recipients: Vec::new(),
     }
 }
 
@@ -0,0 +1,369 @@
+//! FJ-3310: State encryption to age X25519 recipients.
+//!
+//! With `policy.state_encryption` configured, lock files are written as
+//! age ciphertext addressed to every recipient (team members, CI, a
+//! break-glass key) with an `.enc.meta.json` sidecar naming them, and
+//! event log lines as `ENC[age,...]` markers. Reads decrypt with the
+//! identity in `FORJAR_AGE_KEY`, or the file given to [`set_identity_file`].
+//! A file encrypted to recipients stays encrypted to them when rewritten,
+//! even by commands that don't load the config.
+
+use super::state::backend;
+use super::state_encryption::{
+    hash_data, keyed_hash, meta_path_for, read_metadata, verify_keyed_hash, EncryptionMeta,
+};
+use super::types::StateEncryptionConfig;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Metadata version of recipient-encrypted files (passphrase files are 1).
+pub const RECIPIENTS_META_VERSION: u32 = 2;
+
+/// First line of every binary age file.
+const AGE_HEADER: &[u8] = b"age-encryption.org/v1\n";
+
+/// Whether `data` is an age-encrypted file.
+pub fn is_age(data: &[u8]) -> bool {
+    data.starts_with(AGE_HEADER)
+}
+
+/// Check that `recipient` looks like an age X25519 public key.
+pub fn validate_recipient(recipient: &str) -> Result<(), String> {
+    let ok = recipient.starts_with("age1")
+        && recipient.len() == 62
+        && recipient
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    if ok {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid state recipient '{recipient}' (expected an age1... X25519 public key)"
+        ))
+    }
+}
+
+/// Recipients for `state_dir`: the set of its environment when the
+/// directory is `state/<env>/` and `environments` lists that name,
+/// otherwise the base set.
+pub fn configured_recipients(config: &StateEncryptionConfig, state_dir: &Path) -> Vec<String> {
+    let env = state_dir.file_name().and_then(|n| n.to_str());
+    env.and_then(|env| config.environments.get(env))
+        .unwrap_or(&config.recipients)
+        .clone()
+}
+
+/// Sorted, deduplicated recipient set without blank entries.
+pub fn normalize(recipients: &[String]) -> Vec<String> {
+    let mut set: Vec<String> = recipients
+        .iter()
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .collect();
+    set.sort();
+    set.dedup();
+    set
+}
+
+// ─── Registry ────────────────────────────────────────────────────
+
+type Registry = Mutex<HashMap<PathBuf, Vec<String>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+thread_local! {
+    static IDENTITY_FILE: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Encrypt state written to `state_dir` to `recipients`.
+pub fn register_recipients(state_dir: &Path, recipients: &[String]) {
+    if let Ok(mut map) = registry().lock() {
+        map.insert(state_dir.to_path_buf(), normalize(recipients));
+    }
+}
+
+/// Stop encrypting new state files in `state_dir`.
+pub fn unregister_recipients(state_dir: &Path) {
+    if let Ok(mut map) = registry().lock() {
+        map.remove(state_dir);
+    }
+}
+
+fn registered_recipients(state_dir: &Path) -> Option<Vec<String>> {
+    registry()
+        .lock()
+        .ok()
+        .and_then(|map| map.get(state_dir).cloned())
+}
+
+/// Register the recipients `policy.state_encryption` selects for
+/// `state_dir`. A no-op without the block or with an empty set.
+pub fn attach(config: Option<&StateEncryptionConfig>, state_dir: &Path) -> Result<(), String> {
+    let Some(config) = config else {
+        return Ok(());
+    };
+    let recipients = configured_recipients(config, state_dir);
+    for recipient in &recipients {
+        validate_recipient(recipient)?;
+    }
+    if !recipients.is_empty() {
+        register_recipients(state_dir, &recipients);
+    }
+    Ok(())
+}
+
+/// Decrypt on this thread with the identity in `path` instead of
+/// `FORJAR_AGE_KEY`.
+pub fn set_identity_file(path: Option<&Path>) {
+    IDENTITY_FILE.with(|i| *i.borrow_mut() = path.map(Path::to_path_buf));
+}
+
+#[cfg_attr(not(feature = "encryption"), allow(dead_code))]
+fn identity_file() -> Option<PathBuf> {
+    IDENTITY_FILE.with(|i| i.borrow().clone())
+}
+
+// ─── Metadata ────────────────────────────────────────────────────
+
+/// Key of the ciphertext checksum, binding it to the recipient set.
+/// Recipients are public, so the checksum detects corruption or an
+/// edited recipient list but authenticates nothing.
+pub fn recipients_key(recipients: &[String]) -> [u8; 32] {
+    blake3::derive_key(
+        "forjar state recipients v1",
+        normalize(recipients).join("\n").as_bytes(),
+    )
+}
+
+/// Metadata for a file encrypted to `recipients`.
+pub fn create_recipients_metadata(
+    plaintext: &[u8],
+    ciphertext: &[u8],
+    recipients: &[String],
+) -> EncryptionMeta {
+    EncryptionMeta {
+        version: RECIPIENTS_META_VERSION,
+        plaintext_hash: hash_data(plaintext),
+        ciphertext_hmac: keyed_hash(ciphertext, &recipients_key(recipients)),
+        encrypted_at: crate::tripwire::eventlog::now_iso8601(),
+        recipients: normalize(recipients),
+    }
+}
+
+/// Whether `path` has a recipient-encryption sidecar.
+pub fn is_recipient_encrypted(path: &Path) -> bool {
+    read_metadata(path).is_ok_and(|m| m.version == RECIPIENTS_META_VERSION)
+}
+
+/// Decrypt `ciphertext` described by `meta`, checking its checksum and
+/// plaintext hash.
+pub fn open_with_meta(ciphertext: &[u8], meta: &EncryptionMeta) -> Result<Vec<u8>, String> {
+    if meta.version != RECIPIENTS_META_VERSION {
+        return Err(
+            "passphrase-encrypted state (run `forjar state-decrypt --passphrase ...`)".to_string(),
+        );
+    }
+    if !verify_keyed_hash(
+        ciphertext,
+        &recipients_key(&meta.recipients),
+        &meta.ciphertext_hmac,
+    ) {
+        return Err("checksum mismatch: ciphertext or recipient list changed".to_string());
+    }
+    let plaintext = decrypt_state(ciphertext)?;
+    if hash_data(&plaintext) != meta.plaintext_hash {
+        return Err("plaintext hash mismatch".to_string());
+    }
+    Ok(plaintext)
+}
+
+// ─── State I/O ───────────────────────────────────────────────────
+
+fn meta_key(key: &str) -> String {
+    format!("{key}.enc.meta.json")
+}
+
+fn stored_meta(state_dir: &Path, key: &str) -> Result<Option<EncryptionMeta>, String> {
+    let Some((bytes, _)) = backend::backend_for(state_dir).get_versioned(&meta_key(key))? else {
+        return Ok(None);
+    };
+    serde_json::from_slice(&bytes).map(Some).map_err(|e| {
+        format!(
+            "parse {}: {e}",
+            meta_path_for(&state_dir.join(key)).display()
+        )
+    })
+}
+
+/// Recipients a write of `key` must be encrypted to: the registered set,
+/// else the set the stored copy was encrypted to.
+fn recipients_for(state_dir: &Path, key: &str) -> Result<Option<Vec<String>>, String> {
+    if let Some(recipients) = registered_recipients(state_dir) {
+        return Ok(Some(recipients));
+    }
+    Ok(stored_meta(state_dir, key)?
+        .filter(|m| m.version == RECIPIENTS_META_VERSION && !m.recipients.is_empty())
+        .map(|m| m.recipients))
+}
+
+/// Write `plaintext` to `key` through the state directory's backend,
+/// encrypted when recipients apply. Nothing but ciphertext is written.
+pub fn put_state(state_dir: &Path, key: &str, plaintext: &[u8]) -> Result<(), String> {
+    let recipients = recipients_for(state_dir, key)?;
+    put_state_to(state_dir, key, plaintext, recipients.as_deref())
+}
+
+/// Write `plaintext` to `key` through the state directory's backend,
+/// encrypted to `recipients` with its `.enc.meta.json` sidecar, or as
+/// plaintext without them (dropping any stale sidecar).
+pub fn put_state_to(
+    state_dir: &Path,
+    key: &str,
+    plaintext: &[u8],
+    recipients: Option<&[String]>,
+) -> Result<(), String> {
+    let store = backend::backend_for(state_dir);
+    let Some(recipients) = recipients else {
+        store.put(key, plaintext)?;
+        return match store.exists(&meta_key(key))? {
+            true => store.delete(&meta_key(key)),
+            false => Ok(()),
+        };
+    };
+    let ciphertext = encrypt_to_recipients(plaintext, recipients)?;
+    let meta = create_recipients_metadata(plaintext, &ciphertext, recipients);
+    let json = serde_json::to_string_pretty(&meta).map_err(|e| format!("serialize: {e}"))?;
+    store.put(key, &ciphertext)?;
+    store.put(&meta_key(key), json.as_bytes())
+}
+
+/// Plaintext of stored state `data` read from `key`: age ciphertext is
+/// decrypted and checked against its sidecar, anything else is returned
+/// as is.
+pub fn open_state(state_dir: &Path, key: &str, data: Vec<u8>) -> Result<Vec<u8>, String> {
+    if !is_age(&data) {
+        return Ok(data);
+    }
+    let meta = stored_meta(state_dir, key)?
+        .ok_or_else(|| format!("{key} is encrypted but has no .enc.meta.json sidecar"))?;
+    open_with_meta(&data, &meta).map_err(|e| format!("decrypt {key}: {e}"))
+}
+
+/// An event log line as stored: an `ENC[age,...]` marker when the
+/// machine's state is encrypted to recipients, else unchanged.
+pub fn seal_event_line(state_dir: &Path, machine: &str, line: &str) -> Result<String, String> {
+    match recipients_for(state_dir, &format!("{machine}/state.lock.yaml"))? {
+        Some(recipients) => seal_line(line, &recipients),
+        None => Ok(line.to_string()),
+    }
+}
+
+// ─── Age primitives (requires `encryption` feature) ──────────────
+
+/// Encrypt `plaintext` to every X25519 recipient.
+#[cfg(feature = "encryption")]
+pub fn encrypt_to_recipients(plaintext: &[u8], recipients: &[String]) -> Result<Vec<u8>, String> {
+    use std::io::Write;
+
+    if recipients.is_empty() {
+        return Err("at least one recipient required".to_string());
+    }
+    let parsed = recipients
+        .iter()
+        .map(|r| {
+            r.parse::<age::x25519::Recipient>()
+                .map_err(|e| format!("invalid recipient '{r}': {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let encryptor =
+        age::Encryptor::with_recipients(parsed.iter().map(|r| r as &dyn age::Recipient))
+            .map_err(|e| format!("age encrypt: {e}"))?;
+    let mut encrypted = vec![];
+    let mut writer = encryptor
+        .wrap_output(&mut encrypted)
+        .map_err(|e| format!("age encrypt: {e}"))?;
+    writer
+        .write_all(plaintext)
+        .map_err(|e| format!("write: {e}"))?;
+    writer.finish().map_err(|e| format!("finish: {e}"))?;
+    Ok(encrypted)
+}
+
+/// Decrypt age ciphertext with any of `identities`.
+#[cfg(feature = "encryption")]
+pub fn decrypt_with_identities(
+    ciphertext: &[u8],
+    identities: &[age::x25519::Identity],
+) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let decryptor =
+        age::Decryptor::new(ciphertext).map_err(|e| format!("age decrypt init: {e}"))?;
+    let mut reader = decryptor
+        .decrypt(identities.iter().map(|i| i as &dyn age::Identity))
+        .map_err(|e| format!("age decrypt: {e} (is your identity a recipient?)"))?;
+    let mut plaintext = vec![];
+    reader
+        .read_to_end(&mut plaintext)
+        .map_err(|e| format!("read: {e}"))?;
+    Ok(plaintext)
+}
+
+/// Identities for decrypting state: [`set_identity_file`], else
+/// `FORJAR_AGE_KEY`.
+#[cfg(feature = "encryption")]
+pub fn load_identities() -> Result<Vec<age::x25519::Identity>, String> {
+    super::secrets::load_identities(identity_file().as_deref())
+}
+
+/// Decrypt state ciphertext with [`load_identities`].
+#[cfg(feature = "encryption")]
+pub fn decrypt_state(ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+    decrypt_with_identities(ciphertext, &load_identities()?)
+}
+
+/// Encrypt one line to `recipients` as an `ENC[age,...]` marker.
+#[cfg(feature = "encryption")]
+pub fn seal_line(line: &str, recipients: &[String]) -> Result<String, String> {
+    let refs: Vec<&str> = recipients.iter().map(String::as_str).collect();
+    super::secrets::encrypt(line, &refs)
+}
+
+/// Decrypt every `ENC[age,...]` line of an event log.
+#[cfg(feature = "encryption")]
+pub fn open_lines(content: &str) -> Result<String, String> {
+    super::secrets::decrypt_all(content, &load_identities()?)
+}
+
+#[cfg(not(feature = "encryption"))]
+const FEATURE_DISABLED: &str =
+    "state is encrypted to age recipients but forjar was built without encryption support — rebuild with --features encryption";
+
+/// Encrypt stub when encryption feature is disabled.
+#[cfg(not(feature = "encryption"))]
+pub fn encrypt_to_recipients(_plaintext: &[u8], _recipients: &[String]) -> Result<Vec<u8>, String> {
+    Err(FEATURE_DISABLED.into())
+}
+
+/// Decrypt stub when encryption feature is disabled.
+#[cfg(not(feature = "encryption"))]
+pub fn decrypt_state(_ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+    Err(FEATURE_DISABLED.into())
+}
+
+/// Line sealing stub when encryption feature is disabled.
+#[cfg(not(feature = "encryption"))]
+pub fn seal_line(_line: &str, _recipients: &[String]) -> Result<String, String> {
+    Err(FEATURE_DISABLED.into())
+}
+
+/// Line opening stub when encryption feature is disabled.
+#[cfg(not(feature = "encryption"))]
+pub fn open_lines(content: &str) -> Result<String, String> {
+    super::secrets::decrypt_all_inline(content)
+}
@@ -218,3 +218,44 @@ pub fn decode_far_manifest<R: Read>(
 
     Ok((manifest, entries))
 }
+
+/// Byte offset of the `sig_len` field: everything before it is covered by
+/// the archive signature.
+pub fn far_signed_len(data: &[u8]) -> Result<usize, String> {
+    let mut cursor = std::io::Cursor::new(data);
+    let (_, chunks) = decode_far_manifest(&mut cursor)?;
+    let end = chunks
+        .iter()
+        .try_fold(cursor.position(), |pos, c| pos.checked_add(c.length))
+        .ok_or("chunk lengths overflow")? as usize;
+    if end + 8 > data.len() {
+        return Err("truncated FAR archive: missing signature section".to_string());
+    }
+    Ok(end)
+}
+
+/// Split an archive into the signed bytes and its signature (`None` when
+/// `sig_len` is 0).
+pub fn far_signature(data: &[u8]) -> Result<(&[u8], Option<&[u8]>), String> {
+    let end = far_signed_len(data)?;
+    let mut len_buf = [0u8; 8];
+    len_buf.copy_from_slice(&data[end..end + 8]);
+    let sig_len = u64::from_le_bytes(len_buf) as usize;
+    if sig_len == 0 {
+        return Ok((&data[..end], None));
+    }
+    let sig = data
+        .get(end + 8..end + 8 + sig_len)
+        .ok_or("truncated FAR archive: signature shorter than sig_len")?;
+    Ok((&data[..end], Some(sig)))
+}
+
+/// Replace the signature section of an archive with `sig`.
+pub fn with_far_signature(data: &[u8], sig: &[u8]) -> Result<Vec<u8>, String> {
+    let end = far_signed_len(data)?;
+    let mut out = Vec::with_capacity(end + 8 + sig.len());
+    out.extend_from_slice(&data[..end]);
+    out.extend_from_slice(&(sig.len() as u64).to_le_bytes());
+    out.extend_from_slice(sig);
+    Ok(out)
+}
@@ -0,0 +1,235 @@
+//! Hugging Face Hub resolution for `hf://org/repo@revision` model sources.
+//!
+//! Runs on the control machine during apply: the revision (branch, tag or
+//! commit) is resolved to a commit through the Hub API, the file list is
+//! narrowed to what inference needs (safetensors shards, tokenizer files,
+//! `config.json`), and `config.json` is fetched and validated with
+//! [`super::hf_config`]. The target then downloads exactly those files at
+//! that commit and checks each one against the recorded size and SHA-256.
+//!
+//! The endpoint defaults to `https://huggingface.co` and honours
+//! `HF_ENDPOINT`; `HF_TOKEN` is sent as a bearer token when set.
+
+use super::hf_config::{parse_hf_config_str, HfModelConfig};
+use crate::core::fetch;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// Default Hub endpoint.
+pub const DEFAULT_ENDPOINT: &str = "https://huggingface.co";
+
+/// Non-weight files downloaded alongside the shards.
+const AUX_FILES: &[&str] = &[
+    "config.json",
+    "generation_config.json",
+    "model.safetensors.index.json",
+    "tokenizer.json",
+    "tokenizer_config.json",
+    "tokenizer.model",
+    "special_tokens_map.json",
+    "added_tokens.json",
+    "vocab.json",
+    "vocab.txt",
+    "merges.txt",
+];
+
+/// A parsed `hf://org/repo@revision` source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HfSource {
+    /// Repository id (`org/repo`).
+    pub repo: String,
+    /// Requested revision (default `main`).
+    pub revision: String,
+}
+
+/// One file of a resolved snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HfFile {
+    /// Path within the repository.
+    pub name: String,
+    /// Size in bytes, when the Hub reports it.
+    pub size: Option<u64>,
+    /// SHA-256 of the content: from LFS metadata for weights, computed
+    /// here for `config.json`.
+    pub sha256: Option<String>,
+}
+
+/// A revision resolved to a commit with its file list.
+#[derive(Debug, Clone)]
+pub struct HfSnapshot {
+    /// Hub endpoint the snapshot was resolved against.
+    pub endpoint: String,
+    /// Repository id (`org/repo`).
+    pub repo: String,
+    /// Resolved commit SHA.
+    pub commit: String,
+    /// Files to download.
+    pub files: Vec<HfFile>,
+    /// The validated `config.json`.
+    pub config: HfModelConfig,
+}
+
+impl HfSnapshot {
+    /// Download URL of a file, pinned to the resolved commit.
+    pub fn file_url(&self, name: &str) -> String {
+        format!(
+            "{}/{}/resolve/{}/{name}",
+            self.endpoint, self.repo, self.commit
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiModelInfo {
+    sha: String,
+    #[serde(default)]
+    siblings: Vec<ApiSibling>,
+}
+
+#[derive(Deserialize)]
+struct ApiSibling {
+    rfilename: String,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    lfs: Option<ApiLfs>,
+}
+
+#[derive(Deserialize)]
+struct ApiLfs {
+    sha256: String,
+}
+
+/// True for `hf://` sources.
+pub fn is_hf_source(source: &str) -> bool {
+    source.starts_with("hf://")
+}
+
+fn is_repo_part(part: &str) -> bool {
+    !part.is_empty()
+        && part != "."
+        && part != ".."
+        && part
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+/// Parse `hf://org/repo[@revision]`.
+pub fn parse_hf_source(source: &str) -> Result<HfSource, String> {
+    let rest = source
+        .strip_prefix("hf://")
+        .ok_or_else(|| format!("'{source}' is not an hf:// source"))?;
+    let (repo, revision) = match rest.split_once('@') {
+        Some((repo, rev)) => (repo, rev),
+        None => (rest, "main"),
+    };
+    let valid_repo = repo
+        .split_once('/')
+        .is_some_and(|(org, name)| is_repo_part(org) && is_repo_part(name));
+    if !valid_repo {
+        return Err(format!("'{source}': expected hf://org/repo[@revision]"));
+    }
+    let valid_rev = !revision.is_empty()
+        && revision
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'/'));
+    if !valid_rev || revision.contains("..") {
+        return Err(format!("'{source}': invalid revision '{revision}'"));
+    }
+    Ok(HfSource {
+        repo: repo.to_string(),
+        revision: revision.to_string(),
+    })
+}
+
+/// Hub endpoint from `HF_ENDPOINT`, without a trailing slash.
+pub fn endpoint_from_env() -> String {
+    std::env::var("HF_ENDPOINT")
+        .ok()
+        .filter(|e| !e.is_empty())
+        .map(|e| e.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string())
+}
+
+/// GET a URL, sending `HF_TOKEN` as a bearer token when set.
+fn http_get(url: &str) -> Result<Vec<u8>, String> {
+    fetch::http_get(url, &fetch::env_header("HF_TOKEN", "Authorization: Bearer"))
+}
+
+/// True for files a snapshot downloads: safetensors shards and the
+/// tokenizer/config files in [`AUX_FILES`].
+pub fn is_wanted_file(name: &str) -> bool {
+    name.ends_with(".safetensors") || AUX_FILES.contains(&name)
+}
+
+/// Narrow an API file list to the wanted files, failing when the repo has
+/// no `config.json` or no safetensors weights.
+fn select_files(repo: &str, siblings: Vec<ApiSibling>) -> Result<Vec<HfFile>, String> {
+    let mut files: Vec<HfFile> = siblings
+        .into_iter()
+        .filter(|s| is_wanted_file(&s.rfilename))
+        .map(|s| HfFile {
+            name: s.rfilename,
+            size: s.size,
+            sha256: s.lfs.map(|lfs| lfs.sha256),
+        })
+        .collect();
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    if !files.iter().any(|f| f.name == "config.json") {
+        return Err(format!("{repo} has no config.json"));
+    }
+    if !files.iter().any(|f| f.name.ends_with(".safetensors")) {
+        return Err(format!("{repo} has no safetensors weights"));
+    }
+    Ok(files)
+}
+
+/// Resolve a source to a commit and file list, and validate its `config.json`.
+pub fn resolve(source: &HfSource, endpoint: &str) -> Result<HfSnapshot, String> {
+    let api = format!(
+        "{endpoint}/api/models/{}/revision/{}?blobs=true",
+        source.repo, source.revision
+    );
+    let body = http_get(&api)?;
+    let info: ApiModelInfo = serde_json::from_slice(&body).map_err(|e| {
+        format!(
+            "{}@{}: invalid Hub API response: {e}",
+            source.repo, source.revision
+        )
+    })?;
+    if info.sha.len() != 40 || !info.sha.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!(
+            "{}@{}: Hub returned invalid commit '{}'",
+            source.repo, source.revision, info.sha
+        ));
+    }
+    let mut files = select_files(&source.repo, info.siblings)?;
+
+    let config_url = format!(
+        "{endpoint}/{}/resolve/{}/config.json",
+        source.repo, info.sha
+    );
+    let config_json = String::from_utf8(http_get(&config_url)?)
+        .map_err(|_| format!("{}: config.json is not UTF-8", source.repo))?;
+    let config = parse_hf_config_str(&config_json)
+        .map_err(|e| format!("{}@{}: {e}", source.repo, info.sha))?;
+    if config.model_type.is_empty() {
+        return Err(format!(
+            "{}@{}: config.json has an empty model_type",
+            source.repo, info.sha
+        ));
+    }
+    // The target must download the same config.json that was validated here.
+    let digest = format!("{:x}", Sha256::digest(config_json.as_bytes()));
+    for file in files.iter_mut().filter(|f| f.name == "config.json") {
+        file.size = Some(config_json.len() as u64);
+        file.sha256 = Some(digest.clone());
+    }
+    Ok(HfSnapshot {
+        endpoint: endpoint.to_string(),
+        repo: source.repo.clone(),
+        commit: info.sha,
+        files,
+        config,
+    })
+}
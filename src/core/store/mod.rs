@@ -23,6 +23,7 @@ pub mod far;
 pub mod gc;
 pub mod gc_exec;
 pub mod hf_config;
+pub mod hf_hub;
 pub mod image_assembler;
 pub mod ingest;
 pub mod kernel_far;
@@ -115,6 +116,8 @@ mod tests_gc_exec;
 #[cfg(test)]
 mod tests_hf_config;
 #[cfg(test)]
+mod tests_hf_hub;
+#[cfg(test)]
 mod tests_image_assembler;
 #[cfg(test)]
 mod tests_ingest;
@@ -1,7 +1,8 @@
 //! Tests for FJ-1346: FAR binary format encode/decode.
 
 use super::far::{
-    decode_far_manifest, encode_far, FarFileEntry, FarManifest, FarProvenance, FAR_MAGIC,
+    decode_far_manifest, encode_far, far_signature, with_far_signature, FarFileEntry, FarManifest,
+    FarProvenance, FAR_MAGIC,
 };
 
 fn sample_manifest() -> FarManifest {
@@ -183,3 +184,24 @@ fn test_fj1346_truncated_input_error() {
     let result = decode_far_manifest(FAR_MAGIC.as_slice());
     assert!(result.is_err());
 }
+
+#[test]
+fn test_fj1432_signature_section() {
+    let mut buf = Vec::new();
+    encode_far(&sample_manifest(), &sample_chunks(), &mut buf).unwrap();
+    let (signed, sig) = far_signature(&buf).unwrap();
+    assert_eq!(signed.len(), buf.len() - 8);
+    assert!(sig.is_none());
+
+    let signed_buf = with_far_signature(&buf, b"sig-bytes").unwrap();
+    let (signed, sig) = far_signature(&signed_buf).unwrap();
+    assert_eq!(signed, &buf[..buf.len() - 8]);
+    assert_eq!(sig, Some(b"sig-bytes".as_slice()));
+    // Re-signing replaces the section instead of appending.
+    let resigned = with_far_signature(&signed_buf, b"v2").unwrap();
+    assert_eq!(resigned.len(), buf.len() + 2);
+    // The archive still decodes.
+    assert!(decode_far_manifest(resigned.as_slice()).is_ok());
+
+    assert!(far_signature(&buf[..buf.len() - 4]).is_err());
+}
@@ -0,0 +1,47 @@
+//! Tests for hf_hub.rs — `hf://` source parsing and file selection.
+
+use super::hf_hub::*;
+
+#[test]
+fn parse_source_with_and_without_revision() {
+    let src = parse_hf_source("hf://meta-llama/Llama-3.1-8B@v1.0").unwrap();
+    assert_eq!(src.repo, "meta-llama/Llama-3.1-8B");
+    assert_eq!(src.revision, "v1.0");
+    let src = parse_hf_source("hf://Qwen/Qwen2.5-0.5B").unwrap();
+    assert_eq!(src.revision, "main");
+    let src = parse_hf_source("hf://org/repo@refs/pr/3").unwrap();
+    assert_eq!(src.revision, "refs/pr/3");
+}
+
+#[test]
+fn parse_source_rejects_malformed() {
+    for bad in [
+        "https://huggingface.co/org/repo",
+        "hf://repo-only",
+        "hf://org/repo/extra",
+        "hf://org/../repo",
+        "hf://org/repo@",
+        "hf://org/repo@../main",
+        "hf://org/repo@it's",
+    ] {
+        assert!(parse_hf_source(bad).is_err(), "{bad}");
+    }
+}
+
+#[test]
+fn wanted_files() {
+    assert!(is_wanted_file("model-00001-of-00004.safetensors"));
+    assert!(is_wanted_file("model.safetensors.index.json"));
+    assert!(is_wanted_file("tokenizer.model"));
+    assert!(is_wanted_file("config.json"));
+    assert!(!is_wanted_file("pytorch_model.bin"));
+    assert!(!is_wanted_file("README.md"));
+    assert!(!is_wanted_file("original/consolidated.00.pth"));
+}
+
+#[test]
+fn hf_source_detection() {
+    assert!(is_hf_source("hf://org/repo"));
+    assert!(!is_hf_source("org/repo"));
+    assert!(!is_hf_source("/models/x.gguf"));
+}
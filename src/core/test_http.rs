@@ -0,0 +1,105 @@
+//! Plain HTTP/1.1 stand-in for tests of code that talks to remote services
+//! (release hosts, Hugging Face, S3, Vault, remote includes).
+//!
+//! One request per connection, answered in order on a background thread.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+
+/// One parsed request. Header names are lowercased.
+pub(crate) struct Request {
+    pub method: String,
+    pub target: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+/// Raw response bytes with `Content-Length` and `Connection: close` set.
+pub(crate) fn response(status: &str, headers: &[(&str, &str)], body: &[u8]) -> Vec<u8> {
+    let mut out = format!(
+        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (k, v) in headers {
+        out.push_str(&format!("{k}: {v}\r\n"));
+    }
+    out.push_str("\r\n");
+    let mut out = out.into_bytes();
+    out.extend_from_slice(body);
+    out
+}
+
+/// Bind an ephemeral port on localhost; returns the listener and its base URL.
+pub(crate) fn listen() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    (listener, base)
+}
+
+/// Answer every connection on `listener` with `handler`.
+pub(crate) fn serve_on<H>(listener: TcpListener, handler: H)
+where
+    H: Fn(&Request) -> Vec<u8> + Send + 'static,
+{
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let Some(request) = read_request(&stream) else {
+                continue;
+            };
+            let _ = stream.write_all(&handler(&request));
+        }
+    });
+}
+
+/// Serve `handler` on an ephemeral port; returns the base URL.
+pub(crate) fn serve<H>(handler: H) -> String
+where
+    H: Fn(&Request) -> Vec<u8> + Send + 'static,
+{
+    let (listener, base) = listen();
+    serve_on(listener, handler);
+    base
+}
+
+/// Handler answering each request target from `routes`, 404 for anything
+/// else.
+pub(crate) fn routes<V>(routes: HashMap<String, V>) -> impl Fn(&Request) -> Vec<u8>
+where
+    V: AsRef<[u8]>,
+{
+    move |request| match routes.get(&request.target) {
+        Some(body) => response("200 OK", &[], body.as_ref()),
+        None => response("404 Not Found", &[], b""),
+    }
+}
+
+fn read_request(stream: &std::net::TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream.try_clone().ok()?);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or("/").to_string();
+    let mut headers = BTreeMap::new();
+    let mut header = String::new();
+    while reader.read_line(&mut header).unwrap_or(0) > 2 {
+        if let Some((k, v)) = header.trim_end().split_once(':') {
+            headers.insert(k.trim().to_ascii_lowercase(), v.trim().to_string());
+        }
+        header.clear();
+    }
+    let len: usize = headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0u8; len];
+    let _ = reader.read_exact(&mut body);
+    Some(Request {
+        method,
+        target,
+        headers,
+        body,
+    })
+}
@@ -0,0 +1,180 @@
+//! Tests for pki.rs — CSR signing and certificate expiry tracking.
+
+use super::pki::*;
+use crate::core::types::*;
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::PKey;
+use openssl::x509::{X509NameBuilder, X509Req, X509};
+
+fn make_cert(sans: &[&str]) -> Resource {
+    Resource {
+        resource_type: ResourceType::Certificate,
+        machine: MachineTarget::Single("web".to_string()),
+        path: Some("/etc/ssl/web.pem".to_string()),
+        key_path: Some("/etc/ssl/web.key".to_string()),
+        issuer: Some("local".to_string()),
+        sans: sans.iter().map(|s| s.to_string()).collect(),
+        validity_days: Some(90),
+        ..Default::default()
+    }
+}
+
+fn make_csr(cn: &str) -> String {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, cn).unwrap();
+    let mut req = X509Req::builder().unwrap();
+    req.set_subject_name(&name.build()).unwrap();
+    req.set_pubkey(&key).unwrap();
+    req.sign(&key, MessageDigest::sha256()).unwrap();
+    String::from_utf8(req.build().to_pem().unwrap()).unwrap()
+}
+
+fn cert_lock(not_after: &str) -> ResourceLock {
+    let mut outputs = serde_yaml_ng::Mapping::new();
+    outputs.insert("not_after".into(), not_after.into());
+    let mut details = std::collections::HashMap::new();
+    details.insert(
+        "outputs".to_string(),
+        serde_yaml_ng::Value::Mapping(outputs),
+    );
+    ResourceLock {
+        resource_type: ResourceType::Certificate,
+        status: ResourceStatus::Converged,
+        applied_at: None,
+        duration_seconds: None,
+        hash: "blake3:abc".to_string(),
+        details,
+    }
+}
+
+#[test]
+fn sign_csr_uses_resource_subject_and_sans() {
+    let ca = create_ca("test").unwrap();
+    let resource = make_cert(&["web.internal", "10.0.0.5"]);
+    // The CN requested in the CSR is ignored in favour of the resource
+    let pem = sign_csr(&ca, &make_csr("attacker.example"), &resource).unwrap();
+    let cert = X509::from_pem(pem.as_bytes()).unwrap();
+    assert!(cert.verify(&ca.key).unwrap());
+
+    let cn = cert
+        .subject_name()
+        .entries_by_nid(Nid::COMMONNAME)
+        .next()
+        .unwrap()
+        .data()
+        .as_utf8()
+        .unwrap()
+        .to_string();
+    assert_eq!(cn, "web.internal");
+    let sans = cert.subject_alt_names().unwrap();
+    assert_eq!(
+        sans.iter().filter_map(|n| n.dnsname()).collect::<Vec<_>>(),
+        ["web.internal"]
+    );
+    assert_eq!(
+        sans.iter()
+            .filter_map(|n| n.ipaddress())
+            .collect::<Vec<_>>(),
+        [&[10u8, 0, 0, 5][..]]
+    );
+    assert!(ca_pem(&ca)
+        .unwrap()
+        .starts_with("-----BEGIN CERTIFICATE-----"));
+}
+
+#[test]
+fn sign_csr_rejects_garbage() {
+    let ca = create_ca("test").unwrap();
+    let err = sign_csr(&ca, "not a csr", &make_cert(&["a"])).unwrap_err();
+    assert!(err.contains("invalid CSR"));
+}
+
+#[test]
+fn user_ca_loads_from_files() {
+    let ca = create_ca("corp").unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let cert_path = dir.path().join("ca.crt");
+    let key_path = dir.path().join("ca.key");
+    std::fs::write(&cert_path, ca_pem(&ca).unwrap()).unwrap();
+    std::fs::write(&key_path, ca.key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+
+    let mut resource = make_cert(&["db.internal"]);
+    resource.issuer = Some("ca".to_string());
+    resource.ca_cert = Some(cert_path.display().to_string());
+    resource.ca_key = Some(key_path.display().to_string());
+    let loaded = signing_ca(&resource, dir.path()).unwrap();
+    assert_eq!(loaded.cert.to_der().unwrap(), ca.cert.to_der().unwrap());
+
+    resource.ca_key = Some(dir.path().join("missing.key").display().to_string());
+    assert!(signing_ca(&resource, dir.path())
+        .err()
+        .unwrap()
+        .contains("cannot read ca_key"));
+}
+
+#[test]
+fn self_signed_has_no_signing_ca() {
+    let mut resource = make_cert(&["a"]);
+    resource.issuer = None;
+    let dir = tempfile::tempdir().unwrap();
+    assert!(signing_ca(&resource, dir.path()).is_err());
+}
+
+#[test]
+fn parse_not_after_openssl_format() {
+    assert_eq!(parse_not_after("Jan  1 00:00:00 1970 GMT"), Some(0));
+    assert_eq!(
+        parse_not_after("Mar  1 12:00:00 2027 GMT"),
+        Some(1_803_902_400)
+    );
+    assert_eq!(
+        parse_not_after("Feb 29 00:00:00 2028 GMT"),
+        Some(1_835_395_200)
+    );
+    assert_eq!(parse_not_after("garbage"), None);
+    assert_eq!(
+        lock_not_after(&cert_lock("Jan  2 00:00:00 1970 GMT")),
+        Some(86400)
+    );
+}
+
+#[test]
+fn renewals_due_inside_window() {
+    let mut lock = crate::core::state::new_lock("web", "web");
+    // Expires at 1970-02-01; the default window is 30 days
+    lock.resources
+        .insert("tls".to_string(), cert_lock("Feb  1 00:00:00 1970 GMT"));
+    let expiry = 31 * 86400;
+    assert!(due_renewals(&lock, None, 0).is_empty());
+    assert_eq!(
+        due_renewals(&lock, None, 86400),
+        vec![("tls".to_string(), 30 * 86400)]
+    );
+
+    // A configured renew_before narrows the window
+    let mut config: ForjarConfig =
+        serde_yaml_ng::from_str("version: \"1.0\"\nname: t\nresources: {}\n").unwrap();
+    let mut resource = make_cert(&["a"]);
+    resource.renew_before = Some("1d".to_string());
+    config.resources.insert("tls".to_string(), resource);
+    assert!(due_renewals(&lock, Some(&config), 86400).is_empty());
+
+    let now = expiry - 3600;
+    let locks = std::collections::HashMap::from([("web".to_string(), lock)]);
+    let marked = mark_renewals_due(&config, locks, now);
+    assert_eq!(
+        marked["web"].resources["tls"].status,
+        ResourceStatus::Drifted
+    );
+}
+
+#[test]
+fn time_left_wording() {
+    assert_eq!(describe_time_left(3 * 86400 + 5), "expires in 3d");
+    assert_eq!(describe_time_left(7200), "expires in 2h");
+    assert_eq!(describe_time_left(-86400), "expired 1d ago");
+}
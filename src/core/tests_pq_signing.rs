@@ -0,0 +1,128 @@
+//! FJ-1433: ML-DSA-65 keys, signatures and keyring storage.
+
+use super::pq_signing::*;
+use super::signing::Keyring;
+
+#[test]
+fn test_sign_verify_round_trip() {
+    let key = PqSigningKey::generate("ci").unwrap();
+    let public = key.public_key();
+    let sig = key.sign(b"payload").unwrap();
+    assert_eq!(public.as_bytes().len(), 1952);
+    assert_eq!(sig.len(), 3309);
+    assert!(public.verify(b"payload", &sig));
+    assert!(!public.verify(b"payload!", &sig));
+    assert!(!public.verify(b"payload", &sig[..100]));
+}
+
+#[test]
+fn test_signing_is_deterministic() {
+    let key = PqSigningKey::from_seed("k", [7u8; 32]);
+    assert_eq!(key.sign(b"m").unwrap(), key.sign(b"m").unwrap());
+    assert_eq!(
+        key.public_key(),
+        PqSigningKey::from_seed("other", [7u8; 32]).public_key()
+    );
+}
+
+#[test]
+fn test_wrong_key_rejected() {
+    let a = PqSigningKey::from_seed("a", [1u8; 32]);
+    let b = PqSigningKey::from_seed("b", [2u8; 32]);
+    let sig = a.sign(b"m").unwrap();
+    assert!(!b.public_key().verify(b"m", &sig));
+}
+
+#[test]
+fn test_tampered_signature_rejected() {
+    let key = PqSigningKey::from_seed("k", [3u8; 32]);
+    let mut sig = key.sign(b"m").unwrap();
+    sig[10] ^= 1;
+    assert!(!key.public_key().verify(b"m", &sig));
+}
+
+#[test]
+fn test_public_key_text_round_trip() {
+    let public = PqSigningKey::from_seed("k", [4u8; 32]).public_key();
+    let text = public.to_string();
+    assert!(text.starts_with(PQ_KEY_PREFIX));
+    assert_eq!(PqPublicKey::parse(&text).unwrap(), public);
+    assert!(format!("{public:?}").len() < 64);
+}
+
+#[test]
+fn test_public_key_parse_errors() {
+    assert!(PqPublicKey::parse("ed25519:AAAA").unwrap_err().contains("must start"));
+    assert!(PqPublicKey::parse("ml-dsa-65:!!").is_err());
+    let err = PqPublicKey::parse("ml-dsa-65:AAAA").unwrap_err();
+    assert!(err.contains("wrong length"), "{err}");
+}
+
+#[test]
+fn test_debug_does_not_leak_seed() {
+    let key = PqSigningKey::from_seed("secret", [0xab; 32]);
+    let debug = format!("{key:?}");
+    assert!(debug.contains("secret"));
+    assert!(!debug.contains("171"));
+    assert!(!debug.to_lowercase().contains("ab, "));
+}
+
+#[test]
+fn test_keyring_generate_pq() {
+    let dir = tempfile::tempdir().unwrap();
+    let keyring = Keyring::open(dir.path());
+    let key = keyring.generate_pq("ci").unwrap();
+    assert_eq!(keyring.pq_public_key("ci").unwrap(), key.public_key());
+    let loaded = keyring.pq_signing_key("ci").unwrap();
+    assert_eq!(loaded.public_key(), key.public_key());
+    assert!(keyring.generate_pq("ci").unwrap_err().contains("already"));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(dir.path().join("ml-dsa-65/ci.key"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn test_keyring_missing_pq_half() {
+    let dir = tempfile::tempdir().unwrap();
+    let keyring = Keyring::open(dir.path());
+    keyring.generate("ci").unwrap();
+    let err = keyring.pq_signing_key("ci").unwrap_err();
+    assert!(err.contains("--pq"), "{err}");
+    assert!(keyring.pq_public_key("ci").is_err());
+    assert!(keyring.generate_pq("../escape").is_err());
+}
+
+#[test]
+fn test_corrupt_seed_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let keyring = Keyring::open(dir.path());
+    keyring.generate_pq("ci").unwrap();
+    let path = dir.path().join("ml-dsa-65/ci.key");
+    std::fs::write(&path, "ml-dsa-65-seed:AAAA\n").unwrap();
+    assert!(keyring.pq_signing_key("ci").unwrap_err().contains("32-byte"));
+    std::fs::write(&path, "garbage\n").unwrap();
+    assert!(keyring.pq_signing_key("ci").unwrap_err().contains("must start"));
+}
+
+#[test]
+fn test_load_pq_signing_key_from_key_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let keyring = Keyring::open(dir.path());
+    keyring.generate("ci").unwrap();
+    let key = keyring.generate_pq("ci").unwrap();
+    let spec = dir.path().join("ci.key").display().to_string();
+    let loaded = load_pq_signing_key(&spec).unwrap();
+    assert_eq!(loaded.name, "ci");
+    assert_eq!(loaded.public_key(), key.public_key());
+    assert_eq!(
+        resolve_pq_signing_key(Some(&spec)).unwrap().public_key(),
+        key.public_key()
+    );
+}
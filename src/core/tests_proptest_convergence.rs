@@ -66,6 +66,7 @@ fn make_config(resources: Vec<(String, Resource)>) -> ForjarConfig {
         secrets: Default::default(),
         includes: vec![],
         include_provenance: HashMap::new(),
+        recipe_outputs: indexmap::IndexMap::new(),
         data: indexmap::IndexMap::new(),
         checks: indexmap::IndexMap::new(),
         environments: indexmap::IndexMap::new(),
@@ -146,6 +146,7 @@ proptest! {
             secrets: Default::default(),
             includes: vec![],
             include_provenance: HashMap::new(),
+            recipe_outputs: indexmap::IndexMap::new(),
             data: indexmap::IndexMap::new(),
             checks: indexmap::IndexMap::new(),
             environments: indexmap::IndexMap::new(),
@@ -0,0 +1,123 @@
+//! Tests for release version selection and checksum parsing.
+
+use super::release_source::*;
+use semver::Version;
+
+fn release(tag: &str) -> Release {
+    Release {
+        tag: tag.to_string(),
+        version: tag_version(tag).unwrap(),
+        assets: [
+            "tool-linux-amd64.tar.gz",
+            "tool-darwin-arm64.tar.gz",
+            "SHA256SUMS",
+        ]
+        .iter()
+        .map(|name| ReleaseAsset {
+            name: name.to_string(),
+            url: format!("https://example.com/{tag}/{name}"),
+            sha256: None,
+        })
+        .collect(),
+    }
+}
+
+#[test]
+fn test_tag_version_forms() {
+    assert_eq!(tag_version("v1.4.2"), Some(Version::new(1, 4, 2)));
+    assert_eq!(tag_version("1.4.2"), Some(Version::new(1, 4, 2)));
+    assert_eq!(tag_version("release-2.0.1"), Some(Version::new(2, 0, 1)));
+    assert_eq!(tag_version("v1.4"), Some(Version::new(1, 4, 0)));
+    assert_eq!(
+        tag_version("v2.0.0-rc.1").map(|v| v.pre.to_string()),
+        Some("rc.1".to_string())
+    );
+    assert_eq!(tag_version("nightly"), None);
+    assert_eq!(tag_version("build42"), None);
+}
+
+#[test]
+fn test_constraint_selects_newest_matching_stable() {
+    let releases: Vec<Release> = ["v1.3.9", "v1.4.0", "v1.4.7", "v1.5.0-rc.1", "v2.0.0"]
+        .iter()
+        .map(|t| release(t))
+        .collect();
+    let pick = |c: Option<&str>| {
+        let req = parse_constraint(c).unwrap();
+        select_release(&releases, &req).map(|r| r.tag.clone())
+    };
+    assert_eq!(pick(Some("^1.4")).as_deref(), Some("v1.4.7"));
+    assert_eq!(pick(Some("1.4")).as_deref(), Some("v1.4.7"));
+    assert_eq!(pick(Some("~1.3")).as_deref(), Some("v1.3.9"));
+    assert_eq!(pick(Some("=1.4.0")).as_deref(), Some("v1.4.0"));
+    assert_eq!(pick(Some(">=1.4, <1.5")).as_deref(), Some("v1.4.7"));
+    assert_eq!(pick(Some("^1.5.0-rc.1")).as_deref(), Some("v1.5.0-rc.1"));
+    assert_eq!(pick(None).as_deref(), Some("v2.0.0"));
+    assert_eq!(pick(Some("latest")).as_deref(), Some("v2.0.0"));
+    assert_eq!(pick(Some("^3")), None);
+    assert!(parse_constraint(Some("^one")).is_err());
+}
+
+#[test]
+fn test_select_asset_skips_aux_files_and_rejects_ambiguity() {
+    let mut r = release("v1.4.2");
+    r.assets.push(ReleaseAsset {
+        name: "tool-linux-amd64.tar.gz.minisig".to_string(),
+        url: String::new(),
+        sha256: None,
+    });
+    let asset = select_asset(&r, "*linux*").unwrap();
+    assert_eq!(asset.name, "tool-linux-amd64.tar.gz");
+    let err = select_asset(&r, "tool-*").unwrap_err();
+    assert!(err.contains("matches 2 assets"), "{err}");
+    assert!(select_asset(&r, "*windows*").is_err());
+}
+
+#[test]
+fn test_checksum_file_formats() {
+    let a = "a".repeat(64);
+    let b = "B".repeat(64);
+    let sums = format!("{a}  tool-darwin-arm64.tar.gz\n{b} *tool-linux-amd64.tar.gz\n");
+    assert_eq!(
+        checksum_for(&sums, "tool-linux-amd64.tar.gz", false),
+        Some("b".repeat(64))
+    );
+    assert_eq!(checksum_for(&sums, "tool.zip", false), None);
+    let bsd = format!("SHA256 (tool.zip) = {a}\n");
+    assert_eq!(checksum_for(&bsd, "tool.zip", false), Some(a.clone()));
+    assert_eq!(
+        checksum_for(&format!("{a}\n"), "tool.zip", true),
+        Some(a.clone())
+    );
+    assert_eq!(checksum_for(&format!("{a}\n"), "tool.zip", false), None);
+}
+
+#[test]
+fn test_index_links_from_directory_listing() {
+    let html = r#"<a href="../">../</a>
+        <a href="v1.4.2/">v1.4.2/</a>
+        <a href='/dist/v1.5.0/'>v1.5.0/</a>
+        <a href="?C=M;O=A">Modified</a>
+        <a href="https://elsewhere.example/x">x</a>
+        <a href="tool-linux-amd64">tool-linux-amd64</a>"#;
+    assert_eq!(
+        index_links(html),
+        ["v1.4.2/", "v1.5.0/", "tool-linux-amd64"]
+    );
+}
+
+#[test]
+fn test_provider_and_signature_parsing() {
+    assert_eq!(ReleaseProvider::parse(None), Ok(ReleaseProvider::Github));
+    assert_eq!(
+        ReleaseProvider::parse(Some("forgejo")),
+        Ok(ReleaseProvider::Gitea)
+    );
+    assert!(ReleaseProvider::parse(Some("bitbucket")).is_err());
+    assert_eq!(
+        SignatureKind::parse("minisign").unwrap().suffix(),
+        ".minisig"
+    );
+    assert_eq!(SignatureKind::parse("cosign").unwrap().suffix(), ".sig");
+    assert!(SignatureKind::parse("gpg").is_err());
+}
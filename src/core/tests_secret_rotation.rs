@@ -0,0 +1,151 @@
+//! FJ-3312: Single-secret rotation records and consumer discovery.
+
+use super::secret_audit::{read_audit, SecretEventType};
+use super::secret_rotation::*;
+use super::types::ForjarConfig;
+
+const CONFIG: &str = r#"
+version: "1.0"
+name: app
+params:
+  db_password: "ENC[age,b2xk]"
+  other: "ENC[age,b3RoZXI=]"
+  plain: hello
+machines:
+  web:
+    hostname: web
+    addr: 10.0.0.1
+resources:
+  db-conf:
+    type: file
+    machine: web
+    path: /etc/app/db.conf
+    content: "password={{ params.db_password }}"
+  env-file:
+    type: file
+    machine: web
+    path: /etc/app/env
+    content: "DB={{params.db_password}}\nX={{ params.other }}"
+  unrelated:
+    type: file
+    machine: web
+    path: /etc/app/other
+    content: "{{ params.db_password_old }}"
+"#;
+
+fn record(key: &str, old: &str, new: &str) -> RotationRecord {
+    RotationRecord {
+        rotated_at: "2026-01-01T00:00:00Z".to_string(),
+        key: key.to_string(),
+        file: "forjar.yaml".to_string(),
+        old_marker: old.to_string(),
+        new_marker: new.to_string(),
+        old_hash: fingerprint("old"),
+        new_hash: fingerprint("new"),
+        resources: vec!["db-conf".to_string()],
+        rollback: false,
+    }
+}
+
+#[test]
+fn test_param_marker() {
+    assert_eq!(
+        param_marker(CONFIG, "db_password").unwrap(),
+        "ENC[age,b2xk]"
+    );
+    let err = param_marker(CONFIG, "plain").unwrap_err();
+    assert!(err.contains("not a single"), "{err}");
+    let err = param_marker(CONFIG, "missing").unwrap_err();
+    assert!(err.contains("not found"), "{err}");
+}
+
+#[test]
+fn test_param_marker_rejects_embedded_markers() {
+    let yaml = "params:\n  url: \"ENC[age,YQ==]@ENC[age,Yg==]\"\n";
+    assert!(param_marker(yaml, "url").is_err());
+}
+
+#[test]
+fn test_replace_marker_counts() {
+    let (out, n) = replace_marker("a: ENC[age,x]\nb: ENC[age,x]\n", "ENC[age,x]", "ENC[age,y]");
+    assert_eq!(n, 2);
+    assert_eq!(out, "a: ENC[age,y]\nb: ENC[age,y]\n");
+    assert_eq!(replace_marker("a: 1", "ENC[age,x]", "z").1, 0);
+}
+
+#[test]
+fn test_consumers_match_exact_param() {
+    let config: ForjarConfig = serde_yaml_ng::from_str(CONFIG).unwrap();
+    assert_eq!(
+        consumers(&config, "db_password"),
+        vec!["db-conf", "env-file"]
+    );
+    assert_eq!(consumers(&config, "other"), vec!["env-file"]);
+    assert!(consumers(&config, "plain").is_empty());
+}
+
+#[test]
+fn test_generate_value_is_random() {
+    let a = generate_value().unwrap();
+    let b = generate_value().unwrap();
+    assert_eq!(a.len(), 43);
+    assert_ne!(a, b);
+}
+
+#[test]
+fn test_fingerprint_is_blake3_hex() {
+    assert_eq!(fingerprint("x"), blake3::hash(b"x").to_hex().to_string());
+    assert_eq!(fingerprint("x").len(), 64);
+}
+
+#[test]
+fn test_inverse_swaps_markers_and_hashes() {
+    let inverse = record("db_password", "ENC[age,a]", "ENC[age,b]").inverse();
+    assert_eq!(inverse.old_marker, "ENC[age,b]");
+    assert_eq!(inverse.new_marker, "ENC[age,a]");
+    assert_eq!(inverse.old_hash, fingerprint("new"));
+    assert_eq!(inverse.new_hash, fingerprint("old"));
+    assert!(inverse.rollback);
+}
+
+#[test]
+fn test_record_rotation_appends_log_and_audit() {
+    let dir = tempfile::tempdir().unwrap();
+    record_rotation(
+        dir.path(),
+        &record("db_password", "ENC[age,a]", "ENC[age,b]"),
+    )
+    .unwrap();
+    record_rotation(dir.path(), &record("other", "ENC[age,c]", "ENC[age,d]")).unwrap();
+
+    let rotations = rotations(dir.path(), "db_password").unwrap();
+    assert_eq!(rotations.len(), 1);
+    assert_eq!(rotations[0].new_marker, "ENC[age,b]");
+
+    let events = read_audit(dir.path()).unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_type, SecretEventType::Rotate);
+    assert_eq!(events[0].value_hash, fingerprint("new"));
+    let from = format!("rotated_from:{}", fingerprint("old"));
+    assert_eq!(events[0].namespace.as_deref(), Some(from.as_str()));
+    let log = std::fs::read_to_string(dir.path().join("secret-audit.jsonl")).unwrap();
+    assert!(!log.contains("ENC[age,"));
+}
+
+#[test]
+fn test_rollback_target() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = rollback_target(dir.path(), "db_password", "ENC[age,b]").unwrap_err();
+    assert!(err.contains("no rotation"), "{err}");
+
+    let rotation = record("db_password", "ENC[age,a]", "ENC[age,b]");
+    record_rotation(dir.path(), &rotation).unwrap();
+    let err = rollback_target(dir.path(), "db_password", "ENC[age,z]").unwrap_err();
+    assert!(err.contains("changed since"), "{err}");
+    let target = rollback_target(dir.path(), "db_password", "ENC[age,b]").unwrap();
+    assert_eq!(target, rotation);
+
+    record_rotation(dir.path(), &target.inverse()).unwrap();
+    let err = rollback_target(dir.path(), "db_password", "ENC[age,a]").unwrap_err();
+    assert!(err.contains("already rolled back"), "{err}");
+}
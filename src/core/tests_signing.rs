@@ -0,0 +1,339 @@
+//! FJ-1432: Ed25519 signatures, keyring, trust policy and enforcement.
+
+use super::signing::*;
+use std::path::Path;
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn policy(scope: &str, key: &PublicKey) -> TrustPolicy {
+    TrustPolicy::parse(&format!(
+        "{scope}:\n  require_signatures: true\n  signers:\n    - name: ci\n      key: {key}\n"
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_rfc8032_test_vector_1() {
+    let sk = hex("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60");
+    let key = SigningKey::from_seed("rfc", &sk).unwrap();
+    let public = key.public_key().unwrap();
+    assert_eq!(
+        public.as_bytes().to_vec(),
+        hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a")
+    );
+    let expected = hex(
+        "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+    );
+    assert_eq!(key.sign_raw(b"").unwrap(), expected);
+    assert!(public.verify(b"", &expected).unwrap());
+    assert!(!public.verify(b"x", &expected).unwrap());
+    assert!(!public.verify(b"", b"short").unwrap());
+}
+
+#[test]
+fn test_public_key_text_round_trip() {
+    let key = SigningKey::generate("k").unwrap();
+    let public = key.public_key().unwrap();
+    let text = public.to_string();
+    assert!(text.starts_with("ed25519:"));
+    assert_eq!(PublicKey::parse(&text).unwrap(), public);
+    assert!(PublicKey::parse("ssh-ed25519 AAAA").is_err());
+    assert!(PublicKey::parse("ed25519:AAAA").is_err());
+}
+
+#[test]
+fn test_signature_binds_content_and_scope() {
+    let key = SigningKey::generate("ci").unwrap();
+    let sig = key.sign(Scope::Lock, b"resources: {}\n").unwrap();
+    assert_eq!(
+        sig.verify(Scope::Lock, b"resources: {}\n").unwrap(),
+        key.public_key().unwrap()
+    );
+    let err = sig.verify(Scope::Lock, b"resources: {x}\n").unwrap_err();
+    assert!(err.contains("modified after signing"), "{err}");
+    let err = sig.verify(Scope::Recipe, b"resources: {}\n").unwrap_err();
+    assert!(err.contains("covers a lock"), "{err}");
+
+    // Rewriting the recorded hash to match new content breaks the signature.
+    let mut forged = sig.clone();
+    forged.blake3 = blake3::hash(b"evil").to_hex().to_string();
+    let err = forged.verify(Scope::Lock, b"evil").unwrap_err();
+    assert!(err.contains("does not verify"), "{err}");
+}
+
+#[test]
+fn test_keyring_generate_import_list() {
+    let dir = tempfile::tempdir().unwrap();
+    let keyring = Keyring::open(&dir.path().join("keys"));
+    assert!(keyring.list().unwrap().is_empty());
+    let key = keyring.generate("ci").unwrap();
+    assert!(keyring.generate("ci").is_err());
+    assert!(keyring.generate("../escape").is_err());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(dir.path().join("keys/ci.key"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // A private key imports as a keypair, a public key as verify-only.
+    let pem = dir.path().join("laptop.pem");
+    std::fs::write(&pem, SigningKey::generate("x").unwrap().to_pem().unwrap()).unwrap();
+    assert!(keyring.import("laptop", &pem).unwrap().private);
+    let public = dir.path().join("ci.pub.txt");
+    std::fs::write(&public, format!("{}\n", key.public_key().unwrap())).unwrap();
+    let entry = keyring.import("ci-verify", &public).unwrap();
+    assert!(!entry.private);
+
+    let names: Vec<_> = keyring
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|e| e.name)
+        .collect();
+    assert_eq!(names, vec!["ci", "ci-verify", "laptop"]);
+    assert_eq!(
+        keyring.public_key("ci-verify").unwrap(),
+        key.public_key().unwrap()
+    );
+    assert!(keyring.signing_key("ci-verify").is_err());
+    assert_eq!(
+        keyring.signing_key("ci").unwrap().public_key().unwrap(),
+        key.public_key().unwrap()
+    );
+}
+
+#[test]
+fn test_key_specs() {
+    let dir = tempfile::tempdir().unwrap();
+    let keyring = Keyring::open(dir.path());
+    let key = keyring.generate("ci").unwrap();
+    let public = key.public_key().unwrap();
+    let key_file = dir.path().join("ci.key");
+    let pub_file = dir.path().join("ci.pub");
+
+    assert_eq!(
+        load_signing_key(key_file.to_str().unwrap())
+            .unwrap()
+            .public_key()
+            .unwrap(),
+        public
+    );
+    for spec in [
+        public.to_string(),
+        key_file.display().to_string(),
+        pub_file.display().to_string(),
+    ] {
+        assert_eq!(load_public_key(&spec).unwrap(), public, "{spec}");
+    }
+    assert!(load_signing_key(pub_file.to_str().unwrap()).is_err());
+}
+
+#[test]
+fn test_trust_policy_parse_and_check() {
+    let ci = SigningKey::generate("ci").unwrap();
+    let laptop = SigningKey::generate("laptop").unwrap();
+    let policy = policy("recipes", &ci.public_key().unwrap());
+    assert!(policy.requires(Scope::Recipe));
+    assert!(!policy.requires(Scope::Lock));
+
+    let content = b"recipe: {}\n";
+    let good = ci.sign(Scope::Recipe, content).unwrap();
+    assert_eq!(
+        policy
+            .check(Scope::Recipe, "r", content, Some(&good))
+            .unwrap()
+            .as_deref(),
+        Some("ci")
+    );
+    let err = policy.check(Scope::Recipe, "r", content, None).unwrap_err();
+    assert!(err.contains("unsigned"), "{err}");
+    let untrusted = laptop.sign(Scope::Recipe, content).unwrap();
+    let err = policy
+        .check(Scope::Recipe, "r", content, Some(&untrusted))
+        .unwrap_err();
+    assert!(err.contains("does not list"), "{err}");
+    // Scopes that do not require signatures accept anything.
+    assert!(policy
+        .check(Scope::Lock, "l", content, None)
+        .unwrap()
+        .is_none());
+
+    let err = TrustPolicy::parse("locks:\n  require_signatures: true\n").unwrap_err();
+    assert!(err.contains("no signers"), "{err}");
+    let err = TrustPolicy::parse("archives:\n  signers:\n    - name: x\n      key: ed25519:bad\n")
+        .unwrap_err();
+    assert!(err.contains("signer 'x'"), "{err}");
+    assert!(TrustPolicy::parse("recipe:\n  require_signatures: true\n").is_err());
+}
+
+fn write_lock(state_dir: &Path, machine: &str, body: &str) {
+    std::fs::create_dir_all(state_dir.join(machine)).unwrap();
+    std::fs::write(state_dir.join(machine).join("state.lock.yaml"), body).unwrap();
+}
+
+#[test]
+fn test_state_lock_signatures() {
+    let dir = tempfile::tempdir().unwrap();
+    write_lock(dir.path(), "web", "resources: {}\n");
+    write_lock(dir.path(), "db", "resources: {}\n");
+    write_lock(dir.path(), ".hidden", "x\n");
+    assert_eq!(locked_machines(dir.path()), vec!["db", "web"]);
+
+    let ci = SigningKey::generate("ci").unwrap();
+    let policy = policy("locks", &ci.public_key().unwrap());
+    let err = verify_state_locks(dir.path(), &policy).unwrap_err();
+    assert!(err.contains("db/state.lock.yaml"), "{err}");
+
+    assert_eq!(sign_state_locks(dir.path(), &ci).unwrap(), 2);
+    verify_state_locks(dir.path(), &policy).unwrap();
+
+    write_lock(dir.path(), "web", "resources: {nginx: {}}\n");
+    let err = verify_state_locks(dir.path(), &policy).unwrap_err();
+    assert!(err.contains("web/state.lock.yaml"), "{err}");
+    assert!(err.contains("modified after signing"), "{err}");
+}
+
+#[test]
+fn test_prepare_lock_signing() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(prepare_lock_signing(None, dir.path(), true)
+        .unwrap()
+        .is_none());
+
+    let ci = SigningKey::generate("ci").unwrap();
+    let policy = policy("locks", &ci.public_key().unwrap());
+    // Dry runs write no locks, so they need no key.
+    assert!(prepare_lock_signing(Some(&policy), dir.path(), true)
+        .unwrap()
+        .is_none());
+
+    write_lock(dir.path(), "web", "resources: {}\n");
+    let err = prepare_lock_signing(Some(&policy), dir.path(), true).unwrap_err();
+    assert!(err.contains("unsigned"), "{err}");
+}
+
+#[test]
+fn test_recipe_expansion_enforces_trust_policy() {
+    let dir = tempfile::tempdir().unwrap();
+    let recipes = dir.path().join("recipes");
+    std::fs::create_dir_all(&recipes).unwrap();
+    let recipe = recipes.join("web.yaml");
+    std::fs::write(
+        &recipe,
+        "recipe:\n  name: web\nresources:\n  cfg:\n    type: file\n    path: /etc/web.conf\n    content: hi\n",
+    )
+    .unwrap();
+    let ci = SigningKey::generate("ci").unwrap();
+    let trust = serde_yaml_ng::to_string(&policy("recipes", &ci.public_key().unwrap())).unwrap();
+    std::fs::write(dir.path().join("trust.yaml"), trust).unwrap();
+
+    let yaml = "version: \"1.0\"\nname: signed\nmachines:\n  m1:\n    hostname: m1\n    addr: 10.0.0.1\npolicy:\n  trust_policy: trust.yaml\nresources:\n  site:\n    type: recipe\n    machine: m1\n    recipe: web\n";
+    let expand = || {
+        let mut config = crate::core::parser::parse_config(yaml).unwrap();
+        crate::core::parser::expand_recipes(&mut config, Some(dir.path())).map(|_| config)
+    };
+    let err = expand().unwrap_err();
+    assert!(err.contains("recipe 'web'"), "{err}");
+    assert!(err.contains("unsigned"), "{err}");
+
+    let laptop = SigningKey::generate("laptop").unwrap();
+    let sig = laptop
+        .sign(Scope::Recipe, &std::fs::read(&recipe).unwrap())
+        .unwrap();
+    std::fs::write(recipes.join("web.sig.json"), sig.to_json().unwrap()).unwrap();
+    assert!(expand().unwrap_err().contains("does not list"));
+
+    let sig = ci
+        .sign(Scope::Recipe, &std::fs::read(&recipe).unwrap())
+        .unwrap();
+    std::fs::write(recipes.join("web.sig.json"), sig.to_json().unwrap()).unwrap();
+    let config = expand().unwrap();
+    assert!(config.resources.contains_key("site/cfg"));
+}
+
+#[test]
+fn test_locks_are_signed_as_they_are_saved() {
+    let dir = tempfile::tempdir().unwrap();
+    let state_dir = dir.path().join("state");
+    let ci = SigningKey::generate("ci").unwrap();
+    let policy = policy("locks", &ci.public_key().unwrap());
+    register_lock_signer(&state_dir, ci);
+
+    // A failed apply still leaves a signed lock.
+    let yaml = "version: \"1.0\"\nname: signed\nmachines:\n  local:\n    hostname: local\n    addr: 127.0.0.1\nresources:\n  broken:\n    type: task\n    machine: local\n    command: \"false\"\n";
+    let config: crate::core::types::ForjarConfig = serde_yaml_ng::from_str(yaml).unwrap();
+    let cfg = crate::core::executor::ApplyConfig {
+        config: &config,
+        state_dir: &state_dir,
+        force: false,
+        dry_run: false,
+        machine_filter: None,
+        resource_filter: None,
+        tag_filter: None,
+        group_filter: None,
+        timeout_secs: None,
+        force_unlock: false,
+        progress: false,
+        retry: 0,
+        parallel: None,
+        resource_timeout: None,
+        rollback_on_failure: false,
+        max_parallel: None,
+        trace: false,
+        run_id: None,
+        refresh: false,
+        force_tag: None,
+        lock_reason: None,
+    };
+    let results = crate::core::executor::apply(&cfg).unwrap();
+    assert_eq!(results[0].resources_failed, 1);
+    assert_eq!(locked_machines(&state_dir), vec!["local"]);
+    verify_state_locks(&state_dir, &policy).unwrap();
+
+    // So does any other lock writer.
+    let mut lock = crate::core::state::load_lock(&state_dir, "local")
+        .unwrap()
+        .unwrap();
+    lock.resources.clear();
+    crate::core::state::save_lock(&state_dir, &lock).unwrap();
+    verify_state_locks(&state_dir, &policy).unwrap();
+
+    unregister_lock_signer(&state_dir);
+    crate::core::state::save_lock(&state_dir, &crate::core::state::new_lock("local", "x")).unwrap();
+    assert!(verify_state_locks(&state_dir, &policy).is_err());
+}
+
+#[test]
+fn test_operator_trust_policy_wins_over_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let ci = SigningKey::generate("ci").unwrap();
+    let strict = dir.path().join("operator.yaml");
+    let strict_policy = policy("locks", &ci.public_key().unwrap());
+    std::fs::write(&strict, serde_yaml_ng::to_string(&strict_policy).unwrap()).unwrap();
+    std::fs::write(
+        dir.path().join("weak.yaml"),
+        "locks:\n  require_signatures: false\n",
+    )
+    .unwrap();
+    let config: crate::core::types::ForjarConfig = serde_yaml_ng::from_str(
+        "version: \"1.0\"\nname: t\nmachines: {}\nresources: {}\npolicy:\n  trust_policy: weak.yaml\n",
+    )
+    .unwrap();
+
+    let chosen = trust_policy_with(Some(&strict), &config, dir.path()).unwrap();
+    assert_eq!(chosen, Some(strict_policy));
+    let chosen = trust_policy_with(None, &config, dir.path())
+        .unwrap()
+        .unwrap();
+    assert!(!chosen.requires(Scope::Lock));
+}
@@ -0,0 +1,219 @@
+//! FJ-3310: State encryption to age X25519 recipients.
+
+use super::state_encryption::{create_metadata, derive_key};
+use super::state_recipients::*;
+use super::types::StateEncryptionConfig;
+use std::path::Path;
+
+const ALICE: &str = "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p";
+
+fn config() -> StateEncryptionConfig {
+    let mut config = StateEncryptionConfig {
+        recipients: vec![ALICE.to_string()],
+        ..Default::default()
+    };
+    config
+        .environments
+        .insert("prod".to_string(), vec!["age1prod".to_string()]);
+    config
+}
+
+#[test]
+fn test_validate_recipient() {
+    assert!(validate_recipient(ALICE).is_ok());
+    assert!(validate_recipient("age1short").is_err());
+    assert!(validate_recipient(&ALICE.to_uppercase()).is_err());
+    assert!(validate_recipient(&ALICE.replacen("age1", "ssh1", 1)).is_err());
+}
+
+#[test]
+fn test_configured_recipients_by_environment() {
+    let config = config();
+    assert_eq!(
+        configured_recipients(&config, Path::new("state/prod")),
+        vec!["age1prod".to_string()]
+    );
+    assert_eq!(
+        configured_recipients(&config, Path::new("state/dev")),
+        vec![ALICE.to_string()]
+    );
+    assert_eq!(
+        configured_recipients(&config, Path::new("state")),
+        vec![ALICE.to_string()]
+    );
+}
+
+#[test]
+fn test_normalize_sorts_and_dedups() {
+    let set = normalize(&[
+        " b ".to_string(),
+        "a".to_string(),
+        "b".to_string(),
+        String::new(),
+    ]);
+    assert_eq!(set, vec!["a".to_string(), "b".to_string()]);
+}
+
+#[test]
+fn test_recipients_key_ignores_order() {
+    let ab = recipients_key(&["a".to_string(), "b".to_string()]);
+    let ba = recipients_key(&["b".to_string(), "a".to_string()]);
+    assert_eq!(ab, ba);
+    assert_ne!(ab, recipients_key(&["a".to_string()]));
+}
+
+#[test]
+fn test_metadata_binds_recipient_list() {
+    let meta = create_recipients_metadata(b"plain", b"cipher", &[ALICE.to_string()]);
+    assert_eq!(meta.version, RECIPIENTS_META_VERSION);
+    assert_eq!(meta.recipients, vec![ALICE.to_string()]);
+
+    let mut tampered = meta.clone();
+    tampered.recipients.push("age1other".to_string());
+    let err = open_with_meta(b"cipher", &tampered).unwrap_err();
+    assert!(err.contains("checksum mismatch"), "{err}");
+}
+
+#[test]
+fn test_open_with_meta_rejects_passphrase_meta() {
+    let key = derive_key("pass");
+    let meta = create_metadata(b"plain", b"cipher", &key);
+    let err = open_with_meta(b"cipher", &meta).unwrap_err();
+    assert!(err.contains("passphrase"), "{err}");
+}
+
+#[test]
+fn test_attach_rejects_invalid_recipient() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = StateEncryptionConfig {
+        recipients: vec!["not-a-key".to_string()],
+        ..Default::default()
+    };
+    assert!(attach(Some(&config), dir.path()).is_err());
+    assert!(attach(None, dir.path()).is_ok());
+}
+
+#[test]
+fn test_put_state_plaintext_without_recipients() {
+    let dir = tempfile::tempdir().unwrap();
+    put_state(dir.path(), "m/state.lock.yaml", b"schema: '1.0'\n").unwrap();
+    let path = dir.path().join("m/state.lock.yaml");
+    assert_eq!(std::fs::read(&path).unwrap(), b"schema: '1.0'\n");
+    assert!(!is_recipient_encrypted(&path));
+    let data = open_state(dir.path(), "m/state.lock.yaml", b"plain".to_vec()).unwrap();
+    assert_eq!(data, b"plain");
+    assert_eq!(seal_event_line(dir.path(), "m", "{}").unwrap(), "{}");
+}
+
+#[test]
+fn test_open_state_requires_sidecar() {
+    let dir = tempfile::tempdir().unwrap();
+    let data = b"age-encryption.org/v1\n-> X25519 ...".to_vec();
+    assert!(is_age(&data));
+    let err = open_state(dir.path(), "m/state.lock.yaml", data).unwrap_err();
+    assert!(err.contains("sidecar"), "{err}");
+}
+
+#[cfg(not(feature = "encryption"))]
+#[test]
+fn test_registered_write_needs_feature() {
+    let dir = tempfile::tempdir().unwrap();
+    register_recipients(dir.path(), &[ALICE.to_string()]);
+    let err = put_state(dir.path(), "m/state.lock.yaml", b"x").unwrap_err();
+    unregister_recipients(dir.path());
+    assert!(err.contains("encryption"), "{err}");
+    assert!(!dir.path().join("m/state.lock.yaml").exists());
+}
+
+#[cfg(feature = "encryption")]
+mod with_age {
+    use super::super::secrets::{generate_identity, identity_to_recipient};
+    use super::super::state;
+    use super::super::types::StateLock;
+    use super::*;
+    use age::secrecy::ExposeSecret;
+
+    fn keypair(dir: &Path, name: &str) -> (String, std::path::PathBuf) {
+        let identity = generate_identity();
+        let path = dir.join(format!("{name}.key"));
+        std::fs::write(&path, identity.to_string().expose_secret()).unwrap();
+        (identity_to_recipient(&identity), path)
+    }
+
+    fn lock(machine: &str) -> StateLock {
+        StateLock {
+            schema: "1.0".to_string(),
+            machine: machine.to_string(),
+            hostname: "box".to_string(),
+            generated_at: "2026-10-19T00:00:00Z".to_string(),
+            generator: "forjar test".to_string(),
+            blake3_version: "1.8".to_string(),
+            resources: indexmap::IndexMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let identity = generate_identity();
+        let recipients = vec![identity_to_recipient(&identity)];
+        let ciphertext = encrypt_to_recipients(b"secret state", &recipients).unwrap();
+        assert!(is_age(&ciphertext));
+        let plaintext = decrypt_with_identities(&ciphertext, &[identity]).unwrap();
+        assert_eq!(plaintext, b"secret state");
+    }
+
+    #[test]
+    fn test_wrong_identity_cannot_decrypt() {
+        let recipients = vec![identity_to_recipient(&generate_identity())];
+        let ciphertext = encrypt_to_recipients(b"secret", &recipients).unwrap();
+        assert!(decrypt_with_identities(&ciphertext, &[generate_identity()]).is_err());
+    }
+
+    #[test]
+    fn test_save_load_lock_through_recipients() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys = tempfile::tempdir().unwrap();
+        let (recipient, key) = keypair(keys.path(), "ops");
+        register_recipients(dir.path(), std::slice::from_ref(&recipient));
+
+        state::save_lock(dir.path(), &lock("web")).unwrap();
+        let path = dir.path().join("web/state.lock.yaml");
+        assert!(is_age(&std::fs::read(&path).unwrap()));
+        assert!(is_recipient_encrypted(&path));
+
+        set_identity_file(Some(&key));
+        let loaded = state::load_lock(dir.path(), "web").unwrap().unwrap();
+        set_identity_file(None);
+        unregister_recipients(dir.path());
+        assert_eq!(loaded.hostname, "box");
+    }
+
+    #[test]
+    fn test_encryption_is_sticky() {
+        let dir = tempfile::tempdir().unwrap();
+        let recipient = identity_to_recipient(&generate_identity());
+        register_recipients(dir.path(), std::slice::from_ref(&recipient));
+        put_state(dir.path(), "m/state.lock.yaml", b"one").unwrap();
+        unregister_recipients(dir.path());
+
+        // No registration: the stored sidecar keeps the write encrypted.
+        put_state(dir.path(), "m/state.lock.yaml", b"two").unwrap();
+        let path = dir.path().join("m/state.lock.yaml");
+        assert!(is_age(&std::fs::read(&path).unwrap()));
+        let sealed = seal_event_line(dir.path(), "m", "{\"event\":1}").unwrap();
+        assert!(sealed.starts_with("ENC[age,"), "{sealed}");
+    }
+
+    #[test]
+    fn test_sealed_event_lines_open() {
+        let keys = tempfile::tempdir().unwrap();
+        let (recipient, key) = keypair(keys.path(), "ops");
+        let sealed = seal_line("{\"a\":1}", &[recipient]).unwrap();
+        let content = format!("{sealed}\n{{\"plain\":true}}\n");
+
+        set_identity_file(Some(&key));
+        let opened = open_lines(&content);
+        set_identity_file(None);
+        assert_eq!(opened.unwrap(), "{\"a\":1}\n{\"plain\":true}\n");
+    }
+}
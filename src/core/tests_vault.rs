@@ -0,0 +1,372 @@
+//! FJ-3311: Vault provider against a KV v2 / AppRole / JWT / transit stand-in.
+
+use super::secret_audit::{read_audit, SecretEventType};
+use super::secret_provider::{SecretProvider, VaultProvider};
+use super::test_http;
+use super::types::{SecretsConfig, VaultAuthConfig, VaultConfig, VaultTransitConfig};
+use super::vault::*;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// What the stand-in has seen.
+#[derive(Default)]
+struct Seen {
+    logins: AtomicUsize,
+    renewals: AtomicUsize,
+}
+
+fn b64(s: &str) -> String {
+    base64::engine::general_purpose::STANDARD.encode(s)
+}
+
+fn respond(status: &str, body: &Value) -> Vec<u8> {
+    let body = body.to_string();
+    test_http::response(
+        status,
+        &[("Content-Type", "application/json")],
+        body.as_bytes(),
+    )
+}
+
+fn denied() -> Vec<u8> {
+    respond("403 Forbidden", &json!({ "errors": ["permission denied"] }))
+}
+
+fn auth(token: &str, lease: u64) -> Value {
+    json!({ "auth": { "client_token": token, "lease_duration": lease, "renewable": true } })
+}
+
+/// KV v2 data: path → versions (1-based).
+fn kv() -> HashMap<&'static str, Vec<Value>> {
+    let mut kv = HashMap::new();
+    kv.insert(
+        "forjar/db",
+        vec![
+            json!({ "value": "old-pass", "user": "app" }),
+            json!({ "value": "new-pass", "user": "app" }),
+        ],
+    );
+    kv.insert(
+        "forjar/api",
+        vec![json!({ "value": format!("vault:v1:{}", b64("transit-plain")) })],
+    );
+    kv
+}
+
+fn handle(
+    method: &str,
+    target: &str,
+    headers: &BTreeMap<String, String>,
+    body: &[u8],
+    seen: &Seen,
+) -> Vec<u8> {
+    let body: Value = serde_json::from_slice(body).unwrap_or(Value::Null);
+    let token = headers.get("x-vault-token").map(String::as_str);
+    let valid = matches!(token, Some("root" | "t-approle" | "t-jwt"));
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    match (method, path) {
+        ("POST", "/v1/auth/approle/login") => {
+            if body["role_id"] == "web" && body["secret_id"] == "s3cret" {
+                seen.logins.fetch_add(1, Ordering::SeqCst);
+                respond("200 OK", &auth("t-approle", 3600))
+            } else {
+                respond(
+                    "400 Bad Request",
+                    &json!({ "errors": ["invalid role or secret ID"] }),
+                )
+            }
+        }
+        ("POST", "/v1/auth/jwt/login") => {
+            if body["role"] == "ci" && body["jwt"] == "header.payload.sig" {
+                seen.logins.fetch_add(1, Ordering::SeqCst);
+                respond("200 OK", &auth("t-jwt", 600))
+            } else {
+                denied()
+            }
+        }
+        ("GET", "/v1/auth/token/lookup-self") if valid => respond(
+            "200 OK",
+            &json!({ "data": { "ttl": 0, "renewable": false } }),
+        ),
+        ("POST", "/v1/auth/token/renew-self") if valid => {
+            seen.renewals.fetch_add(1, Ordering::SeqCst);
+            respond("200 OK", &auth(token.unwrap_or_default(), 7200))
+        }
+        ("POST", "/v1/transit/decrypt/forjar") if valid => {
+            let ciphertext = body["ciphertext"].as_str().unwrap_or_default();
+            match ciphertext.strip_prefix("vault:v1:") {
+                Some(plain) => respond("200 OK", &json!({ "data": { "plaintext": plain } })),
+                None => respond(
+                    "400 Bad Request",
+                    &json!({ "errors": ["invalid ciphertext"] }),
+                ),
+            }
+        }
+        ("GET", p) if valid && p.starts_with("/v1/secret/data/") => {
+            let kv = kv();
+            let Some(versions) = kv.get(&p["/v1/secret/data/".len()..]) else {
+                return respond("404 Not Found", &json!({ "errors": [] }));
+            };
+            let version = query
+                .strip_prefix("version=")
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(versions.len());
+            match versions.get(version.wrapping_sub(1)) {
+                Some(data) => respond(
+                    "200 OK",
+                    &json!({ "data": { "data": data, "metadata": { "version": version } } }),
+                ),
+                None => respond("404 Not Found", &json!({ "errors": [] })),
+            }
+        }
+        _ => denied(),
+    }
+}
+
+/// Serve the stand-in on an ephemeral port.
+fn serve_vault() -> (String, Arc<Seen>) {
+    let seen = Arc::new(Seen::default());
+    let state = seen.clone();
+    let address =
+        test_http::serve(move |r| handle(&r.method, &r.target, &r.headers, &r.body, &state));
+    (address, seen)
+}
+
+/// Token-auth config reading the token from a test-specific variable.
+#[allow(clippy::disallowed_methods)]
+fn token_config(address: &str, var: &str, token: &str) -> VaultConfig {
+    std::env::set_var(var, token);
+    VaultConfig {
+        address: Some(address.to_string()),
+        path: Some("forjar".to_string()),
+        auth: VaultAuthConfig {
+            secret_env: Some(var.to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_parse_key() {
+    let key = VaultKey::parse("db/postgres#password@3").unwrap();
+    assert_eq!(key.path, "db/postgres");
+    assert_eq!(key.field, "password");
+    assert_eq!(key.version, Some(3));
+
+    let key = VaultKey::parse("db").unwrap();
+    assert_eq!((key.field.as_str(), key.version), ("value", None));
+
+    assert!(VaultKey::parse("db@latest").is_err());
+    assert!(VaultKey::parse("../db").is_err());
+    assert!(VaultKey::parse("db#").is_err());
+}
+
+#[test]
+fn test_config_from_yaml() {
+    let yaml = "provider: vault\nvault:\n  address: http://127.0.0.1:8200\n  path: forjar\n  auth:\n    method: approle\n    role_id: web\n  transit:\n    key: forjar\n";
+    let secrets: SecretsConfig = serde_yaml_ng::from_str(yaml).unwrap();
+    let vault = secrets.vault.unwrap();
+    assert_eq!(vault.auth.method.as_deref(), Some("approle"));
+    assert_eq!(vault.auth.role_id.as_deref(), Some("web"));
+    assert_eq!(vault.transit.unwrap().key, "forjar");
+    assert_eq!(vault.mount, None);
+}
+
+#[test]
+fn test_renewal_due_after_two_thirds() {
+    assert!(!renewal_due(Duration::from_secs(10), 0));
+    assert!(!renewal_due(Duration::from_secs(59), 90));
+    assert!(renewal_due(Duration::from_secs(60), 90));
+}
+
+#[test]
+fn test_token_read_latest_and_pinned() {
+    let (address, _) = serve_vault();
+    let client = VaultClient::new(&token_config(&address, "FJ3311_TOKEN_READ", "root")).unwrap();
+    let latest = client.read("db").unwrap().unwrap();
+    assert_eq!((latest.value.as_str(), latest.version), ("new-pass", 2));
+    let pinned = client.read("db@1").unwrap().unwrap();
+    assert_eq!((pinned.value.as_str(), pinned.version), ("old-pass", 1));
+    assert_eq!(client.read("db#user").unwrap().unwrap().value, "app");
+    assert!(client.read("db#missing").unwrap().is_none());
+    assert!(client.read("nope").unwrap().is_none());
+    assert!(client.read("db@9").unwrap().is_none());
+}
+
+#[test]
+fn test_bad_token_is_an_error() {
+    let (address, _) = serve_vault();
+    let client = VaultClient::new(&token_config(&address, "FJ3311_TOKEN_BAD", "bogus")).unwrap();
+    let err = client.read("db").unwrap_err();
+    assert!(err.contains("permission denied"), "{err}");
+}
+
+#[test]
+#[allow(clippy::disallowed_methods)]
+fn test_approle_login_once() {
+    let (address, seen) = serve_vault();
+    std::env::set_var("FJ3311_SECRET_ID", "s3cret");
+    let config = VaultConfig {
+        address: Some(address),
+        path: Some("forjar".to_string()),
+        auth: VaultAuthConfig {
+            method: Some("approle".to_string()),
+            role_id: Some("web".to_string()),
+            secret_env: Some("FJ3311_SECRET_ID".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let client = VaultClient::new(&config).unwrap();
+    assert_eq!(client.read("db").unwrap().unwrap().value, "new-pass");
+    assert_eq!(client.read("db@1").unwrap().unwrap().value, "old-pass");
+    assert_eq!(seen.logins.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+#[allow(clippy::disallowed_methods)]
+fn test_approle_wrong_secret_id() {
+    let (address, _) = serve_vault();
+    std::env::set_var("FJ3311_SECRET_ID_BAD", "wrong");
+    let config = VaultConfig {
+        address: Some(address),
+        auth: VaultAuthConfig {
+            method: Some("approle".to_string()),
+            role_id: Some("web".to_string()),
+            secret_env: Some("FJ3311_SECRET_ID_BAD".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let err = VaultClient::new(&config).unwrap().read("db").unwrap_err();
+    assert!(err.contains("invalid role or secret ID"), "{err}");
+}
+
+#[test]
+fn test_jwt_login_from_file() {
+    let (address, seen) = serve_vault();
+    let dir = tempfile::tempdir().unwrap();
+    let jwt = dir.path().join("token.jwt");
+    std::fs::write(&jwt, "header.payload.sig\n").unwrap();
+    let config = VaultConfig {
+        address: Some(address),
+        path: Some("forjar".to_string()),
+        auth: VaultAuthConfig {
+            method: Some("jwt".to_string()),
+            role: Some("ci".to_string()),
+            jwt_file: Some(jwt.to_string_lossy().into_owned()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let client = VaultClient::new(&config).unwrap();
+    assert_eq!(client.read("db").unwrap().unwrap().value, "new-pass");
+    assert_eq!(seen.logins.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_transit_decrypts_ciphertext_values() {
+    let (address, _) = serve_vault();
+    let mut config = token_config(&address, "FJ3311_TOKEN_TRANSIT", "root");
+    let raw = VaultClient::new(&config)
+        .unwrap()
+        .read("api")
+        .unwrap()
+        .unwrap();
+    assert!(raw.value.starts_with("vault:v1:"));
+
+    config.transit = Some(VaultTransitConfig {
+        key: "forjar".to_string(),
+        mount: None,
+    });
+    let client = VaultClient::new(&config).unwrap();
+    assert_eq!(client.read("api").unwrap().unwrap().value, "transit-plain");
+}
+
+#[test]
+fn test_renew_and_audit() {
+    let (address, seen) = serve_vault();
+    let dir = tempfile::tempdir().unwrap();
+    let client = VaultClient::new(&token_config(&address, "FJ3311_TOKEN_AUDIT", "root"))
+        .unwrap()
+        .with_audit(dir.path());
+    client.read("db@1").unwrap();
+    assert_eq!(client.renew().unwrap(), 7200);
+    assert_eq!(seen.renewals.load(Ordering::SeqCst), 1);
+
+    let events = read_audit(dir.path()).unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_type, SecretEventType::Resolve);
+    assert_eq!(events[0].provider, "vault");
+    assert_eq!(events[0].version, Some(1));
+    assert_eq!(
+        events[0].value_hash,
+        blake3::hash(b"old-pass").to_hex().to_string()
+    );
+    assert_eq!(events[1].event_type, SecretEventType::Renew);
+    let log = std::fs::read_to_string(dir.path().join("secret-audit.jsonl")).unwrap();
+    assert!(!log.contains("old-pass"));
+}
+
+#[test]
+fn test_secret_provider_trait() {
+    let (address, _) = serve_vault();
+    let config = token_config(&address, "FJ3311_TOKEN_TRAIT", "root");
+    let provider = VaultProvider::new(VaultClient::new(&config).unwrap());
+    assert_eq!(provider.name(), "vault");
+    let secret = provider.resolve("db").unwrap().unwrap();
+    assert_eq!(
+        (secret.value.as_str(), secret.provider),
+        ("new-pass", "vault")
+    );
+    assert!(provider.resolve("nope").unwrap().is_none());
+}
+
+#[test]
+fn test_template_resolution() {
+    let (address, _) = serve_vault();
+    let secrets = SecretsConfig {
+        provider: Some("vault".to_string()),
+        vault: Some(token_config(&address, "FJ3311_TOKEN_TEMPLATE", "root")),
+        ..Default::default()
+    };
+    let out = crate::core::resolver::resolve_template_with_secrets(
+        "pass={{ secrets.db@1 }} user={{ secrets.db#user }}",
+        &HashMap::new(),
+        &indexmap::IndexMap::new(),
+        &secrets,
+    )
+    .unwrap();
+    assert_eq!(out, "pass=old-pass user=app");
+
+    let err = crate::core::resolver::resolve_template_with_secrets(
+        "{{ secrets.nope }}",
+        &HashMap::new(),
+        &indexmap::IndexMap::new(),
+        &secrets,
+    )
+    .unwrap_err();
+    assert!(err.contains("not found in Vault"), "{err}");
+}
+
+#[test]
+fn test_missing_address() {
+    let config = VaultConfig::default();
+    if std::env::var("VAULT_ADDR").is_err() {
+        assert!(VaultClient::new(&config).is_err());
+    }
+}
+
+#[test]
+fn test_shared_client_reused() {
+    let (address, _) = serve_vault();
+    let config = token_config(&address, "FJ3311_TOKEN_SHARED", "root");
+    let a = client_for(&config).unwrap();
+    let b = client_for(&config).unwrap();
+    assert!(Arc::ptr_eq(&a, &b));
+}
@@ -84,6 +84,11 @@ pub struct ForjarConfig {
     #[serde(skip)]
     pub include_provenance: HashMap<String, String>,
 
+    /// FJ-3315: Outputs of expanded recipes, keyed `<recipe_id>.outputs.<name>`
+    /// and addressed as `{{recipes.<recipe_id>.outputs.<name>}}`. Not serialized to YAML.
+    #[serde(skip)]
+    pub recipe_outputs: IndexMap<String, RecipeOutputBinding>,
+
     /// FJ-1200: Post-apply health check blocks (OpenTofu-style check blocks)
     #[serde(default)]
     pub checks: IndexMap<String, CheckBlock>,
@@ -109,13 +114,13 @@ pub struct ForjarConfig {
 /// FJ-2300 + FJ-3300: Secret provider configuration.
 ///
 /// Controls how `{{secrets.*}}` template variables are resolved.
-/// Providers: "env" (default), "file", "sops", "op" (1Password).
+/// Providers: "env" (default), "file", "sops", "op" (1Password), "vault".
 ///
 /// When `ephemeral: true`, resolved secret values are never written to state.
 /// Instead, a BLAKE3 hash is stored for drift detection.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SecretsConfig {
-    /// Provider type: "env" (default), "file", "sops", "op"
+    /// Provider type: "env" (default), "file", "sops", "op", "vault"
     #[serde(default)]
     pub provider: Option<String>,
 
@@ -132,6 +137,10 @@ pub struct SecretsConfig {
     /// exposing cleartext secrets at rest.
     #[serde(default)]
     pub ephemeral: bool,
+
+    /// FJ-3311: Vault settings (used with `provider: vault`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vault: Option<super::VaultConfig>,
 }
 
 /// FJ-1200: A post-apply health check assertion.
@@ -228,6 +237,19 @@ pub struct OutputValue {
     pub description: Option<String>,
 }
 
+/// FJ-3315: An expanded recipe output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecipeOutputBinding {
+    /// Known once the recipe is expanded.
+    Value(String),
+    /// Captured `FORJAR_OUTPUT` of an expanded resource, read from the global
+    /// lock as `<resource_id>.<key>`; `default` applies before the first apply.
+    Capture {
+        output: String,
+        default: Option<String>,
+    },
+}
+
 // ============================================================================
 // Machines
 // ============================================================================
@@ -48,6 +48,7 @@ mod tests_task;
 mod tests_validation_types;
 mod undo_types;
 mod validation_types;
+mod vault_types;
 mod wasm_types;
 
 pub use behavior_types::*;
@@ -84,6 +85,7 @@ pub use task_types::*;
 pub use test_runner_types::*;
 pub use undo_types::*;
 pub use validation_types::*;
+pub use vault_types::*;
 pub use wasm_types::*;
 
 // Shared default functions used by serde across multiple submodules.
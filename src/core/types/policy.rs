@@ -83,6 +83,93 @@ pub struct Policy {
     /// FJ-2301: Log retention policy (gc thresholds for `forjar logs --gc`).
     #[serde(default)]
     pub logs: LogRetention,
+
+    /// FJ-1425: Where state lives. `None` keeps it in the local state directory.
+    #[serde(default)]
+    pub backend: Option<StateBackendConfig>,
+
+    /// FJ-266: Apply lock lease length in seconds (default 300). The holder
+    /// renews it while the apply runs; an unrenewed lease can be taken over.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_ttl: Option<u64>,
+
+    /// FJ-1432: Trust policy file (relative to the config) listing which
+    /// keys may sign recipes, state locks and archives.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust_policy: Option<String>,
+
+    /// FJ-3310: Encrypt lock files and event logs to age recipients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_encryption: Option<StateEncryptionConfig>,
+
+    /// FJ-3314: Registries resolving `recipe: name@constraint` references —
+    /// directories (relative to the config) or `git+<repo>@<ref>[//subdir]`.
+    /// Default: `~/.forjar/registry`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipe_registries: Vec<String>,
+}
+
+/// FJ-1425: State backend configuration (`policy.backend`).
+///
+/// ```yaml
+/// policy:
+///   backend:
+///     type: s3
+///     bucket: forjar-state
+///     prefix: prod/
+///     endpoint: https://minio.internal:9000
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateBackendConfig {
+    /// Backend type: `local` or `s3`
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    /// S3 bucket
+    #[serde(default)]
+    pub bucket: Option<String>,
+
+    /// Key prefix inside the bucket (e.g. `prod/`)
+    #[serde(default)]
+    pub prefix: Option<String>,
+
+    /// S3-compatible endpoint URL; defaults to AWS for `region`
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// Signing region (default `us-east-1`)
+    #[serde(default)]
+    pub region: Option<String>,
+
+    /// Directory for `type: local`
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// FJ-3310: State encryption to age X25519 recipients
+/// (`policy.state_encryption`).
+///
+/// ```yaml
+/// policy:
+///   state_encryption:
+///     recipients:
+///       - age1...   # alice
+///       - age1...   # ci
+///     environments:
+///       prod:       # state/prod/ uses this set instead
+///         - age1... # ci
+///         - age1... # break-glass
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateEncryptionConfig {
+    /// Recipients of every state directory without an environment set
+    #[serde(default)]
+    pub recipients: Vec<String>,
+
+    /// Per-environment recipient sets, replacing `recipients` for
+    /// `state/<env>/`
+    #[serde(default)]
+    pub environments: indexmap::IndexMap<String, Vec<String>>,
 }
 
 /// FJ-225: Notification hooks configuration.
@@ -123,6 +210,11 @@ impl Default for Policy {
             deny_paths: vec![],
             notify: NotifyConfig::default(),
             logs: LogRetention::default(),
+            backend: None,
+            lock_ttl: None,
+            trust_policy: None,
+            state_encryption: None,
+            recipe_registries: vec![],
         }
     }
 }
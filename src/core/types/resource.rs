@@ -4,7 +4,7 @@ use super::resource_enums::{MachineTarget, ResourceType};
 use super::service_mode_types::RestartPolicy;
 use super::task_types::{HealthCheck, PipelineStage, QualityGate, TaskMode};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 // ============================================================================
 // Resources
@@ -302,6 +302,18 @@ pub struct Resource {
     #[serde(default)]
     pub gpu_memory_limit_mb: Option<u64>,
 
+    /// MIG GPU instance profiles created on each device (e.g. "3g.40gb")
+    #[serde(default)]
+    pub mig_profiles: Vec<String>,
+
+    /// Board power limit in watts (`nvidia-smi -pl`)
+    #[serde(default)]
+    pub power_limit_watts: Option<u32>,
+
+    /// Install the NVIDIA container toolkit and generate the CDI spec
+    #[serde(default)]
+    pub container_toolkit: bool,
+
     // -- Task fields (FJ-2700: task framework) --
     /// Task execution mode (batch/pipeline/service/dispatch).
     #[serde(default)]
@@ -318,8 +330,8 @@ pub struct Resource {
     /// Timeout in seconds.
     #[serde(default)]
     pub timeout: Option<u64>,
-    /// Working directory for the command.
-    #[serde(default)]
+    /// Working directory for the command (`cwd` is accepted as an alias).
+    #[serde(default, alias = "cwd")]
     pub working_dir: Option<String>,
     /// Pipeline stages (mode: pipeline).
     #[serde(default)]
@@ -398,6 +410,316 @@ pub struct Resource {
     /// Directory to install binary into (default: /usr/local/bin).
     #[serde(default)]
     pub install_dir: Option<String>,
+
+    /// binary_release: checksum file in the release (asset name or URL);
+    /// default: `SHA256SUMS`, `checksums.txt` or `<asset>.sha256`.
+    #[serde(default)]
+    pub checksum_file: Option<String>,
+
+    /// binary_release: detached signature to verify (`minisign` or `cosign`)
+    /// against `public_key`.
+    #[serde(default)]
+    pub signature: Option<String>,
+
+    // -- Container runtime fields (docker, docker_network, docker_volume) --
+    /// Container runtime: `docker` (default) or `podman` — mirrors `ContainerConfig.runtime`.
+    #[serde(default)]
+    pub runtime: Option<String>,
+
+    /// Image pull policy: `always` (default), `missing`, or `never`.
+    #[serde(default)]
+    pub pull_policy: Option<String>,
+
+    /// Pin the image to a content digest (e.g., "sha256:abc…"); runs `image@digest`.
+    #[serde(default)]
+    pub image_digest: Option<String>,
+
+    /// Container/network/volume labels (e.g., ["team=infra", "tier=web"]).
+    #[serde(default)]
+    pub labels: Vec<String>,
+
+    /// User the container process runs as (e.g., "1000:1000", "app").
+    #[serde(default)]
+    pub user: Option<String>,
+
+    /// Container entrypoint override.
+    #[serde(default)]
+    pub entrypoint: Option<String>,
+
+    /// CPU quota for the container (e.g., "1.5").
+    #[serde(default)]
+    pub cpus: Option<String>,
+
+    /// Networks to attach the container to (first is used at `run`).
+    #[serde(default)]
+    pub networks: Vec<String>,
+
+    /// Network/volume driver (e.g., "bridge", "macvlan", "local").
+    #[serde(default)]
+    pub driver: Option<String>,
+
+    /// Network subnet in CIDR form (docker_network).
+    #[serde(default)]
+    pub subnet: Option<String>,
+
+    /// Network gateway address (docker_network).
+    #[serde(default)]
+    pub gateway: Option<String>,
+
+    // -- Readiness probe fields (wait_for) --
+    /// URL to poll until it answers (wait_for).
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Host for a TCP port probe (default: 127.0.0.1).
+    #[serde(default)]
+    pub host: Option<String>,
+
+    /// HTTP status the URL must return (default: 200).
+    #[serde(default)]
+    pub expect_status: Option<u16>,
+
+    /// Extended regex the HTTP body or log file must match.
+    #[serde(default)]
+    pub pattern: Option<String>,
+
+    /// Seconds between probe attempts (default: 2).
+    #[serde(default)]
+    pub interval: Option<u64>,
+
+    /// Multiply the interval by this factor after each failed attempt (default: 1).
+    #[serde(default)]
+    pub backoff: Option<u32>,
+
+    // -- Imperative command fields (exec) --
+    /// Skip the command when this path exists.
+    #[serde(default)]
+    pub creates: Option<String>,
+
+    /// Skip the command when this guard command exits 0.
+    #[serde(default)]
+    pub unless: Option<String>,
+
+    /// Run the command only when this guard command exits 0.
+    #[serde(default)]
+    pub onlyif: Option<String>,
+
+    /// Run only when a resource listed in `triggers` converged in this apply.
+    #[serde(default)]
+    pub refreshonly: bool,
+
+    /// Exit codes that count as success (default: [0]).
+    #[serde(default)]
+    pub returns: Vec<i32>,
+
+    // -- Disk provisioning fields (swap, partition, lvm_vg, lvm_lv, filesystem) --
+    /// Block device (e.g., "/dev/sdb", "/dev/nvme0n1").
+    #[serde(default)]
+    pub device: Option<String>,
+
+    /// Size with unit suffix (e.g., "4G", "512M"); lvm_lv also accepts "100%FREE".
+    #[serde(default)]
+    pub size: Option<String>,
+
+    /// Partition number on `device`.
+    #[serde(default)]
+    pub number: Option<u32>,
+
+    /// GPT partition type: linux, swap, efi, bios, lvm, raid, or a 4-hex sgdisk code.
+    #[serde(default)]
+    pub part_type: Option<String>,
+
+    /// Physical volumes backing an LVM volume group.
+    #[serde(default)]
+    pub physical_volumes: Vec<String>,
+
+    /// Volume group an LVM logical volume lives in.
+    #[serde(default)]
+    pub volume_group: Option<String>,
+
+    /// `vm.swappiness` to set alongside a swap resource (0-200).
+    #[serde(default)]
+    pub swappiness: Option<u32>,
+
+    /// Allow destructive operations such as reformatting a non-empty device.
+    #[serde(default)]
+    pub force: bool,
+
+    // -- WireGuard fields (wireguard_interface, wireguard_peer) --
+    /// Interface a peer belongs to (e.g., "wg0").
+    #[serde(default)]
+    pub interface: Option<String>,
+
+    /// Interface addresses in CIDR form (e.g., ["10.10.0.1/24"]).
+    #[serde(default)]
+    pub addresses: Vec<String>,
+
+    /// Peer public key (base64), often `{{data.<source>}}` from another machine's outputs.
+    #[serde(default)]
+    pub public_key: Option<String>,
+
+    /// Peer endpoint as host:port.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// Networks routed to a peer (e.g., ["10.10.0.2/32"]).
+    #[serde(default)]
+    pub allowed_ips: Vec<String>,
+
+    /// Keepalive interval in seconds for peers behind NAT.
+    #[serde(default)]
+    pub persistent_keepalive: Option<u32>,
+
+    // -- Host networking fields (network_interface) --
+    /// Link kind: `ethernet` (default), `vlan`, `bond`, or `bridge`.
+    #[serde(default)]
+    pub kind: Option<String>,
+
+    /// Links enslaved to a bond or bridge (e.g., ["eth1", "eth2"]).
+    #[serde(default)]
+    pub members: Vec<String>,
+
+    /// VLAN id (1-4094); the parent link goes in `interface`.
+    #[serde(default)]
+    pub vlan_id: Option<u16>,
+
+    /// Bond mode (e.g., "802.3ad", "active-backup").
+    #[serde(default)]
+    pub bond_mode: Option<String>,
+
+    /// Link MTU in bytes.
+    #[serde(default)]
+    pub mtu: Option<u32>,
+
+    /// DHCP client: `yes`, `ipv4`, `ipv6`, or `no` (default).
+    #[serde(default)]
+    pub dhcp: Option<String>,
+
+    /// DNS servers (e.g., ["1.1.1.1", "2606:4700:4700::1111"]).
+    #[serde(default)]
+    pub dns: Vec<String>,
+
+    /// Static routes as "<destination> via <gateway>" (e.g., "10.20.0.0/16 via 10.0.0.254").
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Safe-apply: revert the change after this many seconds unless forjar reconnects.
+    #[serde(default)]
+    pub revert_after: Option<u64>,
+
+    // -- TLS certificate fields (certificate) --
+    /// Who signs the certificate: `self-signed` (default), `local` (forjar-managed CA), or `ca`.
+    #[serde(default)]
+    pub issuer: Option<String>,
+
+    /// Name of the forjar-managed local CA (default: "forjar").
+    #[serde(default)]
+    pub ca_name: Option<String>,
+
+    /// CA certificate on the control machine (issuer: ca).
+    #[serde(default)]
+    pub ca_cert: Option<String>,
+
+    /// CA private key on the control machine, plain or age-encrypted (issuer: ca).
+    #[serde(default)]
+    pub ca_key: Option<String>,
+
+    /// Certificate subject common name (default: first entry of `sans`).
+    #[serde(default)]
+    pub common_name: Option<String>,
+
+    /// Subject alternative names: DNS names or IP addresses.
+    #[serde(default)]
+    pub sans: Vec<String>,
+
+    /// Certificate lifetime in days (default: 365).
+    #[serde(default)]
+    pub validity_days: Option<u32>,
+
+    /// Re-issue when the certificate expires within this window (e.g., "30d", the default).
+    #[serde(default)]
+    pub renew_before: Option<String>,
+
+    /// Private key algorithm: `ec` (P-256, default) or `rsa` (3072-bit).
+    #[serde(default)]
+    pub key_type: Option<String>,
+
+    /// Private key path on the target.
+    #[serde(default)]
+    pub key_path: Option<String>,
+
+    /// CA chain path on the target.
+    #[serde(default)]
+    pub chain_path: Option<String>,
+
+    // -- Host identity fields (system) --
+    /// Hostname (default: the machine's `hostname`).
+    #[serde(default)]
+    pub hostname: Option<String>,
+
+    /// IANA timezone (e.g. "Europe/Berlin").
+    #[serde(default)]
+    pub timezone: Option<String>,
+
+    /// System locale, generated if missing (e.g. "en_US.UTF-8").
+    #[serde(default)]
+    pub locale: Option<String>,
+
+    /// Console keymap (e.g. "us", "de-latin1").
+    #[serde(default)]
+    pub keyboard: Option<String>,
+
+    /// NTP servers for chrony or systemd-timesyncd.
+    #[serde(default)]
+    pub ntp_servers: Vec<String>,
+
+    // -- ZFS fields (zfs_pool, zfs_dataset, zfs_snapshot_policy) --
+    /// Pool vdev groups in `zpool create` syntax (e.g. "mirror /dev/sdb /dev/sdc").
+    #[serde(default)]
+    pub vdevs: Vec<String>,
+
+    /// ZFS properties (e.g. compression, recordsize, quota, mountpoint).
+    #[serde(default)]
+    pub properties: BTreeMap<String, String>,
+
+    /// Dataset a snapshot policy applies to.
+    #[serde(default)]
+    pub dataset: Option<String>,
+
+    /// Number of snapshots a policy retains.
+    #[serde(default)]
+    pub keep: Option<u32>,
+
+    /// Snapshot child datasets too.
+    #[serde(default)]
+    pub recursive: bool,
+
+    // -- PostgreSQL fields (pg_role, pg_database, pg_extension) --
+    /// Role password, normally `{{secrets.<key>}}`; only a SCRAM-SHA-256
+    /// verifier is sent to the target.
+    #[serde(default)]
+    pub password: Option<String>,
+
+    /// Role attributes (e.g. LOGIN, CREATEDB); undeclared ones are turned off.
+    #[serde(default)]
+    pub attributes: Vec<String>,
+
+    /// Database an extension is installed into.
+    #[serde(default)]
+    pub database: Option<String>,
+
+    /// Database encoding at creation (e.g. "UTF8").
+    #[serde(default)]
+    pub encoding: Option<String>,
+
+    /// Database privileges as `role:PRIVILEGE[,PRIVILEGE]` (e.g. "app:CONNECT,TEMPORARY").
+    #[serde(default)]
+    pub grants: Vec<String>,
+
+    // -- SELinux fields (selinux_fcontext) --
+    /// SELinux type a file context rule assigns (e.g. "httpd_sys_content_t").
+    #[serde(default)]
+    pub selinux_type: Option<String>,
 }
 
 /// FJ-1220: Lifecycle protection rules for a resource.
@@ -48,6 +48,58 @@ pub enum ResourceType {
     Build,
     /// FJ-34: GitHub Release binary installation.
     GithubRelease,
+    /// Docker/Podman network.
+    DockerNetwork,
+    /// Docker/Podman named volume.
+    DockerVolume,
+    /// Multi-container stack (compose or podman Quadlet).
+    ContainerStack,
+    /// Readiness gate: port, URL, file, log line or command.
+    WaitFor,
+    /// Imperative command with creates/unless/onlyif guards.
+    Exec,
+    /// Swap file or partition.
+    Swap,
+    /// GPT partition (sgdisk).
+    Partition,
+    /// LVM volume group.
+    LvmVg,
+    /// LVM logical volume.
+    LvmLv,
+    /// Filesystem on a block device (mkfs, never reformats unless forced).
+    Filesystem,
+    /// WireGuard interface (key generated on the target, wg syncconf reconciliation).
+    WireguardInterface,
+    /// WireGuard peer on an interface.
+    WireguardPeer,
+    /// Host network interface (systemd-networkd or netplan)
+    NetworkInterface,
+    /// TLS certificate and private key
+    Certificate,
+    /// Host identity: hostname, timezone, locale, keyboard and time sync
+    System,
+    /// ZFS storage pool
+    ZfsPool,
+    /// ZFS dataset with properties
+    ZfsDataset,
+    /// Scheduled ZFS snapshots with retention
+    ZfsSnapshotPolicy,
+    /// Release binary from GitHub, GitLab, Gitea or a static index (version constraint, checksum, signature)
+    BinaryRelease,
+    /// PostgreSQL role (attributes, SCRAM password, memberships)
+    PgRole,
+    /// PostgreSQL database (owner, encoding, grants)
+    PgDatabase,
+    /// PostgreSQL extension in a database
+    PgExtension,
+    /// AppArmor profile loaded in enforce or complain mode
+    ApparmorProfile,
+    /// SELinux boolean (persistent)
+    SelinuxBoolean,
+    /// SELinux file context rule plus relabel
+    SelinuxFcontext,
+    /// SELinux mode (enforcing, permissive or disabled)
+    SelinuxMode,
 }
 
 impl fmt::Display for ResourceType {
@@ -70,6 +122,32 @@ impl fmt::Display for ResourceType {
             Self::Image => write!(f, "image"),
             Self::Build => write!(f, "build"),
             Self::GithubRelease => write!(f, "github_release"),
+            Self::DockerNetwork => write!(f, "docker_network"),
+            Self::DockerVolume => write!(f, "docker_volume"),
+            Self::ContainerStack => write!(f, "container_stack"),
+            Self::WaitFor => write!(f, "wait_for"),
+            Self::Exec => write!(f, "exec"),
+            Self::Swap => write!(f, "swap"),
+            Self::Partition => write!(f, "partition"),
+            Self::LvmVg => write!(f, "lvm_vg"),
+            Self::LvmLv => write!(f, "lvm_lv"),
+            Self::Filesystem => write!(f, "filesystem"),
+            Self::WireguardInterface => write!(f, "wireguard_interface"),
+            Self::WireguardPeer => write!(f, "wireguard_peer"),
+            Self::NetworkInterface => write!(f, "network_interface"),
+            Self::Certificate => write!(f, "certificate"),
+            Self::System => write!(f, "system"),
+            Self::ZfsPool => write!(f, "zfs_pool"),
+            Self::ZfsDataset => write!(f, "zfs_dataset"),
+            Self::ZfsSnapshotPolicy => write!(f, "zfs_snapshot_policy"),
+            Self::BinaryRelease => write!(f, "binary_release"),
+            Self::PgRole => write!(f, "pg_role"),
+            Self::PgDatabase => write!(f, "pg_database"),
+            Self::PgExtension => write!(f, "pg_extension"),
+            Self::ApparmorProfile => write!(f, "apparmor_profile"),
+            Self::SelinuxBoolean => write!(f, "selinux_boolean"),
+            Self::SelinuxFcontext => write!(f, "selinux_fcontext"),
+            Self::SelinuxMode => write!(f, "selinux_mode"),
         }
     }
 }
@@ -195,6 +273,32 @@ mod tests {
             (ResourceType::Image, "image"),
             (ResourceType::Build, "build"),
             (ResourceType::GithubRelease, "github_release"),
+            (ResourceType::DockerNetwork, "docker_network"),
+            (ResourceType::DockerVolume, "docker_volume"),
+            (ResourceType::ContainerStack, "container_stack"),
+            (ResourceType::WaitFor, "wait_for"),
+            (ResourceType::Exec, "exec"),
+            (ResourceType::Swap, "swap"),
+            (ResourceType::Partition, "partition"),
+            (ResourceType::LvmVg, "lvm_vg"),
+            (ResourceType::LvmLv, "lvm_lv"),
+            (ResourceType::Filesystem, "filesystem"),
+            (ResourceType::WireguardInterface, "wireguard_interface"),
+            (ResourceType::WireguardPeer, "wireguard_peer"),
+            (ResourceType::NetworkInterface, "network_interface"),
+            (ResourceType::Certificate, "certificate"),
+            (ResourceType::System, "system"),
+            (ResourceType::ZfsPool, "zfs_pool"),
+            (ResourceType::ZfsDataset, "zfs_dataset"),
+            (ResourceType::ZfsSnapshotPolicy, "zfs_snapshot_policy"),
+            (ResourceType::BinaryRelease, "binary_release"),
+            (ResourceType::PgRole, "pg_role"),
+            (ResourceType::PgDatabase, "pg_database"),
+            (ResourceType::PgExtension, "pg_extension"),
+            (ResourceType::ApparmorProfile, "apparmor_profile"),
+            (ResourceType::SelinuxBoolean, "selinux_boolean"),
+            (ResourceType::SelinuxFcontext, "selinux_fcontext"),
+            (ResourceType::SelinuxMode, "selinux_mode"),
         ];
         for (variant, expected) in &cases {
             assert_eq!(variant.to_string(), *expected);
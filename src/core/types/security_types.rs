@@ -32,6 +32,8 @@ pub enum SecretProvider {
     Sops,
     /// Resolve via 1Password CLI (`op read`).
     Op,
+    /// FJ-3311: Resolve from HashiCorp Vault KV v2.
+    Vault,
 }
 
 impl fmt::Display for SecretProvider {
@@ -41,6 +43,7 @@ impl fmt::Display for SecretProvider {
             Self::File => write!(f, "file"),
             Self::Sops => write!(f, "sops"),
             Self::Op => write!(f, "op"),
+            Self::Vault => write!(f, "vault"),
         }
     }
 }
@@ -291,6 +294,7 @@ mod tests {
             SecretProvider::File,
             SecretProvider::Sops,
             SecretProvider::Op,
+            SecretProvider::Vault,
         ] {
             let yaml = serde_yaml_ng::to_string(&provider).unwrap();
             let parsed: SecretProvider = serde_yaml_ng::from_str(&yaml).unwrap();
@@ -309,6 +313,7 @@ mod tests {
         assert_eq!(SecretProvider::File.to_string(), "file");
         assert_eq!(SecretProvider::Sops.to_string(), "sops");
         assert_eq!(SecretProvider::Op.to_string(), "op");
+        assert_eq!(SecretProvider::Vault.to_string(), "vault");
     }
 
     #[test]
@@ -0,0 +1,88 @@
+//! FJ-3311: HashiCorp Vault secret provider config.
+//!
+//! Defines `secrets.vault` in forjar.yaml, used when
+//! `secrets.provider: vault`.
+
+use serde::{Deserialize, Serialize};
+
+/// FJ-3311: Vault connection, KV v2 location, auth and transit settings.
+///
+/// ```yaml
+/// secrets:
+///   provider: vault
+///   vault:
+///     address: https://vault.internal:8200
+///     mount: secret
+///     path: forjar/prod
+///     auth:
+///       method: approle
+///       role_id: 6a1c...
+///     transit:
+///       key: forjar
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultConfig {
+    /// Server URL; defaults to `VAULT_ADDR`.
+    #[serde(default)]
+    pub address: Option<String>,
+
+    /// Enterprise namespace; defaults to `VAULT_NAMESPACE`.
+    #[serde(default)]
+    pub namespace: Option<String>,
+
+    /// KV v2 mount (default: `secret`).
+    #[serde(default)]
+    pub mount: Option<String>,
+
+    /// Path under the mount that secret keys are relative to.
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// How forjar obtains a client token.
+    #[serde(default)]
+    pub auth: VaultAuthConfig,
+
+    /// Decrypt `vault:v1:...` values with this transit key.
+    #[serde(default)]
+    pub transit: Option<VaultTransitConfig>,
+}
+
+/// FJ-3311: Vault auth method.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultAuthConfig {
+    /// "token" (default), "approle" or "jwt".
+    #[serde(default)]
+    pub method: Option<String>,
+
+    /// Auth mount path (default: the method name).
+    #[serde(default)]
+    pub mount: Option<String>,
+
+    /// AppRole role ID; defaults to `VAULT_ROLE_ID`.
+    #[serde(default)]
+    pub role_id: Option<String>,
+
+    /// JWT auth role.
+    #[serde(default)]
+    pub role: Option<String>,
+
+    /// File holding the JWT (e.g. a CI-issued OIDC token).
+    #[serde(default)]
+    pub jwt_file: Option<String>,
+
+    /// Environment variable holding the token, AppRole secret ID or JWT
+    /// (default: `VAULT_TOKEN`, `VAULT_SECRET_ID` or `VAULT_JWT`).
+    #[serde(default)]
+    pub secret_env: Option<String>,
+}
+
+/// FJ-3311: Transit key used to decrypt ciphertext stored in KV.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultTransitConfig {
+    /// Transit key name.
+    pub key: String,
+
+    /// Transit mount (default: `transit`).
+    #[serde(default)]
+    pub mount: Option<String>,
+}
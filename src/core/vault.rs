@@ -0,0 +1,454 @@
+//! FJ-3311: HashiCorp Vault secret provider (KV v2 and transit).
+//!
+//! Secrets are read from a KV v2 mount over Vault's HTTP API. A key is
+//! `[path/]name[#field][@version]`: `db/postgres#password@3` reads field
+//! `password` of version 3 of `<mount>/data/<path>/db/postgres`. The field
+//! defaults to `value` and the version to the latest.
+//!
+//! Authentication is a token (`VAULT_TOKEN`), AppRole (`role_id` plus
+//! `VAULT_SECRET_ID`) or JWT (`role` plus `VAULT_JWT`). The client token is
+//! renewed once two thirds of its TTL have passed, so long applies keep
+//! resolving; a token that can no longer be renewed is replaced by logging
+//! in again. Values stored as transit ciphertext (`vault:v1:...`) are
+//! decrypted with the configured transit key.
+//!
+//! Every resolve and renewal is appended to the secret audit log with the
+//! value's BLAKE3 hash and the KV version, never the value.
+
+use super::fetch::http_request;
+use super::secret_audit;
+use super::types::VaultConfig;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// A secret read from KV v2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultSecret {
+    pub value: String,
+    /// KV v2 version the value came from.
+    pub version: u64,
+}
+
+/// A parsed `[path/]name[#field][@version]` key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultKey {
+    pub path: String,
+    pub field: String,
+    pub version: Option<u64>,
+}
+
+impl VaultKey {
+    /// Parse a template secret key.
+    pub fn parse(key: &str) -> Result<Self, String> {
+        let (rest, version) = match key.rsplit_once('@') {
+            Some((rest, v)) => {
+                let v = v
+                    .parse::<u64>()
+                    .map_err(|_| format!("invalid Vault version in secret key '{key}'"))?;
+                (rest, Some(v))
+            }
+            None => (key, None),
+        };
+        let (path, field) = rest.split_once('#').unwrap_or((rest, "value"));
+        let path = path.trim_matches('/');
+        if path.is_empty() || field.is_empty() || path.split('/').any(|s| s.is_empty() || s == "..")
+        {
+            return Err(format!("invalid Vault secret key '{key}'"));
+        }
+        Ok(Self {
+            path: path.to_string(),
+            field: field.to_string(),
+            version,
+        })
+    }
+}
+
+/// A client token and its lease.
+struct Session {
+    token: String,
+    renewable: bool,
+    /// Lease in seconds; 0 never expires.
+    ttl: u64,
+    obtained: Instant,
+}
+
+/// Whether a token `elapsed` into a `ttl`-second lease should be renewed.
+pub fn renewal_due(elapsed: Duration, ttl: u64) -> bool {
+    ttl > 0 && elapsed.as_secs_f64() * 3.0 >= ttl as f64 * 2.0
+}
+
+/// Vault HTTP API client for one configuration.
+pub struct VaultClient {
+    address: String,
+    config: VaultConfig,
+    session: Mutex<Option<Session>>,
+    audit_dir: Option<PathBuf>,
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+fn audit_dir_slot() -> &'static Mutex<Option<PathBuf>> {
+    static AUDIT_DIR: OnceLock<Mutex<Option<PathBuf>>> = OnceLock::new();
+    AUDIT_DIR.get_or_init(|| Mutex::new(None))
+}
+
+/// Record Vault accesses made while resolving templates in `state_dir`'s
+/// secret audit log.
+pub fn set_audit_dir(state_dir: Option<&Path>) {
+    if let Ok(mut dir) = audit_dir_slot().lock() {
+        *dir = state_dir.map(Path::to_path_buf);
+    }
+}
+
+impl VaultClient {
+    /// Client for `config`; the address falls back to `VAULT_ADDR`.
+    pub fn new(config: &VaultConfig) -> Result<Self, String> {
+        let address = config
+            .address
+            .clone()
+            .or_else(|| env("VAULT_ADDR"))
+            .ok_or("vault: no address (set secrets.vault.address or VAULT_ADDR)")?;
+        Ok(Self {
+            address: address.trim_end_matches('/').to_string(),
+            config: config.clone(),
+            session: Mutex::new(None),
+            audit_dir: None,
+        })
+    }
+
+    /// Append audit events to `state_dir` instead of the process-wide
+    /// [`set_audit_dir`] directory.
+    pub fn with_audit(mut self, state_dir: &Path) -> Self {
+        self.audit_dir = Some(state_dir.to_path_buf());
+        self
+    }
+
+    fn audit(&self, event: secret_audit::SecretAccessEvent) {
+        let global = audit_dir_slot().lock().ok().and_then(|d| d.clone());
+        if let Some(dir) = self.audit_dir.clone().or(global) {
+            let _ = secret_audit::append_audit(&dir, &event);
+        }
+    }
+
+    fn mount(&self) -> &str {
+        self.config
+            .mount
+            .as_deref()
+            .unwrap_or("secret")
+            .trim_matches('/')
+    }
+
+    fn auth_method(&self) -> &str {
+        self.config.auth.method.as_deref().unwrap_or("token")
+    }
+
+    /// Send a request; returns the status and the JSON body (`Null` when empty).
+    fn send(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&Value>,
+        token: Option<&str>,
+    ) -> Result<(u16, Value), String> {
+        let mut headers = vec!["Accept: application/json".to_string()];
+        if let Some(token) = token {
+            headers.push(format!("X-Vault-Token: {token}"));
+        }
+        if let Some(ns) = self
+            .config
+            .namespace
+            .clone()
+            .or_else(|| env("VAULT_NAMESPACE"))
+        {
+            headers.push(format!("X-Vault-Namespace: {ns}"));
+        }
+        let payload = body.map(|b| b.to_string());
+        if payload.is_some() {
+            headers.push("Content-Type: application/json".to_string());
+        }
+        let url = format!("{}/v1/{path}", self.address);
+        let response = http_request(
+            method,
+            &url,
+            &headers,
+            payload.as_deref().map(str::as_bytes),
+        )
+        .map_err(|e| format!("vault: {method} /v1/{path}: {e}"))?;
+        let json = if response.body.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&response.body)
+                .map_err(|e| format!("vault: {method} /v1/{path}: invalid JSON: {e}"))?
+        };
+        Ok((response.status, json))
+    }
+
+    /// `send`, failing on any non-2xx status with Vault's error list.
+    fn call(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&Value>,
+        token: Option<&str>,
+    ) -> Result<Value, String> {
+        let (status, json) = self.send(method, path, body, token)?;
+        if (200..300).contains(&status) {
+            return Ok(json);
+        }
+        Err(format!(
+            "vault: {method} /v1/{path}: HTTP {status}: {}",
+            vault_errors(&json)
+        ))
+    }
+
+    /// The token, AppRole secret ID or JWT from `auth.secret_env`, else
+    /// from `default`.
+    fn credential(&self, default: &str) -> Result<String, String> {
+        let var = self.config.auth.secret_env.as_deref().unwrap_or(default);
+        env(var).ok_or_else(|| format!("vault: {} auth needs {var}", self.auth_method()))
+    }
+
+    /// Log in with the configured auth method.
+    fn login(&self) -> Result<Session, String> {
+        let auth = &self.config.auth;
+        let method = self.auth_method();
+        let (mount, body) = match method {
+            "token" => {
+                let token = self.credential("VAULT_TOKEN")?;
+                let lookup = self.call("GET", "auth/token/lookup-self", None, Some(&token))?;
+                return Ok(Session {
+                    token,
+                    renewable: lookup["data"]["renewable"].as_bool().unwrap_or(false),
+                    ttl: lookup["data"]["ttl"].as_u64().unwrap_or(0),
+                    obtained: Instant::now(),
+                });
+            }
+            "approle" => {
+                let role_id = auth
+                    .role_id
+                    .clone()
+                    .or_else(|| env("VAULT_ROLE_ID"))
+                    .ok_or("vault: approle auth needs auth.role_id or VAULT_ROLE_ID")?;
+                let secret_id = self.credential("VAULT_SECRET_ID")?;
+                (
+                    "approle",
+                    json!({ "role_id": role_id, "secret_id": secret_id }),
+                )
+            }
+            "jwt" => {
+                let role = auth.role.clone().ok_or("vault: jwt auth needs auth.role")?;
+                let jwt = match auth.jwt_file {
+                    Some(ref file) => std::fs::read_to_string(file)
+                        .map_err(|e| format!("vault: read {file}: {e}"))?
+                        .trim()
+                        .to_string(),
+                    None => self.credential("VAULT_JWT")?,
+                };
+                ("jwt", json!({ "role": role, "jwt": jwt }))
+            }
+            other => return Err(format!("vault: unknown auth method '{other}'")),
+        };
+        let mount = auth.mount.as_deref().unwrap_or(mount).trim_matches('/');
+        let reply = self.call("POST", &format!("auth/{mount}/login"), Some(&body), None)?;
+        session_from_auth(&reply)
+    }
+
+    /// Renew the client token. Returns the new lease in seconds.
+    pub fn renew(&self) -> Result<u64, String> {
+        let mut slot = self
+            .session
+            .lock()
+            .map_err(|_| "vault: session lock poisoned")?;
+        let session = match slot.take() {
+            Some(s) => s,
+            None => self.login()?,
+        };
+        let renewed = self.renew_session(&session)?;
+        let ttl = renewed.ttl;
+        *slot = Some(renewed);
+        Ok(ttl)
+    }
+
+    fn renew_session(&self, session: &Session) -> Result<Session, String> {
+        let reply = self.call(
+            "POST",
+            "auth/token/renew-self",
+            Some(&json!({})),
+            Some(&session.token),
+        )?;
+        let renewed = session_from_auth(&reply)?;
+        self.audit(secret_audit::make_renew_event(
+            &format!("auth/{}", self.auth_method()),
+            "vault",
+        ));
+        Ok(renewed)
+    }
+
+    /// A usable client token: logs in on first use, renews when due, and
+    /// logs in again once renewal is no longer possible.
+    fn token(&self) -> Result<String, String> {
+        let mut slot = self
+            .session
+            .lock()
+            .map_err(|_| "vault: session lock poisoned")?;
+        if let Some(session) = slot.take() {
+            let elapsed = session.obtained.elapsed();
+            if !renewal_due(elapsed, session.ttl) {
+                let token = session.token.clone();
+                *slot = Some(session);
+                return Ok(token);
+            }
+            if session.renewable {
+                if let Ok(renewed) = self.renew_session(&session) {
+                    let token = renewed.token.clone();
+                    *slot = Some(renewed);
+                    return Ok(token);
+                }
+            }
+            // Renewal failed or isn't allowed: keep the token until it expires.
+            if elapsed.as_secs() < session.ttl {
+                let token = session.token.clone();
+                *slot = Some(session);
+                return Ok(token);
+            }
+        }
+        let session = self.login()?;
+        let token = session.token.clone();
+        *slot = Some(session);
+        Ok(token)
+    }
+
+    /// Read `key` from KV v2. `None` when the secret or field doesn't exist.
+    pub fn read(&self, key: &str) -> Result<Option<VaultSecret>, String> {
+        let parsed = VaultKey::parse(key)?;
+        let token = self.token()?;
+        let prefix = self.config.path.as_deref().unwrap_or("").trim_matches('/');
+        let full = if prefix.is_empty() {
+            parsed.path.clone()
+        } else {
+            format!("{prefix}/{}", parsed.path)
+        };
+        let mut api = format!("{}/data/{full}", self.mount());
+        if let Some(version) = parsed.version {
+            api.push_str(&format!("?version={version}"));
+        }
+        let (status, reply) = self.send("GET", &api, None, Some(&token))?;
+        if status == 404 {
+            return Ok(None);
+        }
+        if !(200..300).contains(&status) {
+            return Err(format!(
+                "vault: GET /v1/{api}: HTTP {status}: {}",
+                vault_errors(&reply)
+            ));
+        }
+        let data = &reply["data"];
+        let Some(raw) = data["data"].get(&parsed.field) else {
+            return Ok(None);
+        };
+        let raw = match raw {
+            Value::String(s) => s.clone(),
+            Value::Null => return Ok(None),
+            other => other.to_string(),
+        };
+        let value = if raw.starts_with("vault:v") && self.config.transit.is_some() {
+            self.transit_decrypt(&raw)?
+        } else {
+            raw
+        };
+        let version = data["metadata"]["version"].as_u64().unwrap_or(0);
+        self.audit(
+            secret_audit::make_resolve_event(
+                key,
+                "vault",
+                &blake3::hash(value.as_bytes()).to_hex(),
+                None,
+            )
+            .with_version(version),
+        );
+        Ok(Some(VaultSecret { value, version }))
+    }
+
+    /// Decrypt transit `ciphertext` with the configured transit key.
+    pub fn transit_decrypt(&self, ciphertext: &str) -> Result<String, String> {
+        let transit = self
+            .config
+            .transit
+            .as_ref()
+            .ok_or("vault: no transit key configured (secrets.vault.transit)")?;
+        let mount = transit
+            .mount
+            .as_deref()
+            .unwrap_or("transit")
+            .trim_matches('/');
+        let token = self.token()?;
+        let reply = self.call(
+            "POST",
+            &format!("{mount}/decrypt/{}", transit.key),
+            Some(&json!({ "ciphertext": ciphertext })),
+            Some(&token),
+        )?;
+        let b64 = reply["data"]["plaintext"]
+            .as_str()
+            .ok_or("vault: transit decrypt returned no plaintext")?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(b64)
+            .map_err(|e| format!("vault: transit plaintext: {e}"))?;
+        String::from_utf8(bytes).map_err(|_| "vault: transit plaintext is not UTF-8".to_string())
+    }
+}
+
+fn session_from_auth(reply: &Value) -> Result<Session, String> {
+    let auth = &reply["auth"];
+    let token = auth["client_token"]
+        .as_str()
+        .ok_or("vault: login response has no client_token")?;
+    Ok(Session {
+        token: token.to_string(),
+        renewable: auth["renewable"].as_bool().unwrap_or(false),
+        ttl: auth["lease_duration"].as_u64().unwrap_or(0),
+        obtained: Instant::now(),
+    })
+}
+
+fn vault_errors(json: &Value) -> String {
+    match json["errors"].as_array() {
+        Some(errors) if !errors.is_empty() => errors
+            .iter()
+            .map(|e| e.as_str().map_or_else(|| e.to_string(), str::to_string))
+            .collect::<Vec<_>>()
+            .join("; "),
+        _ => "no error detail".to_string(),
+    }
+}
+
+/// Shared client for `config`, so the token and its renewals carry over
+/// between template resolutions.
+pub fn client_for(config: &VaultConfig) -> Result<Arc<VaultClient>, String> {
+    static CLIENTS: OnceLock<Mutex<HashMap<String, Arc<VaultClient>>>> = OnceLock::new();
+    let id = serde_json::to_string(config).map_err(|e| format!("vault config: {e}"))?;
+    let mut clients = CLIENTS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .map_err(|_| "vault: client cache poisoned")?;
+    if let Some(client) = clients.get(&id) {
+        return Ok(client.clone());
+    }
+    let client = Arc::new(VaultClient::new(config)?);
+    clients.insert(id, client.clone());
+    Ok(client)
+}
+
+/// Resolve `key` with the shared client for `config`.
+pub fn resolve(key: &str, config: &VaultConfig) -> Result<String, String> {
+    client_for(config)?
+        .read(key)?
+        .map(|s| s.value)
+        .ok_or_else(|| format!("secret '{key}' not found in Vault"))
+}
@@ -0,0 +1,165 @@
+//! AppArmor profile resource handler.
+//!
+//! `name` is the profile name as `aa-status` reports it (`/usr/sbin/nginx`
+//! or `nginx`). The profile file lives at `path`, by default under
+//! `/etc/apparmor.d` in the usual `usr.sbin.nginx` naming, and is written
+//! from `content` when set; otherwise it is expected to be managed by
+//! another resource (a `file`, a package).
+//!
+//! `state` selects the mode: `enforce` (default), `complain`, `disabled` or
+//! `absent`. Modes persist across reboots the same way `aa-enforce`,
+//! `aa-complain` and `aa-disable` persist them (links in
+//! `/etc/apparmor.d/force-complain` and `/etc/apparmor.d/disable`), but only
+//! `apparmor_parser` is needed on the target. Drift comes from
+//! `aa-status --json` plus the profile file's digest.
+
+use crate::core::types::Resource;
+
+const FORCE_COMPLAIN_DIR: &str = "/etc/apparmor.d/force-complain";
+const DISABLE_DIR: &str = "/etc/apparmor.d/disable";
+
+/// Shell function printing the loaded mode of profile `$1` (empty when not
+/// loaded), read from `aa-status --json`.
+const AA_MODE_FN: &str = "aa_mode() {\n\
+    \x20 aa-status --json 2>/dev/null | grep -o \"\\\"$1\\\": *\\\"[a-z]*\\\"\" | sed 's/.*\"\\([a-z]*\\)\"$/\\1/' || true\n\
+    }";
+
+fn profile_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("unknown")
+}
+
+/// Desired mode: `enforce`, `complain`, `disabled` or `absent`.
+pub fn profile_mode(resource: &Resource) -> &str {
+    resource.state.as_deref().unwrap_or("enforce")
+}
+
+/// Profile file: `path`, or `/etc/apparmor.d/<name>` with the leading `/`
+/// dropped and the other slashes turned into dots.
+pub fn profile_path(resource: &Resource) -> String {
+    match resource.path {
+        Some(ref path) => path.clone(),
+        None => format!(
+            "/etc/apparmor.d/{}",
+            profile_name(resource)
+                .trim_start_matches('/')
+                .replace('/', ".")
+        ),
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Basic-regex escape of a profile name for the `aa_mode` grep.
+fn grep_escape(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '.' | '[' | ']' | '*' | '^' | '$' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Check script: is the profile loaded, and in which mode?
+pub fn check_script(resource: &Resource) -> String {
+    let name = profile_name(resource);
+    format!(
+        "set -euo pipefail\n\
+         {AA_MODE_FN}\n\
+         MODE=\"$(aa_mode '{}')\"\n\
+         if [ -n \"$MODE\" ]; then echo \"exists:{name}:$MODE\"; else echo 'missing:{name}'; fi",
+        grep_escape(name)
+    )
+}
+
+/// Write `content` to the profile file after `apparmor_parser` accepts it.
+fn write_lines(path: &str, content: &str) -> Vec<String> {
+    vec![
+        "NEW=\"$(mktemp)\"".to_string(),
+        format!("cat > \"$NEW\" <<'FORJAR_EOF'\n{content}\nFORJAR_EOF"),
+        "apparmor_parser --skip-kernel-load --skip-cache \"$NEW\"".to_string(),
+        format!(
+            "if cmp -s \"$NEW\" '{path}'; then unlink \"$NEW\"; else\n\
+             \x20 install -m 0644 \"$NEW\" '{path}'\n\
+             \x20 unlink \"$NEW\"\n\
+             \x20 echo 'changed:profile'\n\
+             fi"
+        ),
+    ]
+}
+
+fn unlink_if_link(link: &str) -> String {
+    format!("if [ -L '{link}' ]; then unlink '{link}'; fi")
+}
+
+/// Apply script: write the profile, then load it in the declared mode.
+pub fn apply_script(resource: &Resource) -> String {
+    let name = profile_name(resource);
+    let path = profile_path(resource);
+    let base = file_name(&path);
+    let complain_link = format!("{FORCE_COMPLAIN_DIR}/{base}");
+    let disable_link = format!("{DISABLE_DIR}/{base}");
+    let mode = profile_mode(resource);
+    let mut lines = vec!["set -euo pipefail".to_string(), AA_MODE_FN.to_string()];
+    let unload = format!(
+        "if [ -n \"$(aa_mode '{}')\" ]; then apparmor_parser -R '{path}'; fi",
+        grep_escape(name)
+    );
+
+    if mode == "absent" {
+        lines.push(format!("if [ -f '{path}' ]; then"));
+        lines.push(format!("  {unload}"));
+        lines.push(format!("  unlink '{path}'"));
+        lines.push("fi".to_string());
+        lines.push(unlink_if_link(&complain_link));
+        lines.push(unlink_if_link(&disable_link));
+        lines.push(format!("echo 'removed:{name}'"));
+        return lines.join("\n");
+    }
+
+    if let Some(ref content) = resource.content {
+        lines.extend(write_lines(&path, content));
+    }
+    lines.push(format!(
+        "if [ ! -f '{path}' ]; then echo 'apparmor profile {path} not found' >&2; exit 1; fi"
+    ));
+    match mode {
+        "complain" => {
+            lines.push(unlink_if_link(&disable_link));
+            lines.push(format!("mkdir -p '{FORCE_COMPLAIN_DIR}'"));
+            lines.push(format!("ln -sfn '{path}' '{complain_link}'"));
+            lines.push(format!("apparmor_parser -r -W -C '{path}'"));
+        }
+        "disabled" => {
+            lines.push(unlink_if_link(&complain_link));
+            lines.push(format!("mkdir -p '{DISABLE_DIR}'"));
+            lines.push(format!("ln -sfn '{path}' '{disable_link}'"));
+            lines.push(unload);
+        }
+        _ => {
+            lines.push(unlink_if_link(&complain_link));
+            lines.push(unlink_if_link(&disable_link));
+            lines.push(format!("apparmor_parser -r -W '{path}'"));
+        }
+    }
+    lines.push(format!("echo '{mode}:{name}'"));
+    lines.join("\n")
+}
+
+/// State query: loaded mode (`unloaded` when not loaded) and the SHA-256 of
+/// the profile file.
+pub fn state_query_script(resource: &Resource) -> String {
+    let name = profile_name(resource);
+    let path = profile_path(resource);
+    format!(
+        "set -euo pipefail\n\
+         {AA_MODE_FN}\n\
+         MODE=\"$(aa_mode '{}')\"\n\
+         if [ -f '{path}' ]; then SUM=\"$(sha256sum '{path}' | cut -c1-64)\"; else SUM=none; fi\n\
+         echo \"apparmor_profile={name}:mode=${{MODE:-unloaded}}:sha256=$SUM\"",
+        grep_escape(name)
+    )
+}
@@ -0,0 +1,235 @@
+//! Binary release resource handler.
+//!
+//! Installs a single binary from a release published on GitHub, GitLab,
+//! Gitea/Forgejo or a static index. The release is picked on the control
+//! machine (`core::release_source`) from a version constraint; the install
+//! script generated here downloads the asset, checks its SHA-256 and
+//! optional minisign/cosign signature, and swaps the binary in atomically.
+//! The previous binary is kept as `.<binary>.forjar-prev` and restored when
+//! the new one fails a `--version` smoke check. `github_release` is this
+//! resource with the GitHub provider (see `resources::github_release`).
+//!
+//! # YAML example
+//!
+//! ```yaml
+//! ripgrep:
+//!   type: binary_release
+//!   machine: web
+//!   provider: github
+//!   repo: BurntSushi/ripgrep
+//!   version: "^14.1"
+//!   asset_pattern: "*x86_64-unknown-linux-musl.tar.gz"
+//!   binary: rg
+//! ```
+
+use crate::core::release_source::{ResolvedRelease, SignatureKind};
+use crate::core::types::Resource;
+
+/// Paths of the live binary and its bookkeeping files.
+struct Paths {
+    bin: String,
+    prev: String,
+    new: String,
+    marker: String,
+    dir: String,
+}
+
+fn paths(resource: &Resource) -> Paths {
+    let dir = resource
+        .install_dir
+        .as_deref()
+        .unwrap_or("/usr/local/bin")
+        .trim_end_matches('/')
+        .to_string();
+    let binary = resource.binary.as_deref().unwrap_or("unknown");
+    Paths {
+        bin: format!("{dir}/{binary}"),
+        prev: format!("{dir}/.{binary}.forjar-prev"),
+        new: format!("{dir}/.{binary}.forjar-new"),
+        marker: format!("{dir}/.{binary}.forjar-release"),
+        dir,
+    }
+}
+
+fn label(resource: &Resource) -> &str {
+    resource
+        .repo
+        .as_deref()
+        .or(resource.binary.as_deref())
+        .unwrap_or("unknown")
+}
+
+/// Check script: reports the installed release tag from the marker file.
+pub fn check_script(resource: &Resource) -> String {
+    let p = paths(resource);
+    let label = label(resource);
+    format!(
+        "if [ -x '{bin}' ] && [ -f '{marker}' ]; then\n\
+         \x20 echo \"installed:{label}:$(head -n 1 '{marker}')\"\n\
+         else\n\
+         \x20 echo 'missing:{label}'\n\
+         fi",
+        bin = p.bin,
+        marker = p.marker,
+    )
+}
+
+/// Apply script without a resolved release: removal works offline, an
+/// install needs `forjar apply` to resolve the release first.
+pub fn apply_script(resource: &Resource) -> String {
+    if resource.state.as_deref() == Some("absent") {
+        return absent_script(resource);
+    }
+    format!(
+        "echo \"ERROR: {} {} is resolved against its release source by forjar apply\" >&2\nexit 1",
+        resource.resource_type,
+        label(resource)
+    )
+}
+
+/// Remove the binary and its bookkeeping files.
+pub fn absent_script(resource: &Resource) -> String {
+    let p = paths(resource);
+    let mut lines = vec!["set -euo pipefail".to_string()];
+    for path in [&p.bin, &p.prev, &p.marker] {
+        lines.push(format!("if [ -e '{path}' ]; then unlink '{path}'; fi"));
+    }
+    lines.push(format!("echo 'removed:{}'", label(resource)));
+    lines.join("\n")
+}
+
+/// Shell verifying the downloaded `$ASSET` against its detached signature.
+fn signature_lines(kind: SignatureKind, url: &str, key: &str, asset: &str) -> String {
+    let (tool, verify) = match kind {
+        SignatureKind::Minisign => (
+            "minisign",
+            format!("minisign -Vm \"$ASSET\" -x \"$ASSET.sig\" -P '{key}' >/dev/null"),
+        ),
+        SignatureKind::Cosign => (
+            "cosign",
+            format!(
+                "cosign verify-blob --key '{key}' --signature \"$ASSET.sig\" \"$ASSET\" >/dev/null 2>&1"
+            ),
+        ),
+    };
+    format!(
+        "if ! command -v {tool} >/dev/null 2>&1; then\n\
+         \x20 echo \"ERROR: {tool} is required to verify {asset}\" >&2\n\
+         \x20 exit 1\n\
+         fi\n\
+         curl -fsSL --retry 3 -o \"$ASSET.sig\" '{url}'\n\
+         if ! {verify}; then\n\
+         \x20 echo \"SIGNATURE VERIFICATION FAILED: {asset}\" >&2\n\
+         \x20 exit 1\n\
+         fi"
+    )
+}
+
+/// Install script for a resolved release.
+pub fn install_script(resource: &Resource, release: &ResolvedRelease) -> String {
+    let p = paths(resource);
+    let label = label(resource);
+    let binary = resource.binary.as_deref().unwrap_or("unknown");
+    let tag = &release.tag;
+    let asset = &release.asset.name;
+    let mut lines = vec![
+        "set -euo pipefail".to_string(),
+        format!("BIN='{}'", p.bin),
+        format!("PREV='{}'", p.prev),
+        format!(
+            "CURRENT=\"$(head -n 1 '{marker}' 2>/dev/null || true)\"\n\
+             CURRENT_SHA=\"$(sed -n 2p '{marker}' 2>/dev/null || true)\"",
+            marker = p.marker
+        ),
+        // A re-published tag (`nightly`) has a new checksum
+        format!(
+            "if [ -x \"$BIN\" ] && [ \"$CURRENT\" = '{tag}' ] && [ \"$CURRENT_SHA\" = '{}' ]; then\n\
+             \x20 echo \"FORJAR_OUTPUT version={}\"\n\
+             \x20 echo 'unchanged:{label}:{tag}'\n\
+             \x20 exit 0\n\
+             fi",
+            release.sha256,
+            release.version
+        ),
+        "WORK=\"$(mktemp -d)\"".to_string(),
+        "trap 'rm -rf \"${WORK:?}\"' EXIT".to_string(),
+        format!("ASSET=\"$WORK/{asset}\""),
+        format!("curl -fsSL --retry 3 -o \"$ASSET\" '{}'", release.asset.url),
+        format!(
+            "SUM=\"$(sha256sum \"$ASSET\" | cut -d' ' -f1)\"\n\
+             if [ \"$SUM\" != '{sha}' ]; then\n\
+             \x20 echo \"CHECKSUM MISMATCH: {asset} expected {sha} got $SUM\" >&2\n\
+             \x20 exit 1\n\
+             fi",
+            sha = release.sha256
+        ),
+    ];
+    if let Some((kind, ref url)) = release.signature {
+        let key = resource.public_key.as_deref().unwrap_or("");
+        lines.push(signature_lines(kind, url, key, asset));
+    }
+    lines.push(format!(
+        "case \"$ASSET\" in\n\
+         \x20 *.tar.gz|*.tgz|*.tar.xz|*.tar)\n\
+         \x20   tar xf \"$ASSET\" -C \"$WORK\"\n\
+         \x20   EXTRACTED=\"$(find \"$WORK\" -name '{binary}' -type f | head -n 1)\"\n\
+         \x20   ;;\n\
+         \x20 *.zip)\n\
+         \x20   unzip -oq \"$ASSET\" -d \"$WORK\"\n\
+         \x20   EXTRACTED=\"$(find \"$WORK\" -name '{binary}' -type f | head -n 1)\"\n\
+         \x20   ;;\n\
+         \x20 *)\n\
+         \x20   EXTRACTED=\"$ASSET\"\n\
+         \x20   ;;\n\
+         esac\n\
+         if [ -z \"$EXTRACTED\" ]; then\n\
+         \x20 echo \"ERROR: binary '{binary}' not found in {asset}\" >&2\n\
+         \x20 exit 1\n\
+         fi"
+    ));
+    lines.push(format!(
+        "mkdir -p '{dir}'\n\
+         cp \"$EXTRACTED\" '{new}'\n\
+         chmod 0755 '{new}'\n\
+         if [ -f \"$BIN\" ]; then cp -p \"$BIN\" \"$PREV\"; fi\n\
+         mv -f '{new}' \"$BIN\"\n\
+         if ! \"$BIN\" --version >/dev/null 2>&1; then\n\
+         \x20 if [ -f \"$PREV\" ]; then\n\
+         \x20   mv -f \"$PREV\" \"$BIN\"\n\
+         \x20   echo \"ROLLBACK: restored the previous {binary}\" >&2\n\
+         \x20 else\n\
+         \x20   unlink \"$BIN\"\n\
+         \x20 fi\n\
+         \x20 echo \"ERROR: {binary} {tag} failed its --version smoke check\" >&2\n\
+         \x20 exit 1\n\
+         fi\n\
+         printf '%s\\n' '{tag}' '{sha}' > '{marker}'",
+        dir = p.dir,
+        new = p.new,
+        marker = p.marker,
+        sha = release.sha256,
+    ));
+    lines.push(format!(
+        "echo \"FORJAR_OUTPUT version={}\"",
+        release.version
+    ));
+    lines.push(format!("echo 'installed:{label}:{tag}'"));
+    lines.join("\n")
+}
+
+/// State query: installed tag plus the binary's SHA-256, so a binary
+/// replaced outside forjar shows up as drift.
+pub fn state_query_script(resource: &Resource) -> String {
+    let p = paths(resource);
+    let label = label(resource);
+    format!(
+        "if [ -x '{bin}' ]; then\n\
+         \x20 TAG=\"$(head -n 1 '{marker}' 2>/dev/null || echo unmanaged)\"\n\
+         \x20 echo \"binary_release={label}:$TAG:$(sha256sum '{bin}' | cut -d' ' -f1)\"\n\
+         else\n\
+         \x20 echo 'binary_release=MISSING:{label}'\n\
+         fi",
+        bin = p.bin,
+        marker = p.marker,
+    )
+}
@@ -0,0 +1,326 @@
+//! TLS certificate resource handler.
+//!
+//! The private key is generated on the target (`openssl genpkey`) and never
+//! leaves it. How the certificate is produced depends on `issuer`:
+//!
+//! - `self-signed` (default): the apply script signs it on the target.
+//! - `local` / `ca`: the apply script prints a CSR; the executor signs it on
+//!   the control machine (see [`crate::core::pki`]) and runs
+//!   [`install_script`] to deploy the certificate and CA chain.
+//!
+//! A certificate is kept while it matches the key, the issuance parameters
+//! (recorded in a `<path>.forjar` sidecar) and is not within `renew_before`
+//! of expiry. Every successful apply exports `FORJAR_OUTPUT not_after=...`,
+//! which the lock keeps for renewal planning, `status` and `drift`.
+
+use crate::core::types::Resource;
+
+/// Default certificate lifetime in days.
+pub const DEFAULT_VALIDITY_DAYS: u32 = 365;
+
+/// Default renewal window.
+pub const DEFAULT_RENEW_BEFORE: &str = "30d";
+
+/// PEM header of a certificate signing request.
+pub const CSR_BEGIN: &str = "-----BEGIN CERTIFICATE REQUEST-----";
+const CSR_END: &str = "-----END CERTIFICATE REQUEST-----";
+
+fn cert_path(resource: &Resource) -> &str {
+    resource
+        .path
+        .as_deref()
+        .unwrap_or("/etc/ssl/forjar/cert.pem")
+}
+
+fn key_path(resource: &Resource) -> &str {
+    resource
+        .key_path
+        .as_deref()
+        .unwrap_or("/etc/ssl/forjar/key.pem")
+}
+
+/// Directory part of a path (`.` when it has none).
+fn parent_dir(path: &str) -> &str {
+    match path.rsplit_once('/') {
+        Some(("", _)) => "/",
+        Some((dir, _)) => dir,
+        None => ".",
+    }
+}
+
+/// Issuer: `self-signed`, `local` or `ca`.
+pub fn issuer(resource: &Resource) -> &str {
+    resource.issuer.as_deref().unwrap_or("self-signed")
+}
+
+/// True when the control machine signs the certificate (`local` or `ca`).
+pub fn is_ca_signed(resource: &Resource) -> bool {
+    matches!(issuer(resource), "local" | "ca")
+}
+
+/// Subject common name (`common_name`, else the first SAN).
+pub fn common_name(resource: &Resource) -> &str {
+    resource
+        .common_name
+        .as_deref()
+        .or(resource.sans.first().map(String::as_str))
+        .unwrap_or("localhost")
+}
+
+/// Certificate lifetime in days.
+pub fn validity_days(resource: &Resource) -> u32 {
+    resource.validity_days.unwrap_or(DEFAULT_VALIDITY_DAYS)
+}
+
+/// Renewal window in seconds (`renew_before`, default 30 days).
+pub fn renew_before_secs(resource: &Resource) -> u64 {
+    resource
+        .renew_before
+        .as_deref()
+        .and_then(parse_window)
+        .unwrap_or(30 * 86400)
+}
+
+/// Parse a window like "30d", "72h", "90m" or "3600s" into seconds.
+pub fn parse_window(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let unit = s.chars().last()?;
+    let n: u64 = s[..s.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        's' => Some(n),
+        'm' => Some(n * 60),
+        'h' => Some(n * 3600),
+        'd' => Some(n * 86400),
+        _ => None,
+    }
+}
+
+/// SAN entries in OpenSSL form (`DNS:name`, `IP:addr`).
+pub fn san_entries(resource: &Resource) -> Vec<String> {
+    resource
+        .sans
+        .iter()
+        .map(|san| {
+            if san.parse::<std::net::IpAddr>().is_ok() {
+                format!("IP:{san}")
+            } else {
+                format!("DNS:{san}")
+            }
+        })
+        .collect()
+}
+
+/// Fingerprint of everything that determines the issued certificate.
+///
+/// Stored next to the certificate; a mismatch forces re-issue.
+pub fn params_fingerprint(resource: &Resource) -> String {
+    let ca = match issuer(resource) {
+        "local" => resource.ca_name.as_deref().unwrap_or("forjar"),
+        "ca" => resource.ca_cert.as_deref().unwrap_or(""),
+        _ => "",
+    };
+    let material = format!(
+        "{}\n{ca}\n{}\n{}\n{}\n{}",
+        issuer(resource),
+        common_name(resource),
+        resource.sans.join(","),
+        validity_days(resource),
+        resource.key_type.as_deref().unwrap_or("ec"),
+    );
+    blake3::hash(material.as_bytes()).to_hex()[..16].to_string()
+}
+
+/// Extract the CSR printed by a CA-signed apply script.
+pub fn extract_csr(stdout: &str) -> Option<&str> {
+    let start = stdout.find(CSR_BEGIN)?;
+    let end = stdout[start..].find(CSR_END)? + start + CSR_END.len();
+    Some(&stdout[start..end])
+}
+
+/// Generate the key on first apply.
+fn keygen_line(resource: &Resource) -> String {
+    let alg = match resource.key_type.as_deref() {
+        Some("rsa") => "-algorithm RSA -pkeyopt rsa_keygen_bits:3072",
+        _ => "-algorithm EC -pkeyopt ec_paramgen_curve:P-256",
+    };
+    format!("if [ ! -s \"$KEY\" ]; then openssl genpkey {alg} -out \"$KEY\"; fi")
+}
+
+/// `forjar_cert_current`: certificate matches key and parameters and is
+/// not inside the renewal window.
+fn current_function(resource: &Resource) -> String {
+    format!(
+        "forjar_cert_current() {{\n\
+         \x20 if [ ! -s \"$CERT\" ]; then return 1; fi\n\
+         \x20 PARAMS=\"$(cat \"$CERT.forjar\" 2>/dev/null || true)\"\n\
+         \x20 if [ \"$PARAMS\" != '{fp}' ]; then return 1; fi\n\
+         \x20 if ! openssl x509 -in \"$CERT\" -noout -checkend {renew} >/dev/null; then return 1; fi\n\
+         \x20 CERTPUB=\"$(openssl x509 -in \"$CERT\" -noout -pubkey)\"\n\
+         \x20 KEYPUB=\"$(openssl pkey -in \"$KEY\" -pubout)\"\n\
+         \x20 [ \"$CERTPUB\" = \"$KEYPUB\" ]\n\
+         }}",
+        fp = params_fingerprint(resource),
+        renew = renew_before_secs(resource),
+    )
+}
+
+/// Script preamble: strict umask, paths, key generation.
+fn preamble(resource: &Resource) -> Vec<String> {
+    let cert = cert_path(resource);
+    let key = key_path(resource);
+    let mut dirs = vec![parent_dir(cert), parent_dir(key)];
+    if let Some(ref chain) = resource.chain_path {
+        dirs.push(parent_dir(chain));
+    }
+    dirs.sort_unstable();
+    dirs.dedup();
+    let dirs: Vec<String> = dirs.iter().map(|d| format!("'{d}'")).collect();
+    vec![
+        "set -euo pipefail".to_string(),
+        "umask 077".to_string(),
+        format!("CERT='{cert}'"),
+        format!("KEY='{key}'"),
+        format!("mkdir -p {}", dirs.join(" ")),
+        keygen_line(resource),
+        current_function(resource),
+    ]
+}
+
+/// Enforce ownership and modes, then export the expiry.
+fn finish_lines(resource: &Resource) -> Vec<String> {
+    let key_mode = resource.mode.as_deref().unwrap_or("0600");
+    let mut lines = vec![
+        format!("chmod {key_mode} \"$KEY\""),
+        "chmod 0644 \"$CERT\"".to_string(),
+    ];
+    if let Some(ref chain) = resource.chain_path {
+        lines.push(format!(
+            "if [ -f '{chain}' ]; then chmod 0644 '{chain}'; fi"
+        ));
+    }
+    let owner = match (&resource.owner, &resource.group) {
+        (Some(o), Some(g)) => Some(format!("{o}:{g}")),
+        (Some(o), None) => Some(o.clone()),
+        (None, Some(g)) => Some(format!(":{g}")),
+        (None, None) => None,
+    };
+    if let Some(owner) = owner {
+        lines.push(format!("chown '{owner}' \"$KEY\" \"$CERT\""));
+        if let Some(ref chain) = resource.chain_path {
+            lines.push(format!(
+                "if [ -f '{chain}' ]; then chown '{owner}' '{chain}'; fi"
+            ));
+        }
+    }
+    lines.push("NOT_AFTER=\"$(openssl x509 -in \"$CERT\" -noout -enddate)\"".to_string());
+    lines.push("echo \"FORJAR_OUTPUT not_after=${NOT_AFTER#notAfter=}\"".to_string());
+    lines
+}
+
+/// Generate shell to check whether the certificate is deployed.
+pub fn check_script(resource: &Resource) -> String {
+    let cert = cert_path(resource);
+    format!(
+        "if [ -s '{cert}' ] && [ -s '{}' ]; then echo 'exists:{cert}'; else echo 'missing:{cert}'; fi",
+        key_path(resource)
+    )
+}
+
+/// Generate shell to converge the certificate.
+///
+/// For CA-signed issuers this is the first phase: it keeps a current
+/// certificate, or prints a CSR for the executor to sign.
+pub fn apply_script(resource: &Resource) -> String {
+    if resource.state.as_deref() == Some("absent") {
+        let mut paths = vec![
+            format!("'{}'", cert_path(resource)),
+            format!("'{}.forjar'", cert_path(resource)),
+            format!("'{}'", key_path(resource)),
+        ];
+        if let Some(ref chain) = resource.chain_path {
+            paths.push(format!("'{chain}'"));
+        }
+        return format!("set -euo pipefail\nrm -f {}", paths.join(" "));
+    }
+
+    let mut lines = preamble(resource);
+    if is_ca_signed(resource) {
+        lines.push(format!(
+            "if ! forjar_cert_current; then\n\
+             \x20 openssl req -new -key \"$KEY\" -subj '/CN={}'\n\
+             \x20 exit 0\n\
+             fi",
+            common_name(resource)
+        ));
+    } else {
+        let san = san_entries(resource);
+        let addext = if san.is_empty() {
+            String::new()
+        } else {
+            format!(" -addext 'subjectAltName={}'", san.join(","))
+        };
+        lines.push(format!(
+            "if forjar_cert_current; then\n\
+             \x20 echo 'unchanged:{cert}'\n\
+             else\n\
+             \x20 openssl req -x509 -new -key \"$KEY\" -subj '/CN={cn}' -days {days}{addext} -out \"$CERT.forjar-new\"\n\
+             \x20 mv \"$CERT.forjar-new\" \"$CERT\"\n\
+             \x20 echo '{fp}' > \"$CERT.forjar\"\n\
+             fi",
+            cert = cert_path(resource),
+            cn = common_name(resource),
+            days = validity_days(resource),
+            fp = params_fingerprint(resource),
+        ));
+    }
+    lines.extend(finish_lines(resource));
+    lines.join("\n")
+}
+
+/// Second phase for CA-signed issuers: deploy the signed certificate and chain.
+pub fn install_script(resource: &Resource, cert_pem: &str, chain_pem: &str) -> String {
+    let mut lines = vec![
+        "set -euo pipefail".to_string(),
+        "umask 077".to_string(),
+        format!("CERT='{}'", cert_path(resource)),
+        format!("KEY='{}'", key_path(resource)),
+        format!(
+            "cat > \"$CERT.forjar-new\" <<'FORJAR_EOF'\n{}\nFORJAR_EOF",
+            cert_pem.trim_end()
+        ),
+        "mv \"$CERT.forjar-new\" \"$CERT\"".to_string(),
+    ];
+    if let Some(ref chain) = resource.chain_path {
+        lines.push(format!(
+            "cat > '{chain}' <<'FORJAR_EOF'\n{}\nFORJAR_EOF",
+            chain_pem.trim_end()
+        ));
+    }
+    lines.push(format!(
+        "echo '{}' > \"$CERT.forjar\"",
+        params_fingerprint(resource)
+    ));
+    lines.extend(finish_lines(resource));
+    lines.join("\n")
+}
+
+/// Generate shell to query certificate state (for BLAKE3 hashing).
+///
+/// Subject, expiry and fingerprint of the deployed certificate plus the
+/// ownership and modes of the files — never the key material.
+pub fn state_query_script(resource: &Resource) -> String {
+    let cert = cert_path(resource);
+    let mut files = vec![format!("'{}'", key_path(resource)), format!("'{cert}'")];
+    if let Some(ref chain) = resource.chain_path {
+        files.push(format!("'{chain}'"));
+    }
+    format!(
+        "if [ -s '{cert}' ]; then\n\
+         \x20 openssl x509 -in '{cert}' -noout -subject -enddate -fingerprint -sha256\n\
+         \x20 stat -c '%n %a %U:%G' {}\n\
+         else\n\
+         \x20 echo 'certificate=MISSING:{cert}'\n\
+         fi",
+        files.join(" ")
+    )
+}
@@ -0,0 +1,330 @@
+//! Multi-container stack resource handler.
+//!
+//! A `container_stack` takes a compose-format spec in `content` and brings
+//! it up with one of two backends:
+//! - `format: compose` (default) — writes the spec to `path` and runs
+//!   `<runtime> compose up -d`, which only recreates changed services.
+//! - `format: quadlet` — renders podman Quadlet `.container`/`.network`/
+//!   `.volume` units (see `resources::quadlet`) and starts them via systemd.
+//!
+//! Every service gets a deterministic container name and a
+//! `forjar.config-hash` label, so the state query can report per-service
+//! drift (running state, image ID, config hash). `state: absent` stops the
+//! services in reverse dependency order before tearing the stack down.
+
+use super::docker::{runtime_bin, CONFIG_HASH_LABEL};
+use super::quadlet;
+use crate::core::types::Resource;
+use crate::tripwire::hasher;
+use serde_yaml_ng::{Mapping, Value};
+
+/// One service parsed from a compose spec.
+#[derive(Debug, Clone)]
+pub struct StackService {
+    /// Service key in the compose `services:` map.
+    pub name: String,
+    /// Image reference.
+    pub image: String,
+    /// Services this one depends on.
+    pub depends_on: Vec<String>,
+    /// Raw service mapping (preserves keys forjar does not interpret).
+    pub spec: Value,
+}
+
+/// A parsed compose-format stack spec.
+#[derive(Debug, Clone)]
+pub struct StackSpec {
+    /// Services in declaration order.
+    pub services: Vec<StackService>,
+    /// Top-level network names.
+    pub networks: Vec<String>,
+    /// Top-level named volume names.
+    pub volumes: Vec<String>,
+    /// The full document, used to render the compose file.
+    pub raw: Value,
+}
+
+/// Parse a compose-format spec.
+pub fn parse_stack(content: &str) -> Result<StackSpec, String> {
+    let raw: Value =
+        serde_yaml_ng::from_str(content).map_err(|e| format!("invalid stack spec: {e}"))?;
+    let services_map = raw
+        .get("services")
+        .and_then(Value::as_mapping)
+        .ok_or("stack spec has no services")?;
+
+    let mut services = Vec::new();
+    for (key, spec) in services_map {
+        let name = key.as_str().ok_or("service names must be strings")?;
+        let image = spec
+            .get("image")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("service '{name}' has no image"))?;
+        services.push(StackService {
+            name: name.to_string(),
+            image: image.to_string(),
+            depends_on: key_list(spec.get("depends_on")),
+            spec: spec.clone(),
+        });
+    }
+    if services.is_empty() {
+        return Err("stack spec has no services".to_string());
+    }
+
+    Ok(StackSpec {
+        services,
+        networks: key_list(raw.get("networks")),
+        volumes: key_list(raw.get("volumes")),
+        raw,
+    })
+}
+
+/// Names from a compose field that is either a list or a map keyed by name.
+pub(crate) fn key_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Sequence(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect(),
+        Some(Value::Mapping(map)) => map
+            .keys()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Service indices in dependency order (dependencies first).
+///
+/// Ties keep declaration order. Fails on unknown dependencies and cycles.
+pub fn service_order(spec: &StackSpec) -> Result<Vec<usize>, String> {
+    let index_of = |name: &str| spec.services.iter().position(|s| s.name == name);
+    let mut order = Vec::with_capacity(spec.services.len());
+    let mut placed = vec![false; spec.services.len()];
+    for svc in &spec.services {
+        for dep in &svc.depends_on {
+            if index_of(dep).is_none() {
+                return Err(format!(
+                    "service '{}' depends on unknown service '{dep}'",
+                    svc.name
+                ));
+            }
+        }
+    }
+    while order.len() < spec.services.len() {
+        let next = (0..spec.services.len()).find(|&i| {
+            !placed[i]
+                && spec.services[i]
+                    .depends_on
+                    .iter()
+                    .all(|d| index_of(d).is_some_and(|j| placed[j]))
+        });
+        match next {
+            Some(i) => {
+                placed[i] = true;
+                order.push(i);
+            }
+            None => return Err("stack services have a dependency cycle".to_string()),
+        }
+    }
+    Ok(order)
+}
+
+/// Services in dependency order, falling back to declaration order.
+fn ordered_services(spec: &StackSpec) -> Vec<&StackService> {
+    match service_order(spec) {
+        Ok(order) => order.into_iter().map(|i| &spec.services[i]).collect(),
+        Err(_) => spec.services.iter().collect(),
+    }
+}
+
+/// BLAKE3 of one service's spec.
+pub fn service_hash(svc: &StackService) -> String {
+    let rendered = serde_yaml_ng::to_string(&svc.spec).unwrap_or_default();
+    hasher::composite_hash(&[&svc.name, &rendered])
+}
+
+/// Container name for a service: explicit `container_name`, else `<project>-<service>`.
+pub fn container_name(project: &str, svc: &StackService) -> String {
+    svc.spec
+        .get("container_name")
+        .and_then(Value::as_str)
+        .map(String::from)
+        .unwrap_or_else(|| format!("{project}-{}", svc.name))
+}
+
+/// Render the compose file with container names and config-hash labels injected.
+pub fn render_compose(spec: &StackSpec, project: &str) -> String {
+    let mut raw = spec.raw.clone();
+    if let Some(services) = raw.get_mut("services").and_then(Value::as_mapping_mut) {
+        for svc in &spec.services {
+            let Some(Value::Mapping(entry)) = services.get_mut(svc.name.as_str()) else {
+                continue;
+            };
+            let name = container_name(project, svc);
+            entry.insert("container_name".into(), Value::String(name));
+            insert_label(entry, CONFIG_HASH_LABEL, &service_hash(svc));
+        }
+    }
+    serde_yaml_ng::to_string(&raw).unwrap_or_default()
+}
+
+/// Add a label to a compose service, honouring list or map label syntax.
+fn insert_label(entry: &mut Mapping, key: &str, value: &str) {
+    match entry.get_mut("labels") {
+        Some(Value::Sequence(items)) => items.push(Value::String(format!("{key}={value}"))),
+        Some(Value::Mapping(map)) => {
+            map.insert(key.into(), value.into());
+        }
+        _ => {
+            let mut map = Mapping::new();
+            map.insert(key.into(), value.into());
+            entry.insert("labels".into(), Value::Mapping(map));
+        }
+    }
+}
+
+/// Project name (`name`, else `stack`).
+fn project_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("stack")
+}
+
+/// Whether the Quadlet backend is selected.
+fn is_quadlet(resource: &Resource) -> bool {
+    resource.format.as_deref() == Some("quadlet")
+}
+
+/// Directory holding the compose file or Quadlet units.
+pub fn stack_dir(resource: &Resource) -> String {
+    match (&resource.path, is_quadlet(resource)) {
+        (Some(p), _) => p.clone(),
+        (None, true) => "/etc/containers/systemd".to_string(),
+        (None, false) => format!("/opt/forjar/stacks/{}", project_name(resource)),
+    }
+}
+
+/// Container CLI for the stack — Quadlet stacks always run under podman.
+fn stack_runtime(resource: &Resource) -> &str {
+    if is_quadlet(resource) {
+        "podman"
+    } else {
+        runtime_bin(resource)
+    }
+}
+
+/// Script that fails the apply with a spec error.
+fn error_script(project: &str, err: &str) -> String {
+    let err = err.replace('\'', "");
+    format!("echo 'ERROR: container_stack {project}: {err}' >&2; exit 1")
+}
+
+/// Generate shell script to check whether every service is running.
+pub fn check_script(resource: &Resource) -> String {
+    let project = project_name(resource);
+    let rt = stack_runtime(resource);
+    let spec = match resource.content.as_deref().map(parse_stack) {
+        Some(Ok(spec)) => spec,
+        _ => return format!("echo 'missing:{project}'"),
+    };
+    let checks: Vec<String> = ordered_services(&spec)
+        .iter()
+        .map(|svc| {
+            let cname = container_name(project, svc);
+            format!("[ \"$({rt} inspect -f '{{{{.State.Running}}}}' '{cname}' 2>/dev/null)\" = 'true' ]")
+        })
+        .collect();
+    format!(
+        "if {}; then echo 'exists:{project}'; else echo 'missing:{project}'; fi",
+        checks.join(" && ")
+    )
+}
+
+/// Generate shell script to bring the stack up or tear it down.
+pub fn apply_script(resource: &Resource) -> String {
+    let project = project_name(resource);
+    let absent = resource.state.as_deref() == Some("absent");
+    let spec = match resource.content.as_deref().map(parse_stack) {
+        Some(Ok(spec)) => spec,
+        Some(Err(e)) if !absent => return error_script(project, &e),
+        None if !absent => return error_script(project, "no content (compose spec)"),
+        _ => return teardown_without_spec(resource),
+    };
+    let services = ordered_services(&spec);
+    match (is_quadlet(resource), absent) {
+        (true, false) => quadlet::apply_units(&spec, &services, project, &stack_dir(resource)),
+        (true, true) => quadlet::remove_units(&spec, &services, project, &stack_dir(resource)),
+        (false, false) => compose_up(resource, &spec),
+        (false, true) => compose_down(resource, &services),
+    }
+}
+
+/// `compose up` after writing the rendered spec.
+fn compose_up(resource: &Resource, spec: &StackSpec) -> String {
+    let project = project_name(resource);
+    let rt = stack_runtime(resource);
+    let dir = stack_dir(resource);
+    let rendered = render_compose(spec, project);
+    format!(
+        "set -euo pipefail\n\
+         mkdir -p '{dir}'\n\
+         cat > '{dir}/compose.yaml' <<'FORJAR_STACK'\n\
+         {rendered}FORJAR_STACK\n\
+         {rt} compose -p '{project}' -f '{dir}/compose.yaml' up -d --remove-orphans"
+    )
+}
+
+/// Stop services in reverse dependency order, then `compose down`.
+fn compose_down(resource: &Resource, services: &[&StackService]) -> String {
+    let project = project_name(resource);
+    let rt = stack_runtime(resource);
+    let dir = stack_dir(resource);
+    let mut lines = vec!["set -euo pipefail".to_string()];
+    for svc in services.iter().rev() {
+        let cname = container_name(project, svc);
+        lines.push(format!("{rt} stop '{cname}' 2>/dev/null || true"));
+        lines.push(format!("{rt} rm '{cname}' 2>/dev/null || true"));
+    }
+    lines.push(format!(
+        "if [ -f '{dir}/compose.yaml' ]; then\n\
+         \x20 {rt} compose -p '{project}' -f '{dir}/compose.yaml' down --remove-orphans || true\n\
+         \x20 rm -f '{dir}/compose.yaml'\n\
+         fi"
+    ));
+    lines.join("\n")
+}
+
+/// Teardown when the spec is gone from config: rely on what is on disk.
+fn teardown_without_spec(resource: &Resource) -> String {
+    if is_quadlet(resource) {
+        return quadlet::remove_by_label(project_name(resource), &stack_dir(resource));
+    }
+    compose_down(resource, &[])
+}
+
+/// Generate shell to query per-service state (for BLAKE3 hashing).
+///
+/// One line per service in dependency order: running state, image ID and
+/// config-hash label, or `MISSING` when the container does not exist.
+pub fn state_query_script(resource: &Resource) -> String {
+    let project = project_name(resource);
+    let rt = stack_runtime(resource);
+    let spec = match resource.content.as_deref().map(parse_stack) {
+        Some(Ok(spec)) => spec,
+        _ => return format!("echo 'stack=MISSING:{project}'"),
+    };
+    let format = format!(
+        "running={{{{.State.Running}}}} image={{{{.Image}}}} config_hash={{{{ index .Config.Labels \"{CONFIG_HASH_LABEL}\" }}}}"
+    );
+    let mut lines = Vec::new();
+    for svc in ordered_services(&spec) {
+        let cname = container_name(project, svc);
+        lines.push(format!(
+            "echo \"service={} $({rt} inspect -f '{format}' '{cname}' 2>/dev/null || echo 'MISSING')\"",
+            svc.name
+        ));
+    }
+    lines.push(format!("echo 'stack={project}'"));
+    lines.join("\n")
+}
@@ -0,0 +1,63 @@
+//! Shared helpers for the disk provisioning resources
+//! (swap, partition, lvm_vg, lvm_lv, filesystem).
+
+/// Parse a size such as "512M", "4G" or "1TiB" (binary units) into bytes.
+///
+/// A bare number is taken as bytes. Decimal units ("4GB") are rejected
+/// because sgdisk, fallocate and lvcreate disagree on what they mean.
+pub fn parse_size_bytes(size: &str) -> Option<u64> {
+    let size = size.trim();
+    let (digits, unit) = match size.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => size.split_at(i),
+        None => (size, ""),
+    };
+    let value: u64 = digits.parse().ok()?;
+    let shift = match unit.to_ascii_uppercase().as_str() {
+        "" => 0,
+        "K" | "KIB" => 10,
+        "M" | "MIB" => 20,
+        "G" | "GIB" => 30,
+        "T" | "TIB" => 40,
+        _ => return None,
+    };
+    value.checked_mul(1u64 << shift)
+}
+
+/// Size in the `<n><K|M|G|T>` form understood by sgdisk, fallocate and lvcreate.
+///
+/// Falls back to the input when it does not parse (validation rejects those).
+pub fn size_arg(size: &str) -> String {
+    let size = size.trim();
+    if parse_size_bytes(size).is_none() {
+        return size.to_string();
+    }
+    let upper = size.to_ascii_uppercase();
+    upper.strip_suffix("IB").unwrap_or(&upper).to_string()
+}
+
+/// Device node for partition `number` of `device`.
+///
+/// Devices whose name ends in a digit (nvme0n1, loop0, mmcblk0) take a `p`
+/// separator: `/dev/nvme0n1p2`, `/dev/sdb2`.
+pub fn partition_device(device: &str, number: u32) -> String {
+    if device.ends_with(|c: char| c.is_ascii_digit()) {
+        format!("{device}p{number}")
+    } else {
+        format!("{device}{number}")
+    }
+}
+
+/// sgdisk type code for a partition type alias or explicit 4-hex code.
+pub fn gpt_type_code(part_type: &str) -> Option<String> {
+    let code = match part_type {
+        "linux" => "8300",
+        "swap" => "8200",
+        "efi" => "ef00",
+        "bios" => "ef02",
+        "lvm" => "8e00",
+        "raid" => "fd00",
+        code if code.len() == 4 && code.chars().all(|c| c.is_ascii_hexdigit()) => code,
+        _ => return None,
+    };
+    Some(code.to_ascii_lowercase())
+}
@@ -3,82 +3,252 @@
 //! Manages Docker containers as resources: pull, run, stop, remove.
 //! This is distinct from container *transport* (FJ-021) — this manages
 //! containers deployed ON machines, not containers used AS machines.
+//!
+//! Every container is started with a `forjar.config-hash` label carrying the
+//! BLAKE3 of its run spec. Apply only recreates the container when that label,
+//! the running state, or the resolved image ID differ from the desired spec,
+//! so an env var or tag change triggers a recreate and an unchanged spec does
+//! not. `runtime: podman` swaps the CLI, mirroring `ContainerConfig.runtime`.
 
 use crate::core::types::Resource;
+use crate::tripwire::hasher;
+
+/// Label key holding the BLAKE3 of the container run spec.
+pub const CONFIG_HASH_LABEL: &str = "forjar.config-hash";
+
+/// Container CLI for this resource (`docker` or `podman`).
+pub fn runtime_bin(resource: &Resource) -> &str {
+    match resource.runtime.as_deref() {
+        Some("podman") => "podman",
+        _ => "docker",
+    }
+}
+
+/// Image reference to pull and run — `image@digest` when a digest is pinned.
+pub fn image_ref(resource: &Resource) -> String {
+    let image = resource.image.as_deref().unwrap_or("unknown");
+    match resource.image_digest.as_deref() {
+        Some(digest) if !image.contains('@') => format!("{image}@{digest}"),
+        _ => image.to_string(),
+    }
+}
+
+/// BLAKE3 of every field that shapes the running container.
+///
+/// Field order is stable and must not change — it determines label identity.
+pub fn config_hash(resource: &Resource) -> String {
+    let image = image_ref(resource);
+    let memory = resource.memory_limit.map(|m| m.to_string());
+    let retries = resource
+        .health_check
+        .as_ref()
+        .and_then(|hc| hc.retries)
+        .map(|r| r.to_string());
+    let mut components: Vec<&str> = vec![&image];
+    let opts = [
+        &resource.command,
+        &resource.entrypoint,
+        &resource.user,
+        &resource.restart,
+        &resource.cpus,
+        &resource.cpuset,
+        &memory,
+    ];
+    for opt in opts {
+        components.push(opt.as_deref().unwrap_or(""));
+    }
+    for list in [
+        &resource.ports,
+        &resource.environment,
+        &resource.volumes,
+        &resource.labels,
+        &resource.networks,
+    ] {
+        components.push("\u{1f}");
+        components.extend(list.iter().map(String::as_str));
+    }
+    if let Some(ref hc) = resource.health_check {
+        components.push(&hc.command);
+        components.push(hc.interval.as_deref().unwrap_or(""));
+        components.push(hc.timeout.as_deref().unwrap_or(""));
+        components.push(retries.as_deref().unwrap_or(""));
+    }
+    hasher::composite_hash(&components)
+}
 
 /// Generate shell script to check if a container is running.
 pub fn check_script(resource: &Resource) -> String {
+    let rt = runtime_bin(resource);
     let name = resource.name.as_deref().unwrap_or("unknown");
     format!(
-        "docker inspect -f '{{{{.State.Running}}}}' '{name}' 2>/dev/null && echo 'exists:{name}' || echo 'missing:{name}'"
+        "{rt} inspect -f '{{{{.State.Running}}}}' '{name}' 2>/dev/null && echo 'exists:{name}' || echo 'missing:{name}'"
     )
 }
 
 /// Generate shell script to manage a container.
 pub fn apply_script(resource: &Resource) -> String {
+    let rt = runtime_bin(resource);
     let name = resource.name.as_deref().unwrap_or("unknown");
     let state = resource.state.as_deref().unwrap_or("running");
-    let image = resource.image.as_deref().unwrap_or("unknown");
 
     match state {
         "absent" => format!(
             "set -euo pipefail\n\
-             docker stop '{name}' 2>/dev/null || true\n\
-             docker rm '{name}' 2>/dev/null || true"
+             {rt} stop '{name}' 2>/dev/null || true\n\
+             {rt} rm '{name}' 2>/dev/null || true"
         ),
         "stopped" => format!(
             "set -euo pipefail\n\
-             docker stop '{name}' 2>/dev/null || true"
+             {rt} stop '{name}' 2>/dev/null || true"
         ),
         _ => {
             // "running" or "present"
-            let mut lines = vec![
-                "set -euo pipefail".to_string(),
-                format!("docker pull '{}'", image),
-            ];
+            let image = image_ref(resource);
+            let mut lines = vec!["set -euo pipefail".to_string()];
+            lines.push(pull_line(rt, &image, resource.pull_policy.as_deref()));
 
-            // Stop and remove existing container if it exists
-            lines.push(format!("docker stop '{name}' 2>/dev/null || true"));
-            lines.push(format!("docker rm '{name}' 2>/dev/null || true"));
+            // Skip the recreate when the live container already matches the spec
+            lines.push(format!("WANT_HASH='{}'", config_hash(resource)));
+            lines.push(inspect_var("HAVE_HASH", rt, &hash_label_format(), name));
+            lines.push(inspect_var("HAVE_RUNNING", rt, "{{.State.Running}}", name));
+            lines.push(inspect_var("HAVE_IMAGE", rt, "{{.Image}}", name));
+            lines.push(format!(
+                "WANT_IMAGE=\"$({rt} image inspect -f '{{{{.Id}}}}' '{image}' 2>/dev/null || true)\""
+            ));
+            lines.push(
+                "if [ \"$HAVE_HASH\" = \"$WANT_HASH\" ] && [ \"$HAVE_RUNNING\" = 'true' ] && [ \"$HAVE_IMAGE\" = \"$WANT_IMAGE\" ]; then"
+                    .to_string(),
+            );
+            lines.push(format!("  echo 'unchanged:{name}'"));
+            lines.push("else".to_string());
 
-            // Build run command
-            let mut run_args = vec!["docker run -d".to_string()];
-            run_args.push(format!("--name '{name}'"));
+            // Stop and remove existing container if it exists
+            lines.push(format!("{rt} stop '{name}' 2>/dev/null || true"));
+            lines.push(format!("{rt} rm '{name}' 2>/dev/null || true"));
+            lines.push(run_args(resource, rt, name, &image).join(" \\\n  "));
 
-            if let Some(ref restart) = resource.restart {
-                run_args.push(format!("--restart '{restart}'"));
+            // Additional networks are attached after the container starts
+            for net in resource.networks.iter().skip(1) {
+                lines.push(format!("{rt} network connect '{net}' '{name}'"));
             }
+            lines.push("fi".to_string());
 
-            for port in &resource.ports {
-                run_args.push(format!("-p '{port}'"));
-            }
+            lines.join("\n")
+        }
+    }
+}
 
-            for env in &resource.environment {
-                run_args.push(format!("-e '{env}'"));
-            }
+/// Pull step for the configured policy (`always` when unset).
+fn pull_line(rt: &str, image: &str, policy: Option<&str>) -> String {
+    match policy {
+        Some("missing") => {
+            format!("{rt} image inspect '{image}' >/dev/null 2>&1 || {rt} pull '{image}'")
+        }
+        Some("never") => format!(
+            "{rt} image inspect '{image}' >/dev/null 2>&1 || {{ echo 'image {image} not present and pull_policy=never' >&2; exit 1; }}"
+        ),
+        _ => format!("{rt} pull '{image}'"),
+    }
+}
 
-            for vol in &resource.volumes {
-                run_args.push(format!("-v '{vol}'"));
-            }
+/// Go template reading the config-hash label.
+fn hash_label_format() -> String {
+    format!("{{{{ index .Config.Labels \"{CONFIG_HASH_LABEL}\" }}}}")
+}
 
-            run_args.push(format!("'{image}'"));
+/// Capture one `inspect -f` field into a shell variable (empty when missing).
+fn inspect_var(var: &str, rt: &str, format: &str, name: &str) -> String {
+    format!("{var}=\"$({rt} inspect -f '{format}' '{name}' 2>/dev/null || true)\"")
+}
 
-            // Append command if specified
-            if let Some(ref cmd) = resource.command {
-                run_args.push(cmd.clone());
-            }
+/// Build the `run -d` argument list.
+fn run_args(resource: &Resource, rt: &str, name: &str, image: &str) -> Vec<String> {
+    let mut run_args = vec![format!("{rt} run -d")];
+    run_args.push(format!("--name '{name}'"));
+    run_args.push(format!(
+        "--label '{CONFIG_HASH_LABEL}={}'",
+        config_hash(resource)
+    ));
 
-            lines.push(run_args.join(" \\\n  "));
+    if let Some(ref restart) = resource.restart {
+        run_args.push(format!("--restart '{restart}'"));
+    }
+    if let Some(ref user) = resource.user {
+        run_args.push(format!("--user '{user}'"));
+    }
+    if let Some(ref entrypoint) = resource.entrypoint {
+        run_args.push(format!("--entrypoint '{entrypoint}'"));
+    }
+    if let Some(net) = resource.networks.first() {
+        run_args.push(format!("--network '{net}'"));
+    }
+    push_limits(&mut run_args, resource);
+    push_healthcheck(&mut run_args, resource);
 
-            lines.join("\n")
-        }
+    for label in &resource.labels {
+        run_args.push(format!("--label '{label}'"));
+    }
+    for port in &resource.ports {
+        run_args.push(format!("-p '{port}'"));
+    }
+    for env in &resource.environment {
+        run_args.push(format!("-e '{env}'"));
+    }
+    for vol in &resource.volumes {
+        run_args.push(format!("-v '{vol}'"));
+    }
+
+    run_args.push(format!("'{image}'"));
+
+    // Append command if specified
+    if let Some(ref cmd) = resource.command {
+        run_args.push(cmd.clone());
+    }
+    run_args
+}
+
+/// CPU and memory limits.
+fn push_limits(run_args: &mut Vec<String>, resource: &Resource) {
+    if let Some(ref cpus) = resource.cpus {
+        run_args.push(format!("--cpus '{cpus}'"));
+    }
+    if let Some(ref cpuset) = resource.cpuset {
+        run_args.push(format!("--cpuset-cpus '{cpuset}'"));
+    }
+    if let Some(mem) = resource.memory_limit {
+        run_args.push(format!("--memory '{mem}'"));
+    }
+}
+
+/// Healthcheck flags from `health_check`.
+fn push_healthcheck(run_args: &mut Vec<String>, resource: &Resource) {
+    let Some(ref hc) = resource.health_check else {
+        return;
+    };
+    run_args.push(format!("--health-cmd '{}'", hc.command));
+    if let Some(ref interval) = hc.interval {
+        run_args.push(format!("--health-interval '{interval}'"));
+    }
+    if let Some(ref timeout) = hc.timeout {
+        run_args.push(format!("--health-timeout '{timeout}'"));
+    }
+    if let Some(retries) = hc.retries {
+        run_args.push(format!("--health-retries '{retries}'"));
     }
 }
 
 /// Generate shell to query container state (for BLAKE3 hashing).
+///
+/// Reports running state, image ID and config-hash label so that a stopped,
+/// re-imaged or hand-edited container shows up as drift.
 pub fn state_query_script(resource: &Resource) -> String {
+    let rt = runtime_bin(resource);
     let name = resource.name.as_deref().unwrap_or("unknown");
+    let format = format!(
+        "running={{{{.State.Running}}}} image={{{{.Image}}}} config_hash={}",
+        hash_label_format()
+    );
     format!(
-        "docker inspect '{name}' 2>/dev/null && echo 'container={name}' || echo 'container=MISSING:{name}'"
+        "{rt} inspect -f '{format}' '{name}' 2>/dev/null && echo 'container={name}' || echo 'container=MISSING:{name}'"
     )
 }
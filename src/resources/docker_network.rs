@@ -0,0 +1,100 @@
+//! Docker/Podman network resource handler.
+//!
+//! Creates user-defined networks (bridge, macvlan, …) that `docker`
+//! resources attach to via `networks:`. Networks cannot be edited in place,
+//! so a changed spec (detected through the `forjar.config-hash` label)
+//! removes and recreates the network — which fails loudly while containers
+//! are still attached rather than silently detaching them.
+
+use super::docker::{runtime_bin, CONFIG_HASH_LABEL};
+use crate::core::types::Resource;
+use crate::tripwire::hasher;
+
+/// BLAKE3 of the network spec (driver, subnet, gateway, options, labels).
+pub fn config_hash(resource: &Resource) -> String {
+    let mut components: Vec<&str> = vec![resource.driver.as_deref().unwrap_or("bridge")];
+    for opt in [&resource.subnet, &resource.gateway, &resource.options] {
+        components.push(opt.as_deref().unwrap_or(""));
+    }
+    components.extend(resource.labels.iter().map(String::as_str));
+    hasher::composite_hash(&components)
+}
+
+/// Generate shell script to check if the network exists.
+pub fn check_script(resource: &Resource) -> String {
+    let rt = runtime_bin(resource);
+    let name = resource.name.as_deref().unwrap_or("unknown");
+    format!(
+        "{rt} network inspect '{name}' >/dev/null 2>&1 && echo 'exists:{name}' || echo 'missing:{name}'"
+    )
+}
+
+/// Generate shell script to create, recreate or remove the network.
+pub fn apply_script(resource: &Resource) -> String {
+    let rt = runtime_bin(resource);
+    let name = resource.name.as_deref().unwrap_or("unknown");
+    if resource.state.as_deref() == Some("absent") {
+        return format!(
+            "set -euo pipefail\n\
+             {rt} network rm '{name}' 2>/dev/null || true"
+        );
+    }
+
+    let want = config_hash(resource);
+    let create = create_command(resource, rt, name, &want);
+    format!(
+        "set -euo pipefail\n\
+         WANT_HASH='{want}'\n\
+         if {rt} network inspect '{name}' >/dev/null 2>&1; then\n\
+         \x20 HAVE_HASH=\"$({rt} network inspect -f '{{{{ index .Labels \"{CONFIG_HASH_LABEL}\" }}}}' '{name}' 2>/dev/null || true)\"\n\
+         \x20 if [ \"$HAVE_HASH\" = \"$WANT_HASH\" ]; then\n\
+         \x20   echo 'unchanged:{name}'\n\
+         \x20   exit 0\n\
+         \x20 fi\n\
+         \x20 {rt} network rm '{name}'\n\
+         fi\n\
+         {create}"
+    )
+}
+
+/// Build the `network create` command.
+fn create_command(resource: &Resource, rt: &str, name: &str, hash: &str) -> String {
+    let driver = resource.driver.as_deref().unwrap_or("bridge");
+    let mut args = vec![format!("{rt} network create --driver '{driver}'")];
+    args.push(format!("--label '{CONFIG_HASH_LABEL}={hash}'"));
+    if let Some(ref subnet) = resource.subnet {
+        args.push(format!("--subnet '{subnet}'"));
+    }
+    if let Some(ref gateway) = resource.gateway {
+        args.push(format!("--gateway '{gateway}'"));
+    }
+    for opt in split_options(resource.options.as_deref()) {
+        args.push(format!("--opt '{opt}'"));
+    }
+    for label in &resource.labels {
+        args.push(format!("--label '{label}'"));
+    }
+    args.push(format!("'{name}'"));
+    args.join(" \\\n  ")
+}
+
+/// Split a comma-separated `key=value` options string into driver options.
+pub(crate) fn split_options(options: Option<&str>) -> Vec<&str> {
+    options
+        .map(|o| {
+            o.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Generate shell to query network state (for BLAKE3 hashing).
+pub fn state_query_script(resource: &Resource) -> String {
+    let rt = runtime_bin(resource);
+    let name = resource.name.as_deref().unwrap_or("unknown");
+    format!(
+        "{rt} network inspect -f 'driver={{{{.Driver}}}} config_hash={{{{ index .Labels \"{CONFIG_HASH_LABEL}\" }}}}' '{name}' 2>/dev/null && echo 'network={name}' || echo 'network=MISSING:{name}'"
+    )
+}
@@ -0,0 +1,73 @@
+//! Docker/Podman named volume resource handler.
+//!
+//! Volumes hold data, so unlike networks a changed spec is never applied by
+//! recreating the volume: apply warns and leaves the existing volume alone.
+//! `state: absent` removes the volume (classified irreversible by the planner).
+
+use super::docker::{runtime_bin, CONFIG_HASH_LABEL};
+use super::docker_network::split_options;
+use crate::core::types::Resource;
+use crate::tripwire::hasher;
+
+/// BLAKE3 of the volume spec (driver, options, labels).
+pub fn config_hash(resource: &Resource) -> String {
+    let mut components: Vec<&str> = vec![resource.driver.as_deref().unwrap_or("local")];
+    components.push(resource.options.as_deref().unwrap_or(""));
+    components.extend(resource.labels.iter().map(String::as_str));
+    hasher::composite_hash(&components)
+}
+
+/// Generate shell script to check if the volume exists.
+pub fn check_script(resource: &Resource) -> String {
+    let rt = runtime_bin(resource);
+    let name = resource.name.as_deref().unwrap_or("unknown");
+    format!(
+        "{rt} volume inspect '{name}' >/dev/null 2>&1 && echo 'exists:{name}' || echo 'missing:{name}'"
+    )
+}
+
+/// Generate shell script to create or remove the volume.
+pub fn apply_script(resource: &Resource) -> String {
+    let rt = runtime_bin(resource);
+    let name = resource.name.as_deref().unwrap_or("unknown");
+    if resource.state.as_deref() == Some("absent") {
+        return format!(
+            "set -euo pipefail\n\
+             {rt} volume rm '{name}' 2>/dev/null || true"
+        );
+    }
+
+    let want = config_hash(resource);
+    let driver = resource.driver.as_deref().unwrap_or("local");
+    let mut args = vec![format!("{rt} volume create --driver '{driver}'")];
+    args.push(format!("--label '{CONFIG_HASH_LABEL}={want}'"));
+    for opt in split_options(resource.options.as_deref()) {
+        args.push(format!("--opt '{opt}'"));
+    }
+    for label in &resource.labels {
+        args.push(format!("--label '{label}'"));
+    }
+    args.push(format!("'{name}'"));
+    let create = args.join(" \\\n  ");
+
+    format!(
+        "set -euo pipefail\n\
+         if {rt} volume inspect '{name}' >/dev/null 2>&1; then\n\
+         \x20 HAVE_HASH=\"$({rt} volume inspect -f '{{{{ index .Labels \"{CONFIG_HASH_LABEL}\" }}}}' '{name}' 2>/dev/null || true)\"\n\
+         \x20 if [ \"$HAVE_HASH\" != '{want}' ]; then\n\
+         \x20   echo 'FORJAR_WARN: volume {name} spec changed — volumes are never recreated (remove it manually)'\n\
+         \x20 fi\n\
+         else\n\
+         {create}\n\
+         fi"
+    )
+}
+
+/// Generate shell to query volume state (for BLAKE3 hashing).
+pub fn state_query_script(resource: &Resource) -> String {
+    let rt = runtime_bin(resource);
+    let name = resource.name.as_deref().unwrap_or("unknown");
+    format!(
+        "{rt} volume inspect -f 'driver={{{{.Driver}}}} mountpoint={{{{.Mountpoint}}}} config_hash={{{{ index .Labels \"{CONFIG_HASH_LABEL}\" }}}}' '{name}' 2>/dev/null && echo 'volume={name}' || echo 'volume=MISSING:{name}'"
+    )
+}
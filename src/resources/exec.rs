@@ -0,0 +1,144 @@
+//! Imperative command resource handler.
+//!
+//! A lightweight alternative to `task` for one-off steps, with Puppet-style
+//! guards evaluated on the target before the command runs:
+//! - `creates` — skip when the path exists
+//! - `unless` — skip when the guard command exits 0
+//! - `onlyif` — run only when the guard command exits 0
+//!
+//! Guards and the command run in `working_dir` (alias `cwd`) with
+//! `environment` exported, as `user` when set. `returns` lists the exit codes
+//! that count as success. `refreshonly` is enforced by the executor: the
+//! resource only runs when something in `triggers` converged in the same apply.
+
+use crate::core::types::Resource;
+
+/// Resource name used in status tokens.
+fn exec_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("exec")
+}
+
+/// Accepted exit codes (`returns`, default `[0]`).
+pub fn accepted_codes(resource: &Resource) -> Vec<i32> {
+    if resource.returns.is_empty() {
+        vec![0]
+    } else {
+        resource.returns.clone()
+    }
+}
+
+/// Assign a command to a shell variable through a quoted heredoc.
+fn capture(var: &str, command: &str) -> String {
+    format!("{var}=$(cat <<'FORJAR_EXEC'\n{command}\nFORJAR_EXEC\n)")
+}
+
+/// Shared preamble: working directory, environment, runner and guard bodies.
+fn preamble(resource: &Resource) -> Vec<String> {
+    let mut lines = vec!["set -euo pipefail".to_string()];
+    if let Some(ref dir) = resource.working_dir {
+        lines.push(format!("cd '{dir}'"));
+    }
+    for env in &resource.environment {
+        lines.push(format!("export '{env}'"));
+    }
+    lines.push(match resource.user {
+        Some(ref user) => format!("forjar_run() {{ runuser -u '{user}' -- sh -c \"$1\"; }}"),
+        None => "forjar_run() { sh -c \"$1\"; }".to_string(),
+    });
+    if let Some(ref unless) = resource.unless {
+        lines.push(capture("FORJAR_UNLESS", unless));
+    }
+    if let Some(ref onlyif) = resource.onlyif {
+        lines.push(capture("FORJAR_ONLYIF", onlyif));
+    }
+    lines
+}
+
+/// Guard conditions, each true when the command should be skipped.
+fn skip_conditions(resource: &Resource) -> Vec<(String, &'static str)> {
+    let mut guards = Vec::new();
+    if let Some(ref creates) = resource.creates {
+        guards.push((format!("[ -e '{creates}' ]"), "creates"));
+    }
+    if resource.unless.is_some() {
+        guards.push((
+            "forjar_run \"$FORJAR_UNLESS\" >/dev/null 2>&1".to_string(),
+            "unless",
+        ));
+    }
+    if resource.onlyif.is_some() {
+        guards.push((
+            "! forjar_run \"$FORJAR_ONLYIF\" >/dev/null 2>&1".to_string(),
+            "onlyif",
+        ));
+    }
+    guards
+}
+
+/// Generate shell script reporting whether the command would be skipped.
+///
+/// Without guards an exec always reports `missing`, i.e. it runs when planned.
+pub fn check_script(resource: &Resource) -> String {
+    let name = exec_name(resource);
+    let guards = skip_conditions(resource);
+    if guards.is_empty() {
+        return format!("echo 'missing:{name}'");
+    }
+    let mut lines = preamble(resource);
+    let any: Vec<String> = guards.into_iter().map(|(cond, _)| cond).collect();
+    lines.push(format!(
+        "if {{ {}; }}; then echo 'exists:{name}'; else echo 'missing:{name}'; fi",
+        any.join("; } || { ")
+    ));
+    lines.join("\n")
+}
+
+/// Generate shell script that evaluates the guards and runs the command.
+pub fn apply_script(resource: &Resource) -> String {
+    let name = exec_name(resource);
+    if resource.state.as_deref() == Some("absent") {
+        return format!("echo 'skip:{name}'");
+    }
+    let Some(ref command) = resource.command else {
+        return format!("echo 'ERROR: exec {name} has no command' >&2; exit 1");
+    };
+
+    let mut lines = preamble(resource);
+    for (cond, guard) in skip_conditions(resource) {
+        lines.push(format!(
+            "if {cond}; then echo 'skip:{name} ({guard})'; exit 0; fi"
+        ));
+    }
+    lines.push(capture("FORJAR_CMD", command));
+    lines.push("set +e".to_string());
+    lines.push("forjar_run \"$FORJAR_CMD\"".to_string());
+    lines.push("RC=$?".to_string());
+    lines.push("set -e".to_string());
+
+    let codes: Vec<String> = accepted_codes(resource)
+        .iter()
+        .map(i32::to_string)
+        .collect();
+    lines.push(format!(
+        "case \"$RC\" in\n\
+         \x20 {}) echo \"ran:{name} rc=$RC\" ;;\n\
+         \x20 *) echo \"ERROR: exec {name} exited $RC (accepted: {})\" >&2; exit 1 ;;\n\
+         esac",
+        codes.join("|"),
+        codes.join(",")
+    ));
+    lines.join("\n")
+}
+
+/// Generate shell to query state (for BLAKE3 hashing).
+///
+/// Only `creates` is observable; an exec without it never reports drift.
+pub fn state_query_script(resource: &Resource) -> String {
+    let name = exec_name(resource);
+    match resource.creates {
+        Some(ref creates) => format!(
+            "if [ -e '{creates}' ]; then echo 'exec={name} creates=present'; else echo 'exec={name} creates=absent'; fi"
+        ),
+        None => format!("echo 'exec={name}'"),
+    }
+}
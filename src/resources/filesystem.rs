@@ -0,0 +1,86 @@
+//! Filesystem resource handler (mkfs).
+//!
+//! Formats `device` as `fs_type`, passing `options` through to mkfs.
+//! A device already holding `fs_type` is left alone. Any other signature —
+//! a different filesystem, swap, LVM or a partition table — is never
+//! reformatted unless `force` is set, in which case it is wiped first.
+//! `state: absent` wipes the signatures, and likewise requires `force`.
+
+use crate::core::types::Resource;
+
+fn device(resource: &Resource) -> &str {
+    resource.device.as_deref().unwrap_or("/dev/unknown")
+}
+
+fn fs_type(resource: &Resource) -> &str {
+    resource.fs_type.as_deref().unwrap_or("ext4")
+}
+
+/// Capture the current filesystem type (or partition table type) into `$SIG`.
+fn signature_line(dev: &str) -> String {
+    format!(
+        "SIG=\"$(blkid -p -o value -s TYPE '{dev}' 2>/dev/null || true)\"\n\
+         if [ -z \"$SIG\" ]; then SIG=\"$(blkid -p -o value -s PTTYPE '{dev}' 2>/dev/null || true)\"; fi"
+    )
+}
+
+/// Generate shell to check whether the device holds the filesystem.
+pub fn check_script(resource: &Resource) -> String {
+    let dev = device(resource);
+    let fs = fs_type(resource);
+    format!(
+        "{}\n\
+         if [ \"$SIG\" = '{fs}' ]; then echo 'exists:{dev}'; else echo 'missing:{dev}'; fi",
+        signature_line(dev)
+    )
+}
+
+/// Generate shell to create (or, with force, wipe) the filesystem.
+pub fn apply_script(resource: &Resource) -> String {
+    let dev = device(resource);
+    let fs = fs_type(resource);
+    let mut lines = vec!["set -euo pipefail".to_string(), signature_line(dev)];
+
+    if resource.state.as_deref() == Some("absent") {
+        if resource.force {
+            lines.push(format!("if [ -n \"$SIG\" ]; then wipefs -a '{dev}'; fi"));
+        } else {
+            lines.push(format!(
+                "if [ -n \"$SIG\" ]; then\n\
+                 \x20 echo \"ERROR: refusing to wipe $SIG on {dev} without force\" >&2\n\
+                 \x20 exit 1\n\
+                 fi"
+            ));
+        }
+        return lines.join("\n");
+    }
+
+    lines.push(format!(
+        "if [ \"$SIG\" = '{fs}' ]; then echo 'unchanged:{dev}'; exit 0; fi"
+    ));
+    if resource.force {
+        lines.push(format!("if [ -n \"$SIG\" ]; then wipefs -a '{dev}'; fi"));
+    } else {
+        lines.push(format!(
+            "if [ -n \"$SIG\" ]; then\n\
+             \x20 echo \"ERROR: {dev} already holds $SIG; refusing to reformat as {fs} without force\" >&2\n\
+             \x20 exit 1\n\
+             fi"
+        ));
+    }
+    let mut mkfs = format!("mkfs -t '{fs}'");
+    if let Some(ref opts) = resource.options {
+        mkfs.push(' ');
+        mkfs.push_str(opts);
+    }
+    lines.push(format!("{mkfs} '{dev}'"));
+    lines.join("\n")
+}
+
+/// Generate shell to query filesystem state (for BLAKE3 hashing).
+pub fn state_query_script(resource: &Resource) -> String {
+    let dev = device(resource);
+    format!(
+        "blkid -p -o export '{dev}' 2>/dev/null | grep -E '^(TYPE|UUID|LABEL)=' || echo 'filesystem=NONE:{dev}'"
+    )
+}
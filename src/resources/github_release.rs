@@ -5,6 +5,11 @@
 //! repo publishes aarch64 nightly binaries, and forjar provisions them onto
 //! the Jetson via this resource type.
 //!
+//! `github_release` is `binary_release` with `provider: github`: the release
+//! is resolved by `core::release_source` (`tag` pins it, `nightly` included;
+//! unset or `latest` takes the newest stable release, or `version`), and the
+//! asset is checksum-verified and swapped in with rollback on the target.
+//!
 //! # YAML example
 //!
 //! ```yaml
@@ -18,134 +23,14 @@
 //!   install_dir: /home/user/.cargo/bin
 //! ```
 
-use crate::core::types::Resource;
-
-/// Generate shell script to check if the binary from a GitHub release is installed.
-///
-/// Checks:
-/// 1. Binary exists at `install_dir/binary`
-/// 2. Binary is executable
-pub fn check_script(resource: &Resource) -> String {
-    let repo = resource.repo.as_deref().unwrap_or("unknown/unknown");
-    let binary = resource.binary.as_deref().unwrap_or("unknown");
-    let install_dir = resource.install_dir.as_deref().unwrap_or("/usr/local/bin");
-    let bin_path = format!("{install_dir}/{binary}");
-
-    format!(
-        "if [ -x '{bin_path}' ]; then\n\
-         \x20 VER=$( '{bin_path}' --version 2>/dev/null | head -1 || echo 'unknown' )\n\
-         \x20 echo \"installed:{repo}:$VER\"\n\
-         else\n\
-         \x20 echo 'missing:{repo}'\n\
-         fi"
-    )
-}
-
-/// Generate shell script to download a release asset and install the binary.
-///
-/// Uses `gh release download` (GitHub CLI) which handles authentication,
-/// pagination, and asset matching. Falls back to curl for environments
-/// without `gh`.
-pub fn apply_script(resource: &Resource) -> String {
-    let repo = resource.repo.as_deref().unwrap_or("unknown/unknown");
-    let tag = resource.tag.as_deref().unwrap_or("latest");
-    let asset_pattern = resource.asset_pattern.as_deref().unwrap_or("*");
-    // Strip glob wildcards for use in grep -F (fixed string match)
-    let grep_pattern = asset_pattern.trim_matches('*');
-    let binary = resource.binary.as_deref().unwrap_or("unknown");
-    let install_dir = resource.install_dir.as_deref().unwrap_or("/usr/local/bin");
-    let state = resource.state.as_deref().unwrap_or("present");
-    let bin_path = format!("{install_dir}/{binary}");
-
-    match state {
-        "absent" => format!(
-            "set -euo pipefail\n\
-             rm -f '{bin_path}'\n\
-             echo 'removed:{repo}'"
-        ),
-        _ => format!(
-            "set -euo pipefail\n\
-             TMPDIR=$(mktemp -d)\n\
-             trap 'rm -rf \"$TMPDIR\"' EXIT\n\
-             \n\
-             # Download release asset via GitHub API (no gh CLI required)\n\
-             RELEASE_URL=\"https://api.github.com/repos/{repo}/releases/tags/{tag}\"\n\
-             DOWNLOAD_URL=$(curl -fsSL \"$RELEASE_URL\" | \\\n\
-             \x20 grep -F '{grep_pattern}' | \\\n\
-             \x20 grep -o '\"browser_download_url\": *\"[^\"]*\"' | \\\n\
-             \x20 head -1 | \\\n\
-             \x20 grep -o 'https://[^\"]*')\n\
-             \n\
-             if [ -z \"$DOWNLOAD_URL\" ]; then\n\
-             \x20 echo \"ERROR: no asset matching '{asset_pattern}' in {repo}@{tag}\" >&2\n\
-             \x20 exit 1\n\
-             fi\n\
-             \n\
-             ASSET_NAME=$(basename \"$DOWNLOAD_URL\")\n\
-             curl -fsSL -o \"$TMPDIR/$ASSET_NAME\" \"$DOWNLOAD_URL\"\n\
-             ASSET=\"$TMPDIR/$ASSET_NAME\"\n\
-             case \"$ASSET\" in\n\
-             \x20 *.tar.gz|*.tgz)\n\
-             \x20\x20\x20 tar xzf \"$ASSET\" -C \"$TMPDIR\" --strip-components=0\n\
-             \x20\x20\x20 # Find the binary in extracted files\n\
-             \x20\x20\x20 if [ -f \"$TMPDIR/{binary}\" ]; then\n\
-             \x20\x20\x20\x20\x20 EXTRACTED=\"$TMPDIR/{binary}\"\n\
-             \x20\x20\x20 else\n\
-             \x20\x20\x20\x20\x20 EXTRACTED=$(find \"$TMPDIR\" -name '{binary}' -type f | head -1)\n\
-             \x20\x20\x20 fi\n\
-             \x20\x20\x20 ;;\n\
-             \x20 *.zip)\n\
-             \x20\x20\x20 unzip -o \"$ASSET\" -d \"$TMPDIR\"\n\
-             \x20\x20\x20 EXTRACTED=$(find \"$TMPDIR\" -name '{binary}' -type f | head -1)\n\
-             \x20\x20\x20 ;;\n\
-             \x20 *)\n\
-             \x20\x20\x20 EXTRACTED=\"$ASSET\"\n\
-             \x20\x20\x20 ;;\n\
-             esac\n\
-             \n\
-             if [ -z \"$EXTRACTED\" ] || [ ! -f \"$EXTRACTED\" ]; then\n\
-             \x20 echo \"ERROR: binary '{binary}' not found in release asset\" >&2\n\
-             \x20 exit 1\n\
-             fi\n\
-             \n\
-             # Install (realpath validates path before cp)\n\
-             SAFE_BIN=$(realpath \"$EXTRACTED\")\n\
-             mkdir -p '{install_dir}'\n\
-             cp \"$SAFE_BIN\" '{bin_path}'\n\
-             chmod +x '{bin_path}'\n\
-             \n\
-             # Verify\n\
-             VER=$( '{bin_path}' --version 2>/dev/null | head -1 || echo 'installed' )\n\
-             echo \"installed:{repo}:$VER\""
-        ),
-    }
-}
-
-/// Generate shell to query installed binary state (for BLAKE3 hashing).
-///
-/// Returns version string + file size + modification time for drift detection.
-/// If the nightly binary is updated upstream and forjar re-applies, the hash
-/// changes and drift is detected.
-pub fn state_query_script(resource: &Resource) -> String {
-    let repo = resource.repo.as_deref().unwrap_or("unknown/unknown");
-    let binary = resource.binary.as_deref().unwrap_or("unknown");
-    let install_dir = resource.install_dir.as_deref().unwrap_or("/usr/local/bin");
-    let bin_path = format!("{install_dir}/{binary}");
-
-    format!(
-        "if [ -x '{bin_path}' ]; then\n\
-         \x20 VER=$( '{bin_path}' --version 2>/dev/null | head -1 || echo 'unknown' )\n\
-         \x20 SIZE=$(stat -c%s '{bin_path}' 2>/dev/null || stat -f%z '{bin_path}' 2>/dev/null || echo '0')\n\
-         \x20 echo \"github_release={repo}:$VER:$SIZE\"\n\
-         else\n\
-         \x20 echo 'github_release=MISSING:{repo}'\n\
-         fi"
-    )
-}
+pub use super::binary_release::{
+    absent_script, apply_script, check_script, install_script, state_query_script,
+};
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::release_source::{pinned_tag, ReleaseAsset, ResolvedRelease};
     use crate::core::types::{MachineTarget, Resource, ResourceType};
 
     fn make_github_release_resource(repo: &str, binary: &str) -> Resource {
@@ -161,6 +46,20 @@ mod tests {
         }
     }
 
+    fn nightly(asset: &str) -> ResolvedRelease {
+        ResolvedRelease {
+            tag: "nightly".to_string(),
+            version: semver::Version::new(0, 0, 0),
+            asset: ReleaseAsset {
+                name: asset.to_string(),
+                url: format!("https://github.com/paiml/aprender/releases/download/nightly/{asset}"),
+                sha256: None,
+            },
+            sha256: "ab".repeat(32),
+            signature: None,
+        }
+    }
+
     #[test]
     fn test_fj034_check_installed() {
         let r = make_github_release_resource("paiml/forjar", "forjar");
@@ -174,12 +73,21 @@ mod tests {
     #[test]
     fn test_fj034_apply_present() {
         let r = make_github_release_resource("paiml/aprender", "apr");
-        let script = apply_script(&r);
+        let script = install_script(&r, &nightly("apr-aarch64-unknown-linux-gnu.tar.gz"));
         assert!(script.contains("set -euo pipefail"));
-        assert!(script.contains("paiml/aprender/releases/tags/nightly"));
+        assert!(script.contains("paiml/aprender/releases/download/nightly"));
         assert!(script.contains("aarch64-unknown-linux-gnu"));
         assert!(script.contains("/home/user/.cargo/bin/apr"));
-        assert!(script.contains("chmod +x"));
+        assert!(script.contains("chmod 0755"));
+        assert!(script.contains("CHECKSUM MISMATCH"));
+    }
+
+    #[test]
+    fn test_fj034_apply_needs_resolution() {
+        let r = make_github_release_resource("paiml/aprender", "apr");
+        let script = apply_script(&r);
+        assert!(script.contains("github_release paiml/aprender is resolved"));
+        assert!(script.contains("exit 1"));
     }
 
     #[test]
@@ -187,7 +95,7 @@ mod tests {
         let mut r = make_github_release_resource("paiml/forjar", "forjar");
         r.state = Some("absent".to_string());
         let script = apply_script(&r);
-        assert!(script.contains("rm -f '/home/user/.cargo/bin/forjar'"));
+        assert!(script.contains("unlink '/home/user/.cargo/bin/forjar'"));
         assert!(script.contains("removed:paiml/forjar"));
     }
 
@@ -196,8 +104,9 @@ mod tests {
         let r = make_github_release_resource("paiml/copia", "copia");
         let script = state_query_script(&r);
         assert!(script.contains("/home/user/.cargo/bin/copia"));
-        assert!(script.contains("github_release=paiml/copia"));
-        assert!(script.contains("github_release=MISSING:paiml/copia"));
+        assert!(script.contains("binary_release=paiml/copia"));
+        assert!(script.contains("binary_release=MISSING:paiml/copia"));
+        assert!(script.contains("sha256sum"));
     }
 
     #[test]
@@ -209,58 +118,43 @@ mod tests {
     }
 
     #[test]
-    fn test_fj034_default_tag() {
+    fn test_fj034_tag_pins_release() {
         let mut r = make_github_release_resource("paiml/forjar", "forjar");
+        assert_eq!(pinned_tag(&r), Some("nightly"));
+        r.tag = Some("latest".to_string());
+        assert_eq!(pinned_tag(&r), None);
         r.tag = None;
-        let script = apply_script(&r);
-        assert!(script.contains("releases/tags/latest"));
+        assert_eq!(pinned_tag(&r), None);
     }
 
     #[test]
-    fn test_fj034_tarball_extraction() {
+    fn test_fj034_archive_extraction() {
         let r = make_github_release_resource("paiml/aprender", "apr");
-        let script = apply_script(&r);
-        assert!(script.contains("*.tar.gz|*.tgz)"));
-        assert!(script.contains("tar xzf"));
+        let script = install_script(&r, &nightly("apr.tar.gz"));
+        assert!(script.contains("*.tar.gz|*.tgz|*.tar.xz|*.tar)"));
+        assert!(script.contains("tar xf"));
         assert!(script.contains("*.zip)"));
-        assert!(script.contains("unzip -o"));
-    }
-
-    #[test]
-    fn test_fj034_tmpdir_cleanup() {
-        let r = make_github_release_resource("paiml/forjar", "forjar");
-        let script = apply_script(&r);
+        assert!(script.contains("unzip -oq"));
         assert!(script.contains("mktemp -d"));
         assert!(script.contains("trap 'rm -rf"));
     }
 
     #[test]
-    fn test_fj034_verify_after_install() {
+    fn test_fj034_smoke_check_and_rollback() {
         let r = make_github_release_resource("paiml/forjar", "forjar");
-        let script = apply_script(&r);
+        let script = install_script(&r, &nightly("forjar"));
         assert!(script.contains("--version"));
-        assert!(script.contains("installed:paiml/forjar"));
-    }
-
-    #[test]
-    fn test_fj034_binary_not_found_error() {
-        let r = make_github_release_resource("paiml/forjar", "forjar");
-        let script = apply_script(&r);
-        assert!(script.contains("binary 'forjar' not found in release asset"));
-        assert!(script.contains("exit 1"));
-    }
-
-    #[test]
-    fn test_fj034_mkdir_install_dir() {
-        let r = make_github_release_resource("paiml/forjar", "forjar");
-        let script = apply_script(&r);
+        assert!(script.contains("ROLLBACK: restored the previous forjar"));
+        assert!(script.contains("binary 'forjar' not found in forjar"));
         assert!(script.contains("mkdir -p '/home/user/.cargo/bin'"));
+        assert!(script.contains("installed:paiml/forjar:nightly"));
     }
 
     #[test]
-    fn test_fj034_state_query_includes_size() {
+    fn test_fj034_republished_tag_reinstalls() {
         let r = make_github_release_resource("paiml/forjar", "forjar");
-        let script = state_query_script(&r);
-        assert!(script.contains("stat -c%s") || script.contains("stat"));
+        let script = install_script(&r, &nightly("forjar"));
+        // Unchanged only when both the tag and the asset checksum match
+        assert!(script.contains(&format!("[ \"$CURRENT_SHA\" = '{}' ]", "ab".repeat(32))));
     }
 }
@@ -2,9 +2,22 @@
 //!
 //! Manages GPU driver installation, verification, and state queries.
 //! Supports multiple backends: nvidia (default), rocm (AMD), and cpu (no-op).
+//! On NVIDIA the declared driver and CUDA versions are enforced, and MIG
+//! layout, power limits and the container toolkit are managed (see `gpu_b`).
 
+use super::gpu_b;
 use crate::core::types::Resource;
 
+/// Shell setting `$DRIVER` to the live driver version (empty without one).
+const DRIVER_QUERY: &str = "DRIVER=\"$(nvidia-smi --query-gpu=driver_version --format=csv,noheader 2>/dev/null | head -n 1 || true)\"";
+
+/// Shell setting `$CUDA` to the live CUDA toolkit release (e.g. `12.3`),
+/// preferring `/usr/local/cuda` over whatever `nvcc` is on PATH.
+const CUDA_QUERY: &str = "NVCC=/usr/local/cuda/bin/nvcc; [ -x \"$NVCC\" ] || NVCC=nvcc; CUDA=\"$(\"$NVCC\" --version 2>/dev/null | sed -n 's/.*release \\([0-9][0-9.]*\\),.*/\\1/p' || true)\"";
+
+/// CDI spec generated by `nvidia-ctk` (container toolkit).
+pub(super) const CDI_SPEC: &str = "/etc/cdi/nvidia.yaml";
+
 /// Resolve the GPU backend from the resource config.
 /// Defaults to "nvidia" when `gpu_backend` is None.
 fn resolve_backend(resource: &Resource) -> &str {
@@ -23,17 +36,35 @@ pub fn check_script(resource: &Resource) -> String {
     }
 }
 
-fn check_script_nvidia(name: &str, state: &str, _resource: &Resource) -> String {
-    // FJ-1009: If nvidia-smi works, accept the driver regardless of version.
-    // Vendor/host drivers (Lambda, RunPod, --gpus all) can't be swapped.
-    match state {
-        "absent" => format!(
+fn check_script_nvidia(name: &str, state: &str, resource: &Resource) -> String {
+    if state == "absent" {
+        return format!(
             "if command -v nvidia-smi >/dev/null 2>&1; then\n  echo 'exists:{name}'\nelse\n  echo 'absent:{name}'\nfi"
-        ),
-        _ => format!(
-            "if command -v nvidia-smi >/dev/null 2>&1; then\n  echo 'match:{name}'\nelse\n  echo 'missing:{name}'\nfi"
-        ),
+        );
     }
+    // FJ-1009: a working nvidia-smi is only a match when the live driver
+    // (and CUDA toolkit, if declared) is the declared one.
+    let mut lines = vec![format!(
+        "if ! command -v nvidia-smi >/dev/null 2>&1; then echo 'missing:{name}'; exit 0; fi"
+    )];
+    if let Some(driver) = declared(&resource.driver_version) {
+        lines.push(DRIVER_QUERY.to_string());
+        lines.push(format!(
+            "case \"$DRIVER\" in\n\
+             \x20 {}) ;;\n\
+             \x20 *) echo 'mismatch:{name}'; exit 0 ;;\n\
+             esac",
+            driver_pattern(driver)
+        ));
+    }
+    if let Some(cuda) = declared(&resource.cuda_version) {
+        lines.push(CUDA_QUERY.to_string());
+        lines.push(format!(
+            "if [ \"$CUDA\" != '{cuda}' ]; then echo 'mismatch:{name}'; exit 0; fi"
+        ));
+    }
+    lines.push(format!("echo 'match:{name}'"));
+    lines.join("\n")
 }
 
 fn check_script_rocm(name: &str, state: &str, resource: &Resource) -> String {
@@ -70,45 +101,58 @@ pub fn apply_script(resource: &Resource) -> String {
 
 fn apply_script_nvidia(name: &str, state: &str, resource: &Resource) -> String {
     if state == "absent" {
-        return format!(
-            "set -euo pipefail\n$SUDO apt-get remove -y 'nvidia-driver-*' 2>/dev/null || true\necho 'removed:{name}'"
+        let mut script = String::from(
+            "set -euo pipefail\n$SUDO apt-get remove -y 'nvidia-driver-*' 2>/dev/null || true\n",
         );
+        if resource.container_toolkit {
+            script.push_str(&format!(
+                "if [ -e '{CDI_SPEC}' ]; then $SUDO unlink '{CDI_SPEC}'; fi\n"
+            ));
+        }
+        script.push_str(&format!("echo 'removed:{name}'"));
+        return script;
     }
 
-    let mut script =
-        String::from("set -euo pipefail\nSUDO=\"\"\n[ \"$(id -u)\" -ne 0 ] && SUDO=\"sudo\"\n");
+    let mut script = String::from(
+        "set -euo pipefail\nSUDO=\"\"\n[ \"$(id -u)\" -ne 0 ] && SUDO=\"sudo\"\nREBOOT_REQUIRED=0\n",
+    );
     emit_nvidia_driver_install(&mut script, resource);
     emit_cuda_toolkit(&mut script, resource);
     emit_nvidia_post_install(&mut script, resource);
+    if resource.container_toolkit {
+        gpu_b::emit_container_toolkit(&mut script);
+    }
     script.push_str(&format!("echo 'installed:{name}'"));
     script
 }
 
-/// PMAT-036 + FJ-1009: Install nvidia driver only when nvidia-smi is absent.
-/// When nvidia-smi works, accept the host/vendor driver even on version mismatch
-/// (--gpus-all containers pass through the host driver which cannot be changed).
+/// Declared version, treating an empty string as unset.
+fn declared(version: &Option<String>) -> Option<&str> {
+    version.as_deref().filter(|v| !v.is_empty())
+}
+
+/// `case` pattern matching a driver version and its point releases
+/// (`535` matches `535.129.03` but not `5350.1`).
+fn driver_pattern(driver: &str) -> String {
+    format!("'{driver}'|'{driver}'.*")
+}
+
+/// apt package for a driver version (`535.129.03` → `nvidia-driver-535`).
+pub fn driver_package(driver: &str) -> String {
+    let branch = driver.split('.').next().unwrap_or(driver);
+    format!("nvidia-driver-{branch}")
+}
+
+/// PMAT-036 + FJ-1009: Converge the NVIDIA driver to `driver_version`.
 ///
-/// When nvidia-smi is absent AND apt-get install fails (e.g. no NVIDIA apt repo
-/// in containers), detect /.dockerenv as a container signal and accept gracefully
-/// instead of failing hard — the host is expected to provide GPU access via
-/// --gpus all / NVIDIA Container Toolkit.
+/// A missing or mismatched driver is installed from the `nvidia-driver-<branch>`
+/// package, which replaces any other branch (upgrade or downgrade); the new
+/// kernel module only loads after a reboot, so runtime settings are skipped
+/// until then. Inside a container the host driver cannot be changed: a
+/// missing `nvidia-smi` is accepted (the host provides GPU access via
+/// `--gpus all`), a mismatched one fails the apply.
 fn emit_nvidia_driver_install(script: &mut String, resource: &Resource) {
-    let driver_version = resource.driver_version.as_deref().unwrap_or("");
-    if !driver_version.is_empty() {
-        script.push_str(&format!(
-            "if command -v nvidia-smi >/dev/null 2>&1; then\n\
-             \x20 INSTALLED_VER=$(nvidia-smi --query-gpu=driver_version --format=csv,noheader 2>/dev/null | head -1)\n\
-             \x20 case \"$INSTALLED_VER\" in\n\
-             \x20   '{driver_version}'*) ;;\n\
-             \x20   *) echo \"NOTICE: requested driver {driver_version} but $INSTALLED_VER is installed (vendor/host driver — accepting)\" ;;\n\
-             \x20 esac\n\
-             elif [ -f /.dockerenv ] || grep -q docker /proc/1/cgroup 2>/dev/null; then\n\
-             \x20 echo \"NOTICE: container detected, skipping driver install (host provides GPU via --gpus all)\"\n\
-             else\n\
-             \x20 $SUDO apt-get install -y 'nvidia-driver-{driver_version}'\n\
-             fi\n"
-        ));
-    } else {
+    let Some(driver) = declared(&resource.driver_version) else {
         script.push_str(
             "if command -v nvidia-smi >/dev/null 2>&1; then\n\
              \x20 true\n\
@@ -118,40 +162,83 @@ fn emit_nvidia_driver_install(script: &mut String, resource: &Resource) {
              \x20 $SUDO apt-get install -y nvidia-driver\n\
              fi\n",
         );
-    }
+        return;
+    };
+    script.push_str(&format!(
+        "{DRIVER_QUERY}\n\
+         case \"$DRIVER\" in\n\
+         \x20 {pattern}) ;;\n\
+         \x20 *)\n\
+         \x20   if [ -f /.dockerenv ] || grep -q docker /proc/1/cgroup 2>/dev/null; then\n\
+         \x20     if [ -n \"$DRIVER\" ]; then\n\
+         \x20       echo \"ERROR: host driver $DRIVER does not match {driver} and cannot be changed from inside a container\" >&2\n\
+         \x20       exit 1\n\
+         \x20     fi\n\
+         \x20     echo \"NOTICE: container detected, skipping driver install (host provides GPU via --gpus all)\"\n\
+         \x20   else\n\
+         \x20     $SUDO apt-get install -y '{package}'\n\
+         \x20     if [ -n \"$DRIVER\" ]; then\n\
+         \x20       echo \"NOTICE: driver changed from $DRIVER to {driver}; reboot to load the new kernel module\"\n\
+         \x20       REBOOT_REQUIRED=1\n\
+         \x20     fi\n\
+         \x20     echo 'changed:driver'\n\
+         \x20   fi\n\
+         \x20   ;;\n\
+         esac\n",
+        pattern = driver_pattern(driver),
+        package = driver_package(driver),
+    ));
 }
 
+/// Install `cuda-toolkit-X-Y` when the live `nvcc` reports another release,
+/// and point `/usr/local/cuda` at it.
 fn emit_cuda_toolkit(script: &mut String, resource: &Resource) {
-    if let Some(ref cuda) = resource.cuda_version {
-        if !cuda.is_empty() {
-            let cuda_pkg = cuda.replace('.', "-");
-            script.push_str(&format!(
-                "if command -v nvcc >/dev/null 2>&1; then\n\
-                 \x20 true\n\
-                 elif [ -f /.dockerenv ] || grep -q docker /proc/1/cgroup 2>/dev/null; then\n\
-                 \x20 echo \"NOTICE: container detected, skipping CUDA toolkit install\"\n\
-                 else\n\
-                 \x20 $SUDO apt-get install -y 'cuda-toolkit-{cuda_pkg}'\n\
-                 fi\n"
-            ));
-        }
-    }
+    let Some(cuda) = declared(&resource.cuda_version) else {
+        return;
+    };
+    let cuda_pkg = cuda.replace('.', "-");
+    script.push_str(&format!(
+        "{CUDA_QUERY}\n\
+         if [ \"$CUDA\" != '{cuda}' ]; then\n\
+         \x20 $SUDO apt-get install -y 'cuda-toolkit-{cuda_pkg}'\n\
+         \x20 $SUDO ln -sfn '/usr/local/cuda-{cuda}' /usr/local/cuda\n\
+         \x20 echo 'changed:cuda'\n\
+         fi\n"
+    ));
 }
 
+/// Runtime settings need the loaded driver, so they wait for the reboot
+/// after a driver change.
 fn emit_nvidia_post_install(script: &mut String, resource: &Resource) {
-    if resource.persistence_mode.unwrap_or(true) {
-        script.push_str("$SUDO systemctl enable --now nvidia-persistenced 2>/dev/null || true\n");
-    }
+    let mut runtime = String::new();
     if let Some(ref mode) = resource.compute_mode {
         let mode_val = match mode.as_str() {
             "exclusive_process" => "1",
             "prohibited" => "2",
             _ => "0",
         };
-        script.push_str(&format!(
+        runtime.push_str(&format!(
             "$SUDO nvidia-smi -c {mode_val} 2>/dev/null || true\n"
         ));
     }
+    if let Some(watts) = resource.power_limit_watts {
+        gpu_b::emit_power_limit(&mut runtime, watts);
+    }
+    if !resource.mig_profiles.is_empty() {
+        gpu_b::emit_mig_layout(&mut runtime, resource);
+    }
+    if resource.persistence_mode.unwrap_or(true) {
+        script.push_str("$SUDO systemctl enable --now nvidia-persistenced 2>/dev/null || true\n");
+    }
+    if !runtime.is_empty() {
+        script.push_str("if [ \"$REBOOT_REQUIRED\" = 0 ]; then\n");
+        if resource.power_limit_watts.is_some() || !resource.mig_profiles.is_empty() {
+            script.push_str(&gpu_b::gpu_list_line(resource));
+            script.push('\n');
+        }
+        script.push_str(&runtime);
+        script.push_str("fi\n");
+    }
 }
 
 fn apply_script_rocm(name: &str, state: &str, resource: &Resource) -> String {
@@ -192,8 +279,23 @@ pub fn state_query_script(resource: &Resource) -> String {
         "rocm" => format!(
             "if command -v rocminfo >/dev/null 2>&1; then\n  DEV=$(rocminfo 2>/dev/null | grep -m1 'Marketing Name' | sed 's/.*: *//')\n  VER=$(cat /sys/module/amdgpu/version 2>/dev/null || echo \"kernel-$(uname -r)\")\n  echo \"gpu={name}:$DEV:$VER\"\nelse\n  echo 'gpu=MISSING:{name}'\nfi"
         ),
-        _ => format!(
-            "if command -v nvidia-smi >/dev/null 2>&1; then\n  VER=$(nvidia-smi --query-gpu=driver_version,compute_mode,memory.total --format=csv,noheader 2>/dev/null | head -1)\n  echo \"gpu={name}:$VER\"\nelse\n  echo 'gpu=MISSING:{name}'\nfi"
-        ),
+        _ => state_query_nvidia(name, resource),
+    }
+}
+
+/// NVIDIA state: driver, compute mode and memory, plus the CUDA release,
+/// power limits, MIG layout and CDI spec when those are declared.
+fn state_query_nvidia(name: &str, resource: &Resource) -> String {
+    let mut lines = vec![format!(
+        "  VER=$(nvidia-smi --query-gpu=driver_version,compute_mode,memory.total --format=csv,noheader 2>/dev/null | head -1)\n  echo \"gpu={name}:$VER\""
+    )];
+    if declared(&resource.cuda_version).is_some() {
+        lines.push(format!("  {}", CUDA_QUERY));
+        lines.push("  echo \"cuda=$CUDA\"".to_string());
     }
+    lines.extend(gpu_b::state_query_lines(resource));
+    format!(
+        "if command -v nvidia-smi >/dev/null 2>&1; then\n{}\nelse\n  echo 'gpu=MISSING:{name}'\nfi",
+        lines.join("\n")
+    )
 }
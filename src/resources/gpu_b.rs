@@ -0,0 +1,131 @@
+//! NVIDIA runtime settings for the GPU resource: power limits, MIG layout
+//! and the container toolkit with its CDI spec.
+//!
+//! Power and MIG settings apply to `devices` (default: every GPU), looping
+//! over `$GPUS` set by [`gpu_list_line`].
+
+use super::gpu::CDI_SPEC;
+use crate::core::types::Resource;
+
+/// Shell setting `$GPUS` to the managed device indices.
+pub(super) fn gpu_list_line(resource: &Resource) -> String {
+    if resource.devices.is_empty() {
+        "GPUS=\"$(nvidia-smi --query-gpu=index --format=csv,noheader)\"".to_string()
+    } else {
+        let ids: Vec<String> = resource.devices.iter().map(u32::to_string).collect();
+        format!("GPUS='{}'", ids.join(" "))
+    }
+}
+
+/// `--id=` argument scoping `nvidia-smi --query-gpu` to `devices`.
+fn id_arg(resource: &Resource) -> String {
+    if resource.devices.is_empty() {
+        String::new()
+    } else {
+        let ids: Vec<String> = resource.devices.iter().map(u32::to_string).collect();
+        format!(" --id={}", ids.join(","))
+    }
+}
+
+/// Declared MIG layout as compared on the host: profiles sorted, comma-joined.
+pub fn mig_layout(resource: &Resource) -> String {
+    let mut profiles: Vec<&str> = resource.mig_profiles.iter().map(String::as_str).collect();
+    profiles.sort_unstable();
+    profiles.join(",")
+}
+
+/// Shell printing a GPU's live MIG layout in [`mig_layout`] form.
+fn live_mig_layout(gpu: &str) -> String {
+    format!(
+        "{{ nvidia-smi mig -i {gpu} -lgi 2>/dev/null || true; }} | sed -n 's/.*MIG \\([0-9][0-9a-z.+]*\\) .*/\\1/p' | LC_ALL=C sort | paste -sd, -"
+    )
+}
+
+/// Set the board power limit where the current one differs.
+pub(super) fn emit_power_limit(script: &mut String, watts: u32) {
+    script.push_str(&format!(
+        "for GPU in $GPUS; do\n\
+         \x20 PL=\"$(nvidia-smi -i \"$GPU\" --query-gpu=power.limit --format=csv,noheader,nounits | head -n 1)\"\n\
+         \x20 if [ \"${{PL%%.*}}\" != '{watts}' ]; then\n\
+         \x20   $SUDO nvidia-smi -i \"$GPU\" -pl {watts}\n\
+         \x20   echo \"changed:power_limit:$GPU\"\n\
+         \x20 fi\n\
+         done\n"
+    ));
+}
+
+/// Enable MIG mode and recreate the GPU instances when the live layout
+/// differs from `mig_profiles`. Enabling MIG may need a GPU reset, in which
+/// case the layout is left for the next apply.
+pub(super) fn emit_mig_layout(script: &mut String, resource: &Resource) {
+    let layout = mig_layout(resource);
+    script.push_str(&format!(
+        "for GPU in $GPUS; do\n\
+         \x20 MIG_MODE=\"$(nvidia-smi -i \"$GPU\" --query-gpu=mig.mode.current --format=csv,noheader | head -n 1)\"\n\
+         \x20 if [ \"$MIG_MODE\" != Enabled ]; then\n\
+         \x20   $SUDO nvidia-smi -i \"$GPU\" -mig 1\n\
+         \x20   echo \"changed:mig_mode:$GPU\"\n\
+         \x20   MIG_MODE=\"$(nvidia-smi -i \"$GPU\" --query-gpu=mig.mode.current --format=csv,noheader | head -n 1)\"\n\
+         \x20 fi\n\
+         \x20 if [ \"$MIG_MODE\" != Enabled ]; then\n\
+         \x20   echo \"NOTICE: MIG mode on GPU $GPU is pending; reset the GPU or reboot, then re-apply\"\n\
+         \x20   continue\n\
+         \x20 fi\n\
+         \x20 LAYOUT=\"$({live})\"\n\
+         \x20 if [ \"$LAYOUT\" != '{layout}' ]; then\n\
+         \x20   $SUDO nvidia-smi mig -i \"$GPU\" -dci 2>/dev/null || true\n\
+         \x20   $SUDO nvidia-smi mig -i \"$GPU\" -dgi 2>/dev/null || true\n\
+         \x20   $SUDO nvidia-smi mig -i \"$GPU\" -cgi '{layout}' -C\n\
+         \x20   echo \"changed:mig:$GPU\"\n\
+         \x20 fi\n\
+         done\n",
+        live = live_mig_layout("\"$GPU\""),
+    ));
+}
+
+/// Install the NVIDIA container toolkit and (re)generate the CDI spec.
+/// The spec embeds driver library paths, so it is regenerated on every
+/// apply and replaced only when it changed.
+pub(super) fn emit_container_toolkit(script: &mut String) {
+    script.push_str(&format!(
+        "if ! command -v nvidia-ctk >/dev/null 2>&1; then\n\
+         \x20 $SUDO apt-get install -y nvidia-container-toolkit\n\
+         fi\n\
+         $SUDO mkdir -p \"$(dirname '{CDI_SPEC}')\"\n\
+         $SUDO nvidia-ctk cdi generate --output='{CDI_SPEC}.forjar-new'\n\
+         if cmp -s '{CDI_SPEC}.forjar-new' '{CDI_SPEC}'; then\n\
+         \x20 $SUDO unlink '{CDI_SPEC}.forjar-new'\n\
+         else\n\
+         \x20 $SUDO mv '{CDI_SPEC}.forjar-new' '{CDI_SPEC}'\n\
+         \x20 echo 'changed:cdi'\n\
+         fi\n"
+    ));
+}
+
+/// State query lines (inside the `nvidia-smi` present branch) for the
+/// declared runtime settings.
+pub(super) fn state_query_lines(resource: &Resource) -> Vec<String> {
+    let mut lines = Vec::new();
+    let ids = id_arg(resource);
+    if resource.power_limit_watts.is_some() {
+        lines.push(format!(
+            "  nvidia-smi{ids} --query-gpu=index,power.limit --format=csv,noheader 2>/dev/null | sed 's/^/power=/'"
+        ));
+    }
+    if !resource.mig_profiles.is_empty() {
+        lines.push(format!("  {}", gpu_list_line(resource)));
+        lines.push(format!(
+            "  for GPU in $GPUS; do\n\
+             \x20   MIG_MODE=\"$(nvidia-smi -i \"$GPU\" --query-gpu=mig.mode.current --format=csv,noheader 2>/dev/null | head -n 1)\"\n\
+             \x20   echo \"mig=$GPU:$MIG_MODE:$({})\"\n\
+             \x20 done",
+            live_mig_layout("\"$GPU\"")
+        ));
+    }
+    if resource.container_toolkit {
+        lines.push(format!(
+            "  if [ -f '{CDI_SPEC}' ]; then cksum '{CDI_SPEC}'; else echo 'cdi=MISSING'; fi"
+        ));
+    }
+    lines
+}
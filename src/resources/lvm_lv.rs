@@ -0,0 +1,80 @@
+//! LVM logical volume resource handler.
+//!
+//! `name` is the logical volume inside `volume_group`. `size` is either an
+//! absolute size ("20G") or an extent percentage ("100%FREE", "50%VG").
+//! A missing volume is created with `lvcreate`; an existing one is grown
+//! with `lvextend` when the requested absolute size is larger. Volumes are
+//! never shrunk — a smaller request is reported with `FORJAR_WARN`.
+
+use super::disk::{parse_size_bytes, size_arg};
+use crate::core::types::Resource;
+
+fn lv_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("lv0")
+}
+
+fn vg_name(resource: &Resource) -> &str {
+    resource.volume_group.as_deref().unwrap_or("vg0")
+}
+
+/// Whether `size` is an extent percentage (`-l`) rather than a byte size (`-L`).
+pub fn is_extent_size(size: &str) -> bool {
+    size.contains('%')
+}
+
+/// Generate shell to check whether the logical volume exists.
+pub fn check_script(resource: &Resource) -> String {
+    let lv = format!("{}/{}", vg_name(resource), lv_name(resource));
+    format!("if lvs '{lv}' >/dev/null 2>&1; then echo 'exists:{lv}'; else echo 'missing:{lv}'; fi")
+}
+
+/// Generate shell to converge the logical volume.
+pub fn apply_script(resource: &Resource) -> String {
+    let vg = vg_name(resource);
+    let name = lv_name(resource);
+    let lv = format!("{vg}/{name}");
+    let mut lines = vec!["set -euo pipefail".to_string()];
+
+    if resource.state.as_deref() == Some("absent") {
+        lines.push(format!(
+            "if lvs '{lv}' >/dev/null 2>&1; then lvremove -y '{lv}'; fi"
+        ));
+        return lines.join("\n");
+    }
+
+    let size = resource.size.as_deref().unwrap_or("100%FREE");
+    let size_flag = if is_extent_size(size) {
+        format!("-l '{size}'")
+    } else {
+        format!("-L '{}'", size_arg(size))
+    };
+    let mut grow = String::new();
+    if let Some(bytes) = parse_size_bytes(size) {
+        grow = format!(
+            "\x20 CUR=\"$(lvs --noheadings --nosuffix --units b -o lv_size '{lv}' | tr -d ' ')\"\n\
+             \x20 if [ \"$CUR\" -lt '{bytes}' ]; then\n\
+             \x20   lvextend -L '{arg}' '{lv}'\n\
+             \x20 elif [ \"$CUR\" -gt '{bytes}' ]; then\n\
+             \x20   echo \"FORJAR_WARN: {lv} is $CUR bytes, larger than {size}; volumes are never shrunk\"\n\
+             \x20 fi\n",
+            arg = size_arg(size)
+        );
+    }
+    lines.push(format!(
+        "if ! lvs '{lv}' >/dev/null 2>&1; then\n\
+         \x20 lvcreate -y -n '{name}' {size_flag} '{vg}'\n\
+         else\n\
+         {grow}\
+         \x20 :\n\
+         fi"
+    ));
+    lines.join("\n")
+}
+
+/// Generate shell to query logical volume state (for BLAKE3 hashing).
+pub fn state_query_script(resource: &Resource) -> String {
+    let lv = format!("{}/{}", vg_name(resource), lv_name(resource));
+    format!(
+        "lvs --noheadings --units b -o lv_name,vg_name,lv_size '{lv}' 2>/dev/null || echo 'lvm_lv=MISSING:{lv}'"
+    )
+}
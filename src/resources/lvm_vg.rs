@@ -0,0 +1,95 @@
+//! LVM volume group resource handler.
+//!
+//! `name` is the volume group, `physical_volumes` the devices backing it.
+//! Missing PVs are initialised with `pvcreate` (refused when the device
+//! already carries a foreign signature unless `force`), the group is created
+//! with `vgcreate` or grown with `vgextend`. PVs are never removed from an
+//! existing group, and `state: absent` only removes a group that holds no
+//! logical volumes unless forced.
+
+use crate::core::types::Resource;
+
+fn vg_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("vg0")
+}
+
+fn pv_list(resource: &Resource) -> String {
+    resource
+        .physical_volumes
+        .iter()
+        .map(|pv| format!("'{pv}'"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Generate shell to check whether the volume group exists.
+pub fn check_script(resource: &Resource) -> String {
+    let vg = vg_name(resource);
+    format!("if vgs '{vg}' >/dev/null 2>&1; then echo 'exists:{vg}'; else echo 'missing:{vg}'; fi")
+}
+
+/// Generate shell to converge the volume group.
+pub fn apply_script(resource: &Resource) -> String {
+    let vg = vg_name(resource);
+    let mut lines = vec!["set -euo pipefail".to_string()];
+
+    if resource.state.as_deref() == Some("absent") {
+        if !resource.force {
+            lines.push(format!(
+                "LVS=\"$(vgs --noheadings -o lv_count '{vg}' 2>/dev/null | tr -d ' ' || true)\"\n\
+                 if [ -n \"$LVS\" ] && [ \"$LVS\" != '0' ]; then\n\
+                 \x20 echo 'ERROR: volume group {vg} still holds logical volumes; refusing without force' >&2\n\
+                 \x20 exit 1\n\
+                 fi"
+            ));
+        }
+        lines.push(format!(
+            "if vgs '{vg}' >/dev/null 2>&1; then vgremove -f '{vg}'; fi"
+        ));
+        return lines.join("\n");
+    }
+
+    // Initialise physical volumes that LVM does not know yet
+    let pvcreate = if resource.force {
+        "pvcreate -ff -y"
+    } else {
+        "pvcreate"
+    };
+    lines.push(format!("for pv in {}; do", pv_list(resource)));
+    lines.push("  if ! pvs \"$pv\" >/dev/null 2>&1; then".to_string());
+    if !resource.force {
+        lines.push(
+            "    SIG=\"$(blkid -p -o value -s TYPE \"$pv\" 2>/dev/null || true)\"\n\
+             \x20   if [ -n \"$SIG\" ]; then\n\
+             \x20     echo \"ERROR: $pv holds $SIG; refusing to pvcreate without force\" >&2\n\
+             \x20     exit 1\n\
+             \x20   fi"
+                .to_string(),
+        );
+    }
+    lines.push(format!("    {pvcreate} \"$pv\""));
+    lines.push("  fi".to_string());
+    lines.push("done".to_string());
+
+    lines.push(format!(
+        "if ! vgs '{vg}' >/dev/null 2>&1; then\n\
+         \x20 vgcreate '{vg}' {pvs}\n\
+         else\n\
+         \x20 for pv in {pvs}; do\n\
+         \x20   if [ \"$(pvs --noheadings -o vg_name \"$pv\" | tr -d ' ')\" != '{vg}' ]; then vgextend '{vg}' \"$pv\"; fi\n\
+         \x20 done\n\
+         fi",
+        pvs = pv_list(resource)
+    ));
+    lines.join("\n")
+}
+
+/// Generate shell to query volume group state (for BLAKE3 hashing).
+pub fn state_query_script(resource: &Resource) -> String {
+    let vg = vg_name(resource);
+    format!(
+        "vgs --noheadings -o vg_name,pv_count,vg_size --units b '{vg}' 2>/dev/null \
+         && pvs --noheadings -o pv_name -S 'vg_name={vg}' 2>/dev/null | sort \
+         || echo 'lvm_vg=MISSING:{vg}'"
+    )
+}
@@ -5,48 +5,112 @@
 //! 2. An "apply" script that converges to desired state
 //! 3. A "hash" function that computes the BLAKE3 of observable state
 
+pub mod apparmor_profile;
+pub mod binary_release;
 pub mod build;
+pub mod certificate;
+pub mod container_stack;
 pub mod cron;
+pub mod disk;
 pub mod docker;
+pub mod docker_network;
+pub mod docker_volume;
+pub mod exec;
 pub mod file;
+pub mod filesystem;
 pub mod github_release;
 pub mod gpu;
+mod gpu_b;
+pub mod lvm_lv;
+pub mod lvm_vg;
 pub mod model;
+pub mod model_hf;
 pub mod mount;
 pub mod network;
+pub mod network_interface;
 pub mod package;
+pub mod partition;
 pub mod pepita;
+pub mod pg_database;
+pub mod pg_extension;
+pub mod pg_role;
+pub mod quadlet;
+pub mod selinux_boolean;
+pub mod selinux_fcontext;
+pub mod selinux_mode;
 pub mod service;
+pub mod swap;
+pub mod system;
 pub mod task;
 #[cfg(test)]
 mod tests_service;
 #[cfg(test)]
 mod tests_task;
 pub mod user;
+pub mod wait_for;
 pub mod wasm_bundle;
+pub mod wireguard_interface;
+pub mod wireguard_peer;
+pub mod zfs_dataset;
+pub mod zfs_pool;
+pub mod zfs_snapshot_policy;
 
 mod network_b;
 #[cfg(test)]
+mod tests_binary_release;
+#[cfg(test)]
 mod tests_build;
 #[cfg(test)]
+mod tests_certificate;
+#[cfg(test)]
+mod tests_container_stack;
+#[cfg(test)]
+mod tests_disk;
+#[cfg(test)]
+mod tests_disk_b;
+#[cfg(test)]
 mod tests_docker;
 #[cfg(test)]
 mod tests_docker_b;
 #[cfg(test)]
+mod tests_docker_c;
+#[cfg(test)]
+mod tests_docker_network;
+#[cfg(test)]
+mod tests_exec;
+#[cfg(test)]
 mod tests_file;
 #[cfg(test)]
 mod tests_file_b;
 #[cfg(test)]
 mod tests_gpu;
 #[cfg(test)]
+mod tests_gpu_b;
+#[cfg(test)]
+mod tests_mac;
+#[cfg(test)]
+mod tests_model_hf;
+#[cfg(test)]
 mod tests_mount;
 #[cfg(test)]
 mod tests_mount_b;
 #[cfg(test)]
+mod tests_network_interface;
+#[cfg(test)]
 mod tests_package;
 #[cfg(test)]
 mod tests_package_b;
 #[cfg(test)]
 mod tests_package_c;
 #[cfg(test)]
+mod tests_pg;
+#[cfg(test)]
+mod tests_system;
+#[cfg(test)]
 mod tests_user;
+#[cfg(test)]
+mod tests_wait_for;
+#[cfg(test)]
+mod tests_wireguard;
+#[cfg(test)]
+mod tests_zfs;
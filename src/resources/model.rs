@@ -2,11 +2,16 @@
 //!
 //! Manages ML model downloads, integrity verification, and cache management.
 //! Uses BLAKE3 for content-addressed drift detection on model files.
+//! `hf://org/repo@revision` sources are handled by `model_hf`.
 
+use super::model_hf;
 use crate::core::types::Resource;
 
 /// Generate shell script to check if a model exists and matches checksum.
 pub fn check_script(resource: &Resource) -> String {
+    if model_hf::is_hf(resource) {
+        return model_hf::check_script(resource);
+    }
     let name = resource.name.as_deref().unwrap_or("unknown");
     let path = resource.path.as_deref().unwrap_or("/dev/null");
     let state = resource.state.as_deref().unwrap_or("present");
@@ -30,11 +35,10 @@ fn download_command(source: &str, path: &str, cache_dir: &str) -> String {
     if source.starts_with("http://") || source.starts_with("https://") {
         format!("curl -fSL -o '{}' '{}'\n", path, source)
     } else if source.starts_with('/') || source.starts_with("./") || source.starts_with("~/") {
-        // Local path copy; in containers the source may not exist if it's a
-        // placeholder path — create a stub file so dependent resources can proceed.
+        // Local path copy; a missing source is an error, never a stub file.
         format!(
-            "if [ -f '{}' ]; then cp '{}' '{}'; else echo 'NOTICE: source {} not found, creating stub'; touch '{}'; fi\n",
-            source, source, path, source, path
+            "if [ ! -f '{source}' ]; then echo \"ERROR: model source {source} not found\" >&2; exit 1; fi\n\
+             cp '{source}' '{path}'\n"
         )
     } else if source.contains('/') {
         // HuggingFace repo ID — use apr pull with fallback to huggingface-cli
@@ -64,7 +68,16 @@ fn download_command(source: &str, path: &str, cache_dir: &str) -> String {
 }
 
 /// Generate shell script to download/remove a model.
+///
+/// `hf://` sources need a snapshot resolved on the control machine
+/// (`model_hf::apply_script`); without one the script fails.
 pub fn apply_script(resource: &Resource) -> String {
+    if model_hf::is_hf(resource) {
+        return match resource.state.as_deref() {
+            Some("absent") => model_hf::absent_script(resource),
+            _ => model_hf::unresolved_apply_script(resource),
+        };
+    }
     let name = resource.name.as_deref().unwrap_or("unknown");
     let path = resource.path.as_deref().unwrap_or("/dev/null");
     let state = resource.state.as_deref().unwrap_or("present");
@@ -111,6 +124,9 @@ pub fn apply_script(resource: &Resource) -> String {
 
 /// Generate shell to query model state (for BLAKE3 hashing).
 pub fn state_query_script(resource: &Resource) -> String {
+    if model_hf::is_hf(resource) {
+        return model_hf::state_query_script(resource);
+    }
     let name = resource.name.as_deref().unwrap_or("unknown");
     let path = resource.path.as_deref().unwrap_or("/dev/null");
 
@@ -222,6 +238,83 @@ mod tests {
             asset_pattern: None,
             binary: None,
             install_dir: None,
+            runtime: None,
+            pull_policy: None,
+            image_digest: None,
+            labels: vec![],
+            user: None,
+            entrypoint: None,
+            cpus: None,
+            networks: vec![],
+            driver: None,
+            subnet: None,
+            gateway: None,
+            url: None,
+            host: None,
+            expect_status: None,
+            pattern: None,
+            interval: None,
+            backoff: None,
+            creates: None,
+            unless: None,
+            onlyif: None,
+            refreshonly: false,
+            returns: vec![],
+            device: None,
+            size: None,
+            number: None,
+            part_type: None,
+            physical_volumes: vec![],
+            volume_group: None,
+            swappiness: None,
+            force: false,
+            interface: None,
+            addresses: vec![],
+            public_key: None,
+            endpoint: None,
+            allowed_ips: vec![],
+            persistent_keepalive: None,
+            kind: None,
+            members: vec![],
+            vlan_id: None,
+            bond_mode: None,
+            mtu: None,
+            dhcp: None,
+            dns: vec![],
+            routes: vec![],
+            revert_after: None,
+            issuer: None,
+            ca_name: None,
+            ca_cert: None,
+            ca_key: None,
+            common_name: None,
+            sans: vec![],
+            validity_days: None,
+            renew_before: None,
+            key_type: None,
+            key_path: None,
+            chain_path: None,
+            hostname: None,
+            timezone: None,
+            locale: None,
+            keyboard: None,
+            ntp_servers: vec![],
+            vdevs: vec![],
+            properties: Default::default(),
+            dataset: None,
+            keep: None,
+            recursive: false,
+            mig_profiles: vec![],
+            power_limit_watts: None,
+            container_toolkit: false,
+            checksum_file: None,
+            signature: None,
+            password: None,
+            attributes: Vec::new(),
+            database: None,
+            encoding: None,
+            grants: Vec::new(),
+            selinux_type: None,
         }
     }
 
@@ -319,6 +412,8 @@ mod tests {
         r.source = Some("/shared/models/llama.gguf".to_string());
         let script = apply_script(&r);
         assert!(script.contains("cp '/shared/models/llama.gguf'"));
+        assert!(script.contains("ERROR: model source /shared/models/llama.gguf not found"));
+        assert!(!script.contains("touch"));
     }
 
     #[test]
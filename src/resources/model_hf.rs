@@ -0,0 +1,190 @@
+//! Hugging Face Hub (`hf://org/repo@revision`) sources for model resources.
+//!
+//! The revision and file list are resolved on the control machine
+//! (`core::store::hf_hub`). The apply script generated here downloads that
+//! snapshot into `<cache_dir>/hf/<org>--<repo>/snapshots/<commit>`, resuming
+//! partial downloads, checks every file's size and SHA-256, and points
+//! `path` (default `<cache_dir>/hf/<org>--<repo>/current`) at the snapshot.
+//! The commit is exported as `FORJAR_OUTPUT revision=...`, so the lock
+//! records what was actually installed.
+
+use crate::core::store::hf_hub::{self, HfSnapshot};
+use crate::core::types::Resource;
+
+/// Expanded on the target (see [`shell_path`]), not on the control machine.
+const DEFAULT_CACHE_DIR: &str = "~/.cache/apr";
+
+/// Marker file holding the installed commit.
+const REVISION_FILE: &str = ".forjar-revision";
+
+/// Download, resume and verify one file: name, URL, size, SHA-256.
+const FETCH_FN: &str = "forjar_hf_fetch() {\n\
+    \x20 DEST=\"$SNAP/$1\"\n\
+    \x20 if [ -f \"$DEST\" ]; then return 0; fi\n\
+    \x20 mkdir -p \"$(dirname \"$DEST\")\"\n\
+    \x20 if [ -n \"${HF_TOKEN:-}\" ]; then\n\
+    \x20   curl -fSL --retry 3 -C - -H \"Authorization: Bearer $HF_TOKEN\" -o \"$DEST.part\" \"$2\"\n\
+    \x20 else\n\
+    \x20   curl -fSL --retry 3 -C - -o \"$DEST.part\" \"$2\"\n\
+    \x20 fi\n\
+    \x20 SIZE=\"$(wc -c < \"$DEST.part\" | tr -d ' ')\"\n\
+    \x20 if [ -n \"$3\" ] && [ \"$SIZE\" != \"$3\" ]; then\n\
+    \x20   echo \"SIZE MISMATCH: $1 expected $3 got $SIZE\" >&2\n\
+    \x20   unlink \"$DEST.part\"\n\
+    \x20   exit 1\n\
+    \x20 fi\n\
+    \x20 if [ -n \"$4\" ]; then\n\
+    \x20   SUM=\"$(sha256sum \"$DEST.part\" | cut -d' ' -f1)\"\n\
+    \x20   if [ \"$SUM\" != \"$4\" ]; then\n\
+    \x20     echo \"CHECKSUM MISMATCH: $1 expected $4 got $SUM\" >&2\n\
+    \x20     unlink \"$DEST.part\"\n\
+    \x20     exit 1\n\
+    \x20   fi\n\
+    \x20 fi\n\
+    \x20 mv \"$DEST.part\" \"$DEST\"\n\
+    \x20 echo \"fetched:$1\"\n\
+    }";
+
+/// True when the resource's `source` is an `hf://` reference.
+pub fn is_hf(resource: &Resource) -> bool {
+    resource.source.as_deref().is_some_and(hf_hub::is_hf_source)
+}
+
+/// `path` as one shell word. A leading `~/` becomes `"$HOME"/`: a `~`
+/// inside single quotes is never expanded.
+fn shell_path(path: &str) -> String {
+    match path.strip_prefix("~/") {
+        Some(rest) => format!("\"$HOME\"/'{rest}'"),
+        None => format!("'{path}'"),
+    }
+}
+
+fn cache_dir(resource: &Resource) -> &str {
+    resource.cache_dir.as_deref().unwrap_or(DEFAULT_CACHE_DIR)
+}
+
+/// `<cache_dir>/hf/<org>--<repo>` for a repository id.
+fn repo_dir(resource: &Resource, repo: &str) -> String {
+    format!("{}/hf/{}", cache_dir(resource), repo.replace('/', "--"))
+}
+
+/// The directory the model is used from: `path`, or the repo's `current` link.
+pub fn model_dir(resource: &Resource) -> String {
+    if let Some(ref path) = resource.path {
+        return path.clone();
+    }
+    let source = resource.source.as_deref().unwrap_or("");
+    match hf_hub::parse_hf_source(source) {
+        Ok(src) => format!("{}/current", repo_dir(resource, &src.repo)),
+        Err(_) => format!("{}/hf/unknown", cache_dir(resource)),
+    }
+}
+
+/// Requested revision when it is already a full commit SHA.
+fn pinned_commit(resource: &Resource) -> Option<String> {
+    let source = resource.source.as_deref()?;
+    let rev = hf_hub::parse_hf_source(source).ok()?.revision;
+    (rev.len() == 40 && rev.bytes().all(|b| b.is_ascii_hexdigit())).then_some(rev)
+}
+
+/// Check script: a commit-pinned source must match the installed commit;
+/// a branch or tag only has to be installed.
+pub fn check_script(resource: &Resource) -> String {
+    let name = resource.name.as_deref().unwrap_or("unknown");
+    let marker = shell_path(&format!("{}/{REVISION_FILE}", model_dir(resource)));
+    if resource.state.as_deref() == Some("absent") {
+        return format!(
+            "if [ -f {marker} ]; then echo 'exists:{name}'; else echo 'absent:{name}'; fi"
+        );
+    }
+    match pinned_commit(resource) {
+        Some(commit) => format!(
+            "REV=\"$(head -n 1 {marker} 2>/dev/null || true)\"\n\
+             if [ -z \"$REV\" ]; then echo 'missing:{name}'\n\
+             elif [ \"$REV\" = '{commit}' ]; then echo 'match:{name}'\n\
+             else echo 'mismatch:{name}'; fi"
+        ),
+        None => {
+            format!("if [ -f {marker} ]; then echo 'exists:{name}'; else echo 'missing:{name}'; fi")
+        }
+    }
+}
+
+/// Apply script used when no resolved snapshot is available (e.g. when
+/// rendering scripts outside `forjar apply`): fails instead of guessing.
+pub fn unresolved_apply_script(resource: &Resource) -> String {
+    let source = resource.source.as_deref().unwrap_or("");
+    format!(
+        "echo \"ERROR: {source} is resolved against the Hugging Face Hub by forjar apply\" >&2\nexit 1"
+    )
+}
+
+/// Remove the model link; downloaded snapshots stay in the cache.
+pub fn absent_script(resource: &Resource) -> String {
+    let name = resource.name.as_deref().unwrap_or("unknown");
+    let dir = shell_path(&model_dir(resource));
+    format!(
+        "set -euo pipefail\n\
+         if [ -L {dir} ]; then unlink {dir}; fi\n\
+         echo 'removed:{name}'"
+    )
+}
+
+/// Apply script downloading a resolved snapshot.
+pub fn apply_script(resource: &Resource, snapshot: &HfSnapshot) -> String {
+    let name = resource.name.as_deref().unwrap_or("unknown");
+    let snap = shell_path(&format!(
+        "{}/snapshots/{}",
+        repo_dir(resource, &snapshot.repo),
+        snapshot.commit
+    ));
+    let link = shell_path(&model_dir(resource));
+    let mut lines = vec![
+        "set -euo pipefail".to_string(),
+        format!("SNAP={snap}"),
+        format!("mkdir -p {snap}"),
+        FETCH_FN.to_string(),
+    ];
+    for file in &snapshot.files {
+        lines.push(format!(
+            "forjar_hf_fetch '{}' '{}' '{}' '{}'",
+            file.name,
+            snapshot.file_url(&file.name),
+            file.size.map(|s| s.to_string()).unwrap_or_default(),
+            file.sha256.as_deref().unwrap_or("")
+        ));
+    }
+    lines.push(format!(
+        "printf '%s\\n' '{}' > \"$SNAP/{REVISION_FILE}\"",
+        snapshot.commit
+    ));
+    lines.push(format!("mkdir -p \"$(dirname {link})\""));
+    lines.push(format!("ln -sfn \"$SNAP\" {link}"));
+    if let Some(ref owner) = resource.owner {
+        lines.push(format!("chown -R '{owner}' \"$SNAP\""));
+    }
+    lines.push(format!(
+        "echo \"FORJAR_OUTPUT revision={}\"",
+        snapshot.commit
+    ));
+    lines.push(format!("echo 'downloaded:{name}'"));
+    lines.join("\n")
+}
+
+/// State query: installed commit plus the name and size of every file.
+/// Contents were verified against SHA-256 on download and are not rehashed.
+pub fn state_query_script(resource: &Resource) -> String {
+    let name = resource.name.as_deref().unwrap_or("unknown");
+    let dir = shell_path(&model_dir(resource));
+    let marker = shell_path(&format!("{}/{REVISION_FILE}", model_dir(resource)));
+    format!(
+        "if [ -f {marker} ]; then\n\
+         \x20 echo \"model={name}:revision=$(head -n 1 {marker})\"\n\
+         \x20 for f in {dir}/*; do\n\
+         \x20   if [ -f \"$f\" ]; then echo \"${{f##*/}} $(wc -c < \"$f\" | tr -d ' ')\"; fi\n\
+         \x20 done\n\
+         else\n\
+         \x20 echo 'model=MISSING:{name}'\n\
+         fi"
+    )
+}
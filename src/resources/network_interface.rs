@@ -0,0 +1,449 @@
+//! Host network interface resource handler.
+//!
+//! Renders configuration for one link — addresses, gateway, routes, DNS,
+//! MTU, DHCP — plus the virtual devices forjar can create (VLANs, bonds,
+//! bridges). Two backends, selected with `provider`:
+//!
+//! - `networkd` (default): `/etc/systemd/network/10-forjar-<name>.network`,
+//!   a `.netdev` for virtual kinds, one `.network` per bond/bridge member and
+//!   a `VLAN=` drop-in for the parent's forjar-managed `.network`.
+//! - `netplan`: `/etc/netplan/60-forjar-<name>.yaml`.
+//!
+//! Files are rewritten only when their content differs, and the backend is
+//! reloaded only when something changed.
+//!
+//! Safe-apply (`revert_after: N`) snapshots the backend's config directory
+//! and arms a transient systemd timer that restores it after N seconds. The
+//! executor then reconnects over the transport and runs [`confirm_script`],
+//! which disarms the timer; a change that cuts forjar off reverts itself.
+
+use crate::core::types::Resource;
+
+const NETWORKD_DIR: &str = "/etc/systemd/network";
+const NETPLAN_DIR: &str = "/etc/netplan";
+const REVERT_DIR: &str = "/var/lib/forjar/net-revert";
+
+/// Interface name (`name`, default `eth0`).
+pub fn iface_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("eth0")
+}
+
+fn kind(resource: &Resource) -> &str {
+    resource.kind.as_deref().unwrap_or("ethernet")
+}
+
+fn is_netplan(resource: &Resource) -> bool {
+    resource.provider.as_deref() == Some("netplan")
+}
+
+fn is_virtual(resource: &Resource) -> bool {
+    matches!(kind(resource), "vlan" | "bond" | "bridge")
+}
+
+/// Split a route into destination and optional gateway.
+pub fn parse_route(route: &str) -> (&str, Option<&str>) {
+    match route.split_once(" via ") {
+        Some((dest, via)) => (dest.trim(), Some(via.trim())),
+        None => (route.trim(), None),
+    }
+}
+
+fn dhcp_families(resource: &Resource) -> (bool, bool) {
+    match resource.dhcp.as_deref() {
+        Some("yes") => (true, true),
+        Some("ipv4") => (true, false),
+        Some("ipv6") => (false, true),
+        _ => (false, false),
+    }
+}
+
+fn network_path(name: &str) -> String {
+    format!("{NETWORKD_DIR}/10-forjar-{name}.network")
+}
+
+fn netdev_path(name: &str) -> String {
+    format!("{NETWORKD_DIR}/10-forjar-{name}.netdev")
+}
+
+fn member_path(name: &str, member: &str) -> String {
+    format!("{NETWORKD_DIR}/10-forjar-{name}--{member}.network")
+}
+
+fn vlan_dropin_path(parent: &str, name: &str) -> String {
+    format!("{}.d/forjar-vlan-{name}.conf", network_path(parent))
+}
+
+fn netplan_path(name: &str) -> String {
+    format!("{NETPLAN_DIR}/60-forjar-{name}.yaml")
+}
+
+/// `.network` unit for the interface itself.
+fn network_unit(resource: &Resource, name: &str) -> String {
+    let mut out = format!("[Match]\nName={name}\n");
+    if let Some(mtu) = resource.mtu {
+        out.push_str(&format!("\n[Link]\nMTUBytes={mtu}\n"));
+    }
+    out.push_str("\n[Network]\n");
+    if let Some(ref dhcp) = resource.dhcp {
+        out.push_str(&format!("DHCP={dhcp}\n"));
+    }
+    for addr in &resource.addresses {
+        out.push_str(&format!("Address={addr}\n"));
+    }
+    if let Some(ref gw) = resource.gateway {
+        out.push_str(&format!("Gateway={gw}\n"));
+    }
+    for dns in &resource.dns {
+        out.push_str(&format!("DNS={dns}\n"));
+    }
+    for route in &resource.routes {
+        let (dest, via) = parse_route(route);
+        out.push_str(&format!("\n[Route]\nDestination={dest}\n"));
+        if let Some(gw) = via {
+            out.push_str(&format!("Gateway={gw}\n"));
+        }
+    }
+    out.trim_end().to_string()
+}
+
+/// `.netdev` unit for a VLAN, bond or bridge.
+fn netdev_unit(resource: &Resource, name: &str) -> String {
+    let mut out = format!("[NetDev]\nName={name}\nKind={}\n", kind(resource));
+    match kind(resource) {
+        "vlan" => out.push_str(&format!("\n[VLAN]\nId={}\n", resource.vlan_id.unwrap_or(1))),
+        "bond" => {
+            if let Some(ref mode) = resource.bond_mode {
+                out.push_str(&format!("\n[Bond]\nMode={mode}\n"));
+            }
+        }
+        _ => {}
+    }
+    out.trim_end().to_string()
+}
+
+/// `(path, content)` pairs the networkd backend manages for this resource.
+fn networkd_files(resource: &Resource, name: &str) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+    if is_virtual(resource) {
+        files.push((netdev_path(name), netdev_unit(resource, name)));
+    }
+    files.push((network_path(name), network_unit(resource, name)));
+    let role = if kind(resource) == "bond" {
+        "Bond"
+    } else {
+        "Bridge"
+    };
+    for member in &resource.members {
+        files.push((
+            member_path(name, member),
+            format!("[Match]\nName={member}\n\n[Network]\n{role}={name}"),
+        ));
+    }
+    if kind(resource) == "vlan" {
+        if let Some(ref parent) = resource.interface {
+            files.push((
+                vlan_dropin_path(parent, name),
+                format!("[Network]\nVLAN={name}"),
+            ));
+        }
+    }
+    files
+}
+
+/// Inline YAML list (`[a, b]`).
+fn yaml_list(items: &[String]) -> String {
+    format!("[{}]", items.join(", "))
+}
+
+/// Netplan document for this resource.
+fn netplan_yaml(resource: &Resource, name: &str) -> String {
+    let mut out = String::from("network:\n  version: 2\n");
+    let section = match kind(resource) {
+        "vlan" => "vlans",
+        "bond" => "bonds",
+        "bridge" => "bridges",
+        _ => "ethernets",
+    };
+    if !resource.members.is_empty() {
+        out.push_str("  ethernets:\n");
+        for member in &resource.members {
+            out.push_str(&format!("    {member}: {{}}\n"));
+        }
+    }
+    out.push_str(&format!("  {section}:\n    {name}:\n"));
+    match kind(resource) {
+        "vlan" => {
+            out.push_str(&format!("      id: {}\n", resource.vlan_id.unwrap_or(1)));
+            if let Some(ref parent) = resource.interface {
+                out.push_str(&format!("      link: {parent}\n"));
+            }
+        }
+        "bond" | "bridge" => {
+            out.push_str(&format!(
+                "      interfaces: {}\n",
+                yaml_list(&resource.members)
+            ));
+            if let Some(ref mode) = resource.bond_mode {
+                out.push_str(&format!("      parameters:\n        mode: {mode}\n"));
+            }
+        }
+        _ => {}
+    }
+    let (dhcp4, dhcp6) = dhcp_families(resource);
+    if dhcp4 {
+        out.push_str("      dhcp4: true\n");
+    }
+    if dhcp6 {
+        out.push_str("      dhcp6: true\n");
+    }
+    if !resource.addresses.is_empty() {
+        out.push_str(&format!(
+            "      addresses: {}\n",
+            yaml_list(&resource.addresses)
+        ));
+    }
+    if let Some(mtu) = resource.mtu {
+        out.push_str(&format!("      mtu: {mtu}\n"));
+    }
+    let mut routes: Vec<(&str, Option<&str>)> = Vec::new();
+    if let Some(ref gw) = resource.gateway {
+        routes.push(("default", Some(gw.as_str())));
+    }
+    routes.extend(resource.routes.iter().map(|r| parse_route(r)));
+    if !routes.is_empty() {
+        out.push_str("      routes:\n");
+        for (dest, via) in routes {
+            out.push_str(&format!("        - to: {dest}\n"));
+            if let Some(gw) = via {
+                out.push_str(&format!("          via: {gw}\n"));
+            }
+        }
+    }
+    if !resource.dns.is_empty() {
+        out.push_str(&format!(
+            "      nameservers:\n        addresses: {}\n",
+            yaml_list(&resource.dns)
+        ));
+    }
+    out.trim_end().to_string()
+}
+
+/// Shell function writing stdin to `$1` only when the content differs.
+const WRITE_FN: &str = "forjar_net_write() {\n\
+    \x20 cat > \"$1.forjar-new\"\n\
+    \x20 if cmp -s \"$1.forjar-new\" \"$1\"; then\n\
+    \x20   unlink \"$1.forjar-new\"\n\
+    \x20 else\n\
+    \x20   mv \"$1.forjar-new\" \"$1\"\n\
+    \x20   CHANGED=1\n\
+    \x20 fi\n\
+    }";
+
+fn config_dir(resource: &Resource) -> &'static str {
+    if is_netplan(resource) {
+        NETPLAN_DIR
+    } else {
+        NETWORKD_DIR
+    }
+}
+
+fn revert_unit(name: &str) -> String {
+    format!("forjar-net-revert-{name}")
+}
+
+fn pending_marker(name: &str) -> String {
+    format!("/run/forjar-net-revert-{name}.pending")
+}
+
+fn reverted_marker(name: &str) -> String {
+    format!("/run/forjar-net-revert-{name}.reverted")
+}
+
+/// Commands that make the backend pick up changed files.
+fn reload_commands(resource: &Resource, name: &str) -> Vec<String> {
+    if is_netplan(resource) {
+        return vec!["netplan apply".to_string()];
+    }
+    let mut cmds = vec!["networkctl reload".to_string()];
+    for link in std::iter::once(name).chain(resource.members.iter().map(String::as_str)) {
+        cmds.push(format!(
+            "networkctl reconfigure '{link}' 2>/dev/null || true"
+        ));
+    }
+    cmds
+}
+
+/// Script restoring the config snapshot; run by the revert timer.
+fn revert_script(resource: &Resource, name: &str) -> String {
+    let dir = config_dir(resource);
+    let mut lines = vec![
+        "#!/bin/sh".to_string(),
+        format!("rm -rf '{dir}'"),
+        format!("mkdir -p '{dir}'"),
+        format!("tar -C '{dir}' -xf '{REVERT_DIR}/{name}.tar'"),
+    ];
+    lines.extend(reload_commands(resource, name));
+    lines.push(format!(
+        "unlink '{}' 2>/dev/null || true",
+        pending_marker(name)
+    ));
+    lines.push(format!("touch '{}'", reverted_marker(name)));
+    lines.join("\n")
+}
+
+/// Generate shell to check whether the interface is configured.
+pub fn check_script(resource: &Resource) -> String {
+    let name = iface_name(resource);
+    let conf = if is_netplan(resource) {
+        netplan_path(name)
+    } else {
+        network_path(name)
+    };
+    format!(
+        "if [ -f '{conf}' ] && ip link show dev '{name}' >/dev/null 2>&1; then echo 'exists:{name}'; else echo 'missing:{name}'; fi"
+    )
+}
+
+/// Generate shell to render (or remove) the configuration and reload the backend.
+pub fn apply_script(resource: &Resource) -> String {
+    let name = iface_name(resource);
+    let absent = resource.state.as_deref() == Some("absent");
+    let dir = config_dir(resource);
+    let mut lines = vec![
+        "set -euo pipefail".to_string(),
+        "CHANGED=0".to_string(),
+        WRITE_FN.to_string(),
+    ];
+
+    if resource.revert_after.is_some() {
+        lines.push(format!("mkdir -p '{REVERT_DIR}' '{dir}'"));
+        lines.push(format!(
+            "unlink '{}' 2>/dev/null || true",
+            reverted_marker(name)
+        ));
+        lines.push(format!("tar -C '{dir}' -cf '{REVERT_DIR}/{name}.tar' ."));
+    }
+
+    let files = if is_netplan(resource) {
+        vec![(netplan_path(name), netplan_yaml(resource, name))]
+    } else {
+        networkd_files(resource, name)
+    };
+    if absent {
+        for (path, _) in &files {
+            lines.push(format!(
+                "if [ -e '{path}' ]; then unlink '{path}'; CHANGED=1; fi"
+            ));
+        }
+    } else {
+        lines.push(format!("mkdir -p '{dir}'"));
+        if is_netplan(resource) {
+            // netplan refuses world-readable configs
+            lines.push("umask 077".to_string());
+        }
+        for (path, content) in &files {
+            if let Some((parent, _)) = path.rsplit_once('/') {
+                if parent != dir {
+                    lines.push(format!("mkdir -p '{parent}'"));
+                }
+            }
+            lines.push(format!(
+                "forjar_net_write '{path}' <<'FORJAR_EOF'\n{content}\nFORJAR_EOF"
+            ));
+        }
+    }
+    if !is_netplan(resource) {
+        // Member files for links no longer in the bond/bridge
+        let keep: Vec<String> = resource
+            .members
+            .iter()
+            .filter(|_| !absent)
+            .map(|m| format!("'{}'", member_path(name, m)))
+            .collect();
+        let keep_arm = if keep.is_empty() {
+            String::new()
+        } else {
+            format!("\x20   {}) ;;\n", keep.join("|"))
+        };
+        lines.push(format!(
+            "for f in '{NETWORKD_DIR}/10-forjar-{name}--'*.network; do\n\
+             \x20 if [ ! -e \"$f\" ]; then continue; fi\n\
+             \x20 case \"$f\" in\n\
+             {keep_arm}\
+             \x20   *) unlink \"$f\"; CHANGED=1 ;;\n\
+             \x20 esac\n\
+             done"
+        ));
+    }
+
+    lines.push(format!(
+        "if [ \"$CHANGED\" = 0 ]; then echo 'unchanged:{name}'; exit 0; fi"
+    ));
+
+    if let Some(secs) = resource.revert_after {
+        let unit = revert_unit(name);
+        lines.push(format!(
+            "cat > '{REVERT_DIR}/{name}.sh' <<'FORJAR_EOF'\n{}\nFORJAR_EOF",
+            revert_script(resource, name)
+        ));
+        lines.push(format!("systemctl stop '{unit}.timer' 2>/dev/null || true"));
+        lines.push(format!(
+            "systemctl reset-failed '{unit}.service' 2>/dev/null || true"
+        ));
+        lines.push(format!(
+            "systemd-run --unit='{unit}' --on-active={secs}s /bin/sh '{REVERT_DIR}/{name}.sh'"
+        ));
+        lines.push(format!("touch '{}'", pending_marker(name)));
+    }
+
+    lines.extend(reload_commands(resource, name));
+    if absent && is_virtual(resource) {
+        lines.push(format!(
+            "if ip link show dev '{name}' >/dev/null 2>&1; then ip link delete '{name}'; fi"
+        ));
+    }
+    lines.push(format!("echo 'changed:{name}'"));
+    lines.join("\n")
+}
+
+/// Script the executor runs over a fresh transport connection after a
+/// safe-apply change; it disarms the revert timer.
+///
+/// Returns `None` when safe-apply is off. Fails when the timer already
+/// fired, i.e. the change has been rolled back.
+pub fn confirm_script(resource: &Resource) -> Option<String> {
+    resource.revert_after?;
+    let name = iface_name(resource);
+    let unit = revert_unit(name);
+    let pending = pending_marker(name);
+    Some(format!(
+        "set -euo pipefail\n\
+         if [ -e '{reverted}' ]; then\n\
+         \x20 echo 'ERROR: network_interface {name} was already reverted' >&2\n\
+         \x20 exit 1\n\
+         fi\n\
+         if [ -e '{pending}' ]; then\n\
+         \x20 systemctl stop '{unit}.timer'\n\
+         \x20 unlink '{pending}'\n\
+         fi\n\
+         echo 'confirmed:{name}'",
+        reverted = reverted_marker(name),
+    ))
+}
+
+/// Generate shell to query interface state (for BLAKE3 hashing).
+///
+/// Uses `ip -j` address and route output with the counting-down lease
+/// lifetimes stripped, so only real address, route or link changes drift.
+pub fn state_query_script(resource: &Resource) -> String {
+    let name = iface_name(resource);
+    format!(
+        "if ip link show dev '{name}' >/dev/null 2>&1; then\n\
+         \x20 ip -j address show dev '{name}' | sed -E 's/\"(valid|preferred)_life_time\":[0-9]+,?//g'\n\
+         \x20 echo\n\
+         \x20 ip -j route show dev '{name}' | sed -E 's/\"expires\":[0-9]+,?//g'\n\
+         else\n\
+         \x20 echo 'network_interface=MISSING:{name}'\n\
+         fi"
+    )
+}
@@ -0,0 +1,136 @@
+//! GPT partition resource handler (sgdisk).
+//!
+//! One resource per partition: `device`, `number`, `size` (omit for the rest
+//! of the disk), `part_type` and an optional GPT partition `name`. A disk
+//! with no partition table gets a fresh GPT label; a disk carrying another
+//! table or a filesystem is refused unless `force` is set. Existing
+//! partitions are never resized or retyped, and `state: absent` refuses to
+//! delete a partition that still holds a signature unless forced.
+
+use super::disk::{gpt_type_code, partition_device, size_arg};
+use crate::core::types::Resource;
+
+fn device(resource: &Resource) -> &str {
+    resource.device.as_deref().unwrap_or("/dev/unknown")
+}
+
+fn number(resource: &Resource) -> u32 {
+    resource.number.unwrap_or(1)
+}
+
+/// Capture `sgdisk -i` output for the partition into `$INFO`.
+fn info_line(dev: &str, num: u32) -> String {
+    format!("INFO=\"$(sgdisk -i {num} '{dev}' 2>/dev/null || true)\"")
+}
+
+/// Generate shell to check whether the partition exists.
+pub fn check_script(resource: &Resource) -> String {
+    let dev = device(resource);
+    let num = number(resource);
+    let part = partition_device(dev, num);
+    format!(
+        "{}\n\
+         case \"$INFO\" in\n\
+         \x20 *'Partition GUID code'*) echo 'exists:{part}' ;;\n\
+         \x20 *) echo 'missing:{part}' ;;\n\
+         esac",
+        info_line(dev, num)
+    )
+}
+
+/// Refuse to continue when a signature is present, unless forced.
+fn signature_guard(target: &str, what: &str, force: bool) -> String {
+    if force {
+        return String::new();
+    }
+    format!(
+        "SIG=\"$(blkid -p -o value -s TYPE '{target}' 2>/dev/null || true)\"\n\
+         if [ -n \"$SIG\" ]; then\n\
+         \x20 echo \"ERROR: {target} holds $SIG; refusing to {what} without force\" >&2\n\
+         \x20 exit 1\n\
+         fi\n"
+    )
+}
+
+/// Generate shell to create or delete the partition.
+pub fn apply_script(resource: &Resource) -> String {
+    let dev = device(resource);
+    let num = number(resource);
+    let part = partition_device(dev, num);
+    let mut lines = vec!["set -euo pipefail".to_string(), info_line(dev, num)];
+
+    if resource.state.as_deref() == Some("absent") {
+        lines.push(format!(
+            "case \"$INFO\" in\n\
+             \x20 *'Partition GUID code'*) ;;\n\
+             \x20 *) echo 'unchanged:{part}'; exit 0 ;;\n\
+             esac\n\
+             {}\
+             sgdisk -d {num} '{dev}'\n\
+             partprobe '{dev}' 2>/dev/null || true",
+            signature_guard(&part, "delete it", resource.force)
+        ));
+        return lines.join("\n");
+    }
+
+    // Label the disk GPT when it has no partition table yet
+    let table_guard = if resource.force {
+        format!("sgdisk -Z '{dev}'\n")
+    } else {
+        format!(
+            "if [ -n \"$PTTYPE\" ]; then\n\
+             \x20 echo \"ERROR: {dev} has a $PTTYPE partition table; refusing to relabel without force\" >&2\n\
+             \x20 exit 1\n\
+             fi\n{}",
+            signature_guard(dev, "relabel it", false)
+        )
+    };
+    lines.push(format!(
+        "PTTYPE=\"$(blkid -p -o value -s PTTYPE '{dev}' 2>/dev/null || true)\"\n\
+         if [ \"$PTTYPE\" != 'gpt' ]; then\n\
+         {table_guard}\
+         sgdisk -o '{dev}'\n\
+         fi"
+    ));
+
+    let end = resource
+        .size
+        .as_deref()
+        .map(|s| format!("+{}", size_arg(s)))
+        .unwrap_or_else(|| "0".to_string());
+    let code = resource
+        .part_type
+        .as_deref()
+        .and_then(gpt_type_code)
+        .unwrap_or_else(|| "8300".to_string());
+    let mut create = format!("sgdisk -n {num}:0:{end} -t {num}:{code}");
+    if let Some(ref name) = resource.name {
+        create.push_str(&format!(" -c {num}:'{name}'"));
+    }
+    lines.push(format!(
+        "case \"$INFO\" in\n\
+         \x20 *'Partition GUID code'*) echo 'unchanged:{part}' ;;\n\
+         \x20 *)\n\
+         \x20   {create} '{dev}'\n\
+         \x20   partprobe '{dev}' 2>/dev/null || true\n\
+         \x20   udevadm settle 2>/dev/null || true\n\
+         \x20   ;;\n\
+         esac"
+    ));
+    lines.join("\n")
+}
+
+/// Generate shell to query partition state (for BLAKE3 hashing).
+pub fn state_query_script(resource: &Resource) -> String {
+    let dev = device(resource);
+    let num = number(resource);
+    let part = partition_device(dev, num);
+    format!(
+        "{}\n\
+         case \"$INFO\" in\n\
+         \x20 *'Partition GUID code'*) echo \"$INFO\" | grep -E '^(Partition GUID code|First sector|Partition size|Partition name)' ;;\n\
+         \x20 *) echo 'partition=MISSING:{part}' ;;\n\
+         esac",
+        info_line(dev, num)
+    )
+}
@@ -0,0 +1,146 @@
+//! PostgreSQL database resource handler.
+//!
+//! Creates `name` (with `encoding`/`locale` from `template0` when set),
+//! keeps its `owner`, and manages database privileges from `grants`
+//! (`role:CONNECT,CREATE,TEMPORARY`): listed privileges are granted, the
+//! others revoked from that role. `state: absent` drops the database unless
+//! `lifecycle.prevent_destroy` is set.
+
+use super::pg_role::{
+    exists_check, grantee, psql_heredoc, quote_ident, quote_literal, RUN_AS_PREAMBLE,
+};
+use super::zfs_dataset::{prevent_destroy, refuse_destroy_line};
+use crate::core::types::Resource;
+
+/// Database privileges managed through `grants`.
+pub const DATABASE_PRIVILEGES: &[&str] = &["CONNECT", "CREATE", "TEMPORARY"];
+
+/// A parsed `grants` entry: role and privileges (`ALL` expanded,
+/// `TEMP` normalised to `TEMPORARY`). `role:` with no privileges revokes
+/// them all.
+pub fn parse_grant(grant: &str) -> Result<(String, Vec<&'static str>), String> {
+    let (role, privileges) = grant
+        .split_once(':')
+        .ok_or_else(|| format!("grant '{grant}' is not role:PRIVILEGE[,PRIVILEGE]"))?;
+    let mut out = Vec::new();
+    for privilege in privileges
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        match privilege.to_ascii_uppercase().as_str() {
+            "ALL" => out.extend_from_slice(DATABASE_PRIVILEGES),
+            "TEMP" | "TEMPORARY" => out.push("TEMPORARY"),
+            "CONNECT" => out.push("CONNECT"),
+            "CREATE" => out.push("CREATE"),
+            _ => {
+                return Err(format!(
+                    "grant '{grant}' has unknown privilege '{privilege}' (expected: CONNECT, CREATE, TEMPORARY, ALL)"
+                ))
+            }
+        }
+    }
+    out.sort_unstable();
+    out.dedup();
+    Ok((role.trim().to_string(), out))
+}
+
+fn db_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("unknown")
+}
+
+/// Check script: does the database exist?
+pub fn check_script(resource: &Resource) -> String {
+    let db = db_name(resource);
+    let sql = format!(
+        "SELECT 1 FROM pg_database WHERE datname = {};",
+        quote_literal(db)
+    );
+    exists_check(resource, None, &sql, db)
+}
+
+/// `CREATE DATABASE` statement run through `\gexec` when missing.
+fn create_sql(resource: &Resource) -> String {
+    let db = db_name(resource);
+    let mut create = format!("CREATE DATABASE {}", quote_ident(db));
+    if let Some(ref owner) = resource.owner {
+        create.push_str(&format!(" OWNER {}", quote_ident(owner)));
+    }
+    if let Some(ref encoding) = resource.encoding {
+        create.push_str(&format!(" ENCODING {}", quote_literal(encoding)));
+    }
+    if let Some(ref locale) = resource.locale {
+        create.push_str(&format!(" LOCALE {}", quote_literal(locale)));
+    }
+    if resource.encoding.is_some() || resource.locale.is_some() {
+        create.push_str(" TEMPLATE template0");
+    }
+    format!(
+        "SELECT {} WHERE NOT EXISTS (SELECT FROM pg_database WHERE datname = {})\\gexec",
+        quote_literal(&create),
+        quote_literal(db)
+    )
+}
+
+/// Apply script: create the database, then converge owner and grants.
+pub fn apply_script(resource: &Resource) -> String {
+    let db = db_name(resource);
+    let ident = quote_ident(db);
+    if resource.state.as_deref() == Some("absent") {
+        if prevent_destroy(resource) {
+            return refuse_destroy_line(&format!("database {db}"));
+        }
+        return format!(
+            "{RUN_AS_PREAMBLE}\n{}\necho 'removed:{db}'",
+            psql_heredoc(resource, None, &format!("DROP DATABASE IF EXISTS {ident};"))
+        );
+    }
+    let mut sql = vec![create_sql(resource)];
+    if let Some(ref owner) = resource.owner {
+        sql.push(format!(
+            "ALTER DATABASE {ident} OWNER TO {};",
+            quote_ident(owner)
+        ));
+    }
+    for grant in &resource.grants {
+        let Ok((role, privileges)) = parse_grant(grant) else {
+            continue;
+        };
+        let to = grantee(&role);
+        let revoked: Vec<&str> = DATABASE_PRIVILEGES
+            .iter()
+            .copied()
+            .filter(|p| !privileges.contains(p))
+            .collect();
+        if !privileges.is_empty() {
+            sql.push(format!(
+                "GRANT {} ON DATABASE {ident} TO {to};",
+                privileges.join(", ")
+            ));
+        }
+        if !revoked.is_empty() {
+            sql.push(format!(
+                "REVOKE {} ON DATABASE {ident} FROM {to};",
+                revoked.join(", ")
+            ));
+        }
+    }
+    format!(
+        "{RUN_AS_PREAMBLE}\n{}\necho 'applied:{db}'",
+        psql_heredoc(resource, None, &sql.join("\n"))
+    )
+}
+
+/// State query from `pg_database`: owner, encoding, collation and ACL.
+pub fn state_query_script(resource: &Resource) -> String {
+    let lit = quote_literal(db_name(resource));
+    let sql = format!(
+        "SELECT 'pg_database=' || datname || ':owner=' || pg_get_userbyid(datdba)\n\
+         \x20 || ':encoding=' || pg_encoding_to_char(encoding) || ':collate=' || datcollate\n\
+         \x20 || ':acl=' || coalesce(array_to_string(ARRAY(SELECT a::text FROM unnest(datacl) a ORDER BY 1), ','), '')\n\
+         FROM pg_database WHERE datname = {lit}\n\
+         UNION ALL SELECT 'pg_database=MISSING:' || {lit}\n\
+         WHERE NOT EXISTS (SELECT FROM pg_database WHERE datname = {lit});"
+    );
+    format!("{RUN_AS_PREAMBLE}\n{}", psql_heredoc(resource, None, &sql))
+}
@@ -0,0 +1,69 @@
+//! PostgreSQL extension resource handler.
+//!
+//! Installs extension `name` into `database` with `CREATE EXTENSION IF NOT
+//! EXISTS`, and moves it to `version` with `ALTER EXTENSION ... UPDATE TO`
+//! when one is declared. `state: absent` drops it (without `CASCADE`).
+
+use super::pg_role::{exists_check, psql_heredoc, quote_ident, quote_literal, RUN_AS_PREAMBLE};
+use crate::core::types::Resource;
+
+fn ext_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("unknown")
+}
+
+fn database(resource: &Resource) -> Option<&str> {
+    resource.database.as_deref()
+}
+
+/// Check script: is the extension installed in the database?
+pub fn check_script(resource: &Resource) -> String {
+    let ext = ext_name(resource);
+    let sql = format!(
+        "SELECT 1 FROM pg_extension WHERE extname = {};",
+        quote_literal(ext)
+    );
+    exists_check(resource, database(resource), &sql, ext)
+}
+
+/// Apply script: create or update the extension; `state: absent` drops it.
+pub fn apply_script(resource: &Resource) -> String {
+    let ext = ext_name(resource);
+    let ident = quote_ident(ext);
+    let sql = if resource.state.as_deref() == Some("absent") {
+        format!("DROP EXTENSION IF EXISTS {ident};")
+    } else {
+        match resource.version {
+            Some(ref version) => {
+                let version = quote_literal(version);
+                format!(
+                    "CREATE EXTENSION IF NOT EXISTS {ident} VERSION {version};\n\
+                     ALTER EXTENSION {ident} UPDATE TO {version};"
+                )
+            }
+            None => format!("CREATE EXTENSION IF NOT EXISTS {ident};"),
+        }
+    };
+    let done = if resource.state.as_deref() == Some("absent") {
+        "removed"
+    } else {
+        "applied"
+    };
+    format!(
+        "{RUN_AS_PREAMBLE}\n{}\necho '{done}:{ext}'",
+        psql_heredoc(resource, database(resource), &sql)
+    )
+}
+
+/// State query from `pg_extension`: installed version.
+pub fn state_query_script(resource: &Resource) -> String {
+    let lit = quote_literal(ext_name(resource));
+    let sql = format!(
+        "SELECT 'pg_extension=' || extname || ':' || extversion FROM pg_extension WHERE extname = {lit}\n\
+         UNION ALL SELECT 'pg_extension=MISSING:' || {lit}\n\
+         WHERE NOT EXISTS (SELECT FROM pg_extension WHERE extname = {lit});"
+    );
+    format!(
+        "{RUN_AS_PREAMBLE}\n{}",
+        psql_heredoc(resource, database(resource), &sql)
+    )
+}
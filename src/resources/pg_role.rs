@@ -0,0 +1,273 @@
+//! PostgreSQL role resource handler, plus the `psql` plumbing shared with
+//! `pg_database` and `pg_extension`.
+//!
+//! Scripts run `psql` as the `postgres` system user (directly, via `sudo`,
+//! or via `runuser`), over the local socket unless `host` is set, and feed
+//! SQL through a quoted heredoc so nothing in it is expanded by the shell.
+//!
+//! `password` is normally `{{secrets.<key>}}`. It is turned into a
+//! SCRAM-SHA-256 verifier on the control machine, so the plaintext never
+//! reaches the target; the planner only keeps its BLAKE3 hash
+//! (`core::ephemeral`), which re-plans the role when the secret rotates.
+
+use crate::core::pki::hmac_sha256;
+use crate::core::types::Resource;
+use base64::Engine;
+
+/// SCRAM-SHA-256 iteration count (the PostgreSQL default).
+const SCRAM_ITERATIONS: usize = 4096;
+
+/// Role attributes settable in `attributes`, in `ALTER ROLE` order.
+pub const ROLE_ATTRIBUTES: &[&str] = &[
+    "SUPERUSER",
+    "CREATEDB",
+    "CREATEROLE",
+    "INHERIT",
+    "LOGIN",
+    "REPLICATION",
+    "BYPASSRLS",
+];
+
+/// Shell setting `$RUN_AS` so `$RUN_AS psql` runs as the `postgres` user.
+pub(super) const RUN_AS_PREAMBLE: &str = "set -euo pipefail\n\
+    if [ \"$(id -un)\" = postgres ]; then RUN_AS=''\n\
+    elif command -v sudo >/dev/null 2>&1; then RUN_AS='sudo -u postgres'\n\
+    else RUN_AS='runuser -u postgres --'; fi";
+
+/// `psql` invocation for `database` (default: the `postgres` database).
+pub(super) fn psql(resource: &Resource, database: Option<&str>) -> String {
+    let mut cmd = "$RUN_AS psql -X -q -At -v ON_ERROR_STOP=1".to_string();
+    if let Some(ref host) = resource.host {
+        cmd.push_str(&format!(" -h '{host}'"));
+    }
+    if let Some(ref port) = resource.port {
+        cmd.push_str(&format!(" -p '{port}'"));
+    }
+    if let Some(db) = database {
+        cmd.push_str(&format!(" -d '{db}'"));
+    }
+    cmd
+}
+
+/// Run `sql` through `psql` with a quoted heredoc.
+pub(super) fn psql_heredoc(resource: &Resource, database: Option<&str>, sql: &str) -> String {
+    format!(
+        "{} <<'FORJAR_SQL'\nSET client_min_messages = warning;\n{sql}\nFORJAR_SQL",
+        psql(resource, database)
+    )
+}
+
+/// Check script printing `exists:<name>`/`missing:<name>` depending on
+/// whether `sql` (fed on stdin, like every other statement) returns a row.
+pub(super) fn exists_check(
+    resource: &Resource,
+    database: Option<&str>,
+    sql: &str,
+    name: &str,
+) -> String {
+    format!(
+        "{RUN_AS_PREAMBLE}\n\
+         FOUND=\"$({}\n)\"\n\
+         if [ \"$FOUND\" = 1 ]; then echo 'exists:{name}'; else echo 'missing:{name}'; fi",
+        psql_heredoc(resource, database, sql)
+    )
+}
+
+/// Double-quoted SQL identifier.
+pub fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Single-quoted SQL literal.
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// A role in `GRANT`/`REVOKE`: `PUBLIC` stays a keyword.
+pub(super) fn grantee(role: &str) -> String {
+    if role.eq_ignore_ascii_case("public") {
+        "PUBLIC".to_string()
+    } else {
+        quote_ident(role)
+    }
+}
+
+fn role_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("unknown")
+}
+
+/// Full attribute list for `ALTER ROLE`: declared attributes on, the rest
+/// off, except `INHERIT`, which stays on unless `NOINHERIT` is declared.
+pub fn role_flags(resource: &Resource) -> Vec<String> {
+    let declared = |attr: &str| {
+        resource
+            .attributes
+            .iter()
+            .any(|a| a.eq_ignore_ascii_case(attr))
+    };
+    ROLE_ATTRIBUTES
+        .iter()
+        .map(|&attr| {
+            let on = if attr == "INHERIT" {
+                !declared("NOINHERIT")
+            } else {
+                declared(attr)
+            };
+            if on {
+                attr.to_string()
+            } else {
+                format!("NO{attr}")
+            }
+        })
+        .collect()
+}
+
+/// SCRAM-SHA-256 verifier as stored in `pg_authid.rolpassword`
+/// (RFC 5802/7677): `SCRAM-SHA-256$<iter>:<salt>$<StoredKey>:<ServerKey>`.
+pub fn scram_verifier(password: &str, salt: &[u8]) -> Result<String, String> {
+    let mut salted = [0u8; 32];
+    openssl::pkcs5::pbkdf2_hmac(
+        password.as_bytes(),
+        salt,
+        SCRAM_ITERATIONS,
+        openssl::hash::MessageDigest::sha256(),
+        &mut salted,
+    )
+    .map_err(|e| format!("pbkdf2: {e}"))?;
+    let client_key = hmac_sha256(&salted, b"Client Key")?;
+    let stored_key = openssl::sha::sha256(&client_key);
+    let server_key = hmac_sha256(&salted, b"Server Key")?;
+    let b64 = base64::engine::general_purpose::STANDARD;
+    Ok(format!(
+        "SCRAM-SHA-256${SCRAM_ITERATIONS}:{}${}:{}",
+        b64.encode(salt),
+        b64.encode(stored_key),
+        b64.encode(server_key)
+    ))
+}
+
+/// `ALTER ROLE ... PASSWORD` with a fresh-salted verifier.
+fn password_sql(role: &str, password: &str) -> String {
+    let mut salt = [0u8; 16];
+    let verifier = openssl::rand::rand_bytes(&mut salt)
+        .map_err(|e| format!("salt: {e}"))
+        .and_then(|()| scram_verifier(password, &salt));
+    match verifier {
+        Ok(v) => format!(
+            "ALTER ROLE {} PASSWORD {};",
+            quote_ident(role),
+            quote_literal(&v)
+        ),
+        // Abort the transaction-less script rather than leave the old password.
+        Err(e) => format!("DO $$ BEGIN RAISE EXCEPTION {}; END $$;", quote_literal(&e)),
+    }
+}
+
+/// Memberships of `role` other than `keep`, as a revoking `DO` block.
+fn revoke_other_groups_sql(role: &str, keep: &[String]) -> String {
+    let keep: Vec<String> = keep.iter().map(|g| quote_literal(g)).collect();
+    format!(
+        "DO $$\n\
+         DECLARE g name;\n\
+         BEGIN\n\
+         \x20 FOR g IN SELECT b.rolname FROM pg_auth_members m\n\
+         \x20     JOIN pg_roles b ON b.oid = m.roleid JOIN pg_roles r ON r.oid = m.member\n\
+         \x20     WHERE r.rolname = {role} AND b.rolname <> ALL (ARRAY[{keep}]::name[])\n\
+         \x20 LOOP\n\
+         \x20   EXECUTE format('REVOKE %I FROM %I', g, {role});\n\
+         \x20 END LOOP;\n\
+         END\n\
+         $$;",
+        role = quote_literal(role),
+        keep = keep.join(", "),
+    )
+}
+
+/// Check script: does the role exist?
+pub fn check_script(resource: &Resource) -> String {
+    let role = role_name(resource);
+    let sql = format!(
+        "SELECT 1 FROM pg_roles WHERE rolname = {};",
+        quote_literal(role)
+    );
+    exists_check(resource, None, &sql, role)
+}
+
+/// Apply script: create the role, set attributes, password and memberships;
+/// `state: absent` drops it.
+pub fn apply_script(resource: &Resource) -> String {
+    let role = role_name(resource);
+    let ident = quote_ident(role);
+    if resource.state.as_deref() == Some("absent") {
+        return format!(
+            "{RUN_AS_PREAMBLE}\n{}\necho 'removed:{role}'",
+            psql_heredoc(resource, None, &format!("DROP ROLE IF EXISTS {ident};"))
+        );
+    }
+    let mut sql = vec![
+        format!(
+            "DO $$\n\
+             BEGIN\n\
+             \x20 IF NOT EXISTS (SELECT FROM pg_roles WHERE rolname = {}) THEN\n\
+             \x20   CREATE ROLE {ident};\n\
+             \x20 END IF;\n\
+             END\n\
+             $$;",
+            quote_literal(role)
+        ),
+        format!(
+            "ALTER ROLE {ident} WITH {};",
+            role_flags(resource).join(" ")
+        ),
+    ];
+    if let Some(ref password) = resource.password {
+        sql.push(password_sql(role, password));
+    }
+    for group in &resource.groups {
+        sql.push(format!("GRANT {} TO {ident};", quote_ident(group)));
+    }
+    sql.push(revoke_other_groups_sql(role, &resource.groups));
+    format!(
+        "{RUN_AS_PREAMBLE}\n{}\necho 'applied:{role}'",
+        psql_heredoc(resource, None, &sql.join("\n"))
+    )
+}
+
+/// State query from the catalog: attributes, memberships and, when a
+/// password is managed, a digest of the stored verifier (so a password
+/// changed outside forjar shows up as drift).
+pub fn state_query_script(resource: &Resource) -> String {
+    let role = role_name(resource);
+    let lit = quote_literal(role);
+    let flags: Vec<String> = ROLE_ATTRIBUTES
+        .iter()
+        .map(|attr| {
+            let column = match *attr {
+                "SUPERUSER" => "rolsuper",
+                "CREATEDB" => "rolcreatedb",
+                "CREATEROLE" => "rolcreaterole",
+                "INHERIT" => "rolinherit",
+                "LOGIN" => "rolcanlogin",
+                "REPLICATION" => "rolreplication",
+                _ => "rolbypassrls",
+            };
+            format!("CASE WHEN {column} THEN '{attr}' END")
+        })
+        .collect();
+    let password = if resource.password.is_some() {
+        " || ':password=' || md5(coalesce(a.rolpassword, ''))"
+    } else {
+        ""
+    };
+    let sql = format!(
+        "SELECT 'pg_role=' || a.rolname || ':' || concat_ws(',', {flags})\n\
+         \x20 || ':groups=' || coalesce((SELECT string_agg(b.rolname, ',' ORDER BY b.rolname)\n\
+         \x20   FROM pg_auth_members m JOIN pg_roles b ON b.oid = m.roleid\n\
+         \x20   WHERE m.member = a.oid), ''){password}\n\
+         FROM pg_authid a WHERE a.rolname = {lit}\n\
+         UNION ALL SELECT 'pg_role=MISSING:' || {lit}\n\
+         WHERE NOT EXISTS (SELECT FROM pg_roles WHERE rolname = {lit});",
+        flags = flags.join(", "),
+    );
+    format!("{RUN_AS_PREAMBLE}\n{}", psql_heredoc(resource, None, &sql))
+}
@@ -0,0 +1,262 @@
+//! Podman Quadlet backend for `container_stack`.
+//!
+//! Renders one `.container` unit per compose service plus `.network` and
+//! `.volume` units for the stack's top-level networks and named volumes.
+//! Units are named `<project>-<name>` and carry a `Label=forjar.stack=<project>`
+//! line, which is how a stack finds its own units in the shared unit directory
+//! (a name prefix would also match the units of a stack named `<project>-api`).
+//! Apply rewrites only units whose content changed, prunes stale ones, reloads
+//! systemd and restarts only changed services.
+
+use super::container_stack::{container_name, key_list, service_hash, StackService, StackSpec};
+use super::docker::CONFIG_HASH_LABEL;
+use serde_yaml_ng::Value;
+
+/// Scalar YAML value as a string (numbers and bools included).
+fn scalar_str(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Strings from a compose list field.
+fn str_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Sequence(items)) => items.iter().filter_map(scalar_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `KEY=value` pairs from a compose `environment` list or map.
+fn environment(spec: &Value) -> Vec<String> {
+    match spec.get("environment") {
+        Some(Value::Mapping(map)) => map
+            .iter()
+            .filter_map(|(k, v)| {
+                let key = k.as_str()?;
+                Some(format!("{key}={}", scalar_str(v).unwrap_or_default()))
+            })
+            .collect(),
+        other => str_list(other),
+    }
+}
+
+/// Compose `restart` mapped to a systemd `Restart=` value.
+fn restart_policy(spec: &Value) -> &'static str {
+    match spec.get("restart").and_then(Value::as_str) {
+        Some("no") => "no",
+        Some(p) if p.starts_with("on-failure") => "on-failure",
+        _ => "always",
+    }
+}
+
+/// Render the `.container` unit for one service.
+pub fn container_unit(stack: &StackSpec, project: &str, svc: &StackService) -> String {
+    let mut unit = vec![
+        "[Unit]".to_string(),
+        format!("Description=forjar stack {project} service {}", svc.name),
+    ];
+    for dep in &svc.depends_on {
+        unit.push(format!("Requires={project}-{dep}.service"));
+        unit.push(format!("After={project}-{dep}.service"));
+    }
+
+    unit.push(String::new());
+    unit.push("[Container]".to_string());
+    unit.push(format!("ContainerName={}", container_name(project, svc)));
+    unit.push(format!("Image={}", svc.image));
+    unit.push(format!("Label={}", stack_label(project)));
+    unit.push(format!("Label={CONFIG_HASH_LABEL}={}", service_hash(svc)));
+    match svc.spec.get("command") {
+        Some(Value::Sequence(args)) => {
+            let args: Vec<String> = args.iter().filter_map(scalar_str).collect();
+            unit.push(format!("Exec={}", args.join(" ")));
+        }
+        Some(cmd) => {
+            if let Some(cmd) = scalar_str(cmd) {
+                unit.push(format!("Exec={cmd}"));
+            }
+        }
+        None => {}
+    }
+    for env in environment(&svc.spec) {
+        unit.push(format!("Environment={env}"));
+    }
+    for port in str_list(svc.spec.get("ports")) {
+        unit.push(format!("PublishPort={port}"));
+    }
+    for vol in str_list(svc.spec.get("volumes")) {
+        unit.push(format!("Volume={}", volume_ref(stack, project, &vol)));
+    }
+    for net in key_list(svc.spec.get("networks")) {
+        unit.push(format!("Network={project}-{net}.network"));
+    }
+
+    unit.push(String::new());
+    unit.push("[Service]".to_string());
+    unit.push(format!("Restart={}", restart_policy(&svc.spec)));
+    unit.push(String::new());
+    unit.push("[Install]".to_string());
+    unit.push("WantedBy=multi-user.target default.target".to_string());
+    unit.join("\n")
+}
+
+/// Rewrite a named-volume mount to reference the stack's `.volume` unit.
+fn volume_ref(stack: &StackSpec, project: &str, mount: &str) -> String {
+    match mount.split_once(':') {
+        Some((src, rest)) if stack.volumes.iter().any(|v| v == src) => {
+            format!("{project}-{src}.volume:{rest}")
+        }
+        _ => mount.to_string(),
+    }
+}
+
+/// Ownership label carried by every unit of the stack.
+fn stack_label(project: &str) -> String {
+    format!("forjar.stack={project}")
+}
+
+/// Render a `.network` unit.
+pub fn network_unit(project: &str, net: &str) -> String {
+    format!(
+        "[Network]\nNetworkName={project}-{net}\nLabel={}",
+        stack_label(project)
+    )
+}
+
+/// Render a `.volume` unit.
+pub fn volume_unit(project: &str, vol: &str) -> String {
+    format!(
+        "[Volume]\nVolumeName={project}-{vol}\nLabel={}",
+        stack_label(project)
+    )
+}
+
+/// Shell listing the unit files in `dir` that belong to the stack, one per
+/// line: those with the exact `Label=forjar.stack=<project>` line.
+pub fn stack_units(project: &str, dir: &str) -> String {
+    format!(
+        "{{ grep -lsxF 'Label={}' '{dir}'/*.container '{dir}'/*.network '{dir}'/*.volume || true; }}",
+        stack_label(project)
+    )
+}
+
+/// All unit files for the stack as `(file name, content)` pairs.
+pub fn render_units(
+    stack: &StackSpec,
+    services: &[&StackService],
+    project: &str,
+) -> Vec<(String, String)> {
+    let mut units = Vec::new();
+    for net in &stack.networks {
+        units.push((
+            format!("{project}-{net}.network"),
+            network_unit(project, net),
+        ));
+    }
+    for vol in &stack.volumes {
+        units.push((format!("{project}-{vol}.volume"), volume_unit(project, vol)));
+    }
+    for svc in services {
+        units.push((
+            format!("{project}-{}.container", svc.name),
+            container_unit(stack, project, svc),
+        ));
+    }
+    units
+}
+
+/// Write changed units, prune stale ones, reload and (re)start services.
+pub fn apply_units(
+    stack: &StackSpec,
+    services: &[&StackService],
+    project: &str,
+    dir: &str,
+) -> String {
+    let units = render_units(stack, services, project);
+    let mut lines = vec![
+        "set -euo pipefail".to_string(),
+        format!("mkdir -p '{dir}'"),
+        "CHANGED=''".to_string(),
+    ];
+    for (file, content) in &units {
+        let tmp = format!("{dir}/.{file}.forjar");
+        lines.push(format!(
+            "cat > '{tmp}' <<'FORJAR_UNIT'\n{content}\nFORJAR_UNIT"
+        ));
+        lines.push(format!(
+            "if cmp -s '{tmp}' '{dir}/{file}'; then rm -f '{tmp}'; else mv '{tmp}' '{dir}/{file}'; CHANGED=\"$CHANGED {file}\"; fi"
+        ));
+    }
+
+    // Units labelled with this stack that are no longer in the spec
+    let keep: Vec<String> = units.iter().map(|(f, _)| format!("'{f}'")).collect();
+    lines.push(format!(
+        "{} | while IFS= read -r f; do\n\
+         \x20 case \"$(basename \"$f\")\" in\n\
+         \x20   {}) ;;\n\
+         \x20   *) systemctl stop \"$(basename \"$f\" .container).service\" 2>/dev/null || true; unlink \"$f\" ;;\n\
+         \x20 esac\n\
+         done",
+        stack_units(project, dir),
+        keep.join("|")
+    ));
+    lines.push("systemctl daemon-reload".to_string());
+
+    for svc in services {
+        let unit = format!("{project}-{}", svc.name);
+        lines.push(format!(
+            "case \"$CHANGED \" in\n\
+             \x20 *' {unit}.container '*) systemctl restart '{unit}.service' ;;\n\
+             \x20 *) systemctl start '{unit}.service' ;;\n\
+             esac"
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Stop services in reverse dependency order and remove the stack's units.
+///
+/// Named volumes are left in place — their data outlives the stack.
+pub fn remove_units(
+    stack: &StackSpec,
+    services: &[&StackService],
+    project: &str,
+    dir: &str,
+) -> String {
+    let mut lines = vec!["set -euo pipefail".to_string()];
+    for svc in services.iter().rev() {
+        lines.push(format!(
+            "systemctl stop '{project}-{}.service' 2>/dev/null || true",
+            svc.name
+        ));
+    }
+    for (file, _) in render_units(stack, services, project) {
+        lines.push(format!("rm -f '{dir}/{file}'"));
+    }
+    lines.push("systemctl daemon-reload".to_string());
+    for net in &stack.networks {
+        lines.push(format!(
+            "podman network rm '{project}-{net}' 2>/dev/null || true"
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Remove every unit labelled with the stack (used when no spec is known).
+pub fn remove_by_label(project: &str, dir: &str) -> String {
+    format!(
+        "set -euo pipefail\n\
+         {} | while IFS= read -r f; do\n\
+         \x20 case \"$f\" in\n\
+         \x20   *.container) systemctl stop \"$(basename \"$f\" .container).service\" 2>/dev/null || true ;;\n\
+         \x20 esac\n\
+         \x20 unlink \"$f\"\n\
+         done\n\
+         systemctl daemon-reload",
+        stack_units(project, dir)
+    )
+}
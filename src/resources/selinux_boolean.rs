@@ -0,0 +1,61 @@
+//! SELinux boolean resource handler.
+//!
+//! Sets boolean `name` to `enabled` with `setsebool -P`, so the value
+//! survives a reboot. Drift compares the live value from `getsebool -a`.
+
+use crate::core::types::Resource;
+
+fn boolean_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("unknown")
+}
+
+/// `on` or `off` as `getsebool` prints it.
+pub fn boolean_value(resource: &Resource) -> &'static str {
+    if resource.enabled.unwrap_or(false) {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// Shell reading the live value of the boolean into `$CUR` (empty when the
+/// loaded policy has no such boolean).
+fn current_line(name: &str) -> String {
+    format!("CUR=\"$(getsebool -a | awk -v b='{name}' '$1 == b {{ print $3 }}')\"")
+}
+
+/// Check script: does the loaded policy define the boolean?
+pub fn check_script(resource: &Resource) -> String {
+    let name = boolean_name(resource);
+    format!(
+        "set -euo pipefail\n\
+         {}\n\
+         if [ -n \"$CUR\" ]; then echo \"exists:{name}:$CUR\"; else echo 'missing:{name}'; fi",
+        current_line(name)
+    )
+}
+
+/// Apply script: `setsebool -P` when the live value differs.
+pub fn apply_script(resource: &Resource) -> String {
+    let name = boolean_name(resource);
+    let value = boolean_value(resource);
+    format!(
+        "set -euo pipefail\n\
+         {}\n\
+         if [ -z \"$CUR\" ]; then echo 'unknown SELinux boolean {name}' >&2; exit 1; fi\n\
+         if [ \"$CUR\" != '{value}' ]; then setsebool -P '{name}' '{value}'; fi\n\
+         echo 'applied:{name}={value}'",
+        current_line(name)
+    )
+}
+
+/// State query from `getsebool -a`.
+pub fn state_query_script(resource: &Resource) -> String {
+    let name = boolean_name(resource);
+    format!(
+        "set -euo pipefail\n\
+         {}\n\
+         echo \"selinux_boolean={name}:${{CUR:-MISSING}}\"",
+        current_line(name)
+    )
+}
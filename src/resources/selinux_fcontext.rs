@@ -0,0 +1,99 @@
+//! SELinux file context resource handler.
+//!
+//! Keeps a local `semanage fcontext` rule mapping the path expression in
+//! `path` (e.g. `/srv/www(/.*)?`) to `selinux_type`, then relabels `target`
+//! with `restorecon -R`. `target` defaults to `path` without a trailing
+//! `(/.*)?`. `state: absent` deletes the rule and relabels back to the
+//! policy default. Drift covers both the rule and the label on `target`.
+
+use crate::core::types::Resource;
+
+/// Suffix matching everything below a directory in a path expression.
+const RECURSIVE_SUFFIX: &str = "(/.*)?";
+
+fn spec(resource: &Resource) -> &str {
+    resource.path.as_deref().unwrap_or("/dev/null")
+}
+
+/// Path relabelled after a change: `target`, or `path` without a trailing
+/// `(/.*)?`. `None` when `path` is still a regular expression.
+pub fn relabel_target(resource: &Resource) -> Option<String> {
+    if let Some(ref target) = resource.target {
+        return Some(target.clone());
+    }
+    let base = spec(resource);
+    let base = base.strip_suffix(RECURSIVE_SUFFIX).unwrap_or(base);
+    if base.contains(['(', ')', '[', ']', '*', '?', '+', '|', '\\', '^', '$']) {
+        None
+    } else {
+        Some(base.to_string())
+    }
+}
+
+/// Shell reading the type of the local rule for `path` into `$CUR` (empty
+/// when there is none). The expression goes through the environment so awk
+/// compares it literally.
+fn current_line(spec: &str) -> String {
+    format!(
+        "CUR=\"$(semanage fcontext -l -C | SPEC='{spec}' awk '$1 == ENVIRON[\"SPEC\"] {{ split($NF, c, \":\"); print c[3] }}')\""
+    )
+}
+
+fn relabel_line(resource: &Resource) -> String {
+    match relabel_target(resource) {
+        Some(target) => format!("if [ -e '{target}' ]; then restorecon -R '{target}'; fi"),
+        None => "true".to_string(),
+    }
+}
+
+/// Check script: is there a local rule for the path expression?
+pub fn check_script(resource: &Resource) -> String {
+    let spec = spec(resource);
+    format!(
+        "set -euo pipefail\n\
+         {}\n\
+         if [ -n \"$CUR\" ]; then echo \"exists:$CUR\"; else echo 'missing:{spec}'; fi",
+        current_line(spec)
+    )
+}
+
+/// Apply script: add or modify the rule, or delete it for `state: absent`,
+/// then relabel.
+pub fn apply_script(resource: &Resource) -> String {
+    let spec = spec(resource);
+    let mut lines = vec!["set -euo pipefail".to_string(), current_line(spec)];
+    if resource.state.as_deref() == Some("absent") {
+        lines.push(format!(
+            "if [ -n \"$CUR\" ]; then semanage fcontext -d '{spec}'; fi"
+        ));
+        lines.push(relabel_line(resource));
+        lines.push(format!("echo 'removed:{spec}'"));
+    } else {
+        let setype = resource.selinux_type.as_deref().unwrap_or("unknown");
+        lines.push(format!(
+            "if [ -z \"$CUR\" ]; then semanage fcontext -a -t '{setype}' '{spec}'\n\
+             elif [ \"$CUR\" != '{setype}' ]; then semanage fcontext -m -t '{setype}' '{spec}'; fi"
+        ));
+        lines.push(relabel_line(resource));
+        lines.push(format!("echo 'applied:{spec}={setype}'"));
+    }
+    lines.join("\n")
+}
+
+/// State query: the rule's type and the type currently labelled on `target`.
+pub fn state_query_script(resource: &Resource) -> String {
+    let spec = spec(resource);
+    let label = match relabel_target(resource) {
+        Some(target) => format!(
+            "if [ -e '{target}' ]; then LABEL=\"$(stat -c %C '{target}' | cut -d: -f3)\"; else LABEL=none; fi"
+        ),
+        None => "LABEL=none".to_string(),
+    };
+    format!(
+        "set -euo pipefail\n\
+         {}\n\
+         {label}\n\
+         echo \"selinux_fcontext={spec}:type=${{CUR:-none}}:label=$LABEL\"",
+        current_line(spec)
+    )
+}
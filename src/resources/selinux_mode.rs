@@ -0,0 +1,82 @@
+//! SELinux mode resource handler.
+//!
+//! `state` is the mode: `enforcing`, `permissive` or `disabled`. The mode is
+//! written to `SELINUX=` in `/etc/selinux/config` and, while SELinux is
+//! running, switched live with `setenforce`. Enabling SELinux on a host
+//! where it is disabled, or disabling it, only takes effect after a reboot:
+//! the script says so, and schedules a full relabel (`/.autorelabel`) when
+//! SELinux is being turned on.
+
+use crate::core::types::Resource;
+
+const CONFIG: &str = "/etc/selinux/config";
+
+/// Declared mode (default `enforcing`).
+pub fn selinux_mode(resource: &Resource) -> &str {
+    resource.state.as_deref().unwrap_or("enforcing")
+}
+
+/// Live mode (`missing` without the SELinux userspace) and configured mode.
+const READ_LINES: &str =
+    "LIVE=\"$(getenforce 2>/dev/null | tr '[:upper:]' '[:lower:]' || true)\"\n\
+    LIVE=\"${LIVE:-missing}\"\n\
+    CFG=\"$(sed -n 's/^SELINUX=//p' /etc/selinux/config 2>/dev/null || true)\"";
+
+/// Check script: is SELinux present, and in which mode?
+pub fn check_script(_resource: &Resource) -> String {
+    format!(
+        "set -euo pipefail\n\
+         if ! command -v getenforce >/dev/null 2>&1; then echo 'missing:selinux'; exit 0; fi\n\
+         {READ_LINES}\n\
+         echo \"exists:$LIVE:$CFG\""
+    )
+}
+
+/// Apply script: update the config file, switch the live mode when possible
+/// and report when a reboot is still needed.
+pub fn apply_script(resource: &Resource) -> String {
+    let mode = selinux_mode(resource);
+    let mut lines = vec![
+        "set -euo pipefail".to_string(),
+        "if ! command -v getenforce >/dev/null 2>&1; then echo 'getenforce not found: install the SELinux userspace first' >&2; exit 1; fi".to_string(),
+        READ_LINES.to_string(),
+        format!(
+            "if [ \"$CFG\" != '{mode}' ]; then\n\
+             \x20 if grep -q '^SELINUX=' '{CONFIG}'; then sed -i 's/^SELINUX=.*/SELINUX={mode}/' '{CONFIG}'\n\
+             \x20 else echo 'SELINUX={mode}' >> '{CONFIG}'; fi\n\
+             \x20 echo 'changed:config'\n\
+             fi"
+        ),
+    ];
+    match mode {
+        "disabled" => lines.push(
+            "if [ \"$LIVE\" != disabled ]; then\n\
+             \x20 setenforce 0\n\
+             \x20 echo 'reboot required to disable SELinux (permissive until then)'\n\
+             fi"
+            .to_string(),
+        ),
+        _ => {
+            let flag = if mode == "enforcing" { 1 } else { 0 };
+            lines.push(format!(
+                "if [ \"$LIVE\" = disabled ]; then\n\
+                 \x20 touch /.autorelabel\n\
+                 \x20 echo 'reboot required to enable SELinux (full relabel scheduled)'\n\
+                 elif [ \"$LIVE\" != '{mode}' ]; then\n\
+                 \x20 setenforce {flag}\n\
+                 fi"
+            ));
+        }
+    }
+    lines.push(format!("echo 'applied:{mode}'"));
+    lines.join("\n")
+}
+
+/// State query: live mode from `getenforce` and the configured mode.
+pub fn state_query_script(_resource: &Resource) -> String {
+    format!(
+        "set -euo pipefail\n\
+         {READ_LINES}\n\
+         echo \"selinux_mode=live:$LIVE:config:$CFG\""
+    )
+}
@@ -0,0 +1,113 @@
+//! Swap resource handler (swap file or swap partition).
+//!
+//! `path` is either a file (created with `fallocate` at `size`) or a block
+//! device under `/dev/`. A device is only formatted with `mkswap` when it
+//! carries no signature at all, or already is swap; anything else fails
+//! unless `force` is set. The entry is persisted in `/etc/fstab`, and
+//! `swappiness` is written to `/etc/sysctl.d/99-forjar-swap.conf`.
+
+use super::disk::{parse_size_bytes, size_arg};
+use crate::core::types::Resource;
+
+/// sysctl drop-in holding `vm.swappiness`.
+pub const SWAPPINESS_CONF: &str = "/etc/sysctl.d/99-forjar-swap.conf";
+
+fn swap_path(resource: &Resource) -> &str {
+    resource.path.as_deref().unwrap_or("/swapfile")
+}
+
+/// Whether `path` names a block device rather than a swap file.
+pub fn is_device(path: &str) -> bool {
+    path.starts_with("/dev/")
+}
+
+/// Generate shell to check whether the swap area is active.
+pub fn check_script(resource: &Resource) -> String {
+    let path = swap_path(resource);
+    format!(
+        "if swapon --show=NAME --noheadings | grep -qx '{path}'; then echo 'exists:{path}'; else echo 'missing:{path}'; fi"
+    )
+}
+
+/// Generate shell to converge the swap area.
+pub fn apply_script(resource: &Resource) -> String {
+    let path = swap_path(resource);
+    let mut lines = vec!["set -euo pipefail".to_string()];
+
+    if resource.state.as_deref() == Some("absent") {
+        lines.push(format!(
+            "if swapon --show=NAME --noheadings | grep -qx '{path}'; then swapoff '{path}'; fi"
+        ));
+        lines.push(format!(
+            "sed -i '\\|^{path} none swap|d' /etc/fstab 2>/dev/null || true"
+        ));
+        if !is_device(path) {
+            lines.push(format!("rm -f '{path}'"));
+        }
+        return lines.join("\n");
+    }
+
+    if is_device(path) {
+        lines.push(format!(
+            "SIG=\"$(blkid -p -o value -s TYPE '{path}' 2>/dev/null || true)\""
+        ));
+        let guard = if resource.force {
+            String::new()
+        } else {
+            format!(
+                "if [ -n \"$SIG\" ] && [ \"$SIG\" != 'swap' ]; then\n\
+                 \x20 echo \"ERROR: {path} holds $SIG; refusing to mkswap without force\" >&2\n\
+                 \x20 exit 1\n\
+                 fi\n"
+            )
+        };
+        lines.push(format!(
+            "{guard}if [ \"$SIG\" != 'swap' ]; then mkswap '{path}'; fi"
+        ));
+    } else {
+        let size = size_arg(resource.size.as_deref().unwrap_or("1G"));
+        // A swap file of the wrong size is recreated
+        if let Some(bytes) = parse_size_bytes(&size) {
+            lines.push(format!(
+                "if [ -f '{path}' ] && [ \"$(stat -c %s '{path}')\" != '{bytes}' ]; then\n\
+                 \x20 if swapon --show=NAME --noheadings | grep -qx '{path}'; then swapoff '{path}'; fi\n\
+                 \x20 rm -f '{path}'\n\
+                 fi"
+            ));
+        }
+        lines.push(format!(
+            "if [ ! -f '{path}' ]; then\n\
+             \x20 fallocate -l '{size}' '{path}'\n\
+             \x20 chmod 600 '{path}'\n\
+             \x20 mkswap '{path}'\n\
+             fi"
+        ));
+    }
+    lines.push(format!(
+        "if ! swapon --show=NAME --noheadings | grep -qx '{path}'; then swapon '{path}'; fi"
+    ));
+    lines.push(format!(
+        "if ! grep -q '^{path} none swap' /etc/fstab 2>/dev/null; then\n\
+         \x20 echo '{path} none swap sw 0 0' >> /etc/fstab\n\
+         fi"
+    ));
+    if let Some(swappiness) = resource.swappiness {
+        lines.push(format!(
+            "echo 'vm.swappiness = {swappiness}' > '{SWAPPINESS_CONF}'"
+        ));
+        lines.push(format!("sysctl -q -w 'vm.swappiness={swappiness}'"));
+    }
+    lines.join("\n")
+}
+
+/// Generate shell to query swap state (for BLAKE3 hashing).
+pub fn state_query_script(resource: &Resource) -> String {
+    let path = swap_path(resource);
+    let mut script = format!(
+        "swapon --show=NAME,TYPE,SIZE --noheadings --bytes | grep '^{path} ' || echo 'swap=MISSING:{path}'"
+    );
+    if resource.swappiness.is_some() {
+        script.push_str("\necho \"swappiness=$(cat /proc/sys/vm/swappiness)\"");
+    }
+    script
+}
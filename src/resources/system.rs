@@ -0,0 +1,277 @@
+//! Host identity resource handler.
+//!
+//! One `system` resource per machine manages the settings every new box
+//! needs: hostname (`hostnamectl` plus the `127.0.1.1` line in
+//! `/etc/hosts`), timezone, locale (generated when missing), console keymap
+//! and time sync through chrony or systemd-timesyncd. Each setting is only
+//! touched when it is declared and differs from the live value.
+//!
+//! The hostname defaults to the machine's `hostname` (filled in by the
+//! resolver and executor, see `resolver::apply_machine_defaults`).
+
+use crate::core::types::Resource;
+
+const TIMESYNCD_DROPIN: &str = "/etc/systemd/timesyncd.conf.d/forjar.conf";
+const CHRONY_SOURCES_DIR: &str = "/etc/chrony/sources.d";
+const CHRONY_SOURCES: &str = "/etc/chrony/sources.d/forjar.sources";
+
+const WRITE_FN: &str = "forjar_sys_write() {\n\
+    \x20 cat > \"$1.forjar-new\"\n\
+    \x20 if cmp -s \"$1.forjar-new\" \"$1\"; then\n\
+    \x20   unlink \"$1.forjar-new\"\n\
+    \x20 else\n\
+    \x20   mv \"$1.forjar-new\" \"$1\"\n\
+    \x20   CHANGED=1\n\
+    \x20 fi\n\
+    }";
+
+/// Time sync daemon: `timesyncd` (default) or `chrony`.
+pub fn ntp_provider(resource: &Resource) -> &str {
+    resource.provider.as_deref().unwrap_or("timesyncd")
+}
+
+/// `locale -a` spelling of a locale: lowercase, no dashes (`en_us.utf8`).
+pub fn normalize_locale(locale: &str) -> String {
+    locale.to_ascii_lowercase().replace('-', "")
+}
+
+/// Split a locale into name and charset (`en_US.UTF-8` → `en_US`, `UTF-8`).
+pub fn split_locale(locale: &str) -> (&str, &str) {
+    locale.split_once('.').unwrap_or((locale, "UTF-8"))
+}
+
+/// The `/etc/hosts` line for a hostname: FQDN first, then the short name.
+pub fn hosts_line(hostname: &str) -> String {
+    match hostname.split_once('.') {
+        Some((short, _)) => format!("127.0.1.1 {hostname} {short}"),
+        None => format!("127.0.1.1 {hostname}"),
+    }
+}
+
+fn hostname_lines(hostname: &str) -> Vec<String> {
+    vec![
+        "CUR_HOST=\"$(head -n 1 /etc/hostname 2>/dev/null || true)\"".to_string(),
+        format!(
+            "if [ \"$CUR_HOST\" != '{hostname}' ] || [ \"$(hostname)\" != '{hostname}' ]; then\n\
+             \x20 hostnamectl set-hostname '{hostname}'\n\
+             \x20 echo 'changed:hostname'\n\
+             fi"
+        ),
+        format!(
+            "{{ grep -v '^127\\.0\\.1\\.1[[:space:]]' /etc/hosts || true; echo '{}'; }} | forjar_sys_write /etc/hosts",
+            hosts_line(hostname)
+        ),
+    ]
+}
+
+fn timezone_lines(tz: &str) -> Vec<String> {
+    vec![
+        format!(
+            "if [ ! -e '/usr/share/zoneinfo/{tz}' ]; then echo \"unknown timezone '{tz}'\" >&2; exit 1; fi"
+        ),
+        format!(
+            "if [ \"$(timedatectl show -p Timezone --value)\" != '{tz}' ]; then\n\
+             \x20 timedatectl set-timezone '{tz}'\n\
+             \x20 echo 'changed:timezone'\n\
+             fi"
+        ),
+    ]
+}
+
+fn locale_lines(locale: &str) -> Vec<String> {
+    let (name, charset) = split_locale(locale);
+    vec![
+        "LOCALES=\"$(locale -a | tr 'A-Z' 'a-z' | tr -d '-')\"".to_string(),
+        format!(
+            "if ! grep -qx '{norm}' <<< \"$LOCALES\"; then\n\
+             \x20 if [ -f /etc/locale.gen ]; then\n\
+             \x20   if ! grep -qx '{locale} {charset}' /etc/locale.gen; then echo '{locale} {charset}' >> /etc/locale.gen; fi\n\
+             \x20   locale-gen\n\
+             \x20 else\n\
+             \x20   localedef -i '{name}' -f '{charset}' '{locale}'\n\
+             \x20 fi\n\
+             \x20 echo 'changed:locale-gen'\n\
+             fi",
+            norm = normalize_locale(locale),
+        ),
+        format!(
+            "if ! grep -qsx 'LANG={locale}' /etc/default/locale /etc/locale.conf; then\n\
+             \x20 localectl set-locale 'LANG={locale}'\n\
+             \x20 echo 'changed:locale'\n\
+             fi"
+        ),
+    ]
+}
+
+fn keyboard_lines(keymap: &str) -> Vec<String> {
+    vec![
+        "KEYMAP=\"$(localectl status | sed -n 's/^ *VC Keymap: //p')\"".to_string(),
+        format!(
+            "if [ \"$KEYMAP\" != '{keymap}' ]; then\n\
+             \x20 localectl set-keymap '{keymap}'\n\
+             \x20 echo 'changed:keyboard'\n\
+             fi"
+        ),
+    ]
+}
+
+fn timesyncd_lines(servers: &[String]) -> Vec<String> {
+    vec![
+        "mkdir -p /etc/systemd/timesyncd.conf.d".to_string(),
+        "CHANGED=0".to_string(),
+        format!(
+            "forjar_sys_write '{TIMESYNCD_DROPIN}' <<'FORJAR_EOF'\n[Time]\nNTP={}\nFORJAR_EOF",
+            servers.join(" ")
+        ),
+        "if [ \"$CHANGED\" = 1 ]; then systemctl restart systemd-timesyncd; echo 'changed:ntp'; fi"
+            .to_string(),
+        "if [ \"$(timedatectl show -p NTP --value)\" != yes ]; then timedatectl set-ntp true; fi"
+            .to_string(),
+    ]
+}
+
+fn chrony_lines(servers: &[String]) -> Vec<String> {
+    let sources: Vec<String> = servers
+        .iter()
+        .map(|s| format!("server {s} iburst"))
+        .collect();
+    vec![
+        "CHRONY_CONF=/etc/chrony/chrony.conf".to_string(),
+        "if [ ! -f \"$CHRONY_CONF\" ]; then CHRONY_CONF=/etc/chrony.conf; fi".to_string(),
+        "CHRONY_RESTART=0".to_string(),
+        format!(
+            "if ! grep -qx 'sourcedir {CHRONY_SOURCES_DIR}' \"$CHRONY_CONF\"; then\n\
+             \x20 echo 'sourcedir {CHRONY_SOURCES_DIR}' >> \"$CHRONY_CONF\"\n\
+             \x20 CHRONY_RESTART=1\n\
+             fi"
+        ),
+        format!("mkdir -p '{CHRONY_SOURCES_DIR}'"),
+        "CHANGED=0".to_string(),
+        format!(
+            "forjar_sys_write '{CHRONY_SOURCES}' <<'FORJAR_EOF'\n{}\nFORJAR_EOF",
+            sources.join("\n")
+        ),
+        "if [ \"$CHRONY_RESTART\" = 1 ]; then\n\
+         \x20 systemctl restart chrony 2>/dev/null || systemctl restart chronyd\n\
+         \x20 echo 'changed:ntp'\n\
+         elif [ \"$CHANGED\" = 1 ]; then\n\
+         \x20 chronyc reload sources\n\
+         \x20 echo 'changed:ntp'\n\
+         fi"
+        .to_string(),
+    ]
+}
+
+/// Generate shell to check whether the host identity matches.
+pub fn check_script(resource: &Resource) -> String {
+    let mut lines = Vec::new();
+    let mut conds = Vec::new();
+    if let Some(ref hostname) = resource.hostname {
+        lines.push("CUR_HOST=\"$(head -n 1 /etc/hostname 2>/dev/null || true)\"".to_string());
+        conds.push(format!("[ \"$CUR_HOST\" = '{hostname}' ]"));
+    }
+    if let Some(ref tz) = resource.timezone {
+        lines.push(
+            "CUR_TZ=\"$(timedatectl show -p Timezone --value 2>/dev/null || true)\"".to_string(),
+        );
+        conds.push(format!("[ \"$CUR_TZ\" = '{tz}' ]"));
+    }
+    if conds.is_empty() {
+        return "echo 'match:system'".to_string();
+    }
+    lines.push(format!(
+        "if {}; then echo 'match:system'; else echo 'mismatch:system'; fi",
+        conds.join(" && ")
+    ));
+    lines.join("\n")
+}
+
+/// Generate shell to converge the host identity.
+pub fn apply_script(resource: &Resource) -> String {
+    if resource.state.as_deref() == Some("absent") {
+        // Identity settings are left as they are; only forjar's time sync
+        // configuration is removed.
+        return format!(
+            "set -euo pipefail\n\
+             if [ -e '{TIMESYNCD_DROPIN}' ]; then unlink '{TIMESYNCD_DROPIN}'; systemctl restart systemd-timesyncd; fi\n\
+             if [ -e '{CHRONY_SOURCES}' ]; then unlink '{CHRONY_SOURCES}'; chronyc reload sources; fi"
+        );
+    }
+
+    let mut lines = vec!["set -euo pipefail".to_string(), WRITE_FN.to_string()];
+    if let Some(ref hostname) = resource.hostname {
+        lines.extend(hostname_lines(hostname));
+    }
+    if let Some(ref tz) = resource.timezone {
+        lines.extend(timezone_lines(tz));
+    }
+    if let Some(ref locale) = resource.locale {
+        lines.extend(locale_lines(locale));
+    }
+    if let Some(ref keymap) = resource.keyboard {
+        lines.extend(keyboard_lines(keymap));
+    }
+    if !resource.ntp_servers.is_empty() {
+        match ntp_provider(resource) {
+            "chrony" => lines.extend(chrony_lines(&resource.ntp_servers)),
+            _ => lines.extend(timesyncd_lines(&resource.ntp_servers)),
+        }
+    }
+    lines.join("\n")
+}
+
+/// Generate shell to query host identity state (for BLAKE3 hashing).
+///
+/// Only the declared settings are reported, so unmanaged ones never drift.
+pub fn state_query_script(resource: &Resource) -> String {
+    let mut lines = Vec::new();
+    if resource.hostname.is_some() {
+        lines.push("echo \"hostname=$(head -n 1 /etc/hostname 2>/dev/null || true)\"".to_string());
+        lines.push("echo \"hostname_live=$(hostname)\"".to_string());
+        lines.push(
+            "echo \"hosts=$(grep '^127\\.0\\.1\\.1[[:space:]]' /etc/hosts || true)\"".to_string(),
+        );
+    }
+    if resource.timezone.is_some() {
+        lines.push(
+            "echo \"timezone=$(timedatectl show -p Timezone --value 2>/dev/null || true)\""
+                .to_string(),
+        );
+    }
+    if let Some(ref locale) = resource.locale {
+        lines.push(format!(
+            "echo \"locale_generated=$(locale -a | tr 'A-Z' 'a-z' | tr -d '-' | grep -cx '{}' || true)\"",
+            normalize_locale(locale)
+        ));
+        lines.push(
+            "echo \"lang=$(grep -hs '^LANG=' /etc/default/locale /etc/locale.conf || true)\""
+                .to_string(),
+        );
+    }
+    if resource.keyboard.is_some() {
+        lines.push(
+            "echo \"keymap=$(localectl status 2>/dev/null | sed -n 's/^ *VC Keymap: //p')\""
+                .to_string(),
+        );
+    }
+    if !resource.ntp_servers.is_empty() {
+        let chrony = ntp_provider(resource) == "chrony";
+        let conf = if chrony {
+            CHRONY_SOURCES
+        } else {
+            TIMESYNCD_DROPIN
+        };
+        lines.push(format!(
+            "echo \"ntp_config=$(tr '\\n' ' ' 2>/dev/null < '{conf}' || true)\""
+        ));
+        if !chrony {
+            lines.push(
+                "echo \"ntp=$(timedatectl show -p NTP --value 2>/dev/null || true)\"".to_string(),
+            );
+        }
+    }
+    if lines.is_empty() {
+        return "echo 'system=unmanaged'".to_string();
+    }
+    lines.join("\n")
+}
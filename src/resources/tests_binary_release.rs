@@ -0,0 +1,448 @@
+//! Tests for `binary_release` against local stand-ins for release hosts.
+
+use super::binary_release;
+use crate::core::release_source::{self, ResolvedRelease};
+use crate::core::test_http;
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+const ASSET: &str = "tool-linux-amd64";
+const GOOD_V1: &str = "#!/bin/sh\necho 'tool 1.4.2'\n";
+const GOOD_V2: &str = "#!/bin/sh\necho 'tool 1.4.7'\n";
+const BROKEN: &str = "#!/bin/sh\nexit 3\n";
+
+type Routes = HashMap<String, Vec<u8>>;
+
+fn sha256(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Serve the routes built by `routes(base_url)` on an ephemeral port;
+/// returns the base URL.
+fn serve(routes: impl FnOnce(&str) -> Routes) -> String {
+    let (listener, base) = test_http::listen();
+    test_http::serve_on(listener, test_http::routes(routes(&base)));
+    base
+}
+
+/// Downloadable files of one release under `/dl/<tag>/`.
+fn release_files(routes: &mut Routes, tag: &str, binary: &str) {
+    let sums = format!("{}  {ASSET}\n", sha256(binary.as_bytes()));
+    routes.insert(format!("/dl/{tag}/{ASSET}"), binary.as_bytes().to_vec());
+    routes.insert(format!("/dl/{tag}/{ASSET}.minisig"), b"sig".to_vec());
+    routes.insert(format!("/dl/{tag}/SHA256SUMS"), sums.into_bytes());
+}
+
+/// GitHub/Gitea-style JSON of the release `tag`.
+fn forge_release_json(base: &str, tag: &str) -> String {
+    let dl = format!("{base}/dl/{tag}");
+    format!(
+        r#"{{"tag_name":"{tag}","draft":false,"assets":[
+            {{"name":"{ASSET}","browser_download_url":"{dl}/{ASSET}"}},
+            {{"name":"tool-darwin-arm64","browser_download_url":"{dl}/other"}},
+            {{"name":"{ASSET}.minisig","browser_download_url":"{dl}/{ASSET}.minisig"}},
+            {{"name":"SHA256SUMS","browser_download_url":"{dl}/SHA256SUMS"}}]}}"#
+    )
+}
+
+/// GitHub/Gitea-style release JSON for `releases` (tag, binary).
+fn forge_json(base: &str, releases: &[(&str, &str)]) -> Vec<u8> {
+    let items: Vec<String> = releases
+        .iter()
+        .map(|(tag, _)| forge_release_json(base, tag))
+        .collect();
+    format!("[{}]", items.join(",")).into_bytes()
+}
+
+/// A GitHub-compatible API under `/api` serving `releases`.
+fn github(releases: &'static [(&'static str, &'static str)]) -> String {
+    serve(|base| {
+        let mut routes = Routes::new();
+        for (tag, binary) in releases {
+            release_files(&mut routes, tag, binary);
+        }
+        routes.insert(
+            "/api/repos/acme/tool/releases?per_page=100".to_string(),
+            forge_json(base, releases),
+        );
+        routes
+    })
+}
+
+fn make_release(url: &str, dir: &Path) -> Resource {
+    Resource {
+        resource_type: ResourceType::BinaryRelease,
+        machine: MachineTarget::Single("web".to_string()),
+        provider: Some("github".to_string()),
+        url: Some(url.to_string()),
+        repo: Some("acme/tool".to_string()),
+        version: Some("^1.4".to_string()),
+        asset_pattern: Some("*linux*".to_string()),
+        binary: Some("tool".to_string()),
+        install_dir: Some(dir.display().to_string()),
+        ..Default::default()
+    }
+}
+
+fn bash(script: &str, path_prefix: Option<&Path>) -> std::process::Output {
+    let mut cmd = Command::new("bash");
+    cmd.arg("-c").arg(script);
+    if let Some(prefix) = path_prefix {
+        let path = std::env::var("PATH").unwrap_or_default();
+        cmd.env("PATH", format!("{}:{path}", prefix.display()));
+    }
+    cmd.output().expect("bash")
+}
+
+fn install(r: &Resource, release: &ResolvedRelease) -> std::process::Output {
+    bash(&binary_release::install_script(r, release), None)
+}
+
+fn stderr(out: &std::process::Output) -> String {
+    String::from_utf8_lossy(&out.stderr).to_string()
+}
+
+const V1_ONLY: &[(&str, &str)] = &[("v1.4.2", GOOD_V1), ("v2.0.0", GOOD_V1)];
+const V1_V2: &[(&str, &str)] = &[("v1.4.2", GOOD_V1), ("v1.4.7", GOOD_V2)];
+const V1_BROKEN: &[(&str, &str)] = &[("v1.4.2", GOOD_V1), ("v1.4.7", BROKEN)];
+
+#[test]
+fn test_binary_release_resolves_github_constraint() {
+    let base = github(V1_V2);
+    let dir = tempfile::tempdir().unwrap();
+    let r = make_release(&format!("{base}/api"), dir.path());
+    let release = release_source::resolve(&r).unwrap();
+    assert_eq!(release.tag, "v1.4.7");
+    assert_eq!(release.asset.name, ASSET);
+    assert_eq!(release.sha256, sha256(GOOD_V2.as_bytes()));
+    assert!(release.signature.is_none());
+
+    let mut pinned = r.clone();
+    pinned.version = Some("=1.4.2".to_string());
+    assert_eq!(release_source::resolve(&pinned).unwrap().tag, "v1.4.2");
+    pinned.version = Some("^3".to_string());
+    let err = release_source::resolve(&pinned).unwrap_err();
+    assert!(err.contains("no release of acme/tool"), "{err}");
+}
+
+/// A GitHub-compatible API serving only the `nightly` release of `binary`.
+fn github_nightly(binary: &'static str) -> String {
+    serve(|base| {
+        let mut routes = Routes::new();
+        release_files(&mut routes, "nightly", binary);
+        routes.insert(
+            "/api/repos/acme/tool/releases/tags/nightly".to_string(),
+            forge_release_json(base, "nightly").into_bytes(),
+        );
+        routes
+    })
+}
+
+#[test]
+fn test_github_release_pins_non_version_tag() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut r = make_release(&format!("{}/api", github_nightly(GOOD_V1)), dir.path());
+    r.resource_type = ResourceType::GithubRelease;
+    r.provider = None;
+    r.version = None;
+    r.tag = Some("nightly".to_string());
+    let release = release_source::resolve(&r).unwrap();
+    assert_eq!(release.tag, "nightly");
+    assert_eq!(release.sha256, sha256(GOOD_V1.as_bytes()));
+    let out = install(&r, &release);
+    assert!(out.status.success(), "{}", stderr(&out));
+    assert!(String::from_utf8_lossy(&install(&r, &release).stdout).contains("unchanged:"));
+
+    // The tag is re-published with a new build: reinstalled, not skipped
+    r.url = Some(format!("{}/api", github_nightly(GOOD_V2)));
+    let out = install(&r, &release_source::resolve(&r).unwrap());
+    assert!(out.status.success(), "{}", stderr(&out));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("installed:acme/tool:nightly"));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("tool")).unwrap(),
+        GOOD_V2
+    );
+}
+
+#[test]
+fn test_binary_release_resolves_gitea_and_gitlab() {
+    let base = serve(|base| {
+        let mut routes = Routes::new();
+        release_files(&mut routes, "v1.4.2", GOOD_V1);
+        routes.insert(
+            "/api/v1/repos/acme/tool/releases?limit=50".to_string(),
+            forge_json(base, &[("v1.4.2", GOOD_V1)]),
+        );
+        let gitlab = format!(
+            r#"[{{"tag_name":"v1.4.2","assets":{{"links":[
+                {{"name":"{ASSET}","url":"{base}/x","direct_asset_url":"{base}/dl/v1.4.2/{ASSET}"}},
+                {{"name":"SHA256SUMS","url":"{base}/dl/v1.4.2/SHA256SUMS"}}]}}}},
+               {{"tag_name":"v1.9.0","upcoming_release":true,"assets":{{"links":[]}}}}]"#
+        );
+        routes.insert(
+            "/api/v4/projects/acme%2Ftool/releases?per_page=100".to_string(),
+            gitlab.into_bytes(),
+        );
+        routes
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let mut r = make_release(&base, dir.path());
+    r.provider = Some("gitea".to_string());
+    let release = release_source::resolve(&r).unwrap();
+    assert_eq!(release.tag, "v1.4.2");
+
+    r.provider = Some("gitlab".to_string());
+    let release = release_source::resolve(&r).unwrap();
+    assert_eq!(release.tag, "v1.4.2");
+    assert_eq!(release.asset.url, format!("{base}/dl/v1.4.2/{ASSET}"));
+    assert_eq!(release.sha256, sha256(GOOD_V1.as_bytes()));
+}
+
+#[test]
+fn test_binary_release_resolves_static_index() {
+    let base = serve(|_| {
+        let mut routes = Routes::new();
+        routes.insert(
+            "/dist/".to_string(),
+            br#"<a href="../">../</a><a href="v1.3.0/">v1.3.0/</a>
+                <a href="v1.4.2/">v1.4.2/</a><a href="v2.0.0/">v2.0.0/</a>"#
+                .to_vec(),
+        );
+        let sums = format!("{}  {ASSET}\n", sha256(GOOD_V1.as_bytes()));
+        routes.insert(
+            "/dist/v1.4.2/".to_string(),
+            format!(r#"<a href="{ASSET}">x</a><a href="SHA256SUMS">s</a>"#).into_bytes(),
+        );
+        routes.insert("/dist/v1.4.2/SHA256SUMS".to_string(), sums.into_bytes());
+        routes
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let mut r = make_release(&format!("{base}/dist/"), dir.path());
+    r.provider = Some("static".to_string());
+    r.repo = None;
+    let release = release_source::resolve(&r).unwrap();
+    assert_eq!(release.tag, "v1.4.2");
+    assert_eq!(release.asset.url, format!("{base}/dist/v1.4.2/{ASSET}"));
+    assert_eq!(release.sha256, sha256(GOOD_V1.as_bytes()));
+}
+
+#[test]
+fn test_binary_release_requires_checksum_and_signature_files() {
+    let base = github(V1_ONLY);
+    let dir = tempfile::tempdir().unwrap();
+    let mut r = make_release(&format!("{base}/api"), dir.path());
+    r.checksum_file = Some("checksums.txt".to_string());
+    let err = release_source::resolve(&r).unwrap_err();
+    assert!(
+        err.contains("checksum file 'checksums.txt' not found"),
+        "{err}"
+    );
+
+    r.checksum_file = None;
+    r.signature = Some("cosign".to_string());
+    let err = release_source::resolve(&r).unwrap_err();
+    assert!(err.contains(&format!("no signature {ASSET}.sig")), "{err}");
+    r.signature = Some("minisign".to_string());
+    assert!(release_source::resolve(&r).unwrap().signature.is_some());
+}
+
+#[test]
+fn test_binary_release_installs_and_is_idempotent() {
+    let base = github(V1_ONLY);
+    let dir = tempfile::tempdir().unwrap();
+    let r = make_release(&format!("{base}/api"), dir.path());
+    let release = release_source::resolve(&r).unwrap();
+    assert_eq!(release.tag, "v1.4.2");
+
+    let out = install(&r, &release);
+    let stdout = String::from_utf8_lossy(&out.stdout).to_string();
+    assert!(out.status.success(), "{stdout}{}", stderr(&out));
+    let outputs = crate::core::state::parse_script_outputs(&stdout);
+    assert_eq!(outputs.get("version").map(String::as_str), Some("1.4.2"));
+    assert!(stdout.contains("installed:acme/tool:v1.4.2"));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("tool")).unwrap(),
+        GOOD_V1
+    );
+
+    let again = install(&r, &release);
+    assert!(String::from_utf8_lossy(&again.stdout).contains("unchanged:acme/tool:v1.4.2"));
+
+    let check = bash(&binary_release::check_script(&r), None);
+    assert_eq!(
+        String::from_utf8_lossy(&check.stdout).trim(),
+        "installed:acme/tool:v1.4.2"
+    );
+    let state = bash(&binary_release::state_query_script(&r), None);
+    assert_eq!(
+        String::from_utf8_lossy(&state.stdout).trim(),
+        format!(
+            "binary_release=acme/tool:v1.4.2:{}",
+            sha256(GOOD_V1.as_bytes())
+        )
+    );
+
+    let absent = bash(&binary_release::absent_script(&r), None);
+    assert!(absent.status.success());
+    assert!(!dir.path().join("tool").exists());
+    assert!(!dir.path().join(".tool.forjar-release").exists());
+}
+
+#[test]
+fn test_binary_release_upgrade_keeps_previous() {
+    let base = github(V1_V2);
+    let dir = tempfile::tempdir().unwrap();
+    let mut r = make_release(&format!("{base}/api"), dir.path());
+    r.version = Some("=1.4.2".to_string());
+    assert!(install(&r, &release_source::resolve(&r).unwrap())
+        .status
+        .success());
+    r.version = Some("^1.4".to_string());
+    let out = install(&r, &release_source::resolve(&r).unwrap());
+    assert!(out.status.success(), "{}", stderr(&out));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("tool")).unwrap(),
+        GOOD_V2
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join(".tool.forjar-prev")).unwrap(),
+        GOOD_V1
+    );
+}
+
+#[test]
+fn test_binary_release_rolls_back_on_failed_smoke_check() {
+    let base = github(V1_BROKEN);
+    let dir = tempfile::tempdir().unwrap();
+    let mut r = make_release(&format!("{base}/api"), dir.path());
+    r.version = Some("=1.4.2".to_string());
+    assert!(install(&r, &release_source::resolve(&r).unwrap())
+        .status
+        .success());
+
+    r.version = Some("^1.4".to_string());
+    let release = release_source::resolve(&r).unwrap();
+    assert_eq!(release.tag, "v1.4.7");
+    let out = install(&r, &release);
+    assert!(!out.status.success());
+    let err = stderr(&out);
+    assert!(
+        err.contains("ROLLBACK: restored the previous tool"),
+        "{err}"
+    );
+    assert!(err.contains("tool v1.4.7 failed its --version smoke check"));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("tool")).unwrap(),
+        GOOD_V1
+    );
+    let check = bash(&binary_release::check_script(&r), None);
+    assert!(String::from_utf8_lossy(&check.stdout).contains(":v1.4.2"));
+}
+
+#[test]
+fn test_binary_release_rejects_checksum_mismatch() {
+    let base = github(V1_ONLY);
+    let dir = tempfile::tempdir().unwrap();
+    let r = make_release(&format!("{base}/api"), dir.path());
+    let mut release = release_source::resolve(&r).unwrap();
+    release.sha256 = "0".repeat(64);
+    let out = install(&r, &release);
+    assert!(!out.status.success());
+    assert!(stderr(&out).contains(&format!("CHECKSUM MISMATCH: {ASSET}")));
+    assert!(!dir.path().join("tool").exists());
+}
+
+/// A stand-in `minisign` accepting only `accepted_key`.
+fn fake_minisign(bin_dir: &Path, accepted_key: &str) {
+    let script = bin_dir.join("minisign");
+    std::fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\nfor a in \"$@\"; do [ \"$a\" = '{accepted_key}' ] && exit 0; done\nexit 1\n"
+        ),
+    )
+    .unwrap();
+    Command::new("chmod")
+        .args(["+x", &script.display().to_string()])
+        .status()
+        .unwrap();
+}
+
+#[test]
+fn test_binary_release_verifies_minisign_signature() {
+    let base = github(V1_ONLY);
+    let dir = tempfile::tempdir().unwrap();
+    let tools = tempfile::tempdir().unwrap();
+    fake_minisign(tools.path(), "RWgoodkey");
+    let mut r = make_release(&format!("{base}/api"), dir.path());
+    r.signature = Some("minisign".to_string());
+    r.public_key = Some("RWbadkey".to_string());
+    let release = release_source::resolve(&r).unwrap();
+
+    let script = binary_release::install_script(&r, &release);
+    assert!(script.contains("minisign -Vm \"$ASSET\" -x \"$ASSET.sig\" -P 'RWbadkey'"));
+    let out = bash(&script, Some(tools.path()));
+    assert!(!out.status.success());
+    assert!(stderr(&out).contains(&format!("SIGNATURE VERIFICATION FAILED: {ASSET}")));
+    assert!(!dir.path().join("tool").exists());
+
+    r.public_key = Some("RWgoodkey".to_string());
+    let out = bash(
+        &binary_release::install_script(&r, &release),
+        Some(tools.path()),
+    );
+    assert!(out.status.success(), "{}", stderr(&out));
+    assert!(dir.path().join("tool").exists());
+}
+
+#[test]
+fn test_binary_release_cosign_and_unresolved_scripts() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut r = make_release("http://127.0.0.1:1", dir.path());
+    r.public_key = Some("/etc/forjar/cosign.pub".to_string());
+    let release = ResolvedRelease {
+        tag: "v1.4.2".to_string(),
+        version: semver::Version::new(1, 4, 2),
+        asset: release_source::ReleaseAsset {
+            name: ASSET.to_string(),
+            url: "https://example.com/tool".to_string(),
+            sha256: None,
+        },
+        sha256: "a".repeat(64),
+        signature: Some((
+            release_source::SignatureKind::Cosign,
+            "https://example.com/tool.sig".to_string(),
+        )),
+    };
+    let script = binary_release::install_script(&r, &release);
+    assert!(script.contains("command -v cosign"));
+    assert!(script.contains("cosign verify-blob --key '/etc/forjar/cosign.pub'"));
+
+    let unresolved = binary_release::apply_script(&r);
+    assert!(!bash(&unresolved, None).status.success());
+    r.state = Some("absent".to_string());
+    assert!(binary_release::apply_script(&r).contains("unlink"));
+}
+
+#[test]
+fn test_binary_release_scripts_pass_lint() {
+    let base = github(V1_ONLY);
+    let dir = tempfile::tempdir().unwrap();
+    let mut r = make_release(&format!("{base}/api"), dir.path());
+    r.signature = Some("minisign".to_string());
+    r.public_key = Some("RWkey".to_string());
+    let release = release_source::resolve(&r).unwrap();
+    for script in [
+        binary_release::check_script(&r),
+        binary_release::install_script(&r, &release),
+        binary_release::state_query_script(&r),
+        binary_release::absent_script(&r),
+    ] {
+        if let Err(e) = crate::core::purifier::validate_script(&script) {
+            panic!("lint failed: {e}\n{script}");
+        }
+    }
+}
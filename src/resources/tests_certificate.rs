@@ -0,0 +1,165 @@
+//! Tests for the certificate resource handler.
+
+use super::certificate::*;
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+
+fn make_cert() -> Resource {
+    Resource {
+        resource_type: ResourceType::Certificate,
+        machine: MachineTarget::Single("web".to_string()),
+        path: Some("/etc/ssl/forjar/web.pem".to_string()),
+        key_path: Some("/etc/ssl/private/web.key".to_string()),
+        sans: vec!["web.internal".to_string(), "10.0.0.5".to_string()],
+        validity_days: Some(90),
+        renew_before: Some("14d".to_string()),
+        owner: Some("www-data".to_string()),
+        ..Default::default()
+    }
+}
+
+fn make_local() -> Resource {
+    Resource {
+        issuer: Some("local".to_string()),
+        chain_path: Some("/etc/ssl/forjar/chain.pem".to_string()),
+        ..make_cert()
+    }
+}
+
+fn lint_ok(script: &str) {
+    if let Err(e) = crate::core::purifier::validate_script(script) {
+        panic!("lint failed: {e}\n{script}");
+    }
+}
+
+#[test]
+fn test_self_signed_signs_on_target() {
+    let r = make_cert();
+    let script = apply_script(&r);
+    assert!(script.contains("umask 077"));
+    assert!(script.contains(
+        "if [ ! -s \"$KEY\" ]; then openssl genpkey -algorithm EC -pkeyopt ec_paramgen_curve:P-256 -out \"$KEY\"; fi"
+    ));
+    assert!(script.contains(
+        "openssl req -x509 -new -key \"$KEY\" -subj '/CN=web.internal' -days 90 \
+         -addext 'subjectAltName=DNS:web.internal,IP:10.0.0.5' -out \"$CERT.forjar-new\""
+    ));
+    assert!(script.contains(&format!(
+        "echo '{}' > \"$CERT.forjar\"",
+        params_fingerprint(&r)
+    )));
+    // 14 days in seconds
+    assert!(script.contains("-checkend 1209600"));
+    assert!(script.contains("chmod 0600 \"$KEY\""));
+    assert!(script.contains("chown 'www-data' \"$KEY\" \"$CERT\""));
+    assert!(script.ends_with("echo \"FORJAR_OUTPUT not_after=${NOT_AFTER#notAfter=}\""));
+}
+
+#[test]
+fn test_rsa_key_type() {
+    let mut r = make_cert();
+    r.key_type = Some("rsa".to_string());
+    assert!(apply_script(&r).contains("-algorithm RSA -pkeyopt rsa_keygen_bits:3072"));
+}
+
+#[test]
+fn test_ca_signed_prints_csr() {
+    let r = make_local();
+    let script = apply_script(&r);
+    assert!(script.contains(
+        "if ! forjar_cert_current; then\n  openssl req -new -key \"$KEY\" -subj '/CN=web.internal'\n  exit 0\nfi"
+    ));
+    assert!(!script.contains("-x509"));
+    assert!(script.contains("mkdir -p '/etc/ssl/forjar' '/etc/ssl/private'"));
+    assert!(is_ca_signed(&r));
+    assert!(!is_ca_signed(&make_cert()));
+}
+
+#[test]
+fn test_install_script_writes_cert_and_chain() {
+    let r = make_local();
+    let script = install_script(&r, "CERT-PEM\n", "CA-PEM\n");
+    assert!(script.contains("cat > \"$CERT.forjar-new\" <<'FORJAR_EOF'\nCERT-PEM\nFORJAR_EOF"));
+    assert!(script.contains("cat > '/etc/ssl/forjar/chain.pem' <<'FORJAR_EOF'\nCA-PEM\nFORJAR_EOF"));
+    assert!(script.contains("chmod 0644 '/etc/ssl/forjar/chain.pem'"));
+    assert!(script.contains(&format!(
+        "echo '{}' > \"$CERT.forjar\"",
+        params_fingerprint(&r)
+    )));
+    lint_ok(&script);
+}
+
+#[test]
+fn test_absent_removes_key_and_cert() {
+    let mut r = make_local();
+    r.state = Some("absent".to_string());
+    assert_eq!(
+        apply_script(&r),
+        "set -euo pipefail\nrm -f '/etc/ssl/forjar/web.pem' '/etc/ssl/forjar/web.pem.forjar' \
+         '/etc/ssl/private/web.key' '/etc/ssl/forjar/chain.pem'"
+    );
+}
+
+#[test]
+fn test_state_query_excludes_key_material() {
+    let query = state_query_script(&make_local());
+    assert!(query.contains("-subject -enddate -fingerprint -sha256"));
+    assert!(query.contains(
+        "stat -c '%n %a %U:%G' '/etc/ssl/private/web.key' '/etc/ssl/forjar/web.pem' '/etc/ssl/forjar/chain.pem'"
+    ));
+    assert!(!query.contains("openssl pkey"));
+    assert!(query.contains("certificate=MISSING:/etc/ssl/forjar/web.pem"));
+}
+
+#[test]
+fn test_fingerprint_tracks_issuance_params() {
+    let base = params_fingerprint(&make_cert());
+    assert_eq!(base.len(), 16);
+    let mut r = make_cert();
+    r.owner = Some("nginx".to_string());
+    assert_eq!(params_fingerprint(&r), base);
+    r.sans.push("www.internal".to_string());
+    assert_ne!(params_fingerprint(&r), base);
+    let mut r = make_cert();
+    r.validity_days = Some(30);
+    assert_ne!(params_fingerprint(&r), base);
+}
+
+#[test]
+fn test_parse_window_and_defaults() {
+    assert_eq!(parse_window("30d"), Some(30 * 86400));
+    assert_eq!(parse_window("72h"), Some(72 * 3600));
+    assert_eq!(parse_window("90m"), Some(5400));
+    assert_eq!(parse_window("10s"), Some(10));
+    assert_eq!(parse_window("30"), None);
+    assert_eq!(parse_window("d"), None);
+    let r = Resource {
+        common_name: None,
+        sans: Vec::new(),
+        ..make_cert()
+    };
+    assert_eq!(common_name(&r), "localhost");
+    assert_eq!(validity_days(&Resource::default()), DEFAULT_VALIDITY_DAYS);
+    assert_eq!(renew_before_secs(&Resource::default()), 30 * 86400);
+}
+
+#[test]
+fn test_extract_csr() {
+    let stdout =
+        "noise\n-----BEGIN CERTIFICATE REQUEST-----\nMIIB\n-----END CERTIFICATE REQUEST-----\n";
+    assert_eq!(
+        extract_csr(stdout),
+        Some("-----BEGIN CERTIFICATE REQUEST-----\nMIIB\n-----END CERTIFICATE REQUEST-----")
+    );
+    assert_eq!(extract_csr("FORJAR_OUTPUT not_after=x\n"), None);
+}
+
+#[test]
+fn test_certificate_scripts_pass_lint() {
+    let mut absent = make_cert();
+    absent.state = Some("absent".to_string());
+    for r in [make_cert(), make_local(), absent] {
+        for script in [check_script(&r), apply_script(&r), state_query_script(&r)] {
+            lint_ok(&script);
+        }
+    }
+}
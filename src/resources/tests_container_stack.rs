@@ -0,0 +1,437 @@
+//! Tests for the container_stack resource (compose and Quadlet backends).
+
+use super::container_stack::*;
+use super::quadlet;
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+
+const STACK: &str = r#"
+services:
+  web:
+    image: nginx:1.27
+    ports: ["8080:80"]
+    depends_on: [api]
+    networks: [front]
+  api:
+    image: ghcr.io/acme/api:2.1
+    environment:
+      DB_HOST: db
+      WORKERS: 4
+    depends_on: [db]
+    networks: [front, back]
+    restart: on-failure
+  db:
+    image: postgres:16
+    volumes: ["pgdata:/var/lib/postgresql/data", "/srv/init:/docker-entrypoint-initdb.d"]
+    networks: [back]
+networks:
+  front: {}
+  back: {}
+volumes:
+  pgdata: {}
+"#;
+
+fn make_stack(format: Option<&str>) -> Resource {
+    Resource {
+        resource_type: ResourceType::ContainerStack,
+        machine: MachineTarget::Single("m1".to_string()),
+        name: Some("shop".to_string()),
+        content: Some(STACK.to_string()),
+        format: format.map(String::from),
+        ..Default::default()
+    }
+}
+
+fn pos(script: &str, needle: &str) -> usize {
+    script
+        .find(needle)
+        .unwrap_or_else(|| panic!("missing '{needle}' in:\n{script}"))
+}
+
+#[test]
+fn test_parse_stack_services_networks_volumes() {
+    let spec = parse_stack(STACK).unwrap();
+    let names: Vec<&str> = spec.services.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["web", "api", "db"]);
+    assert_eq!(spec.services[0].depends_on, ["api"]);
+    assert_eq!(spec.networks, ["front", "back"]);
+    assert_eq!(spec.volumes, ["pgdata"]);
+}
+
+#[test]
+fn test_parse_stack_errors() {
+    assert!(parse_stack("services: {}").is_err());
+    assert!(parse_stack("version: '3'").is_err());
+    let err = parse_stack("services:\n  web:\n    ports: [80]").unwrap_err();
+    assert!(err.contains("'web' has no image"), "{err}");
+}
+
+#[test]
+fn test_service_order_dependencies_first() {
+    let spec = parse_stack(STACK).unwrap();
+    let order: Vec<&str> = service_order(&spec)
+        .unwrap()
+        .into_iter()
+        .map(|i| spec.services[i].name.as_str())
+        .collect();
+    assert_eq!(order, ["db", "api", "web"]);
+}
+
+#[test]
+fn test_service_order_rejects_cycles_and_unknown_deps() {
+    let cyclic = "services:\n  a:\n    image: x\n    depends_on: [b]\n  b:\n    image: y\n    depends_on: [a]\n";
+    let err = service_order(&parse_stack(cyclic).unwrap()).unwrap_err();
+    assert!(err.contains("cycle"), "{err}");
+    let unknown = "services:\n  a:\n    image: x\n    depends_on: [ghost]\n";
+    let err = service_order(&parse_stack(unknown).unwrap()).unwrap_err();
+    assert!(err.contains("unknown service 'ghost'"), "{err}");
+}
+
+#[test]
+fn test_depends_on_long_form() {
+    let spec = "services:\n  a:\n    image: x\n    depends_on:\n      b:\n        condition: service_healthy\n  b:\n    image: y\n";
+    let spec = parse_stack(spec).unwrap();
+    assert_eq!(spec.services[0].depends_on, ["b"]);
+}
+
+#[test]
+fn test_service_hash_changes_only_for_that_service() {
+    let before = parse_stack(STACK).unwrap();
+    let after = parse_stack(&STACK.replace("nginx:1.27", "nginx:1.28")).unwrap();
+    assert_ne!(
+        service_hash(&before.services[0]),
+        service_hash(&after.services[0])
+    );
+    assert_eq!(
+        service_hash(&before.services[1]),
+        service_hash(&after.services[1])
+    );
+    assert_eq!(
+        service_hash(&before.services[2]),
+        service_hash(&after.services[2])
+    );
+}
+
+#[test]
+fn test_render_compose_injects_name_and_label() {
+    let spec = parse_stack(STACK).unwrap();
+    let rendered = render_compose(&spec, "shop");
+    assert!(rendered.contains("container_name: shop-web"));
+    assert!(rendered.contains("container_name: shop-db"));
+    assert_eq!(rendered.matches("forjar.config-hash").count(), 3);
+}
+
+#[test]
+fn test_render_compose_label_list_syntax() {
+    let spec = parse_stack("services:\n  a:\n    image: x\n    labels: [team=core]\n").unwrap();
+    let rendered = render_compose(&spec, "p");
+    assert!(rendered.contains("- team=core"));
+    assert!(rendered.contains("- forjar.config-hash=blake3:"));
+}
+
+#[test]
+fn test_explicit_container_name_kept() {
+    let spec = parse_stack("services:\n  a:\n    image: x\n    container_name: custom\n").unwrap();
+    assert_eq!(container_name("p", &spec.services[0]), "custom");
+}
+
+#[test]
+fn test_compose_apply_up() {
+    let script = apply_script(&make_stack(None));
+    assert!(script.contains("mkdir -p '/opt/forjar/stacks/shop'"));
+    assert!(script.contains("cat > '/opt/forjar/stacks/shop/compose.yaml' <<'FORJAR_STACK'"));
+    assert!(script.contains(
+        "docker compose -p 'shop' -f '/opt/forjar/stacks/shop/compose.yaml' up -d --remove-orphans"
+    ));
+}
+
+#[test]
+fn test_compose_apply_podman_and_path() {
+    let mut r = make_stack(None);
+    r.runtime = Some("podman".to_string());
+    r.path = Some("/srv/shop".to_string());
+    let script = apply_script(&r);
+    assert!(script.contains("podman compose -p 'shop' -f '/srv/shop/compose.yaml' up -d"));
+}
+
+#[test]
+fn test_compose_absent_stops_in_reverse_order() {
+    let mut r = make_stack(None);
+    r.state = Some("absent".to_string());
+    let script = apply_script(&r);
+    let web = pos(&script, "docker stop 'shop-web'");
+    let api = pos(&script, "docker stop 'shop-api'");
+    let db = pos(&script, "docker stop 'shop-db'");
+    assert!(web < api && api < db, "dependents must stop first");
+    assert!(pos(&script, "down --remove-orphans") > db);
+}
+
+#[test]
+fn test_absent_without_content_still_tears_down() {
+    let mut r = make_stack(None);
+    r.state = Some("absent".to_string());
+    r.content = None;
+    assert!(apply_script(&r).contains("down --remove-orphans"));
+    r.format = Some("quadlet".to_string());
+    let script = apply_script(&r);
+    assert!(script
+        .contains("grep -lsxF 'Label=forjar.stack=shop' '/etc/containers/systemd'/*.container"));
+    assert!(script.contains("unlink \"$f\""));
+}
+
+#[test]
+fn test_invalid_spec_fails_apply() {
+    let mut r = make_stack(None);
+    r.content = Some("services: [".to_string());
+    let script = apply_script(&r);
+    assert!(script.starts_with("echo 'ERROR: container_stack shop:"));
+    assert!(script.ends_with("exit 1"));
+    r.content = None;
+    assert!(apply_script(&r).contains("no content"));
+}
+
+#[test]
+fn test_check_requires_every_service_running() {
+    let script = check_script(&make_stack(None));
+    assert_eq!(script.matches("State.Running").count(), 3);
+    assert!(script.contains("'shop-db'"));
+    assert!(script.contains("exists:shop"));
+    assert!(script.contains("missing:shop"));
+}
+
+#[test]
+fn test_state_query_per_service() {
+    let script = state_query_script(&make_stack(None));
+    let db = pos(&script, "service=db ");
+    let api = pos(&script, "service=api ");
+    let web = pos(&script, "service=web ");
+    assert!(db < api && api < web);
+    assert!(script.contains("config_hash="));
+    assert!(script.contains("MISSING"));
+    assert!(script.contains("stack=shop"));
+}
+
+#[test]
+fn test_quadlet_units_rendered() {
+    let spec = parse_stack(STACK).unwrap();
+    let web = quadlet::container_unit(&spec, "shop", &spec.services[0]);
+    assert!(web.contains("ContainerName=shop-web"));
+    assert!(web.contains("Image=nginx:1.27"));
+    assert!(web.contains("PublishPort=8080:80"));
+    assert!(web.contains("Requires=shop-api.service"));
+    assert!(web.contains("After=shop-api.service"));
+    assert!(web.contains("Network=shop-front.network"));
+    assert!(web.contains("Label=forjar.config-hash=blake3:"));
+    assert!(web.lines().any(|l| l == "Label=forjar.stack=shop"));
+
+    let api = quadlet::container_unit(&spec, "shop", &spec.services[1]);
+    assert!(api.contains("Environment=DB_HOST=db"));
+    assert!(api.contains("Environment=WORKERS=4"));
+    assert!(api.contains("Restart=on-failure"));
+
+    let db = quadlet::container_unit(&spec, "shop", &spec.services[2]);
+    assert!(db.contains("Volume=shop-pgdata.volume:/var/lib/postgresql/data"));
+    assert!(db.contains("Volume=/srv/init:/docker-entrypoint-initdb.d"));
+    assert!(db.contains("Restart=always"));
+}
+
+#[test]
+fn test_quadlet_apply_writes_changed_units_and_restarts() {
+    let script = apply_script(&make_stack(Some("quadlet")));
+    assert!(script.contains("mkdir -p '/etc/containers/systemd'"));
+    assert!(script.contains("<<'FORJAR_UNIT'"));
+    assert!(script.contains("cmp -s '/etc/containers/systemd/.shop-db.container.forjar'"));
+    assert!(script.contains("/etc/containers/systemd/shop-front.network"));
+    assert!(script.contains("/etc/containers/systemd/shop-pgdata.volume"));
+    assert!(script.contains("systemctl daemon-reload"));
+    assert!(script.contains("*' shop-web.container '*) systemctl restart 'shop-web.service'"));
+    assert!(pos(&script, "daemon-reload") < pos(&script, "start 'shop-db.service'"));
+    assert!(pos(&script, "start 'shop-db.service'") < pos(&script, "start 'shop-web.service'"));
+    assert!(
+        !script.contains("docker "),
+        "quadlet stacks run under podman"
+    );
+}
+
+#[test]
+fn test_quadlet_apply_prunes_stale_units() {
+    let script = apply_script(&make_stack(Some("quadlet")));
+    assert!(script.contains("grep -lsxF 'Label=forjar.stack=shop'"));
+    assert!(script.contains("|'shop-web.container') ;;"));
+    assert!(script.contains("unlink \"$f\""));
+}
+
+/// Units of stacks `web` and `web-api` side by side in `dir`.
+fn two_stack_unit_dir(dir: &std::path::Path) {
+    let spec = parse_stack(STACK).unwrap();
+    for project in ["web", "web-api"] {
+        for svc in &spec.services {
+            std::fs::write(
+                dir.join(format!("{project}-{}.container", svc.name)),
+                quadlet::container_unit(&spec, project, svc),
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            dir.join(format!("{project}-front.network")),
+            quadlet::network_unit(project, "front"),
+        )
+        .unwrap();
+    }
+}
+
+/// Run `script` with a no-op `systemctl` first on PATH.
+fn run_with_stub_systemctl(script: &str, dir: &std::path::Path) {
+    use std::os::unix::fs::PermissionsExt;
+    let bin = dir.join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let stub = bin.join("systemctl");
+    std::fs::write(&stub, "#!/bin/sh\nexit 0\n").unwrap();
+    std::fs::set_permissions(&stub, PermissionsExt::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let out = std::process::Command::new("bash")
+        .arg("-c")
+        .arg(script)
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+fn unit_files(dir: &std::path::Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_quadlet_selects_units_by_stack_label_not_prefix() {
+    let dir = tempfile::tempdir().unwrap();
+    let units = dir.path().join("units");
+    std::fs::create_dir_all(&units).unwrap();
+    two_stack_unit_dir(&units);
+    let dir_str = units.to_str().unwrap();
+
+    let listed = std::process::Command::new("bash")
+        .arg("-c")
+        .arg(quadlet::stack_units("web", dir_str))
+        .output()
+        .unwrap();
+    let mut listed: Vec<String> = String::from_utf8_lossy(&listed.stdout)
+        .lines()
+        .map(|l| l.rsplit('/').next().unwrap().to_string())
+        .collect();
+    listed.sort();
+    assert_eq!(
+        listed,
+        [
+            "web-api.container",
+            "web-db.container",
+            "web-front.network",
+            "web-web.container"
+        ]
+    );
+
+    // Pruning `web` down to one service leaves the `web-api` stack alone.
+    let spec = parse_stack("services:\n  db:\n    image: postgres:16\n").unwrap();
+    let services: Vec<_> = spec.services.iter().collect();
+    run_with_stub_systemctl(
+        &quadlet::apply_units(&spec, &services, "web", dir_str),
+        dir.path(),
+    );
+    let left = unit_files(&units);
+    assert!(left.contains(&"web-db.container".to_string()), "{left:?}");
+    assert!(!left.contains(&"web-web.container".to_string()), "{left:?}");
+    assert!(!left.contains(&"web-api.container".to_string()), "{left:?}");
+    assert!(!left.contains(&"web-front.network".to_string()), "{left:?}");
+    for unit in [
+        "web-api-api.container",
+        "web-api-db.container",
+        "web-api-web.container",
+        "web-api-front.network",
+    ] {
+        assert!(left.contains(&unit.to_string()), "{unit} pruned: {left:?}");
+    }
+
+    run_with_stub_systemctl(&quadlet::remove_by_label("web", dir_str), dir.path());
+    assert!(unit_files(&units).iter().all(|u| u.starts_with("web-api-")));
+    assert_eq!(unit_files(&units).len(), 4);
+}
+
+#[test]
+fn test_quadlet_absent_stops_in_reverse_order_and_keeps_volumes() {
+    let mut r = make_stack(Some("quadlet"));
+    r.state = Some("absent".to_string());
+    let script = apply_script(&r);
+    let web = pos(&script, "systemctl stop 'shop-web.service'");
+    let db = pos(&script, "systemctl stop 'shop-db.service'");
+    assert!(web < db);
+    assert!(script.contains("rm -f '/etc/containers/systemd/shop-db.container'"));
+    assert!(script.contains("podman network rm 'shop-back'"));
+    assert!(!script.contains("volume rm"));
+}
+
+#[test]
+fn test_quadlet_state_query_uses_podman() {
+    let script = state_query_script(&make_stack(Some("quadlet")));
+    assert!(script.contains("podman inspect -f"));
+}
+
+#[test]
+fn test_stack_scripts_pass_lint() {
+    for format in [None, Some("quadlet")] {
+        for state in [None, Some("absent")] {
+            let mut r = make_stack(format);
+            r.state = state.map(String::from);
+            let mut orphan = r.clone();
+            orphan.content = None;
+            for script in [
+                check_script(&r),
+                apply_script(&r),
+                state_query_script(&r),
+                apply_script(&orphan),
+            ] {
+                assert!(
+                    crate::core::purifier::validate_script(&script).is_ok(),
+                    "lint failed: {script}\n{:?}",
+                    crate::core::purifier::validate_script(&script)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_stack_yaml_parsing() {
+    let yaml = r#"
+type: container_stack
+name: shop
+format: quadlet
+content: |
+  services:
+    web:
+      image: nginx:1.27
+"#;
+    let r: Resource = serde_yaml_ng::from_str(yaml).unwrap();
+    assert_eq!(r.resource_type, ResourceType::ContainerStack);
+    assert_eq!(r.format.as_deref(), Some("quadlet"));
+    assert_eq!(
+        parse_stack(r.content.as_deref().unwrap())
+            .unwrap()
+            .services
+            .len(),
+        1
+    );
+}
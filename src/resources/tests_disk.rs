@@ -0,0 +1,142 @@
+//! Tests for the disk helpers, swap and partition resource handlers.
+
+use super::disk::*;
+use super::{partition, swap};
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+
+fn make(rtype: ResourceType) -> Resource {
+    Resource {
+        resource_type: rtype,
+        machine: MachineTarget::Single("m1".to_string()),
+        ..Default::default()
+    }
+}
+
+fn lint_ok(r: &Resource, scripts: [String; 3]) {
+    for script in scripts {
+        if let Err(e) = crate::core::purifier::validate_script(&script) {
+            panic!("lint failed for {:?}: {e}\n{script}", r.resource_type);
+        }
+    }
+}
+
+#[test]
+fn test_parse_size_bytes() {
+    assert_eq!(parse_size_bytes("512"), Some(512));
+    assert_eq!(parse_size_bytes("4K"), Some(4096));
+    assert_eq!(parse_size_bytes("512M"), Some(512 << 20));
+    assert_eq!(parse_size_bytes("2G"), Some(2 << 30));
+    assert_eq!(parse_size_bytes("1TiB"), Some(1 << 40));
+    assert_eq!(parse_size_bytes("4GB"), None);
+    assert_eq!(parse_size_bytes("100%FREE"), None);
+    assert_eq!(parse_size_bytes(""), None);
+}
+
+#[test]
+fn test_size_arg_and_partition_device() {
+    assert_eq!(size_arg("4GiB"), "4G");
+    assert_eq!(size_arg("512m"), "512M");
+    assert_eq!(partition_device("/dev/sdb", 2), "/dev/sdb2");
+    assert_eq!(partition_device("/dev/nvme0n1", 1), "/dev/nvme0n1p1");
+    assert_eq!(partition_device("/dev/loop7", 3), "/dev/loop7p3");
+}
+
+#[test]
+fn test_gpt_type_code() {
+    assert_eq!(gpt_type_code("efi").as_deref(), Some("ef00"));
+    assert_eq!(gpt_type_code("lvm").as_deref(), Some("8e00"));
+    assert_eq!(gpt_type_code("8E00").as_deref(), Some("8e00"));
+    assert_eq!(gpt_type_code("ntfs"), None);
+}
+
+#[test]
+fn test_swap_file_apply() {
+    let mut r = make(ResourceType::Swap);
+    r.size = Some("2GiB".to_string());
+    r.swappiness = Some(10);
+    let script = swap::apply_script(&r);
+    assert!(script.contains("stat -c %s '/swapfile')\" != '2147483648'"));
+    assert!(script.contains("fallocate -l '2G' '/swapfile'"));
+    assert!(script.contains("chmod 600 '/swapfile'"));
+    assert!(script.contains("echo '/swapfile none swap sw 0 0' >> /etc/fstab"));
+    assert!(script.contains("echo 'vm.swappiness = 10' > '/etc/sysctl.d/99-forjar-swap.conf'"));
+    assert!(!script.contains("blkid"));
+    lint_ok(
+        &r,
+        [swap::check_script(&r), script, swap::state_query_script(&r)],
+    );
+}
+
+#[test]
+fn test_swap_device_never_clobbers_signature() {
+    let mut r = make(ResourceType::Swap);
+    r.path = Some("/dev/vdb2".to_string());
+    let script = swap::apply_script(&r);
+    assert!(script.contains("refusing to mkswap without force"));
+    assert!(script.contains("if [ \"$SIG\" != 'swap' ]; then mkswap '/dev/vdb2'; fi"));
+    assert!(!script.contains("fallocate"));
+    r.force = true;
+    assert!(!swap::apply_script(&r).contains("refusing"));
+}
+
+#[test]
+fn test_swap_absent() {
+    let mut r = make(ResourceType::Swap);
+    r.state = Some("absent".to_string());
+    let script = swap::apply_script(&r);
+    assert!(script.contains("swapoff '/swapfile'"));
+    assert!(script.contains("rm -f '/swapfile'"));
+    r.path = Some("/dev/vdb2".to_string());
+    assert!(!swap::apply_script(&r).contains("rm -f"));
+}
+
+fn make_partition() -> Resource {
+    let mut r = make(ResourceType::Partition);
+    r.device = Some("/dev/nvme1n1".to_string());
+    r.number = Some(2);
+    r.size = Some("20G".to_string());
+    r.part_type = Some("lvm".to_string());
+    r.name = Some("data".to_string());
+    r
+}
+
+#[test]
+fn test_partition_apply_creates_gpt_and_partition() {
+    let r = make_partition();
+    let script = partition::apply_script(&r);
+    assert!(script.contains("refusing to relabel without force"));
+    assert!(script.contains("sgdisk -o '/dev/nvme1n1'"));
+    assert!(script.contains("sgdisk -n 2:0:+20G -t 2:8e00 -c 2:'data' '/dev/nvme1n1'"));
+    assert!(script.contains("echo 'unchanged:/dev/nvme1n1p2'"));
+    lint_ok(
+        &r,
+        [
+            partition::check_script(&r),
+            script,
+            partition::state_query_script(&r),
+        ],
+    );
+}
+
+#[test]
+fn test_partition_rest_of_disk_and_force() {
+    let mut r = make_partition();
+    r.size = None;
+    r.part_type = None;
+    r.force = true;
+    let script = partition::apply_script(&r);
+    assert!(script.contains("sgdisk -n 2:0:0 -t 2:8300"));
+    assert!(script.contains("sgdisk -Z '/dev/nvme1n1'"));
+    assert!(!script.contains("refusing"));
+}
+
+#[test]
+fn test_partition_absent_guards_signature() {
+    let mut r = make_partition();
+    r.state = Some("absent".to_string());
+    let script = partition::apply_script(&r);
+    assert!(script.contains("blkid -p -o value -s TYPE '/dev/nvme1n1p2'"));
+    assert!(script.contains("sgdisk -d 2 '/dev/nvme1n1'"));
+    r.force = true;
+    assert!(!partition::apply_script(&r).contains("refusing"));
+}
@@ -0,0 +1,196 @@
+//! Tests for the LVM and filesystem resource handlers.
+
+use super::{filesystem, lvm_lv, lvm_vg, partition};
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+
+fn make(rtype: ResourceType) -> Resource {
+    Resource {
+        resource_type: rtype,
+        machine: MachineTarget::Single("m1".to_string()),
+        ..Default::default()
+    }
+}
+
+fn make_vg() -> Resource {
+    let mut r = make(ResourceType::LvmVg);
+    r.name = Some("data".to_string());
+    r.physical_volumes = vec!["/dev/sdb1".to_string(), "/dev/sdc1".to_string()];
+    r
+}
+
+fn make_lv(size: &str) -> Resource {
+    let mut r = make(ResourceType::LvmLv);
+    r.name = Some("pg".to_string());
+    r.volume_group = Some("data".to_string());
+    r.size = Some(size.to_string());
+    r
+}
+
+fn make_fs() -> Resource {
+    let mut r = make(ResourceType::Filesystem);
+    r.device = Some("/dev/data/pg".to_string());
+    r.fs_type = Some("xfs".to_string());
+    r
+}
+
+#[test]
+fn test_lvm_vg_apply() {
+    let r = make_vg();
+    let script = lvm_vg::apply_script(&r);
+    assert!(script.contains("for pv in '/dev/sdb1' '/dev/sdc1'; do"));
+    assert!(script.contains("refusing to pvcreate without force"));
+    assert!(script.contains("    pvcreate \"$pv\""));
+    assert!(script.contains("vgcreate 'data' '/dev/sdb1' '/dev/sdc1'"));
+    assert!(script.contains("vgextend 'data' \"$pv\""));
+    assert!(!script.contains("vgreduce"));
+}
+
+#[test]
+fn test_lvm_vg_absent_requires_empty_group() {
+    let mut r = make_vg();
+    r.state = Some("absent".to_string());
+    let script = lvm_vg::apply_script(&r);
+    assert!(script.contains("still holds logical volumes"));
+    assert!(script.contains("vgremove -f 'data'"));
+    r.force = true;
+    assert!(!lvm_vg::apply_script(&r).contains("still holds"));
+}
+
+#[test]
+fn test_lvm_lv_create_and_grow() {
+    let r = make_lv("20GiB");
+    let script = lvm_lv::apply_script(&r);
+    assert!(script.contains("lvcreate -y -n 'pg' -L '20G' 'data'"));
+    assert!(script.contains("if [ \"$CUR\" -lt '21474836480' ]; then"));
+    assert!(script.contains("lvextend -L '20G' 'data/pg'"));
+    assert!(script.contains("volumes are never shrunk"));
+    assert!(!script.contains("lvreduce"));
+}
+
+#[test]
+fn test_lvm_lv_extent_size() {
+    assert!(lvm_lv::is_extent_size("100%FREE"));
+    assert!(!lvm_lv::is_extent_size("10G"));
+    let script = lvm_lv::apply_script(&make_lv("100%FREE"));
+    assert!(script.contains("lvcreate -y -n 'pg' -l '100%FREE' 'data'"));
+    assert!(!script.contains("lvextend"));
+}
+
+#[test]
+fn test_filesystem_never_reformats() {
+    let mut r = make_fs();
+    r.options = Some("-L pgdata".to_string());
+    let script = filesystem::apply_script(&r);
+    let unchanged = script
+        .find("if [ \"$SIG\" = 'xfs' ]; then echo 'unchanged:/dev/data/pg'; exit 0; fi")
+        .unwrap();
+    let refuse = script
+        .find("refusing to reformat as xfs without force")
+        .unwrap();
+    let mkfs = script
+        .find("mkfs -t 'xfs' -L pgdata '/dev/data/pg'")
+        .unwrap();
+    assert!(unchanged < refuse && refuse < mkfs);
+    assert!(!script.contains("wipefs"));
+}
+
+#[test]
+fn test_filesystem_force_wipes_first() {
+    let mut r = make_fs();
+    r.force = true;
+    let script = filesystem::apply_script(&r);
+    assert!(!script.contains("refusing"));
+    assert!(script.find("wipefs -a").unwrap() < script.find("mkfs -t").unwrap());
+}
+
+#[test]
+fn test_filesystem_absent_requires_force() {
+    let mut r = make_fs();
+    r.state = Some("absent".to_string());
+    let script = filesystem::apply_script(&r);
+    assert!(script.contains("refusing to wipe"));
+    assert!(!script.contains("wipefs"));
+    r.force = true;
+    assert!(filesystem::apply_script(&r).contains("wipefs -a '/dev/data/pg'"));
+}
+
+#[test]
+fn test_disk_scripts_pass_lint() {
+    let mut absent_vg = make_vg();
+    absent_vg.state = Some("absent".to_string());
+    let vgs = [make_vg(), absent_vg];
+    for r in &vgs {
+        for script in [
+            lvm_vg::check_script(r),
+            lvm_vg::apply_script(r),
+            lvm_vg::state_query_script(r),
+        ] {
+            if let Err(e) = crate::core::purifier::validate_script(&script) {
+                panic!("lint failed: {e}\n{script}");
+            }
+        }
+    }
+    for r in [make_lv("10G"), make_lv("50%VG")] {
+        for script in [
+            lvm_lv::check_script(&r),
+            lvm_lv::apply_script(&r),
+            lvm_lv::state_query_script(&r),
+        ] {
+            if let Err(e) = crate::core::purifier::validate_script(&script) {
+                panic!("lint failed: {e}\n{script}");
+            }
+        }
+    }
+    let fs = make_fs();
+    for script in [
+        filesystem::check_script(&fs),
+        filesystem::apply_script(&fs),
+        filesystem::state_query_script(&fs),
+    ] {
+        if let Err(e) = crate::core::purifier::validate_script(&script) {
+            panic!("lint failed: {e}\n{script}");
+        }
+    }
+}
+
+/// Partition, format and reformat-guard a loop device end to end.
+#[test]
+#[ignore] // requires root, sgdisk and loop devices
+fn test_disk_loop_device_roundtrip() {
+    use std::process::Command;
+    let sh = |script: String| {
+        Command::new("bash")
+            .arg("-c")
+            .arg(script)
+            .output()
+            .expect("bash")
+    };
+    let img = std::env::temp_dir().join("forjar-disk-test.img");
+    let setup = sh(format!(
+        "truncate -s 64M '{0}' && losetup --find --show --partscan '{0}'",
+        img.display()
+    ));
+    assert!(setup.status.success());
+    let loopdev = String::from_utf8_lossy(&setup.stdout).trim().to_string();
+
+    let mut part = make(ResourceType::Partition);
+    part.device = Some(loopdev.clone());
+    part.number = Some(1);
+    part.size = Some("32M".to_string());
+    assert!(sh(partition::apply_script(&part)).status.success());
+    assert!(
+        String::from_utf8_lossy(&sh(partition::check_script(&part)).stdout).contains("exists:")
+    );
+
+    let mut fs = make(ResourceType::Filesystem);
+    fs.device = Some(format!("{loopdev}p1"));
+    fs.fs_type = Some("ext4".to_string());
+    assert!(sh(filesystem::apply_script(&fs)).status.success());
+    // Second run is a no-op, a different fs_type is refused
+    let again = sh(filesystem::apply_script(&fs));
+    assert!(String::from_utf8_lossy(&again.stdout).contains("unchanged:"));
+    fs.fs_type = Some("xfs".to_string());
+    assert!(!sh(filesystem::apply_script(&fs)).status.success());
+
+    sh(format!("losetup -d '{loopdev}'; rm -f '{}'", img.display()));
+}
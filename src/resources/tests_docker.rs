@@ -98,6 +98,83 @@ fn make_docker_resource(name: &str, image: &str) -> Resource {
         asset_pattern: None,
         binary: None,
         install_dir: None,
+        runtime: None,
+        pull_policy: None,
+        image_digest: None,
+        labels: vec![],
+        user: None,
+        entrypoint: None,
+        cpus: None,
+        networks: vec![],
+        driver: None,
+        subnet: None,
+        gateway: None,
+        url: None,
+        host: None,
+        expect_status: None,
+        pattern: None,
+        interval: None,
+        backoff: None,
+        creates: None,
+        unless: None,
+        onlyif: None,
+        refreshonly: false,
+        returns: vec![],
+        device: None,
+        size: None,
+        number: None,
+        part_type: None,
+        physical_volumes: vec![],
+        volume_group: None,
+        swappiness: None,
+        force: false,
+        interface: None,
+        addresses: vec![],
+        public_key: None,
+        endpoint: None,
+        allowed_ips: vec![],
+        persistent_keepalive: None,
+        kind: None,
+        members: vec![],
+        vlan_id: None,
+        bond_mode: None,
+        mtu: None,
+        dhcp: None,
+        dns: vec![],
+        routes: vec![],
+        revert_after: None,
+        issuer: None,
+        ca_name: None,
+        ca_cert: None,
+        ca_key: None,
+        common_name: None,
+        sans: vec![],
+        validity_days: None,
+        renew_before: None,
+        key_type: None,
+        key_path: None,
+        chain_path: None,
+        hostname: None,
+        timezone: None,
+        locale: None,
+        keyboard: None,
+        ntp_servers: vec![],
+        vdevs: vec![],
+        properties: Default::default(),
+        dataset: None,
+        keep: None,
+        recursive: false,
+        mig_profiles: vec![],
+        power_limit_watts: None,
+        container_toolkit: false,
+        checksum_file: None,
+        signature: None,
+        password: None,
+        attributes: Vec::new(),
+        database: None,
+        encoding: None,
+        grants: Vec::new(),
+        selinux_type: None,
     }
 }
 
@@ -177,7 +254,7 @@ fn test_fj030_apply_stopped() {
 fn test_fj030_state_query() {
     let r = make_docker_resource("web", "nginx:latest");
     let script = state_query_script(&r);
-    assert!(script.contains("docker inspect 'web'"));
+    assert!(script.contains("docker inspect -f"));
     assert!(script.contains("container=MISSING:web"));
 }
 
@@ -264,7 +341,7 @@ fn test_fj030_no_name_defaults_to_unknown() {
     let apply = apply_script(&r);
     assert!(apply.contains("--name 'unknown'"));
     let query = state_query_script(&r);
-    assert!(query.contains("docker inspect 'unknown'"));
+    assert!(query.contains("}}' 'unknown'"));
 }
 
 #[test]
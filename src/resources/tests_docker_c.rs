@@ -0,0 +1,251 @@
+//! Docker parity: config-hash recreate, pull policy, digests, limits, podman.
+
+use super::docker::*;
+use crate::core::types::{HealthCheck, MachineTarget, Resource, ResourceType};
+
+fn make_docker(name: &str, image: &str) -> Resource {
+    Resource {
+        resource_type: ResourceType::Docker,
+        machine: MachineTarget::Single("m1".to_string()),
+        name: Some(name.to_string()),
+        image: Some(image.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_config_hash_changes_with_env() {
+    let a = make_docker("web", "nginx:1.25");
+    let mut b = a.clone();
+    b.environment = vec!["MODE=prod".to_string()];
+    assert_ne!(config_hash(&a), config_hash(&b));
+}
+
+#[test]
+fn test_config_hash_changes_with_image_tag() {
+    let a = make_docker("web", "nginx:1.25");
+    let b = make_docker("web", "nginx:1.26");
+    assert_ne!(config_hash(&a), config_hash(&b));
+}
+
+#[test]
+fn test_config_hash_deterministic() {
+    let r = make_docker("web", "nginx:1.25");
+    assert_eq!(config_hash(&r), config_hash(&r));
+    assert!(config_hash(&r).starts_with("blake3:"));
+}
+
+#[test]
+fn test_config_hash_list_boundaries() {
+    // Moving an entry between lists must not collide
+    let mut a = make_docker("web", "nginx");
+    a.ports = vec!["80:80".to_string()];
+    let mut b = make_docker("web", "nginx");
+    b.environment = vec!["80:80".to_string()];
+    assert_ne!(config_hash(&a), config_hash(&b));
+}
+
+#[test]
+fn test_config_hash_changes_with_health_retries() {
+    let mut a = make_docker("db", "postgres:16");
+    a.health_check = Some(HealthCheck {
+        command: "pg_isready".to_string(),
+        interval: None,
+        timeout: None,
+        retries: Some(3),
+    });
+    let mut b = a.clone();
+    b.health_check.as_mut().unwrap().retries = Some(5);
+    assert_ne!(config_hash(&a), config_hash(&b));
+}
+
+#[test]
+fn test_apply_labels_container_with_config_hash() {
+    let r = make_docker("web", "nginx:1.25");
+    let script = apply_script(&r);
+    let hash = config_hash(&r);
+    assert!(script.contains(&format!("--label '{CONFIG_HASH_LABEL}={hash}'")));
+    assert!(script.contains(&format!("WANT_HASH='{hash}'")));
+    assert!(script.contains("echo 'unchanged:web'"));
+}
+
+#[test]
+fn test_apply_skip_guard_before_recreate() {
+    let script = apply_script(&make_docker("web", "nginx"));
+    let guard = script.find("if [ \"$HAVE_HASH\"").unwrap();
+    let stop = script.find("docker stop 'web'").unwrap();
+    let run = script.find("docker run -d").unwrap();
+    assert!(guard < stop && stop < run);
+    assert!(script.trim_end().ends_with("fi"));
+}
+
+#[test]
+fn test_apply_compares_image_id() {
+    let script = apply_script(&make_docker("web", "nginx"));
+    assert!(script.contains("HAVE_IMAGE="));
+    assert!(script.contains("docker image inspect -f '{{.Id}}' 'nginx'"));
+}
+
+#[test]
+fn test_pull_policy_default_always() {
+    let script = apply_script(&make_docker("web", "nginx"));
+    assert!(script.contains("docker pull 'nginx'"));
+}
+
+#[test]
+fn test_pull_policy_missing() {
+    let mut r = make_docker("web", "nginx");
+    r.pull_policy = Some("missing".to_string());
+    let script = apply_script(&r);
+    assert!(script.contains("docker image inspect 'nginx' >/dev/null 2>&1 || docker pull 'nginx'"));
+}
+
+#[test]
+fn test_pull_policy_never() {
+    let mut r = make_docker("web", "nginx");
+    r.pull_policy = Some("never".to_string());
+    let script = apply_script(&r);
+    assert!(!script.contains("docker pull"));
+    assert!(script.contains("pull_policy=never"));
+    assert!(script.contains("exit 1"));
+}
+
+#[test]
+fn test_image_digest_pinning() {
+    let mut r = make_docker("web", "nginx:1.25");
+    r.image_digest = Some(format!("sha256:{}", "a".repeat(64)));
+    let expected = format!("nginx:1.25@sha256:{}", "a".repeat(64));
+    assert_eq!(image_ref(&r), expected);
+    let script = apply_script(&r);
+    assert!(script.contains(&format!("docker pull '{expected}'")));
+    assert!(script.contains(&format!("'{expected}'")));
+}
+
+#[test]
+fn test_image_digest_already_in_image() {
+    let mut r = make_docker("web", "nginx@sha256:abc");
+    r.image_digest = Some("sha256:def".to_string());
+    assert_eq!(image_ref(&r), "nginx@sha256:abc");
+}
+
+#[test]
+fn test_apply_user_entrypoint_labels() {
+    let mut r = make_docker("app", "myapp:v1");
+    r.user = Some("1000:1000".to_string());
+    r.entrypoint = Some("/bin/sh".to_string());
+    r.labels = vec!["team=infra".to_string()];
+    r.command = Some("-c 'sleep 1'".to_string());
+    let script = apply_script(&r);
+    assert!(script.contains("--user '1000:1000'"));
+    assert!(script.contains("--entrypoint '/bin/sh'"));
+    assert!(script.contains("--label 'team=infra'"));
+    let img = script.find("'myapp:v1' \\").unwrap();
+    assert!(script[img..].contains("-c 'sleep 1'"));
+}
+
+#[test]
+fn test_apply_resource_limits() {
+    let mut r = make_docker("app", "myapp:v1");
+    r.cpus = Some("1.5".to_string());
+    r.cpuset = Some("0-1".to_string());
+    r.memory_limit = Some(536_870_912);
+    let script = apply_script(&r);
+    assert!(script.contains("--cpus '1.5'"));
+    assert!(script.contains("--cpuset-cpus '0-1'"));
+    assert!(script.contains("--memory '536870912'"));
+}
+
+#[test]
+fn test_apply_healthcheck() {
+    let mut r = make_docker("db", "postgres:16");
+    r.health_check = Some(HealthCheck {
+        command: "pg_isready -U postgres".to_string(),
+        interval: Some("10s".to_string()),
+        timeout: Some("3s".to_string()),
+        retries: Some(5),
+    });
+    let script = apply_script(&r);
+    assert!(script.contains("--health-cmd 'pg_isready -U postgres'"));
+    assert!(script.contains("--health-interval '10s'"));
+    assert!(script.contains("--health-timeout '3s'"));
+    assert!(script.contains("--health-retries '5'"));
+}
+
+#[test]
+fn test_apply_networks() {
+    let mut r = make_docker("app", "myapp:v1");
+    r.networks = vec!["backend".to_string(), "frontend".to_string()];
+    let script = apply_script(&r);
+    assert!(script.contains("--network 'backend'"));
+    assert!(script.contains("docker network connect 'frontend' 'app'"));
+    assert!(!script.contains("docker network connect 'backend'"));
+}
+
+#[test]
+fn test_podman_runtime() {
+    let mut r = make_docker("web", "nginx");
+    r.runtime = Some("podman".to_string());
+    assert_eq!(runtime_bin(&r), "podman");
+    let apply = apply_script(&r);
+    assert!(apply.contains("podman pull 'nginx'"));
+    assert!(apply.contains("podman run -d"));
+    assert!(!apply.contains("docker "));
+    assert!(check_script(&r).starts_with("podman inspect"));
+    assert!(state_query_script(&r).starts_with("podman inspect"));
+}
+
+#[test]
+fn test_podman_absent_and_stopped() {
+    let mut r = make_docker("web", "nginx");
+    r.runtime = Some("podman".to_string());
+    r.state = Some("absent".to_string());
+    assert!(apply_script(&r).contains("podman rm 'web'"));
+    r.state = Some("stopped".to_string());
+    assert!(apply_script(&r).contains("podman stop 'web'"));
+}
+
+#[test]
+fn test_state_query_reports_hash_and_image() {
+    let script = state_query_script(&make_docker("web", "nginx"));
+    assert!(script.contains("running={{.State.Running}}"));
+    assert!(script.contains("image={{.Image}}"));
+    assert!(script.contains(CONFIG_HASH_LABEL));
+}
+
+#[test]
+fn test_docker_scripts_pass_lint() {
+    let mut r = make_docker("web", "nginx");
+    r.networks = vec!["a".to_string(), "b".to_string()];
+    r.pull_policy = Some("never".to_string());
+    for script in [apply_script(&r), check_script(&r), state_query_script(&r)] {
+        assert!(
+            crate::core::purifier::validate_script(&script).is_ok(),
+            "lint failed: {script}"
+        );
+    }
+}
+
+#[test]
+fn test_docker_parity_yaml_parsing() {
+    let yaml = r#"
+type: docker
+name: api
+image: registry.local/api:2.1
+runtime: podman
+pull_policy: missing
+image_digest: "sha256:0000000000000000000000000000000000000000000000000000000000000000"
+user: "1000"
+entrypoint: /entry.sh
+cpus: "2"
+labels: ["tier=api"]
+networks: [backend]
+health_check:
+  command: curl -fsS localhost:8080/health
+  interval: 30s
+"#;
+    let r: Resource = serde_yaml_ng::from_str(yaml).unwrap();
+    assert_eq!(r.runtime.as_deref(), Some("podman"));
+    assert_eq!(r.pull_policy.as_deref(), Some("missing"));
+    assert_eq!(r.networks, vec!["backend"]);
+    assert_eq!(r.health_check.unwrap().interval.as_deref(), Some("30s"));
+}
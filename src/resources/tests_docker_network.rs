@@ -0,0 +1,158 @@
+//! Tests for docker_network and docker_volume resource handlers.
+
+use super::{docker_network, docker_volume};
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+
+fn make_network(name: &str) -> Resource {
+    Resource {
+        resource_type: ResourceType::DockerNetwork,
+        machine: MachineTarget::Single("m1".to_string()),
+        name: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+fn make_volume(name: &str) -> Resource {
+    Resource {
+        resource_type: ResourceType::DockerVolume,
+        machine: MachineTarget::Single("m1".to_string()),
+        name: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_network_check() {
+    let script = docker_network::check_script(&make_network("backend"));
+    assert!(script.contains("docker network inspect 'backend'"));
+    assert!(script.contains("exists:backend"));
+    assert!(script.contains("missing:backend"));
+}
+
+#[test]
+fn test_network_create_defaults_to_bridge() {
+    let script = docker_network::apply_script(&make_network("backend"));
+    assert!(script.contains("docker network create --driver 'bridge'"));
+    assert!(script.contains("forjar.config-hash="));
+    assert!(script.contains("'backend'"));
+}
+
+#[test]
+fn test_network_create_with_subnet_and_options() {
+    let mut r = make_network("lan");
+    r.driver = Some("macvlan".to_string());
+    r.subnet = Some("10.10.0.0/24".to_string());
+    r.gateway = Some("10.10.0.1".to_string());
+    r.options = Some("parent=eth0, mtu=9000".to_string());
+    r.labels = vec!["site=lab".to_string()];
+    let script = docker_network::apply_script(&r);
+    assert!(script.contains("--driver 'macvlan'"));
+    assert!(script.contains("--subnet '10.10.0.0/24'"));
+    assert!(script.contains("--gateway '10.10.0.1'"));
+    assert!(script.contains("--opt 'parent=eth0'"));
+    assert!(script.contains("--opt 'mtu=9000'"));
+    assert!(script.contains("--label 'site=lab'"));
+}
+
+#[test]
+fn test_network_recreated_on_hash_mismatch() {
+    let r = make_network("backend");
+    let script = docker_network::apply_script(&r);
+    let guard = script
+        .find("if [ \"$HAVE_HASH\" = \"$WANT_HASH\" ]")
+        .unwrap();
+    let rm = script.find("docker network rm 'backend'").unwrap();
+    let create = script.find("docker network create").unwrap();
+    assert!(guard < rm && rm < create);
+}
+
+#[test]
+fn test_network_hash_tracks_subnet() {
+    let a = make_network("n");
+    let mut b = make_network("n");
+    b.subnet = Some("172.30.0.0/16".to_string());
+    assert_ne!(
+        docker_network::config_hash(&a),
+        docker_network::config_hash(&b)
+    );
+}
+
+#[test]
+fn test_network_absent() {
+    let mut r = make_network("old");
+    r.state = Some("absent".to_string());
+    let script = docker_network::apply_script(&r);
+    assert!(script.contains("docker network rm 'old'"));
+    assert!(!script.contains("network create"));
+}
+
+#[test]
+fn test_network_podman_state_query() {
+    let mut r = make_network("backend");
+    r.runtime = Some("podman".to_string());
+    let script = docker_network::state_query_script(&r);
+    assert!(script.starts_with("podman network inspect"));
+    assert!(script.contains("network=MISSING:backend"));
+}
+
+#[test]
+fn test_volume_create() {
+    let mut r = make_volume("pgdata");
+    r.labels = vec!["backup=daily".to_string()];
+    r.options = Some("type=nfs,o=addr=10.0.0.2".to_string());
+    let script = docker_volume::apply_script(&r);
+    assert!(script.contains("docker volume create --driver 'local'"));
+    assert!(script.contains("--opt 'type=nfs'"));
+    assert!(script.contains("--label 'backup=daily'"));
+    assert!(script.contains("'pgdata'"));
+}
+
+#[test]
+fn test_volume_never_recreated() {
+    let script = docker_volume::apply_script(&make_volume("pgdata"));
+    assert!(!script.contains("volume rm"));
+    assert!(script.contains("FORJAR_WARN: volume pgdata spec changed"));
+}
+
+#[test]
+fn test_volume_absent_and_query() {
+    let mut r = make_volume("pgdata");
+    r.state = Some("absent".to_string());
+    assert!(docker_volume::apply_script(&r).contains("docker volume rm 'pgdata'"));
+    let query = docker_volume::state_query_script(&r);
+    assert!(query.contains("mountpoint={{.Mountpoint}}"));
+    assert!(query.contains("volume=MISSING:pgdata"));
+    assert!(docker_volume::check_script(&r).contains("exists:pgdata"));
+}
+
+#[test]
+fn test_network_volume_scripts_pass_lint() {
+    let mut net = make_network("n");
+    net.subnet = Some("10.0.0.0/24".to_string());
+    let vol = make_volume("v");
+    for script in [
+        docker_network::apply_script(&net),
+        docker_network::state_query_script(&net),
+        docker_volume::apply_script(&vol),
+        docker_volume::state_query_script(&vol),
+    ] {
+        assert!(
+            crate::core::purifier::validate_script(&script).is_ok(),
+            "lint failed: {script}"
+        );
+    }
+}
+
+#[test]
+fn test_volume_destroy_irreversible() {
+    use crate::core::planner::reversibility::{classify, Reversibility};
+    use crate::core::types::PlanAction;
+    assert_eq!(
+        classify(&make_volume("v"), &PlanAction::Destroy),
+        Reversibility::Irreversible
+    );
+    assert_eq!(
+        classify(&make_network("n"), &PlanAction::Destroy),
+        Reversibility::Reversible
+    );
+}
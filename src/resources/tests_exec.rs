@@ -0,0 +1,159 @@
+//! Tests for the exec resource handler.
+
+use super::exec::*;
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+
+fn make_exec(command: &str) -> Resource {
+    Resource {
+        resource_type: ResourceType::Exec,
+        machine: MachineTarget::Single("m1".to_string()),
+        name: Some("migrate".to_string()),
+        command: Some(command.to_string()),
+        ..Default::default()
+    }
+}
+
+fn pos(script: &str, needle: &str) -> usize {
+    script
+        .find(needle)
+        .unwrap_or_else(|| panic!("missing '{needle}' in:\n{script}"))
+}
+
+#[test]
+fn test_exec_plain_command() {
+    let script = apply_script(&make_exec("/opt/app/bin/migrate --all"));
+    assert!(script
+        .contains("FORJAR_CMD=$(cat <<'FORJAR_EXEC'\n/opt/app/bin/migrate --all\nFORJAR_EXEC\n)"));
+    assert!(script.contains("forjar_run() { sh -c \"$1\"; }"));
+    assert!(script.contains("forjar_run \"$FORJAR_CMD\""));
+    assert!(script.contains("  0) echo \"ran:migrate rc=$RC\" ;;"));
+    assert!(script.contains("exited $RC (accepted: 0)"));
+    assert!(!script.contains("skip:"));
+}
+
+#[test]
+fn test_exec_creates_guard() {
+    let mut r = make_exec("tar xf /tmp/app.tar -C /opt/app");
+    r.creates = Some("/opt/app/bin/app".to_string());
+    let script = apply_script(&r);
+    let guard = pos(
+        &script,
+        "if [ -e '/opt/app/bin/app' ]; then echo 'skip:migrate (creates)'; exit 0; fi",
+    );
+    assert!(guard < pos(&script, "forjar_run \"$FORJAR_CMD\""));
+}
+
+#[test]
+fn test_exec_unless_and_onlyif_guards() {
+    let mut r = make_exec("psql -c 'CREATE DATABASE app'");
+    r.unless = Some("psql -lqt | grep -qw app".to_string());
+    r.onlyif = Some("systemctl is-active postgresql".to_string());
+    let script = apply_script(&r);
+    assert!(script
+        .contains("FORJAR_UNLESS=$(cat <<'FORJAR_EXEC'\npsql -lqt | grep -qw app\nFORJAR_EXEC\n)"));
+    assert!(script.contains(
+        "if forjar_run \"$FORJAR_UNLESS\" >/dev/null 2>&1; then echo 'skip:migrate (unless)'; exit 0; fi"
+    ));
+    assert!(script.contains(
+        "if ! forjar_run \"$FORJAR_ONLYIF\" >/dev/null 2>&1; then echo 'skip:migrate (onlyif)'; exit 0; fi"
+    ));
+    // Single quotes in the command survive the heredoc untouched
+    assert!(script.contains("psql -c 'CREATE DATABASE app'\nFORJAR_EXEC"));
+}
+
+#[test]
+fn test_exec_cwd_env_user() {
+    let mut r = make_exec("make install");
+    r.working_dir = Some("/srv/src".to_string());
+    r.environment = vec!["PREFIX=/usr/local".to_string()];
+    r.user = Some("build".to_string());
+    let script = apply_script(&r);
+    let cd = pos(&script, "cd '/srv/src'");
+    let env = pos(&script, "export 'PREFIX=/usr/local'");
+    assert!(cd < env && env < pos(&script, "FORJAR_CMD="));
+    assert!(script.contains("forjar_run() { runuser -u 'build' -- sh -c \"$1\"; }"));
+}
+
+#[test]
+fn test_exec_returns_codes() {
+    let mut r = make_exec("apt-get update");
+    r.returns = vec![0, 100];
+    assert_eq!(accepted_codes(&r), vec![0, 100]);
+    let script = apply_script(&r);
+    assert!(script.contains("  0|100) echo"));
+    assert!(script.contains("(accepted: 0,100)"));
+    assert_eq!(accepted_codes(&make_exec("true")), vec![0]);
+}
+
+#[test]
+fn test_exec_check_script() {
+    assert_eq!(check_script(&make_exec("true")), "echo 'missing:migrate'");
+    let mut r = make_exec("true");
+    r.creates = Some("/opt/done".to_string());
+    r.unless = Some("test -f /opt/skip".to_string());
+    let script = check_script(&r);
+    assert!(script.contains("if { [ -e '/opt/done' ]; } || { forjar_run \"$FORJAR_UNLESS\""));
+    assert!(script.contains("exists:migrate"));
+}
+
+#[test]
+fn test_exec_absent_and_missing_command() {
+    let mut r = make_exec("true");
+    r.state = Some("absent".to_string());
+    assert_eq!(apply_script(&r), "echo 'skip:migrate'");
+    r.state = None;
+    r.command = None;
+    assert!(apply_script(&r).contains("has no command' >&2; exit 1"));
+}
+
+#[test]
+fn test_exec_state_query() {
+    assert_eq!(
+        state_query_script(&make_exec("true")),
+        "echo 'exec=migrate'"
+    );
+    let mut r = make_exec("true");
+    r.creates = Some("/opt/done".to_string());
+    let query = state_query_script(&r);
+    assert!(query.contains("creates=present"));
+    assert!(query.contains("creates=absent"));
+}
+
+#[test]
+fn test_exec_scripts_pass_lint() {
+    let mut guarded = make_exec("/opt/app/bin/migrate");
+    guarded.creates = Some("/opt/app/.migrated".to_string());
+    guarded.unless = Some("test -f /opt/app/.skip".to_string());
+    guarded.onlyif = Some("true".to_string());
+    guarded.working_dir = Some("/opt/app".to_string());
+    guarded.environment = vec!["RAILS_ENV=production".to_string()];
+    guarded.user = Some("app".to_string());
+    guarded.returns = vec![0, 3];
+    for r in [make_exec("echo hi"), guarded] {
+        for script in [check_script(&r), apply_script(&r), state_query_script(&r)] {
+            assert!(
+                crate::core::purifier::validate_script(&script).is_ok(),
+                "lint failed: {script}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_exec_yaml_parsing_cwd_alias() {
+    let yaml = r#"
+type: exec
+machine: m1
+command: ./configure && make
+cwd: /srv/src
+creates: /srv/src/build/app
+refreshonly: true
+triggers: [src-checkout]
+returns: [0, 2]
+"#;
+    let r: Resource = serde_yaml_ng::from_str(yaml).unwrap();
+    assert_eq!(r.resource_type, ResourceType::Exec);
+    assert_eq!(r.working_dir.as_deref(), Some("/srv/src"));
+    assert!(r.refreshonly);
+    assert_eq!(r.returns, vec![0, 2]);
+}
@@ -101,6 +101,83 @@ fn make_gpu_resource(name: &str) -> Resource {
         asset_pattern: None,
         binary: None,
         install_dir: None,
+        runtime: None,
+        pull_policy: None,
+        image_digest: None,
+        labels: vec![],
+        user: None,
+        entrypoint: None,
+        cpus: None,
+        networks: vec![],
+        driver: None,
+        subnet: None,
+        gateway: None,
+        url: None,
+        host: None,
+        expect_status: None,
+        pattern: None,
+        interval: None,
+        backoff: None,
+        creates: None,
+        unless: None,
+        onlyif: None,
+        refreshonly: false,
+        returns: vec![],
+        device: None,
+        size: None,
+        number: None,
+        part_type: None,
+        physical_volumes: vec![],
+        volume_group: None,
+        swappiness: None,
+        force: false,
+        interface: None,
+        addresses: vec![],
+        public_key: None,
+        endpoint: None,
+        allowed_ips: vec![],
+        persistent_keepalive: None,
+        kind: None,
+        members: vec![],
+        vlan_id: None,
+        bond_mode: None,
+        mtu: None,
+        dhcp: None,
+        dns: vec![],
+        routes: vec![],
+        revert_after: None,
+        issuer: None,
+        ca_name: None,
+        ca_cert: None,
+        ca_key: None,
+        common_name: None,
+        sans: vec![],
+        validity_days: None,
+        renew_before: None,
+        key_type: None,
+        key_path: None,
+        chain_path: None,
+        hostname: None,
+        timezone: None,
+        locale: None,
+        keyboard: None,
+        ntp_servers: vec![],
+        vdevs: vec![],
+        properties: Default::default(),
+        dataset: None,
+        keep: None,
+        recursive: false,
+        mig_profiles: vec![],
+        power_limit_watts: None,
+        container_toolkit: false,
+        checksum_file: None,
+        signature: None,
+        password: None,
+        attributes: Vec::new(),
+        database: None,
+        encoding: None,
+        grants: Vec::new(),
+        selinux_type: None,
     }
 }
 
@@ -109,7 +186,9 @@ fn test_fj241_check_gpu_present() {
     let r = make_gpu_resource("gpu0");
     let script = check_script(&r);
     assert!(script.contains("nvidia-smi"));
-    // FJ-1009: check reports match when nvidia-smi works, regardless of version
+    // FJ-1009: a working nvidia-smi only matches at the declared version
+    assert!(script.contains("'535'|'535'.*) ;;"));
+    assert!(script.contains("mismatch:gpu0"));
     assert!(script.contains("match:gpu0"));
 }
 
@@ -126,8 +205,8 @@ fn test_fj241_apply_gpu_install() {
     let r = make_gpu_resource("gpu0");
     let script = apply_script(&r);
     assert!(script.contains("set -euo pipefail"));
-    // FJ-1009: when nvidia-smi works, prints NOTICE instead of apt-get install
-    assert!(script.contains("NOTICE: requested driver 535"));
+    // FJ-1009: a missing or mismatched driver is (re)installed
+    assert!(script.contains("apt-get install -y 'nvidia-driver-535'"));
     assert!(script.contains("installed:gpu0"));
 }
 
@@ -344,9 +423,8 @@ fn test_fj1125_state_query_rocm_kernel_fallback() {
 
 #[test]
 fn test_pmat036_apply_nvidia_skips_install_when_driver_present() {
-    // FJ-1009: apply_script checks nvidia-smi first. If present, prints
-    // NOTICE on version mismatch instead of trying apt-get install.
-    // apt-get install only runs in the else branch (nvidia-smi missing).
+    // FJ-1009: apply_script queries the live driver first and only runs
+    // apt-get install when it is missing or a different version.
     let r = make_gpu_resource("gpu0");
     let script = apply_script(&r);
     // Must check nvidia-smi BEFORE running apt-get install
@@ -360,10 +438,10 @@ fn test_pmat036_apply_nvidia_skips_install_when_driver_present() {
         smi_pos < apt_pos,
         "nvidia-smi check must come before apt-get install"
     );
-    // When nvidia-smi exists, version mismatch prints NOTICE (not install)
+    // A mismatched driver is replaced and flagged for reboot
     assert!(
-        script.contains("NOTICE: requested driver"),
-        "version mismatch must print NOTICE, not install"
+        script.contains("reboot to load the new kernel module"),
+        "driver change must require a reboot"
     );
 }
 
@@ -390,14 +468,13 @@ fn test_pmat036_apply_nvidia_no_version_skips_when_exists() {
 
 #[test]
 fn test_pmat036_check_nvidia_version_prefix_match() {
-    // FJ-1009: check_script accepts any working nvidia-smi as match,
-    // regardless of requested driver version. Version mismatches are
-    // handled in apply_script via NOTICE.
+    // FJ-1009: the declared driver matches its point releases only
     let mut r = make_gpu_resource("gpu0");
     r.driver_version = Some("550".to_string());
     let script = check_script(&r);
     assert!(script.contains("nvidia-smi"), "must check nvidia-smi");
-    assert!(script.contains("match:gpu0"), "working driver = match");
+    assert!(script.contains("'550'|'550'.*) ;;"), "prefix match on '.'");
+    assert!(script.contains("*) echo 'mismatch:gpu0'"));
 }
 
 // ── FJ-1005: CPU backend tests ──
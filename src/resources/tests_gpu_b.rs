@@ -0,0 +1,215 @@
+//! GPU resource tests: driver/CUDA enforcement, MIG layout, power limits and
+//! the container toolkit, against a fake `nvidia-smi` on PATH.
+
+use super::gpu::*;
+use super::gpu_b::mig_layout;
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Command;
+
+fn make_gpu(driver: &str) -> Resource {
+    Resource {
+        resource_type: ResourceType::Gpu,
+        machine: MachineTarget::Single("gpu-box".to_string()),
+        name: Some("gpu0".to_string()),
+        driver_version: Some(driver.to_string()),
+        persistence_mode: Some(false),
+        ..Default::default()
+    }
+}
+
+const FAKE_NVIDIA_SMI: &str = r#"#!/bin/sh
+echo "nvidia-smi $*" >> "$FAKE_LOG"
+case "$*" in
+  *driver_version*) echo "$FAKE_DRIVER" ;;
+  *query-gpu=index*) echo 0 ;;
+  *power.limit*) echo "$FAKE_POWER" ;;
+  *mig.mode.current*) echo Enabled ;;
+  *-lgi*) echo '|   0  MIG 3g.40gb           9        1          4:4     |' ;;
+esac
+"#;
+
+/// Write a fake `nvidia-smi`, `nvcc`, `apt-get` and `sudo` into `dir`.
+fn install_fakes(dir: &Path) {
+    let fakes = [
+        ("nvidia-smi", FAKE_NVIDIA_SMI),
+        (
+            "nvcc",
+            "#!/bin/sh\necho 'Cuda compilation tools, release 12.4, V12.4.131'\n",
+        ),
+        (
+            "apt-get",
+            "#!/bin/sh\necho \"apt-get $*\" >> \"$FAKE_LOG\"\n",
+        ),
+        ("sudo", "#!/bin/sh\nexec \"$@\"\n"),
+    ];
+    for (name, body) in fakes {
+        let path = dir.join(name);
+        std::fs::write(&path, body).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+}
+
+/// Run `script` with the fakes first on PATH; returns (success, stdout, log).
+fn run_with_fakes(script: &str, driver: &str) -> (bool, String, String) {
+    let dir = tempfile::tempdir().unwrap();
+    install_fakes(dir.path());
+    let log = dir.path().join("calls.log");
+    std::fs::write(&log, "").unwrap();
+    let out = Command::new("bash")
+        .arg("-c")
+        .arg(script)
+        .env("PATH", format!("{}:/usr/bin:/bin", dir.path().display()))
+        .env("FAKE_LOG", &log)
+        .env("FAKE_DRIVER", driver)
+        .env("FAKE_POWER", "300.00")
+        .output()
+        .expect("bash");
+    (
+        out.status.success(),
+        String::from_utf8_lossy(&out.stdout).to_string(),
+        std::fs::read_to_string(&log).unwrap(),
+    )
+}
+
+#[test]
+fn test_fj1009_check_compares_live_driver() {
+    let (_, out, _) = run_with_fakes(&check_script(&make_gpu("550")), "550.54.15");
+    assert_eq!(out.trim(), "match:gpu0");
+    let (_, out, _) = run_with_fakes(&check_script(&make_gpu("550.54.15")), "550.54.15");
+    assert_eq!(out.trim(), "match:gpu0");
+    let (_, out, _) = run_with_fakes(&check_script(&make_gpu("535")), "550.54.15");
+    assert_eq!(out.trim(), "mismatch:gpu0");
+    let (_, out, _) = run_with_fakes(&check_script(&make_gpu("55")), "550.54.15");
+    assert_eq!(out.trim(), "mismatch:gpu0");
+}
+
+#[test]
+fn test_fj1009_check_compares_cuda_release() {
+    let mut r = make_gpu("550");
+    r.cuda_version = Some("12.4".to_string());
+    let (_, out, _) = run_with_fakes(&check_script(&r), "550.54.15");
+    assert_eq!(out.trim(), "match:gpu0");
+    r.cuda_version = Some("12.3".to_string());
+    let (_, out, _) = run_with_fakes(&check_script(&r), "550.54.15");
+    assert_eq!(out.trim(), "mismatch:gpu0");
+}
+
+#[test]
+fn test_fj1009_state_query_reports_cuda() {
+    let mut r = make_gpu("550");
+    r.cuda_version = Some("12.3".to_string());
+    let (_, out, _) = run_with_fakes(&state_query_script(&r), "550.54.15");
+    assert!(out.contains("cuda=12.4"), "{out}");
+}
+
+#[test]
+fn test_fj1009_apply_replaces_mismatched_driver() {
+    let script = apply_script(&make_gpu("535.129.03"));
+    assert!(script.contains("apt-get install -y 'nvidia-driver-535'"));
+    assert!(script.contains("REBOOT_REQUIRED=1"));
+    assert!(script.contains("cannot be changed from inside a container"));
+    assert_eq!(driver_package("550"), "nvidia-driver-550");
+}
+
+#[test]
+fn test_fj1009_apply_cuda_switches_toolkit() {
+    let mut r = make_gpu("550");
+    r.cuda_version = Some("12.3".to_string());
+    let script = apply_script(&r);
+    assert!(script.contains("if [ \"$CUDA\" != '12.3' ]; then"));
+    assert!(script.contains("apt-get install -y 'cuda-toolkit-12-3'"));
+    assert!(script.contains("ln -sfn '/usr/local/cuda-12.3' /usr/local/cuda"));
+}
+
+#[test]
+fn test_gpu_apply_power_limit_and_mig_with_fake_smi() {
+    let mut r = make_gpu("550");
+    r.power_limit_watts = Some(250);
+    r.mig_profiles = vec!["3g.40gb".to_string(), "3g.40gb".to_string()];
+    let (ok, out, log) = run_with_fakes(&apply_script(&r), "550.54.15");
+    assert!(ok, "{out}\n{log}");
+    assert!(log.contains("nvidia-smi -i 0 -pl 250"), "{log}");
+    assert!(
+        log.contains("nvidia-smi mig -i 0 -cgi 3g.40gb,3g.40gb -C"),
+        "{log}"
+    );
+    assert!(!log.contains("apt-get"), "{log}");
+    assert!(out.contains("changed:mig:0"));
+    assert!(out.contains("installed:gpu0"));
+}
+
+#[test]
+fn test_gpu_apply_converged_runtime_is_noop() {
+    let mut r = make_gpu("550");
+    r.power_limit_watts = Some(300);
+    r.mig_profiles = vec!["3g.40gb".to_string()];
+    let (ok, out, log) = run_with_fakes(&apply_script(&r), "550.54.15");
+    assert!(ok, "{out}\n{log}");
+    assert!(!out.contains("changed:"), "{out}");
+    assert!(!log.contains(" -pl "), "{log}");
+    assert!(!log.contains("-cgi"), "{log}");
+}
+
+#[test]
+fn test_gpu_runtime_settings_wait_for_reboot() {
+    let mut r = make_gpu("550");
+    r.devices = vec![0, 2];
+    r.power_limit_watts = Some(250);
+    let script = apply_script(&r);
+    let guard = script
+        .find("if [ \"$REBOOT_REQUIRED\" = 0 ]; then")
+        .unwrap();
+    assert!(guard < script.find("-pl 250").unwrap());
+    assert!(script.contains("GPUS='0 2'"));
+}
+
+#[test]
+fn test_gpu_mig_layout_sorted() {
+    let mut r = make_gpu("550");
+    r.mig_profiles = vec![
+        "3g.40gb".to_string(),
+        "1g.10gb".to_string(),
+        "2g.20gb".to_string(),
+    ];
+    assert_eq!(mig_layout(&r), "1g.10gb,2g.20gb,3g.40gb");
+    let query = state_query_script(&r);
+    assert!(query.contains("echo \"mig=$GPU:$MIG_MODE:"));
+}
+
+#[test]
+fn test_gpu_container_toolkit_generates_cdi() {
+    let mut r = make_gpu("550");
+    r.container_toolkit = true;
+    let script = apply_script(&r);
+    assert!(script.contains("apt-get install -y nvidia-container-toolkit"));
+    assert!(script.contains("nvidia-ctk cdi generate --output='/etc/cdi/nvidia.yaml.forjar-new'"));
+    assert!(script.contains("echo 'changed:cdi'"));
+    assert!(state_query_script(&r).contains("cksum '/etc/cdi/nvidia.yaml'"));
+    r.state = Some("absent".to_string());
+    assert!(apply_script(&r).contains("unlink '/etc/cdi/nvidia.yaml'"));
+}
+
+#[test]
+fn test_gpu_state_query_scoped_to_devices() {
+    let mut r = make_gpu("550");
+    r.devices = vec![1];
+    r.power_limit_watts = Some(250);
+    let query = state_query_script(&r);
+    assert!(query.contains("nvidia-smi --id=1 --query-gpu=index,power.limit"));
+}
+
+#[test]
+fn test_gpu_nvidia_scripts_pass_lint() {
+    let mut r = make_gpu("550");
+    r.cuda_version = Some("12.4".to_string());
+    r.power_limit_watts = Some(250);
+    r.mig_profiles = vec!["3g.40gb".to_string()];
+    r.container_toolkit = true;
+    for script in [check_script(&r), apply_script(&r), state_query_script(&r)] {
+        if let Err(e) = crate::core::purifier::validate_script(&script) {
+            panic!("lint failed: {e}\n{script}");
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! Tests for the AppArmor and SELinux resources. The stub tests run the
+//! scripts against fake `aa-status`/`semanage`/`getsebool` commands to
+//! check the output parsing.
+
+use super::{apparmor_profile, selinux_boolean, selinux_fcontext, selinux_mode};
+use crate::core::codegen;
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+use std::path::Path;
+
+fn make(rtype: ResourceType) -> Resource {
+    Resource {
+        resource_type: rtype,
+        machine: MachineTarget::Single("m1".to_string()),
+        ..Default::default()
+    }
+}
+
+fn make_profile() -> Resource {
+    let mut r = make(ResourceType::ApparmorProfile);
+    r.name = Some("/usr/sbin/nginx".to_string());
+    r
+}
+
+fn make_fcontext() -> Resource {
+    let mut r = make(ResourceType::SelinuxFcontext);
+    r.path = Some("/srv/www(/.*)?".to_string());
+    r.selinux_type = Some("httpd_sys_content_t".to_string());
+    r
+}
+
+/// Write an executable stub printing `output` and logging its arguments.
+fn stub(dir: &Path, name: &str, output: &str) {
+    use std::os::unix::fs::PermissionsExt;
+    let path = dir.join(name);
+    let log = dir.join("calls.log");
+    std::fs::write(
+        &path,
+        format!(
+            "#!/bin/bash\necho \"{name} $*\" >> '{}'\ncat <<'EOF'\n{output}\nEOF\n",
+            log.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+/// Run `script` with the stub directory first on `PATH`; returns stdout and
+/// the logged stub calls.
+fn run_with_stubs(dir: &Path, script: &str) -> (String, String) {
+    let path = format!(
+        "{}:{}",
+        dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let out = std::process::Command::new("bash")
+        .arg("-c")
+        .arg(script)
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}\n{script}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let calls = std::fs::read_to_string(dir.join("calls.log")).unwrap_or_default();
+    (String::from_utf8_lossy(&out.stdout).into_owned(), calls)
+}
+
+#[test]
+fn test_apparmor_default_path_and_enforce() {
+    let r = make_profile();
+    assert_eq!(
+        apparmor_profile::profile_path(&r),
+        "/etc/apparmor.d/usr.sbin.nginx"
+    );
+    let script = apparmor_profile::apply_script(&r);
+    assert!(script.contains("apparmor_parser -r -W '/etc/apparmor.d/usr.sbin.nginx'"));
+    assert!(script.contains("unlink '/etc/apparmor.d/force-complain/usr.sbin.nginx'"));
+    assert!(script.contains("unlink '/etc/apparmor.d/disable/usr.sbin.nginx'"));
+    // Without content the file belongs to another resource.
+    assert!(!script.contains("FORJAR_EOF"));
+    assert!(script.contains("not found' >&2; exit 1"));
+}
+
+#[test]
+fn test_apparmor_content_complain_and_disabled() {
+    let mut r = make_profile();
+    r.content = Some("/usr/sbin/nginx {\n  /srv/www/** r,\n}".to_string());
+    r.state = Some("complain".to_string());
+    let script = apparmor_profile::apply_script(&r);
+    assert!(script.contains("/srv/www/** r,"));
+    assert!(script.contains("apparmor_parser --skip-kernel-load --skip-cache \"$NEW\""));
+    assert!(script.contains(
+        "ln -sfn '/etc/apparmor.d/usr.sbin.nginx' '/etc/apparmor.d/force-complain/usr.sbin.nginx'"
+    ));
+    assert!(script.contains("apparmor_parser -r -W -C '/etc/apparmor.d/usr.sbin.nginx'"));
+
+    r.state = Some("disabled".to_string());
+    let script = apparmor_profile::apply_script(&r);
+    assert!(script.contains("'/etc/apparmor.d/disable/usr.sbin.nginx'"));
+    assert!(script.contains("apparmor_parser -R '/etc/apparmor.d/usr.sbin.nginx'"));
+    assert!(!script.contains("apparmor_parser -r"));
+
+    r.state = Some("absent".to_string());
+    let script = apparmor_profile::apply_script(&r);
+    assert!(script.contains("unlink '/etc/apparmor.d/usr.sbin.nginx'"));
+    assert!(!script.contains("FORJAR_EOF"));
+}
+
+#[test]
+fn test_apparmor_mode_from_aa_status() {
+    let dir = tempfile::tempdir().unwrap();
+    stub(
+        dir.path(),
+        "aa-status",
+        r#"{"version": "2", "profiles": {"/usr/sbin/nginx": "complain", "/usr/sbin/nginxd": "enforce", "/usr/bin/man": "enforce"}, "processes": {"/usr/sbin/nginx": [{"profile": "/usr/sbin/nginx", "pid": "42", "status": "complain"}]}}"#,
+    );
+    let mut r = make_profile();
+    r.path = Some(dir.path().join("usr.sbin.nginx").display().to_string());
+    let (out, _) = run_with_stubs(dir.path(), &apparmor_profile::check_script(&r));
+    assert_eq!(out.trim(), "exists:/usr/sbin/nginx:complain");
+    let (out, _) = run_with_stubs(dir.path(), &apparmor_profile::state_query_script(&r));
+    assert_eq!(
+        out.trim(),
+        "apparmor_profile=/usr/sbin/nginx:mode=complain:sha256=none"
+    );
+
+    // The dot in a profile name is matched literally.
+    r.name = Some("/usr/sbin.nginx".to_string());
+    let (out, _) = run_with_stubs(dir.path(), &apparmor_profile::check_script(&r));
+    assert_eq!(out.trim(), "missing:/usr/sbin.nginx");
+    let (out, _) = run_with_stubs(dir.path(), &apparmor_profile::state_query_script(&r));
+    assert!(out.contains(":mode=unloaded:"));
+}
+
+#[test]
+fn test_selinux_boolean_apply_only_on_change() {
+    let dir = tempfile::tempdir().unwrap();
+    stub(
+        dir.path(),
+        "getsebool",
+        "httpd_can_network_connect --> off\nhttpd_can_network_connect_db --> on",
+    );
+    stub(dir.path(), "setsebool", "");
+    let mut r = make(ResourceType::SelinuxBoolean);
+    r.name = Some("httpd_can_network_connect".to_string());
+    r.enabled = Some(true);
+    assert_eq!(selinux_boolean::boolean_value(&r), "on");
+
+    let (out, calls) = run_with_stubs(dir.path(), &selinux_boolean::apply_script(&r));
+    assert!(out.contains("applied:httpd_can_network_connect=on"));
+    assert!(calls.contains("setsebool -P httpd_can_network_connect on"));
+
+    let (out, _) = run_with_stubs(dir.path(), &selinux_boolean::state_query_script(&r));
+    assert_eq!(out.trim(), "selinux_boolean=httpd_can_network_connect:off");
+
+    // Already at the declared value: no setsebool call.
+    r.name = Some("httpd_can_network_connect_db".to_string());
+    std::fs::remove_file(dir.path().join("calls.log")).unwrap();
+    let (_, calls) = run_with_stubs(dir.path(), &selinux_boolean::apply_script(&r));
+    assert!(!calls.contains("setsebool"));
+}
+
+#[test]
+fn test_selinux_fcontext_add_modify_delete() {
+    let dir = tempfile::tempdir().unwrap();
+    stub(dir.path(), "restorecon", "");
+    let r = make_fcontext();
+    assert_eq!(
+        selinux_fcontext::relabel_target(&r).as_deref(),
+        Some("/srv/www")
+    );
+
+    // No local rule yet: add.
+    stub(dir.path(), "semanage", "");
+    let (_, calls) = run_with_stubs(dir.path(), &selinux_fcontext::apply_script(&r));
+    assert!(calls.contains("semanage fcontext -a -t httpd_sys_content_t /srv/www(/.*)?"));
+
+    // A rule with another type: modify.
+    stub(
+        dir.path(),
+        "semanage",
+        "/srv/www(/.*)?                                     all files          system_u:object_r:public_content_t:s0",
+    );
+    let (_, calls) = run_with_stubs(dir.path(), &selinux_fcontext::apply_script(&r));
+    assert!(calls.contains("semanage fcontext -m -t httpd_sys_content_t /srv/www(/.*)?"));
+    let (out, _) = run_with_stubs(dir.path(), &selinux_fcontext::state_query_script(&r));
+    assert!(out.starts_with("selinux_fcontext=/srv/www(/.*)?:type=public_content_t:label="));
+
+    let mut absent = r.clone();
+    absent.state = Some("absent".to_string());
+    let (_, calls) = run_with_stubs(dir.path(), &selinux_fcontext::apply_script(&absent));
+    assert!(calls.contains("semanage fcontext -d /srv/www(/.*)?"));
+}
+
+#[test]
+fn test_selinux_fcontext_explicit_target() {
+    let mut r = make_fcontext();
+    r.path = Some("/srv/(www|static)(/.*)?".to_string());
+    assert_eq!(selinux_fcontext::relabel_target(&r), None);
+    assert!(selinux_fcontext::apply_script(&r).contains("\ntrue\n"));
+    r.target = Some("/srv".to_string());
+    assert!(selinux_fcontext::apply_script(&r).contains("restorecon -R '/srv'"));
+}
+
+#[test]
+fn test_selinux_mode_scripts() {
+    let r = make(ResourceType::SelinuxMode);
+    assert_eq!(selinux_mode::selinux_mode(&r), "enforcing");
+    let script = selinux_mode::apply_script(&r);
+    assert!(script.contains("SELINUX=enforcing"));
+    assert!(script.contains("setenforce 1"));
+    assert!(script.contains("touch /.autorelabel"));
+
+    let mut p = r.clone();
+    p.state = Some("permissive".to_string());
+    assert!(selinux_mode::apply_script(&p).contains("setenforce 0"));
+
+    let mut d = r;
+    d.state = Some("disabled".to_string());
+    let script = selinux_mode::apply_script(&d);
+    assert!(script.contains("SELINUX=disabled"));
+    assert!(script.contains("reboot required to disable SELinux"));
+    assert!(!script.contains("autorelabel"));
+}
+
+#[test]
+fn test_mac_scripts_pass_lint() {
+    let mut profile = make_profile();
+    profile.content = Some("/usr/sbin/nginx {\n}".to_string());
+    let mut boolean = make(ResourceType::SelinuxBoolean);
+    boolean.name = Some("httpd_can_network_connect".to_string());
+    boolean.enabled = Some(false);
+    let mut resources = vec![boolean, make_fcontext(), make(ResourceType::SelinuxMode)];
+    for state in ["enforce", "complain", "disabled", "absent"] {
+        let mut p = profile.clone();
+        p.state = Some(state.to_string());
+        resources.push(p);
+    }
+    let mut fc_absent = make_fcontext();
+    fc_absent.state = Some("absent".to_string());
+    resources.push(fc_absent);
+    let mut mode_disabled = make(ResourceType::SelinuxMode);
+    mode_disabled.state = Some("disabled".to_string());
+    resources.push(mode_disabled);
+    for r in &resources {
+        for script in [
+            codegen::check_script(r).unwrap(),
+            codegen::apply_script(r).unwrap(),
+            codegen::state_query_script(r).unwrap(),
+        ] {
+            if let Err(e) = crate::core::purifier::validate_script(&script) {
+                panic!("lint failed: {e}\n{script}");
+            }
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! Tests for `hf://` model sources against a local stand-in for the Hub.
+
+use super::model_hf;
+use crate::core::store::hf_hub::{self, HfSource};
+use crate::core::test_http;
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::process::Command;
+
+const COMMIT: &str = "0123456789abcdef0123456789abcdef01234567";
+const CONFIG_JSON: &str =
+    r#"{"model_type":"llama","architectures":["LlamaForCausalLM"],"hidden_size":64}"#;
+const SHARD_1: &str = "shard-one-bytes";
+const SHARD_2: &str = "shard-two-bytes!";
+
+fn sha256(data: &str) -> String {
+    format!("{:x}", Sha256::digest(data.as_bytes()))
+}
+
+/// A fake repo: API JSON plus file contents. `shard_sha` overrides the
+/// advertised checksum of the first shard.
+fn fake_repo(config: &str, shard_sha: Option<&str>) -> HashMap<String, String> {
+    let api = format!(
+        r#"{{"sha":"{COMMIT}","siblings":[
+            {{"rfilename":"README.md","size":3}},
+            {{"rfilename":"config.json","size":{}}},
+            {{"rfilename":"model-00001-of-00002.safetensors","size":{},"lfs":{{"sha256":"{}","size":{}}}}},
+            {{"rfilename":"model-00002-of-00002.safetensors","size":{},"lfs":{{"sha256":"{}","size":{}}}}},
+            {{"rfilename":"pytorch_model.bin","size":1,"lfs":{{"sha256":"00","size":1}}}},
+            {{"rfilename":"tokenizer.json","size":2}}
+        ]}}"#,
+        config.len(),
+        SHARD_1.len(),
+        shard_sha
+            .map(str::to_string)
+            .unwrap_or_else(|| sha256(SHARD_1)),
+        SHARD_1.len(),
+        SHARD_2.len(),
+        sha256(SHARD_2),
+        SHARD_2.len(),
+    );
+    let base = format!("/acme/tiny/resolve/{COMMIT}");
+    [
+        (
+            "/api/models/acme/tiny/revision/main?blobs=true".to_string(),
+            api,
+        ),
+        (format!("{base}/config.json"), config.to_string()),
+        (
+            format!("{base}/model-00001-of-00002.safetensors"),
+            SHARD_1.to_string(),
+        ),
+        (
+            format!("{base}/model-00002-of-00002.safetensors"),
+            SHARD_2.to_string(),
+        ),
+        (format!("{base}/tokenizer.json"), "{}".to_string()),
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn source() -> HfSource {
+    hf_hub::parse_hf_source("hf://acme/tiny").unwrap()
+}
+
+fn make_model(cache: &std::path::Path) -> Resource {
+    Resource {
+        resource_type: ResourceType::Model,
+        machine: MachineTarget::Single("gpu-box".to_string()),
+        name: Some("tiny".to_string()),
+        source: Some("hf://acme/tiny".to_string()),
+        cache_dir: Some(cache.display().to_string()),
+        ..Default::default()
+    }
+}
+
+fn bash(script: &str) -> std::process::Output {
+    Command::new("bash")
+        .arg("-c")
+        .arg(script)
+        .env_remove("HF_TOKEN")
+        .output()
+        .expect("bash")
+}
+
+/// Run `script` with `HOME` pointing at `home`.
+fn bash_in_home(script: &str, home: &std::path::Path) -> std::process::Output {
+    Command::new("bash")
+        .arg("-c")
+        .arg(script)
+        .current_dir(home)
+        .env("HOME", home)
+        .env_remove("HF_TOKEN")
+        .output()
+        .expect("bash")
+}
+
+#[test]
+fn test_hf_resolve_selects_files_and_validates_config() {
+    let endpoint = test_http::serve(test_http::routes(fake_repo(CONFIG_JSON, None)));
+    let snapshot = hf_hub::resolve(&source(), &endpoint).unwrap();
+    assert_eq!(snapshot.commit, COMMIT);
+    assert_eq!(snapshot.config.model_type, "llama");
+    let names: Vec<&str> = snapshot.files.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "config.json",
+            "model-00001-of-00002.safetensors",
+            "model-00002-of-00002.safetensors",
+            "tokenizer.json"
+        ]
+    );
+    let config = &snapshot.files[0];
+    assert_eq!(config.sha256.as_deref(), Some(sha256(CONFIG_JSON).as_str()));
+    assert_eq!(snapshot.files[1].sha256, Some(sha256(SHARD_1)));
+    assert_eq!(snapshot.files[3].sha256, None);
+    assert_eq!(
+        snapshot.file_url("tokenizer.json"),
+        format!("{endpoint}/acme/tiny/resolve/{COMMIT}/tokenizer.json")
+    );
+}
+
+#[test]
+fn test_hf_resolve_rejects_invalid_config() {
+    let endpoint = test_http::serve(test_http::routes(fake_repo(
+        r#"{"architectures":[]}"#,
+        None,
+    )));
+    let err = hf_hub::resolve(&source(), &endpoint).unwrap_err();
+    assert!(err.contains("parse config.json"), "{err}");
+}
+
+#[test]
+fn test_hf_resolve_unknown_revision_fails() {
+    let endpoint = test_http::serve(test_http::routes(fake_repo(CONFIG_JSON, None)));
+    let src = hf_hub::parse_hf_source("hf://acme/tiny@v9").unwrap();
+    let err = hf_hub::resolve(&src, &endpoint).unwrap_err();
+    assert!(err.contains("/api/models/acme/tiny/revision/v9"), "{err}");
+}
+
+#[test]
+fn test_hf_apply_downloads_verifies_and_records_commit() {
+    let endpoint = test_http::serve(test_http::routes(fake_repo(CONFIG_JSON, None)));
+    let cache = tempfile::tempdir().unwrap();
+    let r = make_model(cache.path());
+    let snapshot = hf_hub::resolve(&source(), &endpoint).unwrap();
+    let script = model_hf::apply_script(&r, &snapshot);
+
+    let out = bash(&script);
+    let stdout = String::from_utf8_lossy(&out.stdout).to_string();
+    assert!(
+        out.status.success(),
+        "{stdout}{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let outputs = crate::core::state::parse_script_outputs(&stdout);
+    assert_eq!(outputs.get("revision").map(String::as_str), Some(COMMIT));
+
+    let current = cache.path().join("hf/acme--tiny/current");
+    assert_eq!(
+        std::fs::read_to_string(current.join("model-00002-of-00002.safetensors")).unwrap(),
+        SHARD_2
+    );
+    assert_eq!(
+        std::fs::read_to_string(current.join(".forjar-revision")).unwrap(),
+        format!("{COMMIT}\n")
+    );
+    assert!(!current.join("README.md").exists());
+
+    // Second run finds every file verified in place
+    let again = bash(&script);
+    assert!(again.status.success());
+    assert!(!String::from_utf8_lossy(&again.stdout).contains("fetched:"));
+
+    let check = bash(&model_hf::check_script(&r));
+    assert_eq!(String::from_utf8_lossy(&check.stdout).trim(), "exists:tiny");
+    let state = bash(&model_hf::state_query_script(&r));
+    let state = String::from_utf8_lossy(&state.stdout);
+    assert!(state.contains(&format!("model=tiny:revision={COMMIT}")));
+    assert!(state.contains("model-00001-of-00002.safetensors 15"));
+}
+
+#[test]
+fn test_hf_default_cache_dir_expands_home_on_target() {
+    let endpoint = test_http::serve(test_http::routes(fake_repo(CONFIG_JSON, None)));
+    let home = tempfile::tempdir().unwrap();
+    let mut r = make_model(home.path());
+    r.cache_dir = None;
+    let snapshot = hf_hub::resolve(&source(), &endpoint).unwrap();
+    let script = model_hf::apply_script(&r, &snapshot);
+    assert!(script.contains("\"$HOME\"/'.cache/apr/hf/acme--tiny/current'"));
+    assert!(!script.contains("'~"), "{script}");
+
+    let out = bash_in_home(&script, home.path());
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let current = home.path().join(".cache/apr/hf/acme--tiny/current");
+    assert_eq!(
+        std::fs::read_to_string(current.join("config.json")).unwrap(),
+        CONFIG_JSON
+    );
+    assert!(!home.path().join("~").exists());
+
+    let check = bash_in_home(&model_hf::check_script(&r), home.path());
+    assert_eq!(String::from_utf8_lossy(&check.stdout).trim(), "exists:tiny");
+    let state = bash_in_home(&model_hf::state_query_script(&r), home.path());
+    assert!(String::from_utf8_lossy(&state.stdout).contains(&format!("revision={COMMIT}")));
+    let absent = bash_in_home(&model_hf::absent_script(&r), home.path());
+    assert!(absent.status.success());
+    assert!(!current.exists());
+}
+
+#[test]
+fn test_hf_apply_fails_on_checksum_mismatch() {
+    let bogus = "f".repeat(64);
+    let endpoint = test_http::serve(test_http::routes(fake_repo(CONFIG_JSON, Some(&bogus))));
+    let cache = tempfile::tempdir().unwrap();
+    let r = make_model(cache.path());
+    let snapshot = hf_hub::resolve(&source(), &endpoint).unwrap();
+
+    let out = bash(&model_hf::apply_script(&r, &snapshot));
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr)
+        .contains("CHECKSUM MISMATCH: model-00001-of-00002.safetensors"));
+    let snap = cache
+        .path()
+        .join(format!("hf/acme--tiny/snapshots/{COMMIT}"));
+    assert!(!snap.join("model-00001-of-00002.safetensors").exists());
+    assert!(!snap.join("model-00001-of-00002.safetensors.part").exists());
+    assert!(!cache.path().join("hf/acme--tiny/current").exists());
+}
+
+#[test]
+fn test_hf_check_pinned_commit_and_absent() {
+    let cache = tempfile::tempdir().unwrap();
+    let mut r = make_model(cache.path());
+    r.source = Some(format!("hf://acme/tiny@{COMMIT}"));
+    r.path = Some("/srv/models/tiny".to_string());
+    let check = model_hf::check_script(&r);
+    assert!(check.contains("head -n 1 '/srv/models/tiny/.forjar-revision'"));
+    assert!(check.contains(&format!("[ \"$REV\" = '{COMMIT}' ]")));
+    r.state = Some("absent".to_string());
+    let script = model_hf::absent_script(&r);
+    assert!(script.contains("unlink '/srv/models/tiny'"));
+}
+
+#[test]
+fn test_hf_unresolved_script_fails_loudly() {
+    let cache = tempfile::tempdir().unwrap();
+    let r = make_model(cache.path());
+    let script = super::model::apply_script(&r);
+    assert!(script.contains("exit 1"));
+    assert!(!bash(&script).status.success());
+}
+
+#[test]
+fn test_hf_scripts_pass_lint() {
+    let endpoint = test_http::serve(test_http::routes(fake_repo(CONFIG_JSON, None)));
+    let cache = tempfile::tempdir().unwrap();
+    let r = make_model(cache.path());
+    let mut default_cache = r.clone();
+    default_cache.cache_dir = None;
+    let snapshot = hf_hub::resolve(&source(), &endpoint).unwrap();
+    for r in [&r, &default_cache] {
+        for script in [
+            model_hf::check_script(r),
+            model_hf::apply_script(r, &snapshot),
+            model_hf::state_query_script(r),
+            model_hf::absent_script(r),
+        ] {
+            if let Err(e) = crate::core::purifier::validate_script(&script) {
+                panic!("lint failed: {e}\n{script}");
+            }
+        }
+    }
+}
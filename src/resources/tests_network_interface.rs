@@ -0,0 +1,179 @@
+//! Tests for the network_interface resource handler.
+
+use super::network_interface::{apply_script, check_script, confirm_script, state_query_script};
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+
+fn make_iface() -> Resource {
+    Resource {
+        resource_type: ResourceType::NetworkInterface,
+        machine: MachineTarget::Single("host".to_string()),
+        name: Some("eth0".to_string()),
+        addresses: vec!["10.0.0.2/24".to_string()],
+        gateway: Some("10.0.0.1".to_string()),
+        dns: vec!["1.1.1.1".to_string()],
+        routes: vec!["10.20.0.0/16 via 10.0.0.254".to_string()],
+        mtu: Some(9000),
+        ..Default::default()
+    }
+}
+
+fn make_bond() -> Resource {
+    Resource {
+        resource_type: ResourceType::NetworkInterface,
+        machine: MachineTarget::Single("host".to_string()),
+        name: Some("bond0".to_string()),
+        kind: Some("bond".to_string()),
+        members: vec!["eth1".to_string(), "eth2".to_string()],
+        bond_mode: Some("802.3ad".to_string()),
+        dhcp: Some("ipv4".to_string()),
+        ..Default::default()
+    }
+}
+
+fn lint_ok(r: &Resource) {
+    for script in [check_script(r), apply_script(r), state_query_script(r)]
+        .into_iter()
+        .chain(confirm_script(r))
+    {
+        if let Err(e) = crate::core::purifier::validate_script(&script) {
+            panic!("lint failed: {e}\n{script}");
+        }
+    }
+}
+
+#[test]
+fn test_networkd_renders_network_unit() {
+    let script = apply_script(&make_iface());
+    assert!(script.contains(
+        "forjar_net_write '/etc/systemd/network/10-forjar-eth0.network' <<'FORJAR_EOF'\n\
+         [Match]\nName=eth0\n\n[Link]\nMTUBytes=9000\n\n[Network]\n\
+         Address=10.0.0.2/24\nGateway=10.0.0.1\nDNS=1.1.1.1\n\n\
+         [Route]\nDestination=10.20.0.0/16\nGateway=10.0.0.254\nFORJAR_EOF"
+    ));
+    assert!(!script.contains(".netdev"));
+    assert!(script.contains("if [ \"$CHANGED\" = 0 ]; then echo 'unchanged:eth0'; exit 0; fi"));
+    assert!(script.contains("networkctl reload\nnetworkctl reconfigure 'eth0'"));
+    // No safe-apply unless asked for
+    assert!(!script.contains("systemd-run"));
+    assert!(confirm_script(&make_iface()).is_none());
+}
+
+#[test]
+fn test_networkd_bond_netdev_and_members() {
+    let script = apply_script(&make_bond());
+    assert!(script.contains("[NetDev]\nName=bond0\nKind=bond\n\n[Bond]\nMode=802.3ad\nFORJAR_EOF"));
+    assert!(script.contains("[Network]\nDHCP=ipv4\nFORJAR_EOF"));
+    assert!(script.contains(
+        "forjar_net_write '/etc/systemd/network/10-forjar-bond0--eth1.network' <<'FORJAR_EOF'\n\
+         [Match]\nName=eth1\n\n[Network]\nBond=bond0\nFORJAR_EOF"
+    ));
+    // Stale member files are pruned
+    assert!(script.contains(
+        "'/etc/systemd/network/10-forjar-bond0--eth1.network'|'/etc/systemd/network/10-forjar-bond0--eth2.network') ;;"
+    ));
+    assert!(script.contains("networkctl reconfigure 'eth2'"));
+}
+
+#[test]
+fn test_networkd_vlan_dropin_on_parent() {
+    let mut r = make_iface();
+    r.name = Some("eth0.100".to_string());
+    r.kind = Some("vlan".to_string());
+    r.vlan_id = Some(100);
+    r.interface = Some("eth0".to_string());
+    let script = apply_script(&r);
+    assert!(script.contains("Kind=vlan\n\n[VLAN]\nId=100\nFORJAR_EOF"));
+    assert!(script.contains("mkdir -p '/etc/systemd/network/10-forjar-eth0.network.d'"));
+    assert!(script.contains(
+        "forjar_net_write '/etc/systemd/network/10-forjar-eth0.network.d/forjar-vlan-eth0.100.conf' \
+         <<'FORJAR_EOF'\n[Network]\nVLAN=eth0.100\nFORJAR_EOF"
+    ));
+}
+
+#[test]
+fn test_netplan_renders_yaml() {
+    let mut r = make_iface();
+    r.provider = Some("netplan".to_string());
+    let script = apply_script(&r);
+    assert!(script.contains(
+        "forjar_net_write '/etc/netplan/60-forjar-eth0.yaml' <<'FORJAR_EOF'\n\
+         network:\n  version: 2\n  ethernets:\n    eth0:\n\
+         \x20     addresses: [10.0.0.2/24]\n      mtu: 9000\n      routes:\n\
+         \x20       - to: default\n          via: 10.0.0.1\n\
+         \x20       - to: 10.20.0.0/16\n          via: 10.0.0.254\n\
+         \x20     nameservers:\n        addresses: [1.1.1.1]\nFORJAR_EOF"
+    ));
+    assert!(script.contains("umask 077"));
+    assert!(script.contains("netplan apply"));
+    assert!(!script.contains("networkctl"));
+}
+
+#[test]
+fn test_netplan_bond_declares_members() {
+    let mut r = make_bond();
+    r.provider = Some("netplan".to_string());
+    let script = apply_script(&r);
+    assert!(script.contains(
+        "  ethernets:\n    eth1: {}\n    eth2: {}\n  bonds:\n    bond0:\n\
+         \x20     interfaces: [eth1, eth2]\n      parameters:\n        mode: 802.3ad\n\
+         \x20     dhcp4: true\n"
+    ));
+}
+
+#[test]
+fn test_safe_apply_arms_revert_timer() {
+    let mut r = make_iface();
+    r.revert_after = Some(60);
+    let script = apply_script(&r);
+    assert!(script
+        .contains("tar -C '/etc/systemd/network' -cf '/var/lib/forjar/net-revert/eth0.tar' ."));
+    assert!(script.contains(
+        "systemd-run --unit='forjar-net-revert-eth0' --on-active=60s /bin/sh '/var/lib/forjar/net-revert/eth0.sh'"
+    ));
+    // The timer is armed before the backend reloads
+    let armed = script.find("systemd-run").unwrap();
+    assert!(armed < script.rfind("networkctl reload").unwrap());
+    assert!(armed > script.find("'unchanged:eth0'").unwrap());
+    assert!(
+        script.contains("tar -C '/etc/systemd/network' -xf '/var/lib/forjar/net-revert/eth0.tar'")
+    );
+
+    let confirm = confirm_script(&r).unwrap();
+    assert!(confirm.contains("systemctl stop 'forjar-net-revert-eth0.timer'"));
+    assert!(confirm.contains("was already reverted"));
+}
+
+#[test]
+fn test_absent_removes_files_and_virtual_link() {
+    let mut r = make_bond();
+    r.state = Some("absent".to_string());
+    let script = apply_script(&r);
+    assert!(script.contains(
+        "if [ -e '/etc/systemd/network/10-forjar-bond0.netdev' ]; then unlink '/etc/systemd/network/10-forjar-bond0.netdev'; CHANGED=1; fi"
+    ));
+    assert!(!script.contains("forjar_net_write '"));
+    assert!(script.contains("*) unlink \"$f\"; CHANGED=1 ;;"));
+    assert!(script.contains("ip link delete 'bond0'"));
+}
+
+#[test]
+fn test_state_query_uses_ip_json() {
+    let query = state_query_script(&make_iface());
+    assert!(query.contains("ip -j address show dev 'eth0'"));
+    assert!(query.contains("ip -j route show dev 'eth0'"));
+    assert!(query.contains("_life_time"));
+    assert!(query.contains("network_interface=MISSING:eth0"));
+}
+
+#[test]
+fn test_network_interface_scripts_pass_lint() {
+    let mut safe = make_iface();
+    safe.revert_after = Some(30);
+    let mut netplan = make_bond();
+    netplan.provider = Some("netplan".to_string());
+    let mut absent = make_bond();
+    absent.state = Some("absent".to_string());
+    for r in [make_iface(), make_bond(), safe, netplan, absent] {
+        lint_ok(&r);
+    }
+}
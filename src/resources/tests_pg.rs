@@ -0,0 +1,294 @@
+//! Tests for the PostgreSQL resources. The live tests start a throwaway
+//! server with the local PostgreSQL binaries and skip when none are
+//! installed or the test cannot switch to the `postgres` user.
+
+use super::{pg_database, pg_extension, pg_role};
+use crate::core::codegen;
+use crate::core::types::{LifecycleRules, MachineTarget, Resource, ResourceType};
+use std::process::{Command, Output};
+
+fn make(rtype: ResourceType, name: &str) -> Resource {
+    Resource {
+        resource_type: rtype,
+        machine: MachineTarget::Single("db".to_string()),
+        name: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+fn bash(script: &str) -> Output {
+    Command::new("bash")
+        .arg("-c")
+        .arg(script)
+        .output()
+        .expect("bash")
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+fn run_ok(script: &str) -> String {
+    let out = bash(script);
+    assert!(
+        out.status.success(),
+        "{}\n{}\n{script}",
+        stdout(&out),
+        String::from_utf8_lossy(&out.stderr)
+    );
+    stdout(&out)
+}
+
+#[test]
+fn test_pg_scram_verifier_format() {
+    let verifier = pg_role::scram_verifier("pencil", b"0123456789abcdef").unwrap();
+    let parts: Vec<&str> = verifier.split(['$', ':']).collect();
+    assert_eq!(parts[0], "SCRAM-SHA-256");
+    assert_eq!(parts[1], "4096");
+    assert_eq!(parts[2], "MDEyMzQ1Njc4OWFiY2RlZg==");
+    assert_eq!(parts[3].len(), 44);
+    assert_eq!(parts[4].len(), 44);
+    assert_eq!(
+        verifier,
+        pg_role::scram_verifier("pencil", b"0123456789abcdef").unwrap()
+    );
+    assert_ne!(
+        verifier,
+        pg_role::scram_verifier("pencil2", b"0123456789abcdef").unwrap()
+    );
+}
+
+#[test]
+fn test_pg_role_flags_and_plaintext_never_in_script() {
+    let mut r = make(ResourceType::PgRole, "app");
+    r.attributes = vec!["login".to_string(), "CREATEDB".to_string()];
+    r.password = Some("hunter2-plaintext".to_string());
+    r.groups = vec!["readers".to_string()];
+    assert_eq!(
+        pg_role::role_flags(&r),
+        [
+            "NOSUPERUSER",
+            "CREATEDB",
+            "NOCREATEROLE",
+            "INHERIT",
+            "LOGIN",
+            "NOREPLICATION",
+            "NOBYPASSRLS"
+        ]
+    );
+    r.attributes.push("NOINHERIT".to_string());
+    assert!(pg_role::role_flags(&r).contains(&"NOINHERIT".to_string()));
+
+    let script = pg_role::apply_script(&r);
+    assert!(!script.contains("hunter2"));
+    assert!(script.contains("PASSWORD 'SCRAM-SHA-256$4096:"));
+    assert!(script.contains("GRANT \"readers\" TO \"app\";"));
+    assert!(script.contains("<<'FORJAR_SQL'"));
+    assert!(pg_role::state_query_script(&r).contains("md5(coalesce(a.rolpassword, ''))"));
+}
+
+#[test]
+fn test_pg_identifiers_are_quoted() {
+    assert_eq!(pg_role::quote_ident("we\"ird"), "\"we\"\"ird\"");
+    assert_eq!(pg_role::quote_literal("it's"), "'it''s'");
+}
+
+#[test]
+fn test_pg_database_grants_and_prevent_destroy() {
+    assert_eq!(
+        pg_database::parse_grant("app:connect,temp").unwrap(),
+        ("app".to_string(), vec!["CONNECT", "TEMPORARY"])
+    );
+    assert_eq!(
+        pg_database::parse_grant("PUBLIC:ALL").unwrap().1,
+        ["CONNECT", "CREATE", "TEMPORARY"]
+    );
+    assert!(pg_database::parse_grant("app:SELECT").is_err());
+    assert!(pg_database::parse_grant("app").is_err());
+
+    let mut r = make(ResourceType::PgDatabase, "appdb");
+    r.owner = Some("app".to_string());
+    r.encoding = Some("UTF8".to_string());
+    r.grants = vec!["public:".to_string(), "app:CONNECT".to_string()];
+    let script = pg_database::apply_script(&r);
+    assert!(script.contains(
+        "SELECT 'CREATE DATABASE \"appdb\" OWNER \"app\" ENCODING ''UTF8'' TEMPLATE template0'"
+    ));
+    assert!(script.contains("REVOKE CONNECT, CREATE, TEMPORARY ON DATABASE \"appdb\" FROM PUBLIC;"));
+    assert!(script.contains("GRANT CONNECT ON DATABASE \"appdb\" TO \"app\";"));
+    assert!(script.contains("REVOKE CREATE, TEMPORARY ON DATABASE \"appdb\" FROM \"app\";"));
+
+    r.state = Some("absent".to_string());
+    r.lifecycle = Some(LifecycleRules {
+        prevent_destroy: true,
+        ..Default::default()
+    });
+    let script = pg_database::apply_script(&r);
+    assert!(script.contains("refusing to destroy database appdb"));
+    assert!(!script.contains("DROP DATABASE"));
+}
+
+#[test]
+fn test_pg_scripts_pass_lint() {
+    let mut role = make(ResourceType::PgRole, "app");
+    role.password = Some("secret".to_string());
+    role.groups = vec!["readers".to_string()];
+    role.port = Some("5433".to_string());
+    let mut db = make(ResourceType::PgDatabase, "appdb");
+    db.owner = Some("app".to_string());
+    db.grants = vec!["app:ALL".to_string()];
+    let mut ext = make(ResourceType::PgExtension, "pgcrypto");
+    ext.database = Some("appdb".to_string());
+    ext.version = Some("1.3".to_string());
+    let mut scripts = Vec::new();
+    for r in [&role, &db, &ext] {
+        scripts.push(codegen::check_script(r).unwrap());
+        scripts.push(codegen::apply_script(r).unwrap());
+        scripts.push(codegen::state_query_script(r).unwrap());
+        let mut absent = r.clone();
+        absent.state = Some("absent".to_string());
+        scripts.push(codegen::apply_script(&absent).unwrap());
+    }
+    for script in scripts {
+        if let Err(e) = crate::core::purifier::validate_script(&script) {
+            panic!("lint failed: {e}\n{script}");
+        }
+    }
+}
+
+/// A throwaway PostgreSQL server on a Unix socket in a temp dir.
+struct PgServer {
+    dir: tempfile::TempDir,
+    port: String,
+}
+
+impl PgServer {
+    fn start() -> Option<Self> {
+        let have = |cmd: &str| bash(&format!("command -v {cmd}")).status.success();
+        let is_root = stdout(&bash("id -u")) == "0";
+        if !is_root || !have("initdb") || !have("pg_ctl") || !have("psql") || !have("runuser") {
+            return None;
+        }
+        if !bash("id postgres").status.success() {
+            return None;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let port = {
+            let l = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            l.local_addr().unwrap().port().to_string()
+        };
+        let d = dir.path().display();
+        let init = bash(&format!(
+            "chown postgres '{d}' && runuser -u postgres -- initdb -D '{d}/data' -U postgres \
+             --auth-local=trust --auth-host=scram-sha-256 >/dev/null && \
+             runuser -u postgres -- pg_ctl -D '{d}/data' -w -l '{d}/log' \
+             -o \"-k '{d}' -p {port} -c listen_addresses=127.0.0.1\" start >/dev/null"
+        ));
+        init.status.success().then_some(Self { dir, port })
+    }
+
+    fn resource(&self, rtype: ResourceType, name: &str) -> Resource {
+        let mut r = make(rtype, name);
+        r.host = Some(self.dir.path().display().to_string());
+        r.port = Some(self.port.clone());
+        r
+    }
+
+    /// Log in over TCP with a password (exercises the SCRAM verifier).
+    fn login(&self, user: &str, password: &str, db: &str) -> bool {
+        Command::new("psql")
+            .args(["-X", "-At", "-h", "127.0.0.1", "-p", &self.port])
+            .args(["-U", user, "-d", db, "-c", "SELECT 1"])
+            .env("PGPASSWORD", password)
+            .env("PGCONNECT_TIMEOUT", "5")
+            .output()
+            .is_ok_and(|o| o.status.success())
+    }
+}
+
+impl Drop for PgServer {
+    fn drop(&mut self) {
+        let d = self.dir.path().display();
+        bash(&format!(
+            "runuser -u postgres -- pg_ctl -D '{d}/data' -m immediate stop >/dev/null 2>&1"
+        ));
+    }
+}
+
+#[test]
+fn test_pg_live_role_database_extension() {
+    let Some(pg) = PgServer::start() else {
+        eprintln!("skipping: no local PostgreSQL server available");
+        return;
+    };
+
+    let mut readers = pg.resource(ResourceType::PgRole, "readers");
+    readers.attributes = vec!["NOINHERIT".to_string()];
+    run_ok(&pg_role::apply_script(&readers));
+
+    let mut app = pg.resource(ResourceType::PgRole, "app");
+    app.attributes = vec!["LOGIN".to_string()];
+    app.password = Some("s3cret-pw".to_string());
+    app.groups = vec!["readers".to_string()];
+    assert_eq!(run_ok(&pg_role::check_script(&app)), "missing:app");
+    assert_eq!(run_ok(&pg_role::apply_script(&app)), "applied:app");
+    assert_eq!(run_ok(&pg_role::check_script(&app)), "exists:app");
+    assert!(pg.login("app", "s3cret-pw", "postgres"));
+    assert!(!pg.login("app", "wrong", "postgres"));
+
+    // Re-apply is idempotent; drift state reflects attributes and groups.
+    let state = run_ok(&pg_role::state_query_script(&app));
+    assert!(state.starts_with("pg_role=app:INHERIT,LOGIN:groups=readers:password="));
+    run_ok(&pg_role::apply_script(&app));
+    assert!(pg.login("app", "s3cret-pw", "postgres"));
+
+    // Membership removed from the declaration is revoked.
+    app.groups.clear();
+    run_ok(&pg_role::apply_script(&app));
+    assert!(run_ok(&pg_role::state_query_script(&app)).contains(":groups=:"));
+
+    let mut db = pg.resource(ResourceType::PgDatabase, "appdb");
+    db.owner = Some("app".to_string());
+    db.encoding = Some("UTF8".to_string());
+    db.grants = vec!["PUBLIC:".to_string(), "app:CONNECT,TEMP".to_string()];
+    assert_eq!(run_ok(&pg_database::check_script(&db)), "missing:appdb");
+    run_ok(&pg_database::apply_script(&db));
+    run_ok(&pg_database::apply_script(&db));
+    let state = run_ok(&pg_database::state_query_script(&db));
+    assert!(
+        state.starts_with("pg_database=appdb:owner=app:encoding=UTF8:"),
+        "{state}"
+    );
+    assert!(pg.login("app", "s3cret-pw", "appdb"));
+    let mut other = pg.resource(ResourceType::PgRole, "other");
+    other.attributes = vec!["LOGIN".to_string()];
+    other.password = Some("other-pw".to_string());
+    run_ok(&pg_role::apply_script(&other));
+    assert!(pg.login("other", "other-pw", "postgres"));
+    assert!(!pg.login("other", "other-pw", "appdb"));
+
+    let mut ext = pg.resource(ResourceType::PgExtension, "pgcrypto");
+    ext.database = Some("appdb".to_string());
+    if bash("test -f \"$(pg_config --sharedir 2>/dev/null)/extension/pgcrypto.control\" || ls /usr/share/postgresql/*/extension/pgcrypto.control").status.success() {
+        assert_eq!(run_ok(&pg_extension::check_script(&ext)), "missing:pgcrypto");
+        run_ok(&pg_extension::apply_script(&ext));
+        run_ok(&pg_extension::apply_script(&ext));
+        assert!(run_ok(&pg_extension::state_query_script(&ext)).starts_with("pg_extension=pgcrypto:"));
+        ext.state = Some("absent".to_string());
+        run_ok(&pg_extension::apply_script(&ext));
+        assert_eq!(
+            run_ok(&pg_extension::state_query_script(&ext)),
+            "pg_extension=MISSING:pgcrypto"
+        );
+    }
+
+    db.state = Some("absent".to_string());
+    run_ok(&pg_database::apply_script(&db));
+    assert_eq!(run_ok(&pg_database::check_script(&db)), "missing:appdb");
+    app.state = Some("absent".to_string());
+    run_ok(&pg_role::apply_script(&app));
+    assert_eq!(
+        run_ok(&pg_role::state_query_script(&app)),
+        "pg_role=MISSING:app"
+    );
+}
@@ -0,0 +1,150 @@
+//! Tests for the system (host identity) resource handler.
+
+use super::system::*;
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+
+fn make_system() -> Resource {
+    Resource {
+        resource_type: ResourceType::System,
+        machine: MachineTarget::Single("web".to_string()),
+        hostname: Some("web1.example.com".to_string()),
+        timezone: Some("Europe/Berlin".to_string()),
+        locale: Some("en_US.UTF-8".to_string()),
+        keyboard: Some("de-latin1".to_string()),
+        ntp_servers: vec!["0.pool.ntp.org".to_string(), "10.0.0.1".to_string()],
+        ..Default::default()
+    }
+}
+
+fn lint_ok(r: &Resource) {
+    for script in [check_script(r), apply_script(r), state_query_script(r)] {
+        if let Err(e) = crate::core::purifier::validate_script(&script) {
+            panic!("lint failed: {e}\n{script}");
+        }
+    }
+}
+
+#[test]
+fn test_hostname_and_hosts_entry() {
+    let script = apply_script(&make_system());
+    assert!(script.contains("hostnamectl set-hostname 'web1.example.com'"));
+    assert!(script.contains("[ \"$(hostname)\" != 'web1.example.com' ]"));
+    assert!(script.contains(
+        "{ grep -v '^127\\.0\\.1\\.1[[:space:]]' /etc/hosts || true; echo '127.0.1.1 web1.example.com web1'; } | forjar_sys_write /etc/hosts"
+    ));
+    assert_eq!(hosts_line("db"), "127.0.1.1 db");
+}
+
+#[test]
+fn test_timezone_locale_keyboard() {
+    let script = apply_script(&make_system());
+    assert!(script.contains("if [ ! -e '/usr/share/zoneinfo/Europe/Berlin' ]"));
+    assert!(script.contains("timedatectl set-timezone 'Europe/Berlin'"));
+    assert!(script.contains("if ! grep -qx 'en_us.utf8' <<< \"$LOCALES\"; then"));
+    assert!(script.contains("echo 'en_US.UTF-8 UTF-8' >> /etc/locale.gen"));
+    assert!(script.contains("localedef -i 'en_US' -f 'UTF-8' 'en_US.UTF-8'"));
+    assert!(script.contains("localectl set-locale 'LANG=en_US.UTF-8'"));
+    assert!(script.contains("localectl set-keymap 'de-latin1'"));
+}
+
+#[test]
+fn test_only_declared_settings_are_managed() {
+    let r = Resource {
+        timezone: None,
+        locale: None,
+        keyboard: None,
+        ntp_servers: Vec::new(),
+        ..make_system()
+    };
+    let script = apply_script(&r);
+    assert!(script.contains("hostnamectl"));
+    assert!(!script.contains("timedatectl"));
+    assert!(!script.contains("localectl"));
+    let query = state_query_script(&r);
+    assert!(query.contains("hostname="));
+    assert!(!query.contains("timezone="));
+}
+
+#[test]
+fn test_timesyncd_dropin() {
+    let script = apply_script(&make_system());
+    assert!(script.contains(
+        "forjar_sys_write '/etc/systemd/timesyncd.conf.d/forjar.conf' <<'FORJAR_EOF'\n\
+         [Time]\nNTP=0.pool.ntp.org 10.0.0.1\nFORJAR_EOF"
+    ));
+    assert!(script.contains("systemctl restart systemd-timesyncd"));
+    assert!(script.contains("timedatectl set-ntp true"));
+    assert!(!script.contains("chrony"));
+}
+
+#[test]
+fn test_chrony_sources() {
+    let mut r = make_system();
+    r.provider = Some("chrony".to_string());
+    let script = apply_script(&r);
+    assert!(script.contains(
+        "forjar_sys_write '/etc/chrony/sources.d/forjar.sources' <<'FORJAR_EOF'\n\
+         server 0.pool.ntp.org iburst\nserver 10.0.0.1 iburst\nFORJAR_EOF"
+    ));
+    assert!(script.contains("echo 'sourcedir /etc/chrony/sources.d' >> \"$CHRONY_CONF\""));
+    assert!(script.contains("chronyc reload sources"));
+    assert!(!script.contains("timesyncd"));
+    assert!(!state_query_script(&r).contains("timedatectl show -p NTP"));
+}
+
+#[test]
+fn test_absent_removes_ntp_config_only() {
+    let mut r = make_system();
+    r.state = Some("absent".to_string());
+    let script = apply_script(&r);
+    assert!(script.contains("unlink '/etc/systemd/timesyncd.conf.d/forjar.conf'"));
+    assert!(script.contains("unlink '/etc/chrony/sources.d/forjar.sources'"));
+    assert!(!script.contains("hostnamectl"));
+}
+
+#[test]
+fn test_state_query_and_check() {
+    let query = state_query_script(&make_system());
+    for key in [
+        "hostname=",
+        "hostname_live=",
+        "hosts=",
+        "timezone=",
+        "locale_generated=",
+        "lang=",
+        "keymap=",
+        "ntp_config=",
+        "ntp=",
+    ] {
+        assert!(query.contains(&format!("echo \"{key}")), "missing {key}");
+    }
+    let check = check_script(&make_system());
+    assert!(check.contains(
+        "if [ \"$CUR_HOST\" = 'web1.example.com' ] && [ \"$CUR_TZ\" = 'Europe/Berlin' ]"
+    ));
+    assert!(check.contains("echo 'mismatch:system'"));
+    assert_eq!(check_script(&Resource::default()), "echo 'match:system'");
+    assert_eq!(
+        state_query_script(&Resource::default()),
+        "echo 'system=unmanaged'"
+    );
+}
+
+#[test]
+fn test_locale_helpers() {
+    assert_eq!(normalize_locale("de_DE.UTF-8"), "de_de.utf8");
+    assert_eq!(split_locale("de_DE.ISO-8859-1"), ("de_DE", "ISO-8859-1"));
+    assert_eq!(split_locale("C"), ("C", "UTF-8"));
+    assert_eq!(ntp_provider(&Resource::default()), "timesyncd");
+}
+
+#[test]
+fn test_system_scripts_pass_lint() {
+    let mut chrony = make_system();
+    chrony.provider = Some("chrony".to_string());
+    let mut absent = make_system();
+    absent.state = Some("absent".to_string());
+    for r in [make_system(), chrony, absent, Resource::default()] {
+        lint_ok(&r);
+    }
+}
@@ -0,0 +1,185 @@
+//! Tests for the wait_for readiness gate.
+
+use super::wait_for::*;
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+
+fn make_wait() -> Resource {
+    Resource {
+        resource_type: ResourceType::WaitFor,
+        machine: MachineTarget::Single("m1".to_string()),
+        ..Default::default()
+    }
+}
+
+fn port_wait() -> Resource {
+    let mut r = make_wait();
+    r.port = Some("5432".to_string());
+    r
+}
+
+#[test]
+fn test_probe_selection() {
+    let r = port_wait();
+    assert_eq!(
+        probe(&r),
+        Some(Probe::Port {
+            host: "127.0.0.1",
+            port: "5432"
+        })
+    );
+
+    let mut r = make_wait();
+    r.url = Some("http://localhost/health".to_string());
+    assert_eq!(
+        probe(&r),
+        Some(Probe::Http {
+            url: "http://localhost/health",
+            status: 200,
+            pattern: None
+        })
+    );
+
+    let mut r = make_wait();
+    r.path = Some("/var/log/pg.log".to_string());
+    assert_eq!(
+        probe(&r),
+        Some(Probe::File {
+            path: "/var/log/pg.log"
+        })
+    );
+    r.pattern = Some("ready".to_string());
+    assert!(matches!(probe(&r), Some(Probe::Log { .. })));
+
+    assert_eq!(probe(&make_wait()), None);
+}
+
+#[test]
+fn test_port_probe_with_host() {
+    let mut r = port_wait();
+    r.host = Some("db.internal".to_string());
+    let script = apply_script(&r);
+    assert!(script.contains("until (: <'/dev/tcp/db.internal/5432') 2>/dev/null; do"));
+    assert!(script.contains("echo 'ready:tcp:db.internal:5432'"));
+}
+
+#[test]
+fn test_http_probe_status_and_body() {
+    let mut r = make_wait();
+    r.url = Some("http://localhost:8080/health".to_string());
+    r.expect_status = Some(204);
+    r.pattern = Some("\"status\":\"ok\"".to_string());
+    let script = check_script(&r);
+    assert!(script.contains("-w '%{http_code}'"));
+    assert!(script.contains("= '204' ]"));
+    assert!(script.contains("| grep -qE '\"status\":\"ok\"'"));
+    assert!(script.contains("exists:http:http://localhost:8080/health"));
+}
+
+#[test]
+fn test_log_file_and_command_probes() {
+    let mut r = make_wait();
+    r.path = Some("/var/log/postgresql/main.log".to_string());
+    r.pattern = Some("ready to accept connections".to_string());
+    assert!(apply_script(&r)
+        .contains("grep -qE 'ready to accept connections' '/var/log/postgresql/main.log'"));
+
+    r.pattern = None;
+    assert!(apply_script(&r).contains("until [ -e '/var/log/postgresql/main.log' ]; do"));
+
+    let mut r = make_wait();
+    r.command = Some("pg_isready -h localhost".to_string());
+    assert!(apply_script(&r).contains("until ( pg_isready -h localhost ) >/dev/null 2>&1; do"));
+}
+
+#[test]
+fn test_apply_defaults_timeout_and_interval() {
+    let script = apply_script(&port_wait());
+    assert!(script.contains(&format!("-ge {DEFAULT_TIMEOUT_SECS} ]")));
+    assert!(script.contains(&format!("DELAY={DEFAULT_INTERVAL_SECS}")));
+    assert!(script.contains("not ready after 60s' >&2"));
+    assert!(script.contains("exit 1"));
+    assert!(!script.contains("DELAY * "), "no backoff by default");
+}
+
+#[test]
+fn test_apply_backoff_capped() {
+    let mut r = port_wait();
+    r.timeout = Some(300);
+    r.interval = Some(1);
+    r.backoff = Some(2);
+    let script = apply_script(&r);
+    assert!(script.contains("\"$WAITED\" -ge 300 ]"));
+    assert!(script.contains("DELAY=1"));
+    assert!(script.contains("DELAY=$(( DELAY * 2 ))"));
+    assert!(script.contains(&format!("-gt {MAX_INTERVAL_SECS} ]")));
+}
+
+#[test]
+fn test_absent_and_missing_probe() {
+    let mut r = port_wait();
+    r.state = Some("absent".to_string());
+    assert_eq!(apply_script(&r), "echo 'skip:tcp:127.0.0.1:5432'");
+    let script = apply_script(&make_wait());
+    assert!(script.contains("ERROR: wait_for needs one of"));
+}
+
+#[test]
+fn test_state_query_is_constant() {
+    let r = port_wait();
+    assert_eq!(state_query_script(&r), "echo 'wait_for=tcp:127.0.0.1:5432'");
+    assert!(!state_query_script(&r).contains("/dev/tcp"));
+}
+
+#[test]
+fn test_wait_for_scripts_pass_lint() {
+    let mut http = make_wait();
+    http.url = Some("http://localhost/health".to_string());
+    http.pattern = Some("ok".to_string());
+    let mut log = make_wait();
+    log.path = Some("/var/log/app.log".to_string());
+    log.pattern = Some("started".to_string());
+    let mut cmd = make_wait();
+    cmd.command = Some("pg_isready".to_string());
+    let mut backoff = port_wait();
+    backoff.backoff = Some(2);
+    for r in [port_wait(), http, log, cmd, backoff] {
+        for script in [check_script(&r), apply_script(&r), state_query_script(&r)] {
+            assert!(
+                crate::core::purifier::validate_script(&script).is_ok(),
+                "lint failed: {script}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_wait_for_yaml_parsing() {
+    let yaml = r#"
+type: wait_for
+machine: m1
+url: http://localhost:8080/health
+expect_status: 200
+pattern: ok
+timeout: 120
+interval: 1
+backoff: 2
+depends_on: [api]
+"#;
+    let r: Resource = serde_yaml_ng::from_str(yaml).unwrap();
+    assert_eq!(r.resource_type, ResourceType::WaitFor);
+    assert_eq!(r.expect_status, Some(200));
+    assert_eq!(r.interval, Some(1));
+    assert_eq!(r.backoff, Some(2));
+}
+
+#[test]
+fn test_wait_for_hash_includes_probe_settings() {
+    use crate::core::planner::hash_desired_state;
+    let a = port_wait();
+    let mut b = port_wait();
+    b.backoff = Some(2);
+    let mut c = port_wait();
+    c.host = Some("db".to_string());
+    assert_ne!(hash_desired_state(&a), hash_desired_state(&b));
+    assert_ne!(hash_desired_state(&a), hash_desired_state(&c));
+}
@@ -0,0 +1,142 @@
+//! Tests for the wireguard_interface and wireguard_peer resource handlers.
+
+use super::{wireguard_interface, wireguard_peer};
+use crate::core::types::{MachineTarget, Resource, ResourceType};
+
+const HUB_KEY: &str = "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=";
+
+fn make_iface() -> Resource {
+    Resource {
+        resource_type: ResourceType::WireguardInterface,
+        machine: MachineTarget::Single("hub".to_string()),
+        name: Some("wg0".to_string()),
+        addresses: vec!["10.10.0.1/24".to_string()],
+        port: Some("51820".to_string()),
+        ..Default::default()
+    }
+}
+
+fn make_peer() -> Resource {
+    Resource {
+        resource_type: ResourceType::WireguardPeer,
+        machine: MachineTarget::Single("spoke".to_string()),
+        interface: Some("wg0".to_string()),
+        name: Some("hub".to_string()),
+        public_key: Some(HUB_KEY.to_string()),
+        endpoint: Some("hub.example.com:51820".to_string()),
+        allowed_ips: vec!["10.10.0.0/24".to_string()],
+        persistent_keepalive: Some(25),
+        ..Default::default()
+    }
+}
+
+fn lint_ok(scripts: [String; 3]) {
+    for script in scripts {
+        if let Err(e) = crate::core::purifier::validate_script(&script) {
+            panic!("lint failed: {e}\n{script}");
+        }
+    }
+}
+
+#[test]
+fn test_wg_interface_generates_key_on_target() {
+    let script = wireguard_interface::apply_script(&make_iface());
+    assert!(script.contains("umask 077"));
+    assert!(script.contains(
+        "if [ ! -s '/etc/wireguard/wg0.key' ]; then wg genkey > '/etc/wireguard/wg0.key'; fi"
+    ));
+    assert!(script.contains("PUB=\"$(wg pubkey < '/etc/wireguard/wg0.key')\""));
+    // Only the public key is exported, as the last line
+    assert!(script.ends_with("echo \"FORJAR_OUTPUT public_key=$PUB\""));
+    assert!(!script.contains("PrivateKey = $(wg genkey"));
+}
+
+#[test]
+fn test_wg_interface_conf_and_live_sync() {
+    let script = wireguard_interface::apply_script(&make_iface());
+    assert!(script.contains("Address = 10.10.0.1/24\nListenPort = 51820\n"));
+    assert!(script.contains("PostUp = wg set %i private-key /etc/wireguard/wg0.key"));
+    assert!(script.contains("PostUp = wg addconf %i /etc/wireguard/wg0.peers.conf"));
+    assert!(script.contains("wg syncconf 'wg0' \"$SYNC\""));
+    assert!(script.contains("echo 'ListenPort = 51820'"));
+    assert!(script.contains("*' 10.10.0.1/24 '*) ;; *) ip address add '10.10.0.1/24' dev 'wg0' ;;"));
+    assert!(script.contains("systemctl start 'wg-quick@wg0'"));
+    assert!(script.contains("systemctl enable 'wg-quick@wg0'"));
+    // A running interface is never restarted
+    assert!(!script.contains("systemctl restart"));
+    assert!(!script.contains("wg-quick down"));
+}
+
+#[test]
+fn test_wg_interface_absent_and_state_query() {
+    let mut r = make_iface();
+    r.state = Some("absent".to_string());
+    let script = wireguard_interface::apply_script(&r);
+    assert!(script.contains("systemctl disable --now 'wg-quick@wg0'"));
+    assert!(script.contains("'/etc/wireguard/wg0.key'"));
+    let query = wireguard_interface::state_query_script(&make_iface());
+    assert!(query.contains("wg show 'wg0' dump | awk 'NR == 1 { print \"interface\", $2, $3 }'"));
+    assert!(query.contains("wireguard=MISSING:wg0"));
+}
+
+#[test]
+fn test_wg_peer_dropin_and_sync() {
+    let script = wireguard_peer::apply_script(&make_peer());
+    assert!(script.contains(&format!(
+        "cat > '/etc/wireguard/wg0.peers.d/hub.conf' <<'FORJAR_EOF'\n[Peer]\nPublicKey = {HUB_KEY}\n\
+         Endpoint = hub.example.com:51820\nAllowedIPs = 10.10.0.0/24\nPersistentKeepalive = 25\nFORJAR_EOF"
+    )));
+    assert!(script.contains("forjar_wg_sync\nfi"));
+    assert!(script.contains("wg syncconf 'wg0' \"$SYNC\""));
+    assert!(!script.contains("ListenPort"));
+}
+
+#[test]
+fn test_wg_peer_name_defaults_to_key_prefix() {
+    let mut r = make_peer();
+    r.name = None;
+    assert_eq!(wireguard_peer::peer_name(&r), "xTIBA5rboUvnH4ht");
+    let mut absent = make_peer();
+    absent.state = Some("absent".to_string());
+    let script = wireguard_peer::apply_script(&absent);
+    assert!(script.contains("rm -f '/etc/wireguard/wg0.peers.d/hub.conf'"));
+    assert!(!script.contains("[Peer]"));
+}
+
+#[test]
+fn test_wg_peer_empty_key_fails() {
+    let mut r = make_peer();
+    r.public_key = Some(String::new());
+    let script = wireguard_peer::apply_script(&r);
+    assert!(script.contains("has no public_key yet"));
+    assert!(script.ends_with("exit 1"));
+}
+
+#[test]
+fn test_wg_peer_state_query_ignores_roaming_endpoint() {
+    let with_endpoint = wireguard_peer::state_query_script(&make_peer());
+    assert!(with_endpoint.contains(&format!(
+        "awk -v k='{HUB_KEY}' '$1 == k {{ print $1, $3, $4, $8 }}'"
+    )));
+    let mut roaming = make_peer();
+    roaming.endpoint = None;
+    let query = wireguard_peer::state_query_script(&roaming);
+    assert!(query.contains("{ print $1, $4, $8 }"));
+    assert!(query.contains("wireguard_peer=MISSING:hub"));
+}
+
+#[test]
+fn test_wg_scripts_pass_lint() {
+    let iface = make_iface();
+    lint_ok([
+        wireguard_interface::check_script(&iface),
+        wireguard_interface::apply_script(&iface),
+        wireguard_interface::state_query_script(&iface),
+    ]);
+    let peer = make_peer();
+    lint_ok([
+        wireguard_peer::check_script(&peer),
+        wireguard_peer::apply_script(&peer),
+        wireguard_peer::state_query_script(&peer),
+    ]);
+}
@@ -0,0 +1,217 @@
+//! Tests for the ZFS pool, dataset and snapshot policy resource handlers.
+
+use super::{zfs_dataset, zfs_pool, zfs_snapshot_policy};
+use crate::core::types::{LifecycleRules, MachineTarget, Resource, ResourceType};
+
+fn make_pool() -> Resource {
+    Resource {
+        resource_type: ResourceType::ZfsPool,
+        machine: MachineTarget::Single("store".to_string()),
+        name: Some("tank".to_string()),
+        vdevs: vec![
+            "mirror /dev/sdb /dev/sdc".to_string(),
+            "log /dev/nvme0n1".to_string(),
+        ],
+        properties: [("ashift".to_string(), "12".to_string())].into(),
+        ..Default::default()
+    }
+}
+
+fn make_dataset() -> Resource {
+    Resource {
+        resource_type: ResourceType::ZfsDataset,
+        machine: MachineTarget::Single("store".to_string()),
+        name: Some("tank/models".to_string()),
+        properties: [
+            ("compression", "zstd"),
+            ("recordsize", "1M"),
+            ("quota", "500G"),
+            ("mountpoint", "/srv/models"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect(),
+        ..Default::default()
+    }
+}
+
+fn make_policy() -> Resource {
+    Resource {
+        resource_type: ResourceType::ZfsSnapshotPolicy,
+        machine: MachineTarget::Single("store".to_string()),
+        name: Some("hourly".to_string()),
+        dataset: Some("tank/models".to_string()),
+        keep: Some(48),
+        recursive: true,
+        ..Default::default()
+    }
+}
+
+fn protected(mut r: Resource) -> Resource {
+    r.state = Some("absent".to_string());
+    r.lifecycle = Some(LifecycleRules {
+        prevent_destroy: true,
+        ..Default::default()
+    });
+    r
+}
+
+fn lint_ok(script: &str) {
+    if let Err(e) = crate::core::purifier::validate_script(script) {
+        panic!("lint failed: {e}\n{script}");
+    }
+}
+
+#[test]
+fn test_pool_create_quotes_vdevs() {
+    let script = zfs_pool::apply_script(&make_pool());
+    assert!(script.contains(
+        "zpool create -o 'ashift=12' \"$POOL\" 'mirror' '/dev/sdb' '/dev/sdc' 'log' '/dev/nvme0n1'"
+    ));
+    assert!(script.contains("zpool set 'ashift=12' \"$POOL\""));
+    let mut forced = make_pool();
+    forced.force = true;
+    assert!(zfs_pool::apply_script(&forced).contains("zpool create -f -o"));
+}
+
+#[test]
+fn test_pool_destroy_requires_force() {
+    let mut r = make_pool();
+    r.state = Some("absent".to_string());
+    let script = zfs_pool::apply_script(&r);
+    assert!(script.contains("refusing to destroy pool tank without force"));
+    assert!(!script.contains("zpool destroy"));
+    r.force = true;
+    assert!(zfs_pool::apply_script(&r).contains("zpool destroy 'tank'"));
+
+    let mut guarded = protected(make_pool());
+    guarded.force = true;
+    let script = zfs_pool::apply_script(&guarded);
+    assert!(script.contains("lifecycle.prevent_destroy is set"));
+    assert!(!script.contains("zpool destroy"));
+}
+
+#[test]
+fn test_pool_state_query_reports_layout() {
+    let query = zfs_pool::state_query_script(&make_pool());
+    assert!(query.contains("zpool list -H -v -P -o name 'tank'"));
+    assert!(query.contains("zpool get -H -p -o property,value ashift 'tank'"));
+    assert!(query.contains("zfs_pool=MISSING:tank"));
+}
+
+#[test]
+fn test_dataset_create_and_properties() {
+    let script = zfs_dataset::apply_script(&make_dataset());
+    assert!(script.contains(
+        "zfs create -p -o 'compression=zstd' -o 'mountpoint=/srv/models' -o 'quota=500G' -o 'recordsize=1M' \"$DS\""
+    ));
+    // Sizes are compared in `zfs get -p` form
+    assert!(script.contains("forjar_zfs_prop 'quota' '536870912000' '500G'"));
+    assert!(script.contains("forjar_zfs_prop 'recordsize' '1048576' '1M'"));
+    assert!(script.contains("forjar_zfs_prop 'compression' 'zstd' 'zstd'"));
+}
+
+#[test]
+fn test_dataset_parsable_values() {
+    assert_eq!(zfs_dataset::parsable_value("quota", "none"), "0");
+    assert_eq!(
+        zfs_dataset::parsable_value("refquota", "10G"),
+        "10737418240"
+    );
+    assert_eq!(zfs_dataset::parsable_value("atime", "off"), "off");
+    assert!(zfs_dataset::is_size_property("special_small_blocks"));
+}
+
+#[test]
+fn test_dataset_absent_respects_prevent_destroy() {
+    let mut r = make_dataset();
+    r.state = Some("absent".to_string());
+    assert!(zfs_dataset::apply_script(&r).contains("zfs destroy 'tank/models'"));
+    r.force = true;
+    assert!(zfs_dataset::apply_script(&r).contains("zfs destroy -r 'tank/models'"));
+
+    let script = zfs_dataset::apply_script(&protected(make_dataset()));
+    assert!(script.contains("refusing to destroy dataset tank/models"));
+    assert!(!script.contains("zfs destroy"));
+}
+
+#[test]
+fn test_dataset_state_query_declared_properties() {
+    let query = zfs_dataset::state_query_script(&make_dataset());
+    assert!(query.contains(
+        "zfs get -H -p -o property,value type,compression,mountpoint,quota,recordsize 'tank/models'"
+    ));
+}
+
+#[test]
+fn test_snapshot_policy_script_and_cron() {
+    let r = make_policy();
+    let script = zfs_snapshot_policy::apply_script(&r);
+    assert!(script.contains(
+        "cat > '/usr/local/lib/forjar/zfs-snapshot-tank-models-hourly.sh' <<'FORJAR_EOF'"
+    ));
+    assert!(script.contains("SNAP='tank/models@forjar-hourly-pending'"));
+    assert!(script.contains("zfs snapshot -r \"$SNAP\""));
+    assert!(script.contains("zfs rename -r \"$SNAP\" 'tank/models@forjar-hourly-'\"$CREATED\""));
+    assert!(script.contains("grep -F '@forjar-hourly-' | tail -n +49"));
+    assert!(script.contains("zfs destroy -r \"$snap\""));
+    assert!(script.contains(
+        "0 * * * * root /bin/sh /usr/local/lib/forjar/zfs-snapshot-tank-models-hourly.sh"
+    ));
+    assert!(script.contains("ERROR: dataset tank/models does not exist"));
+}
+
+#[test]
+fn test_snapshot_policy_defaults_and_absent() {
+    let mut r = make_policy();
+    r.name = None;
+    r.keep = None;
+    r.recursive = false;
+    r.schedule = Some("*/15 * * * *".to_string());
+    r.dataset = Some("tank/vm.images".to_string());
+    assert_eq!(zfs_snapshot_policy::slug(&r), "tank-vm-images-auto");
+    let script = zfs_snapshot_policy::snapshot_script(&r);
+    assert!(script.contains("zfs snapshot \"$SNAP\""));
+    assert!(script.contains("zfs rename \"$SNAP\" 'tank/vm.images@forjar-auto-'"));
+    assert!(script.contains("tail -n +25"));
+    assert!(zfs_snapshot_policy::apply_script(&r).contains("*/15 * * * * root"));
+
+    r.state = Some("absent".to_string());
+    let script = zfs_snapshot_policy::apply_script(&r);
+    assert!(script.contains("unlink '/etc/cron.d/forjar-zfs-tank-vm-images-auto'"));
+    assert!(!script.contains("zfs destroy"));
+}
+
+#[test]
+fn test_zfs_scripts_pass_lint() {
+    let mut absent_pool = make_pool();
+    absent_pool.state = Some("absent".to_string());
+    let mut absent_ds = make_dataset();
+    absent_ds.state = Some("absent".to_string());
+    for r in [make_pool(), absent_pool, protected(make_pool())] {
+        for s in [
+            zfs_pool::check_script(&r),
+            zfs_pool::apply_script(&r),
+            zfs_pool::state_query_script(&r),
+        ] {
+            lint_ok(&s);
+        }
+    }
+    for r in [make_dataset(), absent_ds, protected(make_dataset())] {
+        for s in [
+            zfs_dataset::check_script(&r),
+            zfs_dataset::apply_script(&r),
+            zfs_dataset::state_query_script(&r),
+        ] {
+            lint_ok(&s);
+        }
+    }
+    let r = make_policy();
+    for s in [
+        zfs_snapshot_policy::check_script(&r),
+        zfs_snapshot_policy::apply_script(&r),
+        zfs_snapshot_policy::state_query_script(&r),
+    ] {
+        lint_ok(&s);
+    }
+}
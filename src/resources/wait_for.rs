@@ -0,0 +1,164 @@
+//! Readiness gate resource handler.
+//!
+//! `depends_on` only orders scripts; it says nothing about whether the
+//! service behind a resource is ready. A `wait_for` resource polls a probe on
+//! the target until it succeeds or `timeout` expires:
+//! - `port` — TCP connect to `host:port`
+//! - `url` — HTTP status (`expect_status`, default 200) and optional body `pattern`
+//! - `path` + `pattern` — a log line appears in the file
+//! - `path` — the file exists
+//! - `command` — the command exits 0
+//!
+//! The state query is constant, so a satisfied gate never shows up as drift.
+
+use crate::core::types::Resource;
+
+/// Seconds between attempts when `interval` is unset.
+pub const DEFAULT_INTERVAL_SECS: u64 = 2;
+/// Seconds to wait when `timeout` is unset.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 60;
+/// Upper bound for the interval once backoff applies.
+pub const MAX_INTERVAL_SECS: u64 = 60;
+
+/// The probe a `wait_for` resource runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Probe<'a> {
+    /// TCP connect to host:port.
+    Port { host: &'a str, port: &'a str },
+    /// HTTP GET with expected status and optional body regex.
+    Http {
+        url: &'a str,
+        status: u16,
+        pattern: Option<&'a str>,
+    },
+    /// Regex match in a log file.
+    Log { path: &'a str, pattern: &'a str },
+    /// File exists.
+    File { path: &'a str },
+    /// Command exits 0.
+    Command { command: &'a str },
+}
+
+/// Select the probe from the resource fields (`port`, `url`, `command`, `path`).
+pub fn probe(resource: &Resource) -> Option<Probe<'_>> {
+    if let Some(ref port) = resource.port {
+        let host = resource.host.as_deref().unwrap_or("127.0.0.1");
+        return Some(Probe::Port { host, port });
+    }
+    if let Some(ref url) = resource.url {
+        return Some(Probe::Http {
+            url,
+            status: resource.expect_status.unwrap_or(200),
+            pattern: resource.pattern.as_deref(),
+        });
+    }
+    if let Some(ref command) = resource.command {
+        return Some(Probe::Command { command });
+    }
+    let path = resource.path.as_deref()?;
+    Some(match resource.pattern.as_deref() {
+        Some(pattern) => Probe::Log { path, pattern },
+        None => Probe::File { path },
+    })
+}
+
+/// Short description used in status tokens and error messages.
+pub fn describe(probe: &Probe<'_>) -> String {
+    match probe {
+        Probe::Port { host, port } => format!("tcp:{host}:{port}"),
+        Probe::Http { url, .. } => format!("http:{url}"),
+        Probe::Log { path, .. } => format!("log:{path}"),
+        Probe::File { path } => format!("file:{path}"),
+        Probe::Command { .. } => "command".to_string(),
+    }
+}
+
+/// Shell condition that succeeds when the probe is satisfied.
+fn condition(probe: &Probe<'_>) -> String {
+    match probe {
+        Probe::Port { host, port } => format!("(: <'/dev/tcp/{host}/{port}') 2>/dev/null"),
+        Probe::Http {
+            url,
+            status,
+            pattern,
+        } => {
+            let status_check = format!(
+                "[ \"$(curl -sS -o /dev/null -w '%{{http_code}}' --max-time 5 '{url}' 2>/dev/null)\" = '{status}' ]"
+            );
+            match pattern {
+                Some(p) => format!(
+                    "{status_check} && curl -sS --max-time 5 '{url}' 2>/dev/null | grep -qE '{p}'"
+                ),
+                None => status_check,
+            }
+        }
+        Probe::Log { path, pattern } => format!("grep -qE '{pattern}' '{path}' 2>/dev/null"),
+        Probe::File { path } => format!("[ -e '{path}' ]"),
+        Probe::Command { command } => format!("( {command} ) >/dev/null 2>&1"),
+    }
+}
+
+/// Generate shell script that runs the probe once.
+pub fn check_script(resource: &Resource) -> String {
+    match probe(resource) {
+        Some(p) => {
+            let desc = describe(&p);
+            format!(
+                "if {}; then echo 'exists:{desc}'; else echo 'missing:{desc}'; fi",
+                condition(&p)
+            )
+        }
+        None => "echo 'missing:wait_for'".to_string(),
+    }
+}
+
+/// Generate shell script that polls the probe until it succeeds or times out.
+///
+/// The timeout counts sleep time only (no wall clock), which keeps the script
+/// deterministic; probes themselves are bounded (`curl --max-time 5`).
+pub fn apply_script(resource: &Resource) -> String {
+    let Some(p) = probe(resource) else {
+        return "echo 'ERROR: wait_for needs one of port, url, path or command' >&2; exit 1"
+            .to_string();
+    };
+    let desc = describe(&p);
+    if resource.state.as_deref() == Some("absent") {
+        return format!("echo 'skip:{desc}'");
+    }
+
+    let timeout = resource.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS);
+    let interval = resource.interval.unwrap_or(DEFAULT_INTERVAL_SECS).max(1);
+    let backoff = resource.backoff.unwrap_or(1);
+    let mut lines = vec![
+        "set -euo pipefail".to_string(),
+        "WAITED=0".to_string(),
+        format!("DELAY={interval}"),
+        format!("until {}; do", condition(&p)),
+        format!("  if [ \"$WAITED\" -ge {timeout} ]; then"),
+        format!("    echo 'ERROR: wait_for {desc} not ready after {timeout}s' >&2"),
+        "    exit 1".to_string(),
+        "  fi".to_string(),
+        "  sleep \"$DELAY\"".to_string(),
+        "  WAITED=$(( WAITED + DELAY ))".to_string(),
+    ];
+    if backoff > 1 {
+        lines.push(format!("  DELAY=$(( DELAY * {backoff} ))"));
+        lines.push(format!(
+            "  if [ \"$DELAY\" -gt {MAX_INTERVAL_SECS} ]; then DELAY={MAX_INTERVAL_SECS}; fi"
+        ));
+    }
+    lines.push("done".to_string());
+    lines.push(format!("echo 'ready:{desc}'"));
+    lines.join("\n")
+}
+
+/// Generate shell to query state (for BLAKE3 hashing).
+///
+/// Deliberately constant: a gate that was satisfied once stays converged, so
+/// the service it guards going down is reported by that service, not here.
+pub fn state_query_script(resource: &Resource) -> String {
+    let desc = probe(resource)
+        .map(|p| describe(&p))
+        .unwrap_or_else(|| "none".to_string());
+    format!("echo 'wait_for={desc}'")
+}
@@ -0,0 +1,154 @@
+//! WireGuard interface resource handler.
+//!
+//! The private key is generated on the target (`wg genkey`) and never leaves
+//! it; the apply script exports only the public key as
+//! `FORJAR_OUTPUT public_key=...`, which forjar publishes in the global lock
+//! as `<resource_id>.public_key` for peers on other machines to consume.
+//!
+//! Layout under `/etc/wireguard`:
+//! - `<name>.key` / `<name>.pub` — key pair (0600)
+//! - `<name>.conf` — wg-quick config (addresses, port, PostUp loading the key)
+//! - `<name>.peers.d/*.conf` — one `[Peer]` drop-in per `wireguard_peer`
+//! - `<name>.peers.conf` — peers assembled from the drop-ins
+//!
+//! A running interface is reconciled with `wg syncconf`, which updates
+//! peers and the listen port in place without tearing down tunnels.
+
+use crate::core::types::Resource;
+
+/// Directory holding WireGuard configuration.
+pub const WG_DIR: &str = "/etc/wireguard";
+
+/// Interface name (`name`, default `wg0`).
+pub fn iface_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("wg0")
+}
+
+/// Shell function `forjar_wg_sync` that assembles the peer drop-ins and,
+/// when the interface is up, applies them live with `wg syncconf`.
+pub fn sync_function(iface: &str, port: Option<&str>) -> String {
+    let port_line = port
+        .map(|p| format!("\x20   echo 'ListenPort = {p}'\n"))
+        .unwrap_or_default();
+    format!(
+        "forjar_wg_sync() {{\n\
+         \x20 PEERS='{WG_DIR}/{iface}.peers.conf'\n\
+         \x20 : > \"$PEERS.tmp\"\n\
+         \x20 for f in '{WG_DIR}/{iface}.peers.d'/*.conf; do\n\
+         \x20   if [ -f \"$f\" ]; then cat \"$f\" >> \"$PEERS.tmp\"; fi\n\
+         \x20 done\n\
+         \x20 mv \"$PEERS.tmp\" \"$PEERS\"\n\
+         \x20 if ! ip link show '{iface}' >/dev/null 2>&1; then return 0; fi\n\
+         \x20 SYNC=\"$(mktemp)\"\n\
+         \x20 {{\n\
+         \x20   echo '[Interface]'\n\
+         \x20   echo \"PrivateKey = $(cat '{WG_DIR}/{iface}.key')\"\n\
+         {port_line}\
+         \x20   cat \"$PEERS\"\n\
+         \x20 }} > \"$SYNC\"\n\
+         \x20 wg syncconf '{iface}' \"$SYNC\"\n\
+         \x20 unlink \"$SYNC\"\n\
+         }}"
+    )
+}
+
+/// wg-quick configuration for the interface.
+fn wg_quick_conf(resource: &Resource, iface: &str) -> String {
+    let mut lines = vec!["[Interface]".to_string()];
+    if !resource.addresses.is_empty() {
+        lines.push(format!("Address = {}", resource.addresses.join(", ")));
+    }
+    if let Some(ref port) = resource.port {
+        lines.push(format!("ListenPort = {port}"));
+    }
+    lines.push(format!(
+        "PostUp = wg set %i private-key {WG_DIR}/{iface}.key"
+    ));
+    lines.push(format!(
+        "PostUp = wg addconf %i {WG_DIR}/{iface}.peers.conf"
+    ));
+    lines.join("\n")
+}
+
+/// Generate shell to check whether the interface is configured and up.
+pub fn check_script(resource: &Resource) -> String {
+    let iface = iface_name(resource);
+    format!(
+        "if [ -s '{WG_DIR}/{iface}.key' ] && ip link show '{iface}' >/dev/null 2>&1; then echo 'exists:{iface}'; else echo 'missing:{iface}'; fi"
+    )
+}
+
+/// Generate shell to converge the interface.
+pub fn apply_script(resource: &Resource) -> String {
+    let iface = iface_name(resource);
+    let mut lines = vec!["set -euo pipefail".to_string()];
+
+    if resource.state.as_deref() == Some("absent") {
+        lines.push(format!(
+            "systemctl disable --now 'wg-quick@{iface}' 2>/dev/null || true"
+        ));
+        lines.push(format!(
+            "if ip link show '{iface}' >/dev/null 2>&1; then ip link delete '{iface}'; fi"
+        ));
+        lines.push(format!(
+            "rm -rf '{WG_DIR}/{iface}.conf' '{WG_DIR}/{iface}.key' '{WG_DIR}/{iface}.pub' \
+             '{WG_DIR}/{iface}.peers.conf' '{WG_DIR}/{iface}.peers.d'"
+        ));
+        return lines.join("\n");
+    }
+
+    lines.push("umask 077".to_string());
+    lines.push(format!("mkdir -p '{WG_DIR}/{iface}.peers.d'"));
+    // Generate the key once; the private half never leaves the machine
+    lines.push(format!(
+        "if [ ! -s '{WG_DIR}/{iface}.key' ]; then wg genkey > '{WG_DIR}/{iface}.key'; fi"
+    ));
+    lines.push(format!("PUB=\"$(wg pubkey < '{WG_DIR}/{iface}.key')\""));
+    lines.push(format!("echo \"$PUB\" > '{WG_DIR}/{iface}.pub'"));
+    lines.push(format!(
+        "cat > '{WG_DIR}/{iface}.conf' <<'FORJAR_EOF'\n{}\nFORJAR_EOF",
+        wg_quick_conf(resource, iface)
+    ));
+    lines.push(sync_function(iface, resource.port.as_deref()));
+    lines.push("forjar_wg_sync".to_string());
+
+    // Addresses are added to a live interface; removals apply on restart
+    let mut live = vec![format!(
+        "\x20 ADDRS=\"$(ip -o address show dev '{iface}')\""
+    )];
+    for addr in &resource.addresses {
+        live.push(format!(
+            "\x20 case \"$ADDRS\" in *' {addr} '*) ;; *) ip address add '{addr}' dev '{iface}' ;; esac"
+        ));
+    }
+    lines.push(format!(
+        "if ip link show '{iface}' >/dev/null 2>&1; then\n{}\nelse\n\
+         \x20 systemctl start 'wg-quick@{iface}'\n\
+         fi",
+        live.join("\n")
+    ));
+    if resource.enabled != Some(false) {
+        lines.push(format!(
+            "systemctl enable 'wg-quick@{iface}' 2>/dev/null || true"
+        ));
+    }
+    lines.push("echo \"FORJAR_OUTPUT public_key=$PUB\"".to_string());
+    lines.join("\n")
+}
+
+/// Generate shell to query interface state (for BLAKE3 hashing).
+///
+/// Reads the interface line of `wg show dump` (public key and listen port —
+/// never the private key) plus the assigned addresses. Peers are reported
+/// by their own `wireguard_peer` resources.
+pub fn state_query_script(resource: &Resource) -> String {
+    let iface = iface_name(resource);
+    format!(
+        "if ip link show '{iface}' >/dev/null 2>&1; then\n\
+         \x20 wg show '{iface}' dump | awk 'NR == 1 {{ print \"interface\", $2, $3 }}'\n\
+         \x20 ip -o address show dev '{iface}' | awk '{{ print \"address\", $4 }}' | sort\n\
+         else\n\
+         \x20 echo 'wireguard=MISSING:{iface}'\n\
+         fi"
+    )
+}
@@ -0,0 +1,123 @@
+//! WireGuard peer resource handler.
+//!
+//! Each peer is a `[Peer]` drop-in under `/etc/wireguard/<interface>.peers.d/`.
+//! After writing or removing the drop-in, the interface's peers are
+//! reassembled and applied live with `wg syncconf`, so other tunnels on the
+//! interface stay up. `public_key` is usually the `<resource_id>.public_key`
+//! output exported by a `wireguard_interface` on the other machine.
+
+use super::wireguard_interface::{sync_function, WG_DIR};
+use crate::core::types::Resource;
+
+fn iface(resource: &Resource) -> &str {
+    resource.interface.as_deref().unwrap_or("wg0")
+}
+
+fn public_key(resource: &Resource) -> &str {
+    resource.public_key.as_deref().unwrap_or("")
+}
+
+/// Drop-in name: `name`, or a filename-safe prefix of the public key.
+pub fn peer_name(resource: &Resource) -> String {
+    match resource.name {
+        Some(ref name) => name.clone(),
+        None => public_key(resource)
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .take(16)
+            .collect(),
+    }
+}
+
+fn dropin_path(resource: &Resource) -> String {
+    format!(
+        "{WG_DIR}/{}.peers.d/{}.conf",
+        iface(resource),
+        peer_name(resource)
+    )
+}
+
+/// `[Peer]` section for the drop-in.
+fn peer_section(resource: &Resource) -> String {
+    let mut lines = vec![
+        "[Peer]".to_string(),
+        format!("PublicKey = {}", public_key(resource)),
+    ];
+    if let Some(ref endpoint) = resource.endpoint {
+        lines.push(format!("Endpoint = {endpoint}"));
+    }
+    if !resource.allowed_ips.is_empty() {
+        lines.push(format!("AllowedIPs = {}", resource.allowed_ips.join(", ")));
+    }
+    if let Some(keepalive) = resource.persistent_keepalive {
+        lines.push(format!("PersistentKeepalive = {keepalive}"));
+    }
+    lines.join("\n")
+}
+
+/// Capture this peer's line from `wg show dump` into `$LINE`.
+///
+/// Fields: public key, allowed IPs, keepalive — plus the endpoint when one
+/// is configured (roaming peers change endpoint and must not read as drift).
+fn dump_line(resource: &Resource) -> String {
+    let fields = if resource.endpoint.is_some() {
+        "$1, $3, $4, $8"
+    } else {
+        "$1, $4, $8"
+    };
+    format!(
+        "LINE=\"$(wg show '{}' dump 2>/dev/null | awk -v k='{}' '$1 == k {{ print {fields} }}' || true)\"",
+        iface(resource),
+        public_key(resource)
+    )
+}
+
+/// Generate shell to check whether the peer is configured.
+pub fn check_script(resource: &Resource) -> String {
+    let name = peer_name(resource);
+    format!(
+        "if [ -f '{}' ]; then echo 'exists:{name}'; else echo 'missing:{name}'; fi",
+        dropin_path(resource)
+    )
+}
+
+/// Generate shell to write (or remove) the peer and sync the interface.
+pub fn apply_script(resource: &Resource) -> String {
+    let iface = iface(resource);
+    let absent = resource.state.as_deref() == Some("absent");
+    if !absent && public_key(resource).is_empty() {
+        // e.g. a forjar-state default before the remote interface was applied
+        return format!(
+            "echo 'ERROR: wireguard_peer {} has no public_key yet; apply the remote interface first' >&2; exit 1",
+            peer_name(resource)
+        );
+    }
+    let mut lines = vec!["set -euo pipefail".to_string(), "umask 077".to_string()];
+    if absent {
+        lines.push(format!("rm -f '{}'", dropin_path(resource)));
+    } else {
+        lines.push(format!("mkdir -p '{WG_DIR}/{iface}.peers.d'"));
+        lines.push(format!(
+            "cat > '{}' <<'FORJAR_EOF'\n{}\nFORJAR_EOF",
+            dropin_path(resource),
+            peer_section(resource)
+        ));
+    }
+    // The listen port is left to the interface resource: syncconf keeps
+    // the running port when the file does not set one
+    lines.push(format!("if [ -s '{WG_DIR}/{iface}.key' ]; then"));
+    lines.push(sync_function(iface, None));
+    lines.push("forjar_wg_sync".to_string());
+    lines.push("fi".to_string());
+    lines.join("\n")
+}
+
+/// Generate shell to query peer state (for BLAKE3 hashing).
+pub fn state_query_script(resource: &Resource) -> String {
+    format!(
+        "{}\n\
+         if [ -n \"$LINE\" ]; then echo \"$LINE\"; else echo 'wireguard_peer=MISSING:{}'; fi",
+        dump_line(resource),
+        peer_name(resource)
+    )
+}
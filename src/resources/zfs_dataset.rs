@@ -0,0 +1,143 @@
+//! ZFS dataset resource handler.
+//!
+//! Creates `name` (e.g. `tank/models`) with `zfs create -p`, then keeps the
+//! declared `properties` — compression, recordsize, quota, mountpoint, … —
+//! in line with `zfs set`. Live values come from `zfs get -H -p`, so
+//! size-valued properties are compared in bytes (`500G` matches
+//! `536870912000`).
+//!
+//! `state: absent` destroys the dataset. It is refused while
+//! `lifecycle.prevent_destroy` is set, and only recurses into children and
+//! snapshots with `force`.
+
+use super::disk::parse_size_bytes;
+use crate::core::types::Resource;
+
+/// Properties whose `zfs get -p` value is a byte count.
+const SIZE_PROPERTIES: &[&str] = &[
+    "quota",
+    "refquota",
+    "reservation",
+    "refreservation",
+    "recordsize",
+    "special_small_blocks",
+];
+
+/// Dataset name (`name`).
+pub fn dataset_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("tank/unknown")
+}
+
+/// True for properties compared as byte counts.
+pub fn is_size_property(property: &str) -> bool {
+    SIZE_PROPERTIES.contains(&property)
+}
+
+/// A property value as `zfs get -p` reports it: sizes in bytes, `none` as 0.
+pub fn parsable_value(property: &str, value: &str) -> String {
+    if !is_size_property(property) {
+        return value.to_string();
+    }
+    if value == "none" {
+        return "0".to_string();
+    }
+    parse_size_bytes(value)
+        .map(|bytes| bytes.to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// True when `lifecycle.prevent_destroy` is set.
+pub fn prevent_destroy(resource: &Resource) -> bool {
+    resource
+        .lifecycle
+        .as_ref()
+        .is_some_and(|lifecycle| lifecycle.prevent_destroy)
+}
+
+/// Shell refusing to destroy `what` because of `lifecycle.prevent_destroy`.
+pub fn refuse_destroy_line(what: &str) -> String {
+    format!(
+        "echo \"ERROR: refusing to destroy {what}: lifecycle.prevent_destroy is set\" >&2\nexit 1"
+    )
+}
+
+/// Generate shell to check whether the dataset exists.
+pub fn check_script(resource: &Resource) -> String {
+    let ds = dataset_name(resource);
+    format!(
+        "if zfs list -H -o name '{ds}' >/dev/null 2>&1; then echo 'exists:{ds}'; else echo 'missing:{ds}'; fi"
+    )
+}
+
+/// Generate shell to create the dataset and converge its properties.
+pub fn apply_script(resource: &Resource) -> String {
+    let ds = dataset_name(resource);
+    if resource.state.as_deref() == Some("absent") {
+        let destroy = if prevent_destroy(resource) {
+            refuse_destroy_line(&format!("dataset {ds}"))
+        } else if resource.force {
+            format!("zfs destroy -r '{ds}'")
+        } else {
+            format!("zfs destroy '{ds}'")
+        };
+        return format!(
+            "set -euo pipefail\n\
+             if zfs list -H -o name '{ds}' >/dev/null 2>&1; then\n\
+             {destroy}\n\
+             fi"
+        );
+    }
+
+    let create_opts: String = resource
+        .properties
+        .iter()
+        .map(|(k, v)| format!(" -o '{k}={v}'"))
+        .collect();
+    let mut lines = vec![
+        "set -euo pipefail".to_string(),
+        format!("DS='{ds}'"),
+        format!(
+            "if ! zfs list -H -o name \"$DS\" >/dev/null 2>&1; then\n\
+             \x20 zfs create -p{create_opts} \"$DS\"\n\
+             \x20 echo \"created:$DS\"\n\
+             fi"
+        ),
+    ];
+    if !resource.properties.is_empty() {
+        lines.push(
+            "forjar_zfs_prop() {\n\
+             \x20 CUR=\"$(zfs get -H -p -o value \"$1\" \"$DS\")\"\n\
+             \x20 if [ \"$CUR\" != \"$2\" ]; then\n\
+             \x20   zfs set \"$1=$3\" \"$DS\"\n\
+             \x20   echo \"changed:$1\"\n\
+             \x20 fi\n\
+             }"
+            .to_string(),
+        );
+        for (k, v) in &resource.properties {
+            lines.push(format!(
+                "forjar_zfs_prop '{k}' '{}' '{v}'",
+                parsable_value(k, v)
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Generate shell to query dataset state (for BLAKE3 hashing).
+///
+/// Reports the dataset type and the declared properties in `zfs get -p`
+/// form, so unmanaged properties never drift.
+pub fn state_query_script(resource: &Resource) -> String {
+    let ds = dataset_name(resource);
+    let mut props = vec!["type"];
+    props.extend(resource.properties.keys().map(String::as_str));
+    format!(
+        "if zfs list -H -o name '{ds}' >/dev/null 2>&1; then\n\
+         \x20 zfs get -H -p -o property,value {} '{ds}'\n\
+         else\n\
+         \x20 echo 'zfs_dataset=MISSING:{ds}'\n\
+         fi",
+        props.join(",")
+    )
+}
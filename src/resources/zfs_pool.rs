@@ -0,0 +1,107 @@
+//! ZFS pool resource handler.
+//!
+//! Creates pool `name` from `vdevs` — each entry one group in `zpool create`
+//! syntax, e.g. `mirror /dev/sdb /dev/sdc` — with `properties` passed as
+//! `-o`. An existing pool is never re-laid out; only differing properties
+//! are set. Without `force`, `zpool create` refuses devices that hold data.
+//!
+//! `state: absent` runs `zpool destroy`, which requires `force` and is
+//! refused while `lifecycle.prevent_destroy` is set.
+
+use super::zfs_dataset::{prevent_destroy, refuse_destroy_line};
+use crate::core::types::Resource;
+
+/// Pool name (`name`).
+pub fn pool_name(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("tank")
+}
+
+/// `zpool create` vdev arguments, each word single-quoted.
+fn vdev_args(resource: &Resource) -> String {
+    resource
+        .vdevs
+        .iter()
+        .flat_map(|group| group.split_whitespace())
+        .map(|word| format!(" '{word}'"))
+        .collect()
+}
+
+/// Generate shell to check whether the pool exists.
+pub fn check_script(resource: &Resource) -> String {
+    let pool = pool_name(resource);
+    format!(
+        "if zpool list -H -o name '{pool}' >/dev/null 2>&1; then echo 'exists:{pool}'; else echo 'missing:{pool}'; fi"
+    )
+}
+
+/// Generate shell to create the pool (or, with force, destroy it).
+pub fn apply_script(resource: &Resource) -> String {
+    let pool = pool_name(resource);
+    if resource.state.as_deref() == Some("absent") {
+        let destroy = if prevent_destroy(resource) {
+            refuse_destroy_line(&format!("pool {pool}"))
+        } else if resource.force {
+            format!("zpool destroy '{pool}'")
+        } else {
+            format!("echo \"ERROR: refusing to destroy pool {pool} without force\" >&2\nexit 1")
+        };
+        return format!(
+            "set -euo pipefail\n\
+             if zpool list -H -o name '{pool}' >/dev/null 2>&1; then\n\
+             {destroy}\n\
+             fi"
+        );
+    }
+
+    let opts: String = resource
+        .properties
+        .iter()
+        .map(|(k, v)| format!(" -o '{k}={v}'"))
+        .collect();
+    let force = if resource.force { " -f" } else { "" };
+    let mut lines = vec![
+        "set -euo pipefail".to_string(),
+        format!("POOL='{pool}'"),
+        format!(
+            "if ! zpool list -H -o name \"$POOL\" >/dev/null 2>&1; then\n\
+             \x20 zpool create{force}{opts} \"$POOL\"{}\n\
+             \x20 echo \"created:$POOL\"\n\
+             \x20 exit 0\n\
+             fi",
+            vdev_args(resource)
+        ),
+    ];
+    for (k, v) in &resource.properties {
+        lines.push(format!(
+            "if [ \"$(zpool get -H -p -o value '{k}' \"$POOL\")\" != '{v}' ]; then\n\
+             \x20 zpool set '{k}={v}' \"$POOL\"\n\
+             \x20 echo 'changed:{k}'\n\
+             fi"
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Generate shell to query pool state (for BLAKE3 hashing).
+///
+/// Reports the vdev layout (device paths, no capacity figures) and the
+/// declared properties.
+pub fn state_query_script(resource: &Resource) -> String {
+    let pool = pool_name(resource);
+    let mut lines = vec![format!("  zpool list -H -v -P -o name '{pool}'")];
+    if !resource.properties.is_empty() {
+        let props: Vec<&str> = resource.properties.keys().map(String::as_str).collect();
+        lines.push(format!(
+            "  zpool get -H -p -o property,value {} '{pool}'",
+            props.join(",")
+        ));
+    }
+    format!(
+        "if zpool list -H -o name '{pool}' >/dev/null 2>&1; then\n\
+         {}\n\
+         else\n\
+         \x20 echo 'zfs_pool=MISSING:{pool}'\n\
+         fi",
+        lines.join("\n")
+    )
+}
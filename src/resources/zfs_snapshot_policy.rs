@@ -0,0 +1,147 @@
+//! ZFS snapshot policy resource handler.
+//!
+//! Installs a snapshot script and an `/etc/cron.d` entry that runs it on
+//! `schedule`. Each run snapshots `dataset` as
+//! `<dataset>@forjar-<name>-<creation time>` (recursively with `recursive`)
+//! and prunes this policy's snapshots beyond the newest `keep`. The
+//! snapshot is taken under a `-pending` name and renamed after its own
+//! `creation` property, so the script needs no clock of its own.
+//!
+//! `state: absent` removes the script and cron entry; existing snapshots
+//! are kept.
+
+use crate::core::types::Resource;
+
+const SCRIPT_DIR: &str = "/usr/local/lib/forjar";
+
+/// Default number of snapshots to keep.
+pub const DEFAULT_KEEP: u32 = 24;
+
+/// Default schedule: hourly.
+pub const DEFAULT_SCHEDULE: &str = "0 * * * *";
+
+/// Dataset to snapshot (`dataset`).
+pub fn dataset(resource: &Resource) -> &str {
+    resource.dataset.as_deref().unwrap_or("tank")
+}
+
+/// Policy label (`name`, default `auto`).
+pub fn label(resource: &Resource) -> &str {
+    resource.name.as_deref().unwrap_or("auto")
+}
+
+/// Snapshots to keep.
+pub fn keep(resource: &Resource) -> u32 {
+    resource.keep.unwrap_or(DEFAULT_KEEP)
+}
+
+/// File-name slug for dataset and label (`tank/models` + `hourly` →
+/// `tank-models-hourly`); cron.d ignores names containing dots.
+pub fn slug(resource: &Resource) -> String {
+    format!("{}-{}", dataset(resource), label(resource))
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn script_path(resource: &Resource) -> String {
+    format!("{SCRIPT_DIR}/zfs-snapshot-{}.sh", slug(resource))
+}
+
+fn cron_path(resource: &Resource) -> String {
+    format!("/etc/cron.d/forjar-zfs-{}", slug(resource))
+}
+
+/// The snapshot-and-prune script run by cron.
+pub fn snapshot_script(resource: &Resource) -> String {
+    let ds = dataset(resource);
+    let label = label(resource);
+    let r = if resource.recursive { " -r" } else { "" };
+    format!(
+        "#!/bin/sh\n\
+         # Managed by forjar: snapshot policy '{label}' for {ds}\n\
+         set -eu\n\
+         SNAP='{ds}@forjar-{label}-pending'\n\
+         if ! zfs list -H -t snapshot -o name \"$SNAP\" >/dev/null 2>&1; then zfs snapshot{r} \"$SNAP\"; fi\n\
+         CREATED=\"$(zfs get -H -p -o value creation \"$SNAP\")\"\n\
+         zfs rename{r} \"$SNAP\" '{ds}@forjar-{label}-'\"$CREATED\"\n\
+         zfs list -H -t snapshot -o name -S creation -d 1 '{ds}' | grep -F '@forjar-{label}-' | tail -n +{} | while read -r snap; do\n\
+         \x20 zfs destroy{r} \"$snap\"\n\
+         done",
+        keep(resource) + 1
+    )
+}
+
+fn cron_entry(resource: &Resource) -> String {
+    let schedule = resource.schedule.as_deref().unwrap_or(DEFAULT_SCHEDULE);
+    format!(
+        "# Managed by forjar\n\
+         SHELL=/bin/sh\n\
+         PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin\n\
+         {schedule} root /bin/sh {}",
+        script_path(resource)
+    )
+}
+
+/// Generate shell to check whether the policy is installed.
+pub fn check_script(resource: &Resource) -> String {
+    let cron = cron_path(resource);
+    format!("if [ -f '{cron}' ]; then echo 'exists:{cron}'; else echo 'missing:{cron}'; fi")
+}
+
+/// Generate shell to install (or remove) the snapshot policy.
+pub fn apply_script(resource: &Resource) -> String {
+    let script = script_path(resource);
+    let cron = cron_path(resource);
+    if resource.state.as_deref() == Some("absent") {
+        return format!(
+            "set -euo pipefail\n\
+             if [ -e '{cron}' ]; then unlink '{cron}'; fi\n\
+             if [ -e '{script}' ]; then unlink '{script}'; fi"
+        );
+    }
+    let ds = dataset(resource);
+    [
+        "set -euo pipefail".to_string(),
+        format!(
+            "if ! zfs list -H -o name '{ds}' >/dev/null 2>&1; then\n\
+             \x20 echo \"ERROR: dataset {ds} does not exist\" >&2\n\
+             \x20 exit 1\n\
+             fi"
+        ),
+        format!("mkdir -p '{SCRIPT_DIR}'"),
+        format!(
+            "cat > '{script}' <<'FORJAR_EOF'\n{}\nFORJAR_EOF",
+            snapshot_script(resource)
+        ),
+        format!("chmod 0755 '{script}'"),
+        format!(
+            "cat > '{cron}' <<'FORJAR_EOF'\n{}\nFORJAR_EOF",
+            cron_entry(resource)
+        ),
+        format!("chmod 0644 '{cron}'"),
+    ]
+    .join("\n")
+}
+
+/// Generate shell to query policy state (for BLAKE3 hashing).
+///
+/// Checksums of the installed script and cron entry; the snapshots
+/// themselves change every run and are not hashed.
+pub fn state_query_script(resource: &Resource) -> String {
+    let script = script_path(resource);
+    let cron = cron_path(resource);
+    format!(
+        "if [ -f '{cron}' ]; then\n\
+         \x20 cksum '{cron}' '{script}'\n\
+         else\n\
+         \x20 echo 'zfs_snapshot_policy=MISSING:{cron}'\n\
+         fi"
+    )
+}
@@ -1,17 +1,20 @@
 //! FJ-015: Append-only JSONL provenance event log.
 
 use crate::core::types::{ProvenanceEvent, TimestampedEvent};
-use std::io::Write;
 use std::path::{Path, PathBuf};
 
 /// Generate an ISO 8601 timestamp.
 pub fn now_iso8601() -> String {
-    // Manual implementation — no chrono dependency
     use std::time::{SystemTime, UNIX_EPOCH};
     let dur = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default();
-    let secs = dur.as_secs();
+    iso8601_from_secs(dur.as_secs())
+}
+
+/// ISO 8601 UTC timestamp for `secs` since the Unix epoch.
+pub fn iso8601_from_secs(secs: u64) -> String {
+    // Manual implementation — no chrono dependency
     // Simple UTC conversion (good enough, no TZ complexity)
     let days = secs / 86400;
     let time_secs = secs % 86400;
@@ -80,23 +83,19 @@ pub fn event_log_path(state_dir: &Path, machine: &str) -> PathBuf {
 /// Append an event to the machine's event log.
 pub fn append_event(state_dir: &Path, machine: &str, event: ProvenanceEvent) -> Result<(), String> {
     let path = event_log_path(state_dir, machine);
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| format!("cannot create state dir: {e}"))?;
-    }
-
     let te = TimestampedEvent {
         ts: now_iso8601(),
         event,
     };
     let json = serde_json::to_string(&te).map_err(|e| format!("JSON serialize error: {e}"))?;
+    // FJ-3310: One ENC[age,...] marker per line when state is encrypted.
+    let json = crate::core::state_recipients::seal_event_line(state_dir, machine, &json)
+        .map_err(|e| format!("cannot append to event log {}: {}", path.display(), e))?;
 
-    let mut file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&path)
-        .map_err(|e| format!("cannot open event log {}: {}", path.display(), e))?;
-
-    writeln!(file, "{json}").map_err(|e| format!("write error: {e}"))?;
-
-    Ok(())
+    crate::core::state::backend::backend_for(state_dir)
+        .append(
+            &format!("{machine}/events.jsonl"),
+            format!("{json}\n").as_bytes(),
+        )
+        .map_err(|e| format!("cannot append to event log {}: {}", path.display(), e))
 }
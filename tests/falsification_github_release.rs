@@ -7,13 +7,19 @@
 //! - Absent state (removal script)
 //! - Default values (tag, install_dir)
 //!
+//! `github_release` is `binary_release` with the GitHub provider, so the
+//! install script is generated for a release resolved on the control machine.
+//!
 //! Usage: cargo test --test falsification_github_release
 
 #![allow(clippy::field_reassign_with_default)]
 
 use forjar::core::parser::parse_config;
+use forjar::core::release_source::{pinned_tag, ReleaseAsset, ResolvedRelease};
 use forjar::core::types::{Resource, ResourceType};
-use forjar::resources::github_release::{apply_script, check_script, state_query_script};
+use forjar::resources::github_release::{
+    apply_script, check_script, install_script, state_query_script,
+};
 
 // ============================================================================
 // Helpers
@@ -30,6 +36,30 @@ fn github_resource(repo: &str, binary: &str) -> Resource {
     r
 }
 
+/// The release `r` resolves to: its pinned tag and the asset matching
+/// its pattern.
+fn resolved(r: &Resource) -> ResolvedRelease {
+    let repo = r.repo.as_deref().unwrap_or("org/tool");
+    let tag = pinned_tag(r).unwrap_or("v1.0.0").to_string();
+    let pattern = r.asset_pattern.as_deref().unwrap_or("*").trim_matches('*');
+    let name = format!("tool-{pattern}.tar.gz");
+    ResolvedRelease {
+        version: semver::Version::new(1, 0, 0),
+        asset: ReleaseAsset {
+            url: format!("https://github.com/{repo}/releases/download/{tag}/{name}"),
+            name,
+            sha256: None,
+        },
+        tag,
+        sha256: "0".repeat(64),
+        signature: None,
+    }
+}
+
+fn apply_present(r: &Resource) -> String {
+    install_script(r, &resolved(r))
+}
+
 // ============================================================================
 // FJ-034: check_script
 // ============================================================================
@@ -69,7 +99,7 @@ fn check_script_uses_executable_test() {
 #[test]
 fn apply_present_downloads_from_correct_repo() {
     let r = github_resource("paiml/aprender", "apr");
-    let script = apply_script(&r);
+    let script = apply_present(&r);
     assert!(script.contains("paiml/aprender"));
     assert!(script.contains("v1.0.0"));
 }
@@ -77,22 +107,31 @@ fn apply_present_downloads_from_correct_repo() {
 #[test]
 fn apply_present_installs_binary() {
     let r = github_resource("org/tool", "mytool");
-    let script = apply_script(&r);
-    assert!(script.contains("chmod +x '/usr/local/bin/mytool'"));
+    let script = apply_present(&r);
+    assert!(script.contains("BIN='/usr/local/bin/mytool'"));
+    assert!(script.contains("chmod 0755 '/usr/local/bin/.mytool.forjar-new'"));
+}
+
+#[test]
+fn apply_present_verifies_checksum() {
+    let r = github_resource("org/tool", "mytool");
+    let script = apply_present(&r);
+    assert!(script.contains("sha256sum"));
+    assert!(script.contains("CHECKSUM MISMATCH"));
 }
 
 #[test]
 fn apply_present_handles_tarball() {
     let r = github_resource("org/tool", "mytool");
-    let script = apply_script(&r);
-    assert!(script.contains("tar xzf"));
-    assert!(script.contains("*.tar.gz|*.tgz)"));
+    let script = apply_present(&r);
+    assert!(script.contains("tar xf"));
+    assert!(script.contains("*.tar.gz|*.tgz|*.tar.xz|*.tar)"));
 }
 
 #[test]
 fn apply_present_handles_zip() {
     let r = github_resource("org/tool", "mytool");
-    let script = apply_script(&r);
+    let script = apply_present(&r);
     assert!(script.contains("unzip"));
     assert!(script.contains("*.zip)"));
 }
@@ -100,15 +139,24 @@ fn apply_present_handles_zip() {
 #[test]
 fn apply_present_cleans_up_tmpdir() {
     let r = github_resource("org/tool", "mytool");
-    let script = apply_script(&r);
+    let script = apply_present(&r);
     assert!(script.contains("trap 'rm -rf"));
 }
 
 #[test]
 fn apply_present_verifies_binary() {
     let r = github_resource("org/tool", "mytool");
-    let script = apply_script(&r);
+    let script = apply_present(&r);
     assert!(script.contains("--version"));
+    assert!(script.contains("ROLLBACK"));
+}
+
+#[test]
+fn apply_present_without_resolution_fails() {
+    let r = github_resource("org/tool", "mytool");
+    let script = apply_script(&r);
+    assert!(script.contains("github_release org/tool is resolved"));
+    assert!(script.contains("exit 1"));
 }
 
 // ============================================================================
@@ -120,7 +168,7 @@ fn apply_absent_removes_binary() {
     let mut r = github_resource("org/tool", "mytool");
     r.state = Some("absent".into());
     let script = apply_script(&r);
-    assert!(script.contains("rm -f '/usr/local/bin/mytool'"));
+    assert!(script.contains("unlink '/usr/local/bin/mytool'"));
     assert!(script.contains("removed:org/tool"));
 }
 
@@ -129,12 +177,12 @@ fn apply_absent_removes_binary() {
 // ============================================================================
 
 #[test]
-fn state_query_reports_version_and_size() {
+fn state_query_reports_tag_and_checksum() {
     let r = github_resource("org/tool", "mytool");
     let script = state_query_script(&r);
-    assert!(script.contains("github_release=org/tool"));
-    assert!(script.contains("--version"));
-    assert!(script.contains("stat"));
+    assert!(script.contains("binary_release=org/tool"));
+    assert!(script.contains(".mytool.forjar-release"));
+    assert!(script.contains("sha256sum '/usr/local/bin/mytool'"));
 }
 
 #[test]
@@ -159,17 +207,19 @@ fn default_install_dir_is_usr_local_bin() {
 #[test]
 fn default_tag_is_latest() {
     let mut r = github_resource("org/tool", "mytool");
+    assert_eq!(pinned_tag(&r), Some("v1.0.0"));
+    // Unset and `latest` both take the newest stable release
     r.tag = None;
-    let script = apply_script(&r);
-    assert!(script.contains("releases/tags/latest"));
+    assert_eq!(pinned_tag(&r), None);
+    r.tag = Some("latest".into());
+    assert_eq!(pinned_tag(&r), None);
 }
 
 #[test]
 fn default_asset_pattern_is_star() {
     let mut r = github_resource("org/tool", "mytool");
     r.asset_pattern = None;
-    // With *, the grep_pattern is empty string after trimming
-    let script = apply_script(&r);
+    let script = apply_present(&r);
     assert!(script.contains("curl -fsSL"));
 }
 
@@ -201,16 +251,44 @@ resources:
     assert!(config.is_ok(), "valid github_release config must parse");
 }
 
+#[test]
+fn validate_github_release_rejects_other_providers() {
+    let yaml = r#"
+version: "1.0"
+name: test
+machines:
+  local:
+    hostname: localhost
+    addr: 127.0.0.1
+    user: test
+resources:
+  tool:
+    type: github_release
+    machine: local
+    provider: gitlab
+    repo: org/tool
+    binary: mytool
+"#;
+    let config = parse_config(yaml).unwrap();
+    let errors = forjar::core::parser::validate_config(&config);
+    assert!(
+        errors
+            .iter()
+            .any(|e| e.message.contains("use binary_release")),
+        "{errors:?}"
+    );
+}
+
 #[test]
 fn validate_github_release_missing_repo_uses_fallback() {
-    // Resource with no repo falls back to "unknown/unknown"
+    // Resource with no repo is labelled by its binary
     let mut r = Resource::default();
     r.resource_type = ResourceType::GithubRelease;
     r.binary = Some("mytool".into());
     let script = check_script(&r);
     assert!(
-        script.contains("unknown/unknown"),
-        "missing repo must fall back to unknown/unknown"
+        script.contains("missing:mytool"),
+        "missing repo must fall back to the binary name"
     );
 }
 
@@ -269,14 +347,14 @@ resources:
 fn asset_pattern_included_in_download() {
     let mut r = github_resource("org/tool", "mytool");
     r.asset_pattern = Some("*aarch64-apple-darwin*".into());
-    let script = apply_script(&r);
+    let script = apply_present(&r);
     assert!(script.contains("aarch64-apple-darwin"));
 }
 
 #[test]
 fn apply_mkdir_install_dir() {
     let r = github_resource("org/tool", "mytool");
-    let script = apply_script(&r);
+    let script = apply_present(&r);
     assert!(
         script.contains("mkdir -p '/usr/local/bin'"),
         "apply must create install_dir"
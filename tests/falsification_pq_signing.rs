@@ -0,0 +1,147 @@
+//! FJ-1433: ML-DSA-65 (FIPS 204) known-answer falsification.
+//!
+//! Popperian rejection criteria for:
+//! - PqSigningKey::from_seed: keyGen matches NIST ACVP pk (and sk)
+//! - ML-DSA.Sign_internal: deterministic sigGen matches ACVP signatures
+//! - ML-DSA.Verify_internal: accepts only the unmodified sigVer case
+//! - PqPublicKey::verify: forjar's context-bound signatures round-trip
+//!
+//! Vectors: tests/data/ml_dsa_65_acvp.json, the ML-DSA-65 cases of the
+//! NIST ACVP-Server internal projections (vsId 42).
+//!
+//! Usage: cargo test --test falsification_pq_signing
+
+use forjar::core::pq_signing::{PqPublicKey, PqSigningKey};
+use ml_dsa::{
+    EncodedVerifyingKey, ExpandedSigningKey, ExpandedSigningKeyBytes, MlDsa65, Seed, Signature,
+    VerifyingKey, B32,
+};
+
+const VECTORS: &str = include_str!("data/ml_dsa_65_acvp.json");
+
+fn vectors() -> serde_json::Value {
+    serde_json::from_str(VECTORS).unwrap()
+}
+
+fn hex(v: &serde_json::Value) -> Vec<u8> {
+    let s = v.as_str().unwrap();
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn seed(v: &serde_json::Value) -> [u8; 32] {
+    hex(v).try_into().unwrap()
+}
+
+// ============================================================================
+// keyGen
+// ============================================================================
+
+#[test]
+fn keygen_public_key_matches_acvp() {
+    let v = vectors();
+    let cases = v["keyGen"].as_array().unwrap();
+    assert!(!cases.is_empty());
+    for case in cases {
+        let key = PqSigningKey::from_seed("acvp", seed(&case["seed"]));
+        assert_eq!(
+            key.public_key().as_bytes(),
+            hex(&case["pk"]).as_slice(),
+            "tcId {}",
+            case["tcId"]
+        );
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn keygen_expanded_secret_key_matches_acvp() {
+    let v = vectors();
+    for case in v["keyGen"].as_array().unwrap() {
+        let esk = ExpandedSigningKey::<MlDsa65>::from_seed(&Seed::from(seed(&case["seed"])));
+        assert_eq!(
+            esk.to_expanded().as_slice(),
+            hex(&case["sk"]).as_slice(),
+            "tcId {}",
+            case["tcId"]
+        );
+    }
+}
+
+// ============================================================================
+// sigGen (deterministic, internal interface)
+// ============================================================================
+
+#[test]
+#[allow(deprecated)]
+fn siggen_matches_acvp() {
+    let v = vectors();
+    assert_eq!(v["sigGen"]["deterministic"], true);
+    for case in v["sigGen"]["tests"].as_array().unwrap() {
+        let sk = ExpandedSigningKeyBytes::<MlDsa65>::try_from(hex(&case["sk"]).as_slice()).unwrap();
+        let esk = ExpandedSigningKey::<MlDsa65>::from_expanded(&sk);
+        let sig = esk.sign_internal(&[&hex(&case["message"])], &B32::default());
+        assert_eq!(
+            sig.encode().as_slice(),
+            hex(&case["signature"]).as_slice(),
+            "tcId {}",
+            case["tcId"]
+        );
+    }
+}
+
+// ============================================================================
+// sigVer (internal interface)
+// ============================================================================
+
+#[test]
+fn sigver_matches_acvp() {
+    let v = vectors();
+    let pk = hex(&v["sigVer"]["pk"]);
+    let vk = VerifyingKey::<MlDsa65>::decode(
+        &EncodedVerifyingKey::<MlDsa65>::try_from(pk.as_slice()).unwrap(),
+    );
+    let cases = v["sigVer"]["tests"].as_array().unwrap();
+    assert!(cases.iter().any(|c| c["testPassed"] == false));
+    for case in cases {
+        let expected = case["testPassed"].as_bool().unwrap();
+        // A signature that does not even decode is a failed verification.
+        let got = Signature::<MlDsa65>::try_from(hex(&case["signature"]).as_slice())
+            .map(|sig| vk.verify_internal(&hex(&case["message"]), &sig))
+            .unwrap_or(false);
+        assert_eq!(
+            got, expected,
+            "tcId {} ({})",
+            case["tcId"], case["reason"]
+        );
+    }
+}
+
+// ============================================================================
+// forjar signatures (pure ML-DSA.Sign, context "forjar")
+// ============================================================================
+
+#[test]
+fn forjar_signature_round_trip_on_acvp_key() {
+    let v = vectors();
+    let key = PqSigningKey::from_seed("acvp", seed(&v["keyGen"][0]["seed"]));
+    let public = PqPublicKey::from_bytes(&hex(&v["keyGen"][0]["pk"])).unwrap();
+    let sig = key.sign(b"forjar").unwrap();
+    assert!(public.verify(b"forjar", &sig));
+    assert!(!public.verify(b"forjar ", &sig));
+}
+
+#[test]
+fn forjar_signature_is_not_an_internal_signature() {
+    // The context string is part of the signed message: a signature made
+    // through forjar must not verify under the bare internal interface.
+    let v = vectors();
+    let s = seed(&v["keyGen"][0]["seed"]);
+    let key = PqSigningKey::from_seed("acvp", s);
+    let sig = key.sign(b"m").unwrap();
+    let esk = ExpandedSigningKey::<MlDsa65>::from_seed(&Seed::from(s));
+    let sig = Signature::<MlDsa65>::try_from(sig.as_slice()).unwrap();
+    assert!(!esk.verifying_key().verify_internal(b"m", &sig));
+}
@@ -456,6 +456,10 @@ fn make_meta(
                 min,
                 max,
                 choices: choices.iter().map(|s| s.to_string()).collect(),
+                pattern: None,
+                required: None,
+                items: None,
+                properties: IndexMap::new(),
             },
         );
     }
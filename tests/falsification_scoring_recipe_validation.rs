@@ -244,6 +244,10 @@ fn recipe_input(input_type: &str) -> RecipeInput {
         min: None,
         max: None,
         choices: vec![],
+        pattern: None,
+        required: None,
+        items: None,
+        properties: IndexMap::new(),
     }
 }
 
@@ -288,6 +292,10 @@ fn validate_int_in_range_ok() {
             min: Some(1),
             max: Some(65535),
             choices: vec![],
+            pattern: None,
+            required: None,
+            items: None,
+            properties: IndexMap::new(),
         },
     );
     let recipe = recipe_with_inputs(inputs);
@@ -309,6 +317,10 @@ fn validate_int_below_min_rejected() {
             min: Some(1),
             max: None,
             choices: vec![],
+            pattern: None,
+            required: None,
+            items: None,
+            properties: IndexMap::new(),
         },
     );
     let recipe = recipe_with_inputs(inputs);
@@ -330,6 +342,10 @@ fn validate_int_above_max_rejected() {
             min: None,
             max: Some(32),
             choices: vec![],
+            pattern: None,
+            required: None,
+            items: None,
+            properties: IndexMap::new(),
         },
     );
     let recipe = recipe_with_inputs(inputs);
@@ -407,6 +423,10 @@ fn validate_enum_valid_choice() {
             min: None,
             max: None,
             choices: vec!["dev".into(), "staging".into(), "prod".into()],
+            pattern: None,
+            required: None,
+            items: None,
+            properties: IndexMap::new(),
         },
     );
     let recipe = recipe_with_inputs(inputs);
@@ -428,6 +448,10 @@ fn validate_enum_invalid_choice_rejected() {
             min: None,
             max: None,
             choices: vec!["dev".into(), "prod".into()],
+            pattern: None,
+            required: None,
+            items: None,
+            properties: IndexMap::new(),
         },
     );
     let recipe = recipe_with_inputs(inputs);
@@ -453,6 +477,10 @@ fn validate_uses_default_when_not_provided() {
             min: None,
             max: None,
             choices: vec![],
+            pattern: None,
+            required: None,
+            items: None,
+            properties: IndexMap::new(),
         },
     );
     let recipe = recipe_with_inputs(inputs);
@@ -483,6 +511,10 @@ fn validate_unknown_type_rejected() {
             min: None,
             max: None,
             choices: vec![],
+            pattern: None,
+            required: None,
+            items: None,
+            properties: IndexMap::new(),
         },
     );
     let recipe = recipe_with_inputs(inputs);
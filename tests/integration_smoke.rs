@@ -229,8 +229,11 @@ fn contracts_coverage_succeeds() {
 
 #[test]
 fn state_query_succeeds() {
+    let dir = tempfile::tempdir().expect("tmpdir");
+    let state_dir = dir.path().join("state");
     let out = forjar()
-        .args(["state-query", "bash"])
+        .args(["state-query", "bash", "--state-dir"])
+        .arg(&state_dir)
         .output()
         .expect("failed to run");
     assert!(out.status.success(), "state-query should succeed");